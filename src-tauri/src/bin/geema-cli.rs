@@ -0,0 +1,7 @@
+//! Point d'entrée du mode ligne de commande (fonctionnalité `cli`)
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    tauri_app_lib::cli::run(std::env::args().skip(1).collect())
+}
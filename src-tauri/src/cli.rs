@@ -0,0 +1,272 @@
+//! Mode ligne de commande, sans interface graphique
+//!
+//! Permet de programmer sur le poste de la ferme (tâche planifiée, script)
+//! la sauvegarde, les exports et la vérification de la base de l'application.
+//! Compilé uniquement avec la fonctionnalité `cli`:
+//!
+//! ```text
+//! cargo run --features cli --bin geema-cli -- [--base <fichier.db>] <commande>
+//! ```
+
+use crate::database::{version_schema, DatabaseConfig, DatabaseManager, Storage, NOM_FICHIER_BASE, VERSION_SCHEMA};
+use crate::error::{AppError, AppResult};
+use crate::services::{ExportService, MaintenanceService};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+/// Variable d'environnement donnant le chemin de la base, à défaut de `--base`
+pub const BASE_ENV: &str = "GEEMA_DB_PATH";
+
+/// Configuration de l'application, dont l'identifiant nomme son dossier de données
+const CONFIGURATION_TAURI: &str = include_str!("../tauri.conf.json");
+
+const USAGE: &str = "Usage: geema-cli [--base <fichier.db>] <commande>
+
+Commandes:
+  backup <fichier.db>             Sauvegarde la base dans un fichier SQLite compacté
+  export <fichier.db>             Exporte l'instantané de reporting (bande_facts, daily_facts)
+  export-bande <id> <fichier.json> Exporte une bande au format d'échange JSON
+  check                           Vérifie l'intégrité de la base (code de sortie 1 si un problème est relevé)
+
+La base est celle de l'application, sauf si --base ou GEEMA_DB_PATH en désigne une autre.
+Elle n'est pas migrée: les exports sont refusés si son schéma n'est pas celui de cet outil.
+La phrase secrète d'une base chiffrée est lue dans GEEMA_DB_PASSPHRASE.";
+
+/// Opération demandée sur la ligne de commande
+#[derive(Debug, Clone, PartialEq)]
+pub enum Commande {
+    Sauvegarde { destination: String },
+    Export { destination: String },
+    ExportBande { bande_id: i64, destination: String },
+    Verification,
+}
+
+/// Ligne de commande analysée
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    /// Base désignée par `--base`, `None` pour celle de l'environnement ou de l'application
+    pub base: Option<PathBuf>,
+    pub commande: Commande,
+}
+
+/// Analyse les arguments (sans le nom du programme)
+///
+/// # Returns
+/// L'invocation, ou le message à afficher avant l'aide
+pub fn analyser(arguments: &[String]) -> Result<Invocation, String> {
+    let mut base = None;
+    let mut positionnels = Vec::new();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--base" => match arguments.next() {
+                Some(chemin) => base = Some(PathBuf::from(chemin)),
+                None => return Err("--base attend le chemin de la base".to_string()),
+            },
+            option if option.starts_with("--") => return Err(format!("Option inconnue: {}", option)),
+            _ => positionnels.push(argument.as_str()),
+        }
+    }
+
+    let commande = match positionnels.as_slice() {
+        ["backup", destination] => Commande::Sauvegarde { destination: destination.to_string() },
+        ["export", destination] => Commande::Export { destination: destination.to_string() },
+        ["export-bande", bande_id, destination] => Commande::ExportBande {
+            bande_id: bande_id.parse().map_err(|_| format!("ID de bande invalide: {}", bande_id))?,
+            destination: destination.to_string(),
+        },
+        ["check"] => Commande::Verification,
+        [] => return Err("Aucune commande".to_string()),
+        [commande, ..] => return Err(format!("Commande inconnue ou arguments incorrects: {}", commande)),
+    };
+    Ok(Invocation { base, commande })
+}
+
+/// Exécute la ligne de commande et renvoie le code de sortie
+///
+/// 0 en cas de succès, 1 si l'opération échoue ou si la vérification relève
+/// un problème, 2 si la ligne de commande est incorrecte.
+///
+/// # Arguments
+/// * `arguments` - Les arguments, sans le nom du programme
+pub fn run(arguments: Vec<String>) -> ExitCode {
+    let invocation = match analyser(&arguments) {
+        Ok(invocation) => invocation,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Impossible de démarrer: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match runtime.block_on(executer(invocation)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Ouvre la base et lance l'opération
+///
+/// # Returns
+/// Faux si la vérification d'intégrité a relevé un problème
+async fn executer(invocation: Invocation) -> AppResult<bool> {
+    let chemin = match invocation.base {
+        Some(chemin) => chemin,
+        None => chemin_base_par_defaut()?,
+    };
+    // Ne pas créer une base vide à la place d'un chemin erroné
+    if !chemin.is_file() {
+        return Err(AppError::validation_error(
+            "base",
+            &format!("Base de données introuvable: {}", chemin.display()),
+        ));
+    }
+    // La base est ouverte sans migration: l'outil ne modifie pas le schéma
+    // d'une base que l'application (d'une autre version) utilise peut-être
+    let db: Arc<dyn Storage> = Arc::new(DatabaseManager::with_config(&chemin, DatabaseConfig::from_env())?);
+    let version = version_schema(&db.get_connection()?)?;
+    if version != VERSION_SCHEMA {
+        let ecart = format!(
+            "La base est au schéma version {}, cet outil attend la version {}",
+            version, VERSION_SCHEMA
+        );
+        match invocation.commande {
+            // La sauvegarde et la vérification ne dépendent pas des colonnes des tables
+            Commande::Sauvegarde { .. } | Commande::Verification => eprintln!("Attention: {}", ecart),
+            Commande::Export { .. } | Commande::ExportBande { .. } => {
+                return Err(AppError::business_logic(&format!(
+                    "{}: ouvrez-la avec l'application de la même version avant de l'exporter",
+                    ecart
+                )));
+            }
+        }
+    }
+
+    match invocation.commande {
+        Commande::Sauvegarde { destination } => {
+            let fichier = ExportService::new(db).backup_database(&destination).await?;
+            println!("Sauvegarde écrite dans {}", fichier);
+        }
+        Commande::Export { destination } => {
+            let rapport = ExportService::new(db).export_reporting_snapshot(&destination).await?;
+            println!("Export écrit dans {}: {} bande(s), {} jour(s)", rapport.chemin, rapport.bandes, rapport.jours);
+        }
+        Commande::ExportBande { bande_id, destination } => {
+            let fichier = ExportService::new(db).export_bande_json(bande_id, &destination).await?;
+            println!("Bande {} exportée dans {}", bande_id, fichier);
+        }
+        Commande::Verification => {
+            let rapport = MaintenanceService::new(db).check_integrity().await?;
+            for probleme in rapport.erreurs.iter().chain(&rapport.references_orphelines) {
+                println!("{}", probleme);
+            }
+            if !rapport.integre {
+                return Ok(false);
+            }
+            println!("Base intègre ({})", rapport.verifiee_le);
+        }
+    }
+    Ok(true)
+}
+
+/// Base de l'application: `GEEMA_DB_PATH`, sinon le dossier de données de
+/// l'application de l'utilisateur courant (le même que celui de Tauri)
+fn chemin_base_par_defaut() -> AppResult<PathBuf> {
+    if let Some(chemin) = std::env::var_os(BASE_ENV).filter(|chemin| !chemin.is_empty()) {
+        return Ok(PathBuf::from(chemin));
+    }
+
+    let variable = |nom: &str| std::env::var_os(nom).filter(|valeur| !valeur.is_empty()).map(PathBuf::from);
+    let dossier_donnees = if cfg!(target_os = "windows") {
+        variable("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        variable("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        variable("XDG_DATA_HOME").or_else(|| variable("HOME").map(|home| home.join(".local").join("share")))
+    };
+    let dossier_donnees = dossier_donnees.ok_or_else(|| {
+        AppError::validation_error("base", "Dossier de données introuvable: indiquez la base avec --base")
+    })?;
+    Ok(dossier_donnees.join(identifiant_application()?).join(NOM_FICHIER_BASE))
+}
+
+/// Identifiant de l'application (`identifier` de `tauri.conf.json`), le nom
+/// du dossier de données que Tauri lui attribue
+fn identifiant_application() -> AppResult<String> {
+    let configuration: serde_json::Value = serde_json::from_str(CONFIGURATION_TAURI)?;
+    configuration["identifier"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::business_logic("Identifiant de l'application absent de tauri.conf.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(ligne: &str) -> Vec<String> {
+        ligne.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn command_line_is_parsed() {
+        assert_eq!(
+            analyser(&arguments("--base ferme.db backup sauvegarde.db")),
+            Ok(Invocation {
+                base: Some(PathBuf::from("ferme.db")),
+                commande: Commande::Sauvegarde { destination: "sauvegarde.db".to_string() },
+            })
+        );
+        assert_eq!(
+            analyser(&arguments("export-bande 12 bande.json --base ferme.db")).unwrap().commande,
+            Commande::ExportBande { bande_id: 12, destination: "bande.json".to_string() }
+        );
+        assert_eq!(analyser(&arguments("check")).unwrap(), Invocation { base: None, commande: Commande::Verification });
+
+        assert!(analyser(&arguments("")).is_err());
+        assert!(analyser(&arguments("backup")).is_err());
+        assert!(analyser(&arguments("export-bande douze bande.json")).is_err());
+        assert!(analyser(&arguments("check --base")).is_err());
+        assert!(analyser(&arguments("check --verbose")).is_err());
+        assert!(analyser(&arguments("restore sauvegarde.db")).is_err());
+    }
+
+    #[test]
+    fn database_is_not_migrated_and_exports_require_the_same_schema_version() {
+        let dossier = std::env::temp_dir().join(format!("geema-cli-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dossier).unwrap();
+        let chemin = dossier.join("ferme.db");
+        DatabaseManager::new(&chemin).unwrap().initialize_schema().unwrap();
+        // Base laissée par une version précédente de l'application
+        rusqlite::Connection::open(&chemin).unwrap().pragma_update(None, "user_version", VERSION_SCHEMA - 1).unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let executer_sur = |commande| runtime.block_on(executer(Invocation { base: Some(chemin.clone()), commande }));
+        let export = dossier.join("export.db");
+        assert!(executer_sur(Commande::Export { destination: export.to_string_lossy().into_owned() }).is_err());
+        assert!(!export.exists());
+        assert!(executer_sur(Commande::Verification).unwrap());
+
+        let version = version_schema(&rusqlite::Connection::open(&chemin).unwrap()).unwrap();
+        assert_eq!(version, VERSION_SCHEMA - 1);
+        std::fs::remove_dir_all(&dossier).ok();
+    }
+
+    #[test]
+    fn application_identifier_is_read_from_the_tauri_configuration() {
+        let identifiant = identifiant_application().unwrap();
+        assert!(!identifiant.is_empty());
+        assert!(CONFIGURATION_TAURI.contains(&format!("\"identifier\": \"{}\"", identifiant)));
+    }
+}
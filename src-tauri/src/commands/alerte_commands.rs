@@ -0,0 +1,129 @@
+use crate::database::DatabaseManager;
+use crate::models::{AbonnementAlertes, Alerte, UpdateAbonnementAlertes};
+use crate::services::{AlerteService, AuthService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère les alertes d'une bande
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les alertes de la bande ou une erreur
+    #[tauri::command]
+    pub async fn get_bande_alertes(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Alerte>, String> {
+        let service = AlerteService::new(db.inner().clone());
+        service.get_bande_alertes(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère les alertes en cours de toutes les bandes pas encore enlevées
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `token` - Le token de session: seules les alertes auxquelles l'utilisateur est abonné sont renvoyées
+    /// 
+    /// # Returns
+    /// Les alertes en cours ou une erreur
+    #[tauri::command]
+    pub async fn get_pending_alerts(
+        db: State<'_, Arc<DatabaseManager>>,
+        token: Option<String>,
+    ) -> Result<Vec<Alerte>, String> {
+        let user_id = AuthService::new(db.inner().clone())
+            .author_id(token.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        let service = AlerteService::new(db.inner().clone());
+        service.get_pending_alerts(user_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère l'abonnement aux alertes de l'utilisateur connecté
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `token` - Le token de session de l'utilisateur
+    /// 
+    /// # Returns
+    /// L'abonnement ou une erreur
+    #[tauri::command]
+    pub async fn get_alert_subscription(
+        db: State<'_, Arc<DatabaseManager>>,
+        token: String,
+    ) -> Result<AbonnementAlertes, String> {
+        let service = AlerteService::new(db.inner().clone());
+        service.get_alert_subscription(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Enregistre l'abonnement aux alertes de l'utilisateur connecté
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `abonnement` - Types d'alertes, fermes et gravité minimale (listes vides = tout)
+    /// * `token` - Le token de session de l'utilisateur
+    /// 
+    /// # Returns
+    /// L'abonnement enregistré ou une erreur
+    #[tauri::command]
+    pub async fn set_alert_subscription(
+        db: State<'_, Arc<DatabaseManager>>,
+        abonnement: UpdateAbonnementAlertes,
+        token: String,
+    ) -> Result<AbonnementAlertes, String> {
+        let service = AlerteService::new(db.inner().clone());
+        service.set_alert_subscription(abonnement, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Acquitte une alerte au nom de l'utilisateur connecté
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `id` - L'ID de l'alerte dans l'historique
+    /// * `token` - Le token de session de l'utilisateur
+    /// 
+    /// # Returns
+    /// L'alerte acquittée ou une erreur
+    #[tauri::command]
+    pub async fn acknowledge_alert(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        token: String,
+    ) -> Result<Alerte, String> {
+        let service = AlerteService::new(db.inner().clone());
+        service.acknowledge_alert(id, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère l'historique des alertes avec leur acquittement
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `ferme_id` - La ferme concernée, toutes les fermes si absente
+    /// * `token` - Le token de session de l'utilisateur
+    /// 
+    /// # Returns
+    /// Les alertes de la plus récente à la plus ancienne ou une erreur
+    #[tauri::command]
+    pub async fn get_alert_history(
+        db: State<'_, Arc<DatabaseManager>>,
+        ferme_id: Option<i64>,
+        token: String,
+    ) -> Result<Vec<Alerte>, String> {
+        let service = AlerteService::new(db.inner().clone());
+        service.get_alert_history(ferme_id, &token).await.map_err(|e| e.to_string())
+    }
+}
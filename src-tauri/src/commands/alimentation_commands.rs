@@ -1,66 +1,163 @@
-use crate::database::DatabaseManager;
-use crate::models::alimentation::{AlimentationHistory, CreateAlimentationHistory, UpdateAlimentationHistory};
+use crate::database::{DatabaseManager, Storage};
+use crate::models::alimentation::{
+    AlimentationHistory, AlimentationHistoryFilters, AlimentationTypeTotal, CreateAlimentationHistory,
+    PaginatedAlimentationHistory, PaginatedAlimentationHistoryGlobal, UpdateAlimentationHistory,
+};
 use crate::repositories::AlimentationRepository;
+use crate::services::{AuthService, MetriquesCommandes};
 use std::sync::Arc;
 use tauri::State;
 
-/// Create a new alimentation history record
-#[tauri::command]
-pub async fn create_alimentation_history(
-    database: State<'_, Arc<DatabaseManager>>,
-    alimentation_data: CreateAlimentationHistory,
-) -> Result<AlimentationHistory, String> {
-    let conn = database.get_connection().map_err(|e| e.to_string())?;
-    AlimentationRepository::create(&conn, &alimentation_data).map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Create a new alimentation history record
+    #[tauri::command]
+    pub async fn create_alimentation_history(
+        database: State<'_, Arc<DatabaseManager>>,
+        alimentation_data: CreateAlimentationHistory,
+    ) -> Result<AlimentationHistory, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| AlimentationRepository::create(tx, &alimentation_data))
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Get all alimentation history for a specific bande
-#[tauri::command]
-pub async fn get_alimentation_history_by_bande(
-    database: State<'_, Arc<DatabaseManager>>,
-    bande_id: i64,
-) -> Result<Vec<AlimentationHistory>, String> {
-    let conn = database.get_connection().map_err(|e| e.to_string())?;
-    AlimentationRepository::get_by_bande(&conn, bande_id).map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Get all alimentation history for a specific bande
+    #[tauri::command]
+    pub async fn get_alimentation_history_by_bande(
+        database: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+    ) -> Result<Vec<AlimentationHistory>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        AlimentationRepository::get_by_bande(&conn, bande_id).map_err(|e| e.to_string())
+    }
 }
 
-/// Get a specific alimentation history record by ID
-#[tauri::command]
-pub async fn get_alimentation_history_by_id(
-    database: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-) -> Result<Option<AlimentationHistory>, String> {
-    let conn = database.get_connection().map_err(|e| e.to_string())?;
-    AlimentationRepository::get_by_id(&conn, id).map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Get alimentation history for a bande with pagination, optional date range filtering
+    /// and the total quantity over the filter window
+    #[tauri::command]
+    pub async fn get_alimentation_history_by_bande_paginated(
+        database: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+        page: u32,
+        per_page: u32,
+        date_from: Option<String>, // Format: "YYYY-MM-DD"
+        date_to: Option<String>,   // Format: "YYYY-MM-DD"
+    ) -> Result<PaginatedAlimentationHistory, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        AlimentationRepository::get_by_bande_paginated(&conn, bande_id, page, per_page, date_from, date_to)
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Update an alimentation history record
-#[tauri::command]
-pub async fn update_alimentation_history(
-    database: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-    alimentation_data: UpdateAlimentationHistory,
-) -> Result<(), String> {
-    let conn = database.get_connection().map_err(|e| e.to_string())?;
-    AlimentationRepository::update(&conn, id, &alimentation_data).map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Get alimentation history across all bandes with bande and ferme names, for auditing deliveries
+    #[tauri::command]
+    pub async fn get_alimentation_history_global(
+        database: State<'_, Arc<DatabaseManager>>,
+        page: u32,
+        per_page: u32,
+        filters: Option<AlimentationHistoryFilters>,
+    ) -> Result<PaginatedAlimentationHistoryGlobal, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        AlimentationRepository::get_global_paginated(&conn, page, per_page, &filters.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Delete an alimentation history record
-#[tauri::command]
-pub async fn delete_alimentation_history(
-    database: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-) -> Result<(), String> {
-    let conn = database.get_connection().map_err(|e| e.to_string())?;
-    AlimentationRepository::delete(&conn, id).map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Get a specific alimentation history record by ID
+    #[tauri::command]
+    pub async fn get_alimentation_history_by_id(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<Option<AlimentationHistory>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        AlimentationRepository::get_by_id(&conn, id).map_err(|e| e.to_string())
+    }
 }
 
-/// Get the current alimentation contour for a specific bande
-#[tauri::command]
-pub async fn get_alimentation_contour(
-    database: State<'_, Arc<DatabaseManager>>,
-    bande_id: i64,
-) -> Result<f64, String> {
-    let conn = database.get_connection().map_err(|e| e.to_string())?;
-    AlimentationRepository::get_contour(&conn, bande_id).map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Update an alimentation history record
+    #[tauri::command]
+    pub async fn update_alimentation_history(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        alimentation_data: UpdateAlimentationHistory,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| AlimentationRepository::update(tx, id, &alimentation_data))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete an alimentation history record
+    #[tauri::command]
+    pub async fn delete_alimentation_history(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| AlimentationRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get delivered quantities per feed type, for one bande or for all bandes when `bande_id` is omitted
+    #[tauri::command]
+    pub async fn get_alimentation_totals_by_type(
+        database: State<'_, Arc<DatabaseManager>>,
+        bande_id: Option<i64>,
+    ) -> Result<Vec<AlimentationTypeTotal>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        AlimentationRepository::get_totals_by_type(&conn, bande_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the current alimentation contour for a specific bande
+    #[tauri::command]
+    pub async fn get_alimentation_contour(
+        database: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+    ) -> Result<f64, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        AlimentationRepository::get_contour(&conn, bande_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the duplicate delivery window in minutes (0 when the check is disabled)
+    #[tauri::command]
+    pub async fn get_duplicate_delivery_window(
+        database: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<i64, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        AlimentationRepository::get_duplicate_window(&conn).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Set the duplicate delivery window in minutes (admins only), 0 disables the check
+    #[tauri::command]
+    pub async fn set_duplicate_delivery_window(
+        database: State<'_, Arc<DatabaseManager>>,
+        minutes: i64,
+        token: String,
+    ) -> Result<i64, String> {
+        AuthService::new(database.inner().clone())
+            .require_admin(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| AlimentationRepository::set_duplicate_window(tx, minutes))
+            .map_err(|e| e.to_string())
+    }
 }
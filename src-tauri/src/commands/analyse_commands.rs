@@ -0,0 +1,174 @@
+use crate::database::DatabaseManager;
+use crate::models::{Analyse, CreateAnalyse, UpdateAnalyse};
+use crate::repositories::{AnalyseRepository, AnalyseRepositoryTrait};
+use crate::services::{AnalyseService, MetriquesCommandes};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+commande_mesuree! {
+    /// Enregistre une nouvelle analyse de laboratoire
+    /// 
+    /// # Arguments
+    /// * `analyse` - Les données de l'analyse
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'analyse créée ou une erreur
+    #[tauri::command]
+    pub async fn create_analyse(
+        analyse: CreateAnalyse,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Analyse, String> {
+        let repo = AnalyseRepository::new(db.inner().clone());
+        repo.create(analyse).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les analyses d'un bâtiment
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les analyses, de la plus récente à la plus ancienne
+    #[tauri::command]
+    pub async fn get_analyses_by_batiment(
+        batiment_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Analyse>, String> {
+        let repo = AnalyseRepository::new(db.inner().clone());
+        repo.get_by_batiment(batiment_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les analyses d'une bande par date de prélèvement (suivi des tendances)
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `type_analyse` - Filtre optionnel sur le type d'analyse
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les analyses de tous les bâtiments de la bande
+    #[tauri::command]
+    pub async fn get_analyses_by_bande(
+        bande_id: i64,
+        type_analyse: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Analyse>, String> {
+        let repo = AnalyseRepository::new(db.inner().clone());
+        repo.get_by_bande(bande_id, type_analyse.as_deref()).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les analyses rattachées à un épisode de maladie
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `maladie_id` - L'ID de la maladie
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les analyses de l'épisode par date de prélèvement
+    #[tauri::command]
+    pub async fn get_analyses_by_maladie(
+        batiment_id: i64,
+        maladie_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Analyse>, String> {
+        let repo = AnalyseRepository::new(db.inner().clone());
+        repo.get_by_maladie(batiment_id, maladie_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour une analyse
+    /// 
+    /// # Arguments
+    /// * `analyse` - Les nouvelles données de l'analyse
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'analyse mise à jour ou une erreur
+    #[tauri::command]
+    pub async fn update_analyse(
+        analyse: UpdateAnalyse,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Analyse, String> {
+        let repo = AnalyseRepository::new(db.inner().clone());
+        repo.update(analyse).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime une analyse et son compte rendu PDF
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'analyse
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_analyse(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = AnalyseService::new(db.inner().clone());
+        service.delete_analyse(id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Joint un compte rendu PDF à une analyse
+    /// 
+    /// Le fichier est copié dans le dossier `analyses` des données de l'application.
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'analyse
+    /// * `path` - Le chemin du PDF sélectionné
+    /// * `app` - Le handle de l'application (injecté par Tauri)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'analyse mise à jour ou une erreur
+    #[tauri::command]
+    pub async fn attach_analyse_pdf<R: Runtime>(
+        id: i64,
+        path: String,
+        app: AppHandle<R>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Analyse, String> {
+        let dossier = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("analyses");
+        let service = AnalyseService::new(db.inner().clone());
+        service
+            .attach_pdf(id, &PathBuf::from(path), &dossier)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Retire le compte rendu PDF d'une analyse
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'analyse
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'analyse mise à jour ou une erreur
+    #[tauri::command]
+    pub async fn detach_analyse_pdf(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Analyse, String> {
+        let service = AnalyseService::new(db.inner().clone());
+        service.detach_pdf(id).await.map_err(|e| e.to_string())
+    }
+}
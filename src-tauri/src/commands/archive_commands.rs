@@ -0,0 +1,68 @@
+use crate::database::DatabaseManager;
+use crate::models::{BandeArchivee, ResultatArchivage};
+use crate::services::{ArchiveService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Déplace les bandes clôturées depuis plus de N années dans la base d'archive (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `annees` - L'ancienneté en années (l'ancienneté enregistrée, 3 ans par défaut, si absente)
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les bandes archivées et la date limite appliquée, ou une erreur
+    #[tauri::command]
+    pub async fn archive_old_bandes(
+        annees: Option<i32>,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ResultatArchivage, String> {
+        let service = ArchiveService::new(db.inner().clone());
+        service.archive_old_bandes(annees, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Ramène une bande archivée et toutes ses lignes dans la base principale (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande archivée
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Rien en cas de succès, ou une erreur
+    #[tauri::command]
+    pub async fn restore_archived_bande(
+        bande_id: i64,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = ArchiveService::new(db.inner().clone());
+        service.restore_archived_bande(bande_id, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les bandes archivées
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - Limite la liste à une ferme si renseigné
+    /// * `token` - Le token de session de l'utilisateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le résumé des bandes archivées ou une erreur
+    #[tauri::command]
+    pub async fn query_archive(
+        ferme_id: Option<i64>,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<BandeArchivee>, String> {
+        let service = ArchiveService::new(db.inner().clone());
+        service.query_archive(ferme_id, &token).await.map_err(|e| e.to_string())
+    }
+}
@@ -1,6 +1,9 @@
 use crate::database::DatabaseManager;
-use crate::models::{CreateUser, LoginUser, AuthResponse, UserPublic};
-use crate::services::AuthService;
+use crate::models::{
+    ActiviteUtilisateur, CreateUser, LoginUser, AuthResponse, UserPublic, CreateInvitation, Invitation, MfaEnrollment,
+};
+use crate::services::{AuthService, MetriquesCommandes};
+use chrono::NaiveDate;
 use std::sync::Arc;
 use tauri::State;
 use serde::{Deserialize, Serialize};
@@ -19,104 +22,282 @@ pub struct UpdatePasswordData {
     pub new_password: String,
 }
 
-/// Enregistre un nouvel utilisateur
-/// 
-/// # Arguments
-/// * `user_data` - Les données de l'utilisateur à créer
-/// * `db` - Le gestionnaire de base de données (injecté par Tauri)
-/// 
-/// # Returns
-/// La réponse d'authentification avec l'utilisateur et le token ou une erreur
-#[tauri::command]
-pub async fn register_user(
-    user_data: CreateUser,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<AuthResponse, String> {
-    let service = AuthService::new(db.inner().clone());
-    service.register(user_data).await.map_err(|e| e.to_string())
-}
-
-/// Connecte un utilisateur
-/// 
-/// # Arguments
-/// * `login_data` - Les données de connexion
-/// * `db` - Le gestionnaire de base de données (injecté par Tauri)
-/// 
-/// # Returns
-/// La réponse d'authentification avec l'utilisateur et le token ou une erreur
-#[tauri::command]
-pub async fn login_user(
-    login_data: LoginUser,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<AuthResponse, String> {
-    let service = AuthService::new(db.inner().clone());
-    service.login(login_data).await.map_err(|e| e.to_string())
-}
-
-/// Déconnecte un utilisateur
-/// 
-/// # Arguments
-/// * `token` - Le token de l'utilisateur à déconnecter
-/// * `db` - Le gestionnaire de base de données (injecté par Tauri)
-/// 
-/// # Returns
-/// Un succès vide ou une erreur
-#[tauri::command]
-pub async fn logout_user(
-    token: String,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<(), String> {
-    let service = AuthService::new(db.inner().clone());
-    service.logout(&token).await.map_err(|e| e.to_string())
-}
-
-/// Vérifie la validité d'un token
-/// 
-/// # Arguments
-/// * `token` - Le token à vérifier
-/// * `db` - Le gestionnaire de base de données (injecté par Tauri)
-/// 
-/// # Returns
-/// L'utilisateur correspondant au token ou None si invalide
-#[tauri::command]
-pub async fn verify_token(
-    token: String,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Option<UserPublic>, String> {
-    let service = AuthService::new(db.inner().clone());
-    service.verify_token(&token).await.map_err(|e| e.to_string())
-}
-
-/// Met à jour le profil utilisateur
-/// 
-/// # Arguments
-/// * `profile_data` - Les nouvelles données du profil
-/// * `db` - Le gestionnaire de base de données (injecté par Tauri)
-/// 
-/// # Returns
-/// L'utilisateur mis à jour ou une erreur
-#[tauri::command]
-pub async fn update_user_profile(
-    profile_data: UpdateProfileData,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<UserPublic, String> {
-    let service = AuthService::new(db.inner().clone());
-    service.update_profile(profile_data).await.map_err(|e| e.to_string())
-}
-
-/// Met à jour le mot de passe utilisateur
-/// 
-/// # Arguments
-/// * `password_data` - Les données du mot de passe
-/// * `db` - Le gestionnaire de base de données (injecté par Tauri)
-/// 
-/// # Returns
-/// Un succès vide ou une erreur
-#[tauri::command]
-pub async fn update_user_password(
-    password_data: UpdatePasswordData,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<(), String> {
-    let service = AuthService::new(db.inner().clone());
-    service.update_password(password_data).await.map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Enregistre un nouvel utilisateur
+    /// 
+    /// # Arguments
+    /// * `user_data` - Les données de l'utilisateur à créer
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La réponse d'authentification avec l'utilisateur et le token ou une erreur
+    #[tauri::command]
+    pub async fn register_user(
+        user_data: CreateUser,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<AuthResponse, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.register(user_data).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Connecte un utilisateur
+    /// 
+    /// # Arguments
+    /// * `login_data` - Les données de connexion
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La réponse d'authentification avec l'utilisateur et le token ou une erreur
+    #[tauri::command]
+    pub async fn login_user(
+        login_data: LoginUser,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<AuthResponse, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.login(login_data).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Déconnecte un utilisateur
+    /// 
+    /// # Arguments
+    /// * `token` - Le token de l'utilisateur à déconnecter
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Un succès vide ou une erreur
+    #[tauri::command]
+    pub async fn logout_user(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = AuthService::new(db.inner().clone());
+        service.logout(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Vérifie la validité d'un token
+    /// 
+    /// # Arguments
+    /// * `token` - Le token à vérifier
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'utilisateur correspondant au token ou None si invalide
+    #[tauri::command]
+    pub async fn verify_token(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Option<UserPublic>, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.verify_token(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour le profil utilisateur
+    /// 
+    /// # Arguments
+    /// * `profile_data` - Les nouvelles données du profil
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'utilisateur mis à jour ou une erreur
+    #[tauri::command]
+    pub async fn update_user_profile(
+        profile_data: UpdateProfileData,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<UserPublic, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.update_profile(profile_data).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour le mot de passe utilisateur
+    /// 
+    /// # Arguments
+    /// * `password_data` - Les données du mot de passe
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Un succès vide ou une erreur
+    #[tauri::command]
+    pub async fn update_user_password(
+        password_data: UpdatePasswordData,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = AuthService::new(db.inner().clone());
+        service.update_password(password_data).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Réinitialise le mot de passe d'un utilisateur (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `user_id` - L'ID de l'utilisateur qui a oublié son mot de passe
+    /// * `token` - Le token de session de l'administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le mot de passe temporaire, à changer par l'utilisateur après sa connexion, ou une erreur
+    #[tauri::command]
+    pub async fn reset_user_password(
+        user_id: i64,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<String, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.reset_user_password(user_id, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Crée un code d'invitation pour l'enregistrement d'un compte (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `invitation` - Le code (généré s'il est absent), le rôle attribué, l'expiration et l'usage unique
+    /// * `token` - Le token de session de l'administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'invitation créée ou une erreur
+    #[tauri::command]
+    pub async fn create_invitation(
+        invitation: CreateInvitation,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Invitation, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.create_invitation(invitation, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Révoque un code d'invitation (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'invitation
+    /// * `token` - Le token de session de l'administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Un succès vide ou une erreur
+    #[tauri::command]
+    pub async fn revoke_invitation(
+        id: i64,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = AuthService::new(db.inner().clone());
+        service.revoke_invitation(id, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les codes d'invitation (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `token` - Le token de session de l'administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les invitations, de la plus récente à la plus ancienne, ou une erreur
+    #[tauri::command]
+    pub async fn get_invitations(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Invitation>, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.get_invitations(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Résume l'activité de saisie de chaque utilisateur sur une période (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `date_from` - Début de la période (absent pour ne pas la limiter)
+    /// * `date_to` - Fin de la période, incluse (absente pour ne pas la limiter)
+    /// * `token` - Le token de session de l'administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'activité de chaque utilisateur, du plus actif au moins actif, ou une erreur
+    #[tauri::command]
+    pub async fn get_user_activity(
+        date_from: Option<NaiveDate>,
+        date_to: Option<NaiveDate>,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<ActiviteUtilisateur>, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.get_user_activity(date_from, date_to, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Démarre l'enrôlement du second facteur (TOTP) d'un administrateur
+    /// 
+    /// # Arguments
+    /// * `token` - Le token de session de l'administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le secret et l'URI `otpauth://` à afficher sous forme de QR code, ou une erreur
+    #[tauri::command]
+    pub async fn start_mfa_enrollment(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<MfaEnrollment, String> {
+        let service = AuthService::new(db.inner().clone());
+        service.start_mfa_enrollment(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Confirme l'enrôlement du second facteur; le code sera ensuite exigé à la connexion
+    /// 
+    /// # Arguments
+    /// * `code` - Le code affiché par l'application d'authentification
+    /// * `token` - Le token de session de l'administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Un succès vide ou une erreur
+    #[tauri::command]
+    pub async fn confirm_mfa_enrollment(
+        code: String,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = AuthService::new(db.inner().clone());
+        service.confirm_mfa_enrollment(&code, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Désactive le second facteur de l'utilisateur connecté
+    /// 
+    /// # Arguments
+    /// * `code` - Un code valide de l'application d'authentification
+    /// * `token` - Le token de session de l'utilisateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Un succès vide ou une erreur
+    #[tauri::command]
+    pub async fn disable_mfa(
+        code: String,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = AuthService::new(db.inner().clone());
+        service.disable_mfa(&code, &token).await.map_err(|e| e.to_string())
+    }
 }
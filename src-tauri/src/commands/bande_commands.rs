@@ -4,120 +4,301 @@
 /// in the farm management application with the new batiment structure.
 
 use tauri::State;
+use chrono::Local;
 use std::sync::Arc;
-use crate::database::DatabaseManager;
-use crate::models::{Bande, BandeWithDetails, CreateBande, UpdateBande, PaginatedBandes};
-use crate::repositories::BandeRepository;
-
-/// Create a new bande
-#[tauri::command]
-pub async fn create_bande(
-    db: State<'_, Arc<DatabaseManager>>,
-    bande: CreateBande,
-) -> Result<Bande, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
-    
-    BandeRepository::create(&conn, &bande)
-        .map_err(|e| e.to_string())
+use crate::database::{DatabaseManager, Storage};
+use crate::error::ErreurCommande;
+use crate::models::{Bande, BandeLoadOptions, BudgetBande, BandeWithDetails, CreateBande, CreateBatiment, FiltreBandes, ValidationCreationBande, UpdateBande, PaginatedBandes, PaginatedActiviteBande, EntreeAudit, ChronologieBande};
+use crate::repositories::{ActiviteRepository, BandeRepository, BudgetRepository, FiltreEnregistreRepository};
+use crate::services::{AuthService, BandeService, MetriquesCommandes};
+
+commande_mesuree! {
+    /// Create a new bande
+    ///
+    /// The user of the session `token`, if any, is recorded as its author.
+    /// `budget`, if any, is saved with the bande in the same transaction.
+    #[tauri::command]
+    pub async fn create_bande(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande: CreateBande,
+        token: Option<String>,
+        budget: Option<BudgetBande>,
+    ) -> Result<Bande, String> {
+        let created_by = AuthService::new(db.inner().clone())
+            .author_id(token.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| {
+                let bande = BandeRepository::create(tx, &bande, created_by)?;
+                if let (Some(budget), Some(bande_id)) = (&budget, bande.id) {
+                    BudgetRepository::set(tx, bande_id, budget)?;
+                }
+                Ok(bande)
+            })
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Get all bandes with their batiments (simple, non-paginated)
-#[tauri::command]
-pub async fn get_all_bandes(
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Vec<BandeWithDetails>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
-    
-    BandeRepository::get_all_list(&conn)
-        .map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Check a bande creation without writing anything
+    ///
+    /// Returns every error and warning (building availability, personnel, poussin,
+    /// duplicate numero...) so the creation wizard can validate each step;
+    /// `batiments` is omitted to check the bande step only.
+    #[tauri::command]
+    pub async fn validate_bande_creation(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande: CreateBande,
+        batiments: Option<Vec<CreateBatiment>>,
+    ) -> Result<ValidationCreationBande, String> {
+        let service = BandeService::new(db.inner().clone());
+        service.validate_bande_creation(bande, batiments).await.map_err(|e| e.to_string())
+    }
 }
 
-/// Get bandes by ferme with their batiments (simple, non-paginated)
-#[tauri::command]
-pub async fn get_bandes_by_ferme(
-    db: State<'_, Arc<DatabaseManager>>,
-    ferme_id: i64,
-) -> Result<Vec<BandeWithDetails>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Get all bandes with their batiments (simple, non-paginated)
+    ///
+    /// `options` selects the loaded data (batiments and contour by default).
+    #[tauri::command]
+    pub async fn get_all_bandes(
+        db: State<'_, Arc<DatabaseManager>>,
+        options: Option<BandeLoadOptions>,
+    ) -> Result<Vec<BandeWithDetails>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BandeRepository::get_by_ferme(&conn, ferme_id)
-        .map_err(|e| e.to_string())
-}
-
-/// Get latest bandes by ferme (for selectors)
-#[tauri::command]
-pub async fn get_latest_bandes_by_ferme(
-    db: State<'_, Arc<DatabaseManager>>,
-    ferme_id: i64,
-    limit: Option<u32>,
-) -> Result<Vec<BandeWithDetails>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
-    
-    BandeRepository::get_latest_by_ferme(&conn, ferme_id, limit.unwrap_or(10))
-        .map_err(|e| e.to_string())
-}
-
-/// Get bandes by ferme with pagination and optional date range filtering
-#[tauri::command]
-pub async fn get_bandes_by_ferme_paginated(
-    db: State<'_, Arc<DatabaseManager>>,
-    ferme_id: i64,
-    page: u32,
-    per_page: u32,
-    date_from: Option<String>, // Format: "YYYY-MM-DD"
-    date_to: Option<String>,   // Format: "YYYY-MM-DD"
-) -> Result<PaginatedBandes, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+        BandeRepository::get_all_list(&conn, &options.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get bandes by ferme with their batiments (simple, non-paginated)
+    ///
+    /// `options` selects the loaded data (batiments and contour by default).
+    #[tauri::command]
+    pub async fn get_bandes_by_ferme(
+        db: State<'_, Arc<DatabaseManager>>,
+        ferme_id: i64,
+        options: Option<BandeLoadOptions>,
+    ) -> Result<Vec<BandeWithDetails>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BandeRepository::get_by_ferme_paginated(&conn, ferme_id, page, per_page, date_from, date_to)
-        .map_err(|e| e.to_string())
+        BandeRepository::get_by_ferme(&conn, ferme_id, &options.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Get a bande by ID with its batiments
-#[tauri::command]
-pub async fn get_bande_by_id(
-    db: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-) -> Result<Option<BandeWithDetails>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Get latest bandes by ferme (for selectors)
+    ///
+    /// `options` selects the loaded data (batiments and contour by default).
+    #[tauri::command]
+    pub async fn get_latest_bandes_by_ferme(
+        db: State<'_, Arc<DatabaseManager>>,
+        ferme_id: i64,
+        limit: Option<u32>,
+        options: Option<BandeLoadOptions>,
+    ) -> Result<Vec<BandeWithDetails>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BandeRepository::get_by_id(&conn, id)
-        .map_err(|e| e.to_string())
-}
-
-/// Update a bande
-#[tauri::command]
-pub async fn update_bande(
-    db: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-    bande: UpdateBande,
-) -> Result<(), String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+        BandeRepository::get_latest_by_ferme(&conn, ferme_id, limit.unwrap_or(10), &options.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get bandes by ferme with pagination and optional date range, tag and saved filtering
+    ///
+    /// `options` selects the loaded data (batiments and contour by default);
+    /// `tag_id` keeps only the bandes carrying that tag. The criteria of the saved
+    /// filter `filtre_id` apply where no explicit criterion is given.
+    #[tauri::command]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_bandes_by_ferme_paginated(
+        db: State<'_, Arc<DatabaseManager>>,
+        ferme_id: i64,
+        page: u32,
+        per_page: u32,
+        date_from: Option<String>, // Format: "YYYY-MM-DD"
+        date_to: Option<String>,   // Format: "YYYY-MM-DD"
+        tag_id: Option<i64>,
+        filtre_id: Option<i64>,
+        options: Option<BandeLoadOptions>,
+    ) -> Result<PaginatedBandes, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
+
+        let mut filtre = FiltreBandes { date_from, date_to, tag_id, ..Default::default() };
+        if let Some(filtre_id) = filtre_id {
+            let enregistre = FiltreEnregistreRepository::get_filtre_bandes(&conn, filtre_id).map_err(|e| e.to_string())?;
+            filtre = filtre.ou(enregistre);
+        }
     
-    BandeRepository::update(&conn, id, &bande)
-        .map_err(|e| e.to_string())
+        BandeRepository::get_by_ferme_paginated(&conn, ferme_id, page, per_page, &filtre, &options.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Delete a bande (will cascade delete batiments)
-#[tauri::command]
-pub async fn delete_bande(
-    db: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-) -> Result<(), String> {
-    let mut conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Get a bande by ID with its batiments
+    ///
+    /// `options` selects the loaded data (batiments and contour by default).
+    #[tauri::command]
+    pub async fn get_bande_by_id(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        options: Option<BandeLoadOptions>,
+    ) -> Result<Option<BandeWithDetails>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BandeRepository::delete(&mut conn, id)
-        .map_err(|e| e.to_string())
+        BandeRepository::get_by_id(&conn, id, &options.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Update a bande
+    ///
+    /// A stale `version` is reported as a structured conflict error.
+    #[tauri::command]
+    pub async fn update_bande(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        bande: UpdateBande,
+    ) -> Result<(), ErreurCommande> {
+        // The bande and its custom field values are saved together
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| BandeRepository::update(tx, id, &bande))
+            .map_err(ErreurCommande::from)
+    }
 }
 
-/// Get available batiment numbers for a ferme
-#[tauri::command]
-pub async fn get_available_batiments(
-    db: State<'_, Arc<DatabaseManager>>,
-    ferme_id: i64,
-) -> Result<Vec<String>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Delete a bande (will cascade delete batiments)
+    #[tauri::command]
+    pub async fn delete_bande(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| BandeRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get available batiment numbers for a ferme
+    ///
+    /// Buildings hosting a bande in progress are left out unless
+    /// `autoriser_cohabitation` is set.
+    #[tauri::command]
+    pub async fn get_available_batiments(
+        db: State<'_, Arc<DatabaseManager>>,
+        ferme_id: i64,
+        autoriser_cohabitation: Option<bool>,
+    ) -> Result<Vec<String>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BandeRepository::get_available_batiments(&conn, ferme_id)
-        .map_err(|e| e.to_string())
+        BandeRepository::get_available_batiments(&conn, ferme_id, autoriser_cohabitation.unwrap_or(false))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the activity feed of a bande (deliveries, maladies, analyses, notes, litter), most recent first
+    #[tauri::command]
+    pub async fn get_bande_activity(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    ) -> Result<PaginatedActiviteBande, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
+
+        ActiviteRepository::get_by_bande(&conn, bande_id, page.unwrap_or(1), per_page.unwrap_or(20))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Close a bande: its semaines and suivi become read-only
+    #[tauri::command]
+    pub async fn close_bande(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        token: String,
+    ) -> Result<(), String> {
+        let service = BandeService::new(db.inner().clone());
+        service.close_bande(id, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Reopen a closed bande (admins only), logging the reason in the audit log
+    #[tauri::command]
+    pub async fn reopen_bande(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        reason: String,
+        token: String,
+    ) -> Result<(), String> {
+        let service = BandeService::new(db.inner().clone());
+        service.reopen_bande(id, &reason, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the audit log of a bande (closings, reopenings), most recent first
+    #[tauri::command]
+    pub async fn get_bande_audit_log(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+    ) -> Result<Vec<EntreeAudit>, String> {
+        let service = BandeService::new(db.inner().clone());
+        service.get_bande_audit_log(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the timeline of a bande: age today, current week and expected milestones
+    /// (planned treatments, feed phase changes, projected catch)
+    #[tauri::command]
+    pub async fn get_bande_timeline(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+    ) -> Result<ChronologieBande, String> {
+        let service = BandeService::new(db.inner().clone());
+        service
+            .get_bande_timeline(bande_id, Local::now().date_naive())
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the numbering pattern of new bandes (e.g. `{year}-{ferme_code}-{seq:03}`)
+    #[tauri::command]
+    pub async fn get_bande_numbering_pattern(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<String, String> {
+        let service = BandeService::new(db.inner().clone());
+        service.get_numbering_pattern().await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Set the numbering pattern of new bandes; existing bandes keep their number
+    #[tauri::command]
+    pub async fn set_bande_numbering_pattern(
+        db: State<'_, Arc<DatabaseManager>>,
+        pattern: String,
+    ) -> Result<String, String> {
+        let service = BandeService::new(db.inner().clone());
+        service.set_numbering_pattern(&pattern).await.map_err(|e| e.to_string())
+    }
 }
@@ -5,128 +5,180 @@
 
 use tauri::State;
 use std::sync::Arc;
-use crate::database::DatabaseManager;
-use crate::models::{Batiment, CreateBatiment, UpdateBatiment, BatimentWithDetails, Maladie};
+use crate::database::{DatabaseManager, Storage};
+use crate::error::ErreurCommande;
+use crate::models::{Batiment, CreateBatiment, UpdateBatiment, BatimentWithDetails, Maladie, ModificationBatiment};
 use crate::repositories::BatimentRepository;
 use crate::services::semaine_service::SemaineService;
+use crate::services::{AuthService, MetriquesCommandes};
 
-/// Create a new batiment
-/// 
-/// After creating the batiment, this command automatically initializes
-/// the 8 semaines (weeks) for tracking purposes.
-#[tauri::command]
-pub async fn create_batiment(
-    db: State<'_, Arc<DatabaseManager>>,
-    batiment: CreateBatiment,
-) -> Result<Batiment, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Create a new batiment
+    /// 
+    /// After creating the batiment, this command automatically initializes
+    /// the 8 semaines (weeks) for tracking purposes. The user of the session
+    /// `token`, if any, is recorded as the author of the batiment.
+    #[tauri::command]
+    pub async fn create_batiment(
+        db: State<'_, Arc<DatabaseManager>>,
+        batiment: CreateBatiment,
+        token: Option<String>,
+    ) -> Result<Batiment, String> {
+        let created_by = AuthService::new(db.inner().clone())
+            .author_id(token.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = db.inner().clone();
+
+        // Create the batiment with its custom field values
+        let created_batiment = storage
+            .write(|tx| BatimentRepository::create(tx, &batiment, created_by))
+            .map_err(|e| e.to_string())?;
     
-    // Create the batiment
-    let created_batiment = BatimentRepository::create(&conn, &batiment)
-        .map_err(|e| e.to_string())?;
+        // Initialize the 8 semaines for this batiment
+        if let Some(batiment_id) = created_batiment.id {
+            let semaine_service = SemaineService::new(db.inner().clone());
+            semaine_service.initialize_batiment_semaines(batiment_id)
+                .await
+                .map_err(|e| format!("Erreur lors de l'initialisation des semaines: {}", e))?;
+        }
     
-    // Initialize the 8 semaines for this batiment
-    if let Some(batiment_id) = created_batiment.id {
-        let semaine_service = SemaineService::new(db.inner().clone());
-        semaine_service.initialize_batiment_semaines(batiment_id)
-            .await
-            .map_err(|e| format!("Erreur lors de l'initialisation des semaines: {}", e))?;
+        Ok(created_batiment)
     }
-    
-    Ok(created_batiment)
 }
 
-/// Get all batiments for a specific bande
-#[tauri::command]
-pub async fn get_batiments_by_bande(
-    db: State<'_, Arc<DatabaseManager>>,
-    bande_id: i64,
-) -> Result<Vec<BatimentWithDetails>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Get all batiments for a specific bande
+    #[tauri::command]
+    pub async fn get_batiments_by_bande(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+    ) -> Result<Vec<BatimentWithDetails>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BatimentRepository::get_by_bande(&conn, bande_id)
-        .map_err(|e| e.to_string())
+        BatimentRepository::get_by_bande(&conn, bande_id)
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Get a batiment by ID
-#[tauri::command]
-pub async fn get_batiment_by_id(
-    db: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-) -> Result<Option<BatimentWithDetails>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Get a batiment by ID
+    #[tauri::command]
+    pub async fn get_batiment_by_id(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<Option<BatimentWithDetails>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BatimentRepository::get_by_id(&conn, id)
-        .map_err(|e| e.to_string())
+        BatimentRepository::get_by_id(&conn, id)
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Update a batiment
-#[tauri::command]
-pub async fn update_batiment(
-    db: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-    batiment: UpdateBatiment,
-) -> Result<(), String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
-    
-    BatimentRepository::update(&conn, id, &batiment)
-        .map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Update a batiment
+    ///
+    /// A stale `version` is reported as a structured conflict error.
+    #[tauri::command]
+    pub async fn update_batiment(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        batiment: UpdateBatiment,
+    ) -> Result<(), ErreurCommande> {
+        // The batiment and its custom field values are saved together
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| BatimentRepository::update(tx, id, &batiment))
+            .map_err(ErreurCommande::from)
+    }
 }
 
-/// Delete a batiment
-#[tauri::command]
-pub async fn delete_batiment(
-    db: State<'_, Arc<DatabaseManager>>,
-    id: i64,
-) -> Result<(), String> {
-    let mut conn = db.get_connection().map_err(|e| e.to_string())?;
-    
-    BatimentRepository::delete(&mut conn, id)
-        .map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Reassign the personnel or poussin type of several batiments of a bande in one transaction
+    ///
+    /// Returns the batiments of the bande after the changes.
+    #[tauri::command]
+    pub async fn bulk_update_batiments(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+        changes: Vec<ModificationBatiment>,
+    ) -> Result<Vec<BatimentWithDetails>, String> {
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| {
+                BatimentRepository::bulk_update(tx, bande_id, &changes)?;
+                BatimentRepository::get_by_bande(tx, bande_id)
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete a batiment
+    #[tauri::command]
+    pub async fn delete_batiment(
+        db: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| BatimentRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Get available batiment numbers for a ferme (used for validation)
-#[tauri::command]
-pub async fn get_available_batiment_numbers(
-    db: State<'_, Arc<DatabaseManager>>,
-    ferme_id: i64,
-) -> Result<Vec<String>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
+commande_mesuree! {
+    /// Get available batiment numbers for a ferme (used for validation)
+    #[tauri::command]
+    pub async fn get_available_batiment_numbers(
+        db: State<'_, Arc<DatabaseManager>>,
+        ferme_id: i64,
+    ) -> Result<Vec<String>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
     
-    BatimentRepository::get_available_batiment_numbers(&conn, ferme_id)
-        .map_err(|e| e.to_string())
+        BatimentRepository::get_available_batiment_numbers(&conn, ferme_id)
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Ajoute une maladie à un bâtiment spécifique
-#[tauri::command]
-pub async fn add_maladie_to_batiment(
-    db: State<'_, Arc<DatabaseManager>>,
-    batiment_id: i64,
-    maladie_id: i64,
-) -> Result<(), String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
-    BatimentRepository::add_maladie_to_batiment(&conn, batiment_id, maladie_id)
-        .map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Ajoute une maladie à un bâtiment spécifique
+    #[tauri::command]
+    pub async fn add_maladie_to_batiment(
+        db: State<'_, Arc<DatabaseManager>>,
+        batiment_id: i64,
+        maladie_id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| BatimentRepository::add_maladie_to_batiment(tx, batiment_id, maladie_id))
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Ajoute une maladie à tous les bâtiments d'une même bande
-#[tauri::command]
-pub async fn add_maladie_to_bande_batiments(
-    db: State<'_, Arc<DatabaseManager>>,
-    bande_id: i64,
-    maladie_id: i64,
-) -> Result<usize, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
-    BatimentRepository::add_maladie_to_bande_batiments(&conn, bande_id, maladie_id)
-        .map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Ajoute une maladie à tous les bâtiments d'une même bande
+    #[tauri::command]
+    pub async fn add_maladie_to_bande_batiments(
+        db: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+        maladie_id: i64,
+    ) -> Result<usize, String> {
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        storage
+            .write(|tx| BatimentRepository::add_maladie_to_bande_batiments(tx, bande_id, maladie_id))
+            .map_err(|e| e.to_string())
+    }
 }
 
-/// Récupère les maladies liées à un bâtiment
-#[tauri::command]
-pub async fn get_maladies_by_batiment(
-    db: State<'_, Arc<DatabaseManager>>,
-    batiment_id: i64,
-) -> Result<Vec<Maladie>, String> {
-    let conn = db.get_connection().map_err(|e| e.to_string())?;
-    BatimentRepository::get_maladies_by_batiment(&conn, batiment_id).map_err(|e| e.to_string())
+commande_mesuree! {
+    /// Récupère les maladies liées à un bâtiment
+    #[tauri::command]
+    pub async fn get_maladies_by_batiment(
+        db: State<'_, Arc<DatabaseManager>>,
+        batiment_id: i64,
+    ) -> Result<Vec<Maladie>, String> {
+        let conn = db.get_connection().map_err(|e| e.to_string())?;
+        BatimentRepository::get_maladies_by_batiment(&conn, batiment_id).map_err(|e| e.to_string())
+    }
 }
\ No newline at end of file
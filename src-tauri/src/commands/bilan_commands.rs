@@ -0,0 +1,142 @@
+use crate::database::DatabaseManager;
+use crate::models::{BilanFinancierBande, BudgetBande, ContratBande, EcartBudgetBande, ReglementContrat};
+use crate::services::{BilanService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère le bilan financier d'une bande (aliment, poussins et soins)
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les coûts de la bande ou une erreur
+    #[tauri::command]
+    pub async fn get_bande_financial_summary(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<BilanFinancierBande, String> {
+        let service = BilanService::new(db.inner().clone());
+        service.get_bande_financial_summary(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère le budget prévisionnel d'une bande
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le budget (`None` si aucun budget n'a été défini) ou une erreur
+    #[tauri::command]
+    pub async fn get_bande_budget(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Option<BudgetBande>, String> {
+        let service = BilanService::new(db.inner().clone());
+        service.get_bande_budget(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Définit ou remplace le budget prévisionnel d'une bande
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `budget` - Les prévisions (aliment, mortalité, coûts)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le budget enregistré ou une erreur
+    #[tauri::command]
+    pub async fn set_bande_budget(
+        bande_id: i64,
+        budget: BudgetBande,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<BudgetBande, String> {
+        let service = BilanService::new(db.inner().clone());
+        service.set_bande_budget(bande_id, budget).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Compare le budget d'une bande à son réalisé à ce jour
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le prévu, le réalisé et l'écart de chaque poste ou une erreur
+    #[tauri::command]
+    pub async fn get_budget_variance(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<EcartBudgetBande, String> {
+        let service = BilanService::new(db.inner().clone());
+        service.get_budget_variance(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère le contrat d'élevage d'une bande
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le contrat (`None` si la bande n'est pas sous contrat) ou une erreur
+    #[tauri::command]
+    pub async fn get_bande_contract(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Option<ContratBande>, String> {
+        let service = BilanService::new(db.inner().clone());
+        service.get_bande_contract(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Définit ou remplace le contrat d'élevage d'une bande
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `contrat` - Le prix garanti, les cibles et les primes
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le contrat enregistré ou une erreur
+    #[tauri::command]
+    pub async fn set_bande_contract(
+        bande_id: i64,
+        contrat: ContratBande,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ContratBande, String> {
+        let service = BilanService::new(db.inner().clone());
+        service.set_bande_contract(bande_id, contrat).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Calcule le règlement attendu d'une bande sous contrat
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le décompte (poids vif, primes, intrants déduits, net) ou une erreur
+    #[tauri::command]
+    pub async fn compute_settlement(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ReglementContrat, String> {
+        let service = BilanService::new(db.inner().clone());
+        service.compute_settlement(bande_id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::database::DatabaseManager;
+use crate::models::{AgregatCapteurs, ResultatImportCapteurs};
+use crate::services::{CapteurService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Importe un export CSV ou JSON d'un contrôleur d'ambiance
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `numero_batiment` - Bâtiment par défaut, pour les exports sans colonne bâtiment
+    /// * `format` - "csv" ou "json"
+    /// * `contenu` - Le contenu de l'export
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le nombre de mesures importées et les lignes refusées ou une erreur
+    #[tauri::command]
+    pub async fn import_mesures_capteurs(
+        ferme_id: i64,
+        numero_batiment: Option<String>,
+        format: String,
+        contenu: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ResultatImportCapteurs, String> {
+        let service = CapteurService::new(db.inner().clone());
+        service
+            .import_mesures(ferme_id, numero_batiment, &format, &contenu)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère les mesures d'ambiance agrégées d'un bâtiment (graphiques)
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `numero_batiment` - Le numéro du bâtiment physique
+    /// * `date_from` - Premier jour inclus ("YYYY-MM-DD")
+    /// * `date_to` - Dernier jour inclus ("YYYY-MM-DD")
+    /// * `granularite` - "hour" ou "day" ("hour" par défaut)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les agrégats triés par période ou une erreur
+    #[tauri::command]
+    pub async fn get_mesures_capteurs(
+        ferme_id: i64,
+        numero_batiment: String,
+        date_from: Option<String>,
+        date_to: Option<String>,
+        granularite: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<AgregatCapteurs>, String> {
+        let service = CapteurService::new(db.inner().clone());
+        service
+            .get_agregats(ferme_id, &numero_batiment, date_from, date_to, granularite)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::{ChampPersonnalise, CreateChampPersonnalise};
+use crate::repositories::ChampPersonnaliseRepository;
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Define a custom field for bandes or batiments
+    #[tauri::command]
+    pub async fn create_custom_field(
+        database: State<'_, Arc<DatabaseManager>>,
+        champ: CreateChampPersonnalise,
+    ) -> Result<ChampPersonnalise, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| ChampPersonnaliseRepository::create(tx, &champ))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// List the custom fields, optionally those of one entity (`bande` or `batiment`)
+    #[tauri::command]
+    pub async fn get_custom_fields(
+        database: State<'_, Arc<DatabaseManager>>,
+        entite: Option<String>,
+    ) -> Result<Vec<ChampPersonnalise>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        ChampPersonnaliseRepository::get_all(&conn, entite.as_deref()).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete a custom field and the values recorded for it
+    #[tauri::command]
+    pub async fn delete_custom_field(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| ChampPersonnaliseRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
+}
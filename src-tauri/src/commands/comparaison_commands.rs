@@ -0,0 +1,106 @@
+use crate::database::DatabaseManager;
+use crate::models::{ComparaisonPerformance, IndiceProductionBande, StatistiquesSaisonnieres};
+use crate::services::{ComparaisonService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Compare les indicateurs de performance de toutes les fermes
+    /// 
+    /// # Arguments
+    /// * `date_from` - Début de période sur la date d'entrée des bandes ("YYYY-MM-DD")
+    /// * `date_to` - Fin de période sur la date d'entrée des bandes ("YYYY-MM-DD")
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les indicateurs de chaque ferme ou une erreur
+    #[tauri::command]
+    pub async fn compare_fermes(
+        date_from: Option<String>,
+        date_to: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<ComparaisonPerformance>, String> {
+        let service = ComparaisonService::new(db.inner().clone());
+        service.compare_fermes(date_from, date_to).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Compare les résultats des bâtiments suivis par chaque technicien
+    /// 
+    /// # Arguments
+    /// * `date_from` - Début de période sur la date d'entrée des bandes ("YYYY-MM-DD")
+    /// * `date_to` - Fin de période sur la date d'entrée des bandes ("YYYY-MM-DD")
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les indicateurs de chaque membre du personnel ou une erreur
+    #[tauri::command]
+    pub async fn compare_personnel(
+        date_from: Option<String>,
+        date_to: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<ComparaisonPerformance>, String> {
+        let service = ComparaisonService::new(db.inner().clone());
+        service.compare_personnel(date_from, date_to).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Compare la mortalité et la croissance par type de poussin
+    /// 
+    /// # Arguments
+    /// * `date_from` - Début de période sur la date d'entrée des bandes ("YYYY-MM-DD")
+    /// * `date_to` - Fin de période sur la date d'entrée des bandes ("YYYY-MM-DD")
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les indicateurs de chaque type de poussin ou une erreur
+    #[tauri::command]
+    pub async fn compare_poussins(
+        date_from: Option<String>,
+        date_to: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<ComparaisonPerformance>, String> {
+        let service = ComparaisonService::new(db.inner().clone());
+        service.compare_poussins(date_from, date_to).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Calcule l'indice de production européen (EPEF) d'une bande et de ses bâtiments
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les indicateurs de la bande et de chaque bâtiment ou une erreur
+    #[tauri::command]
+    pub async fn get_production_index(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<IndiceProductionBande, String> {
+        let service = ComparaisonService::new(db.inner().clone());
+        service.get_production_index(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Compare les bandes clôturées selon leur mois et leur saison d'entrée
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - La ferme concernée, toutes les fermes si absente
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les indicateurs par mois et par saison ou une erreur
+    #[tauri::command]
+    pub async fn get_seasonal_statistics(
+        ferme_id: Option<i64>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<StatistiquesSaisonnieres, String> {
+        let service = ComparaisonService::new(db.inner().clone());
+        service.get_seasonal_statistics(ferme_id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::database::DatabaseManager;
+use crate::models::{ConfigurationAppliquee, ConfigurationInitiale};
+use crate::services::{ConfigurationService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Indique si l'assistant de premier démarrage doit être proposé
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Vrai tant qu'aucune ferme n'a été créée
+    #[tauri::command]
+    pub async fn needs_first_run_setup(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<bool, String> {
+        let service = ConfigurationService::new(db.inner().clone());
+        service.needs_first_run_setup().await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Enregistre la configuration saisie dans l'assistant de premier démarrage
+    /// 
+    /// # Arguments
+    /// * `configuration` - Les fermes, le nombre de semaines et le poids d'un sachet
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les fermes créées et les paramètres en vigueur, ou une erreur
+    #[tauri::command]
+    pub async fn first_run_setup(
+        configuration: ConfigurationInitiale,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ConfigurationAppliquee, String> {
+        let service = ConfigurationService::new(db.inner().clone());
+        service.first_run_setup(configuration).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,23 @@
+use crate::database::DatabaseManager;
+use crate::services::{DemoDataSummary, DemoService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Génère des données de démonstration
+    /// 
+    /// # Arguments
+    /// * `scale` - Le nombre de fermes à créer (1 à 50)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le résumé des éléments créés ou une erreur
+    #[tauri::command]
+    pub async fn generate_demo_data(
+        scale: u32,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<DemoDataSummary, String> {
+        let service = DemoService::new(db.inner().clone());
+        service.generate_demo_data(scale).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::database::OuvertureBase;
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State};
+
+commande_mesuree! {
+    /// Indique si la base chiffrée attend sa phrase secrète
+    /// 
+    /// Tant qu'elle est verrouillée, les autres commandes échouent: l'interface
+    /// affiche alors la saisie de la phrase secrète.
+    /// 
+    /// # Arguments
+    /// * `ouverture` - L'ouverture de la base principale (injectée par Tauri)
+    /// 
+    /// # Returns
+    /// Vrai tant que la base n'est pas ouverte
+    #[tauri::command]
+    pub async fn is_database_locked(
+        ouverture: State<'_, OuvertureBase>,
+    ) -> Result<bool, String> {
+        Ok(ouverture.est_verrouillee())
+    }
+}
+
+commande_mesuree! {
+    /// Ouvre la base chiffrée avec la phrase secrète saisie, puis démarre les services
+    /// 
+    /// # Arguments
+    /// * `passphrase` - La phrase secrète de la base
+    /// * `app` - L'application (injectée par Tauri)
+    /// * `ouverture` - L'ouverture de la base principale (injectée par Tauri)
+    /// 
+    /// # Returns
+    /// Rien en cas de succès, ou une erreur (phrase secrète incorrecte, base déjà ouverte)
+    #[tauri::command]
+    pub async fn unlock_database<R: Runtime>(
+        passphrase: String,
+        app: AppHandle<R>,
+        ouverture: State<'_, OuvertureBase>,
+    ) -> Result<(), String> {
+        let db_manager = ouverture.deverrouiller(&passphrase).map_err(|e| e.to_string())?;
+        crate::demarrer(&app, db_manager);
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use crate::database::DatabaseManager;
+use crate::models::{CreateEntretienEquipement, CreateEquipement, EntretienEquipement, Equipement, UpdateEquipement};
+use crate::repositories::{EquipementRepository, EquipementRepositoryTrait};
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Enregistre un équipement d'une ferme
+    /// 
+    /// # Arguments
+    /// * `equipement` - Les données de l'équipement
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'équipement créé ou une erreur
+    #[tauri::command]
+    pub async fn create_equipement(
+        equipement: CreateEquipement,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Equipement, String> {
+        let repo = EquipementRepository::new(db.inner().clone());
+        repo.create(equipement).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les équipements d'une ferme
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les équipements, du prochain entretien le plus proche au plus lointain
+    #[tauri::command]
+    pub async fn get_equipements_by_ferme(
+        ferme_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Equipement>, String> {
+        let repo = EquipementRepository::new(db.inner().clone());
+        repo.get_by_ferme(ferme_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour un équipement
+    /// 
+    /// # Arguments
+    /// * `equipement` - Les nouvelles données de l'équipement
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'équipement mis à jour ou une erreur
+    #[tauri::command]
+    pub async fn update_equipement(
+        equipement: UpdateEquipement,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Equipement, String> {
+        let repo = EquipementRepository::new(db.inner().clone());
+        repo.update(equipement).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime un équipement et ses entretiens
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'équipement
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_equipement(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let repo = EquipementRepository::new(db.inner().clone());
+        repo.delete(id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Enregistre un entretien réalisé sur un équipement
+    /// 
+    /// # Arguments
+    /// * `entretien` - Les données de l'entretien
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'équipement avec sa nouvelle échéance d'entretien
+    #[tauri::command]
+    pub async fn add_entretien_equipement(
+        entretien: CreateEntretienEquipement,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Equipement, String> {
+        let repo = EquipementRepository::new(db.inner().clone());
+        repo.add_entretien(entretien).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les entretiens d'un équipement
+    /// 
+    /// # Arguments
+    /// * `equipement_id` - L'ID de l'équipement
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les entretiens, du plus récent au plus ancien
+    #[tauri::command]
+    pub async fn get_entretiens_equipement(
+        equipement_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<EntretienEquipement>, String> {
+        let repo = EquipementRepository::new(db.inner().clone());
+        repo.get_entretiens(equipement_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime un entretien
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'entretien
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_entretien_equipement(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let repo = EquipementRepository::new(db.inner().clone());
+        repo.delete_entretien(id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::database::DatabaseManager;
+use crate::models::{RapportExport, TvaTrimestre};
+use crate::services::{ExportService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Exporte un instantané dénormalisé (bande_facts, daily_facts) dans un fichier SQLite
+    /// 
+    /// Le fichier peut être ouvert dans Excel ou Power BI sans toucher à la base de l'application.
+    /// 
+    /// # Arguments
+    /// * `path` - Le chemin du fichier à créer (remplacé s'il existe)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le chemin du fichier et le nombre de lignes exportées, ou une erreur
+    #[tauri::command]
+    pub async fn export_reporting_snapshot(
+        path: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<RapportExport, String> {
+        let service = ExportService::new(db.inner().clone());
+        service.export_reporting_snapshot(&path).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récapitule la TVA payée sur les achats d'une année, par trimestre et par taux
+    /// 
+    /// # Arguments
+    /// * `annee` - L'année civile
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Une ligne par trimestre et par taux ou une erreur
+    #[tauri::command]
+    pub async fn get_vat_summary(
+        annee: i32,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<TvaTrimestre>, String> {
+        let service = ExportService::new(db.inner().clone());
+        service.get_vat_summary(annee).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Exporte le récapitulatif de TVA d'une année dans un fichier CSV
+    /// 
+    /// # Arguments
+    /// * `annee` - L'année civile
+    /// * `path` - Le chemin du fichier à créer (remplacé s'il existe)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le chemin du fichier créé ou une erreur
+    #[tauri::command]
+    pub async fn export_vat_summary(
+        annee: i32,
+        path: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<String, String> {
+        let service = ExportService::new(db.inner().clone());
+        service.export_vat_summary(annee, &path).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Exporte une bande dans le format d'échange JSON versionné
+    /// 
+    /// Le fichier peut être lu par un autre logiciel d'élevage ou envoyé au portail de l'intégrateur.
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande à exporter
+    /// * `path` - Le chemin du fichier JSON à créer (remplacé s'il existe)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le chemin du fichier créé ou une erreur
+    #[tauri::command]
+    pub async fn export_bande_json(
+        bande_id: i64,
+        path: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<String, String> {
+        let service = ExportService::new(db.inner().clone());
+        service.export_bande_json(bande_id, &path).await.map_err(|e| e.to_string())
+    }
+}
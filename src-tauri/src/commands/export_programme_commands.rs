@@ -0,0 +1,79 @@
+use crate::database::DatabaseManager;
+use crate::models::{CreateExportProgramme, ExportProgramme};
+use crate::services::{ExportProgrammeService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Programme un export vers un dossier (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `export` - Le type d'export, le dossier de destination et la fréquence
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'export programmé ou une erreur
+    #[tauri::command]
+    pub async fn create_scheduled_export(
+        export: CreateExportProgramme,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ExportProgramme, String> {
+        let service = ExportProgrammeService::new(db.inner().clone());
+        service.create_scheduled_export(export, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les exports programmés et le statut de leur dernière exécution
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn get_scheduled_exports(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<ExportProgramme>, String> {
+        let service = ExportProgrammeService::new(db.inner().clone());
+        service.get_scheduled_exports().await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime un export programmé (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'export programmé
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_scheduled_export(
+        id: i64,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = ExportProgrammeService::new(db.inner().clone());
+        service.delete_scheduled_export(id, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Lance un export programmé immédiatement (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'export programmé
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'export programmé avec le statut de cette exécution, ou une erreur
+    #[tauri::command]
+    pub async fn run_scheduled_export(
+        id: i64,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ExportProgramme, String> {
+        let service = ExportProgrammeService::new(db.inner().clone());
+        service.run_scheduled_export(id, &token).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::{CreateFiltreEnregistre, FiltreEnregistre};
+use crate::repositories::FiltreEnregistreRepository;
+use crate::services::{AuthService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Save a named filter set for the user of the session `token`
+    ///
+    /// A filter with the same name and entity is replaced.
+    #[tauri::command]
+    pub async fn save_filter(
+        database: State<'_, Arc<DatabaseManager>>,
+        filtre: CreateFiltreEnregistre,
+        token: String,
+    ) -> Result<FiltreEnregistre, String> {
+        let user = AuthService::new(database.inner().clone())
+            .current_user(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| FiltreEnregistreRepository::save(tx, user.id, &filtre))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// List the saved filters of the user of the session `token`, optionally for one entity
+    #[tauri::command]
+    pub async fn get_saved_filters(
+        database: State<'_, Arc<DatabaseManager>>,
+        entite: Option<String>,
+        token: String,
+    ) -> Result<Vec<FiltreEnregistre>, String> {
+        let user = AuthService::new(database.inner().clone())
+            .current_user(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        FiltreEnregistreRepository::get_by_user(&conn, user.id, entite.as_deref()).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete a saved filter of the user of the session `token`
+    #[tauri::command]
+    pub async fn delete_saved_filter(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        token: String,
+    ) -> Result<(), String> {
+        let user = AuthService::new(database.inner().clone())
+            .current_user(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| FiltreEnregistreRepository::delete(tx, id, user.id))
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::database::DatabaseManager;
+use crate::models::ResultatFusion;
+use crate::services::{FusionService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Fusionne un doublon (personnel, soin, poussin ou maladie) dans l'entité conservée
+    /// 
+    /// # Arguments
+    /// * `entity` - Le type d'entité: "personnel", "soin", "poussin" ou "maladie"
+    /// * `source_id` - L'ID du doublon, supprimé après la fusion
+    /// * `target_id` - L'ID de l'entité conservée
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le résultat de la fusion ou une erreur
+    #[tauri::command]
+    pub async fn merge_entities(
+        entity: String,
+        source_id: i64,
+        target_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ResultatFusion, String> {
+        let service = FusionService::new(db.inner().clone());
+        service.merge_entities(&entity, source_id, target_id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::database::DatabaseManager;
+use crate::models::{CreateImmobilisation, Immobilisation, TableauAmortissement, UpdateImmobilisation};
+use crate::repositories::{ImmobilisationRepository, ImmobilisationRepositoryTrait};
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Enregistre une immobilisation dans le registre d'une ferme
+    /// 
+    /// # Arguments
+    /// * `immobilisation` - Les données de l'immobilisation
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'immobilisation créée ou une erreur
+    #[tauri::command]
+    pub async fn create_immobilisation(
+        immobilisation: CreateImmobilisation,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Immobilisation, String> {
+        let repo = ImmobilisationRepository::new(db.inner().clone());
+        repo.create(immobilisation).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les immobilisations d'une ferme
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les immobilisations, par date d'achat
+    #[tauri::command]
+    pub async fn get_immobilisations_by_ferme(
+        ferme_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Immobilisation>, String> {
+        let repo = ImmobilisationRepository::new(db.inner().clone());
+        repo.get_by_ferme(ferme_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour une immobilisation
+    /// 
+    /// # Arguments
+    /// * `immobilisation` - Les nouvelles données de l'immobilisation
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'immobilisation mise à jour ou une erreur
+    #[tauri::command]
+    pub async fn update_immobilisation(
+        immobilisation: UpdateImmobilisation,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Immobilisation, String> {
+        let repo = ImmobilisationRepository::new(db.inner().clone());
+        repo.update(immobilisation).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime une immobilisation
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'immobilisation
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_immobilisation(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let repo = ImmobilisationRepository::new(db.inner().clone());
+        repo.delete(id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Tableau d'amortissement d'une ferme pour une année civile
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `annee` - L'année civile
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La dotation de l'année de chaque immobilisation et leur total
+    #[tauri::command]
+    pub async fn get_depreciation_schedule(
+        ferme_id: i64,
+        annee: i32,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<TableauAmortissement, String> {
+        let repo = ImmobilisationRepository::new(db.inner().clone());
+        repo.get_depreciation_schedule(ferme_id, annee).await.map_err(|e| e.to_string())
+    }
+}
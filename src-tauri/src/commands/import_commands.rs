@@ -0,0 +1,52 @@
+use crate::database::DatabaseManager;
+use crate::models::{FeuilleClasseur, RapportImportBande, RapportImportClasseur};
+use crate::services::{ImportService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Importe les bandes d'un classeur Excel de l'ancien système de suivi
+    /// 
+    /// # Arguments
+    /// * `feuilles` - Les feuilles du classeur lues par le frontend, une par bande
+    /// * `dry_run` - Vrai pour obtenir le rapport sans rien enregistrer
+    /// * `token` - Le token de session de l'auteur de l'import, s'il y en a un
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le rapport des lignes lues, ignorées et en erreur, ou une erreur
+    #[tauri::command]
+    pub async fn import_legacy_workbook(
+        feuilles: Vec<FeuilleClasseur>,
+        dry_run: bool,
+        token: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<RapportImportClasseur, String> {
+        let service = ImportService::new(db.inner().clone());
+        service
+            .import_legacy_workbook(feuilles, dry_run, token.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Importe une bande d'un fichier au format d'échange JSON
+    /// 
+    /// # Arguments
+    /// * `path` - Le chemin du fichier JSON exporté par `export_bande_json` ou un autre logiciel
+    /// * `token` - Le token de session de l'auteur de l'import, s'il y en a un
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La bande créée et le nombre d'éléments importés, ou une erreur
+    #[tauri::command]
+    pub async fn import_bande_json(
+        path: String,
+        token: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<RapportImportBande, String> {
+        let service = ImportService::new(db.inner().clone());
+        service.import_bande_json(&path, token.as_deref()).await.map_err(|e| e.to_string())
+    }
+}
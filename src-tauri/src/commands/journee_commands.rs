@@ -0,0 +1,32 @@
+use crate::database::DatabaseManager;
+use crate::models::VueJournee;
+use crate::services::{AuthService, JourneeService, MetriquesCommandes};
+use chrono::Local;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère la vue du jour de tous les bâtiments des bandes actives
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `token` - Le token de session: seules les alertes auxquelles l'utilisateur est abonné sont renvoyées
+    /// 
+    /// # Returns
+    /// Pour chaque bâtiment, la saisie de la veille, les soins et tâches du jour et les alertes en cours
+    #[tauri::command]
+    pub async fn get_today_overview(
+        db: State<'_, Arc<DatabaseManager>>,
+        token: Option<String>,
+    ) -> Result<VueJournee, String> {
+        let user_id = AuthService::new(db.inner().clone())
+            .author_id(token.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        let service = JourneeService::new(db.inner().clone());
+        service
+            .get_today_overview(Local::now().date_naive(), user_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::services::{AuthService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Indique si le mode lecture seule est actif
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Vrai lorsque les modifications sont désactivées
+    #[tauri::command]
+    pub async fn get_read_only_mode(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<bool, String> {
+        Ok(db.read_only())
+    }
+}
+
+commande_mesuree! {
+    /// Active ou désactive le mode lecture seule (consultation sur une tablette partagée)
+    /// 
+    /// Tout utilisateur connecté peut l'activer; seul un administrateur peut le
+    /// désactiver. Le mode dure jusqu'à la fermeture de l'application.
+    /// 
+    /// # Arguments
+    /// * `enabled` - Vrai pour interdire les modifications
+    /// * `token` - Le token de session de l'utilisateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le mode désormais en vigueur ou une erreur
+    #[tauri::command]
+    pub async fn set_read_only_mode(
+        enabled: bool,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<bool, String> {
+        let auth = AuthService::new(db.inner().clone());
+        if enabled {
+            auth.current_user(&token).await.map_err(|e| e.to_string())?;
+        } else {
+            auth.require_admin(&token).await.map_err(|e| e.to_string())?;
+        }
+        db.set_read_only(enabled);
+        Ok(enabled)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::database::DatabaseManager;
+use crate::models::{CreateLitiere, Litiere, UpdateLitiere};
+use crate::repositories::{LitiereRepository, LitiereRepositoryTrait};
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Enregistre une livraison ou un changement de litière
+    /// 
+    /// # Arguments
+    /// * `litiere` - Les données de l'opération
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'opération créée ou une erreur
+    #[tauri::command]
+    pub async fn create_litiere(
+        litiere: CreateLitiere,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Litiere, String> {
+        let repo = LitiereRepository::new(db.inner().clone());
+        repo.create(litiere).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les opérations de litière d'un bâtiment
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les opérations, de la plus récente à la plus ancienne
+    #[tauri::command]
+    pub async fn get_litieres_by_batiment(
+        batiment_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Litiere>, String> {
+        let repo = LitiereRepository::new(db.inner().clone());
+        repo.get_by_batiment(batiment_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les opérations de litière d'une bande par date
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les opérations de tous les bâtiments de la bande
+    #[tauri::command]
+    pub async fn get_litieres_by_bande(
+        bande_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Litiere>, String> {
+        let repo = LitiereRepository::new(db.inner().clone());
+        repo.get_by_bande(bande_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour une opération de litière
+    /// 
+    /// # Arguments
+    /// * `litiere` - Les nouvelles données de l'opération
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'opération mise à jour ou une erreur
+    #[tauri::command]
+    pub async fn update_litiere(
+        litiere: UpdateLitiere,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Litiere, String> {
+        let repo = LitiereRepository::new(db.inner().clone());
+        repo.update(litiere).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime une opération de litière
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'opération
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_litiere(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let repo = LitiereRepository::new(db.inner().clone());
+        repo.delete(id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::database::DatabaseManager;
+use crate::models::{ProgrammationMaintenance, RapportIntegrite, RapportMaintenance};
+use crate::services::{MaintenanceService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Compacte la base de données et met à jour ses statistiques (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La taille de la base avant et après la maintenance, ou une erreur
+    #[tauri::command]
+    pub async fn run_database_maintenance(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<RapportMaintenance, String> {
+        let service = MaintenanceService::new(db.inner().clone());
+        service.run_database_maintenance(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère la programmation de la maintenance automatique
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La programmation et la date de la dernière maintenance, ou une erreur
+    #[tauri::command]
+    pub async fn get_database_maintenance_schedule(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ProgrammationMaintenance, String> {
+        let service = MaintenanceService::new(db.inner().clone());
+        service.get_maintenance_schedule().await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Active ou désactive la maintenance mensuelle automatique (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `mensuelle` - Vrai pour lancer la maintenance chaque mois
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La programmation enregistrée ou une erreur
+    #[tauri::command]
+    pub async fn set_database_maintenance_schedule(
+        mensuelle: bool,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ProgrammationMaintenance, String> {
+        let service = MaintenanceService::new(db.inner().clone());
+        service.set_maintenance_schedule(mensuelle, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Vérifie l'intégrité de la base de données (structure et clés étrangères)
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les problèmes relevés ou une erreur
+    #[tauri::command]
+    pub async fn check_database_integrity(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<RapportIntegrite, String> {
+        let service = MaintenanceService::new(db.inner().clone());
+        service.check_integrity().await.map_err(|e| e.to_string())
+    }
+}
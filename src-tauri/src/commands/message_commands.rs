@@ -0,0 +1,61 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::{CreateMessage, Message};
+use crate::repositories::MessageRepository;
+use crate::services::{AuthService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Post a handover message as the user of the session `token`
+    #[tauri::command]
+    pub async fn post_message(
+        database: State<'_, Arc<DatabaseManager>>,
+        message: CreateMessage,
+        token: String,
+    ) -> Result<Message, String> {
+        let user = AuthService::new(database.inner().clone())
+            .current_user(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| MessageRepository::create(tx, user.id, &message))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// List the unread messages of the user of the session `token`, optionally for one batiment
+    #[tauri::command]
+    pub async fn get_unread_messages(
+        database: State<'_, Arc<DatabaseManager>>,
+        token: String,
+        batiment_id: Option<i64>,
+    ) -> Result<Vec<Message>, String> {
+        let user = AuthService::new(database.inner().clone())
+            .current_user(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        MessageRepository::get_unread(&conn, user.id, batiment_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Mark a message as read by the user of the session `token`
+    #[tauri::command]
+    pub async fn mark_message_read(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        token: String,
+    ) -> Result<Message, String> {
+        let user = AuthService::new(database.inner().clone())
+            .current_user(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| MessageRepository::mark_read(tx, id, user.id))
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,27 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::MetriqueCommande;
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère les mesures locales des commandes (appels, échecs et durées)
+    /// 
+    /// Les mesures restent sur le poste: elles servent à repérer les opérations
+    /// lentes sur le matériel de la ferme.
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `mesures` - Les mesures en attente d'enregistrement (injectées par Tauri)
+    /// 
+    /// # Returns
+    /// Les commandes mesurées, de la plus lente en moyenne à la plus rapide, ou une erreur
+    #[tauri::command]
+    pub async fn get_performance_metrics(
+        db: State<'_, Arc<DatabaseManager>>,
+        mesures: State<'_, Arc<MetriquesCommandes>>,
+    ) -> Result<Vec<MetriqueCommande>, String> {
+        let storage: Arc<dyn Storage> = db.inner().clone();
+        mesures.get_performance_metrics(&storage).map_err(|e| e.to_string())
+    }
+}
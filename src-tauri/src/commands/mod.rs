@@ -1,3 +1,27 @@
+/// Définit une commande Tauri dont les exécutions sont mesurées par `MetriquesCommandes`
+/// 
+/// Entoure une commande `async` ordinaire, sa documentation et son attribut
+/// Tauri compris: la macro lui ajoute le paramètre `metriques` et mesure son corps
+/// sous le nom de la fonction, qui est celui de la commande pour le frontend.
+/// Le module appelant importe `State`, `Arc` et `MetriquesCommandes`.
+/// 
+/// Toutes les commandes des modules ci-dessous sont définies ainsi
+/// (vérifié par tests/etat_commandes.rs).
+macro_rules! commande_mesuree {
+    (
+        $(#[$attribut:meta])*
+        $vis:vis async fn $nom:ident $(<$($generique:ident: $borne:path),+>)?($($argument:ident: $type:ty),* $(,)?) -> $retour:ty $corps:block
+    ) => {
+        $(#[$attribut])*
+        $vis async fn $nom $(<$($generique: $borne),+>)?(
+            $($argument: $type,)*
+            metriques: State<'_, Arc<MetriquesCommandes>>,
+        ) -> $retour {
+            metriques.mesurer(stringify!($nom), async $corps).await
+        }
+    };
+}
+
 /// Tauri commands for the farm management application
 /// 
 /// This module contains all the Tauri command handlers that expose
@@ -14,6 +38,44 @@ pub mod maladie_commands;
 pub mod poussin_commands;
 pub mod semaine_commands;
 pub mod suivi_quotidien_commands;
+pub mod programme_alimentation_commands;
+pub mod demo_commands;
+pub mod configuration_commands;
+pub mod import_commands;
+pub mod profil_commands;
+pub mod lecture_seule_commands;
+pub mod webhook_commands;
+pub mod metrique_commands;
+pub mod alerte_commands;
+pub mod analyse_commands;
+pub mod note_batiment_commands;
+pub mod message_commands;
+pub mod champ_personnalise_commands;
+pub mod tag_commands;
+pub mod filtre_enregistre_commands;
+pub mod journee_commands;
+pub mod litiere_commands;
+pub mod vide_sanitaire_commands;
+pub mod immobilisation_commands;
+pub mod equipement_commands;
+pub mod comparaison_commands;
+pub mod suppression_commands;
+pub mod fusion_commands;
+pub mod plan_ferme_commands;
+pub mod weather_commands;
+pub mod capteur_commands;
+pub mod preference_commands;
+pub mod archive_commands;
+pub mod maintenance_commands;
+pub mod export_commands;
+pub mod verrouillage_commands;
+pub mod plan_soins_commands;
+pub mod bilan_commands;
+pub mod prix_commands;
+pub mod rapport_commands;
+pub mod export_programme_commands;
+pub mod statistics_commands;
+pub mod deverrouillage_commands;
 
 // Re-export all commands for easy access
 pub use ferme_commands::*;
@@ -27,3 +89,41 @@ pub use maladie_commands::*;
 pub use poussin_commands::*;
 pub use semaine_commands::*;
 pub use suivi_quotidien_commands::*;
+pub use programme_alimentation_commands::*;
+pub use demo_commands::*;
+pub use configuration_commands::*;
+pub use import_commands::*;
+pub use profil_commands::*;
+pub use lecture_seule_commands::*;
+pub use webhook_commands::*;
+pub use metrique_commands::*;
+pub use alerte_commands::*;
+pub use analyse_commands::*;
+pub use note_batiment_commands::*;
+pub use message_commands::*;
+pub use champ_personnalise_commands::*;
+pub use tag_commands::*;
+pub use filtre_enregistre_commands::*;
+pub use journee_commands::*;
+pub use litiere_commands::*;
+pub use vide_sanitaire_commands::*;
+pub use immobilisation_commands::*;
+pub use equipement_commands::*;
+pub use comparaison_commands::*;
+pub use suppression_commands::*;
+pub use fusion_commands::*;
+pub use plan_ferme_commands::*;
+pub use weather_commands::*;
+pub use capteur_commands::*;
+pub use preference_commands::*;
+pub use archive_commands::*;
+pub use maintenance_commands::*;
+pub use export_commands::*;
+pub use verrouillage_commands::*;
+pub use plan_soins_commands::*;
+pub use bilan_commands::*;
+pub use prix_commands::*;
+pub use rapport_commands::*;
+pub use export_programme_commands::*;
+pub use statistics_commands::*;
+pub use deverrouillage_commands::*;
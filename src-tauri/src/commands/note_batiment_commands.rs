@@ -0,0 +1,33 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::{CreateNoteBatiment, NoteBatiment};
+use crate::repositories::NoteBatimentRepository;
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Append a note to the timeline of a batiment
+    #[tauri::command]
+    pub async fn add_note_batiment(
+        database: State<'_, Arc<DatabaseManager>>,
+        note: CreateNoteBatiment,
+    ) -> Result<NoteBatiment, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| NoteBatimentRepository::create(tx, &note))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// List the notes of a batiment chronologically, optionally filtered by tag
+    #[tauri::command]
+    pub async fn get_notes_batiment(
+        database: State<'_, Arc<DatabaseManager>>,
+        batiment_id: i64,
+        tag: Option<String>,
+    ) -> Result<Vec<NoteBatiment>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        NoteBatimentRepository::get_by_batiment(&conn, batiment_id, tag.as_deref()).map_err(|e| e.to_string())
+    }
+}
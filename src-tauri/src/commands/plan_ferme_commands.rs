@@ -0,0 +1,96 @@
+use crate::database::DatabaseManager;
+use crate::models::{ChronologieBatiment, PlanFerme, PositionBatiment};
+use crate::services::{PlanFermeService, MetriquesCommandes};
+use chrono::NaiveDate;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère le plan du site d'une ferme avec l'état actuel de chaque bâtiment
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le plan de la ferme ou une erreur
+    #[tauri::command]
+    pub async fn get_ferme_plan(
+        ferme_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<PlanFerme, String> {
+        let service = PlanFermeService::new(db.inner().clone());
+        service.get_ferme_plan(ferme_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour les coordonnées GPS d'une ferme
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `latitude` - Latitude en degrés décimaux (absente pour effacer)
+    /// * `longitude` - Longitude en degrés décimaux (absente pour effacer)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Un succès vide ou une erreur
+    #[tauri::command]
+    pub async fn update_ferme_coordinates(
+        ferme_id: i64,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = PlanFermeService::new(db.inner().clone());
+        service
+            .update_ferme_coordinates(ferme_id, latitude, longitude)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Enregistre la position d'un bâtiment sur le plan du site
+    /// 
+    /// # Arguments
+    /// * `position` - La position du bâtiment (plan et/ou GPS)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La position enregistrée ou une erreur
+    #[tauri::command]
+    pub async fn update_batiment_position(
+        position: PositionBatiment,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<PositionBatiment, String> {
+        let service = PlanFermeService::new(db.inner().clone());
+        service.update_batiment_position(position).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère l'occupation des bâtiments d'une ferme sur une période (diagramme de Gantt)
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `date_from` - Début de la période
+    /// * `date_to` - Fin de la période (incluse)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les périodes d'occupation de chaque bâtiment ou une erreur
+    #[tauri::command]
+    pub async fn get_building_occupancy(
+        ferme_id: i64,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<ChronologieBatiment>, String> {
+        let service = PlanFermeService::new(db.inner().clone());
+        service
+            .get_building_occupancy(ferme_id, date_from, date_to)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
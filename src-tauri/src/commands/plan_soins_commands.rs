@@ -0,0 +1,60 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::plan_soins::{CreatePlanSoin, PlanSoin, UpdatePlanSoin};
+use crate::repositories::PlanSoinsRepository;
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Add a soin to the daily treatment plan of a poussin type
+    #[tauri::command]
+    pub async fn create_plan_soin(
+        database: State<'_, Arc<DatabaseManager>>,
+        plan_soin: CreatePlanSoin,
+    ) -> Result<PlanSoin, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| PlanSoinsRepository::create(tx, &plan_soin))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the daily treatment plan of a poussin type
+    #[tauri::command]
+    pub async fn get_plan_soins(
+        database: State<'_, Arc<DatabaseManager>>,
+        poussin_id: i64,
+    ) -> Result<Vec<PlanSoin>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        PlanSoinsRepository::get_by_poussin(&conn, poussin_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Update a soin of a treatment plan
+    #[tauri::command]
+    pub async fn update_plan_soin(
+        database: State<'_, Arc<DatabaseManager>>,
+        plan_soin: UpdatePlanSoin,
+    ) -> Result<PlanSoin, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| PlanSoinsRepository::update(tx, &plan_soin))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete a soin of a treatment plan
+    #[tauri::command]
+    pub async fn delete_plan_soin(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| PlanSoinsRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
+}
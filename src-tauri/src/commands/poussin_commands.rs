@@ -1,60 +1,71 @@
 use crate::database::DatabaseManager;
 use crate::models::{Poussin, CreatePoussin, UpdatePoussin, PaginatedPoussin};
 use crate::repositories::{PoussinRepository, PoussinRepositoryTrait};
+use crate::services::MetriquesCommandes;
 use std::sync::Arc;
 use tauri::State;
 
-#[tauri::command]
-pub async fn create_poussin(
-    poussin: CreatePoussin,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Poussin, String> {
-    let repo = PoussinRepository::new(db.inner().clone());
-    repo.create(poussin).await.map_err(|e| e.to_string())
+commande_mesuree! {
+    #[tauri::command]
+    pub async fn create_poussin(
+        poussin: CreatePoussin,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Poussin, String> {
+        let repo = PoussinRepository::new(db.inner().clone());
+        repo.create(poussin).await.map_err(|e| e.to_string())
+    }
 }
 
-#[tauri::command]
-pub async fn get_all_poussins(
-    page: Option<u32>,
-    perPage: Option<u32>,
-    nomSearch: Option<String>,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<PaginatedPoussin, String> {
-    let repo = PoussinRepository::new(db.inner().clone());
-    let page = page.unwrap_or(1);
-    let per_page = perPage.unwrap_or(10);
+commande_mesuree! {
+    #[tauri::command]
+    pub async fn get_all_poussins(
+        page: Option<u32>,
+        perPage: Option<u32>,
+        nomSearch: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<PaginatedPoussin, String> {
+        let repo = PoussinRepository::new(db.inner().clone());
+        let page = page.unwrap_or(1);
+        let per_page = perPage.unwrap_or(10);
     
-    // Convert empty strings to None and handle the parameters properly
-    let nom_search = nomSearch.as_ref().and_then(|s| {
-        let trimmed = s.trim();
-        if trimmed.is_empty() { None } else { Some(trimmed) }
-    });
+        // Convert empty strings to None and handle the parameters properly
+        let nom_search = nomSearch.as_ref().and_then(|s| {
+            let trimmed = s.trim();
+            if trimmed.is_empty() { None } else { Some(trimmed) }
+        });
     
-    repo.get_all(page, per_page, nom_search).await.map_err(|e| e.to_string())
+        repo.get_all(page, per_page, nom_search).await.map_err(|e| e.to_string())
+    }
 }
 
-#[tauri::command]
-pub async fn update_poussin(
-    poussin: UpdatePoussin,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Poussin, String> {
-    let repo = PoussinRepository::new(db.inner().clone());
-    repo.update(poussin).await.map_err(|e| e.to_string())
+commande_mesuree! {
+    #[tauri::command]
+    pub async fn update_poussin(
+        poussin: UpdatePoussin,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Poussin, String> {
+        let repo = PoussinRepository::new(db.inner().clone());
+        repo.update(poussin).await.map_err(|e| e.to_string())
+    }
 }
 
-#[tauri::command]
-pub async fn delete_poussin(
-    id: i64,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<(), String> {
-    let repo = PoussinRepository::new(db.inner().clone());
-    repo.delete(id).await.map_err(|e| e.to_string())
+commande_mesuree! {
+    #[tauri::command]
+    pub async fn delete_poussin(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let repo = PoussinRepository::new(db.inner().clone());
+        repo.delete(id).await.map_err(|e| e.to_string())
+    }
 }
 
-#[tauri::command]
-pub async fn get_poussin_list(
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Vec<Poussin>, String> {
-    let repo = PoussinRepository::new(db.inner().clone());
-    repo.get_poussin_list().await.map_err(|e| e.to_string())
+commande_mesuree! {
+    #[tauri::command]
+    pub async fn get_poussin_list(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Poussin>, String> {
+        let repo = PoussinRepository::new(db.inner().clone());
+        repo.get_poussin_list().await.map_err(|e| e.to_string())
+    }
 }
@@ -0,0 +1,47 @@
+use crate::database::DatabaseManager;
+use crate::services::{PreferenceService, MetriquesCommandes};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère les préférences d'affichage de l'utilisateur connecté
+    /// 
+    /// # Arguments
+    /// * `token` - Le token de session de l'utilisateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le document de préférences (objet vide par défaut) ou une erreur
+    #[tauri::command]
+    pub async fn get_user_preferences(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Value, String> {
+        let service = PreferenceService::new(db.inner().clone());
+        service.get_user_preferences(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Enregistre des préférences d'affichage de l'utilisateur connecté
+    /// 
+    /// Les clés reçues remplacent les clés enregistrées, une clé à `null` est supprimée.
+    /// 
+    /// # Arguments
+    /// * `preferences` - Un objet JSON (colonnes visibles, ferme par défaut, disposition...)
+    /// * `token` - Le token de session de l'utilisateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le document de préférences complet ou une erreur
+    #[tauri::command]
+    pub async fn set_user_preferences(
+        preferences: Value,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Value, String> {
+        let service = PreferenceService::new(db.inner().clone());
+        service.set_user_preferences(preferences, &token).await.map_err(|e| e.to_string())
+    }
+}
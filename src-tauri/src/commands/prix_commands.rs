@@ -0,0 +1,106 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::prix::{formater_montant, trouver_devise, CreatePrixHistorique, Devise, PrixHistorique, DEVISES};
+use crate::repositories::PrixRepository;
+use crate::services::{AuthService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Record a new feed (per kg) or chick (per bird) price from a date
+    #[tauri::command]
+    pub async fn create_prix(
+        database: State<'_, Arc<DatabaseManager>>,
+        prix: CreatePrixHistorique,
+    ) -> Result<PrixHistorique, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| PrixRepository::create(tx, &prix))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the price history of feed, or of a poussin type, most recent first
+    #[tauri::command]
+    pub async fn get_historique_prix(
+        database: State<'_, Arc<DatabaseManager>>,
+        article: String,
+        poussin_id: Option<i64>,
+    ) -> Result<Vec<PrixHistorique>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        PrixRepository::get_historique(&conn, &article, poussin_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete a price of the history
+    #[tauri::command]
+    pub async fn delete_prix(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| PrixRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the currencies that can be configured
+    #[tauri::command]
+    pub async fn get_devises() -> Result<Vec<Devise>, String> {
+        Ok(DEVISES.to_vec())
+    }
+}
+
+commande_mesuree! {
+    /// Get the currency of the prices entered from now on
+    #[tauri::command]
+    pub async fn get_devise(database: State<'_, Arc<DatabaseManager>>) -> Result<Devise, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        PrixRepository::get_devise(&conn).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Set the currency of the prices entered from now on (administrators)
+    ///
+    /// Prices, deliveries and batiments already recorded keep their currency.
+    #[tauri::command]
+    pub async fn set_devise(
+        database: State<'_, Arc<DatabaseManager>>,
+        devise: String,
+        token: String,
+    ) -> Result<Devise, String> {
+        AuthService::new(database.inner().clone())
+            .require_admin(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| PrixRepository::set_devise(tx, &devise))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Format amounts for display and PDF reports ("1 234,50 DH")
+    ///
+    /// `devise` is the currency the amounts were recorded in, the current one when omitted.
+    #[tauri::command]
+    pub async fn format_montants(
+        database: State<'_, Arc<DatabaseManager>>,
+        montants: Vec<f64>,
+        devise: Option<String>,
+    ) -> Result<Vec<String>, String> {
+        let devise = match devise {
+            Some(code) => trouver_devise(&code).ok_or_else(|| format!("Devise inconnue: {}", code))?,
+            None => {
+                let conn = database.get_connection().map_err(|e| e.to_string())?;
+                PrixRepository::get_devise(&conn).map_err(|e| e.to_string())?
+            }
+        };
+        Ok(montants.iter().map(|montant| formater_montant(*montant, &devise)).collect())
+    }
+}
@@ -0,0 +1,137 @@
+use crate::database::{DatabaseManager, ProfilBase};
+use crate::services::{AuthService, EventBus, StatisticsCache, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+/// Réinitialise l'état géré par l'application après l'ouverture d'une autre base
+async fn reinitialiser_etat(cache: &StatisticsCache, bus: &EventBus) {
+    cache.clear().await;
+    bus.publish_profile_opened();
+}
+
+commande_mesuree! {
+    /// Ouvre la base d'un profil à la place de la base courante
+    /// 
+    /// Le profil `demo` ouvre un bac à sable (`demo.db`) où les techniciens en
+    /// formation peuvent s'exercer sans toucher aux données réelles; `principal`
+    /// revient à la base de production. Les sessions étant enregistrées dans
+    /// chaque base, l'utilisateur doit se reconnecter après le changement.
+    /// 
+    /// # Arguments
+    /// * `profile_name` - Le nom du profil à ouvrir
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `cache` - Le cache des statistiques (injecté par Tauri)
+    /// * `bus` - Le bus d'événements (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le profil désormais actif ou une erreur
+    #[tauri::command]
+    pub async fn switch_profile(
+        profile_name: String,
+        db: State<'_, Arc<DatabaseManager>>,
+        cache: State<'_, Arc<StatisticsCache>>,
+        bus: State<'_, Arc<EventBus>>,
+    ) -> Result<ProfilBase, String> {
+        let profil = db.switch_profile(&profile_name).map_err(|e| e.to_string())?;
+        reinitialiser_etat(&cache, &bus).await;
+        Ok(profil)
+    }
+}
+
+commande_mesuree! {
+    /// Récupère le profil de la base actuellement ouverte
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le nom du profil, le chemin de sa base et s'il s'agit de la base principale
+    #[tauri::command]
+    pub async fn get_active_profile(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ProfilBase, String> {
+        Ok(db.get_active_profile())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les profils disponibles, proposés au démarrage de l'application
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les profils, la base principale en premier, ou une erreur
+    #[tauri::command]
+    pub async fn list_profiles(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<ProfilBase>, String> {
+        db.list_profiles().map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Crée la base d'un nouveau profil sans l'ouvrir
+    /// 
+    /// # Arguments
+    /// * `profile_name` - Le nom du profil à créer
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le profil créé ou une erreur
+    #[tauri::command]
+    pub async fn create_profile(
+        profile_name: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ProfilBase, String> {
+        db.create_profile(&profile_name).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Ouvre la base d'un profil existant à la place de la base courante
+    /// 
+    /// # Arguments
+    /// * `profile_name` - Le nom du profil à ouvrir
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// * `cache` - Le cache des statistiques (injecté par Tauri)
+    /// * `bus` - Le bus d'événements (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le profil désormais actif ou une erreur
+    #[tauri::command]
+    pub async fn open_profile(
+        profile_name: String,
+        db: State<'_, Arc<DatabaseManager>>,
+        cache: State<'_, Arc<StatisticsCache>>,
+        bus: State<'_, Arc<EventBus>>,
+    ) -> Result<ProfilBase, String> {
+        let profil = db.open_profile(&profile_name).map_err(|e| e.to_string())?;
+        reinitialiser_etat(&cache, &bus).await;
+        Ok(profil)
+    }
+}
+
+commande_mesuree! {
+    /// Supprime définitivement la base d'un profil (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `profile_name` - Le nom du profil à supprimer
+    /// * `token` - Le token de session d'un administrateur de la base ouverte
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Ok si la base a été supprimée, sinon une erreur
+    #[tauri::command]
+    pub async fn delete_profile(
+        profile_name: String,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        AuthService::new(db.inner().clone())
+            .require_admin(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        db.delete_profile(&profile_name).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::programme_alimentation::{
+    ConformiteProgrammeAlimentation, CreatePhaseAlimentation, PhaseAlimentation, PrevisionAliment,
+    UpdatePhaseAlimentation,
+};
+use crate::repositories::ProgrammeAlimentationRepository;
+use crate::services::{AuthService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Add a phase to the feed program of a poussin type
+    #[tauri::command]
+    pub async fn create_phase_alimentation(
+        database: State<'_, Arc<DatabaseManager>>,
+        phase: CreatePhaseAlimentation,
+    ) -> Result<PhaseAlimentation, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| ProgrammeAlimentationRepository::create(tx, &phase))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the feed program (ordered phases) of a poussin type
+    #[tauri::command]
+    pub async fn get_programme_alimentation(
+        database: State<'_, Arc<DatabaseManager>>,
+        poussin_id: i64,
+    ) -> Result<Vec<PhaseAlimentation>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        ProgrammeAlimentationRepository::get_by_poussin(&conn, poussin_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Update a phase of a feed program
+    #[tauri::command]
+    pub async fn update_phase_alimentation(
+        database: State<'_, Arc<DatabaseManager>>,
+        phase: UpdatePhaseAlimentation,
+    ) -> Result<PhaseAlimentation, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| ProgrammeAlimentationRepository::update(tx, &phase))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete a phase of a feed program
+    #[tauri::command]
+    pub async fn delete_phase_alimentation(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| ProgrammeAlimentationRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Compare the daily feed of a batiment against the program of its poussin type
+    #[tauri::command]
+    pub async fn get_feed_program_compliance(
+        database: State<'_, Arc<DatabaseManager>>,
+        batiment_id: i64,
+    ) -> Result<ConformiteProgrammeAlimentation, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        ProgrammeAlimentationRepository::get_compliance(&conn, batiment_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Project the feed a bande needs until its catch date, compared against its stock
+    #[tauri::command]
+    pub async fn forecast_feed_requirements(
+        database: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+    ) -> Result<PrevisionAliment, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        ProgrammeAlimentationRepository::forecast_requirements(&conn, bande_id).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Get the weight of a feed sachet in kg
+    #[tauri::command]
+    pub async fn get_kg_par_sachet(
+        database: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<f64, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        ProgrammeAlimentationRepository::get_kg_par_sachet(&conn).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Set the weight of a feed sachet in kg (administrators only)
+    #[tauri::command]
+    pub async fn set_kg_par_sachet(
+        database: State<'_, Arc<DatabaseManager>>,
+        kg_par_sachet: f64,
+        token: String,
+    ) -> Result<f64, String> {
+        AuthService::new(database.inner().clone())
+            .require_admin(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| ProgrammeAlimentationRepository::set_kg_par_sachet(tx, kg_par_sachet))
+            .map_err(|e| e.to_string())
+    }
+}
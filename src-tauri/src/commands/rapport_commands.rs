@@ -0,0 +1,92 @@
+use crate::database::DatabaseManager;
+use crate::models::{CreateRapportPersonnalise, EntiteRapport, RapportPersonnalise, ResultatRapport};
+use crate::services::{MetriquesCommandes, RapportService};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Liste les entités et les champs utilisables dans un rapport personnalisé
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn get_report_entities(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<EntiteRapport>, String> {
+        let service = RapportService::new(db.inner().clone());
+        Ok(service.get_report_entities())
+    }
+}
+
+commande_mesuree! {
+    /// Enregistre une définition de rapport personnalisé
+    /// 
+    /// # Arguments
+    /// * `rapport` - Le nom et la description du rapport
+    /// * `token` - Le token de session de l'auteur, s'il y en a un
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La définition enregistrée ou une erreur
+    #[tauri::command]
+    pub async fn create_report_definition(
+        rapport: CreateRapportPersonnalise,
+        token: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<RapportPersonnalise, String> {
+        let service = RapportService::new(db.inner().clone());
+        service.create_report_definition(rapport, token.as_deref()).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les définitions de rapport enregistrées
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn get_report_definitions(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<RapportPersonnalise>, String> {
+        let service = RapportService::new(db.inner().clone());
+        service.get_report_definitions().await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime une définition de rapport
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de la définition
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_report_definition(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let service = RapportService::new(db.inner().clone());
+        service.delete_report_definition(id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Exécute une définition de rapport
+    /// 
+    /// # Arguments
+    /// * `definition_id` - L'ID de la définition
+    /// * `params` - Les valeurs des filtres paramétrés, par nom de paramètre
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les colonnes et les lignes du rapport ou une erreur
+    #[tauri::command]
+    pub async fn run_report(
+        definition_id: i64,
+        params: Option<Map<String, Value>>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ResultatRapport, String> {
+        let service = RapportService::new(db.inner().clone());
+        service.run_report(definition_id, params.unwrap_or_default()).await.map_err(|e| e.to_string())
+    }
+}
@@ -1,8 +1,10 @@
-use crate::models::{Semaine, CreateSemaine, UpdateSemaine};
+use crate::models::{AvancementSemaine, Semaine, CreateSemaine, UpdateSemaine, NormalisationPoids, SerieAlimentationSujet, SerieCroissance};
 use crate::repositories::semaine_repository::{SemaineRepository, SemaineRepositoryTrait};
 use crate::services::semaine_service::{SemaineService, SemaineWithDetails};
+use crate::services::{AuthService, MetriquesCommandes};
 use crate::models::Maladie;
 use crate::database::DatabaseManager;
+use crate::error::ErreurCommande;
 use std::sync::Arc;
 use tauri::State;
 use serde::Serialize;
@@ -14,167 +16,372 @@ pub struct SemainesAndMaladies {
     pub maladies: Vec<Maladie>,
 }
 
-/// Commande Tauri pour créer une nouvelle semaine
-/// 
-/// # Arguments
-/// * `semaine` - Les données de la semaine à créer
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<Semaine, String>` contenant la semaine créée ou une erreur
-#[tauri::command]
-pub async fn create_semaine(
-    semaine: CreateSemaine,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Semaine, String> {
-    let repository = SemaineRepository::new(db.inner().clone());
+commande_mesuree! {
+    /// Commande Tauri pour créer une nouvelle semaine
+    /// 
+    /// # Arguments
+    /// * `semaine` - Les données de la semaine à créer
+    /// * `token` - Le token de session de l'auteur, s'il y en a un
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Un `Result<Semaine, String>` contenant la semaine créée ou une erreur
+    #[tauri::command]
+    pub async fn create_semaine(
+        semaine: CreateSemaine,
+        token: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Semaine, String> {
+        let created_by = AuthService::new(db.inner().clone())
+            .author_id(token.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        let repository = SemaineRepository::new(db.inner().clone()).with_author(created_by);
     
-    repository.create(semaine)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// Commande Tauri pour récupérer toutes les semaines
-/// 
-/// # Arguments
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<Vec<Semaine>, String>` contenant toutes les semaines ou une erreur
-#[tauri::command]
-pub async fn get_all_semaines(
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Vec<Semaine>, String> {
-    let repository = SemaineRepository::new(db.inner().clone());
+        repository.create(semaine)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer toutes les semaines
+    /// 
+    /// # Arguments
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Un `Result<Vec<Semaine>, String>` contenant toutes les semaines ou une erreur
+    #[tauri::command]
+    pub async fn get_all_semaines(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Semaine>, String> {
+        let repository = SemaineRepository::new(db.inner().clone());
     
-    repository.get_all()
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// Commande Tauri pour récupérer une semaine par son ID
-/// 
-/// # Arguments
-/// * `id` - L'ID de la semaine
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<Semaine, String>` contenant la semaine trouvée ou une erreur
-#[tauri::command]
-pub async fn get_semaine_by_id(
-    id: i64,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Semaine, String> {
-    let repository = SemaineRepository::new(db.inner().clone());
+        repository.get_all()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer une semaine par son ID
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de la semaine
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Un `Result<Semaine, String>` contenant la semaine trouvée ou une erreur
+    #[tauri::command]
+    pub async fn get_semaine_by_id(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Semaine, String> {
+        let repository = SemaineRepository::new(db.inner().clone());
     
-    repository.get_by_id(id)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// Commande Tauri pour récupérer toutes les semaines d'un bâtiment
-/// 
-/// # Arguments
-/// * `batiment_id` - L'ID du bâtiment
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<Vec<Semaine>, String>` contenant les semaines du bâtiment ou une erreur
-#[tauri::command]
-pub async fn get_semaines_by_batiment(
-    batiment_id: i64,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Vec<Semaine>, String> {
-    let repository = SemaineRepository::new(db.inner().clone());
+        repository.get_by_id(id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer toutes les semaines d'un bâtiment
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Un `Result<Vec<Semaine>, String>` contenant les semaines du bâtiment ou une erreur
+    #[tauri::command]
+    pub async fn get_semaines_by_batiment(
+        batiment_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Semaine>, String> {
+        let repository = SemaineRepository::new(db.inner().clone());
     
-    repository.get_by_batiment(batiment_id)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// Commande Tauri pour mettre à jour une semaine
-/// 
-/// # Arguments
-/// * `semaine` - Les nouvelles données de la semaine
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<Semaine, String>` contenant la semaine mise à jour ou une erreur
-#[tauri::command]
-pub async fn update_semaine(
-    semaine: UpdateSemaine,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Semaine, String> {
-    let repository = SemaineRepository::new(db.inner().clone());
+        repository.get_by_batiment(batiment_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour mettre à jour une semaine
+    /// 
+    /// # Arguments
+    /// * `semaine` - Les nouvelles données de la semaine
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// La semaine mise à jour ou une erreur structurée (conflit de version...)
+    #[tauri::command]
+    pub async fn update_semaine(
+        semaine: UpdateSemaine,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Semaine, ErreurCommande> {
+        let repository = SemaineRepository::new(db.inner().clone());
     
-    repository.update(semaine)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// Commande Tauri pour supprimer une semaine
-/// 
-/// # Arguments
-/// * `id` - L'ID de la semaine à supprimer
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<(), String>` indiquant le succès ou une erreur
-#[tauri::command]
-pub async fn delete_semaine(
-    id: i64,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<(), String> {
-    let repository = SemaineRepository::new(db.inner().clone());
+        repository.update(semaine)
+            .await
+            .map_err(ErreurCommande::from)
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour supprimer une semaine
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de la semaine à supprimer
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Un `Result<(), String>` indiquant le succès ou une erreur
+    #[tauri::command]
+    pub async fn delete_semaine(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let repository = SemaineRepository::new(db.inner().clone());
     
-    repository.delete(id)
-        .await
-        .map_err(|e| e.to_string())
-}
-
-/// Commande Tauri pour récupérer toutes les semaines d'un bâtiment avec leurs suivis quotidiens
-/// 
-/// Cette commande utilise le service semaine pour créer automatiquement les 8 semaines
-/// et leurs 7 suivis quotidiens respectifs s'ils n'existent pas.
-/// 
-/// # Arguments
-/// * `batiment_id` - L'ID du bâtiment
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<SemainesAndMaladies, String>` contenant les 8 semaines et maladies
-#[tauri::command]
-pub async fn get_full_semaines_by_batiment(
-    batiment_id: i64,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<SemainesAndMaladies, String> {
-    let service = SemaineService::new(db.inner().clone());
+        repository.delete(id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer toutes les semaines d'un bâtiment avec leurs suivis quotidiens
+    /// 
+    /// Cette commande utilise le service semaine pour créer automatiquement les 8 semaines
+    /// et leurs 7 suivis quotidiens respectifs s'ils n'existent pas.
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Un `Result<SemainesAndMaladies, String>` contenant les 8 semaines et maladies
+    #[tauri::command]
+    pub async fn get_full_semaines_by_batiment(
+        batiment_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SemainesAndMaladies, String> {
+        let service = SemaineService::new(db.inner().clone());
     
-    service
-        .get_full_semaines_with_maladies_by_batiment(batiment_id)
-        .await
-        .map(|(semaines, maladies)| SemainesAndMaladies { semaines, maladies })
-        .map_err(|e| e.to_string())
-}
-
-/// Commande Tauri pour mettre à jour le poids d'une semaine
-/// 
-/// # Arguments
-/// * `semaine_id` - L'ID de la semaine
-/// * `poids` - Le nouveau poids
-/// * `db` - L'état de la base de données
-/// 
-/// # Returns
-/// Un `Result<Semaine, String>` contenant la semaine mise à jour
-#[tauri::command]
-pub async fn update_semaine_poids(
-    semaine_id: i64,
-    poids: Option<f64>,
-    db: State<'_, Arc<DatabaseManager>>,
-) -> Result<Semaine, String> {
-    let service = SemaineService::new(db.inner().clone());
+        service
+            .get_full_semaines_with_maladies_by_batiment(batiment_id)
+            .await
+            .map(|(semaines, maladies)| SemainesAndMaladies { semaines, maladies })
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour mettre à jour le poids d'une semaine
+    /// 
+    /// # Arguments
+    /// * `semaine_id` - L'ID de la semaine
+    /// * `poids` - Le nouveau poids
+    /// * `unite` - L'unité de la saisie ("g" ou "kg"), l'unité paramétrée si absente
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Un `Result<Semaine, String>` contenant la semaine mise à jour
+    #[tauri::command]
+    pub async fn update_semaine_poids(
+        semaine_id: i64,
+        poids: Option<f64>,
+        unite: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Semaine, String> {
+        let service = SemaineService::new(db.inner().clone());
     
-    service.update_semaine_poids(semaine_id, poids)
-        .await
-        .map_err(|e| e.to_string())
+        service.update_semaine_poids(semaine_id, poids, unite)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer l'unité de saisie des poids
+    /// 
+    /// # Returns
+    /// "g" ou "kg"
+    #[tauri::command]
+    pub async fn get_poids_unit(db: State<'_, Arc<DatabaseManager>>) -> Result<String, String> {
+        SemaineService::new(db.inner().clone())
+            .get_poids_unit()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour définir l'unité de saisie des poids (administrateurs)
+    /// 
+    /// # Arguments
+    /// * `unite` - "g" ou "kg"
+    /// * `token` - Le jeton de session de l'administrateur
+    /// 
+    /// # Returns
+    /// L'unité enregistrée
+    #[tauri::command]
+    pub async fn set_poids_unit(
+        unite: String,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<String, String> {
+        AuthService::new(db.inner().clone())
+            .require_admin(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        SemaineService::new(db.inner().clone())
+            .set_poids_unit(&unite)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer le nombre maximal de semaines d'un bâtiment
+    /// 
+    /// # Returns
+    /// Le nombre maximal de semaines de suivi
+    #[tauri::command]
+    pub async fn get_semaines_max(db: State<'_, Arc<DatabaseManager>>) -> Result<i32, String> {
+        SemaineService::new(db.inner().clone())
+            .get_semaines_max()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour définir le nombre maximal de semaines d'un bâtiment (administrateurs)
+    /// 
+    /// # Arguments
+    /// * `semaines_max` - Entre 5 et 9 semaines
+    /// * `token` - Le jeton de session de l'administrateur
+    /// 
+    /// # Returns
+    /// Le nombre enregistré
+    #[tauri::command]
+    pub async fn set_semaines_max(
+        semaines_max: i32,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<i32, String> {
+        AuthService::new(db.inner().clone())
+            .require_admin(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        SemaineService::new(db.inner().clone())
+            .set_semaines_max(semaines_max)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour passer un bâtiment à la semaine suivante
+    /// 
+    /// La dernière semaine du bâtiment doit être complète et le nombre maximal
+    /// de semaines ne doit pas être atteint.
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `creer_jours` - Crée aussi les 7 jours de suivi de la nouvelle semaine (non par défaut)
+    /// * `token` - Le token de session de l'auteur, s'il y en a un
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// La semaine créée et les âges des jours de suivi créés avec elle
+    #[tauri::command]
+    pub async fn advance_to_next_week(
+        batiment_id: i64,
+        creer_jours: Option<bool>,
+        token: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<AvancementSemaine, String> {
+        let created_by = AuthService::new(db.inner().clone())
+            .author_id(token.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        let repository = SemaineRepository::new(db.inner().clone()).with_author(created_by);
+
+        repository.advance_to_next_week(batiment_id, creer_jours.unwrap_or(false))
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour normaliser les poids déjà enregistrés (administrateurs)
+    /// 
+    /// # Arguments
+    /// * `appliquer` - Enregistre les conversions et signalements (aperçu si absent)
+    /// * `token` - Le jeton de session de l'administrateur
+    /// 
+    /// # Returns
+    /// Les poids convertis en kg et les poids signalés pour vérification
+    #[tauri::command]
+    pub async fn normalize_semaine_poids(
+        appliquer: Option<bool>,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<NormalisationPoids, String> {
+        AuthService::new(db.inner().clone())
+            .require_admin(&token)
+            .await
+            .map_err(|e| e.to_string())?;
+        SemaineService::new(db.inner().clone())
+            .normalize_semaine_poids(appliquer.unwrap_or(false))
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer la courbe de croissance d'un bâtiment
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// Le gain hebdomadaire et le GMQ par semaine, avec la projection après la dernière pesée
+    #[tauri::command]
+    pub async fn get_weight_gain_series(
+        batiment_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SerieCroissance, String> {
+        SemaineService::new(db.inner().clone())
+            .get_weight_gain_series(batiment_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Commande Tauri pour récupérer la consommation d'aliment par sujet d'un bâtiment
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `db` - L'état de la base de données
+    /// 
+    /// # Returns
+    /// L'alimentation de chaque jour saisi en g/sujet/jour, rapportée aux sujets vivants
+    #[tauri::command]
+    pub async fn get_feed_per_bird_series(
+        batiment_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SerieAlimentationSujet, String> {
+        SemaineService::new(db.inner().clone())
+            .get_feed_per_bird_series(batiment_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
 }
@@ -0,0 +1,130 @@
+use crate::database::DatabaseManager;
+use crate::models::{
+    SanteBatiment, SerieGraphique, METRIQUE_ALIMENTATION, METRIQUE_MORTALITE, METRIQUE_POIDS, METRIQUE_TEMPERATURE,
+};
+use crate::services::{MetriquesCommandes, StatisticsService};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Obtient le score de santé d'un bâtiment avec les facteurs qui y contribuent
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le score de santé du bâtiment ou une erreur
+    #[tauri::command]
+    pub async fn get_batiment_health(
+        batiment_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SanteBatiment, String> {
+        let service = StatisticsService::new(db.inner().clone());
+        service.get_batiment_health(batiment_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Obtient la série quotidienne des pertes (décès et éliminés)
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande (tous ses bâtiments), si `batiment_id` est absent
+    /// * `batiment_id` - L'ID du bâtiment, si `bande_id` est absent
+    /// * `lissage` - Nombre de points de la moyenne mobile, sans lissage si absent
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les points {x, y} de la série (sujets par jour) ou une erreur
+    #[tauri::command]
+    pub async fn get_mortality_series(
+        bande_id: Option<i64>,
+        batiment_id: Option<i64>,
+        lissage: Option<u32>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SerieGraphique, String> {
+        let service = StatisticsService::new(db.inner().clone());
+        service
+            .get_chart_series(METRIQUE_MORTALITE, bande_id, batiment_id, lissage)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Obtient la série quotidienne de l'aliment consommé
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande (tous ses bâtiments), si `batiment_id` est absent
+    /// * `batiment_id` - L'ID du bâtiment, si `bande_id` est absent
+    /// * `lissage` - Nombre de points de la moyenne mobile, sans lissage si absent
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les points {x, y} de la série (kg par jour) ou une erreur
+    #[tauri::command]
+    pub async fn get_feed_series(
+        bande_id: Option<i64>,
+        batiment_id: Option<i64>,
+        lissage: Option<u32>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SerieGraphique, String> {
+        let service = StatisticsService::new(db.inner().clone());
+        service
+            .get_chart_series(METRIQUE_ALIMENTATION, bande_id, batiment_id, lissage)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Obtient la série du poids moyen en fin de semaine
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande (tous ses bâtiments), si `batiment_id` est absent
+    /// * `batiment_id` - L'ID du bâtiment, si `bande_id` est absent
+    /// * `lissage` - Nombre de points de la moyenne mobile, sans lissage si absent
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les points {x, y} de la série (kg) ou une erreur
+    #[tauri::command]
+    pub async fn get_weight_series(
+        bande_id: Option<i64>,
+        batiment_id: Option<i64>,
+        lissage: Option<u32>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SerieGraphique, String> {
+        let service = StatisticsService::new(db.inner().clone());
+        service
+            .get_chart_series(METRIQUE_POIDS, bande_id, batiment_id, lissage)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Obtient la série de la température moyenne journalière relevée par les capteurs
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande (tous ses bâtiments), si `batiment_id` est absent
+    /// * `batiment_id` - L'ID du bâtiment, si `bande_id` est absent
+    /// * `lissage` - Nombre de points de la moyenne mobile, sans lissage si absent
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les points {x, y} de la série (°C) ou une erreur
+    #[tauri::command]
+    pub async fn get_temperature_series(
+        bande_id: Option<i64>,
+        batiment_id: Option<i64>,
+        lissage: Option<u32>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<SerieGraphique, String> {
+        let service = StatisticsService::new(db.inner().clone());
+        service
+            .get_chart_series(METRIQUE_TEMPERATURE, bande_id, batiment_id, lissage)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
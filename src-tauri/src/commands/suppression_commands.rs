@@ -0,0 +1,26 @@
+use crate::database::DatabaseManager;
+use crate::models::ImpactSuppression;
+use crate::services::{SuppressionService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Détaille les données dépendant d'une ferme, d'un membre du personnel ou d'un soin avant sa suppression
+    /// 
+    /// # Arguments
+    /// * `entity` - Le type d'entité: "ferme", "personnel", "soin" ou "poussin"
+    /// * `id` - L'ID de l'entité
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'impact de la suppression ou une erreur
+    #[tauri::command]
+    pub async fn get_delete_impact(
+        entity: String,
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<ImpactSuppression, String> {
+        let service = SuppressionService::new(db.inner().clone());
+        service.get_delete_impact(&entity, id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::database::{DatabaseManager, Storage};
+use crate::models::{CreateTag, Tag};
+use crate::repositories::TagRepository;
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Create a bande tag
+    #[tauri::command]
+    pub async fn create_tag(
+        database: State<'_, Arc<DatabaseManager>>,
+        tag: CreateTag,
+    ) -> Result<Tag, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| TagRepository::create(tx, &tag))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Rename a tag or change its color
+    #[tauri::command]
+    pub async fn update_tag(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+        tag: CreateTag,
+    ) -> Result<Tag, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| TagRepository::update(tx, id, &tag))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// List all tags by name
+    #[tauri::command]
+    pub async fn get_tags(
+        database: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<Tag>, String> {
+        let conn = database.get_connection().map_err(|e| e.to_string())?;
+        TagRepository::get_all(&conn).map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Delete a tag and remove it from every bande
+    #[tauri::command]
+    pub async fn delete_tag(
+        database: State<'_, Arc<DatabaseManager>>,
+        id: i64,
+    ) -> Result<(), String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| TagRepository::delete(tx, id))
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Put a tag on a bande
+    #[tauri::command]
+    pub async fn add_tag_to_bande(
+        database: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+        tag_id: i64,
+    ) -> Result<Vec<Tag>, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| {
+                TagRepository::add_to_bande(tx, bande_id, tag_id)?;
+                TagRepository::get_by_bande(tx, bande_id)
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Remove a tag from a bande
+    #[tauri::command]
+    pub async fn remove_tag_from_bande(
+        database: State<'_, Arc<DatabaseManager>>,
+        bande_id: i64,
+        tag_id: i64,
+    ) -> Result<Vec<Tag>, String> {
+        let storage: Arc<dyn Storage> = database.inner().clone();
+        storage
+            .write(|tx| {
+                TagRepository::remove_from_bande(tx, bande_id, tag_id)?;
+                TagRepository::get_by_bande(tx, bande_id)
+            })
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::database::DatabaseManager;
+use crate::models::{DeverrouillagePeriode, FenetreSaisie};
+use crate::services::{VerrouillageService, MetriquesCommandes};
+use chrono::NaiveDate;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère la fenêtre de saisie du suivi
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le nombre de jours modifiables (`None` sans verrouillage) ou une erreur
+    #[tauri::command]
+    pub async fn get_edit_window(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<FenetreSaisie, String> {
+        let service = VerrouillageService::new(db.inner().clone());
+        service.get_edit_window().await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Modifie la fenêtre de saisie du suivi (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `jours` - Le nombre de jours modifiables, absent pour désactiver le verrouillage
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La fenêtre enregistrée ou une erreur
+    #[tauri::command]
+    pub async fn set_edit_window(
+        jours: Option<i32>,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<FenetreSaisie, String> {
+        let service = VerrouillageService::new(db.inner().clone());
+        service.set_edit_window(jours, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Déverrouille temporairement une période d'une bande (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `date_debut` - Le premier jour déverrouillé
+    /// * `date_fin` - Le dernier jour déverrouillé
+    /// * `motif` - La raison de la correction, si renseignée
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le déverrouillage enregistré ou une erreur
+    #[tauri::command]
+    pub async fn unlock_period(
+        bande_id: i64,
+        date_debut: NaiveDate,
+        date_fin: NaiveDate,
+        motif: Option<String>,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<DeverrouillagePeriode, String> {
+        let service = VerrouillageService::new(db.inner().clone());
+        service
+            .unlock_period(bande_id, date_debut, date_fin, motif.as_deref(), &token)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
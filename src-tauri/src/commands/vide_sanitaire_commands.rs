@@ -0,0 +1,98 @@
+use crate::database::DatabaseManager;
+use crate::models::{CreateVideSanitaire, StatistiquesVideSanitaire, UpdateVideSanitaire, VideSanitaire};
+use crate::repositories::{VideSanitaireRepository, VideSanitaireRepositoryTrait};
+use crate::services::MetriquesCommandes;
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Enregistre le nettoyage et la désinfection d'un bâtiment
+    /// 
+    /// # Arguments
+    /// * `vide` - Les données du vide sanitaire
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le vide sanitaire créé ou une erreur
+    #[tauri::command]
+    pub async fn create_vide_sanitaire(
+        vide: CreateVideSanitaire,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<VideSanitaire, String> {
+        let repo = VideSanitaireRepository::new(db.inner().clone());
+        repo.create(vide).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les vides sanitaires d'une ferme
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les vides sanitaires, du plus récent au plus ancien
+    #[tauri::command]
+    pub async fn get_vides_sanitaires_by_ferme(
+        ferme_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<VideSanitaire>, String> {
+        let repo = VideSanitaireRepository::new(db.inner().clone());
+        repo.get_by_ferme(ferme_id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Met à jour un vide sanitaire
+    /// 
+    /// # Arguments
+    /// * `vide` - Les nouvelles données du vide sanitaire
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le vide sanitaire mis à jour ou une erreur
+    #[tauri::command]
+    pub async fn update_vide_sanitaire(
+        vide: UpdateVideSanitaire,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<VideSanitaire, String> {
+        let repo = VideSanitaireRepository::new(db.inner().clone());
+        repo.update(vide).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Supprime un vide sanitaire
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID du vide sanitaire
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    #[tauri::command]
+    pub async fn delete_vide_sanitaire(
+        id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<(), String> {
+        let repo = VideSanitaireRepository::new(db.inner().clone());
+        repo.delete(id).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Durée moyenne des vides sanitaires d'une ferme
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le nombre de vides terminés et leur durée moyenne en jours
+    #[tauri::command]
+    pub async fn get_vide_sanitaire_statistics(
+        ferme_id: i64,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<StatistiquesVideSanitaire, String> {
+        let repo = VideSanitaireRepository::new(db.inner().clone());
+        repo.get_statistics(ferme_id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,76 @@
+use crate::database::DatabaseManager;
+use crate::models::{MeteoJour, MortaliteMeteoJour, SynchronisationMeteo};
+use crate::services::{WeatherService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère la météo des derniers jours pour toutes les fermes géolocalisées
+    /// 
+    /// # Arguments
+    /// * `jours` - Nombre de jours passés à récupérer (7 par défaut)
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le résultat de chaque ferme (les fermes en échec sont signalées) ou une erreur
+    #[tauri::command]
+    pub async fn sync_weather(
+        jours: Option<u32>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<SynchronisationMeteo>, String> {
+        let service = WeatherService::new(db.inner().clone());
+        service.sync_weather(jours).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Récupère les relevés météo enregistrés d'une ferme
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `date_from` - Premier jour inclus ("YYYY-MM-DD")
+    /// * `date_to` - Dernier jour inclus ("YYYY-MM-DD")
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les relevés journaliers triés par date ou une erreur
+    #[tauri::command]
+    pub async fn get_weather_by_ferme(
+        ferme_id: i64,
+        date_from: Option<String>,
+        date_to: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<MeteoJour>, String> {
+        let service = WeatherService::new(db.inner().clone());
+        service
+            .get_weather_by_ferme(ferme_id, date_from, date_to)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Rapproche la mortalité journalière d'une ferme de la météo (vagues de chaleur)
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `date_from` - Premier jour inclus ("YYYY-MM-DD")
+    /// * `date_to` - Dernier jour inclus ("YYYY-MM-DD")
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// La mortalité et la météo de chaque jour suivi ou une erreur
+    #[tauri::command]
+    pub async fn get_mortalite_meteo(
+        ferme_id: i64,
+        date_from: Option<String>,
+        date_to: Option<String>,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<MortaliteMeteoJour>, String> {
+        let service = WeatherService::new(db.inner().clone());
+        service
+            .get_mortalite_meteo(ferme_id, date_from, date_to)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::database::DatabaseManager;
+use crate::models::{EvenementSortant, RapportEnvoiWebhook};
+use crate::services::{WebhookService, MetriquesCommandes};
+use std::sync::Arc;
+use tauri::State;
+
+commande_mesuree! {
+    /// Récupère l'URL du webhook de la coopérative (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'URL configurée, `None` si les notifications sont désactivées, ou une erreur
+    #[tauri::command]
+    pub async fn get_webhook_url(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Option<String>, String> {
+        let service = WebhookService::new(db.inner().clone());
+        service.get_webhook_url(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Configure l'URL du webhook de la coopérative (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `url` - L'URL http(s) du webhook, `None` ou vide pour désactiver les notifications
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'URL enregistrée ou une erreur
+    #[tauri::command]
+    pub async fn set_webhook_url(
+        url: Option<String>,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Option<String>, String> {
+        let service = WebhookService::new(db.inner().clone());
+        service.set_webhook_url(url, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Liste les derniers événements de la file d'envoi du webhook (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Les événements, du plus récent au plus ancien, ou une erreur
+    #[tauri::command]
+    pub async fn get_webhook_outbox(
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<Vec<EvenementSortant>, String> {
+        let service = WebhookService::new(db.inner().clone());
+        service.get_webhook_outbox(&token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Relance l'envoi d'un événement non envoyé (réservé aux administrateurs)
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'événement
+    /// * `token` - Le token de session d'un administrateur
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// L'événement reprogrammé ou une erreur
+    #[tauri::command]
+    pub async fn retry_webhook_event(
+        id: i64,
+        token: String,
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<EvenementSortant, String> {
+        let service = WebhookService::new(db.inner().clone());
+        service.retry_webhook_event(id, &token).await.map_err(|e| e.to_string())
+    }
+}
+
+commande_mesuree! {
+    /// Envoie tout de suite les événements en attente, sans attendre la tâche de fond
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données (injecté par Tauri)
+    /// 
+    /// # Returns
+    /// Le nombre d'événements envoyés et en échec, ou une erreur
+    #[tauri::command]
+    pub async fn deliver_webhook_events(
+        db: State<'_, Arc<DatabaseManager>>,
+    ) -> Result<RapportEnvoiWebhook, String> {
+        let service = WebhookService::new(db.inner().clone());
+        service.deliver_pending().await.map_err(|e| e.to_string())
+    }
+}
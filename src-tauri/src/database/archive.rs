@@ -0,0 +1,119 @@
+use crate::error::AppResult;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Nom sous lequel la base d'archive est attachée à chaque connexion
+pub const SCHEMA_ARCHIVE: &str = "archive";
+
+/// Tables déplacées dans l'archive avec leurs bandes
+///
+/// Les bandes d'abord, puis les lignes qui en dépendent: l'archive reprend les
+/// IDs d'origine, de sorte que les relations restent valables entre tables archivées.
+/// Toute table supprimée en cascade avec une bande ou un bâtiment doit y
+/// figurer, sinon ses lignes seraient perdues à l'archivage (vérifié par
+/// `tests/archivage.rs`).
+pub const TABLES_ARCHIVEES: [&str; 17] = [
+    "bandes",
+    "batiments",
+    "semaines",
+    "suivi_quotidien",
+    "suivi_soins",
+    "alimentation_history",
+    "batiment_maladies",
+    "analyses",
+    "notes_batiment",
+    "litieres",
+    "alertes",
+    "messages",
+    "deverrouillages_periode",
+    "valeurs_champs_personnalises",
+    "bande_tags",
+    "budgets_bande",
+    "contrats_bande",
+];
+
+/// Chemin de la base d'archive, à côté de la base principale
+///
+/// `farm_management.db` est archivée dans `farm_management-archive.db`.
+pub fn chemin_archive(database_path: &Path) -> PathBuf {
+    let nom = match (database_path.file_stem(), database_path.extension()) {
+        (Some(nom), Some(extension)) => format!("{}-archive.{}", nom.to_string_lossy(), extension.to_string_lossy()),
+        _ => format!("{}-archive", database_path.file_name().unwrap_or_default().to_string_lossy()),
+    };
+    database_path.with_file_name(nom)
+}
+
+/// Attache la base d'archive à une connexion (le fichier est créé au besoin)
+///
+/// L'archive est chiffrée avec la même phrase secrète que la base principale.
+/// Les requêtes sans préfixe de schéma continuent de viser la base principale;
+/// les pragmas sans préfixe (`journal_mode`...) s'appliquent aussi à l'archive.
+pub fn attacher(conn: &Connection, chemin: &Path, phrase_secrete: Option<&str>) -> rusqlite::Result<()> {
+    match phrase_secrete {
+        Some(phrase_secrete) => conn.execute(
+            &format!("ATTACH DATABASE ?1 AS {} KEY ?2", SCHEMA_ARCHIVE),
+            params![chemin.to_string_lossy(), phrase_secrete],
+        )?,
+        None => conn.execute(&format!("ATTACH DATABASE ?1 AS {}", SCHEMA_ARCHIVE), [chemin.to_string_lossy()])?,
+    };
+    Ok(())
+}
+
+/// Crée ou complète les tables de l'archive à partir du schéma principal
+///
+/// Les tables archivées n'ont ni contraintes ni déclencheurs: seules les
+/// colonnes sont reprises, et les colonnes ajoutées depuis la dernière
+/// archive sont ajoutées à leur tour. Une clé unique permet de réarchiver une
+/// ligne sans doublon.
+pub fn preparer_tables(conn: &Connection) -> AppResult<()> {
+    for table in TABLES_ARCHIVEES {
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {archive}.{table} AS SELECT * FROM main.{table} WHERE 0",
+            archive = SCHEMA_ARCHIVE,
+        ))?;
+
+        let archivees: HashSet<String> = colonnes(conn, SCHEMA_ARCHIVE, table)?.into_iter().collect();
+        for colonne in colonnes(conn, "main", table)? {
+            if !archivees.contains(&colonne) {
+                conn.execute_batch(&format!("ALTER TABLE {}.{} ADD COLUMN {}", SCHEMA_ARCHIVE, table, colonne))?;
+            }
+        }
+
+        conn.execute_batch(&format!(
+            "CREATE UNIQUE INDEX IF NOT EXISTS {archive}.idx_archive_{table} ON {table}({cle})",
+            archive = SCHEMA_ARCHIVE,
+            cle = cle(table),
+        ))?;
+    }
+    Ok(())
+}
+
+/// Clé d'une table archivée (clé primaire de la table principale)
+fn cle(table: &str) -> &'static str {
+    match table {
+        "batiment_maladies" => "batiment_id, maladie_id",
+        "bande_tags" => "bande_id, tag_id",
+        "budgets_bande" | "contrats_bande" => "bande_id",
+        _ => "id",
+    }
+}
+
+/// Indique si des bandes ont déjà été archivées
+pub fn archive_initialisee(conn: &Connection) -> AppResult<bool> {
+    let nombre: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM {}.sqlite_master WHERE type = 'table' AND name = 'bandes'", SCHEMA_ARCHIVE),
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(nombre > 0)
+}
+
+/// Colonnes d'une table, dans l'ordre de sa définition
+pub fn colonnes(conn: &Connection, schema: &str, table: &str) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info({})", schema, table))?;
+    let colonnes = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(colonnes)
+}
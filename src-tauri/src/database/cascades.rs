@@ -0,0 +1,149 @@
+use crate::error::AppResult;
+use rusqlite::Connection;
+
+/// Politique de suppression de chaque clé étrangère: (table, colonne, politique)
+///
+/// - les lignes qui appartiennent à leur parent (bâtiments d'une bande, semaines
+///   d'un bâtiment, suivis, soins administrés, liaisons bâtiment-maladie...)
+///   sont supprimées avec lui (`CASCADE`): les repositories suppriment
+///   seulement le parent;
+/// - une donnée de référence encore utilisée (ferme, souche, personnel) ne
+///   peut pas être supprimée (`RESTRICT`);
+/// - une référence facultative (soin, maladie d'une analyse, auteur) est
+///   vidée (`SET NULL`).
+pub const POLITIQUES_SUPPRESSION: [(&str, &str, &str); 48] = [
+    ("sessions", "user_id", "CASCADE"),
+    ("user_mfa", "user_id", "CASCADE"),
+    ("user_preferences", "user_id", "CASCADE"),
+    ("abonnements_alertes", "user_id", "CASCADE"),
+    ("filtres_enregistres", "user_id", "CASCADE"),
+    ("invitations", "created_by", "SET NULL"),
+    ("invitations", "last_used_by", "SET NULL"),
+    ("audit_log", "user_id", "SET NULL"),
+    ("positions_batiments", "ferme_id", "CASCADE"),
+    ("meteo_quotidienne", "ferme_id", "CASCADE"),
+    ("mesures_capteurs", "ferme_id", "CASCADE"),
+    ("mesures_capteurs_horaires", "ferme_id", "CASCADE"),
+    ("bandes", "ferme_id", "RESTRICT"),
+    ("batiments", "bande_id", "CASCADE"),
+    ("batiments", "poussin_id", "RESTRICT"),
+    ("batiments", "personnel_id", "RESTRICT"),
+    ("semaines", "batiment_id", "CASCADE"),
+    ("suivi_quotidien", "semaine_id", "CASCADE"),
+    ("suivi_quotidien", "soins_id", "SET NULL"),
+    ("suivi_soins", "suivi_id", "CASCADE"),
+    ("suivi_soins", "soin_id", "SET NULL"),
+    ("alimentation_history", "bande_id", "CASCADE"),
+    ("budgets_bande", "bande_id", "CASCADE"),
+    ("contrats_bande", "bande_id", "CASCADE"),
+    ("alertes", "bande_id", "CASCADE"),
+    ("alertes", "batiment_id", "CASCADE"),
+    ("alertes", "acquittee_par", "SET NULL"),
+    ("batiment_maladies", "batiment_id", "CASCADE"),
+    ("batiment_maladies", "maladie_id", "CASCADE"),
+    ("analyses", "batiment_id", "CASCADE"),
+    ("analyses", "maladie_id", "SET NULL"),
+    ("notes_batiment", "batiment_id", "CASCADE"),
+    ("litieres", "batiment_id", "CASCADE"),
+    ("messages", "auteur_id", "SET NULL"),
+    ("messages", "destinataire_id", "CASCADE"),
+    ("messages", "batiment_id", "CASCADE"),
+    ("vides_sanitaires", "ferme_id", "CASCADE"),
+    ("immobilisations", "ferme_id", "CASCADE"),
+    ("equipements", "ferme_id", "CASCADE"),
+    ("entretiens_equipement", "equipement_id", "CASCADE"),
+    ("deverrouillages_periode", "bande_id", "CASCADE"),
+    ("deverrouillages_periode", "user_id", "SET NULL"),
+    ("phases_alimentation", "poussin_id", "CASCADE"),
+    ("plan_soins", "poussin_id", "CASCADE"),
+    ("plan_soins", "soin_id", "CASCADE"),
+    ("historique_prix", "poussin_id", "CASCADE"),
+    ("report_definitions", "created_by", "SET NULL"),
+    ("valeurs_champs_personnalises", "champ_id", "CASCADE"),
+    ("valeurs_champs_personnalises", "bande_id", "CASCADE"),
+    ("valeurs_champs_personnalises", "batiment_id", "CASCADE"),
+    ("bande_tags", "bande_id", "CASCADE"),
+    ("bande_tags", "tag_id", "CASCADE"),
+];
+
+/// Politique de suppression actuelle d'une clé étrangère, `None` si la colonne n'en a pas
+pub fn politique_actuelle(conn: &Connection, table: &str, colonne: &str) -> AppResult<Option<String>> {
+    let mut stmt = conn.prepare("SELECT \"from\", on_delete FROM pragma_foreign_key_list(?1)")?;
+    let cles = stmt
+        .query_map([table], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(cles.into_iter().find(|(de, _)| de == colonne).map(|(_, politique)| politique))
+}
+
+/// Aligne les clés étrangères des bases existantes sur `POLITIQUES_SUPPRESSION`
+///
+/// SQLite ne permet pas de modifier une clé étrangère: la table est recréée
+/// avec la définition corrigée puis ses lignes sont recopiées. Les index et
+/// déclencheurs de la table sont recréés ensuite avec le reste du schéma.
+pub fn migrer_politiques(conn: &Connection) -> AppResult<()> {
+    for (table, colonne, politique) in POLITIQUES_SUPPRESSION {
+        match politique_actuelle(conn, table, colonne)? {
+            Some(actuelle) if actuelle != politique => {
+                let definition: String = conn.query_row(
+                    "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                    [table],
+                    |row| row.get(0),
+                )?;
+                if let Some(corrigee) = corriger_definition(&definition, colonne, &actuelle, politique) {
+                    reconstruire_table(conn, table, &corrigee)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Remplace la politique de la clé `FOREIGN KEY (colonne)` dans la définition d'une table
+///
+/// `None` si la clé n'a pas la forme attendue: la table est alors laissée telle quelle.
+fn corriger_definition(definition: &str, colonne: &str, actuelle: &str, politique: &str) -> Option<String> {
+    let cle = definition.find(&format!("FOREIGN KEY ({})", colonne))?;
+    let ancienne = format!("ON DELETE {}", actuelle);
+    let position = cle + definition[cle..].find(&ancienne)?;
+    let fin_cle = cle + definition[cle..].find([',', '\n']).unwrap_or(definition.len() - cle);
+    if position > fin_cle {
+        return None;
+    }
+    Some(format!(
+        "{}ON DELETE {}{}",
+        &definition[..position],
+        politique,
+        &definition[position + ancienne.len()..]
+    ))
+}
+
+/// Recrée une table avec une nouvelle définition en conservant ses lignes
+///
+/// Les clés étrangères sont désactivées pendant la reconstruction: la
+/// suppression de l'ancienne table ne doit pas déclencher les cascades.
+pub(crate) fn reconstruire_table(conn: &Connection, table: &str, definition: &str) -> AppResult<()> {
+    let provisoire = format!("{}_reconstruction", table);
+    let debut = definition.find('(').unwrap_or(0);
+    let colonnes = {
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1) ORDER BY cid")?;
+        let noms = stmt.query_map([table], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+        noms.join(", ")
+    };
+
+    conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+    let resultat = (|| -> AppResult<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(&format!(
+            "CREATE TABLE {provisoire} {colonnes_definition};
+             INSERT INTO {provisoire} ({colonnes}) SELECT {colonnes} FROM {table};
+             DROP TABLE {table};
+             ALTER TABLE {provisoire} RENAME TO {table};",
+            colonnes_definition = &definition[debut..],
+        ))?;
+        tx.commit()?;
+        Ok(())
+    })();
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    resultat
+}
@@ -0,0 +1,73 @@
+use rusqlite::hooks::Action;
+use rusqlite::Connection;
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// Nature de la modification d'une ligne
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionModification {
+    Insertion,
+    MiseAJour,
+    Suppression,
+}
+
+/// Ligne modifiée par une écriture validée
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LigneModifiee {
+    pub table: String,
+    pub id: i64,
+    pub action: ActionModification,
+}
+
+/// Abonné aux modifications: reçoit les lignes modifiées de chaque écriture validée
+///
+/// Appelé pendant la validation, sur la connexion qui écrit: l'abonné ne
+/// doit pas accéder à la base de données.
+pub type AbonneModifications = Arc<dyn Fn(&[LigneModifiee]) + Send + Sync>;
+
+/// Abonnés partagés par toutes les connexions d'un gestionnaire
+pub type AbonnesModifications = Arc<RwLock<Vec<AbonneModifications>>>;
+
+/// Suit les modifications faites sur une connexion
+///
+/// `compteur` est incrémenté à chaque ligne écrite; les lignes d'une écriture
+/// sont transmises aux abonnés lorsqu'elle est validée, et oubliées si elle
+/// est annulée.
+pub(crate) fn suivre_modifications(conn: &Connection, compteur: Arc<AtomicU64>, abonnes: AbonnesModifications) {
+    let en_attente: Arc<Mutex<Vec<LigneModifiee>>> = Arc::default();
+
+    let lignes = en_attente.clone();
+    conn.update_hook(Some(move |action, base: &str, table: &str, id| {
+        compteur.fetch_add(1, Ordering::SeqCst);
+        // Les copies vers la base d'archive ne sont pas des modifications de l'application
+        if base != "main" {
+            return;
+        }
+        let action = match action {
+            Action::SQLITE_INSERT => ActionModification::Insertion,
+            Action::SQLITE_DELETE => ActionModification::Suppression,
+            _ => ActionModification::MiseAJour,
+        };
+        lignes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(LigneModifiee { table: table.to_string(), id, action });
+    }));
+
+    let lignes = en_attente.clone();
+    conn.commit_hook(Some(move || {
+        let validees = mem::take(&mut *lignes.lock().unwrap_or_else(PoisonError::into_inner));
+        if !validees.is_empty() {
+            for abonne in abonnes.read().unwrap_or_else(PoisonError::into_inner).iter() {
+                abonne(&validees);
+            }
+        }
+        // `false`: la validation n'est jamais refusée
+        false
+    }));
+
+    conn.rollback_hook(Some(move || {
+        en_attente.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }));
+}
@@ -0,0 +1,111 @@
+use crate::error::{AppError, AppResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// En-tête des fichiers SQLite non chiffrés
+const ENTETE_SQLITE: &[u8; 16] = b"SQLite format 3\0";
+
+/// Indique si SQLite a été compilé avec SQLCipher (fonctionnalité `chiffrement`)
+pub fn sqlcipher_disponible() -> AppResult<bool> {
+    let conn = Connection::open_in_memory()?;
+    let version: Option<String> = conn
+        .query_row("PRAGMA cipher_version", [], |row| row.get(0))
+        .optional()?;
+    Ok(version.is_some())
+}
+
+/// Applique la clé de chiffrement à une connexion
+///
+/// Doit être la première instruction exécutée sur la connexion. SQLCipher
+/// dérive la clé de la phrase secrète (PBKDF2); une phrase incorrecte n'est
+/// détectée qu'à la première lecture, d'où la lecture de `sqlite_master`.
+pub fn appliquer_cle(conn: &Connection, phrase_secrete: &str) -> rusqlite::Result<()> {
+    conn.pragma_update(None, "key", phrase_secrete)?;
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))?;
+    Ok(())
+}
+
+/// Indique si le fichier est une base SQLite non chiffrée
+///
+/// Un fichier absent ou vide n'est pas considéré comme une base en clair:
+/// SQLCipher le chiffrera à la première écriture.
+pub fn est_en_clair(database_path: &Path) -> AppResult<bool> {
+    let mut entete = [0u8; 16];
+    match fs::File::open(database_path) {
+        Ok(mut fichier) => Ok(fichier.read_exact(&mut entete).is_ok() && &entete == ENTETE_SQLITE),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(AppError::business_logic(&format!("Impossible de lire la base de données: {}", e))),
+    }
+}
+
+/// Prépare l'ouverture d'une base chiffrée
+///
+/// Vérifie que SQLCipher est disponible, puis chiffre la base si elle existe
+/// encore en clair (première ouverture après l'activation du chiffrement).
+///
+/// # Arguments
+/// * `database_path` - Le chemin du fichier de base de données
+/// * `phrase_secrete` - La phrase secrète dont est dérivée la clé
+pub fn preparer(database_path: &Path, phrase_secrete: &str) -> AppResult<()> {
+    if phrase_secrete.is_empty() {
+        return Err(AppError::validation_error("phrase_secrete", "La phrase secrète ne peut pas être vide"));
+    }
+    if !sqlcipher_disponible()? {
+        return Err(AppError::business_logic(
+            "Le chiffrement de la base de données n'est pas disponible: l'application doit être compilée avec la fonctionnalité `chiffrement`",
+        ));
+    }
+    if est_en_clair(database_path)? {
+        chiffrer_base(database_path, phrase_secrete)?;
+    }
+    Ok(())
+}
+
+/// Chiffre une base existante en clair
+///
+/// Les données sont exportées dans un fichier chiffré voisin, qui remplace
+/// ensuite l'original: une interruption laisse la base en clair intacte.
+///
+/// # Arguments
+/// * `database_path` - Le chemin de la base en clair, qui ne doit pas être ouverte
+/// * `phrase_secrete` - La phrase secrète dont est dérivée la clé
+pub fn chiffrer_base(database_path: &Path, phrase_secrete: &str) -> AppResult<()> {
+    let chiffree = fichier_voisin(database_path, "chiffrement");
+    supprimer_si_present(&chiffree)?;
+
+    {
+        let conn = Connection::open(database_path)?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS chiffree KEY ?2",
+            params![chiffree.to_string_lossy(), phrase_secrete],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('chiffree')", [], |_| Ok(()))?;
+        let user_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        conn.execute_batch(&format!("PRAGMA chiffree.user_version = {};", user_version))?;
+        conn.execute("DETACH DATABASE chiffree", [])?;
+    }
+
+    // Le journal WAL de la base en clair a été intégré à l'export
+    supprimer_si_present(&fichier_voisin(database_path, "wal"))?;
+    supprimer_si_present(&fichier_voisin(database_path, "shm"))?;
+    fs::rename(&chiffree, database_path)
+        .map_err(|e| AppError::business_logic(&format!("Impossible de remplacer la base en clair: {}", e)))
+}
+
+/// Chemin `<base>-<suffixe>` à côté de la base (journal WAL, export...)
+fn fichier_voisin(database_path: &Path, suffixe: &str) -> PathBuf {
+    let mut nom = database_path.as_os_str().to_owned();
+    nom.push(format!("-{}", suffixe));
+    PathBuf::from(nom)
+}
+
+fn supprimer_si_present(chemin: &Path) -> AppResult<()> {
+    match fs::remove_file(chemin) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(AppError::business_logic(&format!("Impossible de supprimer {}: {}", chemin.display(), e)))
+        }
+        _ => Ok(()),
+    }
+}
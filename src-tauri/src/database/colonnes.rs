@@ -0,0 +1,98 @@
+use crate::error::{AppError, AppResult};
+use rusqlite::Connection;
+
+/// Colonnes lues et écrites par les repositories pour chaque modèle: (table, colonnes)
+///
+/// Un champ de modèle renommé sans migration (ex: `soins.unit` lu sous le nom
+/// `unite_defaut`) ne provoquait une erreur qu'à la première requête concernée;
+/// la vérification au démarrage la signale dès l'ouverture de la base. La liste
+/// reprend toutes les colonnes de chaque table: un test la compare au schéma créé
+/// pour qu'une colonne ajoutée par une migration y soit aussi déclarée.
+pub const COLONNES_MODELES: [(&str, &[&str]); 12] = [
+    ("fermes", &["id", "nom", "nom_normalise", "nbr_meuble", "latitude", "longitude", "version"]),
+    (
+        "bandes",
+        &[
+            "id", "numero_bande", "date_entree", "ferme_id", "notes", "alimentation_contour", "statut", "date_cloture",
+            "numero_affiche", "version", "created_at", "updated_at", "created_by",
+        ],
+    ),
+    (
+        "batiments",
+        &[
+            "id", "bande_id", "numero_batiment", "poussin_id", "personnel_id", "quantite", "prix_poussin", "devise_poussin",
+            "version", "created_at", "updated_at", "created_by",
+        ],
+    ),
+    (
+        "semaines",
+        &["id", "batiment_id", "numero_semaine", "poids", "poids_a_verifier", "version", "created_at", "updated_at", "created_by"],
+    ),
+    (
+        "suivi_quotidien",
+        &[
+            "id", "semaine_id", "age", "deces_par_jour", "elimines_par_jour", "alimentation_par_jour", "soins_id",
+            "soins_quantite", "analyses", "remarques", "version", "created_at", "updated_at", "created_by",
+        ],
+    ),
+    ("suivi_soins", &["id", "suivi_id", "soin_id", "quantite", "unit", "quantite_valeur", "quantite_unite", "created_at"]),
+    ("soins", &["id", "nom", "nom_normalise", "unit", "categorie", "delai_attente_jours", "created_at", "prix_unitaire"]),
+    ("maladies", &["id", "nom", "nom_normalise", "created_at"]),
+    ("poussins", &["id", "nom", "nom_normalise", "created_at"]),
+    ("personnel", &["id", "nom", "nom_normalise", "telephone", "created_at", "version"]),
+    (
+        "alimentation_history",
+        &[
+            "id", "bande_id", "quantite", "created_at", "fournisseur", "type_aliment", "numero_lot", "prix_unitaire",
+            "devise", "taux_tva", "montant_tva", "saisi_le",
+        ],
+    ),
+    ("batiment_maladies", &["batiment_id", "maladie_id", "created_at"]),
+];
+
+/// Colonnes renommées depuis une version précédente: (table, ancien nom, nouveau nom)
+pub const COLONNES_RENOMMEES: [(&str, &str, &str); 1] = [("soins", "unite_defaut", "unit")];
+
+/// Renomme les colonnes des bases créées avec un ancien nom
+///
+/// Une colonne n'est renommée que si l'ancien nom existe et pas le nouveau.
+pub fn renommer_colonnes(conn: &Connection) -> AppResult<()> {
+    for (table, ancienne, nouvelle) in COLONNES_RENOMMEES {
+        let existantes = colonnes_table(conn, table)?;
+        if existantes.iter().any(|nom| nom == ancienne) && !existantes.iter().any(|nom| nom == nouvelle) {
+            conn.execute(&format!("ALTER TABLE {} RENAME COLUMN {} TO {}", table, ancienne, nouvelle), [])?;
+        }
+    }
+    Ok(())
+}
+
+/// Vérifie que chaque colonne de `COLONNES_MODELES` existe dans la base
+///
+/// # Returns
+/// Une erreur listant les colonnes manquantes (`table.colonne`)
+pub fn verifier_colonnes(conn: &Connection) -> AppResult<()> {
+    let mut manquantes = Vec::new();
+    for (table, colonnes) in COLONNES_MODELES {
+        let existantes = colonnes_table(conn, table)?;
+        for colonne in colonnes {
+            if !existantes.iter().any(|nom| nom == colonne) {
+                manquantes.push(format!("{}.{}", table, colonne));
+            }
+        }
+    }
+
+    if manquantes.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::business_logic(&format!(
+            "Le schéma de la base ne correspond pas aux modèles, colonnes manquantes: {}",
+            manquantes.join(", ")
+        )))
+    }
+}
+
+fn colonnes_table(conn: &Connection, table: &str) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let noms = stmt.query_map([table], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(noms)
+}
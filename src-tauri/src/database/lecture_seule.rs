@@ -0,0 +1,42 @@
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Tables que la simple consultation écrit, autorisées en lecture seule
+///
+/// Se connecter enregistre une session, une entrée du journal d'audit et le
+/// dernier code MFA utilisé; le moteur d'alertes enregistre les alertes
+/// qu'il détecte et met les alertes critiques en file d'envoi vers le webhook;
+/// chaque commande mesurée enregistre sa durée.
+const TABLES_CONSULTATION: [&str; 6] =
+    ["sessions", "audit_log", "user_mfa", "alertes", "webhook_outbox", "metriques_commandes"];
+
+/// Refuse les modifications de données sur une connexion tant que le mode lecture seule est actif
+///
+/// Le contrôle a lieu à la préparation de chaque requête: une insertion,
+/// mise à jour ou suppression échoue avec le code `SQLITE_AUTH`, converti en
+/// `AppError::ReadOnly`. Les tables temporaires et `TABLES_CONSULTATION`
+/// restent modifiables.
+///
+/// # Arguments
+/// * `conn` - La connexion à protéger
+/// * `lecture_seule` - L'interrupteur partagé par toutes les connexions du gestionnaire
+pub(crate) fn proteger(conn: &Connection, lecture_seule: Arc<AtomicBool>) {
+    conn.authorizer(Some(move |contexte: AuthContext<'_>| {
+        if !lecture_seule.load(Ordering::SeqCst) || contexte.database_name == Some("temp") {
+            return Authorization::Allow;
+        }
+        let table = match contexte.action {
+            AuthAction::Insert { table_name }
+            | AuthAction::Delete { table_name }
+            | AuthAction::Update { table_name, .. } => table_name,
+            _ => return Authorization::Allow,
+        };
+        if TABLES_CONSULTATION.contains(&table) {
+            Authorization::Allow
+        } else {
+            Authorization::Deny
+        }
+    }));
+}
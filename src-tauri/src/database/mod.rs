@@ -1,39 +1,301 @@
 use crate::error::{AppError, AppResult};
-use r2d2::Pool;
+use changements::{suivre_modifications, AbonnesModifications};
+use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::Connection;
-use std::path::Path;
+use rusqlite::{Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::Duration;
 
-/// Gestionnaire de base de données avec pool de connexions
+pub mod archive;
+pub mod cascades;
+pub mod colonnes;
+pub mod changements;
+pub mod chiffrement;
+pub mod lecture_seule;
+pub mod noms;
+pub mod numerotation;
+pub mod ouverture;
+pub mod profils;
+pub mod requetes_lentes;
+pub mod versions;
+
+pub use changements::{AbonneModifications, ActionModification, LigneModifiee};
+pub use noms::{nom_existe, normaliser_nom};
+pub use ouverture::OuvertureBase;
+pub use profils::{ProfilBase, PROFIL_PRINCIPAL};
+pub use requetes_lentes::NOM_JOURNAL_DIAGNOSTIC;
+pub use versions::erreur_mise_a_jour;
+
+/// Abstraction de l'accès au stockage utilisée par les repositories et services
 /// 
-/// Ce gestionnaire utilise un pool de connexions pour optimiser les performances
-/// et éviter les problèmes de verrouillage de base de données SQLite.
-pub struct DatabaseManager {
-    pub pool: Pool<SqliteConnectionManager>,
+/// `DatabaseManager` l'implémente avec un fichier SQLite; les tests utilisent
+/// une implémentation en mémoire (voir `test_support::MemoryStorage`) afin de
+/// tester les services sans fichier de base de données.
+pub trait Storage: Send + Sync {
+    /// Obtient une connexion prête à être utilisée
+    fn get_connection(&self) -> AppResult<PooledConnection<SqliteConnectionManager>>;
+
+    /// Initialise le schéma de base de données
+    /// 
+    /// Crée toutes les tables et index nécessaires pour l'application
+    /// si elles n'existent pas déjà.
+    fn initialize_schema(&self) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        create_schema(&conn)
+    }
+
+    /// Exécute une écriture dans une transaction immédiate
+    /// 
+    /// L'implémentation par défaut utilise une connexion du pool;
+    /// `DatabaseManager` sérialise les écritures sur sa connexion d'écriture.
+    /// Préférer `write`, qui retourne le résultat de l'écriture.
+    fn execute_write(&self, ecriture: &mut dyn FnMut(&Transaction) -> AppResult<()>) -> AppResult<()> {
+        let mut conn = self.get_connection()?;
+        executer_transaction(&mut conn, ecriture)
+    }
+
+    /// Exécute une opération de maintenance hors transaction (VACUUM, ANALYZE...)
+    /// 
+    /// `DatabaseManager` l'exécute sur sa connexion d'écriture: elle attend la
+    /// fin des écritures en cours et les suivantes attendent sa fin.
+    fn execute_maintenance(&self, maintenance: &mut dyn FnMut(&Connection) -> AppResult<()>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        maintenance(&conn)
+    }
+
+    /// Compteur des modifications de données, incrémenté à chaque ligne écrite
+    /// 
+    /// Sert à invalider les caches: une valeur inchangée garantit qu'aucune
+    /// donnée n'a été modifiée. `None` si le stockage ne suit pas les
+    /// modifications (les caches sont alors ignorés).
+    fn data_version(&self) -> Option<u64> {
+        None
+    }
+
+    /// Vrai lorsque le mode lecture seule est actif (voir `DatabaseManager::set_read_only`)
+    fn read_only(&self) -> bool {
+        false
+    }
+
+    /// Refuse une opération de modification lorsque le mode lecture seule est actif
+    /// 
+    /// Les écritures de données sont déjà refusées par la base; ce contrôle
+    /// sert aux opérations sur les tables que la consultation peut écrire
+    /// (acquittement d'une alerte...) et à celles qui touchent aux fichiers.
+    fn ensure_writable(&self) -> AppResult<()> {
+        if self.read_only() {
+            return Err(AppError::ReadOnly);
+        }
+        Ok(())
+    }
 }
 
-impl DatabaseManager {
-    /// Crée un nouveau gestionnaire de base de données
+impl dyn Storage + '_ {
+    /// Exécute une écriture de plusieurs requêtes de façon atomique
+    /// 
+    /// La transaction est validée si `ecriture` réussit et annulée sinon. Les
+    /// écritures passent l'une après l'autre: deux commandes concurrentes (deux
+    /// fenêtres ouvertes, par exemple) ne se disputent jamais le verrou SQLite.
+    /// `ecriture` ne doit utiliser que la transaction reçue, jamais une autre
+    /// connexion ni un autre appel à `write`.
     /// 
     /// # Arguments
-    /// * `database_path` - Le chemin vers le fichier de base de données SQLite
+    /// * `ecriture` - Les requêtes à exécuter sur la transaction
     /// 
     /// # Returns
-    /// Un `AppResult<DatabaseManager>` contenant le gestionnaire ou une erreur
-    pub fn new<P: AsRef<Path>>(database_path: P) -> AppResult<Self> {
+    /// Le résultat de `ecriture`
+    pub fn write<T>(&self, ecriture: impl FnOnce(&Transaction) -> AppResult<T>) -> AppResult<T> {
+        let mut ecriture = Some(ecriture);
+        let mut resultat = None;
+        self.execute_write(&mut |tx| {
+            if let Some(ecriture) = ecriture.take() {
+                resultat = Some(ecriture(tx)?);
+            }
+            Ok(())
+        })?;
+        resultat.ok_or_else(|| AppError::business_logic("L'écriture n'a pas été exécutée"))
+    }
+
+    /// Exécute une opération de maintenance et retourne son résultat
+    /// 
+    /// Voir `execute_maintenance`: aucune transaction n'est ouverte.
+    pub fn maintenance<T>(&self, maintenance: impl FnOnce(&Connection) -> AppResult<T>) -> AppResult<T> {
+        let mut maintenance = Some(maintenance);
+        let mut resultat = None;
+        self.execute_maintenance(&mut |conn| {
+            if let Some(maintenance) = maintenance.take() {
+                resultat = Some(maintenance(conn)?);
+            }
+            Ok(())
+        })?;
+        resultat.ok_or_else(|| AppError::business_logic("La maintenance n'a pas été exécutée"))
+    }
+}
+
+fn executer_transaction(
+    conn: &mut Connection,
+    ecriture: &mut dyn FnMut(&Transaction) -> AppResult<()>,
+) -> AppResult<()> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    ecriture(&tx)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Nom du fichier de la base principale dans le dossier de données de l'application
+pub const NOM_FICHIER_BASE: &str = "farm_management.db";
+
+/// Version du schéma créé par `create_schema`, enregistrée dans `PRAGMA user_version`
+///
+/// À incrémenter à chaque modification du schéma: les outils qui ouvrent la
+/// base sans la migrer (`geema-cli`) la comparent à celle de la base.
+pub const VERSION_SCHEMA: i64 = 2;
+
+/// Version du schéma d'une base (0 pour une base jamais ouverte par l'application
+/// ou créée avant l'enregistrement de la version)
+pub fn version_schema(conn: &Connection) -> AppResult<i64> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Variable d'environnement fixant le délai d'attente du verrou SQLite (en millisecondes)
+pub const BUSY_TIMEOUT_ENV: &str = "GEEMA_DB_BUSY_TIMEOUT_MS";
+
+/// Variable d'environnement contenant la phrase secrète de chiffrement de la base
+pub const PASSPHRASE_ENV: &str = "GEEMA_DB_PASSPHRASE";
+
+/// Variable d'environnement fixant le seuil des requêtes lentes (en millisecondes, 0 pour désactiver le journal)
+pub const SLOW_QUERY_THRESHOLD_ENV: &str = "GEEMA_SLOW_QUERY_MS";
+
+/// Configuration de l'accès à la base de données
+#[derive(Clone)]
+pub struct DatabaseConfig {
+    /// Délai pendant lequel une connexion attend qu'un verrou SQLite se libère
+    /// avant d'échouer (filet de sécurité derrière la sérialisation des écritures)
+    pub busy_timeout: Duration,
+    /// Phrase secrète dont SQLCipher dérive la clé de chiffrement, `None` pour
+    /// une base en clair. Une base existante en clair est chiffrée à l'ouverture.
+    pub passphrase: Option<String>,
+    /// Durée à partir de laquelle une requête est consignée, valeurs masquées,
+    /// dans le journal de diagnostic (`diagnostics.log`); `None` pour ne rien consigner
+    pub slow_query_threshold: Option<Duration>,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            busy_timeout: Duration::from_secs(5),
+            passphrase: None,
+            slow_query_threshold: Some(Duration::from_millis(500)),
+        }
+    }
+}
+
+// La phrase secrète n'apparaît jamais dans les journaux
+impl std::fmt::Debug for DatabaseConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DatabaseConfig")
+            .field("busy_timeout", &self.busy_timeout)
+            .field("passphrase", &self.passphrase.as_ref().map(|_| "***"))
+            .field("slow_query_threshold", &self.slow_query_threshold)
+            .finish()
+    }
+}
+
+impl DatabaseConfig {
+    /// Configuration par défaut, complétée par `GEEMA_DB_BUSY_TIMEOUT_MS`,
+    /// `GEEMA_DB_PASSPHRASE` et `GEEMA_SLOW_QUERY_MS` s'ils sont renseignés
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(millisecondes) = std::env::var(BUSY_TIMEOUT_ENV).ok().and_then(|v| v.trim().parse::<u64>().ok()) {
+            config.busy_timeout = Duration::from_millis(millisecondes);
+        }
+        if let Some(millisecondes) =
+            std::env::var(SLOW_QUERY_THRESHOLD_ENV).ok().and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            config.slow_query_threshold = (millisecondes > 0).then(|| Duration::from_millis(millisecondes));
+        }
+        config.passphrase = std::env::var(PASSPHRASE_ENV).ok().filter(|v| !v.is_empty());
+        config
+    }
+}
+
+/// Paramètres appliqués à chaque connexion du gestionnaire
+/// 
+/// La clé de chiffrement doit précéder toute autre instruction; la base
+/// d'archive est attachée hors transaction, à l'ouverture de la connexion.
+fn configurer_connexion(
+    conn: &Connection,
+    busy_timeout: Duration,
+    passphrase: Option<&str>,
+    chemin_archive: &Path,
+) -> rusqlite::Result<()> {
+    if let Some(passphrase) = passphrase {
+        chiffrement::appliquer_cle(conn, passphrase)?;
+    }
+    archive::attacher(conn, chemin_archive, passphrase)?;
+    conn.execute_batch(
+        "
+        PRAGMA foreign_keys = ON;
+        PRAGMA journal_mode = WAL;
+        PRAGMA synchronous = NORMAL;
+        PRAGMA cache_size = 1000;
+        PRAGMA temp_store = memory;
+        ",
+    )?;
+    conn.busy_timeout(busy_timeout)
+}
+
+/// Fichier de base ouvert par le gestionnaire: pool de lecture et connexion d'écriture
+struct BaseOuverte {
+    profil: String,
+    chemin: PathBuf,
+    pool: Pool<SqliteConnectionManager>,
+    writer: Mutex<Connection>,
+}
+
+impl BaseOuverte {
+    /// Ouvre un fichier de base avec la configuration du gestionnaire
+    fn ouvrir(
+        profil: &str,
+        database_path: &Path,
+        config: &DatabaseConfig,
+        data_version: &Arc<AtomicU64>,
+        abonnes: &AbonnesModifications,
+        lecture_seule: &Arc<AtomicBool>,
+    ) -> AppResult<Self> {
+        let DatabaseConfig { busy_timeout, passphrase, .. } = config.clone();
+
+        let chemin_archive = archive::chemin_archive(database_path);
+
+        // Chiffrement de la base et de son archive si elles sont encore en clair
+        if let Some(passphrase) = &passphrase {
+            chiffrement::preparer(database_path, passphrase)?;
+            chiffrement::preparer(&chemin_archive, passphrase)?;
+        }
+
+        // Connexion dédiée aux écritures, ouverte en premier: une phrase
+        // secrète incorrecte est signalée avant la création du pool
+        let mut writer = Connection::open(database_path)?;
+        configurer_connexion(&writer, busy_timeout, passphrase.as_deref(), &chemin_archive).map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(erreur, _) if erreur.code == rusqlite::ErrorCode::NotADatabase => {
+                if passphrase.is_some() { AppError::InvalidPassphrase } else { AppError::Locked }
+            }
+            e => AppError::from(e),
+        })?;
+        suivre_modifications(&writer, data_version.clone(), abonnes.clone());
+        lecture_seule::proteger(&writer, lecture_seule.clone());
+        requetes_lentes::suivre(&mut writer);
+
         // Configuration du gestionnaire de connexions SQLite
+        let (compteur, abonnes_pool, lecture_seule) = (data_version.clone(), abonnes.clone(), lecture_seule.clone());
         let manager = SqliteConnectionManager::file(database_path)
-            .with_init(|conn| {
-                // Configuration de la connexion SQLite pour de meilleures performances
-                conn.execute_batch(
-                    "
-                    PRAGMA foreign_keys = ON;
-                    PRAGMA journal_mode = WAL;
-                    PRAGMA synchronous = NORMAL;
-                    PRAGMA cache_size = 1000;
-                    PRAGMA temp_store = memory;
-                    ",
-                )?;
+            .with_init(move |conn| {
+                configurer_connexion(conn, busy_timeout, passphrase.as_deref(), &chemin_archive)?;
+                suivre_modifications(conn, compteur.clone(), abonnes_pool.clone());
+                lecture_seule::proteger(conn, lecture_seule.clone());
+                requetes_lentes::suivre(conn);
                 Ok(())
             });
 
@@ -44,285 +306,1836 @@ impl DatabaseManager {
             .build(manager)
             .map_err(AppError::from)?;
 
-        Ok(DatabaseManager { pool })
+        Ok(BaseOuverte { profil: profil.to_string(), chemin: database_path.to_path_buf(), pool, writer: Mutex::new(writer) })
+    }
+}
+
+/// Gestionnaire de base de données avec pool de connexions
+/// 
+/// Les lectures utilisent un pool de connexions; les écritures passées par
+/// `write` sont sérialisées sur une connexion dédiée, ce qui évite les erreurs
+/// SQLITE_BUSY lorsque plusieurs commandes écrivent en même temps.
+/// 
+/// La base ouverte peut être remplacée en cours d'exécution par celle d'un
+/// autre profil (`switch_profile`); les connexions et écritures déjà en
+/// cours terminent sur l'ancienne base.
+pub struct DatabaseManager {
+    base: RwLock<Arc<BaseOuverte>>,
+    /// Sérialise les créations, ouvertures et suppressions de profils
+    changement_profil: Mutex<()>,
+    chemin_principal: PathBuf,
+    config: DatabaseConfig,
+    data_version: Arc<AtomicU64>,
+    abonnes: AbonnesModifications,
+    lecture_seule: Arc<AtomicBool>,
+}
+
+impl DatabaseManager {
+    /// Crée un nouveau gestionnaire de base de données avec la configuration par défaut
+    /// 
+    /// # Arguments
+    /// * `database_path` - Le chemin vers le fichier de base de données SQLite
+    /// 
+    /// # Returns
+    /// Un `AppResult<DatabaseManager>` contenant le gestionnaire ou une erreur
+    pub fn new<P: AsRef<Path>>(database_path: P) -> AppResult<Self> {
+        Self::with_config(database_path, DatabaseConfig::default())
+    }
+
+    /// Crée un nouveau gestionnaire de base de données
+    /// 
+    /// # Arguments
+    /// * `database_path` - Le chemin vers le fichier de base de données SQLite
+    /// * `config` - La configuration de l'accès à la base
+    pub fn with_config<P: AsRef<Path>>(database_path: P, config: DatabaseConfig) -> AppResult<Self> {
+        requetes_lentes::configurer(
+            &database_path.as_ref().with_file_name(NOM_JOURNAL_DIAGNOSTIC),
+            config.slow_query_threshold,
+        );
+        let data_version = Arc::new(AtomicU64::new(0));
+        let abonnes = AbonnesModifications::default();
+        let lecture_seule = Arc::new(AtomicBool::new(false));
+        let base = BaseOuverte::ouvrir(
+            PROFIL_PRINCIPAL,
+            database_path.as_ref(),
+            &config,
+            &data_version,
+            &abonnes,
+            &lecture_seule,
+        )?;
+
+        Ok(DatabaseManager {
+            base: RwLock::new(Arc::new(base)),
+            changement_profil: Mutex::new(()),
+            chemin_principal: database_path.as_ref().to_path_buf(),
+            config,
+            data_version,
+            abonnes,
+            lecture_seule,
+        })
+    }
+
+    /// Base actuellement ouverte
+    fn base(&self) -> Arc<BaseOuverte> {
+        self.base.read().unwrap_or_else(PoisonError::into_inner).clone()
     }
 
     /// Obtient une connexion du pool
     /// 
     /// # Returns
     /// Une connexion SQLite prête à être utilisée
-    pub fn get_connection(&self) -> AppResult<r2d2::PooledConnection<SqliteConnectionManager>> {
-        let conn = self.pool.get().map_err(AppError::from)?;
+    pub fn get_connection(&self) -> AppResult<PooledConnection<SqliteConnectionManager>> {
+        let conn = self.base().pool.get().map_err(AppError::from)?;
         
         // Ensure foreign key constraints are enabled for this connection
         conn.execute("PRAGMA foreign_keys = ON", [])?;
+
+        // Les requêtes mises en cache ont pu être autorisées avant le passage
+        // en lecture seule
+        if self.read_only() {
+            conn.flush_prepared_statement_cache();
+        }
         
         Ok(conn)
     }
 
-    /// Initialise le schéma de base de données
+    /// Abonne une fonction aux modifications de données validées
     /// 
-    /// Crée toutes les tables et index nécessaires pour l'application
-    /// si elles n'existent pas déjà.
-    pub fn initialize_schema(&self) -> AppResult<()> {
-        let conn = self.get_connection()?;
-        
-        // Enable foreign key constraints for SQLite
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
-        
-        // Création de la table users (pour l'authentification)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS users (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                username TEXT NOT NULL UNIQUE,
-                email TEXT NOT NULL UNIQUE,
-                password_hash TEXT NOT NULL,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+    /// # Arguments
+    /// * `abonne` - Appelée avec les lignes modifiées de chaque écriture validée
+    pub fn subscribe_changes(&self, abonne: AbonneModifications) {
+        self.abonnes.write().unwrap_or_else(PoisonError::into_inner).push(abonne);
+    }
 
-        // Création de la table fermes
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS fermes (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                nom TEXT NOT NULL UNIQUE,
-                nbr_meuble INTEGER NOT NULL DEFAULT 0
-            )",
-            [],
-        )?;
+    /// Active ou désactive le mode lecture seule
+    /// 
+    /// Tant qu'il est actif, toute modification de données est refusée avec
+    /// `AppError::ReadOnly` (voir `lecture_seule::proteger`); la consultation,
+    /// la connexion des utilisateurs et le calcul des alertes restent possibles.
+    /// Le mode vaut pour toutes les bases de profils et n'est pas mémorisé.
+    /// 
+    /// # Arguments
+    /// * `actif` - Vrai pour interdire les modifications
+    pub fn set_read_only(&self, actif: bool) {
+        self.lecture_seule.store(actif, Ordering::SeqCst);
+    }
 
-        // Création de la table personnel
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS personnel (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                nom TEXT NOT NULL UNIQUE,
-                telephone TEXT,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+    /// Profil de la base actuellement ouverte
+    pub fn get_active_profile(&self) -> ProfilBase {
+        let base = self.base();
+        ProfilBase::new(&base.profil, &base.chemin, true)
+    }
 
-        // Création de la table soins
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS soins (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                nom TEXT NOT NULL UNIQUE,
-                unit TEXT NOT NULL,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+    /// Liste les profils disponibles dans le dossier de la base principale
+    /// 
+    /// # Returns
+    /// Les profils, `principal` en premier, avec le profil actif signalé
+    pub fn list_profiles(&self) -> AppResult<Vec<ProfilBase>> {
+        profils::lister_profils(&self.chemin_principal, &self.base().profil)
+    }
 
-        // Création de la table bandes
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS bandes (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                numero_bande INTEGER NOT NULL,
-                date_entree DATE NOT NULL,
-                ferme_id INTEGER NOT NULL,
-                notes TEXT,
-                alimentation_contour REAL NOT NULL DEFAULT 0.0,
-                FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE RESTRICT,
-                UNIQUE(ferme_id, numero_bande)
-            )",
-            [],
-        )?;
+    /// Crée la base d'un nouveau profil, sans l'ouvrir
+    /// 
+    /// # Arguments
+    /// * `profil` - Le nom du profil (ex: `ferme-nord` pour `ferme-nord.db`)
+    /// 
+    /// # Returns
+    /// Le profil créé
+    pub fn create_profile(&self, profil: &str) -> AppResult<ProfilBase> {
+        let profil = profil.trim();
+        let _changement = self.changement_profil.lock().unwrap_or_else(PoisonError::into_inner);
+        let chemin = profils::chemin_profil(&self.chemin_principal, profil)?;
+        if chemin.exists() {
+            return Err(AppError::validation_error("profile_name", &format!("Le profil \"{}\" existe déjà", profil)));
+        }
+        self.ouvrir_base(profil, &chemin)?;
+        Ok(ProfilBase::new(profil, &chemin, false))
+    }
 
-        // Création de la table batiments
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS batiments (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                bande_id INTEGER NOT NULL,
-                numero_batiment TEXT NOT NULL,
-                poussin_id INTEGER NOT NULL,
-                personnel_id INTEGER NOT NULL,
-                quantite INTEGER NOT NULL,
-                FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE,
-                FOREIGN KEY (poussin_id) REFERENCES poussins(id) ON DELETE RESTRICT,
-                FOREIGN KEY (personnel_id) REFERENCES personnel(id) ON DELETE RESTRICT
-            )",
-            [],
+    /// Ouvre la base d'un profil existant à la place de la base courante
+    /// 
+    /// Contrairement à `switch_profile`, la base n'est pas créée si elle
+    /// n'existe pas.
+    /// 
+    /// # Arguments
+    /// * `profil` - `principal` ou le nom d'un profil existant
+    /// 
+    /// # Returns
+    /// Le profil désormais actif
+    pub fn open_profile(&self, profil: &str) -> AppResult<ProfilBase> {
+        let profil = profil.trim();
+        let chemin = profils::chemin_profil(&self.chemin_principal, profil)?;
+        if profil != PROFIL_PRINCIPAL && !chemin.is_file() {
+            return Err(AppError::validation_error("profile_name", &format!("Le profil \"{}\" n'existe pas", profil)));
+        }
+        self.switch_profile(profil)
+    }
+
+    /// Ouvre la base d'un autre profil à la place de la base courante
+    /// 
+    /// La base du profil est créée au besoin, avec le schéma complet, et
+    /// utilise la même configuration (chiffrement compris) que la base
+    /// principale. Les caches sont invalidés par l'incrément du compteur de
+    /// modifications. Le profil n'est pas mémorisé: l'application redémarre
+    /// toujours sur la base principale.
+    /// 
+    /// # Arguments
+    /// * `profil` - `principal` ou le nom d'un profil (ex: `demo` pour `demo.db`)
+    /// 
+    /// # Returns
+    /// Le profil désormais actif
+    pub fn switch_profile(&self, profil: &str) -> AppResult<ProfilBase> {
+        let profil = profil.trim();
+        let _changement = self.changement_profil.lock().unwrap_or_else(PoisonError::into_inner);
+        let chemin = profils::chemin_profil(&self.chemin_principal, profil)?;
+        if self.base().profil != profil {
+            let base = self.ouvrir_base(profil, &chemin)?;
+            *self.base.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(base);
+            self.data_version.fetch_add(1, Ordering::SeqCst);
+        }
+        Ok(self.get_active_profile())
+    }
+
+    /// Supprime définitivement la base d'un profil et son archive
+    /// 
+    /// La base principale et la base actuellement ouverte ne peuvent pas
+    /// être supprimées.
+    /// 
+    /// # Arguments
+    /// * `profil` - Le nom du profil à supprimer
+    pub fn delete_profile(&self, profil: &str) -> AppResult<()> {
+        let profil = profil.trim();
+        let _changement = self.changement_profil.lock().unwrap_or_else(PoisonError::into_inner);
+        let chemin = profils::chemin_profil(&self.chemin_principal, profil)?;
+        if profil == PROFIL_PRINCIPAL {
+            return Err(AppError::business_logic("La base principale ne peut pas être supprimée"));
+        }
+        if self.base().profil == profil {
+            return Err(AppError::business_logic(
+                "Le profil ouvert ne peut pas être supprimé: ouvrir un autre profil d'abord",
+            ));
+        }
+        if !chemin.is_file() {
+            return Err(AppError::validation_error("profile_name", &format!("Le profil \"{}\" n'existe pas", profil)));
+        }
+        self.ensure_writable()?;
+        profils::supprimer_fichiers(&chemin)
+    }
+
+    /// Ouvre la base d'un profil, la crée au besoin et met son schéma à jour
+    /// 
+    /// En lecture seule, seule une base existante peut être ouverte et son
+    /// schéma est laissé tel quel.
+    fn ouvrir_base(&self, profil: &str, chemin: &Path) -> AppResult<BaseOuverte> {
+        if self.read_only() && !chemin.is_file() {
+            return Err(AppError::ReadOnly);
+        }
+        let base = BaseOuverte::ouvrir(
+            profil,
+            chemin,
+            &self.config,
+            &self.data_version,
+            &self.abonnes,
+            &self.lecture_seule,
         )?;
+        if !self.read_only() {
+            create_schema(&base.pool.get().map_err(AppError::from)?)?;
+        }
+        Ok(base)
+    }
+}
 
-        // Création de la table semaines
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS semaines (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                batiment_id INTEGER NOT NULL,
-                numero_semaine INTEGER NOT NULL CHECK (numero_semaine BETWEEN 1 AND 9),
-                poids REAL,
-                FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
-                UNIQUE(batiment_id, numero_semaine)
-            )",
-            [],
+impl Storage for DatabaseManager {
+    fn get_connection(&self) -> AppResult<PooledConnection<SqliteConnectionManager>> {
+        DatabaseManager::get_connection(self)
+    }
+
+    fn execute_write(&self, ecriture: &mut dyn FnMut(&Transaction) -> AppResult<()>) -> AppResult<()> {
+        // Une écriture interrompue par une panique a déjà été annulée: la
+        // connexion reste utilisable
+        let base = self.base();
+        let mut writer = base.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        if self.read_only() {
+            writer.flush_prepared_statement_cache();
+        }
+        executer_transaction(&mut writer, ecriture)
+    }
+
+    fn execute_maintenance(&self, maintenance: &mut dyn FnMut(&Connection) -> AppResult<()>) -> AppResult<()> {
+        let base = self.base();
+        let writer = base.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        maintenance(&writer)
+    }
+
+    fn data_version(&self) -> Option<u64> {
+        Some(self.data_version.load(Ordering::SeqCst))
+    }
+
+    fn read_only(&self) -> bool {
+        self.lecture_seule.load(Ordering::SeqCst)
+    }
+}
+
+/// Crée les tables de l'application sur une connexion donnée
+/// 
+/// Partagé par toutes les implémentations de `Storage` afin que le schéma
+/// de test soit strictement identique au schéma de production.
+/// 
+/// # Arguments
+/// * `conn` - La connexion à la base de données
+pub fn create_schema(conn: &Connection) -> AppResult<()> {
+    // Enable foreign key constraints for SQLite
+    conn.execute("PRAGMA foreign_keys = ON", [])?;
+    
+    // Création de la table users (pour l'authentification)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL UNIQUE,
+            email TEXT NOT NULL UNIQUE,
+            password_hash TEXT NOT NULL,
+            role TEXT NOT NULL DEFAULT 'technicien',
+            must_change_password INTEGER NOT NULL DEFAULT 0,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Création de la table sessions (tokens de connexion conservés entre les redémarrages)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            token TEXT PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Second facteur d'authentification (TOTP) des utilisateurs qui l'ont activé
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS user_mfa (
+            user_id INTEGER PRIMARY KEY,
+            secret TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 0,
+            last_used_step INTEGER,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            enabled_at DATETIME,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Préférences d'affichage de chaque utilisateur (document JSON)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS user_preferences (
+            user_id INTEGER PRIMARY KEY,
+            preferences TEXT NOT NULL,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Paramètres de l'application (clé/valeur)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS parametres (
+            cle TEXT PRIMARY KEY,
+            valeur TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Codes d'invitation gérés par les administrateurs pour la création de comptes
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS invitations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            code TEXT NOT NULL UNIQUE,
+            role TEXT NOT NULL DEFAULT 'technicien',
+            expires_on DATE,
+            single_use INTEGER NOT NULL DEFAULT 1,
+            use_count INTEGER NOT NULL DEFAULT 0,
+            revoked INTEGER NOT NULL DEFAULT 0,
+            created_by INTEGER,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            last_used_by INTEGER,
+            last_used_at DATETIME,
+            FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+            FOREIGN KEY (last_used_by) REFERENCES users(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Journal d'audit des opérations sensibles (clôture, réouverture...)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER,
+            action TEXT NOT NULL,
+            entite TEXT NOT NULL,
+            entite_id INTEGER NOT NULL,
+            details TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Création de la table fermes
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS fermes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nom TEXT NOT NULL UNIQUE,
+            nom_normalise TEXT,
+            nbr_meuble INTEGER NOT NULL DEFAULT 0,
+            latitude REAL,
+            longitude REAL,
+            version INTEGER NOT NULL DEFAULT 1
+        )",
+        [],
+    )?;
+
+    // Position des bâtiments physiques d'une ferme sur le plan du site
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS positions_batiments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ferme_id INTEGER NOT NULL,
+            numero_batiment TEXT NOT NULL,
+            plan_x REAL,
+            plan_y REAL,
+            largeur REAL CHECK (largeur > 0),
+            hauteur REAL CHECK (hauteur > 0),
+            latitude REAL,
+            longitude REAL,
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE CASCADE,
+            UNIQUE(ferme_id, numero_batiment)
+        )",
+        [],
+    )?;
+
+    // Relevés météo journaliers par ferme (service météo en ligne)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS meteo_quotidienne (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ferme_id INTEGER NOT NULL,
+            date DATE NOT NULL,
+            temperature_min REAL,
+            temperature_max REAL,
+            temperature_moyenne REAL,
+            humidite_moyenne REAL,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE CASCADE,
+            UNIQUE(ferme_id, date)
+        )",
+        [],
+    )?;
+
+    // Mesures d'ambiance importées des contrôleurs (par bâtiment physique)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mesures_capteurs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ferme_id INTEGER NOT NULL,
+            numero_batiment TEXT NOT NULL,
+            horodatage DATETIME NOT NULL,
+            temperature REAL,
+            humidite REAL,
+            co2 REAL,
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE CASCADE,
+            UNIQUE(ferme_id, numero_batiment, horodatage)
+        )",
+        [],
+    )?;
+
+    // Agrégats horaires des mesures d'ambiance (graphiques)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mesures_capteurs_horaires (
+            ferme_id INTEGER NOT NULL,
+            numero_batiment TEXT NOT NULL,
+            heure TEXT NOT NULL,
+            nombre_mesures INTEGER NOT NULL,
+            temperature_somme REAL,
+            temperature_nombre INTEGER NOT NULL DEFAULT 0,
+            temperature_min REAL,
+            temperature_max REAL,
+            humidite_somme REAL,
+            humidite_nombre INTEGER NOT NULL DEFAULT 0,
+            co2_somme REAL,
+            co2_nombre INTEGER NOT NULL DEFAULT 0,
+            co2_max REAL,
+            PRIMARY KEY (ferme_id, numero_batiment, heure),
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Création de la table personnel
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS personnel (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nom TEXT NOT NULL UNIQUE,
+            nom_normalise TEXT,
+            telephone TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            version INTEGER NOT NULL DEFAULT 1
+        )",
+        [],
+    )?;
+
+    // Création de la table soins
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS soins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nom TEXT NOT NULL UNIQUE,
+            nom_normalise TEXT,
+            unit TEXT NOT NULL,
+            categorie TEXT,
+            delai_attente_jours INTEGER NOT NULL DEFAULT 0 CHECK (delai_attente_jours >= 0),
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            prix_unitaire REAL CHECK (prix_unitaire >= 0)
+        )",
+        [],
+    )?;
+
+    // Création de la table bandes
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS bandes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            numero_bande INTEGER NOT NULL,
+            date_entree DATE NOT NULL,
+            ferme_id INTEGER NOT NULL,
+            notes TEXT,
+            alimentation_contour REAL NOT NULL DEFAULT 0.0,
+            statut TEXT NOT NULL DEFAULT 'active',
+            date_cloture DATE,
+            numero_affiche TEXT,
+            version INTEGER NOT NULL DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            created_by INTEGER,
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE RESTRICT,
+            FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+            UNIQUE(ferme_id, numero_bande)
+        )",
+        [],
+    )?;
+
+    // Création de la table batiments
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS batiments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            bande_id INTEGER NOT NULL,
+            numero_batiment TEXT NOT NULL,
+            poussin_id INTEGER NOT NULL,
+            personnel_id INTEGER NOT NULL,
+            quantite INTEGER NOT NULL,
+            prix_poussin REAL,
+            devise_poussin TEXT,
+            version INTEGER NOT NULL DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            created_by INTEGER,
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE,
+            FOREIGN KEY (poussin_id) REFERENCES poussins(id) ON DELETE RESTRICT,
+            FOREIGN KEY (personnel_id) REFERENCES personnel(id) ON DELETE RESTRICT,
+            FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Création de la table semaines
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS semaines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batiment_id INTEGER NOT NULL,
+            numero_semaine INTEGER NOT NULL CHECK (numero_semaine BETWEEN 1 AND 9),
+            poids REAL,
+            poids_a_verifier INTEGER NOT NULL DEFAULT 0,
+            version INTEGER NOT NULL DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            created_by INTEGER,
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
+            FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+            UNIQUE(batiment_id, numero_semaine)
+        )",
+        [],
+    )?;
+
+    // Création de la table suivi_quotidien
+    // (soins_id et soins_quantite ne sont plus alimentés: les soins sont dans suivi_soins)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS suivi_quotidien (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            semaine_id INTEGER NOT NULL,
+            age INTEGER NOT NULL CHECK (age > 0),
+            deces_par_jour INTEGER,
+            elimines_par_jour INTEGER,
+            alimentation_par_jour REAL,
+            soins_id INTEGER,
+            soins_quantite TEXT,
+            analyses TEXT,
+            remarques TEXT,
+            version INTEGER NOT NULL DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            created_by INTEGER,
+            FOREIGN KEY (semaine_id) REFERENCES semaines(id) ON DELETE CASCADE,
+            FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+            FOREIGN KEY (soins_id) REFERENCES soins(id) ON DELETE SET NULL,
+            UNIQUE(semaine_id, age)
+        )",
+        [],
+    )?;
+
+    // Soins administrés lors d'un suivi quotidien (plusieurs par jour)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS suivi_soins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            suivi_id INTEGER NOT NULL,
+            soin_id INTEGER,
+            quantite TEXT,
+            unit TEXT,
+            quantite_valeur REAL,
+            quantite_unite TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (suivi_id) REFERENCES suivi_quotidien(id) ON DELETE CASCADE,
+            FOREIGN KEY (soin_id) REFERENCES soins(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Création de la table alimentation_history
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS alimentation_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            bande_id INTEGER NOT NULL,
+            quantite REAL NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            fournisseur TEXT,
+            type_aliment TEXT,
+            numero_lot TEXT,
+            prix_unitaire REAL,
+            devise TEXT,
+            taux_tva REAL,
+            montant_tva REAL,
+            saisi_le DATETIME,
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Création de la table maladies
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS maladies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nom TEXT NOT NULL UNIQUE,
+            nom_normalise TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Table de liaison batiment <-> maladies (plusieurs maladies par bâtiment)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS batiment_maladies (
+            batiment_id INTEGER NOT NULL,
+            maladie_id INTEGER NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (batiment_id, maladie_id),
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
+            FOREIGN KEY (maladie_id) REFERENCES maladies(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Création de la table analyses (résultats de laboratoire par bâtiment,
+    // éventuellement rattachés à un épisode de maladie)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS analyses (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batiment_id INTEGER NOT NULL,
+            maladie_id INTEGER,
+            type_analyse TEXT NOT NULL,
+            laboratoire TEXT,
+            date_prelevement DATE NOT NULL,
+            resultat TEXT,
+            fichier_pdf TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
+            FOREIGN KEY (maladie_id) REFERENCES maladies(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Création de la table notes_batiment (chronologie des observations libres)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS notes_batiment (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batiment_id INTEGER NOT NULL,
+            auteur TEXT NOT NULL,
+            contenu TEXT NOT NULL,
+            tag TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Création de la table litieres (livraisons et changements de litière par bâtiment)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS litieres (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            batiment_id INTEGER NOT NULL,
+            type_operation TEXT NOT NULL,
+            materiau TEXT NOT NULL,
+            quantite REAL NOT NULL CHECK (quantite > 0),
+            unite TEXT NOT NULL DEFAULT 'kg',
+            cout REAL,
+            taux_tva REAL,
+            montant_tva REAL,
+            date_operation DATE NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Historique des alertes détectées et de leur acquittement
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS alertes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            type_alerte TEXT NOT NULL,
+            gravite TEXT NOT NULL,
+            ferme_id INTEGER NOT NULL,
+            bande_id INTEGER,
+            batiment_id INTEGER,
+            date_alerte DATE NOT NULL,
+            message TEXT NOT NULL,
+            detectee_le DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            acquittee_par INTEGER,
+            acquittee_le DATETIME,
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE,
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
+            FOREIGN KEY (acquittee_par) REFERENCES users(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Abonnements des utilisateurs aux alertes (types et fermes en JSON, liste vide = tout)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS abonnements_alertes (
+            user_id INTEGER PRIMARY KEY,
+            types_alerte TEXT NOT NULL DEFAULT '[]',
+            ferme_ids TEXT NOT NULL DEFAULT '[]',
+            gravite_min TEXT NOT NULL DEFAULT 'info',
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Création de la table messages (passation entre équipes, adressée à un utilisateur ou à un bâtiment)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            auteur_id INTEGER,
+            destinataire_id INTEGER,
+            batiment_id INTEGER,
+            contenu TEXT NOT NULL,
+            lu INTEGER NOT NULL DEFAULT 0,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (auteur_id) REFERENCES users(id) ON DELETE SET NULL,
+            FOREIGN KEY (destinataire_id) REFERENCES users(id) ON DELETE CASCADE,
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
+            CHECK (destinataire_id IS NOT NULL OR batiment_id IS NOT NULL)
+        )",
+        [],
+    )?;
+
+    // Création de la table vides_sanitaires (nettoyage et désinfection d'un bâtiment physique entre deux bandes)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS vides_sanitaires (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ferme_id INTEGER NOT NULL,
+            numero_batiment TEXT NOT NULL,
+            date_debut DATE NOT NULL,
+            date_fin DATE,
+            produits TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Registre des immobilisations d'une ferme (bâtiments, équipements) amorties linéairement
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS immobilisations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ferme_id INTEGER NOT NULL,
+            nom TEXT NOT NULL,
+            categorie TEXT NOT NULL DEFAULT 'equipement',
+            date_achat DATE NOT NULL,
+            valeur REAL NOT NULL CHECK (valeur >= 0),
+            duree_amortissement_annees INTEGER NOT NULL CHECK (duree_amortissement_annees > 0),
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Équipements d'une ferme, installés dans un bâtiment physique ou communs à la ferme
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS equipements (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ferme_id INTEGER NOT NULL,
+            nom TEXT NOT NULL,
+            type_equipement TEXT NOT NULL DEFAULT 'autre',
+            numero_batiment TEXT,
+            date_mise_en_service DATE NOT NULL,
+            intervalle_entretien_jours INTEGER CHECK (intervalle_entretien_jours > 0),
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (ferme_id) REFERENCES fermes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Entretiens réalisés sur les équipements
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS entretiens_equipement (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            equipement_id INTEGER NOT NULL,
+            date_entretien DATE NOT NULL,
+            description TEXT,
+            cout REAL CHECK (cout >= 0),
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (equipement_id) REFERENCES equipements(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Périodes d'une bande déverrouillées par un administrateur hors de la fenêtre de saisie
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS deverrouillages_periode (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            bande_id INTEGER NOT NULL,
+            date_debut DATE NOT NULL,
+            date_fin DATE NOT NULL,
+            expire_le DATETIME NOT NULL,
+            user_id INTEGER,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Création de la table poussins
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS poussins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nom TEXT NOT NULL UNIQUE,
+            nom_normalise TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Programme d'alimentation par type de poussin (phases par tranche d'âge)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS phases_alimentation (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            poussin_id INTEGER NOT NULL,
+            nom_phase TEXT NOT NULL,
+            jour_debut INTEGER NOT NULL CHECK (jour_debut > 0),
+            jour_fin INTEGER NOT NULL,
+            grammes_par_sujet REAL NOT NULL CHECK (grammes_par_sujet >= 0),
+            FOREIGN KEY (poussin_id) REFERENCES poussins(id) ON DELETE CASCADE,
+            CHECK (jour_fin >= jour_debut)
+        )",
+        [],
+    )?;
+
+    // Budget prévisionnel d'une bande (au plus un par bande)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS budgets_bande (
+            bande_id INTEGER PRIMARY KEY,
+            aliment_prevu_kg REAL,
+            mortalite_prevue_pourcentage REAL,
+            cout_aliment_prevu REAL,
+            cout_poussins_prevu REAL,
+            cout_soins_prevu REAL,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Contrat d'élevage d'une bande: prix garanti et primes (au plus un par bande)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS contrats_bande (
+            bande_id INTEGER PRIMARY KEY,
+            prix_garanti_kg REAL NOT NULL CHECK (prix_garanti_kg >= 0),
+            ic_cible REAL,
+            prime_ic_par_centieme REAL,
+            mortalite_cible_pourcentage REAL,
+            prime_mortalite_par_point REAL,
+            deduire_intrants INTEGER NOT NULL DEFAULT 0,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Historique des prix de l'aliment (par kg) et des poussins (par sujet et type de poussin)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS historique_prix (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            article TEXT NOT NULL CHECK (article IN ('aliment', 'poussin')),
+            poussin_id INTEGER,
+            prix_unitaire REAL NOT NULL CHECK (prix_unitaire >= 0),
+            devise TEXT NOT NULL DEFAULT 'MAD',
+            date_debut DATE NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (poussin_id) REFERENCES poussins(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Exports programmés vers un dossier et statut de leur dernière exécution
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS exports_programmes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            type_export TEXT NOT NULL,
+            dossier TEXT NOT NULL,
+            frequence TEXT NOT NULL CHECK (frequence IN ('quotidienne', 'hebdomadaire', 'mensuelle')),
+            heure INTEGER NOT NULL CHECK (heure BETWEEN 0 AND 23),
+            jour INTEGER,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            derniere_execution DATETIME,
+            dernier_statut TEXT,
+            dernier_message TEXT
+        )",
+        [],
+    )?;
+
+    // Événements en file d'envoi vers le webhook du système de la coopérative
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhook_outbox (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            type_evenement TEXT NOT NULL,
+            contenu TEXT NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            tentatives INTEGER NOT NULL DEFAULT 0,
+            prochaine_tentative DATETIME,
+            envoye_le DATETIME,
+            derniere_erreur TEXT
+        )",
+        [],
+    )?;
+
+    // Mesures locales des commandes (nombre d'appels et durées), jamais envoyées
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS metriques_commandes (
+            commande TEXT PRIMARY KEY,
+            appels INTEGER NOT NULL DEFAULT 0,
+            echecs INTEGER NOT NULL DEFAULT 0,
+            duree_totale_ms INTEGER NOT NULL DEFAULT 0,
+            duree_max_ms INTEGER NOT NULL DEFAULT 0,
+            derniere_execution DATETIME
+        )",
+        [],
+    )?;
+
+    // Définitions des rapports personnalisés (description JSON)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS report_definitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nom TEXT NOT NULL UNIQUE,
+            definition TEXT NOT NULL,
+            created_by INTEGER,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Plan de soins quotidien par type de poussin (soin donné sur une tranche d'âge)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS plan_soins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            poussin_id INTEGER NOT NULL,
+            soin_id INTEGER NOT NULL,
+            jour_debut INTEGER NOT NULL CHECK (jour_debut > 0),
+            jour_fin INTEGER NOT NULL,
+            quantite TEXT,
+            unit TEXT,
+            FOREIGN KEY (poussin_id) REFERENCES poussins(id) ON DELETE CASCADE,
+            FOREIGN KEY (soin_id) REFERENCES soins(id) ON DELETE CASCADE,
+            CHECK (jour_fin >= jour_debut)
+        )",
+        [],
+    )?;
+
+    // Champs personnalisés des bandes et des bâtiments (définis par l'utilisateur)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS champs_personnalises (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entite TEXT NOT NULL CHECK (entite IN ('bande', 'batiment')),
+            cle TEXT NOT NULL,
+            libelle TEXT NOT NULL,
+            type_valeur TEXT NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(entite, cle)
+        )",
+        [],
+    )?;
+
+    // Valeurs des champs personnalisés, pour une bande ou pour un bâtiment
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS valeurs_champs_personnalises (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            champ_id INTEGER NOT NULL,
+            bande_id INTEGER,
+            batiment_id INTEGER,
+            valeur TEXT NOT NULL,
+            FOREIGN KEY (champ_id) REFERENCES champs_personnalises(id) ON DELETE CASCADE,
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE,
+            FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
+            CHECK ((bande_id IS NULL) != (batiment_id IS NULL))
+        )",
+        [],
+    )?;
+
+    // Étiquettes des bandes
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nom TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            couleur TEXT,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Liaison entre les bandes et leurs étiquettes
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS bande_tags (
+            bande_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (bande_id, tag_id),
+            FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Filtres de liste enregistrés par chaque utilisateur (critères en JSON)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS filtres_enregistres (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            nom TEXT NOT NULL,
+            entite TEXT NOT NULL,
+            filtre TEXT NOT NULL,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            UNIQUE(user_id, entite, nom)
+        )",
+        [],
+    )?;
+
+    // Mise à niveau des bases créées par une version précédente
+    migrate_schema(conn)?;
+
+    // Création des index pour optimiser les performances
+    create_indexes(conn)?;
+
+    // Les colonnes lues par les repositories doivent exister
+    colonnes::verifier_colonnes(conn)?;
+
+    conn.pragma_update(None, "user_version", VERSION_SCHEMA)?;
+
+    Ok(())
+}
+
+/// Ajoute aux tables existantes les colonnes introduites après leur création
+/// 
+/// `CREATE TABLE IF NOT EXISTS` ne modifie pas une table déjà présente: chaque
+/// colonne ajoutée au schéma doit aussi être déclarée ici.
+/// 
+/// # Arguments
+/// * `conn` - La connexion à la base de données
+fn migrate_schema(conn: &Connection) -> AppResult<()> {
+    // Colonnes renommées (soins.unite_defaut devenue soins.unit)
+    colonnes::renommer_colonnes(conn)?;
+
+    // Métadonnées des livraisons d'aliment
+    add_column_if_missing(conn, "alimentation_history", "fournisseur", "TEXT")?;
+    add_column_if_missing(conn, "alimentation_history", "type_aliment", "TEXT")?;
+    add_column_if_missing(conn, "alimentation_history", "numero_lot", "TEXT")?;
+
+    // Prix de l'aliment et des poussins au moment de leur enregistrement
+    add_column_if_missing(conn, "alimentation_history", "prix_unitaire", "REAL")?;
+    add_column_if_missing(conn, "batiments", "prix_poussin", "REAL")?;
+
+    // Devise des prix enregistrés: les prix saisis avant le paramètre de devise
+    // l'ont été dans la devise par défaut
+    add_column_if_missing(conn, "historique_prix", "devise", "TEXT NOT NULL DEFAULT 'MAD'")?;
+    add_column_if_missing(conn, "alimentation_history", "devise", "TEXT")?;
+    add_column_if_missing(conn, "batiments", "devise_poussin", "TEXT")?;
+    conn.execute(
+        "UPDATE alimentation_history SET devise = 'MAD' WHERE prix_unitaire IS NOT NULL AND devise IS NULL",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE batiments SET devise_poussin = 'MAD' WHERE prix_poussin IS NOT NULL AND devise_poussin IS NULL",
+        [],
+    )?;
+
+    // TVA des achats (livraisons d'aliment et opérations de litière)
+    for table in ["alimentation_history", "litieres"] {
+        add_column_if_missing(conn, table, "taux_tva", "REAL")?;
+        add_column_if_missing(conn, table, "montant_tva", "REAL")?;
+    }
+
+    // Heure de saisie des livraisons, pour détecter les doubles saisies
+    add_column_if_missing(conn, "alimentation_history", "saisi_le", "DATETIME")?;
+
+    // Sujets éliminés volontairement (tri, réforme), distincts des décès
+    add_column_if_missing(conn, "suivi_quotidien", "elimines_par_jour", "INTEGER")?;
+
+    // Poids signalés par la normalisation des poids hebdomadaires
+    add_column_if_missing(conn, "semaines", "poids_a_verifier", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Quantité structurée (valeur et unité normalisée) des soins administrés
+    add_column_if_missing(conn, "suivi_soins", "quantite_valeur", "REAL")?;
+    add_column_if_missing(conn, "suivi_soins", "quantite_unite", "TEXT")?;
+
+    // Catégorie et délai d'attente des soins
+    add_column_if_missing(conn, "soins", "categorie", "TEXT")?;
+    add_column_if_missing(conn, "soins", "delai_attente_jours", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Prix unitaire des soins (coût des traitements)
+    add_column_if_missing(conn, "soins", "prix_unitaire", "REAL")?;
+
+    // Rôle des utilisateurs; à défaut d'administrateur, le plus ancien compte le devient
+    add_column_if_missing(conn, "users", "role", "TEXT NOT NULL DEFAULT 'technicien'")?;
+    conn.execute(
+        "UPDATE users SET role = 'admin'
+         WHERE id = (SELECT MIN(id) FROM users)
+           AND NOT EXISTS (SELECT 1 FROM users WHERE role = 'admin')",
+        [],
+    )?;
+
+    // Mot de passe temporaire à changer après une réinitialisation par un administrateur
+    add_column_if_missing(conn, "users", "must_change_password", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Statut des bandes (clôture et réouverture)
+    add_column_if_missing(conn, "bandes", "statut", "TEXT NOT NULL DEFAULT 'active'")?;
+    add_column_if_missing(conn, "bandes", "date_cloture", "DATE")?;
+
+    // Coordonnées des fermes (plan du site)
+    add_column_if_missing(conn, "fermes", "latitude", "REAL")?;
+    add_column_if_missing(conn, "fermes", "longitude", "REAL")?;
+
+    // Numéro affiché des bandes, généré selon le format paramétré
+    // (les bandes existantes affichent leur numéro séquentiel)
+    add_column_if_missing(conn, "bandes", "numero_affiche", "TEXT")?;
+
+    // Noms comparés sans casse ni accents (unicité par index sur nom_normalise)
+    for table in noms::TABLES_NOM_NORMALISE {
+        add_column_if_missing(conn, table, "nom_normalise", "TEXT")?;
+    }
+    noms::renseigner_noms_normalises(conn)?;
+
+    // Souche des bâtiments: l'ancienne colonne texte type_poussin est
+    // remplacée par la clé étrangère poussin_id
+    migrate_type_poussin(conn)?;
+
+    // Version des lignes modifiables (contrôle de concurrence optimiste)
+    for table in versions::TABLES_VERSIONNEES {
+        add_column_if_missing(conn, table, "version", "INTEGER NOT NULL DEFAULT 1")?;
+    }
+
+    // Dates et auteur des lignes (inconnus pour les lignes existantes: SQLite
+    // n'accepte pas CURRENT_TIMESTAMP comme valeur par défaut d'une colonne ajoutée)
+    for table in versions::TABLES_TRACEES {
+        add_column_if_missing(conn, table, "created_at", "DATETIME")?;
+        add_column_if_missing(conn, table, "updated_at", "DATETIME")?;
+        add_column_if_missing(conn, table, "created_by", "INTEGER REFERENCES users(id) ON DELETE SET NULL")?;
+    }
+
+    // Alertes de la ferme elle-même (entretien des équipements), sans bande
+    rendre_colonne_facultative(conn, "alertes", "bande_id", "INTEGER")?;
+
+    // Politique de suppression unifiée (liaisons bâtiment-maladie supprimées avec la maladie)
+    cascades::migrer_politiques(conn)?;
+    versions::creer_declencheurs_version(conn)?;
+
+    // Reprise du soin unique de suivi_quotidien dans suivi_soins; les colonnes
+    // sont vidées ensuite pour que la reprise ne soit faite qu'une fois
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT INTO suivi_soins (suivi_id, soin_id, quantite)
+         SELECT id, soins_id, soins_quantite FROM suivi_quotidien
+         WHERE soins_id IS NOT NULL OR soins_quantite IS NOT NULL",
+        [],
+    )?;
+    tx.execute(
+        "UPDATE suivi_quotidien SET soins_id = NULL, soins_quantite = NULL
+         WHERE soins_id IS NOT NULL OR soins_quantite IS NOT NULL",
+        [],
+    )?;
+    tx.commit()?;
+
+    // Quantités structurées des soins saisis avant leur introduction; une
+    // saisie sans nombre reste à NULL et est relue au démarrage suivant
+    crate::repositories::structurer_quantites_soins(
+        conn,
+        "ss.quantite_valeur IS NULL AND ss.quantite IS NOT NULL",
+        &[],
+    )?;
+
+    Ok(())
+}
+
+/// Ajoute une colonne à une table si elle n'existe pas encore
+/// 
+/// # Arguments
+/// * `conn` - La connexion à la base de données
+/// * `table` - Le nom de la table
+/// * `column` - Le nom de la colonne
+/// * `definition` - Le type et les contraintes de la colonne (ex: `TEXT NOT NULL DEFAULT ''`)
+pub fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> AppResult<()> {
+    let exists: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        [table, column],
+        |row| row.get(0),
+    )?;
+
+    if exists == 0 {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+
+    Ok(())
+}
+
+/// Rend facultative une colonne déclarée `NOT NULL` par une version précédente
+/// 
+/// SQLite ne permet pas de modifier la contrainte d'une colonne: la table est
+/// recréée avec la définition corrigée (voir `cascades::reconstruire_table`).
+/// 
+/// # Arguments
+/// * `conn` - La connexion à la base de données
+/// * `table` - Le nom de la table
+/// * `colonne` - Le nom de la colonne
+/// * `type_colonne` - Le type déclaré de la colonne (ex: `INTEGER`)
+fn rendre_colonne_facultative(conn: &Connection, table: &str, colonne: &str, type_colonne: &str) -> AppResult<()> {
+    let obligatoire: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2 AND \"notnull\" = 1)",
+        [table, colonne],
+        |row| row.get(0),
+    )?;
+    if !obligatoire {
+        return Ok(());
+    }
+
+    let definition: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+    let ancienne = format!("{} {} NOT NULL", colonne, type_colonne);
+    if !definition.contains(&ancienne) {
+        return Ok(());
+    }
+    let corrigee = definition.replacen(&ancienne, &format!("{} {}", colonne, type_colonne), 1);
+    cascades::reconstruire_table(conn, table, &corrigee)
+}
+
+/// Remplace la colonne texte `batiments.type_poussin` par la clé `poussin_id`
+/// 
+/// Chaque souche saisie est rattachée au poussin de même nom (sans tenir
+/// compte de la casse ni des accents), créé s'il n'existe pas encore; une
+/// souche vide est rattachée au poussin "Inconnu".
+/// 
+/// # Arguments
+/// * `conn` - La connexion à la base de données
+fn migrate_type_poussin(conn: &Connection) -> AppResult<()> {
+    let legacy: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('batiments') WHERE name = 'type_poussin'",
+        [],
+        |row| row.get(0),
+    )?;
+    if legacy == 0 {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    add_column_if_missing(&tx, "batiments", "poussin_id", "INTEGER REFERENCES poussins(id) ON DELETE RESTRICT")?;
+
+    let souches: Vec<String> = tx
+        .prepare(
+            "SELECT DISTINCT COALESCE(NULLIF(TRIM(type_poussin), ''), 'Inconnu') FROM batiments
+             WHERE poussin_id IS NULL",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for souche in souches {
+        let nom_normalise = noms::normaliser_nom(&souche);
+        let existant: Option<i64> = tx
+            .query_row("SELECT id FROM poussins WHERE nom_normalise = ?1", [&nom_normalise], |row| row.get(0))
+            .optional()?;
+        let poussin_id = match existant {
+            Some(id) => id,
+            None => {
+                tx.execute("INSERT INTO poussins (nom, nom_normalise) VALUES (?1, ?2)", [&souche, &nom_normalise])?;
+                tx.last_insert_rowid()
+            }
+        };
+        tx.execute(
+            "UPDATE batiments SET poussin_id = ?1
+             WHERE poussin_id IS NULL AND COALESCE(NULLIF(TRIM(type_poussin), ''), 'Inconnu') = ?2",
+            rusqlite::params![poussin_id, souche],
         )?;
+    }
+
+    tx.execute("ALTER TABLE batiments DROP COLUMN type_poussin", [])?;
+    tx.commit()?;
+    Ok(())
+}
 
-        // Création de la table suivi_quotidien
+/// Crée les index de performance pour les requêtes fréquentes
+/// 
+/// # Arguments
+/// * `conn` - La connexion à la base de données
+fn create_indexes(conn: &Connection) -> AppResult<()> {
+    // Unicité des noms sans tenir compte de la casse ni des accents
+    for table in noms::TABLES_NOM_NORMALISE {
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS suivi_quotidien (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                semaine_id INTEGER NOT NULL,
-                age INTEGER NOT NULL CHECK (age > 0),
-                deces_par_jour INTEGER,
-                alimentation_par_jour REAL,
-                soins_id INTEGER,
-                soins_quantite TEXT,
-                analyses TEXT,
-                remarques TEXT,
-                FOREIGN KEY (semaine_id) REFERENCES semaines(id) ON DELETE CASCADE,
-                FOREIGN KEY (soins_id) REFERENCES soins(id) ON DELETE SET NULL,
-                UNIQUE(semaine_id, age)
-            )",
+            &format!("CREATE UNIQUE INDEX IF NOT EXISTS idx_{0}_nom_normalise ON {0}(nom_normalise)", table),
             [],
         )?;
+    }
 
-        // Création de la table alimentation_history
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS alimentation_history (
+    // Index pour les recherches d'utilisateurs par nom d'utilisateur
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_users_username ON users(username)",
+        [],
+    )?;
+
+    // Index pour les recherches de bandes par ferme
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bandes_ferme_id ON bandes(ferme_id)",
+        [],
+    )?;
+
+    // Index pour les recherches de bandes par date
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bandes_date_entree ON bandes(date_entree)",
+        [],
+    )?;
+
+    // Index pour le journal d'audit d'une entité
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_audit_log_entite ON audit_log(entite, entite_id)",
+        [],
+    )?;
+
+    // Index pour l'activité des utilisateurs (connexions et saisies)
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_audit_log_user ON audit_log(user_id, action, created_at)",
+        [],
+    )?;
+
+    // Index pour les recherches de batiments par bande
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_batiments_bande_id ON batiments(bande_id)",
+        [],
+    )?;
+
+    // Index pour les recherches de batiments par personnel
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_batiments_personnel_id ON batiments(personnel_id)",
+        [],
+    )?;
+
+    // Index pour les recherches de batiments par poussin
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_batiments_poussin_id ON batiments(poussin_id)",
+        [],
+    )?;
+
+    // Index pour les recherches de semaines par batiment
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_semaines_batiment_id ON semaines(batiment_id)",
+        [],
+    )?;
+
+    // Index pour les recherches de suivi quotidien par semaine
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_suivi_quotidien_semaine_id ON suivi_quotidien(semaine_id)",
+        [],
+    )?;
+
+    // Index pour les recherches de suivi quotidien par âge
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_suivi_quotidien_age ON suivi_quotidien(age)",
+        [],
+    )?;
+
+    // Index pour les recherches de soins
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_suivi_quotidien_soins_id ON suivi_quotidien(soins_id)",
+        [],
+    )?;
+
+    // Index pour les recherches d'alimentation par bande
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_alimentation_history_bande_id ON alimentation_history(bande_id)",
+        [],
+    )?;
+
+    // Index pour les recherches d'alimentation par date de création
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_alimentation_history_created_at ON alimentation_history(created_at)",
+        [],
+    )?;
+
+    // Index composite pour les recherches par bande et date de création
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_alimentation_history_bande_created ON alimentation_history(bande_id, created_at)",
+        [],
+    )?;
+
+    // Index pour les soins d'un suivi quotidien
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_suivi_soins_suivi_id ON suivi_soins(suivi_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_suivi_soins_soin_id ON suivi_soins(soin_id)",
+        [],
+    )?;
+
+    // Index pour le programme d'alimentation d'un type de poussin
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_phases_alimentation_poussin ON phases_alimentation(poussin_id, jour_debut)",
+        [],
+    )?;
+
+    // Index pour le prix en vigueur d'un article
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_historique_prix_article ON historique_prix(article, poussin_id, date_debut)",
+        [],
+    )?;
+
+    // Index pour le plan de soins d'un type de poussin
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_plan_soins_poussin ON plan_soins(poussin_id, jour_debut)",
+        [],
+    )?;
+
+    // Indexes pour la table de liaison batiment_maladies
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_batiment_maladies_batiment_id ON batiment_maladies(batiment_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_batiment_maladies_maladie_id ON batiment_maladies(maladie_id)",
+        [],
+    )?;
+
+    // Index pour la chronologie des notes de bâtiment
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_notes_batiment_batiment_id ON notes_batiment(batiment_id, created_at)",
+        [],
+    )?;
+
+    // Index pour les opérations de litière d'un bâtiment
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_litieres_batiment_id ON litieres(batiment_id, date_operation)",
+        [],
+    )?;
+
+    // Une alerte n'est inscrite qu'une fois dans l'historique; une alerte de
+    // ferme n'a ni bande ni bâtiment (l'ancien index ne portait que sur la bande)
+    conn.execute("DROP INDEX IF EXISTS idx_alertes_cle", [])?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_alertes_identite
+         ON alertes(type_alerte, ferme_id, COALESCE(bande_id, 0), COALESCE(batiment_id, 0), date_alerte)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_alertes_ferme ON alertes(ferme_id, detectee_le)",
+        [],
+    )?;
+
+    // Index pour les messages non lus d'un utilisateur ou d'un bâtiment
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_messages_destinataire ON messages(destinataire_id, lu)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_messages_batiment ON messages(batiment_id, lu)",
+        [],
+    )?;
+
+    // Une seule valeur par champ et par bande ou bâtiment
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_valeurs_champs_bande ON valeurs_champs_personnalises(bande_id, champ_id)
+         WHERE bande_id IS NOT NULL",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_valeurs_champs_batiment ON valeurs_champs_personnalises(batiment_id, champ_id)
+         WHERE batiment_id IS NOT NULL",
+        [],
+    )?;
+
+    // Index pour filtrer les bandes par étiquette
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bande_tags_tag ON bande_tags(tag_id)",
+        [],
+    )?;
+
+    // Index pour les vides sanitaires d'un bâtiment physique
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_vides_sanitaires_batiment ON vides_sanitaires(ferme_id, numero_batiment, date_debut)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_immobilisations_ferme_id ON immobilisations(ferme_id, date_achat)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_equipements_ferme_id ON equipements(ferme_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_entretiens_equipement ON entretiens_equipement(equipement_id, date_entretien)",
+        [],
+    )?;
+
+    // Indexes pour la table analyses
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_analyses_batiment_id ON analyses(batiment_id, date_prelevement)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_analyses_maladie_id ON analyses(maladie_id)",
+        [],
+    )?;
+
+    // Index pour la recherche des événements à envoyer au webhook
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_outbox_envoi ON webhook_outbox(envoye_le, prochaine_tentative)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_upgrade_adds_missing_columns_to_existing_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        // Table telle que créée par une version précédente
+        conn.execute_batch(
+            "CREATE TABLE alimentation_history (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 bande_id INTEGER NOT NULL,
                 quantite REAL NOT NULL,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE
-            )",
-            [],
-        )?;
-
-        // Création de la table maladies
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS maladies (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                nom TEXT NOT NULL UNIQUE,
                 created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+            );
+            INSERT INTO alimentation_history (bande_id, quantite) VALUES (1, 500.0);",
+        )
+        .unwrap();
 
-        // Table de liaison batiment <-> maladies (plusieurs maladies par bâtiment)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS batiment_maladies (
+        create_schema(&conn).unwrap();
+        // Une seconde initialisation ne doit rien modifier
+        create_schema(&conn).unwrap();
+
+        let fournisseur: Option<String> = conn
+            .query_row("SELECT fournisseur FROM alimentation_history WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fournisseur, None);
+
+        let columns: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('alimentation_history')
+                 WHERE name IN ('fournisseur', 'type_aliment', 'numero_lot')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(columns, 3);
+    }
+
+    #[test]
+    fn schema_upgrade_aligns_foreign_key_delete_policies() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        create_schema(&conn).unwrap();
+
+        // Liaison telle que créée par une version précédente (RESTRICT sur la maladie)
+        conn.execute_batch(
+            "DROP TABLE batiment_maladies;
+             CREATE TABLE batiment_maladies (
                 batiment_id INTEGER NOT NULL,
                 maladie_id INTEGER NOT NULL,
                 created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
                 PRIMARY KEY (batiment_id, maladie_id),
                 FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
                 FOREIGN KEY (maladie_id) REFERENCES maladies(id) ON DELETE RESTRICT
-            )",
-            [],
-        )?;
+             );
+             INSERT INTO fermes (nom, nbr_meuble) VALUES ('Ferme', 2);
+             INSERT INTO bandes (numero_bande, date_entree, ferme_id) VALUES (1, '2024-01-01', 1);
+             INSERT INTO poussins (nom) VALUES ('Cobb');
+             INSERT INTO personnel (nom, telephone) VALUES ('Ali', '0600000000');
+             INSERT INTO batiments (bande_id, numero_batiment, poussin_id, personnel_id, quantite) VALUES (1, '1', 1, 1, 1000);
+             INSERT INTO maladies (nom) VALUES ('Coccidiose');
+             INSERT INTO batiment_maladies (batiment_id, maladie_id) VALUES (1, 1);",
+        )
+        .unwrap();
 
-        // Création de la table poussins
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS poussins (
+        create_schema(&conn).unwrap();
+
+        for (table, colonne, politique) in cascades::POLITIQUES_SUPPRESSION {
+            assert_eq!(
+                cascades::politique_actuelle(&conn, table, colonne).unwrap().as_deref(),
+                Some(politique),
+                "{}.{}",
+                table,
+                colonne
+            );
+        }
+        // Les lignes sont conservées et la liaison suit désormais la maladie
+        assert_eq!(conn.query_row("SELECT COUNT(*) FROM batiment_maladies", [], |row| row.get::<_, i64>(0)).unwrap(), 1);
+        conn.execute("DELETE FROM maladies WHERE id = 1", []).unwrap();
+        assert_eq!(conn.query_row("SELECT COUNT(*) FROM batiment_maladies", [], |row| row.get::<_, i64>(0)).unwrap(), 0);
+    }
+
+    #[test]
+    fn schema_upgrade_links_legacy_type_poussin_to_poussins() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE poussins (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 nom TEXT NOT NULL UNIQUE,
                 created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+             );
+             CREATE TABLE batiments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                bande_id INTEGER NOT NULL,
+                numero_batiment TEXT NOT NULL,
+                type_poussin TEXT NOT NULL,
+                personnel_id INTEGER NOT NULL,
+                quantite INTEGER NOT NULL
+             );
+             INSERT INTO poussins (nom) VALUES ('Cobb 500');
+             INSERT INTO batiments (bande_id, numero_batiment, type_poussin, personnel_id, quantite) VALUES
+                (1, '1', 'cobb 500', 1, 1000),
+                (1, '2', 'Ross', 1, 1000),
+                (1, '3', '', 1, 1000);",
+        )
+        .unwrap();
 
-        // Création des index pour optimiser les performances
-        self.create_indexes(&conn)?;
+        create_schema(&conn).unwrap();
 
-        Ok(())
+        let souches: Vec<(String, String)> = conn
+            .prepare("SELECT b.numero_batiment, p.nom FROM batiments b JOIN poussins p ON b.poussin_id = p.id ORDER BY b.id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            souches,
+            vec![
+                ("1".to_string(), "Cobb 500".to_string()),
+                ("2".to_string(), "Ross".to_string()),
+                ("3".to_string(), "Inconnu".to_string()),
+            ]
+        );
+        let legacy: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pragma_table_info('batiments') WHERE name = 'type_poussin'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(legacy, 0);
     }
 
-    /// Crée les index de performance pour les requêtes fréquentes
-    /// 
-    /// # Arguments
-    /// * `conn` - La connexion à la base de données
-    fn create_indexes(&self, conn: &Connection) -> AppResult<()> {
-        // Index pour les recherches d'utilisateurs par nom d'utilisateur
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_users_username ON users(username)",
-            [],
-        )?;
+    #[test]
+    fn schema_upgrade_renames_legacy_soin_unit_column() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE soins (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                nom TEXT NOT NULL UNIQUE,
+                unite_defaut TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+             );
+             INSERT INTO soins (nom, unite_defaut) VALUES ('Vitamine', 'ml');",
+        )
+        .unwrap();
 
-        // Index pour les recherches de bandes par ferme
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bandes_ferme_id ON bandes(ferme_id)",
-            [],
-        )?;
+        create_schema(&conn).unwrap();
 
-        // Index pour les recherches de bandes par date
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bandes_date_entree ON bandes(date_entree)",
-            [],
-        )?;
+        let unit: String = conn.query_row("SELECT unit FROM soins WHERE nom = 'Vitamine'", [], |row| row.get(0)).unwrap();
+        assert_eq!(unit, "ml");
+    }
 
-        // Index pour les recherches de batiments par bande
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_batiments_bande_id ON batiments(bande_id)",
-            [],
-        )?;
+    #[test]
+    fn schema_upgrade_lets_alerts_belong_to_a_farm_only() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
 
-        // Index pour les recherches de batiments par personnel
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_batiments_personnel_id ON batiments(personnel_id)",
-            [],
-        )?;
+        // Historique tel que créé par une version précédente (bande obligatoire)
+        conn.execute_batch(
+            "DROP TABLE alertes;
+             CREATE TABLE alertes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                type_alerte TEXT NOT NULL,
+                gravite TEXT NOT NULL,
+                ferme_id INTEGER NOT NULL,
+                bande_id INTEGER NOT NULL,
+                batiment_id INTEGER,
+                date_alerte DATE NOT NULL,
+                message TEXT NOT NULL,
+                detectee_le DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                acquittee_par INTEGER,
+                acquittee_le DATETIME,
+                FOREIGN KEY (bande_id) REFERENCES bandes(id) ON DELETE CASCADE,
+                FOREIGN KEY (batiment_id) REFERENCES batiments(id) ON DELETE CASCADE,
+                FOREIGN KEY (acquittee_par) REFERENCES users(id) ON DELETE SET NULL
+             );
+             CREATE UNIQUE INDEX idx_alertes_cle ON alertes(type_alerte, bande_id, COALESCE(batiment_id, 0), date_alerte);
+             INSERT INTO alertes (type_alerte, gravite, ferme_id, bande_id, date_alerte, message)
+                VALUES ('vide_sanitaire', 'warning', 1, 1, '2024-03-01', 'Vide sanitaire court');",
+        )
+        .unwrap();
 
-        // Index pour les recherches de batiments par poussin
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_batiments_poussin_id ON batiments(poussin_id)",
-            [],
-        )?;
+        create_schema(&conn).unwrap();
 
-        // Index pour les recherches de semaines par batiment
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_semaines_batiment_id ON semaines(batiment_id)",
-            [],
-        )?;
+        let obligatoire: bool = conn
+            .query_row("SELECT \"notnull\" FROM pragma_table_info('alertes') WHERE name = 'bande_id'", [], |row| row.get(0))
+            .unwrap();
+        assert!(!obligatoire);
+        assert_eq!(conn.query_row("SELECT COUNT(*) FROM alertes", [], |row| row.get::<_, i64>(0)).unwrap(), 1);
+        let ancien_index: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'idx_alertes_cle'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(ancien_index, 0);
 
-        // Index pour les recherches de suivi quotidien par semaine
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_suivi_quotidien_semaine_id ON suivi_quotidien(semaine_id)",
-            [],
-        )?;
+        // Une alerte de ferme n'est inscrite qu'une fois
+        let inserer = "INSERT OR IGNORE INTO alertes (type_alerte, gravite, ferme_id, date_alerte, message)
+                       VALUES ('entretien_equipement', 'warning', 1, '2024-03-01', 'Groupe')";
+        assert_eq!(conn.execute(inserer, []).unwrap(), 1);
+        assert_eq!(conn.execute(inserer, []).unwrap(), 0);
+    }
 
-        // Index pour les recherches de suivi quotidien par âge
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_suivi_quotidien_age ON suivi_quotidien(age)",
-            [],
-        )?;
+    #[test]
+    fn column_check_reports_columns_missing_from_models() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        conn.execute_batch("ALTER TABLE suivi_soins RENAME COLUMN unit TO unite;").unwrap();
 
-        // Index pour les recherches de soins
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_suivi_quotidien_soins_id ON suivi_quotidien(soins_id)",
-            [],
-        )?;
+        let erreur = colonnes::verifier_colonnes(&conn).unwrap_err().to_string();
+        assert!(erreur.contains("suivi_soins.unit"), "{}", erreur);
+    }
 
-        // Index pour les recherches d'alimentation par bande
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_alimentation_history_bande_id ON alimentation_history(bande_id)",
-            [],
-        )?;
+    #[test]
+    fn model_columns_match_the_created_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
 
-        // Index pour les recherches d'alimentation par date de création
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_alimentation_history_created_at ON alimentation_history(created_at)",
-            [],
-        )?;
+        let mut ecarts = Vec::new();
+        for (table, colonnes) in colonnes::COLONNES_MODELES {
+            let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)").unwrap();
+            let existantes: Vec<String> =
+                stmt.query_map([table], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+            for colonne in &existantes {
+                if !colonnes.contains(&colonne.as_str()) {
+                    ecarts.push(format!("{}.{} absente de COLONNES_MODELES", table, colonne));
+                }
+            }
+            for colonne in colonnes {
+                if !existantes.iter().any(|nom| nom == colonne) {
+                    ecarts.push(format!("{}.{} absente du schéma", table, colonne));
+                }
+            }
+        }
+        assert!(ecarts.is_empty(), "{:?}", ecarts);
+    }
 
-        // Index composite pour les recherches par bande et date de création
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_alimentation_history_bande_created ON alimentation_history(bande_id, created_at)",
-            [],
-        )?;
+    #[test]
+    fn schema_upgrade_moves_legacy_soins_to_suivi_soins() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
 
-        // Indexes pour la table de liaison batiment_maladies
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_batiment_maladies_batiment_id ON batiment_maladies(batiment_id)",
-            [],
-        )?;
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_batiment_maladies_maladie_id ON batiment_maladies(maladie_id)",
-            [],
-        )?;
+        // Suivi enregistré par une version précédente, avec le soin dans suivi_quotidien
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+            INSERT INTO soins (id, nom, unit) VALUES (1, 'Vitamine', 'ml');
+            INSERT INTO suivi_quotidien (id, semaine_id, age, soins_id, soins_quantite)
+            VALUES (1, 1, 1, 1, '250 ml');",
+        )
+        .unwrap();
 
-        Ok(())
+        create_schema(&conn).unwrap();
+        create_schema(&conn).unwrap();
+
+        let (soin_id, quantite): (i64, String) = conn
+            .query_row("SELECT soin_id, quantite FROM suivi_soins WHERE suivi_id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((soin_id, quantite.as_str()), (1, "250 ml"));
+
+        let legacy: Option<i64> = conn
+            .query_row("SELECT soins_id FROM suivi_quotidien WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(legacy, None);
     }
 
+    #[test]
+    fn schema_upgrade_normalizes_names_and_keeps_duplicates_out_of_unique_index() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        // Fermes créées par une version précédente, dont un doublon aux accents près
+        conn.execute_batch(
+            "CREATE TABLE fermes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                nom TEXT NOT NULL UNIQUE,
+                nbr_meuble INTEGER NOT NULL DEFAULT 0
+            );
+            INSERT INTO fermes (id, nom) VALUES (1, 'Ferme A'), (2, 'Férme  a'), (3, 'Ferme B');",
+        )
+        .unwrap();
+
+        create_schema(&conn).unwrap();
+        create_schema(&conn).unwrap();
+
+        let noms: Vec<Option<String>> = conn
+            .prepare("SELECT nom_normalise FROM fermes ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(noms, vec![Some("ferme a".to_string()), None, Some("ferme b".to_string())]);
+
+        assert!(nom_existe(&conn, "fermes", " FERME   b", None).unwrap());
+        assert!(!nom_existe(&conn, "fermes", "Ferme B", Some(3)).unwrap());
+        assert_eq!(normaliser_nom("Œuf  Çà  ÉTÉ"), "oeuf ca ete");
+        assert!(conn
+            .execute("INSERT INTO fermes (nom, nom_normalise) VALUES ('ferme b', 'ferme b')", [])
+            .is_err());
+    }
 }
@@ -0,0 +1,83 @@
+use crate::error::AppResult;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Tables dont le nom est unique sans tenir compte de la casse ni des accents
+pub const TABLES_NOM_NORMALISE: [&str; 5] = ["fermes", "personnel", "soins", "poussins", "maladies"];
+
+/// Forme de comparaison d'un nom: minuscules, sans accents, espaces réduits
+///
+/// "  Férme   A " et "ferme a" donnent tous deux "ferme a".
+pub fn normaliser_nom(nom: &str) -> String {
+    let mut normalise = String::with_capacity(nom.len());
+    for mot in nom.split_whitespace() {
+        if !normalise.is_empty() {
+            normalise.push(' ');
+        }
+        for c in mot.chars().flat_map(char::to_lowercase) {
+            match c {
+                'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => normalise.push('a'),
+                'ç' => normalise.push('c'),
+                'è' | 'é' | 'ê' | 'ë' => normalise.push('e'),
+                'ì' | 'í' | 'î' | 'ï' => normalise.push('i'),
+                'ñ' => normalise.push('n'),
+                'ò' | 'ó' | 'ô' | 'õ' | 'ö' => normalise.push('o'),
+                'ù' | 'ú' | 'û' | 'ü' => normalise.push('u'),
+                'ý' | 'ÿ' => normalise.push('y'),
+                'æ' => normalise.push_str("ae"),
+                'œ' => normalise.push_str("oe"),
+                _ => normalise.push(c),
+            }
+        }
+    }
+    normalise
+}
+
+/// Vérifie si un nom équivalent existe déjà dans la table
+///
+/// # Arguments
+/// * `table` - Une des tables de `TABLES_NOM_NORMALISE`
+/// * `nom` - Le nom saisi
+/// * `exclude_id` - L'ID de la ligne modifiée, ignorée lors d'une mise à jour
+pub fn nom_existe(conn: &Connection, table: &str, nom: &str, exclude_id: Option<i64>) -> AppResult<bool> {
+    let existing: Option<i64> = conn
+        .query_row(
+            &format!(
+                "SELECT id FROM {} WHERE nom_normalise = ?1 AND (?2 IS NULL OR id != ?2) LIMIT 1",
+                table
+            ),
+            params![normaliser_nom(nom), exclude_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(existing.is_some())
+}
+
+/// Renseigne `nom_normalise` pour les lignes qui n'en ont pas encore
+///
+/// Lorsque des doublons existent déjà, seule la ligne la plus ancienne reçoit
+/// le nom normalisé: les suivantes restent à `NULL` (non concernées par
+/// l'index unique) jusqu'à leur fusion ou leur renommage.
+pub fn renseigner_noms_normalises(conn: &Connection) -> AppResult<()> {
+    for table in TABLES_NOM_NORMALISE {
+        let lignes = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, nom FROM {} WHERE nom_normalise IS NULL ORDER BY id",
+                table
+            ))?;
+            let lignes = stmt
+                .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            lignes
+        };
+
+        for (id, nom) in lignes {
+            if !nom_existe(conn, table, &nom, Some(id))? {
+                conn.execute(
+                    &format!("UPDATE {} SET nom_normalise = ?1 WHERE id = ?2", table),
+                    params![normaliser_nom(&nom), id],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
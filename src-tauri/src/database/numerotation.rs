@@ -0,0 +1,128 @@
+use crate::database::normaliser_nom;
+use crate::error::{AppError, AppResult};
+
+/// Clé du paramètre contenant le format des numéros de bande
+pub const PARAMETRE_FORMAT_NUMERO_BANDE: &str = "format_numero_bande";
+
+/// Format par défaut: le numéro séquentiel seul, comme avant l'introduction des formats
+pub const FORMAT_NUMERO_BANDE_DEFAUT: &str = "{seq}";
+
+/// Variables reconnues dans un format de numéro de bande
+///
+/// `{seq:N}` complète le numéro séquentiel avec des zéros sur N chiffres (1 à 9).
+pub const VARIABLES_NUMERO_BANDE: [&str; 4] = ["{year}", "{ferme_code}", "{seq}", "{seq:N}"];
+
+/// Code court d'une ferme utilisé par `{ferme_code}`
+///
+/// Initiales des mots du nom (4 au plus) ou, pour un nom d'un seul mot, ses
+/// trois premiers caractères; en majuscules et sans accents:
+/// "Ferme Atlas" donne "FA", "Benslimane" donne "BEN".
+pub fn code_ferme(nom: &str) -> String {
+    let normalise = normaliser_nom(nom);
+    let mots: Vec<&str> = normalise
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|mot| !mot.is_empty())
+        .collect();
+
+    let code: String = match mots.as_slice() {
+        [] => String::new(),
+        [mot] => mot.chars().take(3).collect(),
+        _ => mots.iter().take(4).filter_map(|mot| mot.chars().next()).collect(),
+    };
+    code.to_uppercase()
+}
+
+/// Génère le numéro affiché d'une bande à partir d'un format
+///
+/// # Arguments
+/// * `format` - Le format, ex: `{year}-{ferme_code}-{seq:03}`
+/// * `annee` - L'année d'entrée de la bande
+/// * `ferme_code` - Le code de la ferme (voir `code_ferme`)
+/// * `seq` - Le numéro séquentiel de la bande dans la ferme (`numero_bande`)
+pub fn formater_numero_bande(format: &str, annee: i32, ferme_code: &str, seq: i32) -> AppResult<String> {
+    let mut numero = String::with_capacity(format.len() + 8);
+    let mut reste = format;
+
+    while let Some(debut) = reste.find('{') {
+        numero.push_str(&reste[..debut]);
+        let fin = reste[debut..].find('}').ok_or_else(|| format_invalide("accolade non fermée"))? + debut;
+        let variable = &reste[debut + 1..fin];
+
+        match variable {
+            "year" => numero.push_str(&annee.to_string()),
+            "ferme_code" => numero.push_str(ferme_code),
+            "seq" => numero.push_str(&seq.to_string()),
+            _ => {
+                let largeur = variable
+                    .strip_prefix("seq:")
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|n| (1..=9).contains(n))
+                    .ok_or_else(|| format_invalide(&format!("variable inconnue {{{}}}", variable)))?;
+                numero.push_str(&format!("{:0largeur$}", seq, largeur = largeur));
+            }
+        }
+        reste = &reste[fin + 1..];
+    }
+
+    if reste.contains('}') {
+        return Err(format_invalide("accolade fermante sans ouverture"));
+    }
+    numero.push_str(reste);
+    Ok(numero)
+}
+
+/// Vérifie qu'un format de numéro de bande est utilisable
+///
+/// Le format doit contenir `{seq}` (ou `{seq:N}`) pour que deux bandes d'une
+/// même ferme ne reçoivent jamais le même numéro.
+pub fn valider_format_numero_bande(format: &str) -> AppResult<()> {
+    if format.trim().is_empty() {
+        return Err(format_invalide("le format ne peut pas être vide"));
+    }
+    if format.len() > 100 {
+        return Err(format_invalide("le format ne peut pas dépasser 100 caractères"));
+    }
+    formater_numero_bande(format, 2024, "FA", 1)?;
+    if !format.contains("{seq}") && !format.contains("{seq:") {
+        return Err(format_invalide("le format doit contenir {seq}"));
+    }
+    Ok(())
+}
+
+fn format_invalide(detail: &str) -> AppError {
+    AppError::validation_error(
+        "format",
+        &format!(
+            "Format de numéro de bande invalide: {}. Variables disponibles: {}",
+            detail,
+            VARIABLES_NUMERO_BANDE.join(", ")
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formater_numero_bande_replaces_variables() {
+        assert_eq!(formater_numero_bande("{seq}", 2024, "FA", 7).unwrap(), "7");
+        assert_eq!(formater_numero_bande("{year}-{ferme_code}-{seq:03}", 2024, "FA", 7).unwrap(), "2024-FA-007");
+        assert_eq!(formater_numero_bande("B{seq:2}/{year}", 2025, "BEN", 12).unwrap(), "B12/2025");
+    }
+
+    #[test]
+    fn invalid_formats_are_rejected() {
+        for format in ["", "{year}-{ferme_code}", "{seq", "{seq}}", "{lot}-{seq}", "{seq:0}", "{seq:x}"] {
+            assert!(valider_format_numero_bande(format).is_err(), "{} devrait être refusé", format);
+        }
+        assert!(valider_format_numero_bande("{year}-{seq:04}").is_ok());
+    }
+
+    #[test]
+    fn code_ferme_uses_initials_or_first_letters() {
+        assert_eq!(code_ferme("Ferme Atlas"), "FA");
+        assert_eq!(code_ferme("Benslimane"), "BEN");
+        assert_eq!(code_ferme("Élevage du Sud-Est"), "EDSE");
+    }
+}
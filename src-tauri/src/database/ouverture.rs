@@ -0,0 +1,66 @@
+use super::{DatabaseConfig, DatabaseManager, Storage};
+use crate::error::{AppError, AppResult};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Ouverture de la base principale au démarrage de l'application
+///
+/// Une base chiffrée dont la phrase secrète est absente (ou incorrecte) dans
+/// `GEEMA_DB_PASSPHRASE` n'empêche pas l'application de démarrer: elle reste
+/// verrouillée, et l'interface demande la phrase secrète (`unlock_database`).
+pub struct OuvertureBase {
+    chemin: PathBuf,
+    config: DatabaseConfig,
+    /// Vrai une fois la base ouverte; sérialise aussi les tentatives d'ouverture
+    ouverte: Mutex<bool>,
+}
+
+impl OuvertureBase {
+    /// Prépare l'ouverture d'une base
+    ///
+    /// # Arguments
+    /// * `chemin` - Le chemin du fichier de la base principale
+    /// * `config` - La configuration de l'accès à la base (phrase secrète de l'environnement comprise)
+    pub fn new(chemin: impl AsRef<Path>, config: DatabaseConfig) -> Self {
+        Self { chemin: chemin.as_ref().to_path_buf(), config, ouverte: Mutex::new(false) }
+    }
+
+    /// Ouvre la base avec la configuration et met son schéma à jour
+    ///
+    /// # Returns
+    /// Le gestionnaire de la base, `AppError::Locked` ou `AppError::InvalidPassphrase`
+    /// si la base chiffrée attend sa phrase secrète
+    pub fn ouvrir(&self) -> AppResult<Arc<DatabaseManager>> {
+        self.ouvrir_avec(self.config.clone())
+    }
+
+    /// Ouvre la base verrouillée avec la phrase secrète saisie
+    ///
+    /// # Arguments
+    /// * `passphrase` - La phrase secrète de la base
+    ///
+    /// # Returns
+    /// Le gestionnaire de la base, ou `AppError::InvalidPassphrase` si la phrase est refusée
+    pub fn deverrouiller(&self, passphrase: &str) -> AppResult<Arc<DatabaseManager>> {
+        if passphrase.is_empty() {
+            return Err(AppError::validation_error("passphrase", "La phrase secrète ne peut pas être vide"));
+        }
+        self.ouvrir_avec(DatabaseConfig { passphrase: Some(passphrase.to_string()), ..self.config.clone() })
+    }
+
+    /// Indique si la base attend encore sa phrase secrète
+    pub fn est_verrouillee(&self) -> bool {
+        !*self.ouverte.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn ouvrir_avec(&self, config: DatabaseConfig) -> AppResult<Arc<DatabaseManager>> {
+        let mut ouverte = self.ouverte.lock().unwrap_or_else(PoisonError::into_inner);
+        if *ouverte {
+            return Err(AppError::business_logic("La base de données est déjà ouverte"));
+        }
+        let db_manager = DatabaseManager::with_config(&self.chemin, config)?;
+        db_manager.initialize_schema()?;
+        *ouverte = true;
+        Ok(Arc::new(db_manager))
+    }
+}
@@ -0,0 +1,120 @@
+use crate::database::archive;
+use crate::error::{AppError, AppResult};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Profil de la base ouverte au démarrage de l'application
+pub const PROFIL_PRINCIPAL: &str = "principal";
+
+/// Longueur maximale d'un nom de profil
+const LONGUEUR_PROFIL_MAX: usize = 32;
+
+/// Base de données d'un profil
+///
+/// Un profil autre que `principal` est une base séparée (dossier d'une autre
+/// exploitation, bac à sable de formation) placée à côté de la base principale.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfilBase {
+    pub nom: String,
+    pub chemin: String,
+    pub principal: bool,
+    /// Vrai pour la base actuellement ouverte
+    pub actif: bool,
+}
+
+impl ProfilBase {
+    pub(crate) fn new(nom: &str, chemin: &Path, actif: bool) -> Self {
+        ProfilBase {
+            nom: nom.to_string(),
+            chemin: chemin.to_string_lossy().into_owned(),
+            principal: nom == PROFIL_PRINCIPAL,
+            actif,
+        }
+    }
+}
+
+/// Chemin de la base d'un profil
+///
+/// Le profil `demo` est ouvert dans `demo.db`, dans le dossier de la base
+/// principale. Le nom est limité aux minuscules, chiffres, `-` et `_` pour
+/// ne jamais désigner un fichier hors de ce dossier.
+///
+/// # Arguments
+/// * `chemin_principal` - Le chemin de la base principale
+/// * `profil` - Le nom du profil
+pub fn chemin_profil(chemin_principal: &Path, profil: &str) -> AppResult<PathBuf> {
+    if profil == PROFIL_PRINCIPAL {
+        return Ok(chemin_principal.to_path_buf());
+    }
+    let valide = !profil.is_empty()
+        && profil.len() <= LONGUEUR_PROFIL_MAX
+        && profil.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valide {
+        return Err(AppError::validation_error(
+            "profile_name",
+            &format!(
+                "Le nom du profil doit compter 1 à {} caractères parmi les minuscules, chiffres, - et _",
+                LONGUEUR_PROFIL_MAX
+            ),
+        ));
+    }
+
+    let chemin = chemin_principal.with_file_name(format!("{}.db", profil));
+    if chemin == chemin_principal {
+        return Err(AppError::validation_error(
+            "profile_name",
+            &format!("Ce nom désigne la base principale: utiliser le profil \"{}\"", PROFIL_PRINCIPAL),
+        ));
+    }
+    // Chaque base a son archive à côté d'elle (`demo-archive.db` pour `demo.db`)
+    if profil.ends_with("-archive") {
+        return Err(AppError::validation_error("profile_name", "Ce nom est réservé aux bases d'archive"));
+    }
+    Ok(chemin)
+}
+
+/// Liste les profils dont la base existe dans le dossier de la base principale
+///
+/// Le profil `principal` vient en premier, les autres par ordre alphabétique.
+///
+/// # Arguments
+/// * `chemin_principal` - Le chemin de la base principale
+/// * `profil_actif` - Le nom du profil actuellement ouvert
+pub fn lister_profils(chemin_principal: &Path, profil_actif: &str) -> AppResult<Vec<ProfilBase>> {
+    let mut profils = vec![ProfilBase::new(PROFIL_PRINCIPAL, chemin_principal, profil_actif == PROFIL_PRINCIPAL)];
+    let Some(dossier) = chemin_principal.parent().filter(|dossier| dossier.is_dir()) else {
+        return Ok(profils);
+    };
+
+    let mut autres = Vec::new();
+    for entree in std::fs::read_dir(dossier)? {
+        let chemin = entree?.path();
+        let Some(nom) = chemin.file_name().and_then(|nom| nom.to_str()).and_then(|nom| nom.strip_suffix(".db")) else {
+            continue;
+        };
+        // Les fichiers qui ne correspondent à aucun nom de profil valide sont ignorés
+        if chemin_profil(chemin_principal, nom).is_ok_and(|attendu| attendu == chemin) && chemin.is_file() {
+            autres.push(ProfilBase::new(nom, &chemin, profil_actif == nom));
+        }
+    }
+    autres.sort_by(|a, b| a.nom.cmp(&b.nom));
+    profils.extend(autres);
+    Ok(profils)
+}
+
+/// Supprime la base d'un profil avec son archive et leurs journaux SQLite
+///
+/// # Arguments
+/// * `chemin` - Le chemin de la base du profil
+pub fn supprimer_fichiers(chemin: &Path) -> AppResult<()> {
+    for base in [chemin.to_path_buf(), archive::chemin_archive(chemin)] {
+        for suffixe in ["", "-wal", "-shm"] {
+            let fichier = PathBuf::from(format!("{}{}", base.to_string_lossy(), suffixe));
+            match std::fs::remove_file(&fichier) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
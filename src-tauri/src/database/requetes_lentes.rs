@@ -0,0 +1,129 @@
+use chrono::Local;
+use rusqlite::Connection;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+/// Nom du journal de diagnostic, à côté de la base principale
+pub const NOM_JOURNAL_DIAGNOSTIC: &str = "diagnostics.log";
+
+/// Au-delà de cette taille, le journal est renommé en `diagnostics.log.old`
+const TAILLE_JOURNAL_MAX: u64 = 1024 * 1024;
+
+/// Seuil en millisecondes, `u64::MAX` lorsque le journal est désactivé
+static SEUIL_MS: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Chemin du journal, sérialise aussi les écritures
+static JOURNAL: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Configure le journal des requêtes lentes
+///
+/// SQLite n'accepte qu'une fonction sans état pour mesurer les requêtes: la
+/// configuration est commune à toutes les connexions du processus, et la
+/// dernière base principale ouverte l'emporte.
+///
+/// # Arguments
+/// * `chemin` - Le fichier du journal de diagnostic
+/// * `seuil` - Durée à partir de laquelle une requête est consignée, `None` pour désactiver le journal
+pub(crate) fn configurer(chemin: &Path, seuil: Option<Duration>) {
+    *JOURNAL.lock().unwrap_or_else(PoisonError::into_inner) = Some(chemin.to_path_buf());
+    let seuil_ms = seuil.map_or(u64::MAX, |seuil| u64::try_from(seuil.as_millis()).unwrap_or(u64::MAX));
+    SEUIL_MS.store(seuil_ms, Ordering::SeqCst);
+}
+
+/// Mesure la durée de chaque requête exécutée sur une connexion
+pub(crate) fn suivre(conn: &mut Connection) {
+    conn.profile(Some(consigner));
+}
+
+/// Consigne une requête si elle a dépassé le seuil
+///
+/// Appelé par SQLite à la fin de chaque requête; les erreurs d'écriture du
+/// journal sont ignorées pour ne jamais faire échouer la requête.
+fn consigner(sql: &str, duree: Duration) {
+    let seuil_ms = SEUIL_MS.load(Ordering::Relaxed);
+    if seuil_ms == u64::MAX || duree.as_millis() < u128::from(seuil_ms) {
+        return;
+    }
+
+    let journal = JOURNAL.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(chemin) = journal.as_ref() else {
+        return;
+    };
+    if std::fs::metadata(chemin).is_ok_and(|fichier| fichier.len() > TAILLE_JOURNAL_MAX) {
+        let mut ancien = chemin.as_os_str().to_owned();
+        ancien.push(".old");
+        let _ = std::fs::rename(chemin, ancien);
+    }
+    if let Ok(mut fichier) = OpenOptions::new().create(true).append(true).open(chemin) {
+        let _ = writeln!(
+            fichier,
+            "{} requête lente ({} ms): {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            duree.as_millis(),
+            masquer_valeurs(sql)
+        );
+    }
+}
+
+/// Remplace les valeurs littérales d'une requête par `?`
+///
+/// Les paramètres liés n'apparaissent jamais dans le texte de la requête;
+/// les chaînes et nombres écrits directement dans le SQL sont masqués pour
+/// que le journal ne contienne aucune donnée de l'élevage. Les blancs sont
+/// réduits à une espace pour tenir sur une ligne.
+pub fn masquer_valeurs(sql: &str) -> String {
+    let mut masquee = String::with_capacity(sql.len());
+    let mut caracteres = sql.chars().peekable();
+    let mut precedent = ' ';
+    while let Some(c) = caracteres.next() {
+        if c == '\'' {
+            // Chaîne littérale, '' y représente une apostrophe
+            while let Some(suivant) = caracteres.next() {
+                if suivant == '\'' && caracteres.next_if_eq(&'\'').is_none() {
+                    break;
+                }
+            }
+            masquee.push('?');
+            precedent = '?';
+        } else if c.is_ascii_digit() && !suite_de_nom(precedent) {
+            while caracteres.next_if(|suivant| suivant.is_ascii_alphanumeric() || *suivant == '.').is_some() {}
+            masquee.push('?');
+            precedent = '?';
+        } else if c.is_whitespace() {
+            if !masquee.is_empty() && precedent != ' ' {
+                masquee.push(' ');
+            }
+            precedent = ' ';
+        } else {
+            masquee.push(c);
+            precedent = c;
+        }
+    }
+    masquee.trim_end().to_string()
+}
+
+/// Vrai si un chiffre suivant `precedent` fait partie d'un nom (`t1`, `?1`, `:age1`)
+fn suite_de_nom(precedent: char) -> bool {
+    precedent.is_alphanumeric() || matches!(precedent, '_' | '?' | '$' | ':' | '@')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masquer_valeurs_hides_literals_but_keeps_placeholders_and_identifiers() {
+        assert_eq!(
+            masquer_valeurs("SELECT id FROM bandes\n     WHERE ferme_id = ?1 AND notes = 'l''aube' LIMIT 50"),
+            "SELECT id FROM bandes WHERE ferme_id = ?1 AND notes = ? LIMIT ?"
+        );
+        assert_eq!(
+            masquer_valeurs("SELECT t1.poids_2 FROM semaines t1 WHERE t1.poids > 1.5e3 AND age = :age1"),
+            "SELECT t1.poids_2 FROM semaines t1 WHERE t1.poids > ? AND age = :age1"
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use crate::error::{AppError, AppResult};
+use rusqlite::{Connection, OptionalExtension};
+
+/// Tables dont les lignes portent une colonne `version` (contrôle de concurrence optimiste)
+///
+/// Chaque mise à jour d'une ligne incrémente sa version; une modification
+/// envoyée avec une version périmée est refusée par `AppError::Conflict`,
+/// transmis au frontend par `ErreurCommande` (`kind` = "conflict").
+///
+/// Le contrôle est facultatif: une modification envoyée avec `version: None`
+/// écrase la ligne quelle que soit sa version (la dernière écriture l'emporte).
+/// Les formulaires doivent toujours envoyer la version chargée; l'absence de
+/// version est réservée aux écrans antérieurs au contrôle et aux écritures
+/// d'un seul champ faites par l'application (poids d'une semaine...).
+pub const TABLES_VERSIONNEES: [&str; 6] = ["fermes", "bandes", "batiments", "semaines", "suivi_quotidien", "personnel"];
+
+/// Tables dont les lignes portent `created_at`, `updated_at` et `created_by`
+///
+/// Les requêtes d'insertion renseignent les trois colonnes; `updated_at` est
+/// ensuite tenu à jour avec la version.
+pub const TABLES_TRACEES: [&str; 4] = ["bandes", "batiments", "semaines", "suivi_quotidien"];
+
+/// Crée les déclencheurs qui incrémentent `version` à chaque mise à jour
+///
+/// Les requêtes qui vérifient la version l'incrémentent elles-mêmes; les
+/// autres écritures (saisie d'une cellule, clôture, fusion...) passent par le
+/// déclencheur, de sorte qu'un formulaire ouvert avant elles devient périmé.
+/// Pour les tables de `TABLES_TRACEES`, le déclencheur renseigne aussi `updated_at`.
+///
+/// Les déclencheurs sont recréés à chaque démarrage pour suivre leur définition.
+pub fn creer_declencheurs_version(conn: &Connection) -> AppResult<()> {
+    for table in TABLES_VERSIONNEES {
+        let horodatage = if TABLES_TRACEES.contains(&table) { ", updated_at = CURRENT_TIMESTAMP" } else { "" };
+        conn.execute_batch(&format!(
+            "DROP TRIGGER IF EXISTS {table}_version;
+             CREATE TRIGGER {table}_version AFTER UPDATE ON {table}
+             FOR EACH ROW WHEN NEW.version = OLD.version
+             BEGIN
+                 UPDATE {table} SET version = OLD.version + 1{horodatage} WHERE id = OLD.id;
+             END;"
+        ))?;
+    }
+    Ok(())
+}
+
+/// Erreur d'une mise à jour conditionnée par la version qui n'a modifié aucune ligne
+///
+/// # Arguments
+/// * `table` - Une des tables de `TABLES_VERSIONNEES`
+/// * `entity` - Le nom de l'entité dans le message d'erreur (ex: "Bande")
+/// * `id` - L'ID de la ligne
+/// * `version` - La version envoyée par le client
+///
+/// # Returns
+/// `AppError::Conflict` si la ligne existe dans une autre version, `AppError::NotFound` sinon
+pub fn erreur_mise_a_jour(conn: &Connection, table: &str, entity: &str, id: i64, version: Option<i64>) -> AppError {
+    let actuelle: rusqlite::Result<Option<i64>> = conn
+        .query_row(&format!("SELECT version FROM {} WHERE id = ?1", table), [id], |row| row.get(0))
+        .optional();
+
+    match (actuelle, version) {
+        (Ok(Some(actuelle)), Some(attendue)) if actuelle != attendue => {
+            AppError::conflict(entity, id, attendue, actuelle)
+        }
+        (Err(e), _) => AppError::from(e),
+        _ => AppError::not_found(entity, id),
+    }
+}
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use thiserror::Error;
 
 /// Erreurs personnalisées pour l'application de gestion de ferme
@@ -8,7 +9,7 @@ use thiserror::Error;
 pub enum AppError {
     /// Erreurs liées à la base de données SQLite
     #[error("Erreur de base de données: {0}")]
-    Database(#[from] rusqlite::Error),
+    Database(rusqlite::Error),
 
     /// Erreurs de sérialisation/désérialisation JSON
     #[error("Erreur de sérialisation: {0}")]
@@ -34,6 +35,30 @@ pub enum AppError {
     #[error("{message}")]
     BusinessLogic { message: String },
 
+    /// Modification refusée: l'entité a été modifiée depuis son chargement par le client
+    #[error("Conflit de modification: {entity} avec l'ID {id} a été modifié par un autre utilisateur (version {current}, version chargée {expected}). Rechargez les données avant d'enregistrer.")]
+    Conflict { entity: String, id: i64, expected: i64, current: i64 },
+
+    /// Création refusée: l'entité existe déjà (contrainte d'unicité), `id` est celui de l'entité existante
+    #[error("{message}")]
+    Duplicate { entity: String, id: i64, message: String },
+
+    /// Opération suspecte (double saisie...) à confirmer par l'utilisateur avant d'être renvoyée
+    #[error("Confirmation requise: {message}")]
+    ConfirmationRequired { field: String, message: String },
+
+    /// Modification refusée: l'application est en mode lecture seule
+    #[error("Mode lecture seule: les modifications sont désactivées sur ce poste")]
+    ReadOnly,
+
+    /// Base chiffrée ouverte sans phrase secrète: elle reste verrouillée jusqu'à sa saisie
+    #[error("Base de données verrouillée: saisissez la phrase secrète pour l'ouvrir")]
+    Locked,
+
+    /// Phrase secrète refusée par la base chiffrée
+    #[error("Phrase secrète incorrecte ou base de données illisible")]
+    InvalidPassphrase,
+
     /// Erreur d'E/O générique
     #[error("Erreur d'entrée/sortie: {0}")]
     Io(#[from] std::io::Error),
@@ -69,6 +94,18 @@ impl AppError {
         }
     }
 
+    /// Crée une erreur demandant une confirmation de l'utilisateur
+    /// 
+    /// # Arguments
+    /// * `field` - Le champ du formulaire à confirmer
+    /// * `message` - Le message présenté à l'utilisateur
+    pub fn confirmation_required(field: &str, message: &str) -> Self {
+        AppError::ConfirmationRequired {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+
     /// Crée une erreur de logique métier
     /// 
     /// # Arguments
@@ -79,6 +116,36 @@ impl AppError {
         }
     }
 
+    /// Crée une erreur de conflit de version
+    /// 
+    /// # Arguments
+    /// * `entity` - Le nom de l'entité (ex: "Bande")
+    /// * `id` - L'ID de l'entité
+    /// * `expected` - La version chargée par le client
+    /// * `current` - La version enregistrée
+    pub fn conflict(entity: &str, id: i64, expected: i64, current: i64) -> Self {
+        AppError::Conflict {
+            entity: entity.to_string(),
+            id,
+            expected,
+            current,
+        }
+    }
+
+    /// Crée une erreur de doublon
+    /// 
+    /// # Arguments
+    /// * `entity` - Le nom de l'entité (ex: "Semaine")
+    /// * `id` - L'ID de l'entité existante
+    /// * `message` - Le message d'erreur descriptif
+    pub fn duplicate(entity: &str, id: i64, message: &str) -> Self {
+        AppError::Duplicate {
+            entity: entity.to_string(),
+            id,
+            message: message.to_string(),
+        }
+    }
+
     /// Crée une erreur de contrainte
     /// 
     /// # Arguments
@@ -90,6 +157,53 @@ impl AppError {
     }
 }
 
+/// Convertit une erreur SQLite, les écritures refusées par le mode lecture
+/// seule (voir `database::lecture_seule`) devenant `AppError::ReadOnly`
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        match &error {
+            rusqlite::Error::SqliteFailure(erreur, _)
+                if erreur.code == rusqlite::ErrorCode::AuthorizationForStatementDenied =>
+            {
+                AppError::ReadOnly
+            }
+            _ => AppError::Database(error),
+        }
+    }
+}
+
+/// Erreur structurée transmise au frontend par les commandes de modification
+/// 
+/// Le frontend distingue ainsi un conflit de version (`kind` = "conflict") des
+/// autres erreurs et peut proposer de recharger l'entité au lieu d'afficher
+/// seulement le message.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErreurCommande {
+    /// "conflict", "not_found", "validation", "read_only" ou "error"
+    pub kind: &'static str,
+    pub message: String,
+    pub entity: Option<String>,
+    pub id: Option<i64>,
+    /// Version chargée par le client (conflit uniquement)
+    pub expected: Option<i64>,
+    /// Version enregistrée (conflit uniquement)
+    pub current: Option<i64>,
+}
+
+impl From<AppError> for ErreurCommande {
+    fn from(error: AppError) -> Self {
+        let message = error.to_string();
+        let (kind, entity, id, expected, current) = match error {
+            AppError::Conflict { entity, id, expected, current } => ("conflict", Some(entity), Some(id), Some(expected), Some(current)),
+            AppError::NotFound { entity, id } => ("not_found", Some(entity), Some(id), None, None),
+            AppError::ValidationError { .. } => ("validation", None, None, None, None),
+            AppError::ReadOnly => ("read_only", None, None, None, None),
+            _ => ("error", None, None, None, None),
+        };
+        ErreurCommande { kind, message, entity, id, expected, current }
+    }
+}
+
 /// Convertit AppError en String pour les commandes Tauri
 /// 
 /// Tauri nécessite que les erreurs soient converties en String
@@ -1,133 +1,422 @@
+// Les traits de repository ne sont utilisés qu'avec des types concrets:
+// les bornes `Send` sur les futures ne sont pas nécessaires
+#![allow(async_fn_in_trait)]
+
 /// Modules for the farm management application
-mod models;
-mod error;
-mod database;
-mod repositories;
-mod services;
-mod commands;
+///
+/// They are public so the integration tests under `tests/` can drive the
+/// service and repository layers directly.
+pub mod models;
+pub mod error;
+pub mod database;
+pub mod repositories;
+pub mod services;
+#[macro_use]
+pub mod commands;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(test)]
+mod test_support;
 
 use std::sync::Arc;
-use tauri::Manager;
-use database::DatabaseManager;
+use tauri::{Manager, Runtime, State};
+use database::{DatabaseConfig, DatabaseManager, OuvertureBase, NOM_FICHIER_BASE};
+use error::AppError;
+use services::{
+    EventBus, ExportProgrammeService, MaintenanceService, MetriquesCommandes, StatisticsCache, TauriEventSink,
+    WebhookService, INTERVALLE_ENREGISTREMENT_METRIQUES, INTERVALLE_ENVOI_WEBHOOK,
+    INTERVALLE_RAFRAICHISSEMENT_STATISTIQUES, INTERVALLE_VERIFICATION_EXPORTS, INTERVALLE_VERIFICATION_MAINTENANCE,
+};
+
+commande_mesuree! {
+    // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+    #[tauri::command]
+    async fn greet(name: String) -> Result<String, String> {
+        Ok(format!("Hello, {}! You've been greeted from Rust!", name))
+    }
+}
+
+/// Démarre les services qui dépendent de la base, une fois celle-ci ouverte
+///
+/// Appelé au démarrage, ou par `unlock_database` lorsque la base chiffrée
+/// attendait sa phrase secrète: jusque-là, les commandes qui demandent la
+/// base échouent car son état n'est pas encore géré.
+///
+/// Public pour que tests/etat_commandes.rs appelle les commandes avec l'état
+/// réellement géré par l'application.
+pub fn demarrer<R: Runtime>(app: &tauri::AppHandle<R>, db_manager: Arc<DatabaseManager>) {
+    // Événements `entity://changed` émis vers les fenêtres après chaque écriture validée
+    let event_bus = EventBus::new();
+    event_bus.subscribe(Arc::new(TauriEventSink::new(app.clone())));
+    event_bus.attach(&db_manager);
+    app.manage(event_bus);
+
+    // Statistiques du tableau de bord recalculées en arrière-plan après chaque modification
+    let statistics_cache = Arc::new(StatisticsCache::new());
+    tauri::async_runtime::spawn(statistics_cache.clone().run_background_refresh(
+        db_manager.clone(),
+        INTERVALLE_RAFRAICHISSEMENT_STATISTIQUES,
+    ));
+    app.manage(statistics_cache);
+
+    // Maintenance mensuelle de la base, si elle est programmée
+    tauri::async_runtime::spawn(
+        MaintenanceService::new(db_manager.clone()).run_scheduled_maintenance(INTERVALLE_VERIFICATION_MAINTENANCE),
+    );
+
+    // Exports programmés vers les dossiers choisis par les utilisateurs
+    tauri::async_runtime::spawn(
+        ExportProgrammeService::new(db_manager.clone()).run_scheduled_exports(INTERVALLE_VERIFICATION_EXPORTS),
+    );
 
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+    // Notifications du système de la coopérative, retentées tant qu'elles ne sont pas reçues
+    tauri::async_runtime::spawn(
+        WebhookService::new(db_manager.clone()).run_webhook_delivery(INTERVALLE_ENVOI_WEBHOOK),
+    );
+
+    // Mesures locales des commandes (gérées dès l'enregistrement des commandes),
+    // enregistrées périodiquement dans la base
+    let metriques = app.state::<Arc<MetriquesCommandes>>().inner().clone();
+    tauri::async_runtime::spawn(
+        metriques.run_periodic_flush(db_manager.clone(), INTERVALLE_ENREGISTREMENT_METRIQUES),
+    );
+
+    // Store database manager in app state; commands take it as
+    // `State<'_, Arc<DatabaseManager>>` (checked by tests/etat_commandes.rs)
+    app.manage(db_manager);
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
             // Initialize database
-            let app_dir = app.path().app_data_dir().expect("Failed to get app data directory");
-            std::fs::create_dir_all(&app_dir).expect("Failed to create app data directory");
-            
-            let db_path = app_dir.join("farm_management.db");
-            let db_manager = Arc::new(
-                DatabaseManager::new(&db_path)
-                    .expect("Failed to initialize database")
-            );
-            
-            // Initialize database schema
-            db_manager.initialize_schema()
-                .expect("Failed to initialize database schema");
-            
-            // Store database manager in app state
-            app.manage(db_manager);
-            
+            let app_dir = app.path().app_data_dir()?;
+            std::fs::create_dir_all(&app_dir)?;
+
+            // Une base chiffrée sans phrase secrète valide reste verrouillée:
+            // l'interface la demande (`unlock_database`) au lieu d'un arrêt brutal
+            let ouverture = OuvertureBase::new(app_dir.join(NOM_FICHIER_BASE), DatabaseConfig::from_env());
+            match ouverture.ouvrir() {
+                Ok(db_manager) => demarrer(app.handle(), db_manager),
+                Err(AppError::Locked | AppError::InvalidPassphrase) => {}
+                Err(e) => return Err(e.into()),
+            }
+            app.manage(ouverture);
+
             Ok(())
-        })
-        .invoke_handler(tauri::generate_handler![
-            greet,
-            // Auth commands
-            commands::register_user,
-            commands::login_user,
-            commands::logout_user,
-            commands::verify_token,
-            commands::update_user_profile,
-            commands::update_user_password,
-            // Ferme commands
-            commands::create_ferme,
-            commands::get_all_fermes,
-            commands::get_ferme_by_id,
-            commands::update_ferme,
-            commands::delete_ferme,
-            commands::search_fermes,
-            commands::get_ferme_statistics,
-            commands::get_ferme_detailed_statistics,
-            commands::get_global_statistics,
-            // Personnel commands
-            commands::create_personnel,
-            commands::get_all_personnel,
-            commands::get_personnel_list,
-            commands::update_personnel,
-            commands::delete_personnel,
-            // Soin commands
-            commands::create_soin,
-            commands::get_all_soins,
-            commands::get_soins_list,
-            commands::get_soin_by_id,
-            commands::update_soin,
-            commands::delete_soin,
-            // Bande commands
-            commands::create_bande,
-            commands::get_all_bandes,
-            commands::get_bandes_by_ferme,
-            commands::get_latest_bandes_by_ferme,
-            commands::get_bandes_by_ferme_paginated,
-            commands::get_bande_by_id,
-            commands::update_bande,
-            commands::delete_bande,
-            commands::get_available_batiments,
-            // Batiment commands
-            commands::create_batiment,
-            commands::get_batiments_by_bande,
-            commands::get_batiment_by_id,
-            commands::update_batiment,
-            commands::delete_batiment,
-            commands::get_available_batiment_numbers,
-            commands::add_maladie_to_batiment,
-            commands::add_maladie_to_bande_batiments,
-            // Alimentation commands
-            commands::create_alimentation_history,
-            commands::get_alimentation_history_by_bande,
-            commands::get_alimentation_history_by_id,
-            commands::update_alimentation_history,
-            commands::delete_alimentation_history,
-            commands::get_alimentation_contour,
-            // Maladie commands
-            commands::create_maladie,
-            commands::get_maladies,
-            commands::get_maladies_list,
-            commands::update_maladie,
-            commands::delete_maladie,
-            // Poussin commands
-            commands::create_poussin,
-            commands::get_all_poussins,
-            commands::get_poussin_list,
-            commands::update_poussin,
-            commands::delete_poussin,
-            // Semaine commands
-            commands::create_semaine,
-            commands::get_all_semaines,
-            commands::get_semaine_by_id,
-            commands::get_semaines_by_batiment,
-            commands::get_full_semaines_by_batiment,
-            commands::update_semaine,
-            commands::update_semaine_poids,
-            commands::delete_semaine,
-            // Suivi quotidien commands
-            commands::create_suivi_quotidien,
-            commands::get_all_suivi_quotidien,
-            commands::get_suivi_quotidien_by_id,
-            commands::get_suivi_quotidien_by_semaine,
-            commands::update_suivi_quotidien,
-            commands::delete_suivi_quotidien,
-            commands::upsert_suivi_quotidien_field,
-        ])
+        });
+
+    enregistrer_commandes(builder)
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// Enregistre les commandes de l'application sur un constructeur Tauri
+///
+/// Partagé par `run()` et par les tests, qui l'appliquent à
+/// `tauri::test::mock_builder()`. Toutes les commandes sont mesurées (voir
+/// `commande_mesuree!`): leurs mesures sont gérées dès maintenant, pour que
+/// celles appelées avant l'ouverture de la base (`unlock_database`) les trouvent.
+pub fn enregistrer_commandes<R: Runtime>(builder: tauri::Builder<R>) -> tauri::Builder<R> {
+    builder.manage(Arc::new(MetriquesCommandes::new())).invoke_handler(tauri::generate_handler![
+        greet,
+        commands::is_database_locked,
+        commands::unlock_database,
+        // Auth commands
+        commands::register_user,
+        commands::login_user,
+        commands::logout_user,
+        commands::verify_token,
+        commands::update_user_profile,
+        commands::update_user_password,
+        commands::reset_user_password,
+        commands::create_invitation,
+        commands::revoke_invitation,
+        commands::get_invitations,
+        commands::get_user_activity,
+        commands::start_mfa_enrollment,
+        commands::confirm_mfa_enrollment,
+        commands::disable_mfa,
+        commands::get_user_preferences,
+        commands::set_user_preferences,
+        commands::archive_old_bandes,
+        commands::query_archive,
+        commands::restore_archived_bande,
+        commands::run_database_maintenance,
+        commands::get_database_maintenance_schedule,
+        commands::set_database_maintenance_schedule,
+        commands::check_database_integrity,
+        commands::export_reporting_snapshot,
+        commands::get_vat_summary,
+        commands::export_vat_summary,
+        commands::export_bande_json,
+        commands::create_scheduled_export,
+        commands::get_scheduled_exports,
+        commands::delete_scheduled_export,
+        commands::run_scheduled_export,
+        commands::get_edit_window,
+        commands::set_edit_window,
+        commands::unlock_period,
+        // Ferme commands
+        commands::create_ferme,
+        commands::get_all_fermes,
+        commands::get_ferme_by_id,
+        commands::update_ferme,
+        commands::delete_ferme,
+        commands::search_fermes,
+        commands::get_ferme_statistics,
+        commands::get_ferme_detailed_statistics,
+        commands::get_global_statistics,
+        // Statistics commands
+        commands::get_batiment_health,
+        commands::get_mortality_series,
+        commands::get_feed_series,
+        commands::get_weight_series,
+        commands::get_temperature_series,
+        // Personnel commands
+        commands::create_personnel,
+        commands::get_all_personnel,
+        commands::get_personnel_list,
+        commands::update_personnel,
+        commands::delete_personnel,
+        // Soin commands
+        commands::create_soin,
+        commands::get_all_soins,
+        commands::get_soins_list,
+        commands::get_soin_by_id,
+        commands::update_soin,
+        commands::delete_soin,
+        commands::get_soins_usage,
+        commands::get_soins_usage_by_ferme,
+        // Bande commands
+        commands::create_bande,
+        commands::validate_bande_creation,
+        commands::get_all_bandes,
+        commands::get_bandes_by_ferme,
+        commands::get_latest_bandes_by_ferme,
+        commands::get_bandes_by_ferme_paginated,
+        commands::get_bande_by_id,
+        commands::update_bande,
+        commands::delete_bande,
+        commands::get_bande_activity,
+        commands::close_bande,
+        commands::reopen_bande,
+        commands::get_bande_audit_log,
+        commands::get_bande_timeline,
+        commands::get_bande_financial_summary,
+        commands::get_bande_budget,
+        commands::set_bande_budget,
+        commands::get_budget_variance,
+        commands::get_bande_contract,
+        commands::set_bande_contract,
+        commands::compute_settlement,
+        commands::get_bande_numbering_pattern,
+        commands::set_bande_numbering_pattern,
+        commands::get_available_batiments,
+        // Batiment commands
+        commands::create_batiment,
+        commands::get_batiments_by_bande,
+        commands::get_batiment_by_id,
+        commands::update_batiment,
+        commands::bulk_update_batiments,
+        commands::delete_batiment,
+        commands::get_available_batiment_numbers,
+        commands::add_maladie_to_batiment,
+        commands::add_maladie_to_bande_batiments,
+        commands::get_maladies_by_batiment,
+        // Alimentation commands
+        commands::create_alimentation_history,
+        commands::get_alimentation_history_by_bande,
+        commands::get_alimentation_history_by_bande_paginated,
+        commands::get_alimentation_history_global,
+        commands::get_alimentation_history_by_id,
+        commands::update_alimentation_history,
+        commands::delete_alimentation_history,
+        commands::get_alimentation_contour,
+        commands::get_duplicate_delivery_window,
+        commands::set_duplicate_delivery_window,
+        commands::get_alimentation_totals_by_type,
+        // Maladie commands
+        commands::create_maladie,
+        commands::get_maladies,
+        commands::get_maladies_list,
+        commands::update_maladie,
+        commands::delete_maladie,
+        commands::get_maladie_trends,
+        // Poussin commands
+        commands::create_poussin,
+        commands::get_all_poussins,
+        commands::get_poussin_list,
+        commands::update_poussin,
+        commands::delete_poussin,
+        // Semaine commands
+        commands::create_semaine,
+        commands::get_all_semaines,
+        commands::get_semaine_by_id,
+        commands::get_semaines_by_batiment,
+        commands::get_full_semaines_by_batiment,
+        commands::update_semaine,
+        commands::update_semaine_poids,
+        commands::get_poids_unit,
+        commands::set_poids_unit,
+        commands::get_semaines_max,
+        commands::set_semaines_max,
+        commands::advance_to_next_week,
+        commands::normalize_semaine_poids,
+        commands::get_weight_gain_series,
+        commands::get_feed_per_bird_series,
+        commands::delete_semaine,
+        // Suivi quotidien commands
+        commands::create_suivi_quotidien,
+        commands::get_all_suivi_quotidien,
+        commands::get_suivi_quotidien_by_id,
+        commands::get_suivi_quotidien_by_semaine,
+        commands::update_suivi_quotidien,
+        commands::delete_suivi_quotidien,
+        commands::upsert_suivi_quotidien_field,
+        commands::save_suivi_grid,
+        commands::add_suivi_soin,
+        commands::update_suivi_soin,
+        commands::delete_suivi_soin,
+        commands::get_suivi_soins,
+        commands::cleanup_empty_suivi,
+        commands::get_entry_completeness,
+        // Programme alimentation commands
+        commands::create_phase_alimentation,
+        commands::get_programme_alimentation,
+        commands::update_phase_alimentation,
+        commands::delete_phase_alimentation,
+        commands::get_feed_program_compliance,
+        commands::forecast_feed_requirements,
+        commands::get_kg_par_sachet,
+        commands::set_kg_par_sachet,
+        // Plan soins commands
+        commands::create_plan_soin,
+        commands::get_plan_soins,
+        commands::update_plan_soin,
+        commands::delete_plan_soin,
+        // Prix commands
+        commands::create_prix,
+        commands::get_historique_prix,
+        commands::delete_prix,
+        commands::get_devises,
+        commands::get_devise,
+        commands::set_devise,
+        commands::format_montants,
+        // Analyse commands
+        commands::create_analyse,
+        commands::get_analyses_by_batiment,
+        commands::get_analyses_by_bande,
+        commands::get_analyses_by_maladie,
+        commands::update_analyse,
+        commands::delete_analyse,
+        commands::attach_analyse_pdf,
+        commands::detach_analyse_pdf,
+        // Note batiment commands
+        commands::add_note_batiment,
+        commands::get_notes_batiment,
+        // Message commands
+        commands::post_message,
+        commands::get_unread_messages,
+        commands::mark_message_read,
+        // Custom field commands
+        commands::create_custom_field,
+        commands::get_custom_fields,
+        commands::delete_custom_field,
+        // Tag commands
+        commands::create_tag,
+        commands::update_tag,
+        commands::get_tags,
+        commands::delete_tag,
+        commands::add_tag_to_bande,
+        commands::remove_tag_from_bande,
+        // Saved filter commands
+        commands::save_filter,
+        commands::get_saved_filters,
+        commands::delete_saved_filter,
+        // Litière commands
+        commands::create_litiere,
+        commands::get_litieres_by_batiment,
+        commands::get_litieres_by_bande,
+        commands::update_litiere,
+        commands::delete_litiere,
+        // Vide sanitaire commands
+        commands::create_vide_sanitaire,
+        commands::get_vides_sanitaires_by_ferme,
+        commands::update_vide_sanitaire,
+        commands::delete_vide_sanitaire,
+        commands::get_vide_sanitaire_statistics,
+        commands::create_immobilisation,
+        commands::get_immobilisations_by_ferme,
+        commands::update_immobilisation,
+        commands::delete_immobilisation,
+        commands::get_depreciation_schedule,
+        commands::create_equipement,
+        commands::get_equipements_by_ferme,
+        commands::update_equipement,
+        commands::delete_equipement,
+        commands::add_entretien_equipement,
+        commands::get_entretiens_equipement,
+        commands::delete_entretien_equipement,
+        // Comparaison commands
+        commands::compare_fermes,
+        commands::compare_personnel,
+        commands::compare_poussins,
+        commands::get_production_index,
+        commands::get_seasonal_statistics,
+        // Rapports personnalisés commands
+        commands::get_report_entities,
+        commands::create_report_definition,
+        commands::get_report_definitions,
+        commands::delete_report_definition,
+        commands::run_report,
+        // Suppression commands
+        commands::get_delete_impact,
+        // Fusion commands
+        commands::merge_entities,
+        // Plan de ferme commands
+        commands::get_ferme_plan,
+        commands::update_ferme_coordinates,
+        commands::update_batiment_position,
+        commands::get_building_occupancy,
+        // Meteo commands
+        commands::sync_weather,
+        commands::get_weather_by_ferme,
+        commands::get_mortalite_meteo,
+        // Capteur commands
+        commands::import_mesures_capteurs,
+        commands::get_mesures_capteurs,
+        // Alerte commands
+        commands::get_bande_alertes,
+        commands::get_pending_alerts,
+        commands::get_alert_subscription,
+        commands::set_alert_subscription,
+        commands::acknowledge_alert,
+        commands::get_alert_history,
+        // Vue du jour commands
+        commands::get_today_overview,
+        // Demo commands
+        commands::generate_demo_data,
+        commands::needs_first_run_setup,
+        commands::first_run_setup,
+        commands::import_legacy_workbook,
+        commands::import_bande_json,
+        commands::switch_profile,
+        commands::get_active_profile,
+        commands::list_profiles,
+        commands::create_profile,
+        commands::open_profile,
+        commands::delete_profile,
+        commands::get_read_only_mode,
+        commands::set_read_only_mode,
+        commands::get_webhook_url,
+        commands::set_webhook_url,
+        commands::get_webhook_outbox,
+        commands::retry_webhook_event,
+        commands::deliver_webhook_events,
+        // Mesures locales des commandes
+        commands::get_performance_metrics,
+    ])
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Types d'événements du fil d'activité d'une bande
+pub const ACTIVITE_LIVRAISON_ALIMENT: &str = "livraison_aliment";
+pub const ACTIVITE_MALADIE: &str = "maladie";
+pub const ACTIVITE_ANALYSE: &str = "analyse";
+pub const ACTIVITE_NOTE: &str = "note";
+pub const ACTIVITE_LITIERE: &str = "litiere";
+
+/// Événement du fil d'activité d'une bande
+/// 
+/// Regroupe dans une même chronologie des événements issus de plusieurs
+/// tables; `reference_id` est l'ID de l'élément dans sa table d'origine
+/// (livraison, maladie, analyse, note ou litière selon `type_activite`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiviteBande {
+    pub type_activite: String,
+    pub date: String,
+    pub batiment_id: Option<i64>,
+    pub numero_batiment: Option<String>,
+    pub reference_id: i64,
+    pub description: String,
+}
+
+/// Page du fil d'activité d'une bande, du plus récent au plus ancien
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedActiviteBande {
+    pub data: Vec<ActiviteBande>,
+    pub total: i64,
+    pub page: u32,
+    pub limit: u32,
+    pub total_pages: u32,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+/// Type d'alerte: enlèvement prévu pendant le délai d'attente d'un soin
+pub const ALERTE_DELAI_ATTENTE: &str = "delai_attente";
+
+/// Type d'alerte: jours passés sans décès ou alimentation saisis
+pub const ALERTE_SAISIE_MANQUANTE: &str = "saisie_manquante";
+
+/// Type d'alerte: bâtiment nettoyé et vide depuis assez longtemps pour recevoir une bande
+pub const ALERTE_VIDE_SANITAIRE: &str = "vide_sanitaire";
+
+/// Type d'alerte: entretien d'un équipement bientôt dû ou en retard
+pub const ALERTE_ENTRETIEN_EQUIPEMENT: &str = "entretien_equipement";
+
+/// Niveaux de gravité des alertes, du moins grave au plus grave
+pub const GRAVITE_INFO: &str = "info";
+pub const GRAVITE_WARNING: &str = "warning";
+pub const GRAVITE_CRITICAL: &str = "critical";
+pub const GRAVITES_ALERTE: [&str; 3] = [GRAVITE_INFO, GRAVITE_WARNING, GRAVITE_CRITICAL];
+
+/// Délai après lequel une alerte critique non acquittée est escaladée
+pub const DELAI_ESCALADE_HEURES: i64 = 12;
+
+/// Nombre maximal d'alertes renvoyées par l'historique
+pub const HISTORIQUE_ALERTES_MAX: i64 = 500;
+
+/// Types d'alertes produits par le moteur d'alertes
+pub const TYPES_ALERTE: [&str; 4] =
+    [ALERTE_DELAI_ATTENTE, ALERTE_SAISIE_MANQUANTE, ALERTE_VIDE_SANITAIRE, ALERTE_ENTRETIEN_EQUIPEMENT];
+
+/// Gravité d'un type d'alerte
+///
+/// Un abattage pendant le délai d'attente d'un soin est critique; une saisie
+/// manquante fausse les statistiques, un entretien oublié peut priver un
+/// bâtiment de ventilation ou de courant; un bâtiment prêt n'est qu'une information.
+pub fn gravite_alerte(type_alerte: &str) -> &'static str {
+    match type_alerte {
+        ALERTE_DELAI_ATTENTE => GRAVITE_CRITICAL,
+        ALERTE_SAISIE_MANQUANTE | ALERTE_ENTRETIEN_EQUIPEMENT => GRAVITE_WARNING,
+        _ => GRAVITE_INFO,
+    }
+}
+
+/// Rang d'une gravité dans `GRAVITES_ALERTE`, `None` si elle est inconnue
+pub fn rang_gravite(gravite: &str) -> Option<usize> {
+    GRAVITES_ALERTE.iter().position(|g| *g == gravite)
+}
+
+/// Alerte calculée par le moteur d'alertes
+///
+/// Les alertes sont recalculées à partir des données de suivi à chaque
+/// consultation. Les alertes en cours sont aussi inscrites dans l'historique
+/// à leur première détection: `id` est alors leur ID dans l'historique, qui
+/// conserve leur acquittement. Une alerte critique non acquittée depuis plus
+/// de `DELAI_ESCALADE_HEURES` heures est escaladée.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Alerte {
+    pub id: Option<i64>,
+    pub type_alerte: String,
+    /// `info`, `warning` ou `critical`
+    pub gravite: String,
+    pub ferme_id: i64,
+    /// `None` pour une alerte de la ferme elle-même (entretien d'un équipement)
+    pub bande_id: Option<i64>,
+    pub batiment_id: Option<i64>,
+    /// Date concernée par l'alerte (YYYY-MM-DD)
+    pub date: String,
+    pub message: String,
+    /// Première détection de l'alerte (YYYY-MM-DD HH:MM:SS, UTC)
+    pub detectee_le: Option<String>,
+    pub acquittee_par: Option<i64>,
+    pub acquittee_par_nom: Option<String>,
+    pub acquittee_le: Option<String>,
+    pub escalade: bool,
+}
+
+/// Alertes auxquelles un utilisateur est abonné
+///
+/// Une liste vide vaut pour tous les types ou toutes les fermes; seules les
+/// alertes d'une gravité au moins égale à `gravite_min` sont retenues.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbonnementAlertes {
+    pub user_id: i64,
+    #[serde(default)]
+    pub types_alerte: Vec<String>,
+    #[serde(default)]
+    pub ferme_ids: Vec<i64>,
+    pub gravite_min: String,
+}
+
+impl AbonnementAlertes {
+    /// Abonnement par défaut: toutes les alertes
+    pub fn tout(user_id: i64) -> Self {
+        Self { user_id, types_alerte: Vec::new(), ferme_ids: Vec::new(), gravite_min: GRAVITE_INFO.to_string() }
+    }
+
+    /// Vrai si l'alerte correspond à l'abonnement
+    ///
+    /// Une alerte escaladée est envoyée à tous les utilisateurs.
+    pub fn accepte(&self, alerte: &Alerte) -> bool {
+        alerte.escalade
+            || ((self.types_alerte.is_empty() || self.types_alerte.contains(&alerte.type_alerte))
+                && (self.ferme_ids.is_empty() || self.ferme_ids.contains(&alerte.ferme_id))
+                && rang_gravite(&alerte.gravite) >= rang_gravite(&self.gravite_min))
+    }
+}
+
+/// Structure pour modifier l'abonnement aux alertes de l'utilisateur connecté
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateAbonnementAlertes {
+    #[serde(default)]
+    pub types_alerte: Vec<String>,
+    #[serde(default)]
+    pub ferme_ids: Vec<i64>,
+    pub gravite_min: String,
+}
@@ -1,5 +1,15 @@
+use crate::models::SaisieTva;
 use serde::{Deserialize, Serialize};
 
+/// Feed types accepted for `type_aliment`, in program order
+pub const TYPES_ALIMENT: [&str; 3] = ["démarrage", "croissance", "finition"];
+
+/// Parameter: minutes during which a same-quantity delivery on the same bande needs a confirmation (0 disables the check)
+pub const PARAMETRE_FENETRE_DOUBLON_LIVRAISON: &str = "fenetre_doublon_livraison_minutes";
+
+/// Duplicate delivery window used when the parameter is not set
+pub const FENETRE_DOUBLON_LIVRAISON_DEFAUT_MINUTES: i64 = 5;
+
 /// Alimentation history record - tracks quantity changes over time
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlimentationHistory {
@@ -7,19 +17,122 @@ pub struct AlimentationHistory {
     pub bande_id: i64,
     pub quantite: f64, // Can be positive (addition) or negative (subtraction)
     pub created_at: String, // ISO format datetime string
+    pub fournisseur: Option<String>,
+    pub type_aliment: Option<String>, // One of TYPES_ALIMENT
+    pub numero_lot: Option<String>,
+    /// Feed price per kg when the record was created, None if no price was known
+    #[serde(default)]
+    pub prix_unitaire: Option<f64>,
+    /// Currency of `prix_unitaire` when it was recorded
+    #[serde(default)]
+    pub devise: Option<String>,
+    /// VAT rate in %, the price being recorded before tax
+    #[serde(default)]
+    pub taux_tva: Option<f64>,
+    /// VAT amount entered, or computed from the rate, the quantity and the price
+    #[serde(default)]
+    pub montant_tva: Option<f64>,
 }
 
 /// Data for creating a new alimentation history record
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Without `prix_unitaire`, the feed price in effect on `created_at` is recorded.
+/// A `prix_unitaire` entered tax included is converted before tax with `tva`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateAlimentationHistory {
     pub bande_id: i64,
     pub quantite: f64, // Can be positive or negative
     pub created_at: String, // ISO format datetime string
+    pub fournisseur: Option<String>,
+    pub type_aliment: Option<String>, // One of TYPES_ALIMENT
+    pub numero_lot: Option<String>,
+    #[serde(default)]
+    pub prix_unitaire: Option<f64>,
+    #[serde(default, flatten)]
+    pub tva: SaisieTva,
+    /// Set after the user confirmed a delivery flagged as a possible duplicate
+    #[serde(default)]
+    pub confirmer_doublon: bool,
 }
 
 /// Data for updating an alimentation history record
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// The price and VAT are resolved again as on creation: without `prix_unitaire`,
+/// the feed price in effect on the record's `created_at` is recorded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateAlimentationHistory {
     pub bande_id: i64,
     pub quantite: f64, // Can be positive or negative
+    pub fournisseur: Option<String>,
+    pub type_aliment: Option<String>, // One of TYPES_ALIMENT
+    pub numero_lot: Option<String>,
+    #[serde(default)]
+    pub prix_unitaire: Option<f64>,
+    #[serde(default, flatten)]
+    pub tva: SaisieTva,
+}
+
+/// Paginated alimentation history for a bande
+///
+/// `total_quantite` is the sum of all quantities over the filter window,
+/// not only the current page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedAlimentationHistory {
+    pub data: Vec<AlimentationHistory>,
+    pub total: u32,
+    pub page: u32,
+    pub limit: u32,
+    pub total_pages: u32,
+    pub has_next: bool,
+    pub has_prev: bool,
+    pub total_quantite: f64,
+}
+
+/// Alimentation history record with the names of its bande and ferme
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlimentationHistoryWithDetails {
+    pub id: i64,
+    pub bande_id: i64,
+    pub numero_bande: i32,
+    pub ferme_id: i64,
+    pub ferme_nom: String,
+    pub quantite: f64,
+    pub created_at: String,
+    pub fournisseur: Option<String>,
+    pub type_aliment: Option<String>,
+    pub numero_lot: Option<String>,
+}
+
+/// Filters for the global alimentation history view
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlimentationHistoryFilters {
+    pub ferme_id: Option<i64>,
+    pub bande_id: Option<i64>,
+    pub date_from: Option<String>, // Format: "YYYY-MM-DD"
+    pub date_to: Option<String>,   // Format: "YYYY-MM-DD"
+    pub type_aliment: Option<String>,
+    pub fournisseur: Option<String>,
+    /// Keep only deliveries (positive quantities), hiding corrections
+    pub deliveries_only: Option<bool>,
+}
+
+/// Paginated alimentation history across all bandes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedAlimentationHistoryGlobal {
+    pub data: Vec<AlimentationHistoryWithDetails>,
+    pub total: u32,
+    pub page: u32,
+    pub limit: u32,
+    pub total_pages: u32,
+    pub has_next: bool,
+    pub has_prev: bool,
+    pub total_quantite: f64,
+}
+
+/// Delivered quantities grouped by feed type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlimentationTypeTotal {
+    pub type_aliment: Option<String>, // None for deliveries recorded without a type
+    pub total_quantite: f64,
+    pub nombre_livraisons: u32,
 }
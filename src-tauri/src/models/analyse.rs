@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Types d'analyses reconnus
+pub const TYPES_ANALYSE: [&str; 8] = [
+    "sérologie",
+    "bactériologie",
+    "virologie",
+    "parasitologie",
+    "autopsie",
+    "eau",
+    "aliment",
+    "autre",
+];
+
+/// Analyse de laboratoire réalisée sur un bâtiment
+/// 
+/// Remplace la saisie libre de `suivi_quotidien.analyses` (conservée pour les
+/// anciennes saisies) par un enregistrement interrogeable. Une analyse peut être
+/// rattachée à un épisode de maladie du bâtiment via `maladie_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Analyse {
+    pub id: Option<i64>,
+    pub batiment_id: i64,
+    pub maladie_id: Option<i64>,
+    pub maladie_nom: Option<String>,
+    pub type_analyse: String,
+    pub laboratoire: Option<String>,
+    pub date_prelevement: String, // YYYY-MM-DD
+    pub resultat: Option<String>,
+    /// Chemin du compte rendu PDF copié dans le dossier de l'application
+    pub fichier_pdf: Option<String>,
+    pub created_at: String,
+}
+
+/// Structure pour créer une nouvelle analyse
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateAnalyse {
+    pub batiment_id: i64,
+    pub maladie_id: Option<i64>,
+    pub type_analyse: String,
+    pub laboratoire: Option<String>,
+    pub date_prelevement: String,
+    pub resultat: Option<String>,
+}
+
+/// Structure pour mettre à jour une analyse existante
+/// 
+/// Le PDF joint se gère séparément (voir `attach_analyse_pdf`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateAnalyse {
+    pub id: i64,
+    pub maladie_id: Option<i64>,
+    pub type_analyse: String,
+    pub laboratoire: Option<String>,
+    pub date_prelevement: String,
+    pub resultat: Option<String>,
+}
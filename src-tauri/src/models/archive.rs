@@ -0,0 +1,38 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Paramètre: ancienneté en années à partir de laquelle les bandes clôturées sont archivées
+pub const PARAMETRE_ARCHIVAGE_ANNEES: &str = "archivage_annees";
+
+/// Ancienneté d'archivage par défaut, en années
+pub const ARCHIVAGE_ANNEES_DEFAUT: i32 = 3;
+
+/// Bande déplacée dans la base d'archive
+///
+/// Les bâtiments, semaines et suivis quotidiens de la bande sont archivés avec
+/// elle; seul leur résumé est présenté ici.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandeArchivee {
+    pub id: i64,
+    pub numero_bande: i32,
+    pub numero_affiche: String,
+    pub ferme_id: i64,
+    /// `None` si la ferme a été supprimée depuis l'archivage
+    pub ferme_nom: Option<String>,
+    pub date_entree: NaiveDate,
+    pub date_cloture: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub nombre_batiments: i64,
+    pub quantite_totale: i64,
+    pub total_deces: i64,
+}
+
+/// Résultat d'un archivage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultatArchivage {
+    /// Ancienneté appliquée, en années
+    pub annees: i32,
+    /// Les bandes clôturées avant cette date ont été archivées
+    pub date_limite: NaiveDate,
+    pub bandes_archivees: Vec<i64>,
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// Actions enregistrées dans le journal d'audit
+pub const AUDIT_CLOTURE_BANDE: &str = "cloture_bande";
+pub const AUDIT_REOUVERTURE_BANDE: &str = "reouverture_bande";
+pub const AUDIT_REINITIALISATION_MOT_DE_PASSE: &str = "reinitialisation_mot_de_passe";
+pub const AUDIT_ARCHIVAGE_BANDE: &str = "archivage_bande";
+pub const AUDIT_RESTAURATION_BANDE: &str = "restauration_bande";
+pub const AUDIT_DEVERROUILLAGE_PERIODE: &str = "deverrouillage_periode";
+pub const AUDIT_CONNEXION: &str = "connexion";
+pub const AUDIT_SAISIE_SUIVI: &str = "saisie_suivi";
+
+/// Entités concernées par le journal d'audit
+pub const AUDIT_ENTITE_BANDE: &str = "bande";
+pub const AUDIT_ENTITE_UTILISATEUR: &str = "utilisateur";
+pub const AUDIT_ENTITE_SUIVI: &str = "suivi_quotidien";
+
+/// Entrée du journal d'audit
+///
+/// `username` vaut `None` lorsque le compte de l'auteur a été supprimé.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntreeAudit {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub username: Option<String>,
+    pub action: String,
+    pub entite: String,
+    pub entite_id: i64,
+    /// Motif ou précisions saisis par l'utilisateur
+    pub details: Option<String>,
+    pub created_at: String,
+}
+
+/// Bande et nombre de saisies qu'un utilisateur y a faites
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandeSaisie {
+    pub bande_id: i64,
+    pub numero_affiche: String,
+    pub nombre_saisies: i64,
+}
+
+/// Activité d'un utilisateur sur une période, tirée du journal d'audit
+///
+/// Les saisies sont les cellules du suivi quotidien enregistrées par
+/// l'utilisateur connecté; `derniere_connexion` n'est pas limitée à la période.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiviteUtilisateur {
+    pub user_id: i64,
+    pub username: String,
+    pub nombre_saisies: i64,
+    pub derniere_connexion: Option<String>,
+    /// Bandes les plus saisies par l'utilisateur, de la plus saisie à la moins saisie
+    pub bandes_plus_modifiees: Vec<BandeSaisie>,
+}
+
+/// Dates de création et de dernière modification d'une ligne, et son auteur
+///
+/// Les champs valent `None` pour les lignes créées avant leur enregistrement
+/// (et `created_by` pour les lignes créées sans session ou par l'application).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Tracabilite {
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub created_by: Option<i64>,
+    /// Nom d'utilisateur de l'auteur, `None` si son compte a été supprimé
+    pub created_by_nom: Option<String>,
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::NaiveDate;
-use crate::models::BatimentWithDetails;
+use crate::models::{BatimentWithDetails, Tag, Tracabilite, ValeursChamps};
+
+/// Statuts d'une bande: les semaines et le suivi d'une bande clôturée ne sont plus modifiables
+pub const STATUT_BANDE_ACTIVE: &str = "active";
+pub const STATUT_BANDE_CLOTUREE: &str = "cloturee";
 
 /// Représente une bande d'animaux dans le système
 /// 
@@ -10,9 +14,16 @@ use crate::models::BatimentWithDetails;
 pub struct Bande {
     pub id: Option<i64>,
     pub numero_bande: i32,
+    /// Numéro présenté à l'utilisateur, généré selon le format paramétré
+    /// (égal à `numero_bande` pour les bandes créées avant les formats)
+    pub numero_affiche: String,
     pub date_entree: NaiveDate,
     pub ferme_id: i64,
     pub notes: Option<String>,
+    pub statut: String,
+    pub date_cloture: Option<NaiveDate>,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
 }
 
 /// Structure pour créer une nouvelle bande
@@ -24,6 +35,36 @@ pub struct CreateBande {
     pub date_entree: NaiveDate,
     pub ferme_id: i64,
     pub notes: Option<String>,
+    /// Valeurs des champs personnalisés, par clé de champ
+    #[serde(default)]
+    pub champs_personnalises: ValeursChamps,
+}
+
+/// Niveaux d'un problème relevé par `validate_bande_creation`
+pub const NIVEAU_ERREUR: &str = "erreur";
+pub const NIVEAU_AVERTISSEMENT: &str = "avertissement";
+
+/// Problème relevé lors de la validation d'une création de bande
+///
+/// Une erreur empêche la création, un avertissement est à confirmer par l'utilisateur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemeCreationBande {
+    pub niveau: String,
+    /// Champ concerné (`ferme_id`, `date_entree`, `batiments`, `numero_batiment`...)
+    pub champ: String,
+    /// Rang du bâtiment concerné dans la liste envoyée, `None` pour la bande
+    pub batiment: Option<usize>,
+    pub message: String,
+}
+
+/// Résultat de la validation d'une création de bande, sans écriture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationCreationBande {
+    /// Vrai si aucune erreur n'a été relevée (les avertissements n'empêchent pas la création)
+    pub valide: bool,
+    /// Numéro que recevrait la bande, `None` si la ferme n'existe pas
+    pub numero_affiche: Option<String>,
+    pub problemes: Vec<ProblemeCreationBande>,
 }
 
 /// Structure pour mettre à jour une bande existante
@@ -37,6 +78,14 @@ pub struct UpdateBande {
     pub date_entree: NaiveDate,
     pub ferme_id: i64,
     pub notes: Option<String>,
+    /// Version chargée par le client: la mise à jour est refusée si la ligne
+    /// a été modifiée depuis (`None` désactive le contrôle)
+    #[serde(default)]
+    pub version: Option<i64>,
+    /// Valeurs des champs personnalisés, par clé de champ: remplacent les
+    /// valeurs enregistrées (un champ absent est vidé)
+    #[serde(default)]
+    pub champs_personnalises: ValeursChamps,
 }
 
 /// Vue étendue d'une bande avec les informations des entités liées
@@ -47,12 +96,68 @@ pub struct UpdateBande {
 pub struct BandeWithDetails {
     pub id: Option<i64>,
     pub numero_bande: i32,
+    pub numero_affiche: String,
     pub date_entree: NaiveDate,
     pub ferme_id: i64,
     pub ferme_nom: String,
     pub notes: Option<String>,
+    pub statut: String,
+    pub date_cloture: Option<NaiveDate>,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
+    /// Dates de création et de modification, et auteur
+    #[serde(flatten)]
+    pub tracabilite: Tracabilite,
+    /// Valeurs des champs personnalisés, par clé de champ
+    pub champs_personnalises: ValeursChamps,
+    /// Étiquettes de la bande, par nom
+    pub tags: Vec<Tag>,
+    /// Bâtiments de la bande, vide s'ils n'ont pas été demandés (voir `BandeLoadOptions`)
     pub batiments: Vec<BatimentWithDetails>,
-    pub alimentation_contour: f64,  // Total accumulation d'alimentation en kg
+    pub alimentation_contour: f64,  // Total accumulation d'alimentation en kg (0 si non demandé)
+    /// Indicateurs de la bande, présents seulement s'ils ont été demandés
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<BandeStats>,
+}
+
+/// Indicateurs calculés d'une bande
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandeStats {
+    /// Somme des quantités de poussins des bâtiments
+    pub effectif_initial: i64,
+    pub total_deces: i64,
+    /// `None` si la bande n'a pas d'effectif
+    pub mortalite_pourcentage: Option<f64>,
+    /// Somme des alimentations journalières saisies, en kg
+    pub alimentation_consommee: f64,
+    /// Âge du dernier jour de suivi saisi, `None` sans suivi
+    pub dernier_age: Option<i32>,
+}
+
+/// Données chargées avec les bandes par les commandes de liste et de détail
+///
+/// Par défaut, les bâtiments et le contour d'alimentation sont chargés et les
+/// indicateurs ne le sont pas: les listes peuvent demander des bandes sans
+/// bâtiments, les vues de détail des bandes complètes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BandeLoadOptions {
+    pub include_batiments: bool,
+    pub include_contour: bool,
+    pub include_stats: bool,
+}
+
+impl Default for BandeLoadOptions {
+    fn default() -> Self {
+        Self { include_batiments: true, include_contour: true, include_stats: false }
+    }
+}
+
+impl BandeLoadOptions {
+    /// Toutes les données: bâtiments, contour et indicateurs
+    pub fn complet() -> Self {
+        Self { include_batiments: true, include_contour: true, include_stats: true }
+    }
 }
 
 /// Structure de pagination pour les bandes
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::{Tracabilite, ValeursChamps};
 
 /// Représente un bâtiment dans une bande
 /// 
@@ -12,6 +13,8 @@ pub struct Batiment {
     pub poussin_id: i64,
     pub personnel_id: i64,
     pub quantite: i32,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
 }
 
 /// Structure pour créer un nouveau bâtiment
@@ -25,6 +28,13 @@ pub struct CreateBatiment {
     pub poussin_id: i64,
     pub personnel_id: i64,
     pub quantite: i32,
+    /// Autorise volontairement la cohabitation avec une bande en cours dans
+    /// le même bâtiment (refusée par défaut)
+    #[serde(default)]
+    pub autoriser_cohabitation: bool,
+    /// Valeurs des champs personnalisés, par clé de champ
+    #[serde(default)]
+    pub champs_personnalises: ValeursChamps,
 }
 
 /// Structure pour mettre à jour un bâtiment existant
@@ -39,6 +49,25 @@ pub struct UpdateBatiment {
     pub poussin_id: i64,
     pub personnel_id: i64,
     pub quantite: i32,
+    /// Version chargée par le client: la mise à jour est refusée si la ligne
+    /// a été modifiée depuis (`None` désactive le contrôle)
+    #[serde(default)]
+    pub version: Option<i64>,
+    /// Valeurs des champs personnalisés, par clé de champ: remplacent les
+    /// valeurs enregistrées (un champ absent est vidé)
+    #[serde(default)]
+    pub champs_personnalises: ValeursChamps,
+}
+
+/// Réaffectation d'un bâtiment dans une modification groupée
+///
+/// Seuls les champs renseignés sont modifiés (par exemple le seul personnel
+/// lorsqu'un technicien quitte la ferme en cours de bande).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModificationBatiment {
+    pub batiment_id: i64,
+    pub personnel_id: Option<i64>,
+    pub poussin_id: Option<i64>,
 }
 
 /// Vue étendue d'un bâtiment avec les informations du personnel et du poussin
@@ -55,4 +84,11 @@ pub struct BatimentWithDetails {
     pub personnel_id: i64,
     pub personnel_nom: String,
     pub quantite: i32,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
+    /// Dates de création et de modification, et auteur
+    #[serde(flatten)]
+    pub tracabilite: Tracabilite,
+    /// Valeurs des champs personnalisés, par clé de champ
+    pub champs_personnalises: ValeursChamps,
 }
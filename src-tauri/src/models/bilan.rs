@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// Coût des soins d'un jour d'âge de la bande, tous bâtiments confondus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoutSoinsJour {
+    pub age: i32,
+    /// Date du jour (YYYY-MM-DD), déduite de la date d'entrée de la bande
+    pub date: String,
+    pub cout: f64,
+}
+
+/// Bilan financier d'une bande
+///
+/// L'aliment et les poussins sont valorisés au prix enregistré avec chaque
+/// livraison et chaque bâtiment: une modification ultérieure des prix ne
+/// change pas le coût des bandes passées. Les soins sont valorisés au prix
+/// unitaire actuel du soin. Les administrations sans prix, sans quantité
+/// numérique ou saisies dans une autre unité que celle du soin ne sont pas
+/// valorisées et sont comptées dans `soins_non_valorises`. La litière est
+/// valorisée au coût saisi avec chaque livraison ou changement.
+/// L'amortissement des immobilisations de la ferme est réparti chaque jour
+/// entre les bandes présentes, au prorata des poussins mis en place.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BilanFinancierBande {
+    pub bande_id: i64,
+    pub cout_soins: f64,
+    pub cout_soins_par_jour: Vec<CoutSoinsJour>,
+    pub soins_non_valorises: u32,
+    /// Coût des livraisons d'aliment (corrections comprises)
+    #[serde(default)]
+    pub cout_aliment: f64,
+    /// Livraisons enregistrées sans prix de l'aliment
+    #[serde(default)]
+    pub livraisons_non_valorisees: u32,
+    #[serde(default)]
+    pub cout_poussins: f64,
+    /// Bâtiments dont le prix des poussins n'était pas connu à l'entrée de la bande
+    #[serde(default)]
+    pub batiments_non_valorises: u32,
+    /// Coût des livraisons et changements de litière des bâtiments
+    #[serde(default)]
+    pub cout_litiere: f64,
+    /// Opérations de litière enregistrées sans coût
+    #[serde(default)]
+    pub litieres_non_valorisees: u32,
+    /// Part de l'amortissement des immobilisations de la ferme imputée à la bande
+    #[serde(default)]
+    pub cout_amortissement: f64,
+    /// Somme des coûts de l'aliment, des poussins, des soins, de la litière et de l'amortissement
+    #[serde(default)]
+    pub cout_total: f64,
+}
+
+impl BilanFinancierBande {
+    /// Coût des intrants consommés par la bande, hors amortissement
+    pub fn cout_intrants(&self) -> f64 {
+        self.cout_total - self.cout_amortissement
+    }
+}
+
+/// Budget prévisionnel d'une bande
+///
+/// Chaque poste est facultatif: un poste sans prévision n'a pas d'écart.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetBande {
+    /// Aliment consommé prévu sur le cycle (kg)
+    pub aliment_prevu_kg: Option<f64>,
+    /// Mortalité prévue en % des poussins placés
+    pub mortalite_prevue_pourcentage: Option<f64>,
+    pub cout_aliment_prevu: Option<f64>,
+    pub cout_poussins_prevu: Option<f64>,
+    pub cout_soins_prevu: Option<f64>,
+}
+
+/// Postes comparés par `get_budget_variance`
+pub const POSTE_ALIMENT_KG: &str = "aliment_kg";
+pub const POSTE_MORTALITE: &str = "mortalite_pourcentage";
+pub const POSTE_COUT_ALIMENT: &str = "cout_aliment";
+pub const POSTE_COUT_POUSSINS: &str = "cout_poussins";
+pub const POSTE_COUT_SOINS: &str = "cout_soins";
+pub const POSTE_COUT_TOTAL: &str = "cout_total";
+
+/// Écart entre le budget et le réalisé d'un poste
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EcartPoste {
+    pub poste: String,
+    pub prevu: Option<f64>,
+    /// Réalisé à ce jour
+    pub reel: f64,
+    /// Réalisé moins prévu (positif = dépassement)
+    pub ecart: Option<f64>,
+    /// Écart en % du prévu
+    pub ecart_pourcentage: Option<f64>,
+}
+
+impl EcartPoste {
+    pub fn calculer(poste: &str, prevu: Option<f64>, reel: f64) -> Self {
+        Self {
+            poste: poste.to_string(),
+            prevu,
+            reel,
+            ecart: prevu.map(|prevu| reel - prevu),
+            ecart_pourcentage: prevu.filter(|&prevu| prevu > 0.0).map(|prevu| (reel - prevu) / prevu * 100.0),
+        }
+    }
+}
+
+/// Comparaison du budget d'une bande à son réalisé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EcartBudgetBande {
+    pub bande_id: i64,
+    /// `None` si aucun budget n'a été défini
+    pub budget: Option<BudgetBande>,
+    /// Jours de suivi saisis (âge le plus élevé), pour situer le réalisé dans le cycle
+    pub age_atteint: i32,
+    pub postes: Vec<EcartPoste>,
+}
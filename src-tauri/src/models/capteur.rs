@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Formats d'export des contrôleurs d'ambiance acceptés à l'import
+pub const FORMATS_CAPTEURS: [&str; 2] = ["csv", "json"];
+
+/// Pas d'agrégation des mesures pour les graphiques
+pub const GRANULARITES_CAPTEURS: [&str; 2] = ["hour", "day"];
+
+/// Mesure d'ambiance horodatée d'un bâtiment physique
+///
+/// Au moins une des valeurs est renseignée.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MesureCapteur {
+    pub numero_batiment: String,
+    pub horodatage: String, // YYYY-MM-DD HH:MM:SS (heure locale du contrôleur)
+    /// Température (°C)
+    pub temperature: Option<f64>,
+    /// Humidité relative (%)
+    pub humidite: Option<f64>,
+    /// Dioxyde de carbone (ppm)
+    pub co2: Option<f64>,
+}
+
+/// Ligne d'un export refusée à l'import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LigneRejetee {
+    /// Numéro de ligne du fichier CSV ou rang (à partir de 1) de la mesure JSON
+    pub ligne: usize,
+    pub motif: String,
+}
+
+/// Résultat de l'import d'un export de contrôleur
+///
+/// Les mesures déjà importées (même bâtiment, même horodatage) sont remplacées:
+/// réimporter un export ne crée pas de doublons.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultatImportCapteurs {
+    pub lignes_lues: usize,
+    pub mesures_importees: usize,
+    /// Premières lignes refusées (50 au plus)
+    pub lignes_rejetees: Vec<LigneRejetee>,
+    pub nombre_lignes_rejetees: usize,
+}
+
+/// Agrégat des mesures d'un bâtiment sur une heure ou une journée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgregatCapteurs {
+    /// "YYYY-MM-DD HH:00" (heure) ou "YYYY-MM-DD" (jour)
+    pub periode: String,
+    pub nombre_mesures: i64,
+    pub temperature_min: Option<f64>,
+    pub temperature_max: Option<f64>,
+    pub temperature_moyenne: Option<f64>,
+    pub humidite_moyenne: Option<f64>,
+    pub co2_moyen: Option<f64>,
+    pub co2_max: Option<f64>,
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Entités pouvant porter des champs personnalisés
+pub const ENTITE_CHAMP_BANDE: &str = "bande";
+pub const ENTITE_CHAMP_BATIMENT: &str = "batiment";
+pub const ENTITES_CHAMP: [&str; 2] = [ENTITE_CHAMP_BANDE, ENTITE_CHAMP_BATIMENT];
+
+/// Types de valeur d'un champ personnalisé
+pub const TYPE_CHAMP_TEXTE: &str = "texte";
+pub const TYPE_CHAMP_NOMBRE: &str = "nombre";
+pub const TYPE_CHAMP_DATE: &str = "date";
+pub const TYPE_CHAMP_BOOLEEN: &str = "booleen";
+pub const TYPES_CHAMP: [&str; 4] = [TYPE_CHAMP_TEXTE, TYPE_CHAMP_NOMBRE, TYPE_CHAMP_DATE, TYPE_CHAMP_BOOLEEN];
+
+/// Valeurs des champs personnalisés d'une bande ou d'un bâtiment, par clé de champ
+///
+/// Les valeurs sont stockées en texte: nombre décimal, date AAAA-MM-JJ ou
+/// `true`/`false` selon le type du champ.
+pub type ValeursChamps = BTreeMap<String, String>;
+
+/// Champ défini par l'utilisateur pour suivre une donnée propre à la ferme
+/// (âge du troupeau parental, transporteur des poussins...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChampPersonnalise {
+    pub id: i64,
+    /// `bande` ou `batiment`
+    pub entite: String,
+    /// Identifiant du champ dans les payloads et les exports (minuscules, chiffres et `_`)
+    pub cle: String,
+    pub libelle: String,
+    pub type_valeur: String,
+    pub created_at: String,
+}
+
+/// Structure pour définir un nouveau champ personnalisé
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateChampPersonnalise {
+    pub entite: String,
+    pub cle: String,
+    pub libelle: String,
+    pub type_valeur: String,
+}
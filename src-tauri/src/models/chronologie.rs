@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+
+/// Types d'étapes de la chronologie d'une bande
+pub const JALON_SOIN: &str = "soin";
+pub const JALON_PHASE_ALIMENTATION: &str = "phase_alimentation";
+pub const JALON_ENLEVEMENT: &str = "enlevement";
+
+/// Étape prévue de la vie d'une bande (soin du plan, changement de phase
+/// d'aliment, enlèvement)
+///
+/// Une même étape prévue pour plusieurs bâtiments au même âge n'apparaît
+/// qu'une fois, avec la liste des bâtiments concernés.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JalonBande {
+    pub type_jalon: String,
+    pub age: i32,
+    pub date: NaiveDate,
+    pub libelle: String,
+    pub batiment_ids: Vec<i64>,
+    /// L'étape est antérieure à la date de référence
+    pub passe: bool,
+}
+
+/// Chronologie d'une bande pour l'en-tête de l'interface ("J23 / Semaine 4")
+///
+/// L'âge est compté à partir du jour d'entrée (jour 1) jusqu'à la date de
+/// référence: aujourd'hui, ou la date de clôture pour une bande clôturée.
+/// `age_jours` et `semaine_courante` valent 0 avant l'entrée de la bande.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChronologieBande {
+    pub bande_id: i64,
+    pub date_entree: NaiveDate,
+    pub date_reference: NaiveDate,
+    pub age_jours: i32,
+    pub semaine_courante: i32,
+    /// Enlèvement projeté du dernier bâtiment, à la fin de sa dernière semaine
+    pub age_enlevement: i32,
+    pub date_enlevement: NaiveDate,
+    pub jalons: Vec<JalonBande>,
+}
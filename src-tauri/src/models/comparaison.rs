@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+/// Indicateurs de performance agrégés sur un ensemble de bâtiments
+///
+/// Les ratios valent `None` lorsqu'aucun bâtiment du groupe ne fournit les
+/// données nécessaires (aucun poussin placé, aucun poids saisi...).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndicateursPerformance {
+    pub nombre_bandes: i64,
+    pub nombre_batiments: i64,
+    pub poussins_places: i64,
+    pub total_deces: i64,
+    /// Mortalité cumulée en % des poussins placés
+    pub mortalite_pourcentage: Option<f64>,
+    /// Sujets éliminés volontairement (tri, réforme), hors mortalité
+    #[serde(default)]
+    pub total_elimines: i64,
+    #[serde(default)]
+    pub elimines_pourcentage: Option<f64>,
+    /// Sujets restants (ni morts ni éliminés) en % des poussins placés
+    #[serde(default)]
+    pub viabilite_pourcentage: Option<f64>,
+    /// Aliment consommé (kg)
+    pub aliment_kg: f64,
+    /// Indice de consommation: kg d'aliment par kg de poids vif produit
+    pub indice_consommation: Option<f64>,
+    /// Poids moyen (kg) à la dernière pesée, pondéré par les sujets vivants
+    pub poids_moyen: Option<f64>,
+    /// Poids moyen (kg) à 35 jours (pesée de la semaine 5), pondéré par les sujets vivants
+    pub poids_35j: Option<f64>,
+    /// Coût de production par kg de poids vif, `None` tant qu'aucun prix n'est enregistré
+    pub cout_par_kg: Option<f64>,
+    /// Indice de production européen (EPEF) des bâtiments pesés, voir `indice_production`
+    #[serde(default)]
+    pub indice_production: Option<f64>,
+}
+
+/// Ligne d'un rapport de comparaison (ferme, technicien, type de poussin)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparaisonPerformance {
+    pub id: i64,
+    pub nom: String,
+    pub indicateurs: IndicateursPerformance,
+}
+
+/// Indice de production européen (EPEF)
+///
+/// `viabilité (%) × poids vif (kg) / (âge (jours) × indice de consommation) × 100`,
+/// `None` si l'âge ou l'indice de consommation est nul.
+///
+/// # Arguments
+/// * `viabilite_pourcentage` - Sujets vivants en % des poussins placés
+/// * `poids_kg` - Poids vif moyen à la dernière pesée
+/// * `age_jours` - Âge à la dernière pesée
+/// * `indice_consommation` - kg d'aliment par kg de poids vif produit
+pub fn indice_production(viabilite_pourcentage: f64, poids_kg: f64, age_jours: f64, indice_consommation: f64) -> Option<f64> {
+    (age_jours > 0.0 && indice_consommation > 0.0)
+        .then(|| viabilite_pourcentage * poids_kg / (age_jours * indice_consommation) * 100.0)
+}
+
+/// Indice de production d'une bande et de chacun de ses bâtiments
+///
+/// Chaque ligne de `batiments` porte l'ID et le numéro du bâtiment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndiceProductionBande {
+    pub bande_id: i64,
+    pub indicateurs: IndicateursPerformance,
+    pub batiments: Vec<ComparaisonPerformance>,
+}
+
+/// Saisons d'entrée des bandes, dans l'ordre de l'année
+pub const SAISONS: [&str; 4] = ["hiver", "printemps", "ete", "automne"];
+
+/// Saison d'un mois (1 à 12): l'hiver couvre décembre à février
+pub fn saison(mois: u32) -> &'static str {
+    SAISONS[(mois % 12 / 3) as usize]
+}
+
+/// Indicateurs des bandes entrées sur une période de l'année
+///
+/// `periode` est le mois ("01" à "12") ou la saison (voir `SAISONS`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformancePeriode {
+    pub periode: String,
+    pub indicateurs: IndicateursPerformance,
+}
+
+/// Performances des bandes clôturées selon leur mois et leur saison d'entrée
+///
+/// Tous les mois et toutes les saisons figurent, y compris sans bande.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatistiquesSaisonnieres {
+    pub ferme_id: Option<i64>,
+    pub mois: Vec<PerformancePeriode>,
+    pub saisons: Vec<PerformancePeriode>,
+}
@@ -0,0 +1,26 @@
+use crate::models::{CreateFerme, Ferme};
+use serde::{Deserialize, Serialize};
+
+/// Données de l'assistant de premier démarrage
+///
+/// Chaque ferme est créée avec son nombre de bâtiments (`nbr_meuble`); le
+/// nombre de semaines et le poids d'un sachet gardent leur valeur par
+/// défaut lorsqu'ils ne sont pas renseignés.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigurationInitiale {
+    pub fermes: Vec<CreateFerme>,
+    /// Nombre habituel de semaines de suivi d'une bande
+    #[serde(default)]
+    pub semaines_max: Option<i32>,
+    /// Poids d'un sachet d'aliment en kg
+    #[serde(default)]
+    pub kg_par_sachet: Option<f64>,
+}
+
+/// Configuration enregistrée par l'assistant de premier démarrage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigurationAppliquee {
+    pub fermes: Vec<Ferme>,
+    pub semaines_max: i32,
+    pub kg_par_sachet: f64,
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// Contrat d'élevage d'une bande (intégration)
+///
+/// L'éleveur est payé au kg de poids vif livré, au prix garanti. Les primes
+/// sont facultatives: une prime positive récompense un résultat meilleur que
+/// la cible, la même formule pénalise un résultat moins bon.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContratBande {
+    /// Prix garanti par kg de poids vif
+    pub prix_garanti_kg: f64,
+    /// Indice de consommation cible
+    pub ic_cible: Option<f64>,
+    /// Prime par kg de poids vif pour chaque centième d'indice de consommation sous la cible
+    pub prime_ic_par_centieme: Option<f64>,
+    /// Mortalité cible en % des poussins placés
+    pub mortalite_cible_pourcentage: Option<f64>,
+    /// Prime pour chaque point de mortalité sous la cible
+    pub prime_mortalite_par_point: Option<f64>,
+    /// Les intrants (poussins, aliment, soins, litière) fournis par l'intégrateur sont déduits du règlement
+    #[serde(default)]
+    pub deduire_intrants: bool,
+}
+
+/// Décompte prévisionnel du règlement d'une bande sous contrat
+///
+/// Les indicateurs sont ceux de l'indice de production de la bande: seuls les
+/// bâtiments pesés entrent dans le poids vif. Le décompte n'est `definitif`
+/// qu'une fois la bande clôturée.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReglementContrat {
+    pub bande_id: i64,
+    pub contrat: ContratBande,
+    /// Poids vif des sujets restants des bâtiments pesés (kg)
+    pub poids_vif_kg: f64,
+    /// Bâtiments sans pesée, absents du poids vif
+    pub batiments_non_peses: u32,
+    pub indice_consommation: Option<f64>,
+    pub mortalite_pourcentage: Option<f64>,
+    /// Poids vif au prix garanti
+    pub montant_base: f64,
+    /// Prime (positive) ou pénalité (négative) sur l'indice de consommation
+    pub prime_ic: f64,
+    /// Prime (positive) ou pénalité (négative) sur la mortalité
+    pub prime_mortalite: f64,
+    /// Coût total du bilan financier, lorsque le contrat prévoit de déduire les intrants
+    pub intrants_deduits: f64,
+    /// Base plus primes moins intrants
+    pub montant_net: f64,
+    pub definitif: bool,
+}
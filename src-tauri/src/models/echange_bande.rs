@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+/// Identifiant du format d'échange d'une bande
+pub const FORMAT_ECHANGE_BANDE: &str = "technicien-app/bande";
+
+/// Version du schéma d'échange produite par l'export
+///
+/// Elle n'augmente que si le sens d'un champ change ou si un champ devient
+/// obligatoire; un champ facultatif ajouté ne change pas la version. Les
+/// lecteurs ignorent les champs qu'ils ne connaissent pas.
+pub const VERSION_ECHANGE_BANDE: u32 = 1;
+
+/// Document d'échange d'une bande avec un autre logiciel d'élevage
+///
+/// Les référentiels (ferme, souche, technicien, soins) sont désignés par
+/// leur nom et non par leur ID, propre à chaque base. Les dates sont au
+/// format AAAA-MM-JJ, les poids en kg et l'aliment en sachets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EchangeBande {
+    /// Toujours `FORMAT_ECHANGE_BANDE`
+    pub format: String,
+    pub version: u32,
+    /// Date et heure de l'export (AAAA-MM-JJ HH:MM:SS)
+    pub exporte_le: String,
+    pub bande: BandeEchangee,
+}
+
+/// Bande d'un document d'échange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandeEchangee {
+    /// Nom de la ferme
+    pub ferme: String,
+    /// Numéro affiché dans l'application d'origine, à titre indicatif
+    #[serde(default)]
+    pub numero: Option<String>,
+    pub date_entree: String,
+    /// Renseignée si la bande est clôturée
+    #[serde(default)]
+    pub date_cloture: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub batiments: Vec<BatimentEchange>,
+}
+
+/// Bâtiment d'une bande échangée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatimentEchange {
+    pub numero_batiment: String,
+    /// Nom de la souche de poussins
+    pub souche: String,
+    /// Nom du technicien responsable
+    pub technicien: String,
+    /// Poussins placés
+    pub effectif: i32,
+    #[serde(default)]
+    pub semaines: Vec<SemaineEchangee>,
+}
+
+/// Semaine d'élevage d'un bâtiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemaineEchangee {
+    pub numero_semaine: i32,
+    /// Poids moyen (kg)
+    #[serde(default)]
+    pub poids: Option<f64>,
+    /// Jours saisis de la semaine; un jour absent n'a pas été renseigné
+    #[serde(default)]
+    pub jours: Vec<JourEchange>,
+}
+
+/// Jour de suivi d'une semaine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JourEchange {
+    /// Âge des sujets en jours, de 1 à 7 pour la première semaine
+    pub age: i32,
+    #[serde(default)]
+    pub deces: Option<i64>,
+    #[serde(default)]
+    pub elimines: Option<i64>,
+    /// Aliment distribué (sachets)
+    #[serde(default)]
+    pub aliment: Option<f64>,
+    #[serde(default)]
+    pub remarques: Option<String>,
+    #[serde(default)]
+    pub soins: Vec<SoinEchange>,
+}
+
+/// Traitement administré un jour de suivi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoinEchange {
+    /// Nom du soin
+    pub soin: String,
+    /// Quantité telle que saisie (ex: "5", "2,5 l")
+    #[serde(default)]
+    pub quantite: Option<String>,
+    #[serde(default)]
+    pub unite: Option<String>,
+}
+
+/// Résultat de l'import d'un document d'échange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RapportImportBande {
+    pub bande_id: i64,
+    /// Version du schéma du document importé
+    pub version: u32,
+    pub batiments: usize,
+    pub semaines: usize,
+    pub jours: usize,
+    pub soins: usize,
+}
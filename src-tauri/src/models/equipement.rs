@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// Types d'équipements acceptés
+pub const TYPES_EQUIPEMENT: [&str; 6] =
+    ["ventilateur", "mangeoire", "abreuvoir", "chauffage", "groupe_electrogene", "autre"];
+
+/// Nombre de jours avant l'échéance d'un entretien à partir duquel il est rappelé
+pub const DELAI_RAPPEL_ENTRETIEN_JOURS: i64 = 7;
+
+/// Équipement d'une ferme (ventilateur, mangeoire, groupe électrogène...)
+///
+/// Comme le vide sanitaire, un équipement installé dans un bâtiment porte sur
+/// le bâtiment physique (numéro dans la ferme); `numero_batiment` est vide
+/// pour un équipement commun à la ferme. Lorsque `intervalle_entretien_jours`
+/// est renseigné, `prochain_entretien` est le dernier entretien (ou, à défaut,
+/// la mise en service) plus l'intervalle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Equipement {
+    pub id: i64,
+    pub ferme_id: i64,
+    pub nom: String,
+    pub type_equipement: String,
+    pub numero_batiment: Option<String>,
+    pub date_mise_en_service: String, // YYYY-MM-DD
+    /// Intervalle entre deux entretiens, `None` sans entretien périodique
+    pub intervalle_entretien_jours: Option<i64>,
+    pub dernier_entretien: Option<String>,
+    pub prochain_entretien: Option<String>,
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer un équipement
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateEquipement {
+    pub ferme_id: i64,
+    pub nom: String,
+    pub type_equipement: String,
+    pub numero_batiment: Option<String>,
+    pub date_mise_en_service: String,
+    pub intervalle_entretien_jours: Option<i64>,
+}
+
+/// Structure pour mettre à jour un équipement
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateEquipement {
+    pub id: i64,
+    pub nom: String,
+    pub type_equipement: String,
+    pub numero_batiment: Option<String>,
+    pub date_mise_en_service: String,
+    pub intervalle_entretien_jours: Option<i64>,
+}
+
+/// Entretien réalisé sur un équipement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntretienEquipement {
+    pub id: i64,
+    pub equipement_id: i64,
+    pub date_entretien: String, // YYYY-MM-DD
+    pub description: Option<String>,
+    pub cout: Option<f64>,
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer un entretien
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateEntretienEquipement {
+    pub equipement_id: i64,
+    pub date_entretien: String,
+    pub description: Option<String>,
+    pub cout: Option<f64>,
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// Résultat de l'export d'un instantané pour les outils de reporting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RapportExport {
+    /// Chemin du fichier SQLite créé
+    pub chemin: String,
+    /// Nombre de lignes de la table `bande_facts`
+    pub bandes: i64,
+    /// Nombre de lignes de la table `daily_facts`
+    pub jours: i64,
+    pub genere_le: String,
+}
+
+/// Exports programmables
+pub const EXPORT_INSTANTANE: &str = "instantane";
+pub const EXPORT_RAPPORT_HEBDOMADAIRE: &str = "rapport_hebdomadaire";
+pub const EXPORT_COMPTABILITE: &str = "comptabilite_csv";
+pub const EXPORT_SAUVEGARDE: &str = "sauvegarde";
+/// Récapitulatif de TVA par trimestre de l'année en cours
+pub const EXPORT_TVA: &str = "tva_csv";
+pub const TYPES_EXPORT_PROGRAMME: [&str; 5] =
+    [EXPORT_INSTANTANE, EXPORT_RAPPORT_HEBDOMADAIRE, EXPORT_COMPTABILITE, EXPORT_SAUVEGARDE, EXPORT_TVA];
+
+/// Fréquences d'un export programmé
+pub const FREQUENCE_QUOTIDIENNE: &str = "quotidienne";
+pub const FREQUENCE_HEBDOMADAIRE: &str = "hebdomadaire";
+pub const FREQUENCE_MENSUELLE: &str = "mensuelle";
+pub const FREQUENCES_EXPORT: [&str; 3] = [FREQUENCE_QUOTIDIENNE, FREQUENCE_HEBDOMADAIRE, FREQUENCE_MENSUELLE];
+
+/// Statut de la dernière exécution d'un export programmé
+pub const STATUT_EXPORT_SUCCES: &str = "succes";
+pub const STATUT_EXPORT_ECHEC: &str = "echec";
+
+/// Export programmé vers un dossier (partage réseau, disque externe...)
+///
+/// L'export est lancé à `heure` chaque jour, chaque semaine le `jour` de la
+/// semaine (1 = lundi) ou chaque mois le `jour` du mois (1 à 28).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgramme {
+    pub id: i64,
+    pub type_export: String,
+    pub dossier: String,
+    pub frequence: String,
+    pub heure: u32,
+    pub jour: Option<u32>,
+    pub created_at: String,
+    /// `None` si l'export n'a pas encore été lancé
+    pub derniere_execution: Option<String>,
+    pub dernier_statut: Option<String>,
+    /// Fichier créé, ou message d'erreur
+    pub dernier_message: Option<String>,
+}
+
+/// Structure pour programmer un nouvel export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateExportProgramme {
+    pub type_export: String,
+    pub dossier: String,
+    pub frequence: String,
+    pub heure: u32,
+    #[serde(default)]
+    pub jour: Option<u32>,
+}
@@ -9,6 +9,8 @@ pub struct Ferme {
     pub id: Option<i64>,
     pub nom: String,
     pub nbr_meuble: i32,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
 }
 
 /// Structure pour créer une nouvelle ferme
@@ -30,4 +32,8 @@ pub struct UpdateFerme {
     pub id: i64,
     pub nom: String,
     pub nbr_meuble: i32,
+    /// Version chargée par le client: la mise à jour est refusée si la ligne
+    /// a été modifiée depuis (`None` désactive le contrôle)
+    #[serde(default)]
+    pub version: Option<i64>,
 }
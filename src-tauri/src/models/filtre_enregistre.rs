@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Listes pouvant recevoir un filtre enregistré
+pub const FILTRE_ENTITE_BANDES: &str = "bandes";
+pub const ENTITES_FILTRE: [&str; 1] = [FILTRE_ENTITE_BANDES];
+
+/// Critères de la liste paginée des bandes, un critère absent ne filtrant pas
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FiltreBandes {
+    /// Entrée à partir de cette date (AAAA-MM-JJ)
+    pub date_from: Option<String>,
+    /// Entrée jusqu'à cette date (AAAA-MM-JJ)
+    pub date_to: Option<String>,
+    pub tag_id: Option<i64>,
+    /// `active` ou `cloturee`
+    pub statut: Option<String>,
+    /// Mortalité minimale de la bande, en pourcentage de l'effectif initial
+    pub mortalite_min: Option<f64>,
+}
+
+impl FiltreBandes {
+    /// Complète les critères absents par ceux d'un filtre enregistré
+    pub fn ou(self, enregistre: FiltreBandes) -> FiltreBandes {
+        FiltreBandes {
+            date_from: self.date_from.or(enregistre.date_from),
+            date_to: self.date_to.or(enregistre.date_to),
+            tag_id: self.tag_id.or(enregistre.tag_id),
+            statut: self.statut.or(enregistre.statut),
+            mortalite_min: self.mortalite_min.or(enregistre.mortalite_min),
+        }
+    }
+}
+
+/// Jeu de filtres nommé, propre à un utilisateur (ex: « Mortalité élevée 2024 »)
+///
+/// `filtre` suit la structure des critères de l'entité (`FiltreBandes` pour `bandes`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiltreEnregistre {
+    pub id: i64,
+    pub user_id: i64,
+    pub nom: String,
+    pub entite: String,
+    pub filtre: Value,
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer un filtre (un filtre du même nom est remplacé)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateFiltreEnregistre {
+    pub nom: String,
+    pub entite: String,
+    pub filtre: Value,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Entités dont les doublons peuvent être fusionnés
+pub const ENTITES_FUSION: [&str; 4] = ["personnel", "soin", "poussin", "maladie"];
+
+/// Résultat de la fusion d'un doublon dans l'entité conservée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultatFusion {
+    pub entity: String,
+    /// ID du doublon, supprimé par la fusion
+    pub source_id: i64,
+    /// ID de l'entité conservée
+    pub target_id: i64,
+    pub nom_conserve: String,
+    /// Nombre de lignes rattachées à l'entité conservée
+    pub lignes_rattachees: i64,
+}
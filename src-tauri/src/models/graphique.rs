@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Métriques disponibles en séries prêtes à tracer
+pub const METRIQUE_MORTALITE: &str = "mortalite";
+pub const METRIQUE_ALIMENTATION: &str = "alimentation";
+pub const METRIQUE_POIDS: &str = "poids";
+pub const METRIQUE_TEMPERATURE: &str = "temperature";
+
+/// Fenêtre de lissage maximale (nombre de points de la moyenne mobile)
+pub const LISSAGE_MAX: u32 = 30;
+
+/// Point d'une série: `x` est une date (YYYY-MM-DD)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointSerie {
+    pub x: String,
+    pub y: f64,
+}
+
+/// Série d'une métrique pour un bâtiment ou une bande, prête à tracer
+///
+/// Avec `lissage`, chaque point est la moyenne mobile des `lissage` derniers
+/// points (moins en début de série).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerieGraphique {
+    pub metrique: String,
+    pub unite: String,
+    pub lissage: Option<u32>,
+    pub points: Vec<PointSerie>,
+}
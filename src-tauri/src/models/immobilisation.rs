@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Catégories d'immobilisations acceptées
+pub const CATEGORIES_IMMOBILISATION: [&str; 3] = ["batiment", "equipement", "autre"];
+
+/// Bien durable d'une ferme (bâtiment, équipement) amorti sur plusieurs années
+///
+/// L'amortissement est linéaire à partir de la date d'achat:
+/// `date_fin_amortissement` est la date d'achat plus la durée, et la dotation
+/// d'une période est la valeur au prorata des jours compris dans la période.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Immobilisation {
+    pub id: i64,
+    pub ferme_id: i64,
+    pub nom: String,
+    pub categorie: String,
+    pub date_achat: String, // YYYY-MM-DD
+    /// Valeur d'achat
+    pub valeur: f64,
+    pub duree_amortissement_annees: i32,
+    pub date_fin_amortissement: String,
+    /// Dotation d'une année pleine (valeur / durée)
+    pub amortissement_annuel: f64,
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer une immobilisation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateImmobilisation {
+    pub ferme_id: i64,
+    pub nom: String,
+    pub categorie: String,
+    pub date_achat: String,
+    pub valeur: f64,
+    pub duree_amortissement_annees: i32,
+}
+
+/// Structure pour mettre à jour une immobilisation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateImmobilisation {
+    pub id: i64,
+    pub nom: String,
+    pub categorie: String,
+    pub date_achat: String,
+    pub valeur: f64,
+    pub duree_amortissement_annees: i32,
+}
+
+/// Dotation d'une immobilisation sur une année civile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LigneAmortissement {
+    pub immobilisation_id: i64,
+    pub nom: String,
+    pub categorie: String,
+    pub dotation: f64,
+    /// Valeur restant à amortir au 31 décembre
+    pub valeur_nette_fin_annee: f64,
+}
+
+/// Tableau d'amortissement d'une ferme pour une année civile
+///
+/// Seules les immobilisations amorties pendant l'année figurent dans `lignes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableauAmortissement {
+    pub ferme_id: i64,
+    pub annee: i32,
+    pub lignes: Vec<LigneAmortissement>,
+    pub total: f64,
+}
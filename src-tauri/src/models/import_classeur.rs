@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Feuille d'un classeur Excel de l'ancien système de suivi
+///
+/// Le classeur est lu par le frontend; chaque cellule est transmise sous
+/// forme de texte (date au format de la cellule ou numéro de série Excel).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeuilleClasseur {
+    pub nom: String,
+    /// Lignes de la feuille, de haut en bas, cellule par cellule
+    pub lignes: Vec<Vec<String>>,
+}
+
+/// Problème relevé à la lecture d'une feuille
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErreurImport {
+    /// Numéro de la ligne dans la feuille, 0 pour la feuille entière
+    pub ligne: usize,
+    pub motif: String,
+}
+
+/// Résultat de la lecture d'une feuille (une bande)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RapportFeuilleImport {
+    pub feuille: String,
+    pub ferme: Option<String>,
+    pub date_entree: Option<String>,
+    pub batiments: usize,
+    /// Lignes du tableau de suivi lues
+    pub lignes_lues: usize,
+    /// Lignes sans aucune valeur saisie
+    pub lignes_ignorees: usize,
+    pub lignes_en_erreur: usize,
+    pub erreurs: Vec<ErreurImport>,
+    /// Bande créée, `None` lors d'un essai
+    pub bande_id: Option<i64>,
+}
+
+/// Rapport d'import d'un classeur, feuille par feuille
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RapportImportClasseur {
+    /// Vrai si rien n'a été enregistré (essai)
+    pub essai: bool,
+    pub feuilles: Vec<RapportFeuilleImport>,
+    pub lignes_lues: usize,
+    pub lignes_ignorees: usize,
+    pub lignes_en_erreur: usize,
+    pub bandes_creees: usize,
+}
@@ -0,0 +1,33 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Code d'invitation permettant de créer un compte
+///
+/// Les codes sont créés et révoqués par un administrateur; le compte créé
+/// reçoit le rôle de l'invitation. Un code à usage unique n'est plus valable
+/// une fois utilisé.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invitation {
+    pub id: i64,
+    pub code: String,
+    /// `admin` ou `technicien`
+    pub role: String,
+    /// Dernier jour de validité, `None` si le code n'expire pas
+    pub expires_on: Option<NaiveDate>,
+    pub single_use: bool,
+    /// Nombre de comptes créés avec ce code
+    pub use_count: i64,
+    pub revoked: bool,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+/// Structure pour créer un code d'invitation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInvitation {
+    /// Code choisi par l'administrateur, généré s'il est absent
+    pub code: Option<String>,
+    pub role: String,
+    pub expires_on: Option<NaiveDate>,
+    pub single_use: bool,
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+use crate::models::{Alerte, PlanSoin};
+
+/// Saisie d'un jour de suivi d'un bâtiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaisieJour {
+    pub age: i32,
+    pub date: NaiveDate,
+    pub deces_par_jour: Option<i32>,
+    pub elimines_par_jour: Option<i32>,
+    pub alimentation_par_jour: Option<f64>,
+}
+
+/// Ce que le technicien doit voir pour un bâtiment en début de journée
+///
+/// `saisie_hier_manquante` indique que les décès ou l'alimentation de la
+/// veille ne sont pas saisis (toujours faux le jour d'entrée de la bande).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JourneeBatiment {
+    pub ferme_id: i64,
+    pub ferme_nom: String,
+    pub bande_id: i64,
+    pub numero_bande: String,
+    pub batiment_id: i64,
+    pub numero_batiment: String,
+    /// Âge des sujets aujourd'hui (jour 1 = date d'entrée de la bande)
+    pub age_jours: i32,
+    pub hier: Option<SaisieJour>,
+    pub saisie_hier_manquante: bool,
+    /// Soins du plan prévus aujourd'hui pour le type de poussin du bâtiment
+    pub soins_prevus: Vec<PlanSoin>,
+    /// Autres tâches du jour (pesée de fin de semaine, changement d'aliment)
+    pub taches: Vec<String>,
+    pub alertes: Vec<Alerte>,
+}
+
+/// Vue du jour sur tous les bâtiments des bandes actives
+///
+/// `autres_alertes` regroupe les alertes qui ne portent sur aucun de ces
+/// bâtiments (bâtiments vides prêts à recevoir une bande...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VueJournee {
+    pub date: NaiveDate,
+    pub batiments: Vec<JourneeBatiment>,
+    pub autres_alertes: Vec<Alerte>,
+}
@@ -0,0 +1,63 @@
+use crate::models::SaisieTva;
+use serde::{Deserialize, Serialize};
+
+/// Opérations de litière reconnues: apport de litière neuve ou renouvellement
+/// (retrait de la litière usagée et remplacement)
+pub const TYPES_OPERATION_LITIERE: [&str; 2] = ["livraison", "changement"];
+
+/// Unités de quantité de litière acceptées
+pub const UNITES_LITIERE: [&str; 4] = ["kg", "botte", "sac", "m3"];
+
+/// Livraison ou changement de litière d'un bâtiment
+///
+/// `cout` est le coût total hors taxes de l'opération; il entre dans le bilan
+/// financier de la bande du bâtiment. `None` lorsque le coût n'est pas connu.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Litiere {
+    pub id: Option<i64>,
+    pub batiment_id: i64,
+    pub type_operation: String,
+    /// Matériau utilisé (paille, copeaux, balle de riz...)
+    pub materiau: String,
+    pub quantite: f64,
+    pub unite: String,
+    pub cout: Option<f64>,
+    /// Taux de TVA en %
+    #[serde(default)]
+    pub taux_tva: Option<f64>,
+    /// Montant de TVA saisi, à défaut calculé à partir du taux et du coût
+    #[serde(default)]
+    pub montant_tva: Option<f64>,
+    pub date_operation: String, // YYYY-MM-DD
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer une opération de litière
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateLitiere {
+    pub batiment_id: i64,
+    pub type_operation: String,
+    pub materiau: String,
+    pub quantite: f64,
+    pub unite: String,
+    pub cout: Option<f64>,
+    /// TVA du coût, saisi hors taxes ou TTC
+    #[serde(default, flatten)]
+    pub tva: SaisieTva,
+    pub date_operation: String,
+}
+
+/// Structure pour mettre à jour une opération de litière existante
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateLitiere {
+    pub id: i64,
+    pub type_operation: String,
+    pub materiau: String,
+    pub quantite: f64,
+    pub unite: String,
+    pub cout: Option<f64>,
+    /// TVA du coût, saisi hors taxes ou TTC
+    #[serde(default, flatten)]
+    pub tva: SaisieTva,
+    pub date_operation: String,
+}
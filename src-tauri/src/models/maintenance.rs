@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// Paramètre: "1" si la maintenance est lancée chaque mois par la tâche de fond
+pub const PARAMETRE_MAINTENANCE_MENSUELLE: &str = "maintenance_mensuelle";
+
+/// Paramètre: date et heure de la dernière maintenance (`YYYY-MM-DD HH:MM:SS`)
+pub const PARAMETRE_DERNIERE_MAINTENANCE: &str = "derniere_maintenance";
+
+/// Rapport d'une maintenance de la base de données
+///
+/// Les tailles couvrent la base principale et la base d'archive, hors journal WAL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RapportMaintenance {
+    pub taille_avant: i64,
+    pub taille_apres: i64,
+    /// Index plein texte reconstruits
+    pub index_reconstruits: Vec<String>,
+    pub duree_ms: i64,
+    pub effectuee_le: String,
+}
+
+/// Programmation de la maintenance automatique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgrammationMaintenance {
+    pub mensuelle: bool,
+    /// `None` si aucune maintenance n'a encore été faite
+    pub derniere_maintenance: Option<String>,
+}
+
+/// Résultat d'une vérification d'intégrité de la base de données
+///
+/// Couvre la base principale et la base d'archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RapportIntegrite {
+    /// Vrai si aucun problème n'a été relevé
+    pub integre: bool,
+    /// Problèmes de structure relevés par SQLite (`PRAGMA integrity_check`)
+    pub erreurs: Vec<String>,
+    /// Lignes dont une clé étrangère désigne une ligne absente
+    pub references_orphelines: Vec<String>,
+    pub verifiee_le: String,
+}
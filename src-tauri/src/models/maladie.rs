@@ -29,3 +29,16 @@ pub struct PaginatedMaladies {
     pub has_next: bool,
     pub has_prev: bool,
 }
+
+/// Granularités acceptées par `get_maladie_trends`
+pub const GRANULARITES_TENDANCE: [&str; 2] = ["month", "quarter"];
+
+/// Nombre de bâtiments touchés par une maladie sur une période
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TendanceMaladie {
+    /// Période de déclaration: "YYYY-MM" par mois, "YYYY-T1".."YYYY-T4" par trimestre
+    pub periode: String,
+    pub maladie_id: i64,
+    pub maladie_nom: String,
+    pub batiments_affectes: i64,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Message de passation laissé par un utilisateur à un collègue ou sur un bâtiment
+///
+/// Un message a un destinataire, un bâtiment, ou les deux: un message sur un
+/// bâtiment sans destinataire s'adresse à toute l'équipe qui prend la relève.
+/// `lu` passe à vrai dès qu'un lecteur le marque comme lu.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub id: i64,
+    /// `None` si le compte de l'auteur a été supprimé
+    pub auteur_id: Option<i64>,
+    pub auteur: Option<String>,
+    pub destinataire_id: Option<i64>,
+    pub batiment_id: Option<i64>,
+    pub numero_batiment: Option<String>,
+    pub contenu: String,
+    pub lu: bool,
+    pub created_at: String, // YYYY-MM-DD HH:MM:SS (UTC)
+}
+
+/// Structure pour poster un message de passation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateMessage {
+    pub destinataire_id: Option<i64>,
+    pub batiment_id: Option<i64>,
+    pub contenu: String,
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Température maximale (°C) à partir de laquelle une journée compte comme
+/// journée de vague de chaleur (stress thermique des poulets de chair)
+pub const SEUIL_VAGUE_CHALEUR_C: f64 = 32.0;
+
+/// Relevé météo journalier d'une ferme
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeteoJour {
+    pub ferme_id: i64,
+    pub date: String, // YYYY-MM-DD
+    pub temperature_min: Option<f64>,
+    pub temperature_max: Option<f64>,
+    pub temperature_moyenne: Option<f64>,
+    /// Humidité relative moyenne (%)
+    pub humidite_moyenne: Option<f64>,
+}
+
+/// Résultat de la synchronisation météo d'une ferme
+///
+/// `erreur` est renseignée lorsque la ferme n'a pas de coordonnées ou que le
+/// service météo n'a pas répondu (hors ligne): les autres fermes sont tout de
+/// même synchronisées.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynchronisationMeteo {
+    pub ferme_id: i64,
+    pub ferme_nom: String,
+    pub jours_enregistres: i64,
+    pub erreur: Option<String>,
+}
+
+/// Mortalité d'une journée rapprochée de la météo de la ferme
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MortaliteMeteoJour {
+    pub date: String, // YYYY-MM-DD
+    pub deces: i64,
+    /// Sujets placés dans les bâtiments suivis ce jour-là
+    pub effectif: i64,
+    /// Mortalité du jour en % de l'effectif
+    pub mortalite_pourcentage: Option<f64>,
+    pub temperature_max: Option<f64>,
+    pub humidite_moyenne: Option<f64>,
+    /// Température maximale supérieure ou égale à `SEUIL_VAGUE_CHALEUR_C`
+    pub vague_de_chaleur: bool,
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Mesures cumulées des exécutions d'une commande
+///
+/// Enregistrées uniquement dans la base locale, jamais envoyées: elles
+/// servent à repérer les opérations lentes sur le poste de la ferme.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetriqueCommande {
+    /// Nom de la commande Tauri, ex: "run_report"
+    pub commande: String,
+    pub appels: i64,
+    /// Nombre d'exécutions terminées par une erreur
+    pub echecs: i64,
+    pub duree_totale_ms: i64,
+    pub duree_moyenne_ms: f64,
+    pub duree_max_ms: i64,
+    /// Date de la dernière exécution (YYYY-MM-DD HH:MM:SS)
+    pub derniere_execution: Option<String>,
+}
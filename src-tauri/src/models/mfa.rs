@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Second facteur d'authentification (TOTP) d'un utilisateur
+///
+/// Le secret est enregistré dès le début de l'enrôlement; il n'est exigé à
+/// la connexion qu'une fois l'enrôlement confirmé par un premier code.
+#[derive(Debug, Clone)]
+pub struct UserMfa {
+    pub user_id: i64,
+    /// Secret partagé, encodé en base32
+    pub secret: String,
+    pub enabled: bool,
+    /// Pas de temps du dernier code accepté: un code ne sert qu'une fois
+    pub last_used_step: Option<i64>,
+}
+
+/// Informations d'enrôlement à afficher à l'utilisateur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaEnrollment {
+    /// Secret à saisir manuellement si le QR code ne peut pas être scanné
+    pub secret: String,
+    /// URI `otpauth://` à afficher sous forme de QR code
+    pub provisioning_uri: String,
+}
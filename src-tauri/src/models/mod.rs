@@ -14,6 +14,47 @@ pub mod user;
 pub mod alimentation;
 pub mod maladie;
 pub mod poussin;
+pub mod programme_alimentation;
+pub mod alerte;
+pub mod analyse;
+pub mod note_batiment;
+pub mod message;
+pub mod litiere;
+pub mod vide_sanitaire;
+pub mod activite;
+pub mod comparaison;
+pub mod suppression;
+pub mod fusion;
+pub mod audit;
+pub mod plan_ferme;
+pub mod meteo;
+pub mod capteur;
+pub mod invitation;
+pub mod mfa;
+pub mod archive;
+pub mod maintenance;
+pub mod export;
+pub mod verrouillage;
+pub mod plan_soins;
+pub mod bilan;
+pub mod prix;
+pub mod rapport_personnalise;
+pub mod sante;
+pub mod graphique;
+pub mod champ_personnalise;
+pub mod tag;
+pub mod filtre_enregistre;
+pub mod chronologie;
+pub mod journee;
+pub mod contrat;
+pub mod tva;
+pub mod immobilisation;
+pub mod equipement;
+pub mod configuration;
+pub mod import_classeur;
+pub mod echange_bande;
+pub mod webhook;
+pub mod metrique_commande;
 
 // Re-export all models for easy access
 pub use ferme::*;
@@ -27,3 +68,44 @@ pub use user::*;
 pub use alimentation::*;
 pub use maladie::*;
 pub use poussin::*;
+pub use programme_alimentation::*;
+pub use alerte::*;
+pub use analyse::*;
+pub use note_batiment::*;
+pub use message::*;
+pub use litiere::*;
+pub use vide_sanitaire::*;
+pub use activite::*;
+pub use comparaison::*;
+pub use suppression::*;
+pub use fusion::*;
+pub use audit::*;
+pub use plan_ferme::*;
+pub use meteo::*;
+pub use capteur::*;
+pub use invitation::*;
+pub use mfa::*;
+pub use archive::*;
+pub use maintenance::*;
+pub use export::*;
+pub use verrouillage::*;
+pub use plan_soins::*;
+pub use bilan::*;
+pub use prix::*;
+pub use rapport_personnalise::*;
+pub use sante::*;
+pub use graphique::*;
+pub use champ_personnalise::*;
+pub use tag::*;
+pub use filtre_enregistre::*;
+pub use chronologie::*;
+pub use journee::*;
+pub use contrat::*;
+pub use tva::*;
+pub use immobilisation::*;
+pub use equipement::*;
+pub use configuration::*;
+pub use import_classeur::*;
+pub use echange_bande::*;
+pub use webhook::*;
+pub use metrique_commande::*;
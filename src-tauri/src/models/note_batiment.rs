@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Note libre rattachée à un bâtiment
+/// 
+/// Les notes forment une chronologie en ajout seul des observations qui ne
+/// rentrent pas dans une cellule du suivi quotidien (comportement, état de
+/// la litière, matériel...). `tag` permet de les regrouper ou les filtrer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteBatiment {
+    pub id: i64,
+    pub batiment_id: i64,
+    pub auteur: String,
+    pub contenu: String,
+    pub tag: Option<String>,
+    pub created_at: String, // YYYY-MM-DD HH:MM:SS (UTC)
+}
+
+/// Structure pour ajouter une note à un bâtiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateNoteBatiment {
+    pub batiment_id: i64,
+    pub auteur: String,
+    pub contenu: String,
+    pub tag: Option<String>,
+}
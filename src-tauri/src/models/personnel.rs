@@ -11,6 +11,8 @@ pub struct Personnel {
     pub nom: String,
     pub telephone: String,
     pub created_at: DateTime<Utc>,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
 }
 
 /// Structure pour créer un nouveau membre du personnel
@@ -32,6 +34,10 @@ pub struct UpdatePersonnel {
     pub id: i64,
     pub nom: String,
     pub telephone: String,
+    /// Version chargée par le client: la mise à jour est refusée si la ligne
+    /// a été modifiée depuis (`None` désactive le contrôle)
+    #[serde(default)]
+    pub version: Option<i64>,
 }
 
 /// Structure pour les résultats paginés du personnel
@@ -0,0 +1,79 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Position d'un bâtiment physique de la ferme sur le plan du site
+///
+/// Un bâtiment physique est identifié par son numéro dans la ferme (1 à
+/// `nbr_meuble`): la position est conservée d'une bande à l'autre.
+/// `plan_x`/`plan_y` (coin supérieur gauche), `largeur` et `hauteur` sont
+/// exprimés dans l'unité du plan choisie par le frontend; `latitude` et
+/// `longitude` en degrés décimaux (WGS 84).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionBatiment {
+    pub ferme_id: i64,
+    pub numero_batiment: String,
+    pub plan_x: Option<f64>,
+    pub plan_y: Option<f64>,
+    pub largeur: Option<f64>,
+    pub hauteur: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Occupation actuelle d'un bâtiment physique par une bande en cours
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OccupationBatiment {
+    pub batiment_id: i64,
+    pub bande_id: i64,
+    pub numero_bande: String,
+    pub age_jours: i64,
+    pub effectif_initial: i64,
+    pub deces: i64,
+    pub effectif_vivant: i64,
+    /// Mortalité cumulée en % de l'effectif initial
+    pub mortalite_pourcentage: f64,
+}
+
+/// Bâtiment physique sur le plan: sa position (si elle a été saisie) et son
+/// occupation (si une bande en cours l'utilise)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatimentPlan {
+    pub numero_batiment: String,
+    pub position: Option<PositionBatiment>,
+    pub occupation: Option<OccupationBatiment>,
+}
+
+/// Plan du site d'une ferme avec l'état actuel de chaque bâtiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanFerme {
+    pub ferme_id: i64,
+    pub ferme_nom: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub batiments: Vec<BatimentPlan>,
+}
+
+/// Période d'occupation d'un bâtiment physique par une bande
+///
+/// L'occupation va de l'entrée de la bande au dernier jour de la dernière
+/// semaine du bâtiment (`SEMAINES_SUIVI` semaines tant qu'aucune n'a été
+/// créée), ou à la clôture de la bande si elle est antérieure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntervalleOccupation {
+    pub batiment_id: i64,
+    pub bande_id: i64,
+    pub numero_bande: String,
+    pub statut: String,
+    pub date_debut: NaiveDate,
+    pub date_fin: NaiveDate,
+    /// Vrai si la période chevauche celle d'une autre bande dans le même bâtiment
+    pub chevauchement: bool,
+}
+
+/// Occupation d'un bâtiment physique sur une période, pour le diagramme de Gantt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChronologieBatiment {
+    pub numero_batiment: String,
+    /// Périodes d'occupation triées par date d'entrée
+    pub intervalles: Vec<IntervalleOccupation>,
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Représente un soin du plan de traitement d'un type de poussin
+///
+/// Le soin est donné chaque jour d'âge de `jour_debut` à `jour_fin` (inclus).
+/// Il est reporté dans le suivi quotidien des bâtiments de ce type de poussin
+/// à la création de chaque semaine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanSoin {
+    pub id: Option<i64>,
+    pub poussin_id: i64,
+    pub soin_id: i64,
+    pub soin_nom: String,
+    pub jour_debut: i32,
+    pub jour_fin: i32,
+    pub quantite: Option<String>,
+    /// Unité de la quantité, celle du soin si elle est absente
+    pub unit: Option<String>,
+}
+
+/// Structure pour ajouter un soin au plan d'un type de poussin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePlanSoin {
+    pub poussin_id: i64,
+    pub soin_id: i64,
+    pub jour_debut: i32,
+    pub jour_fin: i32,
+    pub quantite: Option<String>,
+    pub unit: Option<String>,
+}
+
+/// Structure pour mettre à jour un soin du plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePlanSoin {
+    pub id: i64,
+    pub soin_id: i64,
+    pub jour_debut: i32,
+    pub jour_fin: i32,
+    pub quantite: Option<String>,
+    pub unit: Option<String>,
+}
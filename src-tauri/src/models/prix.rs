@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// Articles suivis dans l'historique des prix
+pub const ARTICLE_ALIMENT: &str = "aliment";
+pub const ARTICLE_POUSSIN: &str = "poussin";
+
+/// Paramètre: code de la devise des prix saisis
+pub const PARAMETRE_DEVISE: &str = "devise";
+
+/// Devise proposée pour les prix et les montants
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Devise {
+    /// Code ISO 4217
+    pub code: &'static str,
+    /// Symbole affiché après le montant
+    pub symbole: &'static str,
+    /// Nombre de décimales affichées
+    pub decimales: usize,
+}
+
+/// Devises acceptées par `PARAMETRE_DEVISE`; la première est utilisée tant
+/// que le paramètre n'est pas défini
+pub const DEVISES: [Devise; 5] = [
+    Devise { code: "MAD", symbole: "DH", decimales: 2 },
+    Devise { code: "EUR", symbole: "€", decimales: 2 },
+    Devise { code: "USD", symbole: "$", decimales: 2 },
+    Devise { code: "XOF", symbole: "FCFA", decimales: 0 },
+    Devise { code: "DZD", symbole: "DA", decimales: 2 },
+];
+
+/// Devise d'un code ISO 4217 (insensible à la casse), `None` si elle n'est pas dans `DEVISES`
+pub fn trouver_devise(code: &str) -> Option<Devise> {
+    DEVISES.iter().copied().find(|devise| devise.code.eq_ignore_ascii_case(code.trim()))
+}
+
+/// Met un montant au format français: milliers séparés par une espace
+/// insécable, virgule décimale et symbole de la devise ("1 234,50 DH")
+pub fn formater_montant(montant: f64, devise: &Devise) -> String {
+    format!("{}\u{a0}{}", formater_nombre(montant, devise.decimales, Some('\u{a0}')), devise.symbole)
+}
+
+/// Met un montant au format des CSV: virgule décimale, sans séparateur de
+/// milliers ni symbole, pour qu'Excel en français le lise comme un nombre
+pub fn montant_csv(montant: f64, devise: &Devise) -> String {
+    formater_nombre(montant, devise.decimales, None)
+}
+
+fn formater_nombre(valeur: f64, decimales: usize, separateur_milliers: Option<char>) -> String {
+    let texte = format!("{:.*}", decimales, valeur);
+    let (signe, texte) = match texte.strip_prefix('-') {
+        // "-0,00" s'affiche sans signe
+        Some(reste) if reste.contains(|c: char| matches!(c, '1'..='9')) => ("-", reste),
+        Some(reste) => ("", reste),
+        None => ("", texte.as_str()),
+    };
+    let (entier, decimales) = texte.split_once('.').unwrap_or((texte, ""));
+
+    let mut nombre = signe.to_string();
+    for (i, chiffre) in entier.chars().enumerate() {
+        if let Some(separateur) = separateur_milliers.filter(|_| i > 0 && (entier.len() - i) % 3 == 0) {
+            nombre.push(separateur);
+        }
+        nombre.push(chiffre);
+    }
+    if !decimales.is_empty() {
+        nombre.push(',');
+        nombre.push_str(decimales);
+    }
+    nombre
+}
+
+/// Prix d'un article à partir d'une date
+///
+/// Le prix de l'aliment est exprimé par kg, celui d'un poussin par sujet
+/// (pour le type de poussin `poussin_id`). Un prix reste en vigueur jusqu'à
+/// la `date_debut` du prix suivant du même article.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrixHistorique {
+    pub id: i64,
+    pub article: String,
+    /// Type de poussin, `None` pour l'aliment
+    pub poussin_id: Option<i64>,
+    pub prix_unitaire: f64,
+    /// Devise en vigueur lors de l'enregistrement du prix
+    pub devise: String,
+    /// Date d'entrée en vigueur (YYYY-MM-DD)
+    pub date_debut: String,
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer un nouveau prix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePrixHistorique {
+    pub article: String,
+    pub poussin_id: Option<i64>,
+    pub prix_unitaire: f64,
+    /// Date d'entrée en vigueur (YYYY-MM-DD)
+    pub date_debut: String,
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+/// Poids d'un sachet d'aliment en kg (unité de saisie de `alimentation_par_jour`)
+/// tant que le paramètre `PARAMETRE_KG_PAR_SACHET` n'est pas défini
+pub const KG_PAR_SACHET: f64 = 50.0;
+
+/// Paramètre: poids d'un sachet d'aliment en kg
+pub const PARAMETRE_KG_PAR_SACHET: &str = "kg_par_sachet";
+
+/// Valeurs acceptées pour le poids d'un sachet (kg)
+pub const PLAGE_KG_PAR_SACHET: std::ops::RangeInclusive<f64> = 1.0..=100.0;
+
+/// Représente une phase du programme d'alimentation d'un type de poussin
+///
+/// Une phase couvre les jours d'âge `jour_debut` à `jour_fin` (inclus) avec
+/// une consommation attendue en grammes par sujet et par jour.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseAlimentation {
+    pub id: Option<i64>,
+    pub poussin_id: i64,
+    pub nom_phase: String,
+    pub jour_debut: i32,
+    pub jour_fin: i32,
+    pub grammes_par_sujet: f64,
+}
+
+/// Structure pour ajouter une phase au programme d'un type de poussin
+///
+/// Si `jour_debut` n'est pas fourni, la phase commence le lendemain de la
+/// dernière phase existante (ou au jour 1 pour la première phase).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePhaseAlimentation {
+    pub poussin_id: i64,
+    pub nom_phase: String,
+    pub jour_debut: Option<i32>,
+    pub jour_fin: i32,
+    pub grammes_par_sujet: f64,
+}
+
+/// Structure pour mettre à jour une phase existante
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePhaseAlimentation {
+    pub id: i64,
+    pub nom_phase: String,
+    pub jour_debut: i32,
+    pub jour_fin: i32,
+    pub grammes_par_sujet: f64,
+}
+
+/// Consommation réelle d'un jour comparée au programme
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConformiteAlimentationJour {
+    pub age: i32,
+    pub nom_phase: Option<String>,
+    /// Effectif vivant en début de journée
+    pub effectif: i64,
+    pub grammes_attendus: Option<f64>,
+    pub grammes_reels: Option<f64>,
+    /// Écart en % entre le réel et l'attendu (positif = surconsommation)
+    pub ecart_pourcentage: Option<f64>,
+}
+
+/// Synthèse de la conformité sur une phase du programme
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConformitePhase {
+    pub nom_phase: String,
+    pub jour_debut: i32,
+    pub jour_fin: i32,
+    pub grammes_attendus: f64,
+    pub grammes_reels_moyens: Option<f64>,
+    pub ecart_pourcentage: Option<f64>,
+    pub jours_saisis: u32,
+}
+
+/// Conformité de l'alimentation d'un bâtiment à son programme
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConformiteProgrammeAlimentation {
+    pub batiment_id: i64,
+    pub poussin_id: i64,
+    pub phases: Vec<ConformitePhase>,
+    pub jours: Vec<ConformiteAlimentationJour>,
+}
+
+/// Nombre de derniers jours saisis qui donnent la consommation actuelle d'un bâtiment
+pub const JOURS_REFERENCE_PREVISION: usize = 7;
+
+/// Besoin en aliment projeté pour un bâtiment jusqu'à son enlèvement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrevisionAlimentBatiment {
+    pub batiment_id: i64,
+    pub numero_batiment: String,
+    /// Effectif vivant après les dernières sorties saisies
+    pub effectif: i64,
+    /// Premier jour projeté (lendemain du dernier jour d'aliment saisi)
+    pub age_depart: i32,
+    pub age_enlevement: i32,
+    /// Rapport moyen entre consommation réelle et programme sur les derniers jours saisis
+    pub facteur_consommation: Option<f64>,
+    pub besoin_kg: f64,
+    /// Jours ni couverts par le programme ni estimables depuis la consommation actuelle
+    pub jours_sans_estimation: u32,
+}
+
+/// Besoin en aliment projeté d'une semaine d'âge de la bande
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BesoinAlimentSemaine {
+    pub numero_semaine: i32,
+    pub date_debut: String, // YYYY-MM-DD
+    pub besoin_kg: f64,
+}
+
+/// Besoins en aliment d'une bande jusqu'à l'enlèvement, comparés au stock
+///
+/// Le programme de chaque bâtiment est corrigé par son facteur de
+/// consommation; au-delà du programme, la consommation actuelle est
+/// prolongée. L'effectif est supposé constant jusqu'à l'enlèvement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrevisionAliment {
+    pub bande_id: i64,
+    /// Stock de la bande (contour d'alimentation) en kg
+    pub stock_kg: f64,
+    pub besoin_kg: f64,
+    pub besoin_sachets: f64,
+    /// Quantité à acheter pour couvrir le besoin, 0 si le stock suffit
+    pub manque_kg: f64,
+    /// Premier jour où le stock ne couvre plus le besoin cumulé
+    pub date_rupture: Option<String>,
+    pub semaines: Vec<BesoinAlimentSemaine>,
+    pub batiments: Vec<PrevisionAlimentBatiment>,
+}
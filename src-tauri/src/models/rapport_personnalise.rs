@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Nombre maximal de lignes renvoyées par l'exécution d'un rapport
+pub const LIGNES_MAX_RAPPORT: usize = 10_000;
+
+/// Description d'un rapport tabulaire personnalisé
+///
+/// Les noms d'entité et de champs sont ceux du catalogue (`get_report_entities`):
+/// ils sont traduits en SQL par le repository, jamais insérés tels quels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefinitionRapport {
+    /// Entité interrogée (`bandes`, `batiments`, `suivi_quotidien`, `alimentation`)
+    pub entite: String,
+    pub colonnes: Vec<ColonneRapport>,
+    #[serde(default)]
+    pub filtres: Vec<FiltreRapport>,
+    /// Champs de regroupement: les colonnes hors regroupement doivent alors être agrégées
+    #[serde(default)]
+    pub regroupement: Vec<String>,
+}
+
+/// Colonne d'un rapport, éventuellement agrégée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColonneRapport {
+    pub champ: String,
+    /// `somme`, `moyenne`, `min`, `max` ou `nombre`
+    #[serde(default)]
+    pub agregat: Option<String>,
+}
+
+/// Filtre d'un rapport
+///
+/// La valeur est soit fixe (`valeur`), soit fournie à l'exécution sous le
+/// nom `parametre`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiltreRapport {
+    pub champ: String,
+    /// `=`, `!=`, `<`, `<=`, `>`, `>=` ou `contient`
+    pub operateur: String,
+    #[serde(default)]
+    pub valeur: Option<Value>,
+    #[serde(default)]
+    pub parametre: Option<String>,
+}
+
+/// Définition de rapport enregistrée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RapportPersonnalise {
+    pub id: i64,
+    pub nom: String,
+    pub definition: DefinitionRapport,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer une nouvelle définition de rapport
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRapportPersonnalise {
+    pub nom: String,
+    pub definition: DefinitionRapport,
+}
+
+/// Entité disponible pour les rapports et ses champs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntiteRapport {
+    pub entite: String,
+    pub champs: Vec<String>,
+}
+
+/// Résultat de l'exécution d'un rapport
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultatRapport {
+    pub colonnes: Vec<String>,
+    pub lignes: Vec<Vec<Value>>,
+    /// Vrai si le résultat a été limité à `LIGNES_MAX_RAPPORT` lignes
+    pub tronque: bool,
+}
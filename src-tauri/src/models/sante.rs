@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// Facteurs du score de santé d'un bâtiment
+pub const FACTEUR_MORTALITE: &str = "mortalite";
+pub const FACTEUR_TENDANCE_MORTALITE: &str = "tendance_mortalite";
+pub const FACTEUR_TENDANCE_ALIMENTATION: &str = "tendance_alimentation";
+pub const FACTEUR_RATIO_EAU_ALIMENT: &str = "ratio_eau_aliment";
+pub const FACTEUR_MALADIES: &str = "maladies";
+
+/// Nombre de jours de chaque fenêtre comparée par le score de santé
+pub const JOURS_FENETRE_SANTE: i32 = 7;
+
+/// Contribution d'un signal au score de santé d'un bâtiment
+///
+/// `valeur` est `None` lorsque le signal n'est pas mesurable (pas assez de
+/// saisies, donnée non suivie): il ne retire alors aucun point.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacteurSante {
+    pub facteur: String,
+    pub valeur: Option<f64>,
+    pub points_perdus: f64,
+    pub description: String,
+}
+
+/// Score de santé d'un bâtiment au dernier jour saisi
+///
+/// Le score part de 100 et perd les points de chaque facteur, sans
+/// descendre sous 0. Les tendances comparent les `JOURS_FENETRE_SANTE`
+/// derniers jours saisis aux jours qui les précèdent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanteBatiment {
+    pub batiment_id: i64,
+    pub bande_id: i64,
+    pub numero_batiment: String,
+    /// Dernier âge (jour) avec des décès ou de l'alimentation saisis
+    pub age_reference: Option<i32>,
+    pub score: f64,
+    pub facteurs: Vec<FacteurSante>,
+}
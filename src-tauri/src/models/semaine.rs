@@ -1,4 +1,43 @@
 use serde::{Deserialize, Serialize};
+use crate::models::Tracabilite;
+
+/// Paramètre: unité dans laquelle les poids hebdomadaires sont saisis
+pub const PARAMETRE_UNITE_POIDS: &str = "unite_poids";
+
+/// Unités de saisie des poids acceptées: (unité, nombre de grammes pour une unité)
+pub const UNITES_POIDS: [(&str, f64); 2] = [("g", 1.0), ("kg", 1000.0)];
+
+/// Unité de saisie utilisée tant que le paramètre n'est pas défini
+pub const UNITE_POIDS_DEFAUT: &str = "kg";
+
+/// Paramètre: nombre maximal de semaines de suivi d'un bâtiment
+pub const PARAMETRE_SEMAINES_MAX: &str = "semaines_max";
+
+/// Valeurs acceptées pour le nombre maximal de semaines (la base refuse au-delà de 9)
+pub const PLAGE_SEMAINES_MAX: std::ops::RangeInclusive<i32> = 5..=9;
+
+/// Nombre maximal de semaines tant que le paramètre n'est pas défini
+pub const SEMAINES_MAX_DEFAUT: i32 = 9;
+
+/// Poids moyen plausible d'un poulet de chair, en kg (du poussin d'un jour à l'abattage)
+pub const POIDS_MIN_KG: f64 = 0.02;
+pub const POIDS_MAX_KG: f64 = 6.0;
+
+/// Croissance maximale plausible d'une semaine pesée à la suivante (rapport des poids)
+pub const CROISSANCE_MAX_HEBDOMADAIRE: f64 = 3.0;
+
+/// Convertit un poids saisi dans l'unité donnée en kilogrammes
+///
+/// # Returns
+/// `None` si l'unité n'est pas dans `UNITES_POIDS`
+pub fn poids_en_kg(poids: f64, unite: &str) -> Option<f64> {
+    UNITES_POIDS.iter().find(|(nom, _)| *nom == unite).map(|(_, grammes)| poids * grammes / 1000.0)
+}
+
+/// Indique si un poids en kg est dans la plage plausible
+pub fn poids_plausible(poids_kg: f64) -> bool {
+    (POIDS_MIN_KG..=POIDS_MAX_KG).contains(&poids_kg)
+}
 
 /// Représente une semaine de suivi dans un bâtiment
 /// 
@@ -9,7 +48,15 @@ pub struct Semaine {
     pub id: Option<i64>,
     pub batiment_id: i64,
     pub numero_semaine: i32,
-    pub poids: Option<f64>, // Poids moyen des poussins en grammes
+    pub poids: Option<f64>, // Poids moyen des poussins en kilogrammes
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
+    /// Dates de création et de modification, et auteur
+    #[serde(flatten)]
+    pub tracabilite: Tracabilite,
+    /// Poids signalé comme suspect par la normalisation, à vérifier
+    #[serde(default)]
+    pub poids_a_verifier: bool,
 }
 
 /// Structure pour créer une nouvelle semaine
@@ -23,6 +70,16 @@ pub struct CreateSemaine {
     pub poids: Option<f64>,
 }
 
+/// Semaine suivante créée par le passage à la semaine suivante d'un bâtiment
+///
+/// `jours_crees` liste les âges des jours de suivi créés avec la semaine:
+/// les 7 jours s'ils ont été demandés, sinon ceux créés par le plan de soins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvancementSemaine {
+    pub semaine: Semaine,
+    pub jours_crees: Vec<i32>,
+}
+
 /// Structure pour mettre à jour une semaine existante
 /// 
 /// Permet de modifier les informations d'une semaine
@@ -33,4 +90,188 @@ pub struct UpdateSemaine {
     pub batiment_id: i64,
     pub numero_semaine: i32,
     pub poids: Option<f64>,
+    /// Version chargée par le client: la mise à jour est refusée si la ligne
+    /// a été modifiée depuis (`None` désactive le contrôle)
+    #[serde(default)]
+    pub version: Option<i64>,
+}
+
+/// Poids d'une semaine relevé par la normalisation des poids enregistrés
+///
+/// `poids_normalise` est le poids converti en kg lorsque la valeur stockée a
+/// manifestement été saisie en grammes; `None` pour un poids seulement suspect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoidsReleve {
+    pub semaine_id: i64,
+    pub bande_id: i64,
+    pub batiment_id: i64,
+    pub numero_batiment: String,
+    pub numero_semaine: i32,
+    pub poids: f64,
+    pub poids_normalise: Option<f64>,
+    pub motif: String,
+}
+
+/// Résultat de la normalisation des poids enregistrés
+///
+/// Sans `appliquee`, rien n'est modifié: le résultat est un aperçu. Sinon les
+/// poids de `convertis` sont enregistrés en kg et ceux de `a_verifier` sont
+/// signalés (`Semaine::poids_a_verifier`) jusqu'à leur prochaine saisie.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalisationPoids {
+    pub appliquee: bool,
+    pub convertis: Vec<PoidsReleve>,
+    pub a_verifier: Vec<PoidsReleve>,
+}
+
+/// Poids moyen d'un poussin d'un jour (kg), point de départ de la croissance
+pub const POIDS_POUSSIN_KG: f64 = 0.04;
+
+/// Nombre d'intervalles de pesée récents dont le GMQ moyen sert à la projection
+pub const INTERVALLES_PROJECTION: usize = 2;
+
+/// Un point de la courbe de croissance d'un bâtiment, en fin de semaine
+///
+/// `gain_hebdomadaire` (kg) et `gmq` (gain moyen quotidien, en g/jour) sont
+/// calculés depuis la pesée précédente (ou le poussin d'un jour pour la
+/// première); pour une semaine non pesée après la dernière pesée, `poids` est
+/// projeté avec le GMQ récent et `projete` vaut `true`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointCroissance {
+    pub numero_semaine: i32,
+    pub age_jours: i32,
+    pub poids: Option<f64>,
+    pub gain_hebdomadaire: Option<f64>,
+    pub gmq: Option<f64>,
+    pub projete: bool,
+}
+
+/// Courbe de croissance (GMQ et gain hebdomadaire) d'un bâtiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerieCroissance {
+    pub batiment_id: i64,
+    pub points: Vec<PointCroissance>,
+    /// GMQ moyen depuis le poussin d'un jour jusqu'à la dernière pesée (g/jour)
+    pub gmq_moyen: Option<f64>,
+    /// GMQ des dernières pesées, utilisé pour la projection (g/jour)
+    pub gmq_projection: Option<f64>,
+}
+
+impl SerieCroissance {
+    /// Calcule la courbe de croissance à partir des poids hebdomadaires
+    ///
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `pesees` - Les poids en kg par numéro de semaine (semaines non pesées comprises)
+    /// * `derniere_semaine` - La dernière semaine de la courbe
+    pub fn calculer(batiment_id: i64, pesees: &[(i32, Option<f64>)], derniere_semaine: i32) -> Self {
+        let mut points = Vec::new();
+        // Pesée précédente: (âge en jours, poids en kg)
+        let mut precedente = (0, POIDS_POUSSIN_KG);
+        let mut gmqs = Vec::new();
+
+        for numero_semaine in 1..=derniere_semaine {
+            let age_jours = numero_semaine * 7;
+            let poids = pesees
+                .iter()
+                .find(|(numero, _)| *numero == numero_semaine)
+                .and_then(|(_, poids)| *poids);
+            let mut point = PointCroissance {
+                numero_semaine,
+                age_jours,
+                poids,
+                gain_hebdomadaire: None,
+                gmq: None,
+                projete: false,
+            };
+            if let Some(poids) = poids {
+                let (age_precedent, poids_precedent) = precedente;
+                let gmq = (poids - poids_precedent) * 1000.0 / (age_jours - age_precedent) as f64;
+                point.gmq = Some(gmq);
+                point.gain_hebdomadaire = Some(gmq * 7.0 / 1000.0);
+                gmqs.push(gmq);
+                precedente = (age_jours, poids);
+            }
+            points.push(point);
+        }
+
+        let (age_derniere_pesee, poids_derniere_pesee) = precedente;
+        let gmq_moyen = (age_derniere_pesee > 0)
+            .then(|| (poids_derniere_pesee - POIDS_POUSSIN_KG) * 1000.0 / age_derniere_pesee as f64);
+        let recents = &gmqs[gmqs.len().saturating_sub(INTERVALLES_PROJECTION)..];
+        let gmq_projection = (!recents.is_empty()).then(|| recents.iter().sum::<f64>() / recents.len() as f64);
+
+        if let Some(gmq) = gmq_projection {
+            for point in points.iter_mut().filter(|point| point.age_jours > age_derniere_pesee) {
+                point.poids = Some(poids_derniere_pesee + gmq * (point.age_jours - age_derniere_pesee) as f64 / 1000.0);
+                point.gmq = Some(gmq);
+                point.gain_hebdomadaire = Some(gmq * 7.0 / 1000.0);
+                point.projete = true;
+            }
+        }
+
+        Self { batiment_id, points, gmq_moyen, gmq_projection }
+    }
+}
+
+/// Alimentation d'un jour de suivi rapportée aux sujets vivants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointAlimentationSujet {
+    pub age: i32,
+    /// Alimentation saisie du jour, en sachets
+    pub alimentation: Option<f64>,
+    /// Sujets vivants au début du jour (sorties des jours précédents déduites)
+    pub effectif: i64,
+    /// Décès et éliminations du jour
+    pub sorties: i64,
+    /// Alimentation par sujet vivant, en g/sujet/jour
+    pub grammes_par_sujet: Option<f64>,
+}
+
+/// Consommation journalière par sujet d'un bâtiment
+///
+/// Rapportée à l'effectif vivant de chaque jour plutôt qu'à l'effectif
+/// initial, elle distingue une vraie baisse de consommation d'une baisse
+/// due à la mortalité.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerieAlimentationSujet {
+    pub batiment_id: i64,
+    pub effectif_initial: i64,
+    pub points: Vec<PointAlimentationSujet>,
+}
+
+impl SerieAlimentationSujet {
+    /// Calcule la série à partir des suivis quotidiens saisis
+    ///
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// * `effectif_initial` - La quantité de poussins du bâtiment
+    /// * `jours` - `(âge, décès et éliminations, alimentation en sachets)` de chaque jour saisi, par âge croissant
+    /// * `kg_par_sachet` - Le poids d'un sachet d'aliment
+    pub fn calculer(
+        batiment_id: i64,
+        effectif_initial: i64,
+        jours: &[(i32, i64, Option<f64>)],
+        kg_par_sachet: f64,
+    ) -> Self {
+        let mut sorties_precedentes = 0;
+        let points = jours
+            .iter()
+            .map(|&(age, sorties, alimentation)| {
+                let effectif = (effectif_initial - sorties_precedentes).max(0);
+                sorties_precedentes += sorties;
+                PointAlimentationSujet {
+                    age,
+                    alimentation,
+                    effectif,
+                    sorties,
+                    grammes_par_sujet: alimentation
+                        .filter(|_| effectif > 0)
+                        .map(|sachets| sachets * kg_par_sachet * 1000.0 / effectif as f64),
+                }
+            })
+            .collect();
+
+        Self { batiment_id, effectif_initial, points }
+    }
 }
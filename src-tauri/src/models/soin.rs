@@ -1,37 +1,58 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+/// Catégories de soins reconnues
+pub const CATEGORIES_SOIN: [&str; 6] = ["vaccin", "antibiotique", "anticoccidien", "vitamine", "désinfectant", "autre"];
+
 /// Représente un soin (médicament) dans le système
 /// 
 /// Les soins sont une base de données centrale de tous les
 /// traitements/soins disponibles avec leurs unités par défaut.
+/// `delai_attente_jours` est le délai d'attente avant abattage après la
+/// dernière administration (0 si le soin n'en impose pas).
+/// `prix_unitaire` est le prix d'une unité (`unit`) du soin, s'il est connu.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Soin {
     pub id: Option<i64>,
     pub nom: String,
     pub unit: String, // Unité par défaut (l, kg, etc.)
+    pub categorie: Option<String>,
+    pub delai_attente_jours: i32,
     pub created_at: DateTime<Utc>,
+    pub prix_unitaire: Option<f64>,
 }
 
 /// Structure pour créer un nouveau soin
 /// 
 /// Utilisée lors de la création d'un soin sans ID
 /// car l'ID est généré automatiquement par la base de données.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateSoin {
     pub nom: String,
     pub unit: String,
+    #[serde(default)]
+    pub categorie: Option<String>,
+    #[serde(default)]
+    pub delai_attente_jours: i32,
+    #[serde(default)]
+    pub prix_unitaire: Option<f64>,
 }
 
 /// Structure pour mettre à jour un soin existant
 /// 
 /// Permet de modifier les informations d'un soin
 /// en spécifiant son ID et les nouvelles données.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateSoin {
     pub id: i64,
     pub nom: String,
     pub unit: String,
+    #[serde(default)]
+    pub categorie: Option<String>,
+    #[serde(default)]
+    pub delai_attente_jours: i32,
+    #[serde(default)]
+    pub prix_unitaire: Option<f64>,
 }
 
 /// Structure pour les résultats paginés des soins
@@ -47,3 +68,113 @@ pub struct PaginatedSoin {
     pub has_next: bool,
     pub has_prev: bool,
 }
+
+/// Consommation agrégée d'un soin sur une bande ou une ferme
+/// 
+/// Les quantités structurées (`QuantiteSoin`) sont converties dans l'unité du
+/// soin avant d'être additionnées; les saisies sans nombre sont comptées à part.
+/// Une quantité dans une unité non convertible donne une entrée pour cette unité.
+/// `cout` n'est calculé que dans l'unité du soin, lorsque son prix est connu.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoinUsage {
+    pub soin_id: i64,
+    pub soin_nom: String,
+    pub unit: String,
+    pub total_quantite: f64,
+    pub nombre_administrations: u32,
+    pub saisies_non_numeriques: u32,
+    #[serde(default)]
+    pub cout: Option<f64>,
+}
+
+/// Unités de soin convertibles entre elles: (alias, unité normalisée, dimension, facteur)
+///
+/// Le facteur exprime l'unité dans l'unité de base de sa dimension (g, ml, dose).
+pub const UNITES_SOIN: [(&[&str], &str, &str, f64); 7] = [
+    (&["mg", "milligramme", "milligrammes"], "mg", "masse", 0.001),
+    (&["g", "gr", "gramme", "grammes"], "g", "masse", 1.0),
+    (&["kg", "kilo", "kilos", "kilogramme", "kilogrammes"], "kg", "masse", 1000.0),
+    (&["ml", "cc", "cm3", "millilitre", "millilitres"], "ml", "volume", 1.0),
+    (&["cl", "centilitre", "centilitres"], "cl", "volume", 10.0),
+    (&["l", "litre", "litres"], "l", "volume", 1000.0),
+    (&["dose", "doses"], "dose", "dose", 1.0),
+];
+
+/// Normalise une unité saisie librement ("Litres" → "l", "gr" → "g")
+///
+/// Une unité inconnue (ex: "ml/L") est seulement mise en minuscules: elle
+/// reste comparable à elle-même mais n'est convertible vers aucune autre.
+pub fn normaliser_unite(unite: &str) -> String {
+    let unite = unite.trim().trim_end_matches('.').to_lowercase();
+    UNITES_SOIN
+        .iter()
+        .find(|(alias, ..)| alias.contains(&unite.as_str()))
+        .map(|(_, normalisee, ..)| normalisee.to_string())
+        .unwrap_or(unite)
+}
+
+/// Quantité structurée d'une administration de soin
+///
+/// Extraite de la saisie libre (`suivi_soins.quantite`, ex: "5l", "0,5 L"):
+/// le nombre en tête de saisie et l'unité qui le suit, ou à défaut l'unité
+/// de l'administration puis celle du soin. L'unité est normalisée.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantiteSoin {
+    pub valeur: f64,
+    pub unite: String,
+}
+
+impl QuantiteSoin {
+    /// Analyse une quantité saisie librement
+    ///
+    /// # Arguments
+    /// * `texte` - La saisie (la virgule décimale est acceptée)
+    /// * `unite_defaut` - L'unité retenue si la saisie n'en précise pas
+    ///
+    /// # Returns
+    /// `None` si la saisie ne commence pas par un nombre ou si aucune unité n'est connue
+    pub fn parse(texte: &str, unite_defaut: Option<&str>) -> Option<Self> {
+        let texte = texte.trim();
+        let fin = texte
+            .char_indices()
+            .find(|(_, c)| !(c.is_ascii_digit() || *c == '.' || *c == ','))
+            .map(|(i, _)| i)
+            .unwrap_or(texte.len());
+        let valeur = texte[..fin].replace(',', ".").parse::<f64>().ok()?;
+
+        let unite = Some(texte[fin..].trim())
+            .filter(|unite| !unite.is_empty())
+            .or(unite_defaut.map(str::trim).filter(|unite| !unite.is_empty()))?;
+        Some(Self { valeur, unite: normaliser_unite(unite) })
+    }
+
+    /// Valeur de la quantité exprimée dans une autre unité
+    ///
+    /// # Returns
+    /// `None` si les deux unités ne sont pas de la même dimension
+    pub fn convertir(&self, vers: &str) -> Option<f64> {
+        convertir_quantite(self.valeur, &self.unite, vers)
+    }
+}
+
+/// Convertit une valeur d'une unité de soin vers une autre
+///
+/// Les unités sont comparées après normalisation; deux unités inconnues
+/// identiques sont compatibles (facteur 1).
+///
+/// # Returns
+/// `None` si les unités ne sont pas convertibles entre elles
+pub fn convertir_quantite(valeur: f64, de: &str, vers: &str) -> Option<f64> {
+    let (de, vers) = (normaliser_unite(de), normaliser_unite(vers));
+    if de == vers {
+        return Some(valeur);
+    }
+    let unite = |nom: &str| {
+        UNITES_SOIN
+            .iter()
+            .find(|(_, normalisee, ..)| *normalisee == nom)
+            .map(|(_, _, dimension, facteur)| (*dimension, *facteur))
+    };
+    let ((dimension_de, facteur_de), (dimension_vers, facteur_vers)) = (unite(&de)?, unite(&vers)?);
+    (dimension_de == dimension_vers).then(|| valeur * facteur_de / facteur_vers)
+}
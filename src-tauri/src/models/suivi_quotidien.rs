@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+use crate::models::Tracabilite;
+use std::collections::BTreeMap;
 
 /// Représente le suivi quotidien d'une semaine
 /// 
@@ -11,11 +14,16 @@ pub struct SuiviQuotidien {
     pub semaine_id: i64,
     pub age: i32, // Âge en jours depuis l'éclosion
     pub deces_par_jour: Option<i32>,
+    /// Sujets éliminés volontairement (tri, réforme), comptés à part des décès
+    #[serde(default)]
+    pub elimines_par_jour: Option<i32>,
     pub alimentation_par_jour: Option<f64>, // En kg ou autre unité
     pub soins_id: Option<i64>,
     pub soins_quantite: Option<String>, // Quantité avec unité (ex: "5l", "2kg")
     pub analyses: Option<String>,
     pub remarques: Option<String>,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
 }
 
 /// Structure pour créer un nouveau suivi quotidien
@@ -27,6 +35,8 @@ pub struct CreateSuiviQuotidien {
     pub semaine_id: i64,
     pub age: i32,
     pub deces_par_jour: Option<i32>,
+    #[serde(default)]
+    pub elimines_par_jour: Option<i32>,
     pub alimentation_par_jour: Option<f64>,
     pub soins_id: Option<i64>,
     pub soins_quantite: Option<String>,
@@ -44,11 +54,17 @@ pub struct UpdateSuiviQuotidien {
     pub semaine_id: i64,
     pub age: i32,
     pub deces_par_jour: Option<i32>,
+    #[serde(default)]
+    pub elimines_par_jour: Option<i32>,
     pub alimentation_par_jour: Option<f64>,
     pub soins_id: Option<i64>,
     pub soins_quantite: Option<String>,
     pub analyses: Option<String>,
     pub remarques: Option<String>,
+    /// Version chargée par le client: la mise à jour est refusée si la ligne
+    /// a été modifiée depuis (`None` désactive le contrôle)
+    #[serde(default)]
+    pub version: Option<i64>,
 }
 
 /// Vue étendue du suivi quotidien avec les informations des soins
@@ -57,12 +73,17 @@ pub struct UpdateSuiviQuotidien {
 /// sans nécessiter de requêtes supplémentaires côté frontend.
 /// Les totaux (deces_total, alimentation_total) sont calculés uniquement
 /// côté frontend et ne font pas partie de cette structure.
+/// 
+/// `soins` contient tous les traitements du jour; les champs `soins_*`
+/// reprennent le premier d'entre eux pour les écrans à un seul soin.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuiviQuotidienWithDetails {
     pub id: Option<i64>,
     pub semaine_id: i64,
     pub age: i32,
     pub deces_par_jour: Option<i32>,
+    #[serde(default)]
+    pub elimines_par_jour: Option<i32>,
     pub alimentation_par_jour: Option<f64>,
     pub soins_id: Option<i64>,
     pub soins_nom: Option<String>,
@@ -70,4 +91,110 @@ pub struct SuiviQuotidienWithDetails {
     pub soins_quantite: Option<String>,
     pub analyses: Option<String>,
     pub remarques: Option<String>,
+    /// Version de la ligne, à renvoyer lors de la mise à jour
+    pub version: i64,
+    /// Dates de création et de modification, et auteur
+    #[serde(flatten)]
+    pub tracabilite: Tracabilite,
+    pub soins: Vec<SuiviSoin>,
+}
+
+/// Un traitement administré lors d'un suivi quotidien
+/// 
+/// Un même jour peut comporter plusieurs soins. `unit` est l'unité saisie
+/// pour ce traitement ou, à défaut, l'unité par défaut du soin.
+/// `quantite_valeur` et `quantite_unite` sont la quantité structurée extraite
+/// de la saisie (`None` si elle ne commence pas par un nombre).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuiviSoin {
+    pub id: Option<i64>,
+    pub suivi_id: i64,
+    pub soin_id: Option<i64>,
+    pub soin_nom: Option<String>,
+    pub unit: Option<String>,
+    pub quantite: Option<String>,
+    #[serde(default)]
+    pub quantite_valeur: Option<f64>,
+    #[serde(default)]
+    pub quantite_unite: Option<String>,
+}
+
+/// Structure pour ajouter un soin à un jour de suivi
+/// 
+/// Le jour est désigné par sa semaine et son âge: la ligne de suivi est
+/// créée si elle n'existe pas encore (jour virtuel).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSuiviSoin {
+    pub semaine_id: i64,
+    pub age: i32,
+    pub soin_id: Option<i64>,
+    pub quantite: Option<String>,
+    pub unit: Option<String>,
+}
+
+/// Structure pour modifier un soin d'un jour de suivi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSuiviSoin {
+    pub id: i64,
+    pub soin_id: Option<i64>,
+    pub quantite: Option<String>,
+    pub unit: Option<String>,
+}
+
+/// Ligne modifiée de la grille de saisie d'une semaine
+///
+/// `champs` ne contient que les cellules modifiées du jour, par nom de champ
+/// (mêmes champs et mêmes valeurs que pour la saisie cellule par cellule).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LigneGrilleSuivi {
+    pub age: i32,
+    pub champs: BTreeMap<String, String>,
+}
+
+/// Résultat de l'enregistrement d'une cellule de la grille
+///
+/// `suivi` est le jour de suivi après l'enregistrement de la cellule; il est
+/// absent lorsque la cellule a été refusée, `erreur` en donnant la raison.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultatCelluleGrille {
+    pub age: i32,
+    pub field: String,
+    pub suivi: Option<SuiviQuotidien>,
+    pub erreur: Option<String>,
+}
+
+/// Nombre de semaines de suivi d'un bâtiment sans semaine supplémentaire
+pub const SEMAINES_SUIVI: i32 = 8;
+
+/// Saisie d'un jour de suivi pour l'indicateur de complétude
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JourSaisie {
+    pub age: i32,
+    pub date: NaiveDate,
+    pub deces_saisi: bool,
+    pub alimentation_saisie: bool,
+}
+
+/// Jours de suivi d'un bâtiment, du premier jour à la date de référence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletudeBatiment {
+    pub batiment_id: i64,
+    pub numero_batiment: String,
+    pub jours: Vec<JourSaisie>,
+    /// Jours sans décès ou sans alimentation saisis
+    pub jours_incomplets: i32,
+}
+
+/// Complétude des saisies quotidiennes d'une bande (carte des données manquantes)
+///
+/// Les jours vont du jour d'entrée jusqu'à la date de référence, sans
+/// dépasser la date de clôture ni la dernière semaine de chaque bâtiment
+/// (au moins `SEMAINES_SUIVI` semaines).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletudeSaisie {
+    pub bande_id: i64,
+    pub ferme_id: i64,
+    pub date_entree: NaiveDate,
+    pub jusqu_au: NaiveDate,
+    pub batiments: Vec<CompletudeBatiment>,
 }
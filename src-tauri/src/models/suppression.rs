@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Entités dont la suppression peut être prévisualisée
+pub const ENTITES_SUPPRESSION: [&str; 4] = ["ferme", "personnel", "soin", "poussin"];
+
+/// Lignes dépendant d'une entité à supprimer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependanceSuppression {
+    /// Identifiant stable de la dépendance ("bandes", "batiments", "suivis_quotidiens"...)
+    pub cle: String,
+    pub libelle: String,
+    pub nombre: i64,
+    /// Vrai si la présence de ces lignes empêche la suppression
+    pub bloquante: bool,
+}
+
+/// Aperçu des conséquences de la suppression d'une entité
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactSuppression {
+    pub entity: String,
+    pub id: i64,
+    pub nom: String,
+    pub dependances: Vec<DependanceSuppression>,
+    pub suppression_possible: bool,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Étiquette posée sur des bandes (bande d'essai, bande à problème, intégration...)
+///
+/// Les noms sont uniques sans tenir compte de la casse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: i64,
+    pub nom: String,
+    /// Couleur d'affichage au format #RRGGBB, `None` pour la couleur par défaut
+    pub couleur: Option<String>,
+    pub created_at: String,
+}
+
+/// Structure pour créer ou modifier une étiquette
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateTag {
+    pub nom: String,
+    #[serde(default)]
+    pub couleur: Option<String>,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Modes de saisie d'un montant soumis à la TVA: hors taxes ou toutes taxes comprises
+pub const MODE_TVA_HT: &str = "ht";
+pub const MODE_TVA_TTC: &str = "ttc";
+pub const MODES_TVA: [&str; 2] = [MODE_TVA_HT, MODE_TVA_TTC];
+
+/// TVA saisie avec un achat
+///
+/// Le montant de l'achat est saisi hors taxes ou TTC selon `mode_tva` (hors
+/// taxes par défaut) et toujours enregistré hors taxes. `montant_tva` n'est à
+/// saisir que s'il diffère du montant calculé à partir du taux (arrondi de la
+/// facture, TVA non récupérable...).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaisieTva {
+    /// Taux de TVA en %
+    #[serde(default)]
+    pub taux_tva: Option<f64>,
+    #[serde(default)]
+    pub montant_tva: Option<f64>,
+    #[serde(default)]
+    pub mode_tva: Option<String>,
+}
+
+/// Totaux de TVA d'un trimestre pour un taux
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvaTrimestre {
+    /// Trimestre au format "AAAA-T1" à "AAAA-T4"
+    pub trimestre: String,
+    /// `None` pour les achats saisis avec un montant de TVA sans taux
+    pub taux_tva: Option<f64>,
+    pub achats: i64,
+    pub base_ht: f64,
+    pub montant_tva: f64,
+    pub montant_ttc: f64,
+}
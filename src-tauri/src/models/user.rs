@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+/// Rôles des utilisateurs
+pub const ROLE_ADMIN: &str = "admin";
+pub const ROLE_TECHNICIEN: &str = "technicien";
+
 /// Modèle représentant un utilisateur dans le système
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
@@ -7,6 +11,10 @@ pub struct User {
     pub username: String,
     pub email: String,
     pub password_hash: String,
+    /// `admin` ou `technicien`
+    pub role: String,
+    /// Mot de passe temporaire attribué par un administrateur, à changer avant toute saisie
+    pub must_change_password: bool,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -17,6 +25,7 @@ pub struct CreateUser {
     pub username: String,
     pub email: String,
     pub password: String,
+    /// Code d'invitation créé par un administrateur (ignoré pour le premier compte)
     pub registration_code: String,
 }
 
@@ -25,6 +34,9 @@ pub struct CreateUser {
 pub struct LoginUser {
     pub username: String,
     pub password: String,
+    /// Code TOTP, exigé des utilisateurs qui ont activé le second facteur
+    #[serde(default)]
+    pub totp_code: Option<String>,
 }
 
 /// Structure pour la réponse d'authentification
@@ -40,6 +52,8 @@ pub struct UserPublic {
     pub id: i64,
     pub username: String,
     pub email: String,
+    pub role: String,
+    pub must_change_password: bool,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -50,8 +64,17 @@ impl From<User> for UserPublic {
             id: user.id,
             username: user.username,
             email: user.email,
+            role: user.role,
+            must_change_password: user.must_change_password,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
 }
+
+impl User {
+    /// Indique si l'utilisateur a les droits d'administration
+    pub fn is_admin(&self) -> bool {
+        self.role == ROLE_ADMIN
+    }
+}
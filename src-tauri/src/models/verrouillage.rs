@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Paramètre: nombre de jours pendant lesquels un jour de suivi reste modifiable
+/// (absent ou "0": pas de verrouillage)
+pub const PARAMETRE_FENETRE_SAISIE_JOURS: &str = "fenetre_saisie_jours";
+
+/// Durée d'un déverrouillage accordé par un administrateur
+pub const DUREE_DEVERROUILLAGE_HEURES: i64 = 24;
+
+/// Fenêtre de saisie du suivi
+///
+/// Les jours de suivi (et les semaines terminées) plus anciens que `jours`
+/// ne peuvent plus être modifiés sans déverrouillage par un administrateur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FenetreSaisie {
+    /// `None` si le verrouillage est désactivé
+    pub jours: Option<i32>,
+}
+
+/// Période d'une bande déverrouillée temporairement par un administrateur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeverrouillagePeriode {
+    pub id: i64,
+    pub bande_id: i64,
+    /// Premier et dernier jour déverrouillés (YYYY-MM-DD)
+    pub date_debut: String,
+    pub date_fin: String,
+    /// Fin du déverrouillage (YYYY-MM-DD HH:MM:SS, UTC)
+    pub expire_le: String,
+    pub user_id: Option<i64>,
+}
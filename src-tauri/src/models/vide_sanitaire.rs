@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Durée minimale du vide sanitaire (jours) avant de remettre des poussins dans un bâtiment
+pub const DUREE_VIDE_SANITAIRE_JOURS: i64 = 14;
+
+/// Nettoyage et désinfection d'un bâtiment physique entre deux bandes
+///
+/// Comme la position sur le plan, le vide sanitaire porte sur le bâtiment
+/// physique (numéro dans la ferme) et non sur le bâtiment d'une bande.
+/// `date_remise_en_place` est l'entrée de la bande suivante dans le bâtiment
+/// et `jours_vide` la durée du vide depuis le début du nettoyage, jusqu'à
+/// aujourd'hui tant qu'aucune bande n'est entrée.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideSanitaire {
+    pub id: i64,
+    pub ferme_id: i64,
+    pub numero_batiment: String,
+    pub date_debut: String, // YYYY-MM-DD
+    pub date_fin: Option<String>,
+    /// Produits de nettoyage et de désinfection utilisés
+    pub produits: Option<String>,
+    pub date_remise_en_place: Option<String>,
+    pub jours_vide: i64,
+    pub created_at: String,
+}
+
+/// Structure pour enregistrer un vide sanitaire
+///
+/// `date_fin` reste vide tant que le nettoyage est en cours.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateVideSanitaire {
+    pub ferme_id: i64,
+    pub numero_batiment: String,
+    pub date_debut: String,
+    pub date_fin: Option<String>,
+    pub produits: Option<String>,
+}
+
+/// Structure pour mettre à jour un vide sanitaire (fin du nettoyage, produits)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateVideSanitaire {
+    pub id: i64,
+    pub date_debut: String,
+    pub date_fin: Option<String>,
+    pub produits: Option<String>,
+}
+
+/// Durée moyenne des vides sanitaires d'une ferme
+///
+/// Seuls les vides suivis de l'entrée d'une bande sont comptés.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatistiquesVideSanitaire {
+    pub ferme_id: i64,
+    pub nombre_vides: i64,
+    pub duree_moyenne_jours: Option<f64>,
+}
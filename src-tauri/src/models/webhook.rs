@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Paramètre: URL du webhook du système de la coopérative
+///
+/// Absent ou vide: les événements ne sont pas mis en file d'envoi.
+pub const PARAMETRE_WEBHOOK_URL: &str = "webhook_url";
+
+/// Événements notifiés au webhook
+pub const EVENEMENT_BANDE_CLOTUREE: &str = "bande.cloturee";
+pub const EVENEMENT_ALERTE_CRITIQUE: &str = "alerte.critique";
+
+/// Nombre d'envois tentés avant d'abandonner un événement
+pub const TENTATIVES_WEBHOOK_MAX: i64 = 10;
+
+/// Événement en file d'envoi vers le webhook
+///
+/// Un envoi échoué est retenté avec un délai qui double à chaque tentative
+/// (1 minute, 2, 4... plafonné à 6 heures). Après `TENTATIVES_WEBHOOK_MAX`
+/// tentatives, `prochaine_tentative` est vide: l'événement n'est plus envoyé
+/// sauf s'il est relancé.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvenementSortant {
+    pub id: i64,
+    pub type_evenement: String,
+    /// Corps JSON envoyé au webhook
+    pub contenu: String,
+    pub created_at: String,
+    pub tentatives: i64,
+    pub prochaine_tentative: Option<String>,
+    /// `None` tant que l'événement n'a pas été reçu par le webhook
+    pub envoye_le: Option<String>,
+    pub derniere_erreur: Option<String>,
+}
+
+/// Résultat d'un passage d'envoi de la file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RapportEnvoiWebhook {
+    pub envoyes: usize,
+    pub echecs: usize,
+}
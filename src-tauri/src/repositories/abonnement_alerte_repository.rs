@@ -0,0 +1,51 @@
+use crate::error::AppError;
+use crate::models::AbonnementAlertes;
+use rusqlite::{Connection, OptionalExtension};
+
+/// Repository for the users' alert subscriptions (one row per user)
+pub struct AbonnementAlerteRepository;
+
+impl AbonnementAlerteRepository {
+    /// Get the subscription of a user, every alert when nothing was saved yet
+    pub fn get(
+        conn: &Connection,
+        user_id: i64,
+    ) -> Result<AbonnementAlertes, AppError> {
+        let ligne = conn
+            .query_row(
+                "SELECT types_alerte, ferme_ids, gravite_min FROM abonnements_alertes WHERE user_id = ?1",
+                [user_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+            )
+            .optional()?;
+
+        let Some((types_alerte, ferme_ids, gravite_min)) = ligne else {
+            return Ok(AbonnementAlertes::tout(user_id));
+        };
+        Ok(AbonnementAlertes {
+            user_id,
+            types_alerte: serde_json::from_str(&types_alerte)
+                .map_err(|e| AppError::business_logic(&format!("Abonnement aux alertes illisible: {}", e)))?,
+            ferme_ids: serde_json::from_str(&ferme_ids)
+                .map_err(|e| AppError::business_logic(&format!("Abonnement aux alertes illisible: {}", e)))?,
+            gravite_min,
+        })
+    }
+
+    /// Create or replace the subscription of a user
+    pub fn set(
+        conn: &Connection,
+        abonnement: &AbonnementAlertes,
+    ) -> Result<(), AppError> {
+        let types_alerte = serde_json::to_string(&abonnement.types_alerte)?;
+        let ferme_ids = serde_json::to_string(&abonnement.ferme_ids)?;
+        conn.execute(
+            "INSERT INTO abonnements_alertes (user_id, types_alerte, ferme_ids, gravite_min, updated_at)
+             VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+             ON CONFLICT(user_id) DO UPDATE SET types_alerte = excluded.types_alerte, ferme_ids = excluded.ferme_ids,
+                 gravite_min = excluded.gravite_min, updated_at = CURRENT_TIMESTAMP",
+            rusqlite::params![abonnement.user_id, types_alerte, ferme_ids, abonnement.gravite_min],
+        )?;
+        Ok(())
+    }
+}
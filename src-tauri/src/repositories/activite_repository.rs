@@ -0,0 +1,107 @@
+use crate::error::AppError;
+use crate::models::{ActiviteBande, PaginatedActiviteBande};
+use rusqlite::{params, Connection};
+
+/// Events of a bande from every source table, one row per event
+///
+/// Deliveries are bande-wide; the other events belong to one of its batiments.
+const ACTIVITE_UNION: &str =
+    "SELECT 'livraison_aliment' AS type_activite, ah.created_at AS date,
+            NULL AS batiment_id, NULL AS numero_batiment, ah.id AS reference_id,
+            printf('%.0f kg', ah.quantite)
+                || COALESCE(' ' || ah.type_aliment, '')
+                || COALESCE(' - ' || ah.fournisseur, '')
+                || COALESCE(' (lot ' || ah.numero_lot || ')', '') AS description
+     FROM alimentation_history ah
+     WHERE ah.bande_id = ?1 AND ah.quantite > 0
+     UNION ALL
+     SELECT 'maladie', bm.created_at, b.id, b.numero_batiment, m.id, m.nom
+     FROM batiment_maladies bm
+     JOIN batiments b ON bm.batiment_id = b.id
+     JOIN maladies m ON bm.maladie_id = m.id
+     WHERE b.bande_id = ?1
+     UNION ALL
+     SELECT 'analyse', a.date_prelevement, b.id, b.numero_batiment, a.id,
+            a.type_analyse || COALESCE(': ' || a.resultat, '')
+     FROM analyses a
+     JOIN batiments b ON a.batiment_id = b.id
+     WHERE b.bande_id = ?1
+     UNION ALL
+     SELECT 'note', n.created_at, b.id, b.numero_batiment, n.id, n.auteur || ': ' || n.contenu
+     FROM notes_batiment n
+     JOIN batiments b ON n.batiment_id = b.id
+     WHERE b.bande_id = ?1
+     UNION ALL
+     SELECT 'litiere', l.date_operation, b.id, b.numero_batiment, l.id,
+            l.type_operation || ' de litière: ' || printf('%g', l.quantite) || ' ' || l.unite || ' de ' || l.materiau
+     FROM litieres l
+     JOIN batiments b ON l.batiment_id = b.id
+     WHERE b.bande_id = ?1";
+
+/// Repository for the bande activity feed
+pub struct ActiviteRepository;
+
+impl ActiviteRepository {
+    /// Get one page of the activity feed of a bande, most recent first
+    ///
+    /// Merges feed deliveries, maladie declarations, laboratory analyses,
+    /// batiment notes and litter records. Analyses are dated by their sampling
+    /// day, litter records by their operation day.
+    pub fn get_by_bande(
+        conn: &Connection,
+        bande_id: i64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<PaginatedActiviteBande, AppError> {
+        let bande_exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM bandes WHERE id = ?1",
+            [bande_id],
+            |row| row.get(0),
+        )?;
+        if bande_exists == 0 {
+            return Err(AppError::not_found("Bande", bande_id));
+        }
+
+        let page = page.max(1);
+        let per_page = per_page.max(1);
+        let offset = (page - 1) * per_page;
+
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({})", ACTIVITE_UNION),
+            [bande_id],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT type_activite, date, batiment_id, numero_batiment, reference_id, description
+             FROM ({})
+             ORDER BY date DESC, type_activite, reference_id DESC
+             LIMIT ?2 OFFSET ?3",
+            ACTIVITE_UNION
+        ))?;
+
+        let data = stmt.query_map(params![bande_id, per_page, offset], |row| {
+            Ok(ActiviteBande {
+                type_activite: row.get(0)?,
+                date: row.get(1)?,
+                batiment_id: row.get(2)?,
+                numero_batiment: row.get(3)?,
+                reference_id: row.get(4)?,
+                description: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let total_pages = (total as u32).div_ceil(per_page);
+
+        Ok(PaginatedActiviteBande {
+            data,
+            total,
+            page,
+            limit: per_page,
+            total_pages,
+            has_next: page < total_pages,
+            has_prev: page > 1,
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use crate::error::AppError;
+use crate::models::{Alerte, DELAI_ESCALADE_HEURES, EVENEMENT_ALERTE_CRITIQUE, GRAVITE_CRITICAL};
+use crate::repositories::WebhookRepository;
+use rusqlite::{params, Connection, Row};
+
+/// Colonnes lues pour une `Alerte` de l'historique, dans l'ordre attendu par `map_alerte_row`
+///
+/// Une alerte critique non acquittée plus de `DELAI_ESCALADE_HEURES` heures
+/// après sa détection est escaladée.
+const ALERTE_SELECT: &str =
+    "SELECT a.id, a.type_alerte, a.gravite, a.ferme_id, a.bande_id, a.batiment_id, a.date_alerte, a.message,
+            a.detectee_le, a.acquittee_par, u.username, a.acquittee_le,
+            a.gravite = ?1 AND a.acquittee_le IS NULL
+                AND a.detectee_le <= datetime('now', '-' || ?2 || ' hours') AS escalade
+     FROM alertes a
+     LEFT JOIN users u ON a.acquittee_par = u.id";
+
+fn map_alerte_row(row: &Row) -> rusqlite::Result<Alerte> {
+    Ok(Alerte {
+        id: Some(row.get(0)?),
+        type_alerte: row.get(1)?,
+        gravite: row.get(2)?,
+        ferme_id: row.get(3)?,
+        bande_id: row.get(4)?,
+        batiment_id: row.get(5)?,
+        date: row.get(6)?,
+        message: row.get(7)?,
+        detectee_le: row.get(8)?,
+        acquittee_par: row.get(9)?,
+        acquittee_par_nom: row.get(10)?,
+        acquittee_le: row.get(11)?,
+        escalade: row.get(12)?,
+    })
+}
+
+/// Repository for the alert history and acknowledgments
+pub struct AlerteRepository;
+
+impl AlerteRepository {
+    /// Record computed alerts in the history and return them as stored
+    ///
+    /// An alert is identified by its type, ferme, bande, batiment and date: an alert
+    /// already recorded keeps its detection time and acknowledgment, only its
+    /// message and severity are refreshed. A critical alert is queued for the
+    /// webhook when it is first detected.
+    pub fn record(
+        conn: &Connection,
+        alertes: &[Alerte],
+    ) -> Result<Vec<Alerte>, AppError> {
+        let mut enregistrees = Vec::with_capacity(alertes.len());
+        for alerte in alertes {
+            let nouvelle = conn.execute(
+                "INSERT OR IGNORE INTO alertes (type_alerte, gravite, ferme_id, bande_id, batiment_id, date_alerte, message)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    alerte.type_alerte,
+                    alerte.gravite,
+                    alerte.ferme_id,
+                    alerte.bande_id,
+                    alerte.batiment_id,
+                    alerte.date,
+                    alerte.message,
+                ],
+            )? == 1;
+            let id: i64 = conn.query_row(
+                "SELECT id FROM alertes
+                 WHERE type_alerte = ?1 AND ferme_id = ?2 AND COALESCE(bande_id, 0) = COALESCE(?3, 0)
+                   AND COALESCE(batiment_id, 0) = COALESCE(?4, 0) AND date_alerte = ?5",
+                params![alerte.type_alerte, alerte.ferme_id, alerte.bande_id, alerte.batiment_id, alerte.date],
+                |row| row.get(0),
+            )?;
+            conn.execute(
+                "UPDATE alertes SET gravite = ?1, message = ?2 WHERE id = ?3",
+                params![alerte.gravite, alerte.message, id],
+            )?;
+            let enregistree = Self::find(conn, id)?;
+            if nouvelle && enregistree.gravite == GRAVITE_CRITICAL {
+                WebhookRepository::enqueue(
+                    conn,
+                    EVENEMENT_ALERTE_CRITIQUE,
+                    &serde_json::json!({
+                        "alerte_id": id,
+                        "type_alerte": enregistree.type_alerte,
+                        "ferme_id": enregistree.ferme_id,
+                        "bande_id": enregistree.bande_id,
+                        "batiment_id": enregistree.batiment_id,
+                        "date": enregistree.date,
+                        "message": enregistree.message,
+                    }),
+                )?;
+            }
+            enregistrees.push(enregistree);
+        }
+        Ok(enregistrees)
+    }
+
+    /// Acknowledge an alert on behalf of a user
+    pub fn acknowledge(
+        conn: &Connection,
+        id: i64,
+        user_id: i64,
+    ) -> Result<Alerte, AppError> {
+        let alerte = Self::find(conn, id)?;
+        if alerte.acquittee_le.is_some() {
+            return Err(AppError::business_logic("Cette alerte a déjà été acquittée"));
+        }
+
+        conn.execute(
+            "UPDATE alertes SET acquittee_par = ?1, acquittee_le = CURRENT_TIMESTAMP WHERE id = ?2",
+            params![user_id, id],
+        )?;
+
+        Self::find(conn, id)
+    }
+
+    /// List the recorded alerts, most recently detected first, optionally for one ferme
+    pub fn get_history(
+        conn: &Connection,
+        ferme_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Alerte>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE (?3 IS NULL OR a.ferme_id = ?3) ORDER BY a.detectee_le DESC, a.id DESC LIMIT ?4",
+            ALERTE_SELECT
+        ))?;
+
+        let alertes = stmt
+            .query_map(params![GRAVITE_CRITICAL, DELAI_ESCALADE_HEURES, ferme_id, limit], map_alerte_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(alertes)
+    }
+
+    fn find(conn: &Connection, id: i64) -> Result<Alerte, AppError> {
+        conn.query_row(
+            &format!("{} WHERE a.id = ?3", ALERTE_SELECT),
+            params![GRAVITE_CRITICAL, DELAI_ESCALADE_HEURES, id],
+            map_alerte_row,
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Alerte", id),
+            _ => AppError::from(e),
+        })
+    }
+}
@@ -1,7 +1,83 @@
 use crate::error::AppError;
-use crate::models::alimentation::{AlimentationHistory, CreateAlimentationHistory, UpdateAlimentationHistory};
-use r2d2::PooledConnection;
-use r2d2_sqlite::SqliteConnectionManager;
+use crate::models::alimentation::{
+    AlimentationHistory, AlimentationHistoryFilters, AlimentationHistoryWithDetails, AlimentationTypeTotal,
+    CreateAlimentationHistory, PaginatedAlimentationHistory, PaginatedAlimentationHistoryGlobal,
+    UpdateAlimentationHistory, FENETRE_DOUBLON_LIVRAISON_DEFAUT_MINUTES, PARAMETRE_FENETRE_DOUBLON_LIVRAISON,
+    TYPES_ALIMENT,
+};
+use crate::models::prix::ARTICLE_ALIMENT;
+use crate::models::SaisieTva;
+use crate::repositories::{ventiler_tva, ParametreRepository, PrixRepository, TvaAchat};
+use rusqlite::{params, Connection, Row};
+
+/// Columns read for an `AlimentationHistory`, in the order expected by `map_history_row`
+const HISTORY_COLUMNS: &str = "id, bande_id, quantite, created_at, fournisseur, type_aliment, numero_lot, prix_unitaire,
+     devise, taux_tva, COALESCE(montant_tva, quantite * prix_unitaire * taux_tva / 100)";
+
+fn map_history_row(row: &Row) -> rusqlite::Result<AlimentationHistory> {
+    Ok(AlimentationHistory {
+        id: Some(row.get(0)?),
+        bande_id: row.get(1)?,
+        quantite: row.get(2)?,
+        created_at: row.get(3)?,
+        fournisseur: row.get(4)?,
+        type_aliment: row.get(5)?,
+        numero_lot: row.get(6)?,
+        prix_unitaire: row.get(7)?,
+        devise: row.get(8)?,
+        taux_tva: row.get(9)?,
+        montant_tva: row.get(10)?,
+    })
+}
+
+/// Trim optional text metadata, turning blank values into None
+fn clean_text(value: &Option<String>) -> Option<String> {
+    value.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Validate and normalize the feed type against TYPES_ALIMENT
+fn clean_type_aliment(value: &Option<String>) -> Result<Option<String>, AppError> {
+    match clean_text(value) {
+        None => Ok(None),
+        Some(type_aliment) => {
+            let normalized = type_aliment.to_lowercase();
+            let normalized = if normalized == "demarrage" { "démarrage".to_string() } else { normalized };
+            if TYPES_ALIMENT.contains(&normalized.as_str()) {
+                Ok(Some(normalized))
+            } else {
+                Err(AppError::validation_error(
+                    "type_aliment",
+                    "Le type d'aliment doit être démarrage, croissance ou finition",
+                ))
+            }
+        }
+    }
+}
+
+/// Resolve the unit price, its currency and the VAT of a delivery
+///
+/// The price entered is converted before tax; without one, the feed price in
+/// effect on `created_at` is used.
+fn resoudre_prix(
+    conn: &Connection,
+    prix_unitaire: Option<f64>,
+    saisie_tva: &SaisieTva,
+    created_at: &str,
+) -> Result<(Option<f64>, Option<String>, TvaAchat), AppError> {
+    let tva = ventiler_tva(prix_unitaire, saisie_tva)?;
+    let prix_unitaire = match prix_unitaire {
+        Some(prix) if !prix.is_finite() || prix < 0.0 => {
+            return Err(AppError::validation_error("prix_unitaire", "Le prix ne peut pas être négatif"));
+        }
+        Some(_) => tva.montant_ht,
+        None => PrixRepository::prix_en_vigueur(conn, ARTICLE_ALIMENT, None, created_at)?,
+    };
+    let devise = match prix_unitaire {
+        Some(_) => Some(PrixRepository::get_devise(conn)?.code),
+        None => None,
+    };
+    Ok((prix_unitaire, devise, tva))
+}
 
 /// Repository for managing alimentation history
 pub struct AlimentationRepository;
@@ -9,7 +85,7 @@ pub struct AlimentationRepository;
 impl AlimentationRepository {
     /// Create a new alimentation history record and update the bande contour
     pub fn create(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         alimentation: &CreateAlimentationHistory,
     ) -> Result<AlimentationHistory, AppError> {
         // Validation de la bande
@@ -26,13 +102,48 @@ impl AlimentationRepository {
             ));
         }
 
+        let type_aliment = clean_type_aliment(&alimentation.type_aliment)?;
+
+        // Double clic sur "Enregistrer": même quantité sur la même bande saisie il y a quelques minutes
+        if !alimentation.confirmer_doublon {
+            let fenetre = Self::get_duplicate_window(conn)?;
+            let doublon: bool = fenetre > 0
+                && conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM alimentation_history
+                                   WHERE bande_id = ?1 AND quantite = ?2 AND saisi_le >= datetime('now', ?3))",
+                    params![alimentation.bande_id, alimentation.quantite, format!("-{} minutes", fenetre)],
+                    |row| row.get(0),
+                )?;
+            if doublon {
+                return Err(AppError::confirmation_required(
+                    "quantite",
+                    &format!(
+                        "Une livraison de {} kg a déjà été enregistrée sur cette bande il y a moins de {} minutes",
+                        alimentation.quantite, fenetre
+                    ),
+                ));
+            }
+        }
+
+        let (prix_unitaire, devise, tva) =
+            resoudre_prix(conn, alimentation.prix_unitaire, &alimentation.tva, &alimentation.created_at)?;
+
         // Insertion de l'historique d'alimentation
         conn.execute(
-            "INSERT INTO alimentation_history (bande_id, quantite, created_at) VALUES (?1, ?2, ?3)",
-            [
-                &alimentation.bande_id.to_string(),
-                &alimentation.quantite.to_string(),
-                &alimentation.created_at,
+            "INSERT INTO alimentation_history (bande_id, quantite, created_at, fournisseur, type_aliment, numero_lot,
+                                               prix_unitaire, devise, taux_tva, montant_tva, saisi_le)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, datetime('now'))",
+            params![
+                alimentation.bande_id,
+                alimentation.quantite,
+                alimentation.created_at,
+                clean_text(&alimentation.fournisseur),
+                type_aliment,
+                clean_text(&alimentation.numero_lot),
+                prix_unitaire,
+                devise,
+                tva.taux_tva,
+                tva.montant_tva,
             ],
         )?;
 
@@ -49,16 +160,9 @@ impl AlimentationRepository {
 
         // Get the created record with its timestamp
         let created_record = conn.query_row(
-            "SELECT id, bande_id, quantite, created_at FROM alimentation_history WHERE id = ?1",
+            &format!("SELECT {} FROM alimentation_history WHERE id = ?1", HISTORY_COLUMNS),
             [id],
-            |row| {
-                Ok(AlimentationHistory {
-                    id: Some(row.get(0)?),
-                    bande_id: row.get(1)?,
-                    quantite: row.get(2)?,
-                    created_at: row.get(3)?,
-                })
-            },
+            map_history_row,
         )?;
 
         Ok(created_record)
@@ -66,47 +170,239 @@ impl AlimentationRepository {
 
     /// Get all alimentation history for a specific bande, ordered by creation date (most recent first)
     pub fn get_by_bande(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         bande_id: i64,
     ) -> Result<Vec<AlimentationHistory>, AppError> {
-        let mut stmt = conn.prepare(
-            "SELECT id, bande_id, quantite, created_at
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}
              FROM alimentation_history
              WHERE bande_id = ?1
-             ORDER BY created_at DESC, id DESC"
-        )?;
+             ORDER BY created_at DESC, id DESC",
+            HISTORY_COLUMNS
+        ))?;
         
-        let alimentation_history = stmt.query_map([bande_id], |row| {
-            Ok(AlimentationHistory {
-                id: Some(row.get(0)?),
+        let alimentation_history = stmt.query_map([bande_id], map_history_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(alimentation_history)
+    }
+
+    /// Get alimentation history for a bande with pagination and optional date range filtering
+    ///
+    /// Dates are compared on the day part of `created_at` ("YYYY-MM-DD"), bounds included.
+    /// The returned `total_quantite` covers every record matching the filters.
+    pub fn get_by_bande_paginated(
+        conn: &Connection,
+        bande_id: i64,
+        page: u32,
+        per_page: u32,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> Result<PaginatedAlimentationHistory, AppError> {
+        let page = page.max(1);
+        let per_page = per_page.max(1);
+        let offset = (page - 1) * per_page;
+
+        // Build the WHERE clause based on date filters
+        let mut where_conditions = vec!["bande_id = ?1".to_string()];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(bande_id)];
+        let mut param_index = 2;
+
+        if let Some(from_date) = &date_from {
+            where_conditions.push(format!("date(created_at) >= date(?{})", param_index));
+            params.push(Box::new(from_date.clone()));
+            param_index += 1;
+        }
+
+        if let Some(to_date) = &date_to {
+            where_conditions.push(format!("date(created_at) <= date(?{})", param_index));
+            params.push(Box::new(to_date.clone()));
+            param_index += 1;
+        }
+
+        let where_clause = where_conditions.join(" AND ");
+
+        // Count and sum over the whole filter window
+        let (total, total_quantite): (u32, f64) = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT COUNT(*), COALESCE(SUM(quantite), 0) FROM alimentation_history WHERE {}",
+                where_clause
+            ))?;
+            let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            stmt.query_row(&params_refs[..], |row| {
+                Ok((row.get::<_, i64>(0)? as u32, row.get::<_, f64>(1)?))
+            })?
+        };
+
+        let select_query = format!(
+            "SELECT {}
+             FROM alimentation_history
+             WHERE {}
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?{} OFFSET ?{}",
+            HISTORY_COLUMNS, where_clause, param_index, param_index + 1
+        );
+
+        params.push(Box::new(per_page as i64));
+        params.push(Box::new(offset as i64));
+
+        let mut stmt = conn.prepare(&select_query)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+        let data = stmt.query_map(&params_refs[..], map_history_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Calculate pagination metadata
+        let total_pages = total.div_ceil(per_page);
+        let has_next = page < total_pages;
+        let has_prev = page > 1;
+
+        Ok(PaginatedAlimentationHistory {
+            data,
+            total,
+            page,
+            limit: per_page,
+            total_pages,
+            has_next,
+            has_prev,
+            total_quantite,
+        })
+    }
+
+    /// Get alimentation history across all bandes, with bande and ferme names
+    ///
+    /// Used to audit every feed delivery in one place and reconcile them with
+    /// supplier invoices. `total_quantite` covers every record matching the filters.
+    pub fn get_global_paginated(
+        conn: &Connection,
+        page: u32,
+        per_page: u32,
+        filters: &AlimentationHistoryFilters,
+    ) -> Result<PaginatedAlimentationHistoryGlobal, AppError> {
+        let page = page.max(1);
+        let per_page = per_page.max(1);
+        let offset = (page - 1) * per_page;
+
+        // Build the WHERE clause based on the filters
+        let mut where_conditions = vec!["1 = 1".to_string()];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let mut param_index = 1;
+
+        if let Some(ferme_id) = filters.ferme_id {
+            where_conditions.push(format!("b.ferme_id = ?{}", param_index));
+            params.push(Box::new(ferme_id));
+            param_index += 1;
+        }
+
+        if let Some(bande_id) = filters.bande_id {
+            where_conditions.push(format!("a.bande_id = ?{}", param_index));
+            params.push(Box::new(bande_id));
+            param_index += 1;
+        }
+
+        if let Some(from_date) = &filters.date_from {
+            where_conditions.push(format!("date(a.created_at) >= date(?{})", param_index));
+            params.push(Box::new(from_date.clone()));
+            param_index += 1;
+        }
+
+        if let Some(to_date) = &filters.date_to {
+            where_conditions.push(format!("date(a.created_at) <= date(?{})", param_index));
+            params.push(Box::new(to_date.clone()));
+            param_index += 1;
+        }
+
+        if let Some(type_aliment) = clean_type_aliment(&filters.type_aliment)? {
+            where_conditions.push(format!("a.type_aliment = ?{}", param_index));
+            params.push(Box::new(type_aliment));
+            param_index += 1;
+        }
+
+        if let Some(fournisseur) = clean_text(&filters.fournisseur) {
+            where_conditions.push(format!("a.fournisseur LIKE ?{}", param_index));
+            params.push(Box::new(format!("%{}%", fournisseur)));
+            param_index += 1;
+        }
+
+        if filters.deliveries_only.unwrap_or(false) {
+            where_conditions.push("a.quantite > 0".to_string());
+        }
+
+        let where_clause = where_conditions.join(" AND ");
+        let from_clause = "FROM alimentation_history a
+             JOIN bandes b ON a.bande_id = b.id
+             JOIN fermes f ON b.ferme_id = f.id";
+
+        // Count and sum over the whole filter window
+        let (total, total_quantite): (u32, f64) = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT COUNT(*), COALESCE(SUM(a.quantite), 0) {} WHERE {}",
+                from_clause, where_clause
+            ))?;
+            let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            stmt.query_row(&params_refs[..], |row| {
+                Ok((row.get::<_, i64>(0)? as u32, row.get::<_, f64>(1)?))
+            })?
+        };
+
+        let select_query = format!(
+            "SELECT a.id, a.bande_id, b.numero_bande, b.ferme_id, f.nom, a.quantite, a.created_at,
+                    a.fournisseur, a.type_aliment, a.numero_lot
+             {}
+             WHERE {}
+             ORDER BY a.created_at DESC, a.id DESC
+             LIMIT ?{} OFFSET ?{}",
+            from_clause, where_clause, param_index, param_index + 1
+        );
+
+        params.push(Box::new(per_page as i64));
+        params.push(Box::new(offset as i64));
+
+        let mut stmt = conn.prepare(&select_query)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+        let data = stmt.query_map(&params_refs[..], |row| {
+            Ok(AlimentationHistoryWithDetails {
+                id: row.get(0)?,
                 bande_id: row.get(1)?,
-                quantite: row.get(2)?,
-                created_at: row.get(3)?,
+                numero_bande: row.get(2)?,
+                ferme_id: row.get(3)?,
+                ferme_nom: row.get(4)?,
+                quantite: row.get(5)?,
+                created_at: row.get(6)?,
+                fournisseur: row.get(7)?,
+                type_aliment: row.get(8)?,
+                numero_lot: row.get(9)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(alimentation_history)
+        // Calculate pagination metadata
+        let total_pages = total.div_ceil(per_page);
+        let has_next = page < total_pages;
+        let has_prev = page > 1;
+
+        Ok(PaginatedAlimentationHistoryGlobal {
+            data,
+            total,
+            page,
+            limit: per_page,
+            total_pages,
+            has_next,
+            has_prev,
+            total_quantite,
+        })
     }
 
     /// Get a specific alimentation history record by ID
     pub fn get_by_id(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         id: i64,
     ) -> Result<Option<AlimentationHistory>, AppError> {
         let result = conn.query_row(
-            "SELECT id, bande_id, quantite, created_at
-             FROM alimentation_history
-             WHERE id = ?1",
+            &format!("SELECT {} FROM alimentation_history WHERE id = ?1", HISTORY_COLUMNS),
             [id],
-            |row| {
-                Ok(AlimentationHistory {
-                    id: Some(row.get(0)?),
-                    bande_id: row.get(1)?,
-                    quantite: row.get(2)?,
-                    created_at: row.get(3)?,
-                })
-            },
+            map_history_row,
         );
 
         match result {
@@ -118,7 +414,7 @@ impl AlimentationRepository {
 
     /// Update an alimentation history record and adjust the bande contour accordingly
     pub fn update(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         id: i64,
         alimentation: &UpdateAlimentationHistory,
     ) -> Result<(), AppError> {
@@ -138,25 +434,40 @@ impl AlimentationRepository {
 
         // Get the old quantity to adjust the contour properly
         let old_record = conn.query_row(
-            "SELECT bande_id, quantite FROM alimentation_history WHERE id = ?1",
+            "SELECT bande_id, quantite, created_at FROM alimentation_history WHERE id = ?1",
             [id],
             |row| {
-                Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
+                Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?))
             },
         ).map_err(|e| match e {
             rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Alimentation History", id),
             _ => AppError::from(e),
         })?;
 
-        let (old_bande_id, old_quantite) = old_record;
+        let (old_bande_id, old_quantite, created_at) = old_record;
+
+        let type_aliment = clean_type_aliment(&alimentation.type_aliment)?;
+        // Prix et TVA recalculés comme à la création: le montant de TVA suit la quantité
+        let (prix_unitaire, devise, tva) =
+            resoudre_prix(conn, alimentation.prix_unitaire, &alimentation.tva, &created_at)?;
 
         // Update the alimentation history record
         let rows_affected = conn.execute(
-            "UPDATE alimentation_history SET bande_id = ?1, quantite = ?2 WHERE id = ?3",
-            [
-                &alimentation.bande_id.to_string(),
-                &alimentation.quantite.to_string(),
-                &id.to_string(),
+            "UPDATE alimentation_history
+             SET bande_id = ?1, quantite = ?2, fournisseur = ?3, type_aliment = ?4, numero_lot = ?5,
+                 prix_unitaire = ?6, devise = ?7, taux_tva = ?8, montant_tva = ?9
+             WHERE id = ?10",
+            params![
+                alimentation.bande_id,
+                alimentation.quantite,
+                clean_text(&alimentation.fournisseur),
+                type_aliment,
+                clean_text(&alimentation.numero_lot),
+                prix_unitaire,
+                devise,
+                tva.taux_tva,
+                tva.montant_tva,
+                id,
             ],
         )?;
 
@@ -189,7 +500,7 @@ impl AlimentationRepository {
 
     /// Delete an alimentation history record and adjust the bande contour
     pub fn delete(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         id: i64,
     ) -> Result<(), AppError> {
         // Get the record details before deleting to adjust the contour
@@ -225,9 +536,59 @@ impl AlimentationRepository {
         Ok(())
     }
 
+    /// Get delivered quantities grouped by feed type, for one bande or for all bandes
+    ///
+    /// Only deliveries (positive quantities) are counted; records without a type
+    /// are grouped under `type_aliment: None`.
+    pub fn get_totals_by_type(
+        conn: &Connection,
+        bande_id: Option<i64>,
+    ) -> Result<Vec<AlimentationTypeTotal>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT type_aliment, SUM(quantite), COUNT(*)
+             FROM alimentation_history
+             WHERE quantite > 0 AND (?1 IS NULL OR bande_id = ?1)
+             GROUP BY type_aliment
+             ORDER BY CASE type_aliment
+                 WHEN 'démarrage' THEN 1
+                 WHEN 'croissance' THEN 2
+                 WHEN 'finition' THEN 3
+                 ELSE 4
+             END"
+        )?;
+
+        let totals = stmt.query_map([bande_id], |row| {
+            Ok(AlimentationTypeTotal {
+                type_aliment: row.get(0)?,
+                total_quantite: row.get(1)?,
+                nombre_livraisons: row.get::<_, i64>(2)? as u32,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(totals)
+    }
+
+    /// Get the duplicate delivery window in minutes (0 when the check is disabled)
+    pub fn get_duplicate_window(conn: &Connection) -> Result<i64, AppError> {
+        let minutes = ParametreRepository::get(conn, PARAMETRE_FENETRE_DOUBLON_LIVRAISON)?
+            .and_then(|valeur| valeur.parse::<i64>().ok())
+            .unwrap_or(FENETRE_DOUBLON_LIVRAISON_DEFAUT_MINUTES);
+        Ok(minutes.max(0))
+    }
+
+    /// Set the duplicate delivery window in minutes, 0 disables the check
+    pub fn set_duplicate_window(conn: &Connection, minutes: i64) -> Result<i64, AppError> {
+        if minutes < 0 {
+            return Err(AppError::validation_error("minutes", "La fenêtre ne peut pas être négative"));
+        }
+        ParametreRepository::set(conn, PARAMETRE_FENETRE_DOUBLON_LIVRAISON, &minutes.to_string())?;
+        Self::get_duplicate_window(conn)
+    }
+
     /// Get the current alimentation contour for a specific bande (from bandes table)
     pub fn get_contour(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         bande_id: i64,
     ) -> Result<f64, AppError> {
         let result = conn.query_row(
@@ -245,7 +606,7 @@ impl AlimentationRepository {
     /// Delete all alimentation history for a specific bande and reset its contour
     /// Useful when deleting a bande
     pub fn delete_by_bande(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         bande_id: i64,
     ) -> Result<u64, AppError> {
         // Delete all alimentation history for this bande
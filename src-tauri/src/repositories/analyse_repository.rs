@@ -0,0 +1,262 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{Analyse, CreateAnalyse, UpdateAnalyse, TYPES_ANALYSE};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row, ToSql};
+use std::sync::Arc;
+
+/// Colonnes lues pour une `Analyse`, dans l'ordre attendu par `map_analyse_row`
+const ANALYSE_SELECT: &str =
+    "SELECT a.id, a.batiment_id, a.maladie_id, m.nom, a.type_analyse, a.laboratoire,
+            a.date_prelevement, a.resultat, a.fichier_pdf, a.created_at
+     FROM analyses a
+     LEFT JOIN maladies m ON a.maladie_id = m.id";
+
+fn map_analyse_row(row: &Row) -> rusqlite::Result<Analyse> {
+    Ok(Analyse {
+        id: Some(row.get(0)?),
+        batiment_id: row.get(1)?,
+        maladie_id: row.get(2)?,
+        maladie_nom: row.get(3)?,
+        type_analyse: row.get(4)?,
+        laboratoire: row.get(5)?,
+        date_prelevement: row.get(6)?,
+        resultat: row.get(7)?,
+        fichier_pdf: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+/// Supprime les espaces superflus, une saisie vide devenant `None`
+fn clean_text(value: &Option<String>) -> Option<String> {
+    value.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Trait pour les opérations sur les analyses de laboratoire
+pub trait AnalyseRepositoryTrait: Send + Sync {
+    /// Enregistre une nouvelle analyse
+    ///
+    /// # Arguments
+    /// * `analyse` - Les données de l'analyse à créer
+    ///
+    /// # Returns
+    /// L'analyse créée avec son ID généré
+    async fn create(&self, analyse: CreateAnalyse) -> AppResult<Analyse>;
+
+    /// Récupère une analyse par son ID
+    async fn get_by_id(&self, id: i64) -> AppResult<Analyse>;
+
+    /// Liste les analyses d'un bâtiment, de la plus récente à la plus ancienne
+    async fn get_by_batiment(&self, batiment_id: i64) -> AppResult<Vec<Analyse>>;
+
+    /// Liste les analyses de tous les bâtiments d'une bande par date de prélèvement
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `type_analyse` - Filtre optionnel sur le type d'analyse
+    async fn get_by_bande(&self, bande_id: i64, type_analyse: Option<&str>) -> AppResult<Vec<Analyse>>;
+
+    /// Liste les analyses rattachées à un épisode de maladie d'un bâtiment
+    async fn get_by_maladie(&self, batiment_id: i64, maladie_id: i64) -> AppResult<Vec<Analyse>>;
+
+    /// Met à jour une analyse existante
+    async fn update(&self, analyse: UpdateAnalyse) -> AppResult<Analyse>;
+
+    /// Supprime une analyse
+    async fn delete(&self, id: i64) -> AppResult<()>;
+
+    /// Enregistre (ou retire) le chemin du compte rendu PDF d'une analyse
+    async fn set_fichier_pdf(&self, id: i64, fichier_pdf: Option<String>) -> AppResult<Analyse>;
+}
+
+/// Repository implementation for analyses
+pub struct AnalyseRepository {
+    db: Arc<dyn Storage>,
+}
+
+impl AnalyseRepository {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Valide les champs communs à la création et à la mise à jour
+    ///
+    /// # Returns
+    /// Le type d'analyse normalisé (minuscules)
+    fn validate(
+        conn: &Connection,
+        maladie_id: Option<i64>,
+        type_analyse: &str,
+        date_prelevement: &str,
+    ) -> AppResult<String> {
+        let type_analyse = type_analyse.trim().to_lowercase();
+        if !TYPES_ANALYSE.contains(&type_analyse.as_str()) {
+            return Err(AppError::validation_error(
+                "type_analyse",
+                &format!("Type d'analyse non reconnu. Types valides: {}", TYPES_ANALYSE.join(", "))
+            ));
+        }
+
+        if NaiveDate::parse_from_str(date_prelevement, "%Y-%m-%d").is_err() {
+            return Err(AppError::validation_error(
+                "date_prelevement",
+                "La date de prélèvement doit être au format AAAA-MM-JJ"
+            ));
+        }
+
+        if let Some(maladie_id) = maladie_id {
+            let maladie_exists: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM maladies WHERE id = ?1",
+                [maladie_id],
+                |row| row.get(0),
+            )?;
+            if maladie_exists == 0 {
+                return Err(AppError::not_found("Maladie", maladie_id));
+            }
+        }
+
+        Ok(type_analyse)
+    }
+
+    /// Rattache la maladie au bâtiment si l'épisode n'est pas encore déclaré
+    fn link_maladie(conn: &Connection, batiment_id: i64, maladie_id: Option<i64>) -> AppResult<()> {
+        if let Some(maladie_id) = maladie_id {
+            conn.execute(
+                "INSERT OR IGNORE INTO batiment_maladies (batiment_id, maladie_id) VALUES (?1, ?2)",
+                params![batiment_id, maladie_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn query(conn: &Connection, condition: &str, order: &str, params: &[&dyn ToSql]) -> AppResult<Vec<Analyse>> {
+        let mut stmt = conn.prepare(&format!("{} WHERE {} ORDER BY {}", ANALYSE_SELECT, condition, order))?;
+        let analyses = stmt.query_map(params, map_analyse_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(analyses)
+    }
+
+    fn find(conn: &Connection, id: i64) -> AppResult<Analyse> {
+        conn.query_row(&format!("{} WHERE a.id = ?1", ANALYSE_SELECT), [id], map_analyse_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Analyse", id),
+                _ => AppError::from(e),
+            })
+    }
+}
+
+impl AnalyseRepositoryTrait for AnalyseRepository {
+    async fn create(&self, analyse: CreateAnalyse) -> AppResult<Analyse> {
+        self.db.write(|tx| {
+            let batiment_exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM batiments WHERE id = ?1",
+                [analyse.batiment_id],
+                |row| row.get(0),
+            )?;
+            if batiment_exists == 0 {
+                return Err(AppError::not_found("Batiment", analyse.batiment_id));
+            }
+
+            let type_analyse = Self::validate(tx, analyse.maladie_id, &analyse.type_analyse, &analyse.date_prelevement)?;
+            tx.execute(
+                "INSERT INTO analyses (batiment_id, maladie_id, type_analyse, laboratoire, date_prelevement, resultat)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    analyse.batiment_id,
+                    analyse.maladie_id,
+                    type_analyse,
+                    clean_text(&analyse.laboratoire),
+                    analyse.date_prelevement,
+                    clean_text(&analyse.resultat),
+                ],
+            )?;
+            let id = tx.last_insert_rowid();
+            Self::link_maladie(tx, analyse.batiment_id, analyse.maladie_id)?;
+
+            Self::find(tx, id)
+        })
+    }
+
+    async fn get_by_id(&self, id: i64) -> AppResult<Analyse> {
+        let conn = self.db.get_connection()?;
+        Self::find(&conn, id)
+    }
+
+    async fn get_by_batiment(&self, batiment_id: i64) -> AppResult<Vec<Analyse>> {
+        let conn = self.db.get_connection()?;
+        Self::query(&conn, "a.batiment_id = ?1", "a.date_prelevement DESC, a.id DESC", &[&batiment_id])
+    }
+
+    async fn get_by_bande(&self, bande_id: i64, type_analyse: Option<&str>) -> AppResult<Vec<Analyse>> {
+        let conn = self.db.get_connection()?;
+        let type_analyse = type_analyse.map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty());
+        Self::query(
+            &conn,
+            "a.batiment_id IN (SELECT id FROM batiments WHERE bande_id = ?1)
+             AND (?2 IS NULL OR a.type_analyse = ?2)",
+            "a.date_prelevement, a.id",
+            &[&bande_id, &type_analyse],
+        )
+    }
+
+    async fn get_by_maladie(&self, batiment_id: i64, maladie_id: i64) -> AppResult<Vec<Analyse>> {
+        let conn = self.db.get_connection()?;
+        Self::query(
+            &conn,
+            "a.batiment_id = ?1 AND a.maladie_id = ?2",
+            "a.date_prelevement, a.id",
+            &[&batiment_id, &maladie_id],
+        )
+    }
+
+    async fn update(&self, analyse: UpdateAnalyse) -> AppResult<Analyse> {
+        self.db.write(|tx| {
+            let batiment_id = Self::find(tx, analyse.id)?.batiment_id;
+
+            let type_analyse = Self::validate(tx, analyse.maladie_id, &analyse.type_analyse, &analyse.date_prelevement)?;
+            tx.execute(
+                "UPDATE analyses SET maladie_id = ?1, type_analyse = ?2, laboratoire = ?3,
+                        date_prelevement = ?4, resultat = ?5
+                 WHERE id = ?6",
+                params![
+                    analyse.maladie_id,
+                    type_analyse,
+                    clean_text(&analyse.laboratoire),
+                    analyse.date_prelevement,
+                    clean_text(&analyse.resultat),
+                    analyse.id,
+                ],
+            )?;
+            Self::link_maladie(tx, batiment_id, analyse.maladie_id)?;
+
+            Self::find(tx, analyse.id)
+        })
+    }
+
+    async fn delete(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| {
+            let rows_affected = tx.execute("DELETE FROM analyses WHERE id = ?1", [id])?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Analyse", id));
+            }
+
+            Ok(())
+        })
+    }
+
+    async fn set_fichier_pdf(&self, id: i64, fichier_pdf: Option<String>) -> AppResult<Analyse> {
+        self.db.write(|tx| {
+            let rows_affected = tx.execute(
+                "UPDATE analyses SET fichier_pdf = ?1 WHERE id = ?2",
+                params![fichier_pdf, id],
+            )?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Analyse", id));
+            }
+
+            Self::find(tx, id)
+        })
+    }
+}
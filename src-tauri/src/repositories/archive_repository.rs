@@ -0,0 +1,164 @@
+use crate::database::archive::{archive_initialisee, colonnes, preparer_tables, SCHEMA_ARCHIVE, TABLES_ARCHIVEES};
+use crate::error::AppError;
+use crate::models::BandeArchivee;
+use chrono::NaiveDate;
+use rusqlite::{Connection, Row};
+
+/// Repository for the archive database (attached as `archive` on every connection)
+pub struct ArchiveRepository;
+
+/// Closed bandes whose closing date (entry date when missing) is before `?1`
+const BANDES_ELIGIBLES: &str =
+    "SELECT id FROM main.bandes WHERE statut = 'cloturee' AND COALESCE(date_cloture, date_entree) < ?1";
+
+/// Rows of `table` in `schema` belonging to the bandes selected by `bandes`
+///
+/// `bandes` is a subquery returning bande IDs; it may use the `?1` parameter.
+fn condition(table: &str, schema: &str, bandes: &str) -> String {
+    let batiments = format!("SELECT id FROM {}.batiments WHERE bande_id IN ({})", schema, bandes);
+    let semaines = format!("SELECT id FROM {}.semaines WHERE batiment_id IN ({})", schema, batiments);
+    let suivis = format!("SELECT id FROM {}.suivi_quotidien WHERE semaine_id IN ({})", schema, semaines);
+    match table {
+        "bandes" => format!("id IN ({})", bandes),
+        "batiments" | "alimentation_history" | "alertes" | "deverrouillages_periode" | "bande_tags"
+        | "budgets_bande" | "contrats_bande" => {
+            format!("bande_id IN ({})", bandes)
+        }
+        "semaines" | "batiment_maladies" | "analyses" | "notes_batiment" | "litieres" | "messages" => {
+            format!("batiment_id IN ({})", batiments)
+        }
+        "valeurs_champs_personnalises" => format!("bande_id IN ({}) OR batiment_id IN ({})", bandes, batiments),
+        "suivi_quotidien" => format!("semaine_id IN ({})", semaines),
+        "suivi_soins" => format!("suivi_id IN ({})", suivis),
+        _ => unreachable!("table non archivée: {}", table),
+    }
+}
+
+fn map_bande_archivee(row: &Row) -> rusqlite::Result<BandeArchivee> {
+    Ok(BandeArchivee {
+        id: row.get(0)?,
+        numero_bande: row.get(1)?,
+        numero_affiche: row.get(2)?,
+        ferme_id: row.get(3)?,
+        ferme_nom: row.get(4)?,
+        date_entree: row.get(5)?,
+        date_cloture: row.get(6)?,
+        notes: row.get(7)?,
+        nombre_batiments: row.get(8)?,
+        quantite_totale: row.get(9)?,
+        total_deces: row.get(10)?,
+    })
+}
+
+impl ArchiveRepository {
+    /// Move the eligible bandes and all their child rows to the archive database
+    ///
+    /// Rows keep their IDs. Children are deleted before their parents so the
+    /// selection subqueries still see the bandes being archived.
+    ///
+    /// # Returns
+    /// The IDs of the archived bandes
+    pub fn archive_bandes(conn: &Connection, date_limite: NaiveDate) -> Result<Vec<i64>, AppError> {
+        let ids: Vec<i64> = conn
+            .prepare(&format!("{} ORDER BY id", BANDES_ELIGIBLES))?
+            .query_map([date_limite], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+
+        preparer_tables(conn)?;
+        for table in TABLES_ARCHIVEES {
+            let colonnes = colonnes(conn, "main", table)?.join(", ");
+            conn.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO {archive}.{table} ({colonnes}) SELECT {colonnes} FROM main.{table} WHERE {condition}",
+                    archive = SCHEMA_ARCHIVE,
+                    condition = condition(table, "main", BANDES_ELIGIBLES),
+                ),
+                [date_limite],
+            )?;
+        }
+        for table in TABLES_ARCHIVEES.iter().rev() {
+            conn.execute(
+                &format!("DELETE FROM main.{} WHERE {}", table, condition(table, "main", BANDES_ELIGIBLES)),
+                [date_limite],
+            )?;
+        }
+
+        Ok(ids)
+    }
+
+    /// Move an archived bande and all its child rows back to the main database
+    ///
+    /// Rows keep their IDs. Parents are restored before their children, then
+    /// removed from the archive children first. Columns added to the main
+    /// tables since the archival take their default value.
+    pub fn restore_bande(conn: &Connection, bande_id: i64) -> Result<(), AppError> {
+        let archivee = archive_initialisee(conn)?
+            && conn.query_row(
+                &format!("SELECT COUNT(*) FROM {}.bandes WHERE id = ?1", SCHEMA_ARCHIVE),
+                [bande_id],
+                |row| row.get::<_, i64>(0),
+            )? > 0;
+        if !archivee {
+            return Err(AppError::not_found("BandeArchivee", bande_id));
+        }
+
+        preparer_tables(conn)?;
+        for table in TABLES_ARCHIVEES {
+            let archivees: Vec<String> = colonnes(conn, SCHEMA_ARCHIVE, table)?;
+            let colonnes = colonnes(conn, "main", table)?
+                .into_iter()
+                .filter(|colonne| archivees.contains(colonne))
+                .collect::<Vec<_>>()
+                .join(", ");
+            conn.execute(
+                &format!(
+                    "INSERT INTO main.{table} ({colonnes}) SELECT {colonnes} FROM {archive}.{table} WHERE {condition}",
+                    archive = SCHEMA_ARCHIVE,
+                    condition = condition(table, SCHEMA_ARCHIVE, "SELECT ?1"),
+                ),
+                [bande_id],
+            )?;
+        }
+        for table in TABLES_ARCHIVEES.iter().rev() {
+            conn.execute(
+                &format!(
+                    "DELETE FROM {}.{} WHERE {}",
+                    SCHEMA_ARCHIVE,
+                    table,
+                    condition(table, SCHEMA_ARCHIVE, "SELECT ?1")
+                ),
+                [bande_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Get the archived bandes, optionally for one ferme, most recent first
+    pub fn get_bandes(conn: &Connection, ferme_id: Option<i64>) -> Result<Vec<BandeArchivee>, AppError> {
+        if !archive_initialisee(conn)? {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT b.id, b.numero_bande, COALESCE(b.numero_affiche, CAST(b.numero_bande AS TEXT)), b.ferme_id, f.nom,
+                    b.date_entree, b.date_cloture, b.notes,
+                    (SELECT COUNT(*) FROM archive.batiments bt WHERE bt.bande_id = b.id),
+                    (SELECT COALESCE(SUM(bt.quantite), 0) FROM archive.batiments bt WHERE bt.bande_id = b.id),
+                    (SELECT COALESCE(SUM(sq.deces_par_jour), 0) FROM archive.suivi_quotidien sq
+                     JOIN archive.semaines s ON sq.semaine_id = s.id
+                     JOIN archive.batiments bt ON s.batiment_id = bt.id
+                     WHERE bt.bande_id = b.id)
+             FROM archive.bandes b
+             LEFT JOIN main.fermes f ON b.ferme_id = f.id
+             WHERE ?1 IS NULL OR b.ferme_id = ?1
+             ORDER BY b.date_entree DESC, b.id DESC",
+        )?;
+        let bandes = stmt
+            .query_map([ferme_id], map_bande_archivee)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(bandes)
+    }
+}
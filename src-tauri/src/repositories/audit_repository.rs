@@ -0,0 +1,146 @@
+use crate::error::AppError;
+use crate::models::{
+    ActiviteUtilisateur, BandeSaisie, EntreeAudit, Tracabilite, AUDIT_CONNEXION, AUDIT_ENTITE_SUIVI, AUDIT_SAISIE_SUIVI,
+};
+use chrono::NaiveDate;
+use rusqlite::{Connection, Row};
+
+/// Repository for the audit log
+pub struct AuditRepository;
+
+impl AuditRepository {
+    /// Record an action performed by a user on an entity
+    pub fn log(
+        conn: &Connection,
+        user_id: i64,
+        action: &str,
+        entite: &str,
+        entite_id: i64,
+        details: Option<&str>,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO audit_log (user_id, action, entite, entite_id, details) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![user_id, action, entite, entite_id, details],
+        )?;
+        Ok(())
+    }
+
+    /// Get the audit entries of an entity, most recent first
+    pub fn get_by_entite(
+        conn: &Connection,
+        entite: &str,
+        entite_id: i64,
+    ) -> Result<Vec<EntreeAudit>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.user_id, u.username, a.action, a.entite, a.entite_id, a.details, a.created_at
+             FROM audit_log a
+             LEFT JOIN users u ON a.user_id = u.id
+             WHERE a.entite = ?1 AND a.entite_id = ?2
+             ORDER BY a.created_at DESC, a.id DESC"
+        )?;
+
+        let entrees = stmt.query_map(rusqlite::params![entite, entite_id], |row| {
+            Ok(EntreeAudit {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                username: row.get(2)?,
+                action: row.get(3)?,
+                entite: row.get(4)?,
+                entite_id: row.get(5)?,
+                details: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entrees)
+    }
+
+    /// Summarize per user the daily follow-up entries recorded between two dates
+    ///
+    /// Every user is listed, even without entries. The last login is the most
+    /// recent one, whatever the period; `bandes_max` limits the most edited bandes
+    /// returned per user. Users are sorted by number of entries, most active first.
+    pub fn get_user_activity(
+        conn: &Connection,
+        date_from: Option<NaiveDate>,
+        date_to: Option<NaiveDate>,
+        bandes_max: usize,
+    ) -> Result<Vec<ActiviteUtilisateur>, AppError> {
+        let periode = "a.user_id = u.id AND a.action = ?1
+                       AND (?2 IS NULL OR date(a.created_at) >= ?2) AND (?3 IS NULL OR date(a.created_at) <= ?3)";
+        let mut stmt = conn.prepare(&format!(
+            "SELECT u.id, u.username,
+                    (SELECT COUNT(*) FROM audit_log a WHERE {}),
+                    (SELECT MAX(a.created_at) FROM audit_log a WHERE a.user_id = u.id AND a.action = ?4)
+             FROM users u
+             ORDER BY 3 DESC, u.username",
+            periode
+        ))?;
+        let mut activites = stmt.query_map(
+            rusqlite::params![AUDIT_SAISIE_SUIVI, date_from, date_to, AUDIT_CONNEXION],
+            |row| {
+                Ok(ActiviteUtilisateur {
+                    user_id: row.get(0)?,
+                    username: row.get(1)?,
+                    nombre_saisies: row.get(2)?,
+                    derniere_connexion: row.get(3)?,
+                    bandes_plus_modifiees: Vec::new(),
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        // Les saisies portent sur un jour de suivi: la bande est celle de son bâtiment
+        let mut stmt = conn.prepare(&format!(
+            "SELECT bd.id, COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)), COUNT(*)
+             FROM users u
+             JOIN audit_log a ON {}
+             JOIN suivi_quotidien sq ON a.entite = ?4 AND a.entite_id = sq.id
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE u.id = ?5
+             GROUP BY bd.id
+             ORDER BY 3 DESC, bd.id DESC
+             LIMIT ?6",
+            periode
+        ))?;
+        for activite in activites.iter_mut().filter(|activite| activite.nombre_saisies > 0) {
+            activite.bandes_plus_modifiees = stmt.query_map(
+                rusqlite::params![
+                    AUDIT_SAISIE_SUIVI,
+                    date_from,
+                    date_to,
+                    AUDIT_ENTITE_SUIVI,
+                    activite.user_id,
+                    bandes_max as i64,
+                ],
+                |row| {
+                    Ok(BandeSaisie {
+                        bande_id: row.get(0)?,
+                        numero_affiche: row.get(1)?,
+                        nombre_saisies: row.get(2)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        Ok(activites)
+    }
+}
+
+/// Read the `created_at`, `updated_at`, `created_by` and author username columns
+/// selected from position `first`
+///
+/// The author username is selected with
+/// `(SELECT username FROM users WHERE id = <alias>.created_by)`.
+pub fn read_tracabilite(row: &Row, first: usize) -> rusqlite::Result<Tracabilite> {
+    Ok(Tracabilite {
+        created_at: row.get(first)?,
+        updated_at: row.get(first + 1)?,
+        created_by: row.get(first + 2)?,
+        created_by_nom: row.get(first + 3)?,
+    })
+}
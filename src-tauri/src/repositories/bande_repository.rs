@@ -1,17 +1,41 @@
+use crate::database::erreur_mise_a_jour;
+use crate::database::numerotation::{
+    code_ferme, formater_numero_bande, FORMAT_NUMERO_BANDE_DEFAUT, PARAMETRE_FORMAT_NUMERO_BANDE,
+};
 use crate::error::AppError;
-use crate::models::{Bande, BandeWithDetails, BatimentWithDetails, CreateBande, UpdateBande, PaginatedBandes};
-use crate::repositories::AlimentationRepository;
-use r2d2::PooledConnection;
-use r2d2_sqlite::SqliteConnectionManager;
+use crate::models::{
+    Bande, BandeLoadOptions, BandeStats, BandeWithDetails, BatimentWithDetails, CreateBande, FiltreBandes,
+    UpdateBande, PaginatedBandes, ENTITE_CHAMP_BANDE, ENTITE_CHAMP_BATIMENT, STATUT_BANDE_ACTIVE, STATUT_BANDE_CLOTUREE,
+};
+use crate::repositories::{
+    read_tracabilite, AlimentationRepository, ChampPersonnaliseRepository, FiltreEnregistreRepository,
+    ParametreRepository, TagRepository,
+};
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{Connection, OptionalExtension, Transaction};
+
+/// SQL condition selecting the bandes still in progress (table alias `bd`)
+///
+/// A bande is active from its entry date until its projected catch date: the
+/// last day of the last semaine of its batiments (8 semaines when none exist yet),
+/// unless it has been closed beforehand.
+pub const BANDE_ACTIVE_CONDITION: &str =
+    "bd.statut != 'cloturee'
+     AND bd.date_entree <= date('now', 'localtime')
+     AND date(bd.date_entree, '+' || (COALESCE(
+            (SELECT MAX(s.numero_semaine) FROM semaines s
+             JOIN batiments b ON s.batiment_id = b.id
+             WHERE b.bande_id = bd.id), 8) * 7 - 1) || ' days') >= date('now', 'localtime')";
 
 /// Repository for managing bandes
 pub struct BandeRepository;
 
 impl BandeRepository {
-    /// Create a new bande
+    /// Create a new bande, `created_by` being the ID of the user who creates it
     pub fn create(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         bande: &CreateBande,
+        created_by: Option<i64>,
     ) -> Result<Bande, AppError> {
         // Validation de la ferme
         let ferme_exists: i64 = conn.query_row(
@@ -34,34 +58,47 @@ impl BandeRepository {
             |row| row.get(0),
         )?;
 
+        let numero_affiche = Self::generate_numero_affiche(conn, bande.ferme_id, bande.date_entree, next_numero)?;
+
         // Insertion de la bande
         conn.execute(
-            "INSERT INTO bandes (numero_bande, date_entree, ferme_id, notes) VALUES (?1, ?2, ?3, ?4)",
-            [
-                &next_numero.to_string(),
-                &bande.date_entree.to_string(),
-                &bande.ferme_id.to_string(),
-                &bande.notes.as_ref().unwrap_or(&String::new()),
+            "INSERT INTO bandes (numero_bande, numero_affiche, date_entree, ferme_id, notes, created_at, updated_at, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?6)",
+            rusqlite::params![
+                next_numero,
+                numero_affiche,
+                bande.date_entree.to_string(),
+                bande.ferme_id,
+                bande.notes.as_ref().unwrap_or(&String::new()),
+                created_by,
             ],
         )?;
 
         let id = conn.last_insert_rowid();
+        ChampPersonnaliseRepository::set_valeurs(conn, ENTITE_CHAMP_BANDE, id, &bande.champs_personnalises)?;
 
         Ok(Bande {
             id: Some(id),
             numero_bande: next_numero,
+            numero_affiche,
             date_entree: bande.date_entree.clone(),
             ferme_id: bande.ferme_id,
             notes: bande.notes.clone(),
+            statut: STATUT_BANDE_ACTIVE.to_string(),
+            date_cloture: None,
+            version: 1,
         })
     }
 
     /// Get all bandes with their batiments (non-paginated list)
     pub fn get_all_list(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
+        options: &BandeLoadOptions,
     ) -> Result<Vec<BandeWithDetails>, AppError> {
         let mut stmt = conn.prepare(
-            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes
+            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes,
+                    b.statut, b.date_cloture, COALESCE(b.numero_affiche, CAST(b.numero_bande AS TEXT)), b.version,
+                    b.created_at, b.updated_at, b.created_by, (SELECT username FROM users WHERE id = b.created_by)
              FROM bandes b
              JOIN fermes f ON b.ferme_id = f.id
              ORDER BY b.date_entree DESC"
@@ -75,26 +112,38 @@ impl BandeRepository {
                 row.get::<_, i64>(3)?,
                 row.get::<_, String>(4)?,
                 row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<NaiveDate>>(7)?,
+                row.get::<_, String>(8)?,
+                row.get::<_, i64>(9)?,
+                read_tracabilite(row, 10)?,
             ))
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
         let mut bandes = Vec::new();
-        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes) in bandes_result {
+        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes, statut, date_cloture, numero_affiche, version, tracabilite) in bandes_result {
             let date_entree = date_entree_str.parse().map_err(|_| {
                 AppError::business_logic("Format de date invalide dans la base de données")
             })?;
-            let batiments = Self::load_batiments(conn, id)?;
-            let alimentation_contour = AlimentationRepository::get_contour(conn, id)?;
+            let (batiments, alimentation_contour, stats) = Self::load_details(conn, id, options)?;
             bandes.push(BandeWithDetails {
                 id: Some(id),
                 numero_bande,
+                numero_affiche,
                 date_entree,
                 ferme_id,
                 ferme_nom,
                 notes,
+                statut,
+                date_cloture,
+                version,
+                tracabilite,
+                champs_personnalises: ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BANDE, id)?,
+                tags: TagRepository::get_by_bande(conn, id)?,
                 batiments,
                 alimentation_contour,
+                stats,
             });
         }
 
@@ -103,11 +152,14 @@ impl BandeRepository {
 
     /// Get bandes by ferme with their batiments
     pub fn get_by_ferme(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         ferme_id: i64,
+        options: &BandeLoadOptions,
     ) -> Result<Vec<BandeWithDetails>, AppError> {
         let mut stmt = conn.prepare(
-            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes
+            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes,
+                    b.statut, b.date_cloture, COALESCE(b.numero_affiche, CAST(b.numero_bande AS TEXT)), b.version,
+                    b.created_at, b.updated_at, b.created_by, (SELECT username FROM users WHERE id = b.created_by)
              FROM bandes b
              JOIN fermes f ON b.ferme_id = f.id
              WHERE b.ferme_id = ?1
@@ -122,26 +174,38 @@ impl BandeRepository {
                 row.get::<_, i64>(3)?,
                 row.get::<_, String>(4)?,
                 row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<NaiveDate>>(7)?,
+                row.get::<_, String>(8)?,
+                row.get::<_, i64>(9)?,
+                read_tracabilite(row, 10)?,
             ))
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
         let mut bandes = Vec::new();
-        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes) in bandes_result {
+        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes, statut, date_cloture, numero_affiche, version, tracabilite) in bandes_result {
             let date_entree = date_entree_str.parse().map_err(|_| {
                 AppError::business_logic("Format de date invalide dans la base de données")
             })?;
-            let batiments = Self::load_batiments(conn, id)?;
-            let alimentation_contour = AlimentationRepository::get_contour(conn, id)?;
+            let (batiments, alimentation_contour, stats) = Self::load_details(conn, id, options)?;
             bandes.push(BandeWithDetails {
                 id: Some(id),
                 numero_bande,
+                numero_affiche,
                 date_entree,
                 ferme_id,
                 ferme_nom,
                 notes,
+                statut,
+                date_cloture,
+                version,
+                tracabilite,
+                champs_personnalises: ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BANDE, id)?,
+                tags: TagRepository::get_by_bande(conn, id)?,
                 batiments,
                 alimentation_contour,
+                stats,
             });
         }
 
@@ -150,12 +214,15 @@ impl BandeRepository {
 
     /// Get latest bandes by ferme (limited for selectors)
     pub fn get_latest_by_ferme(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         ferme_id: i64,
         limit: u32,
+        options: &BandeLoadOptions,
     ) -> Result<Vec<BandeWithDetails>, AppError> {
         let mut stmt = conn.prepare(
-            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes
+            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes,
+                    b.statut, b.date_cloture, COALESCE(b.numero_affiche, CAST(b.numero_bande AS TEXT)), b.version,
+                    b.created_at, b.updated_at, b.created_by, (SELECT username FROM users WHERE id = b.created_by)
              FROM bandes b
              JOIN fermes f ON b.ferme_id = f.id
              WHERE b.ferme_id = ?1
@@ -171,59 +238,101 @@ impl BandeRepository {
                 row.get::<_, i64>(3)?,
                 row.get::<_, String>(4)?,
                 row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<NaiveDate>>(7)?,
+                row.get::<_, String>(8)?,
+                row.get::<_, i64>(9)?,
+                read_tracabilite(row, 10)?,
             ))
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
         let mut bandes = Vec::new();
-        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes) in bandes_result {
+        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes, statut, date_cloture, numero_affiche, version, tracabilite) in bandes_result {
             let date_entree = date_entree_str.parse().map_err(|_| {
                 AppError::business_logic("Format de date invalide dans la base de données")
             })?;
-            let batiments = Self::load_batiments(conn, id)?;
-            let alimentation_contour = AlimentationRepository::get_contour(conn, id)?;
+            let (batiments, alimentation_contour, stats) = Self::load_details(conn, id, options)?;
             bandes.push(BandeWithDetails {
                 id: Some(id),
                 numero_bande,
+                numero_affiche,
                 date_entree,
                 ferme_id,
                 ferme_nom,
                 notes,
+                statut,
+                date_cloture,
+                version,
+                tracabilite,
+                champs_personnalises: ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BANDE, id)?,
+                tags: TagRepository::get_by_bande(conn, id)?,
                 batiments,
                 alimentation_contour,
+                stats,
             });
         }
 
         Ok(bandes)
     }
 
-    /// Get bandes by ferme with pagination and optional date range filtering
+    /// Get bandes by ferme with pagination, filtered by the given criteria
     pub fn get_by_ferme_paginated(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         ferme_id: i64,
         page: u32,
         per_page: u32,
-        date_from: Option<String>,
-        date_to: Option<String>,
+        filtre: &FiltreBandes,
+        options: &BandeLoadOptions,
     ) -> Result<PaginatedBandes, AppError> {
+        FiltreEnregistreRepository::validate_filtre_bandes(filtre)?;
         let offset = (page - 1) * per_page;
         
-        // Build the WHERE clause based on date filters
+        // Build the WHERE clause based on the filter criteria
         let mut where_conditions = vec!["b.ferme_id = ?1".to_string()];
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(ferme_id)];
         let mut param_index = 2;
         
-        if let Some(from_date) = &date_from {
+        if let Some(from_date) = &filtre.date_from {
             where_conditions.push(format!("b.date_entree >= ?{}", param_index));
             params.push(Box::new(from_date.clone()));
             param_index += 1;
         }
         
-        if let Some(to_date) = &date_to {
+        if let Some(to_date) = &filtre.date_to {
             where_conditions.push(format!("b.date_entree <= ?{}", param_index));
             params.push(Box::new(to_date.clone()));
             param_index += 1;
         }
+
+        if let Some(tag_id) = filtre.tag_id {
+            where_conditions.push(format!(
+                "b.id IN (SELECT bande_id FROM bande_tags WHERE tag_id = ?{})",
+                param_index
+            ));
+            params.push(Box::new(tag_id));
+            param_index += 1;
+        }
+
+        if let Some(statut) = &filtre.statut {
+            where_conditions.push(format!("b.statut = ?{}", param_index));
+            params.push(Box::new(statut.clone()));
+            param_index += 1;
+        }
+
+        // Mortality as in `load_stats`: deaths over the initial headcount
+        if let Some(mortalite_min) = filtre.mortalite_min {
+            where_conditions.push(format!(
+                "(SELECT COALESCE(SUM(sq.deces_par_jour), 0) FROM suivi_quotidien sq
+                  JOIN semaines s ON sq.semaine_id = s.id
+                  JOIN batiments bt ON s.batiment_id = bt.id
+                  WHERE bt.bande_id = b.id) * 100.0
+                 >= ?{} * (SELECT NULLIF(SUM(quantite), 0) FROM batiments WHERE bande_id = b.id)",
+                param_index
+            ));
+            params.push(Box::new(mortalite_min));
+            param_index += 1;
+        }
         
         let where_clause = where_conditions.join(" AND ");
         
@@ -241,7 +350,9 @@ impl BandeRepository {
         
         // Get paginated data with filters
         let select_query = format!(
-            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes
+            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes,
+                    b.statut, b.date_cloture, COALESCE(b.numero_affiche, CAST(b.numero_bande AS TEXT)), b.version,
+                    b.created_at, b.updated_at, b.created_by, (SELECT username FROM users WHERE id = b.created_by)
              FROM bandes b
              JOIN fermes f ON b.ferme_id = f.id
              WHERE {}
@@ -265,26 +376,38 @@ impl BandeRepository {
                 row.get::<_, i64>(3)?,
                 row.get::<_, String>(4)?,
                 row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<NaiveDate>>(7)?,
+                row.get::<_, String>(8)?,
+                row.get::<_, i64>(9)?,
+                read_tracabilite(row, 10)?,
             ))
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
         let mut bandes = Vec::new();
-        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes) in bandes_result {
+        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes, statut, date_cloture, numero_affiche, version, tracabilite) in bandes_result {
             let date_entree = date_entree_str.parse().map_err(|_| {
                 AppError::business_logic("Format de date invalide dans la base de données")
             })?;
-            let batiments = Self::load_batiments(conn, id)?;
-            let alimentation_contour = AlimentationRepository::get_contour(conn, id)?;
+            let (batiments, alimentation_contour, stats) = Self::load_details(conn, id, options)?;
             bandes.push(BandeWithDetails {
                 id: Some(id),
                 numero_bande,
+                numero_affiche,
                 date_entree,
                 ferme_id,
                 ferme_nom,
                 notes,
+                statut,
+                date_cloture,
+                version,
+                tracabilite,
+                champs_personnalises: ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BANDE, id)?,
+                tags: TagRepository::get_by_bande(conn, id)?,
                 batiments,
                 alimentation_contour,
+                stats,
             });
         }
 
@@ -305,12 +428,13 @@ impl BandeRepository {
 
     /// Get bandes by ferme with pagination and date range filtering
     pub fn get_by_ferme_paginated_with_date_filter(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         ferme_id: i64,
         page: u32,
         per_page: u32,
         date_from: Option<String>,
         date_to: Option<String>,
+        options: &BandeLoadOptions,
     ) -> Result<PaginatedBandes, AppError> {
         let offset = (page - 1) * per_page;
         
@@ -347,7 +471,9 @@ impl BandeRepository {
         
         // Get paginated data with filters
         let select_query = format!(
-            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes
+            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes,
+                    b.statut, b.date_cloture, COALESCE(b.numero_affiche, CAST(b.numero_bande AS TEXT)), b.version,
+                    b.created_at, b.updated_at, b.created_by, (SELECT username FROM users WHERE id = b.created_by)
              FROM bandes b
              JOIN fermes f ON b.ferme_id = f.id
              WHERE {}
@@ -371,26 +497,38 @@ impl BandeRepository {
                 row.get::<_, i64>(3)?,
                 row.get::<_, String>(4)?,
                 row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<NaiveDate>>(7)?,
+                row.get::<_, String>(8)?,
+                row.get::<_, i64>(9)?,
+                read_tracabilite(row, 10)?,
             ))
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
         let mut bandes = Vec::new();
-        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes) in bandes_result {
+        for (id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes, statut, date_cloture, numero_affiche, version, tracabilite) in bandes_result {
             let date_entree = date_entree_str.parse().map_err(|_| {
                 AppError::business_logic("Format de date invalide dans la base de données")
             })?;
-            let batiments = Self::load_batiments(conn, id)?;
-            let alimentation_contour = AlimentationRepository::get_contour(conn, id)?;
+            let (batiments, alimentation_contour, stats) = Self::load_details(conn, id, options)?;
             bandes.push(BandeWithDetails {
                 id: Some(id),
                 numero_bande,
+                numero_affiche,
                 date_entree,
                 ferme_id,
                 ferme_nom,
                 notes,
+                statut,
+                date_cloture,
+                version,
+                tracabilite,
+                champs_personnalises: ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BANDE, id)?,
+                tags: TagRepository::get_by_bande(conn, id)?,
                 batiments,
                 alimentation_contour,
+                stats,
             });
         }
 
@@ -412,11 +550,14 @@ impl BandeRepository {
 
     /// Get a bande by ID with its batiments
     pub fn get_by_id(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         id: i64,
+        options: &BandeLoadOptions,
     ) -> Result<Option<BandeWithDetails>, AppError> {
         let result = conn.query_row(
-            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes
+            "SELECT b.id, b.numero_bande, b.date_entree, b.ferme_id, f.nom as ferme_nom, b.notes,
+                    b.statut, b.date_cloture, COALESCE(b.numero_affiche, CAST(b.numero_bande AS TEXT)), b.version,
+                    b.created_at, b.updated_at, b.created_by, (SELECT username FROM users WHERE id = b.created_by)
              FROM bandes b
              JOIN fermes f ON b.ferme_id = f.id
              WHERE b.id = ?1",
@@ -428,25 +569,37 @@ impl BandeRepository {
                 row.get::<_, i64>(3)?,
                 row.get::<_, String>(4)?,
                 row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<NaiveDate>>(7)?,
+                row.get::<_, String>(8)?,
+                row.get::<_, i64>(9)?,
+                read_tracabilite(row, 10)?,
             )),
         );
 
         match result {
-            Ok((id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes)) => {
+            Ok((id, numero_bande, date_entree_str, ferme_id, ferme_nom, notes, statut, date_cloture, numero_affiche, version, tracabilite)) => {
                 let date_entree = date_entree_str.parse().map_err(|_| {
                     AppError::business_logic("Format de date invalide dans la base de données")
                 })?;
-                let batiments = Self::load_batiments(conn, id)?;
-                let alimentation_contour = AlimentationRepository::get_contour(conn, id)?;
+                let (batiments, alimentation_contour, stats) = Self::load_details(conn, id, options)?;
                 Ok(Some(BandeWithDetails {
                     id: Some(id),
                     numero_bande,
+                    numero_affiche,
                     date_entree,
                     ferme_id,
                     ferme_nom,
                     notes,
+                    statut,
+                    date_cloture,
+                    version,
+                    tracabilite,
+                    champs_personnalises: ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BANDE, id)?,
+                    tags: TagRepository::get_by_bande(conn, id)?,
                     batiments,
                     alimentation_contour,
+                    stats,
                 }))
             }
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
@@ -456,7 +609,7 @@ impl BandeRepository {
 
     /// Update a bande
     pub fn update(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         id: i64,
         bande: &UpdateBande,
     ) -> Result<(), AppError> {
@@ -474,18 +627,90 @@ impl BandeRepository {
             ));
         }
 
-        // Mise à jour de la bande
+        // The display number is regenerated only when one of its inputs changes,
+        // so that a later change of format does not renumber existing bandes
+        let existing: Option<(i32, NaiveDate, i64, Option<String>)> = conn.query_row(
+            "SELECT numero_bande, date_entree, ferme_id, numero_affiche FROM bandes WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        ).optional()?;
+        let Some((numero_bande, date_entree, ferme_id, numero_affiche)) = existing else {
+            return Err(AppError::not_found("Bande", id));
+        };
+        let numero_affiche = if numero_bande != bande.numero_bande
+            || ferme_id != bande.ferme_id
+            || date_entree.year() != bande.date_entree.year()
+        {
+            Some(Self::generate_numero_affiche(conn, bande.ferme_id, bande.date_entree, bande.numero_bande)?)
+        } else {
+            numero_affiche
+        };
+
+        // Mise à jour de la bande, refusée si elle a été modifiée depuis son chargement
         let rows_affected = conn.execute(
-            "UPDATE bandes SET numero_bande = ?1, date_entree = ?2, ferme_id = ?3, notes = ?4 WHERE id = ?5",
-            [
-                &bande.numero_bande.to_string(),
-                &bande.date_entree.to_string(),
-                &bande.ferme_id.to_string(),
-                &bande.notes.as_ref().unwrap_or(&String::new()),
-                &id.to_string(),
+            "UPDATE bandes SET numero_bande = ?1, date_entree = ?2, ferme_id = ?3, notes = ?4, numero_affiche = ?5,
+                    version = version + 1, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?6 AND (?7 IS NULL OR version = ?7)",
+            rusqlite::params![
+                bande.numero_bande,
+                bande.date_entree.to_string(),
+                bande.ferme_id,
+                bande.notes.as_ref().unwrap_or(&String::new()),
+                numero_affiche,
+                id,
+                bande.version,
             ],
         )?;
 
+        if rows_affected == 0 {
+            return Err(erreur_mise_a_jour(conn, "bandes", "Bande", id, bande.version));
+        }
+
+        ChampPersonnaliseRepository::set_valeurs(conn, ENTITE_CHAMP_BANDE, id, &bande.champs_personnalises)?;
+
+        Ok(())
+    }
+
+    /// Build the display number of a bande from the configured format
+    fn generate_numero_affiche(
+        conn: &Connection,
+        ferme_id: i64,
+        date_entree: NaiveDate,
+        numero_bande: i32,
+    ) -> Result<String, AppError> {
+        let format = ParametreRepository::get(conn, PARAMETRE_FORMAT_NUMERO_BANDE)?
+            .unwrap_or_else(|| FORMAT_NUMERO_BANDE_DEFAUT.to_string());
+        let ferme_nom: String = conn.query_row(
+            "SELECT nom FROM fermes WHERE id = ?1",
+            [ferme_id],
+            |row| row.get(0),
+        )?;
+
+        formater_numero_bande(&format, date_entree.year(), &code_ferme(&ferme_nom), numero_bande)
+    }
+
+    /// Get the statut of a bande
+    pub fn get_statut(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<String, AppError> {
+        conn.query_row("SELECT statut FROM bandes WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Bande", id),
+                _ => AppError::from(e),
+            })
+    }
+
+    /// Close a bande: its semaines and suivi become read-only
+    pub fn close(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute(
+            "UPDATE bandes SET statut = ?1, date_cloture = date('now', 'localtime') WHERE id = ?2",
+            rusqlite::params![STATUT_BANDE_CLOTUREE, id],
+        )?;
+
         if rows_affected == 0 {
             return Err(AppError::not_found("Bande", id));
         }
@@ -493,65 +718,101 @@ impl BandeRepository {
         Ok(())
     }
 
-    /// Delete a bande with cascade deletion of all associated data
-    /// 
-    /// This function manually deletes all associated data in the correct order:
-    /// 1. suivi_quotidien (through batiments -> semaines)
-    /// 2. semaines (through batiments)
-    /// 3. batiment_maladies (through batiments)
-    /// 4. batiments
-    /// 5. bandes
-    pub fn delete(
-        conn: &mut PooledConnection<SqliteConnectionManager>,
+    /// Reopen a closed bande, making its semaines and suivi editable again
+    pub fn reopen(
+        conn: &Connection,
         id: i64,
     ) -> Result<(), AppError> {
-        // Start a transaction to ensure data consistency
-        let tx = conn.transaction()?;
-        
-        // 1. Delete all suivi_quotidien records associated with this bande's batiments
-        let semaine_ids: Vec<i64> = tx.prepare(
-            "SELECT s.id FROM semaines s
+        let rows_affected = conn.execute(
+            "UPDATE bandes SET statut = ?1, date_cloture = NULL WHERE id = ?2",
+            rusqlite::params![STATUT_BANDE_ACTIVE, id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("Bande", id));
+        }
+
+        Ok(())
+    }
+
+    /// Reject writes on the semaines of a batiment whose bande is closed
+    pub fn ensure_batiment_modifiable(conn: &Connection, batiment_id: i64) -> Result<(), AppError> {
+        Self::ensure_ouverte(
+            conn,
+            "SELECT bd.numero_bande, bd.statut FROM batiments b
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE b.id = ?1",
+            batiment_id,
+        )
+    }
+
+    /// Reject writes on a semaine (and its suivi) whose bande is closed
+    pub fn ensure_semaine_modifiable(conn: &Connection, semaine_id: i64) -> Result<(), AppError> {
+        Self::ensure_ouverte(
+            conn,
+            "SELECT bd.numero_bande, bd.statut FROM semaines s
              JOIN batiments b ON s.batiment_id = b.id
-             WHERE b.bande_id = ?1"
-        )?
-        .query_map([id], |row| row.get(0))?
-        .collect::<Result<Vec<_>, _>>()?;
-        
-        if !semaine_ids.is_empty() {
-            let placeholders = std::iter::repeat("?")
-                .take(semaine_ids.len())
-                .collect::<Vec<_>>()
-                .join(",");
-            
-            tx.execute(
-                &format!("DELETE FROM suivi_quotidien WHERE semaine_id IN ({})", placeholders),
-                rusqlite::params_from_iter(semaine_ids.iter()),
-            )?;
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE s.id = ?1",
+            semaine_id,
+        )
+    }
+
+    /// Reject writes on a suivi_quotidien row whose bande is closed
+    pub fn ensure_suivi_modifiable(conn: &Connection, suivi_id: i64) -> Result<(), AppError> {
+        Self::ensure_ouverte(
+            conn,
+            "SELECT bd.numero_bande, bd.statut FROM suivi_quotidien sq
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE sq.id = ?1",
+            suivi_id,
+        )
+    }
+
+    /// Reject writes on a suivi_soins row whose bande is closed
+    pub fn ensure_suivi_soin_modifiable(conn: &Connection, suivi_soin_id: i64) -> Result<(), AppError> {
+        Self::ensure_ouverte(
+            conn,
+            "SELECT bd.numero_bande, bd.statut FROM suivi_soins ss
+             JOIN suivi_quotidien sq ON ss.suivi_id = sq.id
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE ss.id = ?1",
+            suivi_soin_id,
+        )
+    }
+
+    /// Fail when the bande selected by `bande_query` (from the row ID `?1`) is closed
+    ///
+    /// A missing row is left to the caller's own existence checks.
+    fn ensure_ouverte(conn: &Connection, bande_query: &str, id: i64) -> Result<(), AppError> {
+        let bande: Option<(i32, String)> = conn
+            .query_row(bande_query, [id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?;
+
+        match bande {
+            Some((numero_bande, statut)) if statut == STATUT_BANDE_CLOTUREE => Err(AppError::business_logic(&format!(
+                "La bande {} est clôturée: ses semaines et son suivi ne sont plus modifiables",
+                numero_bande
+            ))),
+            _ => Ok(()),
         }
-        
-        // 2. Delete all semaines for this bande's batiments
-        tx.execute(
-            "DELETE FROM semaines WHERE batiment_id IN (
-                SELECT id FROM batiments WHERE bande_id = ?1
-            )",
-            [id],
-        )?;
-        
-        // 3. Delete all maladie associations for this bande's batiments
-        tx.execute(
-            "DELETE FROM batiment_maladies WHERE batiment_id IN (
-                SELECT id FROM batiments WHERE bande_id = ?1
-            )",
-            [id],
-        )?;
-        
-        // 4. Delete all batiments for this bande
-        tx.execute(
-            "DELETE FROM batiments WHERE bande_id = ?1",
-            [id],
-        )?;
-        
-        // 5. Finally delete the bande itself
+    }
+
+    /// Delete a bande
+    /// 
+    /// Its batiments, semaines, suivis, feed deliveries, maladie links,
+    /// analyses, notes and litter records are deleted by the schema's `ON DELETE CASCADE`
+    /// (see `database::cascades`).
+    /// 
+    /// Must run inside a write transaction (see `Storage::write`).
+    pub fn delete(
+        tx: &Transaction,
+        id: i64,
+    ) -> Result<(), AppError> {
         let rows_affected = tx.execute(
             "DELETE FROM bandes WHERE id = ?1",
             [id],
@@ -560,17 +821,18 @@ impl BandeRepository {
         if rows_affected == 0 {
             return Err(AppError::not_found("Bande", id));
         }
-        
-        // Commit the transaction
-        tx.commit()?;
 
         Ok(())
     }
 
     /// Get available batiment numbers for a ferme
+    ///
+    /// Buildings hosting a bande in progress (see `BANDE_ACTIVE_CONDITION`) are
+    /// left out, unless `autoriser_cohabitation` is set for an intentional co-location.
     pub fn get_available_batiments(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         ferme_id: i64,
+        autoriser_cohabitation: bool,
     ) -> Result<Vec<String>, AppError> {
         // Get the number of meubles in the ferme
         let nbr_meuble: i32 = conn.query_row(
@@ -582,21 +844,127 @@ impl BandeRepository {
             _ => AppError::from(e),
         })?;
 
-        // Return all available batiment numbers (1 to nbr_meuble)
-        // Since bandes are historical records, all batiments can be reused for new bandes
-        let available: Vec<String> = (1..=nbr_meuble).map(|i| i.to_string()).collect();
+        let en_cours = if autoriser_cohabitation {
+            Vec::new()
+        } else {
+            Self::get_batiments_en_cours(conn, ferme_id, None)?
+        };
+
+        // Batiment numbers 1 to nbr_meuble, buildings of finished bandes being reusable
+        let available: Vec<String> = (1..=nbr_meuble)
+            .map(|i| i.to_string())
+            .filter(|numero| !en_cours.iter().any(|(occupe, _)| occupe == numero))
+            .collect();
 
         Ok(available)
     }
 
+    /// Batiment numbers hosting a bande in progress of a ferme, with the numero of that bande
+    ///
+    /// `exclude_bande_id` leaves out the batiments of a bande (the one being filled).
+    pub fn get_batiments_en_cours(
+        conn: &Connection,
+        ferme_id: i64,
+        exclude_bande_id: Option<i64>,
+    ) -> Result<Vec<(String, String)>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT DISTINCT bat.numero_batiment, COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT))
+             FROM batiments bat
+             JOIN bandes bd ON bat.bande_id = bd.id
+             WHERE bd.ferme_id = ?1 AND (?2 IS NULL OR bd.id != ?2) AND {}
+             ORDER BY CAST(bat.numero_batiment AS INTEGER), bat.numero_batiment",
+            BANDE_ACTIVE_CONDITION
+        ))?;
+        let batiments = stmt
+            .query_map(rusqlite::params![ferme_id, exclude_bande_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(batiments)
+    }
+
+    /// Batiment numbers used by the active bandes of a ferme
+    pub fn get_occupied_batiments(
+        conn: &Connection,
+        ferme_id: i64,
+    ) -> Result<Vec<String>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT b.numero_batiment FROM batiments b
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE bd.ferme_id = ?1 AND bd.statut = ?2",
+        )?;
+        let numeros = stmt
+            .query_map(rusqlite::params![ferme_id, STATUT_BANDE_ACTIVE], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(numeros)
+    }
+
+    /// Numero the next bande of a ferme would get, and whether another bande already shows it
+    pub fn preview_numero_affiche(
+        conn: &Connection,
+        ferme_id: i64,
+        date_entree: NaiveDate,
+    ) -> Result<(String, bool), AppError> {
+        let next_numero: i32 = conn.query_row(
+            "SELECT COALESCE(MAX(numero_bande), 0) + 1 FROM bandes WHERE ferme_id = ?1",
+            [ferme_id],
+            |row| row.get(0),
+        )?;
+        let numero_affiche = Self::generate_numero_affiche(conn, ferme_id, date_entree, next_numero)?;
+        let deja_utilise: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM bandes WHERE ferme_id = ?1 AND numero_affiche = ?2)",
+            rusqlite::params![ferme_id, numero_affiche],
+            |row| row.get(0),
+        )?;
+        Ok((numero_affiche, deja_utilise))
+    }
+
+    /// Load the batiments, contour and stats of a bande, as requested by `options`
+    fn load_details(
+        conn: &Connection,
+        bande_id: i64,
+        options: &BandeLoadOptions,
+    ) -> Result<(Vec<BatimentWithDetails>, f64, Option<BandeStats>), AppError> {
+        let batiments = if options.include_batiments { Self::load_batiments(conn, bande_id)? } else { Vec::new() };
+        let contour = if options.include_contour { AlimentationRepository::get_contour(conn, bande_id)? } else { 0.0 };
+        let stats = if options.include_stats { Some(Self::load_stats(conn, bande_id)?) } else { None };
+        Ok((batiments, contour, stats))
+    }
+
+    /// Compute the stats of a bande from its batiments and daily suivi
+    fn load_stats(
+        conn: &Connection,
+        bande_id: i64,
+    ) -> Result<BandeStats, AppError> {
+        let (effectif_initial, total_deces, alimentation_consommee, dernier_age) = conn.query_row(
+            "SELECT (SELECT COALESCE(SUM(quantite), 0) FROM batiments WHERE bande_id = ?1),
+                    COALESCE(SUM(sq.deces_par_jour), 0),
+                    COALESCE(SUM(sq.alimentation_par_jour), 0.0),
+                    MAX(sq.age)
+             FROM suivi_quotidien sq
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             WHERE b.bande_id = ?1",
+            [bande_id],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, f64>(2)?, row.get::<_, Option<i32>>(3)?)),
+        )?;
+
+        Ok(BandeStats {
+            effectif_initial,
+            total_deces,
+            mortalite_pourcentage: (effectif_initial > 0).then(|| total_deces as f64 * 100.0 / effectif_initial as f64),
+            alimentation_consommee,
+            dernier_age,
+        })
+    }
+
     /// Load batiments for a bande
     fn load_batiments(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         bande_id: i64,
     ) -> Result<Vec<BatimentWithDetails>, AppError> {
         let mut stmt = conn.prepare(
             "SELECT bat.id, bat.bande_id, bat.numero_batiment, bat.poussin_id,
-                    pous.nom as poussin_nom, bat.personnel_id, p.nom as personnel_nom, bat.quantite
+                    pous.nom as poussin_nom, bat.personnel_id, p.nom as personnel_nom, bat.quantite, bat.version,
+                    bat.created_at, bat.updated_at, bat.created_by, (SELECT username FROM users WHERE id = bat.created_by)
              FROM batiments bat
              JOIN personnel p ON bat.personnel_id = p.id
              JOIN poussins pous ON bat.poussin_id = pous.id
@@ -604,7 +972,7 @@ impl BandeRepository {
              ORDER BY bat.numero_batiment"
         )?;
         
-        let batiments = stmt.query_map([bande_id], |row| {
+        let mut batiments = stmt.query_map([bande_id], |row| {
             Ok(BatimentWithDetails {
                 id: Some(row.get(0)?),
                 bande_id: row.get(1)?,
@@ -614,10 +982,19 @@ impl BandeRepository {
                 personnel_id: row.get(5)?,
                 personnel_nom: row.get(6)?,
                 quantite: row.get(7)?,
+                version: row.get(8)?,
+                tracabilite: read_tracabilite(row, 9)?,
+                champs_personnalises: Default::default(),
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
+        for batiment in &mut batiments {
+            if let Some(id) = batiment.id {
+                batiment.champs_personnalises = ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BATIMENT, id)?;
+            }
+        }
+
         Ok(batiments)
     }
 }
\ No newline at end of file
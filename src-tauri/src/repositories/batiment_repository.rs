@@ -1,17 +1,25 @@
+use crate::database::erreur_mise_a_jour;
 use crate::error::AppError;
-use crate::models::{Batiment, BatimentWithDetails, CreateBatiment, UpdateBatiment, Maladie};
+use crate::models::{
+    Batiment, BatimentWithDetails, CreateBatiment, ModificationBatiment, UpdateBatiment, Maladie, ARTICLE_POUSSIN,
+    ENTITE_CHAMP_BATIMENT,
+};
+use crate::repositories::{
+    read_tracabilite, BandeRepository, ChampPersonnaliseRepository, PersonnelRepository, PoussinRepository,
+    PrixRepository,
+};
 use chrono::{DateTime, Utc};
-use r2d2::PooledConnection;
-use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, OptionalExtension, Transaction};
 
 /// Repository for managing batiments
 pub struct BatimentRepository;
 
 impl BatimentRepository {
-    /// Create a new batiment
+    /// Create a new batiment, `created_by` being the ID of the user who creates it
     pub fn create(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         batiment: &CreateBatiment,
+        created_by: Option<i64>,
     ) -> Result<Batiment, AppError> {
         // Validation des clés étrangères
         let bande_exists: i64 = conn.query_row(
@@ -68,20 +76,48 @@ impl BatimentRepository {
             ));
         }
 
-        // Insertion du bâtiment
+        // Refuser un bâtiment occupé par une autre bande en cours, sauf cohabitation voulue
+        if !batiment.autoriser_cohabitation {
+            let ferme_id: i64 = conn.query_row(
+                "SELECT ferme_id FROM bandes WHERE id = ?1",
+                [batiment.bande_id],
+                |row| row.get(0),
+            )?;
+            let numero = batiment.numero_batiment.trim();
+            let occupant = BandeRepository::get_batiments_en_cours(conn, ferme_id, Some(batiment.bande_id))?
+                .into_iter()
+                .find(|(occupe, _)| occupe == numero);
+            if let Some((_, numero_bande)) = occupant {
+                return Err(AppError::validation_error(
+                    "numero_batiment",
+                    &format!(
+                        "Le bâtiment {} est occupé par la bande {} en cours; autorisez la cohabitation pour l'y ajouter",
+                        numero, numero_bande
+                    )
+                ));
+            }
+        }
+
+        // Insertion du bâtiment, au prix des poussins à l'entrée de la bande
+        let (prix_poussin, devise_poussin) = Self::prix_poussin(conn, batiment.bande_id, batiment.poussin_id)?;
         conn.execute(
-            "INSERT INTO batiments (bande_id, numero_batiment, poussin_id, personnel_id, quantite) 
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO batiments (bande_id, numero_batiment, poussin_id, personnel_id, quantite, prix_poussin,
+                                    devise_poussin, created_at, updated_at, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?8)",
             rusqlite::params![
                 batiment.bande_id,
                 batiment.numero_batiment,
                 batiment.poussin_id,
                 batiment.personnel_id,
                 batiment.quantite,
+                prix_poussin,
+                devise_poussin,
+                created_by,
             ],
         )?;
 
         let id = conn.last_insert_rowid();
+        ChampPersonnaliseRepository::set_valeurs(conn, ENTITE_CHAMP_BATIMENT, id, &batiment.champs_personnalises)?;
 
         Ok(Batiment {
             id: Some(id),
@@ -90,17 +126,19 @@ impl BatimentRepository {
             poussin_id: batiment.poussin_id,
             personnel_id: batiment.personnel_id,
             quantite: batiment.quantite,
+            version: 1,
         })
     }
 
     /// Get all batiments for a specific bande
     pub fn get_by_bande(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         bande_id: i64,
     ) -> Result<Vec<BatimentWithDetails>, AppError> {
         let mut stmt = conn.prepare(
             "SELECT bat.id, bat.bande_id, bat.numero_batiment, bat.poussin_id,
-                    pous.nom as poussin_nom, bat.personnel_id, p.nom as personnel_nom, bat.quantite
+                    pous.nom as poussin_nom, bat.personnel_id, p.nom as personnel_nom, bat.quantite, bat.version,
+                    bat.created_at, bat.updated_at, bat.created_by, (SELECT username FROM users WHERE id = bat.created_by)
              FROM batiments bat
              JOIN personnel p ON bat.personnel_id = p.id
              JOIN poussins pous ON bat.poussin_id = pous.id
@@ -108,7 +146,7 @@ impl BatimentRepository {
              ORDER BY bat.numero_batiment"
         )?;
         
-        let batiments = stmt.query_map([bande_id], |row| {
+        let mut batiments = stmt.query_map([bande_id], |row| {
             Ok(BatimentWithDetails {
                 id: Some(row.get(0)?),
                 bande_id: row.get(1)?,
@@ -118,21 +156,31 @@ impl BatimentRepository {
                 personnel_id: row.get(5)?,
                 personnel_nom: row.get(6)?,
                 quantite: row.get(7)?,
+                version: row.get(8)?,
+                tracabilite: read_tracabilite(row, 9)?,
+                champs_personnalises: Default::default(),
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
+        for batiment in &mut batiments {
+            if let Some(id) = batiment.id {
+                batiment.champs_personnalises = ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BATIMENT, id)?;
+            }
+        }
+
         Ok(batiments)
     }
 
     /// Get a batiment by ID
     pub fn get_by_id(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         id: i64,
     ) -> Result<Option<BatimentWithDetails>, AppError> {
         let result = conn.query_row(
             "SELECT bat.id, bat.bande_id, bat.numero_batiment, bat.poussin_id,
-                    pous.nom as poussin_nom, bat.personnel_id, p.nom as personnel_nom, bat.quantite
+                    pous.nom as poussin_nom, bat.personnel_id, p.nom as personnel_nom, bat.quantite, bat.version,
+                    bat.created_at, bat.updated_at, bat.created_by, (SELECT username FROM users WHERE id = bat.created_by)
              FROM batiments bat
              JOIN personnel p ON bat.personnel_id = p.id
              JOIN poussins pous ON bat.poussin_id = pous.id
@@ -147,11 +195,17 @@ impl BatimentRepository {
                 personnel_id: row.get(5)?,
                 personnel_nom: row.get(6)?,
                 quantite: row.get(7)?,
+                version: row.get(8)?,
+                tracabilite: read_tracabilite(row, 9)?,
+                champs_personnalises: Default::default(),
             }),
         );
 
         match result {
-            Ok(batiment) => Ok(Some(batiment)),
+            Ok(mut batiment) => {
+                batiment.champs_personnalises = ChampPersonnaliseRepository::get_valeurs(conn, ENTITE_CHAMP_BATIMENT, id)?;
+                Ok(Some(batiment))
+            }
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(AppError::from(e)),
         }
@@ -159,7 +213,7 @@ impl BatimentRepository {
 
     /// Update a batiment
     pub fn update(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         id: i64,
         batiment: &UpdateBatiment,
     ) -> Result<(), AppError> {
@@ -203,10 +257,16 @@ impl BatimentRepository {
             ));
         }
 
-        // Mise à jour du bâtiment
+        // Mise à jour du bâtiment, refusée s'il a été modifié depuis son chargement
+        // (le prix des poussins n'est relu que si leur type change)
+        let (prix_poussin, devise_poussin) = Self::prix_poussin(conn, batiment.bande_id, batiment.poussin_id)?;
         let rows_affected = conn.execute(
             "UPDATE batiments SET bande_id = ?1, numero_batiment = ?2, poussin_id = ?3, 
-                                  personnel_id = ?4, quantite = ?5 WHERE id = ?6",
+                                  personnel_id = ?4, quantite = ?5, version = version + 1,
+                                  prix_poussin = CASE WHEN poussin_id = ?3 THEN prix_poussin ELSE ?8 END,
+                                  devise_poussin = CASE WHEN poussin_id = ?3 THEN devise_poussin ELSE ?9 END,
+                                  updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?6 AND (?7 IS NULL OR version = ?7)",
             rusqlite::params![
                 batiment.bande_id,
                 batiment.numero_batiment,
@@ -214,62 +274,139 @@ impl BatimentRepository {
                 batiment.personnel_id,
                 batiment.quantite,
                 id,
+                batiment.version,
+                prix_poussin,
+                devise_poussin,
             ],
         )?;
 
         if rows_affected == 0 {
-            return Err(AppError::not_found("Batiment", id));
+            return Err(erreur_mise_a_jour(conn, "batiments", "Batiment", id, batiment.version));
         }
 
+        ChampPersonnaliseRepository::set_valeurs(conn, ENTITE_CHAMP_BATIMENT, id, &batiment.champs_personnalises)?;
+
         Ok(())
     }
 
-    /// Delete a batiment with cascade deletion of all associated data
+    /// Reassign the personnel and/or poussin type of several batiments of a bande
+    ///
+    /// Every change is checked before the first write: one invalid change
+    /// rejects the whole batch. As in `update`, the chick price is read again
+    /// only when the poussin type changes.
+    ///
+    /// Must run inside a write transaction (see `Storage::write`).
+    ///
+    /// # Returns
+    /// The number of batiments updated
+    pub fn bulk_update(
+        tx: &Transaction,
+        bande_id: i64,
+        changes: &[ModificationBatiment],
+    ) -> Result<usize, AppError> {
+        let bande_exists: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM bandes WHERE id = ?1",
+            [bande_id],
+            |row| row.get(0),
+        )?;
+        if bande_exists == 0 {
+            return Err(AppError::not_found("Bande", bande_id));
+        }
+
+        if changes.is_empty() {
+            return Err(AppError::validation_error(
+                "changes",
+                "Au moins un bâtiment doit être modifié"
+            ));
+        }
+
+        for (rang, change) in changes.iter().enumerate() {
+            let batiment_bande: Option<i64> = tx.query_row(
+                "SELECT bande_id FROM batiments WHERE id = ?1",
+                [change.batiment_id],
+                |row| row.get(0),
+            ).optional()?;
+            if batiment_bande != Some(bande_id) {
+                return Err(AppError::validation_error(
+                    "batiment_id",
+                    &format!("Le bâtiment {} n'appartient pas à cette bande", change.batiment_id)
+                ));
+            }
+            if changes[..rang].iter().any(|autre| autre.batiment_id == change.batiment_id) {
+                return Err(AppError::validation_error(
+                    "batiment_id",
+                    &format!("Le bâtiment {} est modifié deux fois", change.batiment_id)
+                ));
+            }
+
+            if change.personnel_id.is_none() && change.poussin_id.is_none() {
+                return Err(AppError::validation_error(
+                    "changes",
+                    "Chaque modification doit changer le personnel ou le poussin"
+                ));
+            }
+            if let Some(personnel_id) = change.personnel_id {
+                if !PersonnelRepository::exists(tx, personnel_id)? {
+                    return Err(AppError::validation_error(
+                        "personnel_id",
+                        "Le personnel spécifié n'existe pas"
+                    ));
+                }
+            }
+            if let Some(poussin_id) = change.poussin_id {
+                if !PoussinRepository::exists(tx, poussin_id)? {
+                    return Err(AppError::validation_error(
+                        "poussin_id",
+                        "Le poussin spécifié n'existe pas"
+                    ));
+                }
+            }
+        }
+
+        for change in changes {
+            let (prix_poussin, devise_poussin) = match change.poussin_id {
+                Some(poussin_id) => Self::prix_poussin(tx, bande_id, poussin_id)?,
+                None => (None, None),
+            };
+            tx.execute(
+                "UPDATE batiments SET personnel_id = COALESCE(?1, personnel_id),
+                                      prix_poussin = CASE WHEN ?2 IS NULL OR poussin_id = ?2 THEN prix_poussin ELSE ?3 END,
+                                      devise_poussin = CASE WHEN ?2 IS NULL OR poussin_id = ?2 THEN devise_poussin ELSE ?5 END,
+                                      poussin_id = COALESCE(?2, poussin_id),
+                                      version = version + 1, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?4",
+                rusqlite::params![change.personnel_id, change.poussin_id, prix_poussin, change.batiment_id, devise_poussin],
+            )?;
+        }
+
+        Ok(changes.len())
+    }
+
+    /// Price per chick of a poussin type in effect on the entry date of a bande, with its currency
+    fn prix_poussin(
+        conn: &Connection,
+        bande_id: i64,
+        poussin_id: i64,
+    ) -> Result<(Option<f64>, Option<&'static str>), AppError> {
+        let date_entree: String = conn.query_row("SELECT date_entree FROM bandes WHERE id = ?1", [bande_id], |row| row.get(0))?;
+        let prix = PrixRepository::prix_en_vigueur(conn, ARTICLE_POUSSIN, Some(poussin_id), &date_entree)?;
+        let devise = match prix {
+            Some(_) => Some(PrixRepository::get_devise(conn)?.code),
+            None => None,
+        };
+        Ok((prix, devise))
+    }
+
+    /// Delete a batiment
+    /// 
+    /// Its semaines, suivis, maladie links, analyses, notes and litter records
+    /// are deleted by the schema's `ON DELETE CASCADE` (see `database::cascades`).
     /// 
-    /// This function manually deletes all associated data in the correct order:
-    /// 1. suivi_quotidien (through semaines)
-    /// 2. semaines
-    /// 3. batiment_maladies
-    /// 4. batiments
+    /// Must run inside a write transaction (see `Storage::write`).
     pub fn delete(
-        conn: &mut PooledConnection<SqliteConnectionManager>,
+        tx: &Transaction,
         id: i64,
     ) -> Result<(), AppError> {
-        // Start a transaction to ensure data consistency
-        let tx = conn.transaction()?;
-        
-        // 1. Delete all suivi_quotidien records associated with this batiment's semaines
-        let semaine_ids: Vec<i64> = tx.prepare(
-            "SELECT id FROM semaines WHERE batiment_id = ?1"
-        )?
-        .query_map([id], |row| row.get(0))?
-        .collect::<Result<Vec<_>, _>>()?;
-        
-        if !semaine_ids.is_empty() {
-            let placeholders = std::iter::repeat("?")
-                .take(semaine_ids.len())
-                .collect::<Vec<_>>()
-                .join(",");
-            
-            tx.execute(
-                &format!("DELETE FROM suivi_quotidien WHERE semaine_id IN ({})", placeholders),
-                rusqlite::params_from_iter(semaine_ids.iter()),
-            )?;
-        }
-        
-        // 2. Delete all semaines for this batiment
-        tx.execute(
-            "DELETE FROM semaines WHERE batiment_id = ?1",
-            [id],
-        )?;
-        
-        // 3. Delete all maladie associations for this batiment
-        tx.execute(
-            "DELETE FROM batiment_maladies WHERE batiment_id = ?1",
-            [id],
-        )?;
-        
-        // 4. Finally delete the batiment itself
         let rows_affected = tx.execute(
             "DELETE FROM batiments WHERE id = ?1",
             [id],
@@ -278,16 +415,13 @@ impl BatimentRepository {
         if rows_affected == 0 {
             return Err(AppError::not_found("Batiment", id));
         }
-        
-        // Commit the transaction
-        tx.commit()?;
 
         Ok(())
     }
 
     /// Get available batiment numbers for a ferme (all numbers are available since they can be reused across different bands)
     pub fn get_available_batiment_numbers(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         ferme_id: i64,
     ) -> Result<Vec<String>, AppError> {
         // Vérifier que la ferme existe
@@ -313,7 +447,7 @@ impl BatimentRepository {
 
     /// Link a maladie to a batiment (idempotent)
     pub fn add_maladie_to_batiment(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         batiment_id: i64,
         maladie_id: i64,
     ) -> Result<(), AppError> {
@@ -347,7 +481,7 @@ impl BatimentRepository {
 
     /// Add a maladie to all batiments in a specific bande
     pub fn add_maladie_to_bande_batiments(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         bande_id: i64,
         maladie_id: i64,
     ) -> Result<usize, AppError> {
@@ -373,7 +507,7 @@ impl BatimentRepository {
 
     /// Get maladies linked to a specific batiment
     pub fn get_maladies_by_batiment(
-        conn: &PooledConnection<SqliteConnectionManager>,
+        conn: &Connection,
         batiment_id: i64,
     ) -> Result<Vec<Maladie>, AppError> {
         // Validate batiment
@@ -0,0 +1,81 @@
+use crate::error::AppError;
+use crate::models::BudgetBande;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Repository for the budget of a bande (one optional row per bande)
+pub struct BudgetRepository;
+
+impl BudgetRepository {
+    /// Get the budget of a bande, `None` when no budget was defined
+    pub fn get(
+        conn: &Connection,
+        bande_id: i64,
+    ) -> Result<Option<BudgetBande>, AppError> {
+        let budget = conn
+            .query_row(
+                "SELECT aliment_prevu_kg, mortalite_prevue_pourcentage, cout_aliment_prevu, cout_poussins_prevu,
+                        cout_soins_prevu
+                 FROM budgets_bande WHERE bande_id = ?1",
+                [bande_id],
+                |row| {
+                    Ok(BudgetBande {
+                        aliment_prevu_kg: row.get(0)?,
+                        mortalite_prevue_pourcentage: row.get(1)?,
+                        cout_aliment_prevu: row.get(2)?,
+                        cout_poussins_prevu: row.get(3)?,
+                        cout_soins_prevu: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(budget)
+    }
+
+    /// Create or replace the budget of a bande
+    pub fn set(
+        conn: &Connection,
+        bande_id: i64,
+        budget: &BudgetBande,
+    ) -> Result<BudgetBande, AppError> {
+        for (champ, valeur) in [
+            ("aliment_prevu_kg", budget.aliment_prevu_kg),
+            ("mortalite_prevue_pourcentage", budget.mortalite_prevue_pourcentage),
+            ("cout_aliment_prevu", budget.cout_aliment_prevu),
+            ("cout_poussins_prevu", budget.cout_poussins_prevu),
+            ("cout_soins_prevu", budget.cout_soins_prevu),
+        ] {
+            if valeur.is_some_and(|valeur| !valeur.is_finite() || valeur < 0.0) {
+                return Err(AppError::validation_error(champ, "Une prévision du budget ne peut pas être négative"));
+            }
+        }
+        if budget.mortalite_prevue_pourcentage.is_some_and(|mortalite| mortalite > 100.0) {
+            return Err(AppError::validation_error(
+                "mortalite_prevue_pourcentage",
+                "La mortalité prévue ne peut pas dépasser 100 %",
+            ));
+        }
+
+        conn.execute(
+            "INSERT INTO budgets_bande (bande_id, aliment_prevu_kg, mortalite_prevue_pourcentage, cout_aliment_prevu,
+                                        cout_poussins_prevu, cout_soins_prevu, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP)
+             ON CONFLICT(bande_id) DO UPDATE SET
+                aliment_prevu_kg = excluded.aliment_prevu_kg,
+                mortalite_prevue_pourcentage = excluded.mortalite_prevue_pourcentage,
+                cout_aliment_prevu = excluded.cout_aliment_prevu,
+                cout_poussins_prevu = excluded.cout_poussins_prevu,
+                cout_soins_prevu = excluded.cout_soins_prevu,
+                updated_at = CURRENT_TIMESTAMP",
+            params![
+                bande_id,
+                budget.aliment_prevu_kg,
+                budget.mortalite_prevue_pourcentage,
+                budget.cout_aliment_prevu,
+                budget.cout_poussins_prevu,
+                budget.cout_soins_prevu,
+            ],
+        )?;
+
+        Ok(budget.clone())
+    }
+}
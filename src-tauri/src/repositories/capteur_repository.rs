@@ -0,0 +1,116 @@
+use crate::error::AppError;
+use crate::models::{AgregatCapteurs, MesureCapteur};
+use rusqlite::Connection;
+
+/// Repository for climate controller readings and their hourly aggregates
+pub struct CapteurRepository;
+
+impl CapteurRepository {
+    /// Insert readings, replacing those already stored for the same building and timestamp
+    pub fn upsert_mesures(
+        conn: &Connection,
+        ferme_id: i64,
+        mesures: &[MesureCapteur],
+    ) -> Result<(), AppError> {
+        let mut stmt = conn.prepare(
+            "INSERT INTO mesures_capteurs (ferme_id, numero_batiment, horodatage, temperature, humidite, co2)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(ferme_id, numero_batiment, horodatage) DO UPDATE SET
+                temperature = excluded.temperature,
+                humidite = excluded.humidite,
+                co2 = excluded.co2"
+        )?;
+
+        for mesure in mesures {
+            stmt.execute(rusqlite::params![
+                ferme_id,
+                mesure.numero_batiment,
+                mesure.horodatage,
+                mesure.temperature,
+                mesure.humidite,
+                mesure.co2,
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// Recompute the hourly aggregates of a building between two hours ("YYYY-MM-DD HH:00", inclusive)
+    ///
+    /// Sums and counts are stored per metric so that daily averages can be
+    /// derived exactly from the hourly rows.
+    pub fn refresh_agregats(
+        conn: &Connection,
+        ferme_id: i64,
+        numero_batiment: &str,
+        heure_debut: &str,
+        heure_fin: &str,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "DELETE FROM mesures_capteurs_horaires
+             WHERE ferme_id = ?1 AND numero_batiment = ?2 AND heure BETWEEN ?3 AND ?4",
+            rusqlite::params![ferme_id, numero_batiment, heure_debut, heure_fin],
+        )?;
+        conn.execute(
+            "INSERT INTO mesures_capteurs_horaires (
+                ferme_id, numero_batiment, heure, nombre_mesures,
+                temperature_somme, temperature_nombre, temperature_min, temperature_max,
+                humidite_somme, humidite_nombre, co2_somme, co2_nombre, co2_max
+             )
+             SELECT ferme_id, numero_batiment, strftime('%Y-%m-%d %H:00', horodatage) AS heure, COUNT(*),
+                    SUM(temperature), COUNT(temperature), MIN(temperature), MAX(temperature),
+                    SUM(humidite), COUNT(humidite), SUM(co2), COUNT(co2), MAX(co2)
+             FROM mesures_capteurs
+             WHERE ferme_id = ?1 AND numero_batiment = ?2
+               AND horodatage >= ?3 AND horodatage < datetime(?4, '+1 hour')
+             GROUP BY heure",
+            rusqlite::params![ferme_id, numero_batiment, heure_debut, heure_fin],
+        )?;
+        Ok(())
+    }
+
+    /// Get the aggregates of a building per hour or per day
+    pub fn get_agregats(
+        conn: &Connection,
+        ferme_id: i64,
+        numero_batiment: &str,
+        par_jour: bool,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<AgregatCapteurs>, AppError> {
+        let periode = if par_jour { "substr(heure, 1, 10)" } else { "heure" };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} AS periode, SUM(nombre_mesures),
+                    MIN(temperature_min), MAX(temperature_max),
+                    SUM(temperature_somme) / NULLIF(SUM(temperature_nombre), 0),
+                    SUM(humidite_somme) / NULLIF(SUM(humidite_nombre), 0),
+                    SUM(co2_somme) / NULLIF(SUM(co2_nombre), 0),
+                    MAX(co2_max)
+             FROM mesures_capteurs_horaires
+             WHERE ferme_id = ?1 AND numero_batiment = ?2
+               AND (?3 IS NULL OR heure >= ?3)
+               AND (?4 IS NULL OR heure < date(?4, '+1 day'))
+             GROUP BY periode
+             ORDER BY periode",
+            periode
+        ))?;
+
+        let agregats = stmt.query_map(
+            rusqlite::params![ferme_id, numero_batiment, date_from, date_to],
+            |row| {
+                Ok(AgregatCapteurs {
+                    periode: row.get(0)?,
+                    nombre_mesures: row.get(1)?,
+                    temperature_min: row.get(2)?,
+                    temperature_max: row.get(3)?,
+                    temperature_moyenne: row.get(4)?,
+                    humidite_moyenne: row.get(5)?,
+                    co2_moyen: row.get(6)?,
+                    co2_max: row.get(7)?,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(agregats)
+    }
+}
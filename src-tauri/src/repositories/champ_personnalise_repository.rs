@@ -0,0 +1,213 @@
+use crate::error::AppError;
+use crate::models::{
+    ChampPersonnalise, CreateChampPersonnalise, ValeursChamps, ENTITES_CHAMP, ENTITE_CHAMP_BANDE, TYPES_CHAMP,
+    TYPE_CHAMP_BOOLEEN, TYPE_CHAMP_DATE, TYPE_CHAMP_NOMBRE,
+};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row};
+
+fn map_champ_row(row: &Row) -> rusqlite::Result<ChampPersonnalise> {
+    Ok(ChampPersonnalise {
+        id: row.get(0)?,
+        entite: row.get(1)?,
+        cle: row.get(2)?,
+        libelle: row.get(3)?,
+        type_valeur: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+/// Column of `valeurs_champs_personnalises` referencing the given entity
+fn colonne_entite(entite: &str) -> &'static str {
+    if entite == ENTITE_CHAMP_BANDE { "bande_id" } else { "batiment_id" }
+}
+
+/// Repository for user-defined fields on bandes and batiments
+pub struct ChampPersonnaliseRepository;
+
+impl ChampPersonnaliseRepository {
+    /// Define a new custom field
+    ///
+    /// The key is stored lowercased; it must be unique for the entity.
+    pub fn create(
+        conn: &Connection,
+        champ: &CreateChampPersonnalise,
+    ) -> Result<ChampPersonnalise, AppError> {
+        let entite = champ.entite.trim().to_lowercase();
+        if !ENTITES_CHAMP.contains(&entite.as_str()) {
+            return Err(AppError::validation_error(
+                "entite",
+                &format!("Entité non reconnue. Entités valides: {}", ENTITES_CHAMP.join(", "))
+            ));
+        }
+
+        let cle = champ.cle.trim().to_lowercase();
+        if cle.is_empty() || !cle.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(AppError::validation_error(
+                "cle",
+                "La clé du champ ne peut contenir que des lettres sans accent, des chiffres et des _"
+            ));
+        }
+
+        let libelle = champ.libelle.trim();
+        if libelle.is_empty() {
+            return Err(AppError::validation_error("libelle", "Le libellé du champ est obligatoire"));
+        }
+
+        let type_valeur = champ.type_valeur.trim().to_lowercase();
+        if !TYPES_CHAMP.contains(&type_valeur.as_str()) {
+            return Err(AppError::validation_error(
+                "type_valeur",
+                &format!("Type de valeur non reconnu. Types valides: {}", TYPES_CHAMP.join(", "))
+            ));
+        }
+
+        let existe: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM champs_personnalises WHERE entite = ?1 AND cle = ?2",
+            params![entite, cle],
+            |row| row.get(0),
+        )?;
+        if existe > 0 {
+            return Err(AppError::validation_error(
+                "cle",
+                &format!("Un champ « {} » existe déjà pour cette entité", cle)
+            ));
+        }
+
+        conn.execute(
+            "INSERT INTO champs_personnalises (entite, cle, libelle, type_valeur) VALUES (?1, ?2, ?3, ?4)",
+            params![entite, cle, libelle, type_valeur],
+        )?;
+
+        let champ = conn.query_row(
+            "SELECT id, entite, cle, libelle, type_valeur, created_at FROM champs_personnalises WHERE id = ?1",
+            [conn.last_insert_rowid()],
+            map_champ_row,
+        )?;
+
+        Ok(champ)
+    }
+
+    /// List the custom fields, optionally those of one entity only
+    pub fn get_all(
+        conn: &Connection,
+        entite: Option<&str>,
+    ) -> Result<Vec<ChampPersonnalise>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT id, entite, cle, libelle, type_valeur, created_at
+             FROM champs_personnalises
+             WHERE ?1 IS NULL OR entite = ?1
+             ORDER BY entite, libelle, id",
+        )?;
+
+        let champs = stmt.query_map([entite], map_champ_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(champs)
+    }
+
+    /// Delete a custom field along with its values
+    pub fn delete(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute("DELETE FROM champs_personnalises WHERE id = ?1", [id])?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("ChampPersonnalise", id));
+        }
+
+        Ok(())
+    }
+
+    /// Replace the custom field values of a bande or batiment
+    ///
+    /// Every key must be a field of the entity and every value must match the
+    /// field type; an empty value removes the field from the entity. Fields
+    /// missing from `valeurs` are cleared.
+    pub fn set_valeurs(
+        conn: &Connection,
+        entite: &str,
+        entite_id: i64,
+        valeurs: &ValeursChamps,
+    ) -> Result<(), AppError> {
+        let champs = Self::get_all(conn, Some(entite))?;
+        let mut lignes = Vec::with_capacity(valeurs.len());
+        for (cle, valeur) in valeurs {
+            let Some(champ) = champs.iter().find(|c| c.cle == cle.trim().to_lowercase()) else {
+                return Err(AppError::validation_error(
+                    "champs_personnalises",
+                    &format!("Le champ « {} » n'est pas défini pour cette entité", cle)
+                ));
+            };
+            let valeur = valeur.trim();
+            if valeur.is_empty() {
+                continue;
+            }
+            lignes.push((champ.id, normaliser_valeur(champ, valeur)?));
+        }
+
+        let colonne = colonne_entite(entite);
+        conn.execute(
+            &format!("DELETE FROM valeurs_champs_personnalises WHERE {} = ?1", colonne),
+            [entite_id],
+        )?;
+        for (champ_id, valeur) in lignes {
+            conn.execute(
+                &format!("INSERT INTO valeurs_champs_personnalises (champ_id, {}, valeur) VALUES (?1, ?2, ?3)", colonne),
+                params![champ_id, entite_id, valeur],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Custom field values of a bande or batiment, by field key
+    pub fn get_valeurs(
+        conn: &Connection,
+        entite: &str,
+        entite_id: i64,
+    ) -> Result<ValeursChamps, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT c.cle, v.valeur
+             FROM valeurs_champs_personnalises v
+             JOIN champs_personnalises c ON v.champ_id = c.id
+             WHERE v.{} = ?1",
+            colonne_entite(entite)
+        ))?;
+
+        let valeurs = stmt.query_map([entite_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<ValeursChamps, _>>()?;
+
+        Ok(valeurs)
+    }
+}
+
+/// Check a value against the field type and return it in its stored form
+fn normaliser_valeur(champ: &ChampPersonnalise, valeur: &str) -> Result<String, AppError> {
+    let invalide = |attendu: &str| {
+        AppError::validation_error(
+            "champs_personnalises",
+            &format!("Le champ « {} » attend {}", champ.libelle, attendu)
+        )
+    };
+
+    match champ.type_valeur.as_str() {
+        TYPE_CHAMP_NOMBRE => valeur
+            .replace(',', ".")
+            .parse::<f64>()
+            .ok()
+            .filter(|nombre| nombre.is_finite())
+            .map(|nombre| nombre.to_string())
+            .ok_or_else(|| invalide("un nombre")),
+        TYPE_CHAMP_DATE => NaiveDate::parse_from_str(valeur, "%Y-%m-%d")
+            .map(|date| date.to_string())
+            .map_err(|_| invalide("une date au format AAAA-MM-JJ")),
+        TYPE_CHAMP_BOOLEEN => match valeur.to_lowercase().as_str() {
+            "true" | "oui" | "1" => Ok("true".to_string()),
+            "false" | "non" | "0" => Ok("false".to_string()),
+            _ => Err(invalide("oui ou non")),
+        },
+        _ => Ok(valeur.to_string()),
+    }
+}
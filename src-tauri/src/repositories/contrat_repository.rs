@@ -0,0 +1,96 @@
+use crate::error::AppError;
+use crate::models::ContratBande;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Repository for the contract terms of a bande (one optional row per bande)
+pub struct ContratRepository;
+
+impl ContratRepository {
+    /// Get the contract of a bande, `None` when the bande is not under contract
+    pub fn get(
+        conn: &Connection,
+        bande_id: i64,
+    ) -> Result<Option<ContratBande>, AppError> {
+        let contrat = conn
+            .query_row(
+                "SELECT prix_garanti_kg, ic_cible, prime_ic_par_centieme, mortalite_cible_pourcentage,
+                        prime_mortalite_par_point, deduire_intrants
+                 FROM contrats_bande WHERE bande_id = ?1",
+                [bande_id],
+                |row| {
+                    Ok(ContratBande {
+                        prix_garanti_kg: row.get(0)?,
+                        ic_cible: row.get(1)?,
+                        prime_ic_par_centieme: row.get(2)?,
+                        mortalite_cible_pourcentage: row.get(3)?,
+                        prime_mortalite_par_point: row.get(4)?,
+                        deduire_intrants: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(contrat)
+    }
+
+    /// Create or replace the contract of a bande
+    pub fn set(
+        conn: &Connection,
+        bande_id: i64,
+        contrat: &ContratBande,
+    ) -> Result<ContratBande, AppError> {
+        if !contrat.prix_garanti_kg.is_finite() || contrat.prix_garanti_kg < 0.0 {
+            return Err(AppError::validation_error("prix_garanti_kg", "Le prix garanti ne peut pas être négatif"));
+        }
+        for (champ, valeur) in [
+            ("ic_cible", contrat.ic_cible),
+            ("prime_ic_par_centieme", contrat.prime_ic_par_centieme),
+            ("mortalite_cible_pourcentage", contrat.mortalite_cible_pourcentage),
+            ("prime_mortalite_par_point", contrat.prime_mortalite_par_point),
+        ] {
+            if valeur.is_some_and(|valeur| !valeur.is_finite() || valeur < 0.0) {
+                return Err(AppError::validation_error(champ, "Une cible ou une prime du contrat ne peut pas être négative"));
+            }
+        }
+        if contrat.mortalite_cible_pourcentage.is_some_and(|mortalite| mortalite > 100.0) {
+            return Err(AppError::validation_error(
+                "mortalite_cible_pourcentage",
+                "La mortalité cible ne peut pas dépasser 100 %",
+            ));
+        }
+        if contrat.prime_ic_par_centieme.is_some() && contrat.ic_cible.is_none() {
+            return Err(AppError::validation_error("ic_cible", "Une prime sur l'indice de consommation demande une cible"));
+        }
+        if contrat.prime_mortalite_par_point.is_some() && contrat.mortalite_cible_pourcentage.is_none() {
+            return Err(AppError::validation_error(
+                "mortalite_cible_pourcentage",
+                "Une prime sur la mortalité demande une cible",
+            ));
+        }
+
+        conn.execute(
+            "INSERT INTO contrats_bande (bande_id, prix_garanti_kg, ic_cible, prime_ic_par_centieme,
+                                         mortalite_cible_pourcentage, prime_mortalite_par_point, deduire_intrants,
+                                         updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+             ON CONFLICT(bande_id) DO UPDATE SET
+                prix_garanti_kg = excluded.prix_garanti_kg,
+                ic_cible = excluded.ic_cible,
+                prime_ic_par_centieme = excluded.prime_ic_par_centieme,
+                mortalite_cible_pourcentage = excluded.mortalite_cible_pourcentage,
+                prime_mortalite_par_point = excluded.prime_mortalite_par_point,
+                deduire_intrants = excluded.deduire_intrants,
+                updated_at = CURRENT_TIMESTAMP",
+            params![
+                bande_id,
+                contrat.prix_garanti_kg,
+                contrat.ic_cible,
+                contrat.prime_ic_par_centieme,
+                contrat.mortalite_cible_pourcentage,
+                contrat.prime_mortalite_par_point,
+                contrat.deduire_intrants,
+            ],
+        )?;
+
+        Ok(contrat.clone())
+    }
+}
@@ -0,0 +1,309 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CreateEntretienEquipement, CreateEquipement, EntretienEquipement, Equipement, UpdateEquipement, TYPES_EQUIPEMENT,
+};
+use crate::repositories::VideSanitaireRepository;
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row, ToSql};
+use std::sync::Arc;
+
+/// Colonnes lues pour un `Equipement`, dans l'ordre attendu par `map_equipement_row`
+///
+/// Le prochain entretien est compté à partir du dernier entretien ou, à
+/// défaut, de la mise en service.
+const EQUIPEMENT_SELECT: &str =
+    "SELECT id, ferme_id, nom, type_equipement, numero_batiment, date_mise_en_service,
+            intervalle_entretien_jours, dernier_entretien,
+            CASE WHEN intervalle_entretien_jours IS NULL THEN NULL
+                 ELSE date(COALESCE(dernier_entretien, date_mise_en_service), '+' || intervalle_entretien_jours || ' days')
+            END AS prochain_entretien,
+            created_at
+     FROM (
+        SELECT e.*,
+               (SELECT MAX(en.date_entretien) FROM entretiens_equipement en
+                WHERE en.equipement_id = e.id) AS dernier_entretien
+        FROM equipements e
+     )";
+
+const ENTRETIEN_SELECT: &str =
+    "SELECT id, equipement_id, date_entretien, description, cout, created_at FROM entretiens_equipement";
+
+fn map_equipement_row(row: &Row) -> rusqlite::Result<Equipement> {
+    Ok(Equipement {
+        id: row.get(0)?,
+        ferme_id: row.get(1)?,
+        nom: row.get(2)?,
+        type_equipement: row.get(3)?,
+        numero_batiment: row.get(4)?,
+        date_mise_en_service: row.get(5)?,
+        intervalle_entretien_jours: row.get(6)?,
+        dernier_entretien: row.get(7)?,
+        prochain_entretien: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+fn map_entretien_row(row: &Row) -> rusqlite::Result<EntretienEquipement> {
+    Ok(EntretienEquipement {
+        id: row.get(0)?,
+        equipement_id: row.get(1)?,
+        date_entretien: row.get(2)?,
+        description: row.get(3)?,
+        cout: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+/// Trait pour les opérations sur les équipements et leurs entretiens
+pub trait EquipementRepositoryTrait: Send + Sync {
+    /// Enregistre un équipement d'une ferme
+    ///
+    /// # Arguments
+    /// * `equipement` - Les données de l'équipement
+    ///
+    /// # Returns
+    /// L'équipement créé avec son ID généré
+    async fn create(&self, equipement: CreateEquipement) -> AppResult<Equipement>;
+
+    /// Liste les équipements d'une ferme, du prochain entretien le plus proche au plus lointain
+    async fn get_by_ferme(&self, ferme_id: i64) -> AppResult<Vec<Equipement>>;
+
+    /// Met à jour un équipement
+    async fn update(&self, equipement: UpdateEquipement) -> AppResult<Equipement>;
+
+    /// Supprime un équipement et ses entretiens
+    async fn delete(&self, id: i64) -> AppResult<()>;
+
+    /// Enregistre un entretien réalisé sur un équipement
+    ///
+    /// # Arguments
+    /// * `entretien` - Les données de l'entretien
+    ///
+    /// # Returns
+    /// L'équipement avec sa nouvelle échéance d'entretien
+    async fn add_entretien(&self, entretien: CreateEntretienEquipement) -> AppResult<Equipement>;
+
+    /// Liste les entretiens d'un équipement, du plus récent au plus ancien
+    async fn get_entretiens(&self, equipement_id: i64) -> AppResult<Vec<EntretienEquipement>>;
+
+    /// Supprime un entretien
+    async fn delete_entretien(&self, id: i64) -> AppResult<()>;
+}
+
+/// Repository implementation for farm equipment and its maintenance log
+pub struct EquipementRepository {
+    db: Arc<dyn Storage>,
+}
+
+impl EquipementRepository {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Valide les données saisies et renvoie le numéro de bâtiment nettoyé
+    fn validate(
+        conn: &Connection,
+        ferme_id: i64,
+        nom: &str,
+        type_equipement: &str,
+        numero_batiment: &Option<String>,
+        date_mise_en_service: &str,
+        intervalle_entretien_jours: Option<i64>,
+    ) -> AppResult<Option<String>> {
+        if nom.trim().is_empty() {
+            return Err(AppError::validation_error("nom", "Le nom de l'équipement est obligatoire"));
+        }
+        if !TYPES_EQUIPEMENT.contains(&type_equipement) {
+            return Err(AppError::validation_error(
+                "type_equipement",
+                &format!("Le type doit être l'un de: {}", TYPES_EQUIPEMENT.join(", ")),
+            ));
+        }
+        NaiveDate::parse_from_str(date_mise_en_service, "%Y-%m-%d").map_err(|_| {
+            AppError::validation_error("date_mise_en_service", "La date de mise en service doit être au format AAAA-MM-JJ")
+        })?;
+        if intervalle_entretien_jours.is_some_and(|jours| jours <= 0) {
+            return Err(AppError::validation_error(
+                "intervalle_entretien_jours",
+                "L'intervalle entre deux entretiens doit être d'au moins un jour",
+            ));
+        }
+
+        let numero_batiment = numero_batiment.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        match &numero_batiment {
+            Some(numero) => VideSanitaireRepository::validate_batiment(conn, ferme_id, numero)?,
+            None => {
+                let ferme_existe: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM fermes WHERE id = ?1)",
+                    [ferme_id],
+                    |row| row.get(0),
+                )?;
+                if !ferme_existe {
+                    return Err(AppError::not_found("Ferme", ferme_id));
+                }
+            }
+        }
+        Ok(numero_batiment)
+    }
+
+    fn query(conn: &Connection, condition: &str, order: &str, params: &[&dyn ToSql]) -> AppResult<Vec<Equipement>> {
+        let mut stmt = conn.prepare(&format!("{} WHERE {} ORDER BY {}", EQUIPEMENT_SELECT, condition, order))?;
+        let equipements = stmt.query_map(params, map_equipement_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(equipements)
+    }
+
+    fn find(conn: &Connection, id: i64) -> AppResult<Equipement> {
+        conn.query_row(&format!("{} WHERE id = ?1", EQUIPEMENT_SELECT), [id], map_equipement_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Equipement", id),
+                _ => AppError::from(e),
+            })
+    }
+
+    /// Équipements dont l'entretien arrive à échéance au plus tard à `date_limite`
+    ///
+    /// # Arguments
+    /// * `conn` - La connexion à la base de données
+    /// * `date_limite` - La date limite (YYYY-MM-DD)
+    pub fn entretiens_a_prevoir(conn: &Connection, date_limite: &str) -> AppResult<Vec<Equipement>> {
+        Self::query(
+            conn,
+            "prochain_entretien IS NOT NULL AND prochain_entretien <= ?1",
+            "prochain_entretien, id",
+            &[&date_limite],
+        )
+    }
+}
+
+/// Supprime les espaces superflus, une saisie vide devenant `None`
+fn clean_text(value: &Option<String>) -> Option<String> {
+    value.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+impl EquipementRepositoryTrait for EquipementRepository {
+    async fn create(&self, equipement: CreateEquipement) -> AppResult<Equipement> {
+        self.db.write(|tx| {
+            let nom = equipement.nom.trim();
+            let numero_batiment = Self::validate(
+                tx,
+                equipement.ferme_id,
+                nom,
+                &equipement.type_equipement,
+                &equipement.numero_batiment,
+                &equipement.date_mise_en_service,
+                equipement.intervalle_entretien_jours,
+            )?;
+
+            tx.execute(
+                "INSERT INTO equipements (ferme_id, nom, type_equipement, numero_batiment, date_mise_en_service, intervalle_entretien_jours)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    equipement.ferme_id,
+                    nom,
+                    equipement.type_equipement,
+                    numero_batiment,
+                    equipement.date_mise_en_service,
+                    equipement.intervalle_entretien_jours
+                ],
+            )?;
+
+            Self::find(tx, tx.last_insert_rowid())
+        })
+    }
+
+    async fn get_by_ferme(&self, ferme_id: i64) -> AppResult<Vec<Equipement>> {
+        let conn = self.db.get_connection()?;
+        Self::query(&conn, "ferme_id = ?1", "prochain_entretien IS NULL, prochain_entretien, nom", &[&ferme_id])
+    }
+
+    async fn update(&self, equipement: UpdateEquipement) -> AppResult<Equipement> {
+        self.db.write(|tx| {
+            let existant = Self::find(tx, equipement.id)?;
+            let nom = equipement.nom.trim();
+            let numero_batiment = Self::validate(
+                tx,
+                existant.ferme_id,
+                nom,
+                &equipement.type_equipement,
+                &equipement.numero_batiment,
+                &equipement.date_mise_en_service,
+                equipement.intervalle_entretien_jours,
+            )?;
+
+            tx.execute(
+                "UPDATE equipements
+                 SET nom = ?1, type_equipement = ?2, numero_batiment = ?3, date_mise_en_service = ?4,
+                     intervalle_entretien_jours = ?5
+                 WHERE id = ?6",
+                params![
+                    nom,
+                    equipement.type_equipement,
+                    numero_batiment,
+                    equipement.date_mise_en_service,
+                    equipement.intervalle_entretien_jours,
+                    equipement.id
+                ],
+            )?;
+
+            Self::find(tx, equipement.id)
+        })
+    }
+
+    async fn delete(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| {
+            let rows_affected = tx.execute("DELETE FROM equipements WHERE id = ?1", [id])?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Equipement", id));
+            }
+
+            Ok(())
+        })
+    }
+
+    async fn add_entretien(&self, entretien: CreateEntretienEquipement) -> AppResult<Equipement> {
+        self.db.write(|tx| {
+            Self::find(tx, entretien.equipement_id)?;
+            NaiveDate::parse_from_str(&entretien.date_entretien, "%Y-%m-%d").map_err(|_| {
+                AppError::validation_error("date_entretien", "La date de l'entretien doit être au format AAAA-MM-JJ")
+            })?;
+            if entretien.cout.is_some_and(|cout| !cout.is_finite() || cout < 0.0) {
+                return Err(AppError::validation_error("cout", "Le coût ne peut pas être négatif"));
+            }
+
+            tx.execute(
+                "INSERT INTO entretiens_equipement (equipement_id, date_entretien, description, cout)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![entretien.equipement_id, entretien.date_entretien, clean_text(&entretien.description), entretien.cout],
+            )?;
+
+            Self::find(tx, entretien.equipement_id)
+        })
+    }
+
+    async fn get_entretiens(&self, equipement_id: i64) -> AppResult<Vec<EntretienEquipement>> {
+        let conn = self.db.get_connection()?;
+        Self::find(&conn, equipement_id)?;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE equipement_id = ?1 ORDER BY date_entretien DESC, id DESC",
+            ENTRETIEN_SELECT
+        ))?;
+        let entretiens = stmt.query_map([equipement_id], map_entretien_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entretiens)
+    }
+
+    async fn delete_entretien(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| {
+            let rows_affected = tx.execute("DELETE FROM entretiens_equipement WHERE id = ?1", [id])?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("EntretienEquipement", id));
+            }
+
+            Ok(())
+        })
+    }
+}
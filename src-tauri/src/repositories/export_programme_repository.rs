@@ -0,0 +1,85 @@
+use crate::error::AppError;
+use crate::models::{CreateExportProgramme, ExportProgramme};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+const EXPORT_PROGRAMME_COLUMNS: &str =
+    "id, type_export, dossier, frequence, heure, jour, created_at, derniere_execution, dernier_statut, dernier_message";
+
+/// Repository for the scheduled exports
+pub struct ExportProgrammeRepository;
+
+impl ExportProgrammeRepository {
+    /// Create a scheduled export
+    pub fn create(
+        conn: &Connection,
+        export: &CreateExportProgramme,
+    ) -> Result<ExportProgramme, AppError> {
+        conn.execute(
+            "INSERT INTO exports_programmes (type_export, dossier, frequence, heure, jour)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![export.type_export, export.dossier.trim(), export.frequence, export.heure, export.jour],
+        )?;
+        let id = conn.last_insert_rowid();
+        Self::get_by_id(conn, id)?.ok_or_else(|| AppError::not_found("ExportProgramme", id))
+    }
+
+    /// Get a scheduled export by ID
+    pub fn get_by_id(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<Option<ExportProgramme>, AppError> {
+        let export = conn
+            .query_row(
+                &format!("SELECT {} FROM exports_programmes WHERE id = ?1", EXPORT_PROGRAMME_COLUMNS),
+                [id],
+                map_row,
+            )
+            .optional()?;
+        Ok(export)
+    }
+
+    /// Get all scheduled exports
+    pub fn get_all(conn: &Connection) -> Result<Vec<ExportProgramme>, AppError> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM exports_programmes ORDER BY id", EXPORT_PROGRAMME_COLUMNS))?;
+        let exports = stmt.query_map([], map_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(exports)
+    }
+
+    /// Delete a scheduled export
+    pub fn delete(conn: &Connection, id: i64) -> Result<(), AppError> {
+        if conn.execute("DELETE FROM exports_programmes WHERE id = ?1", [id])? == 0 {
+            return Err(AppError::not_found("ExportProgramme", id));
+        }
+        Ok(())
+    }
+
+    /// Record the outcome of a run (`executee_le` in UTC, `message` is the file created or the error)
+    pub fn record_run(
+        conn: &Connection,
+        id: i64,
+        executee_le: &str,
+        statut: &str,
+        message: &str,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "UPDATE exports_programmes SET derniere_execution = ?2, dernier_statut = ?3, dernier_message = ?4 WHERE id = ?1",
+            params![id, executee_le, statut, message],
+        )?;
+        Ok(())
+    }
+}
+
+fn map_row(row: &Row) -> rusqlite::Result<ExportProgramme> {
+    Ok(ExportProgramme {
+        id: row.get(0)?,
+        type_export: row.get(1)?,
+        dossier: row.get(2)?,
+        frequence: row.get(3)?,
+        heure: row.get(4)?,
+        jour: row.get(5)?,
+        created_at: row.get(6)?,
+        derniere_execution: row.get(7)?,
+        dernier_statut: row.get(8)?,
+        dernier_message: row.get(9)?,
+    })
+}
@@ -0,0 +1,372 @@
+use crate::error::AppError;
+use crate::models::{
+    montant_csv, trouver_devise, BandeEchangee, BatimentEchange, JourEchange, SemaineEchangee, SoinEchange,
+    TvaTrimestre, DEVISES, STATUT_BANDE_CLOTUREE,
+};
+use crate::repositories::PrixRepository;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OptionalExtension, Params};
+
+/// Repository for the denormalized reporting snapshot
+///
+/// The snapshot tables are created in an attached database so that reporting
+/// tools (Excel, Power BI...) never open the live database.
+pub struct ExportRepository;
+
+/// One row per bande, with its totals and its custom field values (JSON object by key)
+const BANDE_FACTS: &str = "
+    SELECT bd.id AS bande_id,
+           bd.numero_bande,
+           COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)) AS numero_affiche,
+           bd.ferme_id,
+           f.nom AS ferme,
+           bd.date_entree,
+           bd.statut,
+           bd.date_cloture,
+           (SELECT COUNT(*) FROM main.batiments b WHERE b.bande_id = bd.id) AS nombre_batiments,
+           (SELECT COALESCE(SUM(b.quantite), 0) FROM main.batiments b WHERE b.bande_id = bd.id) AS effectif_initial,
+           COALESCE(t.deces, 0) AS total_deces,
+           CASE WHEN e.effectif > 0 THEN ROUND(COALESCE(t.deces, 0) * 100.0 / e.effectif, 2) END AS mortalite_pourcentage,
+           COALESCE(t.alimentation, 0) AS alimentation_consommee,
+           (SELECT COALESCE(SUM(ah.quantite), 0) FROM main.alimentation_history ah WHERE ah.bande_id = bd.id) AS alimentation_livree,
+           bd.alimentation_contour,
+           (SELECT MAX(sq.age) FROM main.suivi_quotidien sq
+            JOIN main.semaines s ON sq.semaine_id = s.id
+            JOIN main.batiments b ON s.batiment_id = b.id
+            WHERE b.bande_id = bd.id) AS dernier_age,
+           bd.notes,
+           (SELECT json_group_object(c.cle, v.valeur) FROM main.valeurs_champs_personnalises v
+            JOIN main.champs_personnalises c ON v.champ_id = c.id
+            WHERE v.bande_id = bd.id) AS champs_personnalises
+    FROM main.bandes bd
+    JOIN main.fermes f ON bd.ferme_id = f.id
+    LEFT JOIN (SELECT b.bande_id, SUM(b.quantite) AS effectif FROM main.batiments b GROUP BY b.bande_id) e
+           ON e.bande_id = bd.id
+    LEFT JOIN (SELECT b.bande_id, SUM(sq.deces_par_jour) AS deces, SUM(sq.alimentation_par_jour) AS alimentation
+               FROM main.suivi_quotidien sq
+               JOIN main.semaines s ON sq.semaine_id = s.id
+               JOIN main.batiments b ON s.batiment_id = b.id
+               GROUP BY b.bande_id) t
+           ON t.bande_id = bd.id";
+
+/// One row per batiment with suivi in the 7 days ending on ?1
+const WEEKLY_REPORT: &str = "
+    SELECT f.nom AS ferme,
+           COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)) AS bande,
+           b.numero_batiment AS batiment,
+           MAX(sq.age) AS age,
+           SUM(COALESCE(sq.deces_par_jour, 0)) AS deces_semaine,
+           SUM(COALESCE(sq.alimentation_par_jour, 0)) AS sachets_semaine,
+           (SELECT SUM(COALESCE(t.deces_par_jour, 0)) FROM suivi_quotidien t
+            JOIN semaines ts ON t.semaine_id = ts.id
+            WHERE ts.batiment_id = b.id) AS deces_cumules,
+           b.quantite AS effectif_initial
+    FROM suivi_quotidien sq
+    JOIN semaines s ON sq.semaine_id = s.id
+    JOIN batiments b ON s.batiment_id = b.id
+    JOIN bandes bd ON b.bande_id = bd.id
+    JOIN fermes f ON bd.ferme_id = f.id
+    WHERE date(bd.date_entree, '+' || (sq.age - 1) || ' days') BETWEEN date(?1, '-6 days') AND date(?1)
+    GROUP BY b.id
+    ORDER BY f.nom, bd.date_entree, b.numero_batiment";
+
+/// One row per priced purchase: feed deliveries and chicks placed
+const ACCOUNTING_LEDGER: &str = "
+    SELECT date, ferme, bande, article, quantite, prix_unitaire, quantite * prix_unitaire AS montant, devise
+    FROM (
+        SELECT date(a.created_at) AS date, f.nom AS ferme,
+               COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)) AS bande,
+               'aliment (kg)' AS article, a.quantite, a.prix_unitaire, a.devise
+        FROM alimentation_history a
+        JOIN bandes bd ON a.bande_id = bd.id
+        JOIN fermes f ON bd.ferme_id = f.id
+        UNION ALL
+        SELECT bd.date_entree, f.nom,
+               COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)),
+               'poussins (bâtiment ' || b.numero_batiment || ')', b.quantite, b.prix_poussin, b.devise_poussin
+        FROM batiments b
+        JOIN bandes bd ON b.bande_id = bd.id
+        JOIN fermes f ON bd.ferme_id = f.id
+    )
+    ORDER BY date, ferme, bande, article";
+
+/// Purchases with VAT (feed deliveries and litter operations), totalled by quarter and rate
+const VAT_SUMMARY: &str = "
+    WITH achats AS (
+        SELECT date(created_at) AS date, quantite * prix_unitaire AS base_ht, taux_tva,
+               COALESCE(montant_tva, quantite * prix_unitaire * taux_tva / 100) AS montant_tva
+        FROM alimentation_history
+        WHERE taux_tva IS NOT NULL OR montant_tva IS NOT NULL
+        UNION ALL
+        SELECT date_operation, cout, taux_tva, COALESCE(montant_tva, cout * taux_tva / 100)
+        FROM litieres
+        WHERE taux_tva IS NOT NULL OR montant_tva IS NOT NULL
+    )
+    SELECT strftime('%Y', date) || '-T' || ((CAST(strftime('%m', date) AS INTEGER) + 2) / 3) AS trimestre,
+           taux_tva, COUNT(*), COALESCE(SUM(base_ht), 0), COALESCE(SUM(montant_tva), 0)
+    FROM achats
+    WHERE strftime('%Y', date) = printf('%04d', ?1)
+    GROUP BY trimestre, taux_tva
+    ORDER BY trimestre, taux_tva";
+
+/// One row per day of suivi, with its bande, batiment (and its custom field values) and semaine
+const DAILY_FACTS: &str = "
+    SELECT sq.id AS suivi_id,
+           bd.id AS bande_id,
+           COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)) AS numero_affiche,
+           f.nom AS ferme,
+           b.id AS batiment_id,
+           b.numero_batiment,
+           p.nom AS souche,
+           pe.nom AS technicien,
+           s.numero_semaine,
+           sq.age,
+           date(bd.date_entree, '+' || (sq.age - 1) || ' days') AS date,
+           sq.deces_par_jour,
+           sq.alimentation_par_jour,
+           s.poids AS poids_semaine,
+           (SELECT GROUP_CONCAT(so.nom || COALESCE(' ' || ss.quantite, '') || COALESCE(' ' || ss.unit, ''), ', ')
+            FROM main.suivi_soins ss JOIN main.soins so ON ss.soin_id = so.id
+            WHERE ss.suivi_id = sq.id) AS soins,
+           sq.remarques,
+           (SELECT json_group_object(c.cle, v.valeur) FROM main.valeurs_champs_personnalises v
+            JOIN main.champs_personnalises c ON v.champ_id = c.id
+            WHERE v.batiment_id = b.id) AS champs_batiment
+    FROM main.suivi_quotidien sq
+    JOIN main.semaines s ON sq.semaine_id = s.id
+    JOIN main.batiments b ON s.batiment_id = b.id
+    JOIN main.bandes bd ON b.bande_id = bd.id
+    JOIN main.fermes f ON bd.ferme_id = f.id
+    LEFT JOIN main.poussins p ON b.poussin_id = p.id
+    LEFT JOIN main.personnel pe ON b.personnel_id = pe.id";
+
+impl ExportRepository {
+    /// Create the `bande_facts` and `daily_facts` tables in the attached `schema`
+    ///
+    /// # Returns
+    /// The number of rows of each table
+    pub fn create_reporting_tables(conn: &Connection, schema: &str) -> Result<(i64, i64), AppError> {
+        conn.execute_batch(&format!(
+            "CREATE TABLE {schema}.bande_facts AS {BANDE_FACTS} ORDER BY bd.date_entree, bd.id;
+             CREATE TABLE {schema}.daily_facts AS {DAILY_FACTS} ORDER BY bd.id, b.numero_batiment, sq.age;"
+        ))?;
+
+        let bandes = conn.query_row(&format!("SELECT COUNT(*) FROM {}.bande_facts", schema), [], |row| row.get(0))?;
+        let jours = conn.query_row(&format!("SELECT COUNT(*) FROM {}.daily_facts", schema), [], |row| row.get(0))?;
+        Ok((bandes, jours))
+    }
+
+    /// Weekly report rows for the 7 days ending on `date_fin` (YYYY-MM-DD)
+    ///
+    /// # Returns
+    /// The header then one row per batiment
+    pub fn weekly_report(conn: &Connection, date_fin: &str) -> Result<Vec<Vec<String>>, AppError> {
+        lignes_texte(conn, WEEKLY_REPORT, [date_fin])
+    }
+
+    /// Accounting ledger rows (feed deliveries and chicks, at their recorded prices)
+    ///
+    /// The price and amount are written with a decimal comma and the decimals
+    /// of the currency they were recorded in, given in the last column.
+    ///
+    /// # Returns
+    /// The header then one row per purchase; the price and amount are empty when not recorded
+    pub fn accounting_ledger(conn: &Connection) -> Result<Vec<Vec<String>>, AppError> {
+        let mut stmt = conn.prepare(ACCOUNTING_LEDGER)?;
+        let mut lignes = vec![stmt.column_names().into_iter().map(str::to_string).collect::<Vec<_>>()];
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let code: Option<String> = row.get(7)?;
+            let devise = code.as_deref().and_then(trouver_devise).unwrap_or(DEVISES[0]);
+            let montant = |valeur: Option<f64>| valeur.map(|valeur| montant_csv(valeur, &devise)).unwrap_or_default();
+            let quantite: f64 = row.get(4)?;
+            lignes.push(vec![
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                quantite.to_string(),
+                montant(row.get(5)?),
+                montant(row.get(6)?),
+                code.unwrap_or_default(),
+            ]);
+        }
+        Ok(lignes)
+    }
+
+    /// VAT paid on purchases of a year, by quarter and rate
+    ///
+    /// Amounts without a recorded price count in `achats` with a zero base.
+    pub fn vat_summary(conn: &Connection, annee: i32) -> Result<Vec<TvaTrimestre>, AppError> {
+        let mut stmt = conn.prepare(VAT_SUMMARY)?;
+        let trimestres = stmt
+            .query_map([annee], |row| {
+                let base_ht: f64 = row.get(3)?;
+                let montant_tva: f64 = row.get(4)?;
+                Ok(TvaTrimestre {
+                    trimestre: row.get(0)?,
+                    taux_tva: row.get(1)?,
+                    achats: row.get(2)?,
+                    base_ht,
+                    montant_tva,
+                    montant_ttc: base_ht + montant_tva,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(trimestres)
+    }
+
+    /// VAT summary rows of a year, amounts in the current currency
+    ///
+    /// # Returns
+    /// The header then one row per quarter and rate
+    pub fn vat_summary_rows(conn: &Connection, annee: i32) -> Result<Vec<Vec<String>>, AppError> {
+        let devise = PrixRepository::get_devise(conn)?;
+        let mut lignes = vec![["trimestre", "taux_tva", "achats", "base_ht", "montant_tva", "montant_ttc", "devise"]
+            .map(str::to_string)
+            .to_vec()];
+        for trimestre in Self::vat_summary(conn, annee)? {
+            lignes.push(vec![
+                trimestre.trimestre,
+                trimestre.taux_tva.map(|taux| taux.to_string().replace('.', ",")).unwrap_or_default(),
+                trimestre.achats.to_string(),
+                montant_csv(trimestre.base_ht, &devise),
+                montant_csv(trimestre.montant_tva, &devise),
+                montant_csv(trimestre.montant_ttc, &devise),
+                devise.code.to_string(),
+            ]);
+        }
+        Ok(lignes)
+    }
+
+    /// A bande with its batiments, semaines, daily rows and treatments, in the exchange format
+    ///
+    /// Reference data is given by name. Treatments whose soin was deleted are
+    /// left out since they cannot be named.
+    pub fn exchange_bande(conn: &Connection, bande_id: i64) -> Result<BandeEchangee, AppError> {
+        let (ferme, numero, date_entree, statut, date_cloture, notes): (
+            String,
+            Option<String>,
+            String,
+            String,
+            Option<String>,
+            Option<String>,
+        ) = conn
+            .query_row(
+                "SELECT f.nom, bd.numero_affiche, bd.date_entree, bd.statut, bd.date_cloture, bd.notes
+                 FROM bandes bd
+                 JOIN fermes f ON bd.ferme_id = f.id
+                 WHERE bd.id = ?1",
+                [bande_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::not_found("Bande", bande_id))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT b.id, b.numero_batiment, p.nom, pe.nom, b.quantite
+             FROM batiments b
+             JOIN poussins p ON b.poussin_id = p.id
+             JOIN personnel pe ON b.personnel_id = pe.id
+             WHERE b.bande_id = ?1
+             ORDER BY b.numero_batiment, b.id",
+        )?;
+        let batiments = stmt
+            .query_map([bande_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    BatimentEchange {
+                        numero_batiment: row.get(1)?,
+                        souche: row.get(2)?,
+                        technicien: row.get(3)?,
+                        effectif: row.get(4)?,
+                        semaines: Vec::new(),
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut semaines_stmt = conn.prepare(
+            "SELECT id, numero_semaine, poids FROM semaines WHERE batiment_id = ?1 ORDER BY numero_semaine",
+        )?;
+        let mut jours_stmt = conn.prepare(
+            "SELECT id, age, deces_par_jour, elimines_par_jour, alimentation_par_jour, remarques
+             FROM suivi_quotidien
+             WHERE semaine_id = ?1
+             ORDER BY age",
+        )?;
+        let mut soins_stmt = conn.prepare(
+            "SELECT so.nom, ss.quantite, COALESCE(ss.unit, so.unit)
+             FROM suivi_soins ss
+             JOIN soins so ON ss.soin_id = so.id
+             WHERE ss.suivi_id = ?1
+             ORDER BY ss.id",
+        )?;
+
+        let mut batiments_echanges = Vec::with_capacity(batiments.len());
+        for (batiment_id, mut batiment) in batiments {
+            let semaines = semaines_stmt
+                .query_map([batiment_id], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<Result<Vec<(i64, i32, Option<f64>)>, _>>()?;
+            for (semaine_id, numero_semaine, poids) in semaines {
+                let jours = jours_stmt
+                    .query_map([semaine_id], |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            JourEchange {
+                                age: row.get(1)?,
+                                deces: row.get(2)?,
+                                elimines: row.get(3)?,
+                                aliment: row.get(4)?,
+                                remarques: row.get::<_, Option<String>>(5)?.filter(|r| !r.trim().is_empty()),
+                                soins: Vec::new(),
+                            },
+                        ))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut jours_echanges = Vec::with_capacity(jours.len());
+                for (suivi_id, mut jour) in jours {
+                    jour.soins = soins_stmt
+                        .query_map([suivi_id], |row| {
+                            Ok(SoinEchange { soin: row.get(0)?, quantite: row.get(1)?, unite: row.get(2)? })
+                        })?
+                        .collect::<Result<Vec<_>, _>>()?;
+                    jours_echanges.push(jour);
+                }
+                batiment.semaines.push(SemaineEchangee { numero_semaine, poids, jours: jours_echanges });
+            }
+            batiments_echanges.push(batiment);
+        }
+
+        Ok(BandeEchangee {
+            ferme,
+            numero,
+            date_entree,
+            date_cloture: date_cloture.filter(|_| statut == STATUT_BANDE_CLOTUREE),
+            notes: notes.filter(|n| !n.trim().is_empty()),
+            batiments: batiments_echanges,
+        })
+    }
+}
+
+/// Run a query and return its header and rows as text
+fn lignes_texte(conn: &Connection, sql: &str, params: impl Params) -> Result<Vec<Vec<String>>, AppError> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut lignes = vec![stmt.column_names().into_iter().map(str::to_string).collect::<Vec<_>>()];
+    let colonnes = stmt.column_count();
+    let mut rows = stmt.query(params)?;
+    while let Some(row) = rows.next()? {
+        let mut ligne = Vec::with_capacity(colonnes);
+        for i in 0..colonnes {
+            ligne.push(match row.get_ref(i)? {
+                ValueRef::Null | ValueRef::Blob(_) => String::new(),
+                ValueRef::Integer(entier) => entier.to_string(),
+                ValueRef::Real(reel) => reel.to_string(),
+                ValueRef::Text(texte) => String::from_utf8_lossy(texte).into_owned(),
+            });
+        }
+        lignes.push(ligne);
+    }
+    Ok(lignes)
+}
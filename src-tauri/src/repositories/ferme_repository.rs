@@ -1,22 +1,26 @@
-use crate::database::DatabaseManager;
+use crate::database::{erreur_mise_a_jour, nom_existe, normaliser_nom, Storage};
 use crate::error::{AppError, AppResult};
 use crate::models::{Ferme, CreateFerme, UpdateFerme, Bande};
+use crate::repositories::BANDE_ACTIVE_CONDITION;
 use std::sync::Arc;
 use chrono::{Utc, Datelike};
-use r2d2::PooledConnection;
-use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, OptionalExtension};
 
 /// Statistiques globales du système
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct GlobalStatistics {
     pub total_fermes: i32,
     pub total_bandes: i32,
     pub bandes_par_ferme: Vec<BandeParFerme>,
     pub maladies_par_ferme: Vec<FermeMaladieStats>,
+    /// Date du calcul (RFC 3339)
+    pub computed_at: String,
+    /// Âge en secondes des statistiques servies depuis le cache (0 si calculées à la demande)
+    pub cache_age_seconds: i64,
 }
 
 /// Statistiques des bandes par ferme
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct BandeParFerme {
     pub ferme_nom: String,
     pub total_bandes: i32,
@@ -24,7 +28,7 @@ pub struct BandeParFerme {
 }
 
 /// Informations sur la dernière bande d'une ferme
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct LatestBandeInfo {
     pub bande_id: i64,
     pub numero_bande: i32,
@@ -33,7 +37,7 @@ pub struct LatestBandeInfo {
 }
 
 /// Statistiques des maladies par ferme
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct FermeMaladieStats {
     pub ferme_nom: String,
     pub maladie_nom: String,
@@ -44,6 +48,19 @@ pub struct FermeMaladieStats {
 
 
 
+/// Statistiques d'une ferme pour le tableau de bord
+#[derive(Debug, serde::Serialize)]
+pub struct FermeBreakdown {
+    pub ferme_id: i64,
+    pub ferme_nom: String,
+    pub total_bandes: i32,
+    pub active_bandes: i32,
+    pub poussins_places: i64,
+    pub total_deaths: i64,
+    /// Décès rapportés aux sujets mis en place, en % (`None` sans mise en place)
+    pub mortality_rate: Option<f64>,
+}
+
 /// Données de décès pour une bande spécifique
 #[derive(Debug, serde::Serialize)]
 pub struct BandeDeathData {
@@ -61,7 +78,7 @@ pub struct BandeDeathData {
 /// # Returns
 /// Les statistiques des maladies par ferme
 fn get_maladie_statistics_sync(
-    conn: &PooledConnection<SqliteConnectionManager>,
+    conn: &Connection,
     current_year: u32,
 ) -> AppResult<Vec<FermeMaladieStats>> {
     // Récupérer toutes les fermes avec leurs bandes de l'année en cours et leurs maladies
@@ -198,9 +215,11 @@ pub trait FermeRepositoryTrait: Send + Sync {
     /// Le total des décès pour cette bande
     async fn get_deaths_for_bande(&self, bande_id: i64) -> AppResult<i32>;
 
-
-
-
+    /// Récupère les statistiques de chaque ferme (bandes, bandes actives, mortalité)
+    /// 
+    /// # Returns
+    /// Une entrée par ferme, triée par nom
+    async fn get_ferme_breakdown(&self) -> AppResult<Vec<FermeBreakdown>>;
 }
 
 /// Implémentation du repository pour les fermes
@@ -208,7 +227,7 @@ pub trait FermeRepositoryTrait: Send + Sync {
 /// Utilise SQLite avec un pool de connexions pour
 /// optimiser les performances et éviter les conflits.
 pub struct FermeRepository {
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl FermeRepository {
@@ -216,15 +235,16 @@ impl FermeRepository {
     /// 
     /// # Arguments
     /// * `db` - Le gestionnaire de base de données partagé
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
         Self { db }
     }
-}
 
-impl FermeRepositoryTrait for FermeRepository {
-    async fn create(&self, ferme: CreateFerme) -> AppResult<Ferme> {
-        let conn = self.db.get_connection()?;
-        
+    /// Valide et insère une ferme sur une connexion existante
+    /// 
+    /// # Arguments
+    /// * `conn` - La connexion (ou transaction) à utiliser
+    /// * `ferme` - Les données de la ferme à créer
+    pub fn inserer(conn: &Connection, ferme: CreateFerme) -> AppResult<Ferme> {
         // Validation des données d'entrée
         if ferme.nom.trim().is_empty() {
             return Err(AppError::validation_error(
@@ -240,26 +260,18 @@ impl FermeRepositoryTrait for FermeRepository {
             ));
         }
 
-        // Vérifier que le nom n'existe pas déjà
-        let existing: Result<i64, _> = conn.query_row(
-            "SELECT COUNT(*) FROM fermes WHERE nom = ?1",
-            [&ferme.nom],
-            |row| row.get(0),
-        );
-
-        if let Ok(count) = existing {
-            if count > 0 {
-                return Err(AppError::validation_error(
-                    "nom",
-                    "Une ferme avec ce nom existe déjà"
-                ));
-            }
+        // Vérifier que le nom n'existe pas déjà (sans tenir compte de la casse ni des accents)
+        if nom_existe(conn, "fermes", &ferme.nom, None)? {
+            return Err(AppError::validation_error(
+                "nom",
+                "Une ferme avec ce nom existe déjà"
+            ));
         }
 
         // Insertion de la nouvelle ferme
         conn.execute(
-            "INSERT INTO fermes (nom, nbr_meuble) VALUES (?1, ?2)",
-            [&ferme.nom, &ferme.nbr_meuble.to_string()],
+            "INSERT INTO fermes (nom, nom_normalise, nbr_meuble) VALUES (?1, ?2, ?3)",
+            [&ferme.nom, &normaliser_nom(&ferme.nom), &ferme.nbr_meuble.to_string()],
         )?;
 
         let id = conn.last_insert_rowid();
@@ -268,19 +280,27 @@ impl FermeRepositoryTrait for FermeRepository {
             id: Some(id),
             nom: ferme.nom,
             nbr_meuble: ferme.nbr_meuble,
+            version: 1,
         })
     }
+}
+
+impl FermeRepositoryTrait for FermeRepository {
+    async fn create(&self, ferme: CreateFerme) -> AppResult<Ferme> {
+        self.db.write(|tx| Self::inserer(tx, ferme))
+    }
 
     async fn get_all(&self) -> AppResult<Vec<Ferme>> {
         let conn = self.db.get_connection()?;
         
-        let mut stmt = conn.prepare("SELECT id, nom, nbr_meuble FROM fermes ORDER BY nom")?;
+        let mut stmt = conn.prepare("SELECT id, nom, nbr_meuble, version FROM fermes ORDER BY nom")?;
         
         let fermes = stmt.query_map([], |row| {
             Ok(Ferme {
                 id: Some(row.get(0)?),
                 nom: row.get(1)?,
                 nbr_meuble: row.get(2)?,
+                version: row.get(3)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -292,12 +312,13 @@ impl FermeRepositoryTrait for FermeRepository {
         let conn = self.db.get_connection()?;
         
         let ferme = conn.query_row(
-            "SELECT id, nom, nbr_meuble FROM fermes WHERE id = ?1",
+            "SELECT id, nom, nbr_meuble, version FROM fermes WHERE id = ?1",
             [id],
             |row| Ok(Ferme {
                 id: Some(row.get(0)?),
                 nom: row.get(1)?,
                 nbr_meuble: row.get(2)?,
+                version: row.get(3)?,
             }),
         ).map_err(|e| match e {
             rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Ferme", id),
@@ -308,8 +329,6 @@ impl FermeRepositoryTrait for FermeRepository {
     }
 
     async fn update(&self, ferme: UpdateFerme) -> AppResult<Ferme> {
-        let conn = self.db.get_connection()?;
-        
         // Validation des données d'entrée
         if ferme.nom.trim().is_empty() {
             return Err(AppError::validation_error(
@@ -325,66 +344,61 @@ impl FermeRepositoryTrait for FermeRepository {
             ));
         }
 
-        // Vérifier que le nom n'existe pas déjà pour une autre ferme
-        let existing: Result<i64, _> = conn.query_row(
-            "SELECT COUNT(*) FROM fermes WHERE nom = ?1 AND id != ?2",
-            [&ferme.nom, &ferme.id.to_string()],
-            |row| row.get(0),
-        );
-
-        if let Ok(count) = existing {
-            if count > 0 {
+        self.db.write(|tx| {
+            // Vérifier que le nom n'existe pas déjà pour une autre ferme
+            if nom_existe(tx, "fermes", &ferme.nom, Some(ferme.id))? {
                 return Err(AppError::validation_error(
                     "nom",
                     "Une autre ferme avec ce nom existe déjà"
                 ));
             }
-        }
 
-        // Mise à jour de la ferme
-        let rows_affected = conn.execute(
-            "UPDATE fermes SET nom = ?1, nbr_meuble = ?2 WHERE id = ?3",
-            [&ferme.nom, &ferme.nbr_meuble.to_string(), &ferme.id.to_string()],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Ferme", ferme.id));
-        }
+            // Mise à jour de la ferme, refusée si elle a été modifiée depuis son chargement
+            let version: i64 = tx.query_row(
+                "UPDATE fermes SET nom = ?1, nom_normalise = ?2, nbr_meuble = ?3, version = version + 1
+                 WHERE id = ?4 AND (?5 IS NULL OR version = ?5)
+                 RETURNING version",
+                rusqlite::params![ferme.nom, normaliser_nom(&ferme.nom), ferme.nbr_meuble, ferme.id, ferme.version],
+                |row| row.get(0),
+            ).optional()?
+            .ok_or_else(|| erreur_mise_a_jour(tx, "fermes", "Ferme", ferme.id, ferme.version))?;
 
-        Ok(Ferme {
-            id: Some(ferme.id),
-            nom: ferme.nom,
-            nbr_meuble: ferme.nbr_meuble,
+            Ok(Ferme {
+                id: Some(ferme.id),
+                nom: ferme.nom,
+                nbr_meuble: ferme.nbr_meuble,
+                version,
+            })
         })
     }
 
     async fn delete(&self, id: i64) -> AppResult<()> {
-        let conn = self.db.get_connection()?;
-        
-        // Vérifier s'il y a des bandes liées à cette ferme
-        let bande_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM bandes WHERE ferme_id = ?1",
-            [id],
-            |row| row.get(0),
-        )?;
+        self.db.write(|tx| {
+            // Vérifier s'il y a des bandes liées à cette ferme
+            let bande_count: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM bandes WHERE ferme_id = ?1",
+                [id],
+                |row| row.get(0),
+            )?;
 
-        if bande_count > 0 {
-            return Err(AppError::constraint_violation(
-                "Impossible de supprimer la ferme car elle contient des bandes"
-            ));
-        }
+            if bande_count > 0 {
+                return Err(AppError::constraint_violation(
+                    "Impossible de supprimer la ferme car elle contient des bandes"
+                ));
+            }
 
-        // Suppression de la ferme
-        let rows_affected = conn.execute(
-            "DELETE FROM fermes WHERE id = ?1",
-            [id],
-        )?;
+            // Suppression de la ferme
+            let rows_affected = tx.execute(
+                "DELETE FROM fermes WHERE id = ?1",
+                [id],
+            )?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Ferme", id));
-        }
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Ferme", id));
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     async fn search_by_name(&self, nom: &str) -> AppResult<Vec<Ferme>> {
@@ -392,7 +406,7 @@ impl FermeRepositoryTrait for FermeRepository {
         
         let search_pattern = format!("%{}%", nom);
         let mut stmt = conn.prepare(
-            "SELECT id, nom, nbr_meuble FROM fermes WHERE nom LIKE ?1 ORDER BY nom"
+            "SELECT id, nom, nbr_meuble, version FROM fermes WHERE nom LIKE ?1 ORDER BY nom"
         )?;
         
         let fermes = stmt.query_map([search_pattern], |row| {
@@ -400,6 +414,7 @@ impl FermeRepositoryTrait for FermeRepository {
                 id: Some(row.get(0)?),
                 nom: row.get(1)?,
                 nbr_meuble: row.get(2)?,
+                version: row.get(3)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -411,16 +426,22 @@ impl FermeRepositoryTrait for FermeRepository {
         let conn = self.db.get_connection()?;
         
         let mut stmt = conn.prepare(
-            "SELECT id, numero_bande, date_entree, ferme_id, notes FROM bandes WHERE ferme_id = ?1 ORDER BY date_entree"
+            "SELECT id, numero_bande, date_entree, ferme_id, notes, statut, date_cloture,
+                    COALESCE(numero_affiche, CAST(numero_bande AS TEXT)), version
+             FROM bandes WHERE ferme_id = ?1 ORDER BY date_entree"
         )?;
         
         let bandes = stmt.query_map([ferme_id], |row| {
             Ok(Bande {
                 id: Some(row.get(0)?),
                 numero_bande: row.get(1)?,
+                numero_affiche: row.get(7)?,
                 date_entree: row.get(2)?,
                 ferme_id: row.get(3)?,
                 notes: row.get(4)?,
+                statut: row.get(5)?,
+                date_cloture: row.get(6)?,
+                version: row.get(8)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -511,6 +532,8 @@ impl FermeRepositoryTrait for FermeRepository {
             total_bandes: total_bandes as i32,
             bandes_par_ferme,
             maladies_par_ferme,
+            computed_at: Utc::now().to_rfc3339(),
+            cache_age_seconds: 0,
         })
     }
 
@@ -531,4 +554,49 @@ impl FermeRepositoryTrait for FermeRepository {
 
         Ok(total_deaths as i32)
     }
+
+    async fn get_ferme_breakdown(&self) -> AppResult<Vec<FermeBreakdown>> {
+        let conn = self.db.get_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT f.id, f.nom,
+                (SELECT COUNT(*) FROM bandes bd WHERE bd.ferme_id = f.id),
+                (SELECT COUNT(*) FROM bandes bd WHERE bd.ferme_id = f.id AND {}),
+                (SELECT COALESCE(SUM(b.quantite), 0)
+                 FROM batiments b
+                 JOIN bandes bd ON b.bande_id = bd.id
+                 WHERE bd.ferme_id = f.id),
+                (SELECT COALESCE(SUM(sq.deces_par_jour), 0)
+                 FROM suivi_quotidien sq
+                 JOIN semaines s ON sq.semaine_id = s.id
+                 JOIN batiments b ON s.batiment_id = b.id
+                 JOIN bandes bd ON b.bande_id = bd.id
+                 WHERE bd.ferme_id = f.id)
+             FROM fermes f
+             ORDER BY f.nom ASC",
+            BANDE_ACTIVE_CONDITION
+        ))?;
+
+        let breakdown = stmt.query_map([], |row| {
+            let poussins_places: i64 = row.get(4)?;
+            let total_deaths: i64 = row.get(5)?;
+
+            Ok(FermeBreakdown {
+                ferme_id: row.get(0)?,
+                ferme_nom: row.get(1)?,
+                total_bandes: row.get(2)?,
+                active_bandes: row.get(3)?,
+                poussins_places,
+                total_deaths,
+                mortality_rate: if poussins_places > 0 {
+                    Some(total_deaths as f64 / poussins_places as f64 * 100.0)
+                } else {
+                    None
+                },
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(breakdown)
+    }
 }
@@ -0,0 +1,145 @@
+use crate::error::AppError;
+use crate::models::{
+    CreateFiltreEnregistre, FiltreBandes, FiltreEnregistre, ENTITES_FILTRE, STATUT_BANDE_ACTIVE,
+    STATUT_BANDE_CLOTUREE,
+};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row};
+
+/// Columns read for a `FiltreEnregistre`, in the order expected by `map_filtre_row`
+const FILTRE_SELECT: &str = "SELECT id, user_id, nom, entite, filtre, created_at FROM filtres_enregistres";
+
+fn map_filtre_row(row: &Row) -> rusqlite::Result<FiltreEnregistre> {
+    let filtre: String = row.get(4)?;
+    Ok(FiltreEnregistre {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        nom: row.get(2)?,
+        entite: row.get(3)?,
+        filtre: serde_json::from_str(&filtre)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?,
+        created_at: row.get(5)?,
+    })
+}
+
+/// Repository for the named filter sets saved by each user
+pub struct FiltreEnregistreRepository;
+
+impl FiltreEnregistreRepository {
+    /// Save a named filter set for a user, replacing the one with the same name
+    ///
+    /// The criteria are checked against the entity before being stored.
+    pub fn save(
+        conn: &Connection,
+        user_id: i64,
+        filtre: &CreateFiltreEnregistre,
+    ) -> Result<FiltreEnregistre, AppError> {
+        let nom = filtre.nom.trim();
+        if nom.is_empty() {
+            return Err(AppError::validation_error("nom", "Le nom du filtre est obligatoire"));
+        }
+
+        let entite = filtre.entite.trim().to_lowercase();
+        if !ENTITES_FILTRE.contains(&entite.as_str()) {
+            return Err(AppError::validation_error(
+                "entite",
+                &format!("Liste non reconnue. Listes valides: {}", ENTITES_FILTRE.join(", "))
+            ));
+        }
+
+        let criteres: FiltreBandes = serde_json::from_value(filtre.filtre.clone()).map_err(|e| {
+            AppError::validation_error("filtre", &format!("Critères de filtre invalides: {}", e))
+        })?;
+        Self::validate_filtre_bandes(&criteres)?;
+
+        conn.execute(
+            "INSERT INTO filtres_enregistres (user_id, nom, entite, filtre) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(user_id, entite, nom) DO UPDATE SET filtre = excluded.filtre",
+            params![user_id, nom, entite, serde_json::to_string(&criteres)?],
+        )?;
+
+        let filtre = conn.query_row(
+            &format!("{} WHERE user_id = ?1 AND entite = ?2 AND nom = ?3", FILTRE_SELECT),
+            params![user_id, entite, nom],
+            map_filtre_row,
+        )?;
+
+        Ok(filtre)
+    }
+
+    /// List the filters of a user by name, optionally for one entity
+    pub fn get_by_user(
+        conn: &Connection,
+        user_id: i64,
+        entite: Option<&str>,
+    ) -> Result<Vec<FiltreEnregistre>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE user_id = ?1 AND (?2 IS NULL OR entite = ?2) ORDER BY entite, nom",
+            FILTRE_SELECT
+        ))?;
+
+        let filtres = stmt.query_map(params![user_id, entite], map_filtre_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(filtres)
+    }
+
+    /// Delete a filter of a user
+    pub fn delete(
+        conn: &Connection,
+        id: i64,
+        user_id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute(
+            "DELETE FROM filtres_enregistres WHERE id = ?1 AND user_id = ?2",
+            [id, user_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("FiltreEnregistre", id));
+        }
+
+        Ok(())
+    }
+
+    /// Criteria of a saved bande filter
+    pub fn get_filtre_bandes(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<FiltreBandes, AppError> {
+        let filtre = conn
+            .query_row(&format!("{} WHERE id = ?1", FILTRE_SELECT), [id], map_filtre_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("FiltreEnregistre", id),
+                _ => AppError::from(e),
+            })?;
+
+        Ok(serde_json::from_value(filtre.filtre)?)
+    }
+
+    /// Check the values of bande criteria: dates as YYYY-MM-DD, known status,
+    /// mortality between 0 and 100%
+    pub fn validate_filtre_bandes(filtre: &FiltreBandes) -> Result<(), AppError> {
+        for (champ, date) in [("date_from", &filtre.date_from), ("date_to", &filtre.date_to)] {
+            if date.as_deref().is_some_and(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_err()) {
+                return Err(AppError::validation_error(champ, "La date doit être au format AAAA-MM-JJ"));
+            }
+        }
+
+        if filtre.statut.as_deref().is_some_and(|s| s != STATUT_BANDE_ACTIVE && s != STATUT_BANDE_CLOTUREE) {
+            return Err(AppError::validation_error(
+                "statut",
+                &format!("Statut non reconnu. Statuts valides: {}, {}", STATUT_BANDE_ACTIVE, STATUT_BANDE_CLOTUREE)
+            ));
+        }
+
+        if filtre.mortalite_min.is_some_and(|m| !(0.0..=100.0).contains(&m)) {
+            return Err(AppError::validation_error(
+                "mortalite_min",
+                "La mortalité minimale doit être comprise entre 0 et 100%"
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CreateImmobilisation, Immobilisation, LigneAmortissement, TableauAmortissement, UpdateImmobilisation,
+    CATEGORIES_IMMOBILISATION,
+};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row, ToSql};
+use std::sync::Arc;
+
+/// Colonnes lues pour une `Immobilisation`, dans l'ordre attendu par `map_immobilisation_row`
+const IMMOBILISATION_SELECT: &str =
+    "SELECT id, ferme_id, nom, categorie, date_achat, valeur, duree_amortissement_annees,
+            date(date_achat, '+' || duree_amortissement_annees || ' years') AS date_fin_amortissement,
+            valeur / duree_amortissement_annees AS amortissement_annuel,
+            created_at
+     FROM immobilisations";
+
+fn map_immobilisation_row(row: &Row) -> rusqlite::Result<Immobilisation> {
+    Ok(Immobilisation {
+        id: row.get(0)?,
+        ferme_id: row.get(1)?,
+        nom: row.get(2)?,
+        categorie: row.get(3)?,
+        date_achat: row.get(4)?,
+        valeur: row.get(5)?,
+        duree_amortissement_annees: row.get(6)?,
+        date_fin_amortissement: row.get(7)?,
+        amortissement_annuel: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+/// Trait pour les opérations sur le registre des immobilisations
+pub trait ImmobilisationRepositoryTrait: Send + Sync {
+    /// Enregistre une immobilisation dans le registre d'une ferme
+    ///
+    /// # Arguments
+    /// * `immobilisation` - Les données de l'immobilisation
+    ///
+    /// # Returns
+    /// L'immobilisation créée avec son ID généré
+    async fn create(&self, immobilisation: CreateImmobilisation) -> AppResult<Immobilisation>;
+
+    /// Liste les immobilisations d'une ferme, par date d'achat
+    async fn get_by_ferme(&self, ferme_id: i64) -> AppResult<Vec<Immobilisation>>;
+
+    /// Met à jour une immobilisation
+    async fn update(&self, immobilisation: UpdateImmobilisation) -> AppResult<Immobilisation>;
+
+    /// Supprime une immobilisation
+    async fn delete(&self, id: i64) -> AppResult<()>;
+
+    /// Tableau d'amortissement d'une ferme pour une année civile
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `annee` - L'année civile
+    ///
+    /// # Returns
+    /// La dotation de l'année et la valeur nette au 31 décembre de chaque immobilisation
+    async fn get_depreciation_schedule(&self, ferme_id: i64, annee: i32) -> AppResult<TableauAmortissement>;
+}
+
+/// Repository implementation for the farm assets register
+pub struct ImmobilisationRepository {
+    db: Arc<dyn Storage>,
+}
+
+impl ImmobilisationRepository {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Liste les immobilisations d'une ferme sur une connexion existante
+    ///
+    /// # Arguments
+    /// * `conn` - La connexion à la base de données
+    /// * `ferme_id` - L'ID de la ferme
+    pub fn list_by_ferme(conn: &Connection, ferme_id: i64) -> AppResult<Vec<Immobilisation>> {
+        Self::query(conn, "ferme_id = ?1", "date_achat, id", &[&ferme_id])
+    }
+
+    /// Dotation d'une immobilisation entre deux dates incluses
+    ///
+    /// La valeur est répartie uniformément sur les jours de la durée
+    /// d'amortissement; hors de cette durée, la dotation est nulle.
+    ///
+    /// # Arguments
+    /// * `immobilisation` - L'immobilisation amortie
+    /// * `debut` - Premier jour de la période
+    /// * `fin` - Dernier jour de la période
+    pub fn dotation_periode(immobilisation: &Immobilisation, debut: NaiveDate, fin: NaiveDate) -> f64 {
+        let parse = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+        let (Some(achat), Some(fin_amortissement)) =
+            (parse(&immobilisation.date_achat), parse(&immobilisation.date_fin_amortissement))
+        else {
+            return 0.0;
+        };
+
+        let jours_amortissement = (fin_amortissement - achat).num_days();
+        // Le dernier jour amorti est la veille de la fin d'amortissement
+        let jours_periode = (fin.min(fin_amortissement.pred_opt().unwrap_or(fin_amortissement)) - debut.max(achat))
+            .num_days()
+            + 1;
+        if jours_amortissement <= 0 || jours_periode <= 0 {
+            return 0.0;
+        }
+        immobilisation.valeur * jours_periode as f64 / jours_amortissement as f64
+    }
+
+    /// Valide les données saisies: nom, catégorie, date, valeur et durée
+    fn validate(nom: &str, categorie: &str, date_achat: &str, valeur: f64, duree: i32) -> AppResult<()> {
+        if nom.trim().is_empty() {
+            return Err(AppError::validation_error("nom", "Le nom de l'immobilisation est obligatoire"));
+        }
+        if !CATEGORIES_IMMOBILISATION.contains(&categorie) {
+            return Err(AppError::validation_error(
+                "categorie",
+                &format!("La catégorie doit être l'une de: {}", CATEGORIES_IMMOBILISATION.join(", ")),
+            ));
+        }
+        NaiveDate::parse_from_str(date_achat, "%Y-%m-%d").map_err(|_| {
+            AppError::validation_error("date_achat", "La date d'achat doit être au format AAAA-MM-JJ")
+        })?;
+        if !valeur.is_finite() || valeur < 0.0 {
+            return Err(AppError::validation_error("valeur", "La valeur ne peut pas être négative"));
+        }
+        if duree <= 0 {
+            return Err(AppError::validation_error(
+                "duree_amortissement_annees",
+                "La durée d'amortissement doit être d'au moins un an",
+            ));
+        }
+        Ok(())
+    }
+
+    fn query(conn: &Connection, condition: &str, order: &str, params: &[&dyn ToSql]) -> AppResult<Vec<Immobilisation>> {
+        let mut stmt = conn.prepare(&format!("{} WHERE {} ORDER BY {}", IMMOBILISATION_SELECT, condition, order))?;
+        let immobilisations = stmt.query_map(params, map_immobilisation_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(immobilisations)
+    }
+
+    fn find(conn: &Connection, id: i64) -> AppResult<Immobilisation> {
+        conn.query_row(&format!("{} WHERE id = ?1", IMMOBILISATION_SELECT), [id], map_immobilisation_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Immobilisation", id),
+                _ => AppError::from(e),
+            })
+    }
+}
+
+impl ImmobilisationRepositoryTrait for ImmobilisationRepository {
+    async fn create(&self, immobilisation: CreateImmobilisation) -> AppResult<Immobilisation> {
+        self.db.write(|tx| {
+            let nom = immobilisation.nom.trim();
+            Self::validate(
+                nom,
+                &immobilisation.categorie,
+                &immobilisation.date_achat,
+                immobilisation.valeur,
+                immobilisation.duree_amortissement_annees,
+            )?;
+            let ferme_existe: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM fermes WHERE id = ?1)",
+                [immobilisation.ferme_id],
+                |row| row.get(0),
+            )?;
+            if !ferme_existe {
+                return Err(AppError::not_found("Ferme", immobilisation.ferme_id));
+            }
+
+            tx.execute(
+                "INSERT INTO immobilisations (ferme_id, nom, categorie, date_achat, valeur, duree_amortissement_annees)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    immobilisation.ferme_id,
+                    nom,
+                    immobilisation.categorie,
+                    immobilisation.date_achat,
+                    immobilisation.valeur,
+                    immobilisation.duree_amortissement_annees
+                ],
+            )?;
+
+            Self::find(tx, tx.last_insert_rowid())
+        })
+    }
+
+    async fn get_by_ferme(&self, ferme_id: i64) -> AppResult<Vec<Immobilisation>> {
+        let conn = self.db.get_connection()?;
+        Self::list_by_ferme(&conn, ferme_id)
+    }
+
+    async fn update(&self, immobilisation: UpdateImmobilisation) -> AppResult<Immobilisation> {
+        self.db.write(|tx| {
+            Self::find(tx, immobilisation.id)?;
+            let nom = immobilisation.nom.trim();
+            Self::validate(
+                nom,
+                &immobilisation.categorie,
+                &immobilisation.date_achat,
+                immobilisation.valeur,
+                immobilisation.duree_amortissement_annees,
+            )?;
+
+            tx.execute(
+                "UPDATE immobilisations
+                 SET nom = ?1, categorie = ?2, date_achat = ?3, valeur = ?4, duree_amortissement_annees = ?5
+                 WHERE id = ?6",
+                params![
+                    nom,
+                    immobilisation.categorie,
+                    immobilisation.date_achat,
+                    immobilisation.valeur,
+                    immobilisation.duree_amortissement_annees,
+                    immobilisation.id
+                ],
+            )?;
+
+            Self::find(tx, immobilisation.id)
+        })
+    }
+
+    async fn delete(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| {
+            let rows_affected = tx.execute("DELETE FROM immobilisations WHERE id = ?1", [id])?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Immobilisation", id));
+            }
+
+            Ok(())
+        })
+    }
+
+    async fn get_depreciation_schedule(&self, ferme_id: i64, annee: i32) -> AppResult<TableauAmortissement> {
+        let (Some(debut), Some(fin)) = (NaiveDate::from_ymd_opt(annee, 1, 1), NaiveDate::from_ymd_opt(annee, 12, 31))
+        else {
+            return Err(AppError::validation_error("annee", "L'année n'est pas valide"));
+        };
+        let conn = self.db.get_connection()?;
+
+        let lignes: Vec<LigneAmortissement> = Self::list_by_ferme(&conn, ferme_id)?
+            .into_iter()
+            .filter_map(|immobilisation| {
+                let dotation = Self::dotation_periode(&immobilisation, debut, fin);
+                if dotation <= 0.0 {
+                    return None;
+                }
+                let cumul = Self::dotation_periode(&immobilisation, NaiveDate::MIN, fin);
+                Some(LigneAmortissement {
+                    immobilisation_id: immobilisation.id,
+                    nom: immobilisation.nom,
+                    categorie: immobilisation.categorie,
+                    dotation,
+                    valeur_nette_fin_annee: (immobilisation.valeur - cumul).max(0.0),
+                })
+            })
+            .collect();
+        let total = lignes.iter().map(|ligne| ligne.dotation).sum();
+
+        Ok(TableauAmortissement { ferme_id, annee, lignes, total })
+    }
+}
@@ -0,0 +1,102 @@
+use crate::error::AppError;
+use crate::models::Invitation;
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, Row};
+
+/// Repository for registration invitation codes
+pub struct InvitationRepository;
+
+const INVITATION_COLUMNS: &str =
+    "id, code, role, expires_on, single_use, use_count, revoked, created_by, created_at";
+
+fn map_invitation(row: &Row) -> rusqlite::Result<Invitation> {
+    Ok(Invitation {
+        id: row.get(0)?,
+        code: row.get(1)?,
+        role: row.get(2)?,
+        expires_on: row.get(3)?,
+        single_use: row.get(4)?,
+        use_count: row.get(5)?,
+        revoked: row.get(6)?,
+        created_by: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+impl InvitationRepository {
+    /// Create an invitation code
+    pub fn create(
+        conn: &Connection,
+        code: &str,
+        role: &str,
+        expires_on: Option<NaiveDate>,
+        single_use: bool,
+        created_by: i64,
+    ) -> Result<Invitation, AppError> {
+        conn.execute(
+            "INSERT INTO invitations (code, role, expires_on, single_use, created_by) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![code, role, expires_on, single_use, created_by],
+        )?;
+        Self::get_by_id(conn, conn.last_insert_rowid())?
+            .ok_or_else(|| AppError::not_found("Invitation", conn.last_insert_rowid()))
+    }
+
+    /// Get an invitation by ID
+    pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Invitation>, AppError> {
+        Ok(conn
+            .query_row(
+                &format!("SELECT {} FROM invitations WHERE id = ?1", INVITATION_COLUMNS),
+                [id],
+                map_invitation,
+            )
+            .optional()?)
+    }
+
+    /// Get all invitations, most recent first
+    pub fn get_all(conn: &Connection) -> Result<Vec<Invitation>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM invitations ORDER BY created_at DESC, id DESC",
+            INVITATION_COLUMNS
+        ))?;
+        let invitations = stmt.query_map([], map_invitation)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(invitations)
+    }
+
+    /// Find a code that can still be used today: not revoked, not expired,
+    /// and not already used if it is single-use
+    pub fn find_usable(conn: &Connection, code: &str, today: NaiveDate) -> Result<Option<Invitation>, AppError> {
+        Ok(conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM invitations
+                     WHERE code = ?1 AND revoked = 0
+                       AND (expires_on IS NULL OR expires_on >= ?2)
+                       AND (single_use = 0 OR use_count = 0)",
+                    INVITATION_COLUMNS
+                ),
+                rusqlite::params![code, today],
+                map_invitation,
+            )
+            .optional()?)
+    }
+
+    /// Record that an account was created with an invitation
+    pub fn mark_used(conn: &Connection, id: i64, user_id: i64) -> Result<(), AppError> {
+        conn.execute(
+            "UPDATE invitations SET use_count = use_count + 1, last_used_by = ?2, last_used_at = CURRENT_TIMESTAMP
+             WHERE id = ?1",
+            rusqlite::params![id, user_id],
+        )?;
+        Ok(())
+    }
+
+    /// Revoke an invitation so it can no longer be used
+    pub fn revoke(conn: &Connection, id: i64) -> Result<(), AppError> {
+        let rows_affected = conn.execute("UPDATE invitations SET revoked = 1 WHERE id = ?1", [id])?;
+        if rows_affected == 0 {
+            return Err(AppError::not_found("Invitation", id));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateLitiere, Litiere, SaisieTva, UpdateLitiere, TYPES_OPERATION_LITIERE, UNITES_LITIERE};
+use crate::repositories::{ventiler_tva, TvaAchat};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row, ToSql};
+use std::sync::Arc;
+
+/// Colonnes lues pour une `Litiere`, dans l'ordre attendu par `map_litiere_row`
+const LITIERE_SELECT: &str =
+    "SELECT l.id, l.batiment_id, l.type_operation, l.materiau, l.quantite, l.unite, l.cout,
+            l.date_operation, l.created_at, l.taux_tva, COALESCE(l.montant_tva, l.cout * l.taux_tva / 100)
+     FROM litieres l";
+
+fn map_litiere_row(row: &Row) -> rusqlite::Result<Litiere> {
+    Ok(Litiere {
+        id: Some(row.get(0)?),
+        batiment_id: row.get(1)?,
+        type_operation: row.get(2)?,
+        materiau: row.get(3)?,
+        quantite: row.get(4)?,
+        unite: row.get(5)?,
+        cout: row.get(6)?,
+        date_operation: row.get(7)?,
+        created_at: row.get(8)?,
+        taux_tva: row.get(9)?,
+        montant_tva: row.get(10)?,
+    })
+}
+
+/// Champs d'une opération de litière validés et normalisés
+struct LitiereValidee {
+    type_operation: String,
+    materiau: String,
+    unite: String,
+    /// Coût hors taxes et TVA
+    tva: TvaAchat,
+}
+
+/// Trait pour les opérations sur les livraisons et changements de litière
+pub trait LitiereRepositoryTrait: Send + Sync {
+    /// Enregistre une livraison ou un changement de litière
+    ///
+    /// # Arguments
+    /// * `litiere` - Les données de l'opération
+    ///
+    /// # Returns
+    /// L'opération créée avec son ID généré
+    async fn create(&self, litiere: CreateLitiere) -> AppResult<Litiere>;
+
+    /// Liste les opérations de litière d'un bâtiment, de la plus récente à la plus ancienne
+    async fn get_by_batiment(&self, batiment_id: i64) -> AppResult<Vec<Litiere>>;
+
+    /// Liste les opérations de litière de tous les bâtiments d'une bande par date
+    async fn get_by_bande(&self, bande_id: i64) -> AppResult<Vec<Litiere>>;
+
+    /// Met à jour une opération de litière existante
+    async fn update(&self, litiere: UpdateLitiere) -> AppResult<Litiere>;
+
+    /// Supprime une opération de litière
+    async fn delete(&self, id: i64) -> AppResult<()>;
+}
+
+/// Repository implementation for litter records
+pub struct LitiereRepository {
+    db: Arc<dyn Storage>,
+}
+
+impl LitiereRepository {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Valide les champs communs à la création et à la mise à jour
+    ///
+    /// Le type d'opération et l'unité sont normalisés en minuscules, le coût
+    /// est converti hors taxes.
+    fn validate(
+        type_operation: &str,
+        materiau: &str,
+        quantite: f64,
+        unite: &str,
+        cout: Option<f64>,
+        tva: &SaisieTva,
+        date_operation: &str,
+    ) -> AppResult<LitiereValidee> {
+        let type_operation = type_operation.trim().to_lowercase();
+        if !TYPES_OPERATION_LITIERE.contains(&type_operation.as_str()) {
+            return Err(AppError::validation_error(
+                "type_operation",
+                &format!("Type d'opération non reconnu. Types valides: {}", TYPES_OPERATION_LITIERE.join(", "))
+            ));
+        }
+
+        let materiau = materiau.trim().to_string();
+        if materiau.is_empty() {
+            return Err(AppError::validation_error("materiau", "Le matériau de la litière est obligatoire"));
+        }
+
+        if !quantite.is_finite() || quantite <= 0.0 {
+            return Err(AppError::validation_error("quantite", "La quantité doit être supérieure à zéro"));
+        }
+
+        let unite = unite.trim().to_lowercase();
+        if !UNITES_LITIERE.contains(&unite.as_str()) {
+            return Err(AppError::validation_error(
+                "unite",
+                &format!("Unité non reconnue. Unités valides: {}", UNITES_LITIERE.join(", "))
+            ));
+        }
+
+        if cout.is_some_and(|cout| !cout.is_finite() || cout < 0.0) {
+            return Err(AppError::validation_error("cout", "Le coût ne peut pas être négatif"));
+        }
+
+        if NaiveDate::parse_from_str(date_operation, "%Y-%m-%d").is_err() {
+            return Err(AppError::validation_error(
+                "date_operation",
+                "La date de l'opération doit être au format AAAA-MM-JJ"
+            ));
+        }
+
+        let tva = ventiler_tva(cout, tva)?;
+
+        Ok(LitiereValidee { type_operation, materiau, unite, tva })
+    }
+
+    fn query(conn: &Connection, condition: &str, order: &str, params: &[&dyn ToSql]) -> AppResult<Vec<Litiere>> {
+        let mut stmt = conn.prepare(&format!("{} WHERE {} ORDER BY {}", LITIERE_SELECT, condition, order))?;
+        let litieres = stmt.query_map(params, map_litiere_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(litieres)
+    }
+
+    fn find(conn: &Connection, id: i64) -> AppResult<Litiere> {
+        conn.query_row(&format!("{} WHERE l.id = ?1", LITIERE_SELECT), [id], map_litiere_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Litiere", id),
+                _ => AppError::from(e),
+            })
+    }
+}
+
+impl LitiereRepositoryTrait for LitiereRepository {
+    async fn create(&self, litiere: CreateLitiere) -> AppResult<Litiere> {
+        self.db.write(|tx| {
+            let batiment_exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM batiments WHERE id = ?1",
+                [litiere.batiment_id],
+                |row| row.get(0),
+            )?;
+            if batiment_exists == 0 {
+                return Err(AppError::not_found("Batiment", litiere.batiment_id));
+            }
+
+            let valide = Self::validate(
+                &litiere.type_operation,
+                &litiere.materiau,
+                litiere.quantite,
+                &litiere.unite,
+                litiere.cout,
+                &litiere.tva,
+                &litiere.date_operation,
+            )?;
+            tx.execute(
+                "INSERT INTO litieres (batiment_id, type_operation, materiau, quantite, unite, cout, taux_tva,
+                                       montant_tva, date_operation)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    litiere.batiment_id,
+                    valide.type_operation,
+                    valide.materiau,
+                    litiere.quantite,
+                    valide.unite,
+                    valide.tva.montant_ht,
+                    valide.tva.taux_tva,
+                    valide.tva.montant_tva,
+                    litiere.date_operation,
+                ],
+            )?;
+
+            Self::find(tx, tx.last_insert_rowid())
+        })
+    }
+
+    async fn get_by_batiment(&self, batiment_id: i64) -> AppResult<Vec<Litiere>> {
+        let conn = self.db.get_connection()?;
+        Self::query(&conn, "l.batiment_id = ?1", "l.date_operation DESC, l.id DESC", &[&batiment_id])
+    }
+
+    async fn get_by_bande(&self, bande_id: i64) -> AppResult<Vec<Litiere>> {
+        let conn = self.db.get_connection()?;
+        Self::query(
+            &conn,
+            "l.batiment_id IN (SELECT id FROM batiments WHERE bande_id = ?1)",
+            "l.date_operation, l.id",
+            &[&bande_id],
+        )
+    }
+
+    async fn update(&self, litiere: UpdateLitiere) -> AppResult<Litiere> {
+        self.db.write(|tx| {
+            Self::find(tx, litiere.id)?;
+
+            let valide = Self::validate(
+                &litiere.type_operation,
+                &litiere.materiau,
+                litiere.quantite,
+                &litiere.unite,
+                litiere.cout,
+                &litiere.tva,
+                &litiere.date_operation,
+            )?;
+            tx.execute(
+                "UPDATE litieres SET type_operation = ?1, materiau = ?2, quantite = ?3, unite = ?4,
+                        cout = ?5, taux_tva = ?6, montant_tva = ?7, date_operation = ?8
+                 WHERE id = ?9",
+                params![
+                    valide.type_operation,
+                    valide.materiau,
+                    litiere.quantite,
+                    valide.unite,
+                    valide.tva.montant_ht,
+                    valide.tva.taux_tva,
+                    valide.tva.montant_tva,
+                    litiere.date_operation,
+                    litiere.id,
+                ],
+            )?;
+
+            Self::find(tx, litiere.id)
+        })
+    }
+
+    async fn delete(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| {
+            let rows_affected = tx.execute("DELETE FROM litieres WHERE id = ?1", [id])?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Litiere", id));
+            }
+
+            Ok(())
+        })
+    }
+}
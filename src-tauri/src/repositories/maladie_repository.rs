@@ -1,6 +1,6 @@
-use crate::database::DatabaseManager;
+use crate::database::{nom_existe, normaliser_nom, Storage};
 use crate::error::{AppError, AppResult};
-use crate::models::{Maladie, CreateMaladie, UpdateMaladie, PaginatedMaladies};
+use crate::models::{Maladie, CreateMaladie, UpdateMaladie, PaginatedMaladies, TendanceMaladie, GRANULARITES_TENDANCE};
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
 
@@ -20,48 +20,55 @@ pub trait MaladieRepositoryTrait: Send + Sync {
     
     /// Delete maladie by ID
     async fn delete(&self, id: i64) -> AppResult<()>;
+
+    /// Check whether a maladie name exists, ignoring case and accents
+    async fn exists_by_nom(&self, nom: &str, exclude_id: Option<i64>) -> AppResult<bool>;
+
+    /// Count affected batiments per maladie and per month or quarter
+    async fn get_trends(&self, ferme_id: Option<i64>, granularite: &str) -> AppResult<Vec<TendanceMaladie>>;
 }
 
 /// Maladie repository implementation
 pub struct MaladieRepository {
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl MaladieRepository {
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
         Self { db }
     }
 }
 
 impl MaladieRepositoryTrait for MaladieRepository {
     async fn create(&self, maladie: CreateMaladie) -> AppResult<Maladie> {
-        let conn = self.db.get_connection()?;
-        let now = Utc::now();
+        self.db.write(|tx| {
+            let now = Utc::now();
         
-        conn.execute(
-            "INSERT INTO maladies (nom, created_at) VALUES (?1, ?2)",
-            [&maladie.nom, &now.to_rfc3339()],
-        )?;
-
-        let id = conn.last_insert_rowid();
-
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM maladies WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([id], |row| {
-            Ok(row.get(0)?)
-        })?;
-
-        // Parse the timestamp
-        let created_at = DateTime::parse_from_rfc3339(&created_at)
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
-            })?
-            .with_timezone(&Utc);
-
-        Ok(Maladie {
-            id,
-            nom: maladie.nom,
-            created_at,
+            tx.execute(
+                "INSERT INTO maladies (nom, nom_normalise, created_at) VALUES (?1, ?2, ?3)",
+                [&maladie.nom, &normaliser_nom(&maladie.nom), &now.to_rfc3339()],
+            )?;
+
+            let id = tx.last_insert_rowid();
+
+            // Get the created_at timestamp from the database
+            let mut stmt = tx.prepare("SELECT created_at FROM maladies WHERE id = ?1")?;
+            let created_at: String = stmt.query_row([id], |row| {
+                Ok(row.get(0)?)
+            })?;
+
+            // Parse the timestamp
+            let created_at = DateTime::parse_from_rfc3339(&created_at)
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?
+                .with_timezone(&Utc);
+
+            Ok(Maladie {
+                id,
+                nom: maladie.nom,
+                created_at,
+            })
         })
     }
 
@@ -146,50 +153,50 @@ impl MaladieRepositoryTrait for MaladieRepository {
     }
 
     async fn update(&self, maladie: UpdateMaladie) -> AppResult<Maladie> {
-        let conn = self.db.get_connection()?;
-        
-        let rows_affected = conn.execute(
-            "UPDATE maladies SET nom = ?1 WHERE id = ?2",
-            [&maladie.nom, &maladie.id.to_string()],
-        )?;
+        self.db.write(|tx| {
+            let rows_affected = tx.execute(
+                "UPDATE maladies SET nom = ?1, nom_normalise = ?2 WHERE id = ?3",
+                [&maladie.nom, &normaliser_nom(&maladie.nom), &maladie.id.to_string()],
+            )?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Maladie", maladie.id));
-        }
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Maladie", maladie.id));
+            }
+
+            // Get the created_at timestamp from the database
+            let mut stmt = tx.prepare("SELECT created_at FROM maladies WHERE id = ?1")?;
+            let created_at: String = stmt.query_row([maladie.id], |row| {
+                Ok(row.get(0)?)
+            })?;
+
+            // Parse the timestamp
+            let created_at = DateTime::parse_from_rfc3339(&created_at)
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?
+                .with_timezone(&Utc);
 
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM maladies WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([maladie.id], |row| {
-            Ok(row.get(0)?)
-        })?;
-
-        // Parse the timestamp
-        let created_at = DateTime::parse_from_rfc3339(&created_at)
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
-            })?
-            .with_timezone(&Utc);
-
-        Ok(Maladie {
-            id: maladie.id,
-            nom: maladie.nom,
-            created_at,
+            Ok(Maladie {
+                id: maladie.id,
+                nom: maladie.nom,
+                created_at,
+            })
         })
     }
 
     async fn delete(&self, id: i64) -> AppResult<()> {
-        let conn = self.db.get_connection()?;
-        
-        let rows_affected = conn.execute(
-            "DELETE FROM maladies WHERE id = ?1",
-            [id],
-        )?;
+        self.db.write(|tx| {
+            let rows_affected = tx.execute(
+                "DELETE FROM maladies WHERE id = ?1",
+                [id],
+            )?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Maladie", id));
-        }
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Maladie", id));
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     async fn get_maladies_list(&self) -> AppResult<Vec<Maladie>> {
@@ -215,4 +222,46 @@ impl MaladieRepositoryTrait for MaladieRepository {
         
         Ok(maladies_list)
     }
+    async fn exists_by_nom(&self, nom: &str, exclude_id: Option<i64>) -> AppResult<bool> {
+        let conn = self.db.get_connection()?;
+        nom_existe(&conn, "maladies", nom, exclude_id)
+    }
+
+    async fn get_trends(&self, ferme_id: Option<i64>, granularite: &str) -> AppResult<Vec<TendanceMaladie>> {
+        // An episode is dated by the moment the maladie was linked to the batiment
+        let periode = match granularite {
+            "month" => "strftime('%Y-%m', bm.created_at)",
+            "quarter" => "strftime('%Y', bm.created_at) || '-T' || ((CAST(strftime('%m', bm.created_at) AS INTEGER) + 2) / 3)",
+            _ => {
+                return Err(AppError::validation_error(
+                    "granularity",
+                    &format!("Granularité non reconnue. Valeurs valides: {}", GRANULARITES_TENDANCE.join(", ")),
+                ))
+            }
+        };
+
+        let conn = self.db.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} AS periode, m.id, m.nom, COUNT(DISTINCT bm.batiment_id)
+             FROM batiment_maladies bm
+             JOIN maladies m ON bm.maladie_id = m.id
+             JOIN batiments b ON bm.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE ?1 IS NULL OR bd.ferme_id = ?1
+             GROUP BY periode, m.id
+             ORDER BY periode, m.nom",
+            periode
+        ))?;
+
+        let trends = stmt.query_map([ferme_id], |row| {
+            Ok(TendanceMaladie {
+                periode: row.get(0)?,
+                maladie_id: row.get(1)?,
+                maladie_nom: row.get(2)?,
+                batiments_affectes: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(trends)
+    }
 }
@@ -0,0 +1,122 @@
+use crate::error::AppError;
+use crate::models::{CreateMessage, Message};
+use rusqlite::{params, Connection, Row};
+
+/// Colonnes lues pour un `Message`, dans l'ordre attendu par `map_message_row`
+const MESSAGE_SELECT: &str =
+    "SELECT m.id, m.auteur_id, u.username, m.destinataire_id, m.batiment_id, b.numero_batiment,
+            m.contenu, m.lu, m.created_at
+     FROM messages m
+     LEFT JOIN users u ON m.auteur_id = u.id
+     LEFT JOIN batiments b ON m.batiment_id = b.id";
+
+fn map_message_row(row: &Row) -> rusqlite::Result<Message> {
+    Ok(Message {
+        id: row.get(0)?,
+        auteur_id: row.get(1)?,
+        auteur: row.get(2)?,
+        destinataire_id: row.get(3)?,
+        batiment_id: row.get(4)?,
+        numero_batiment: row.get(5)?,
+        contenu: row.get(6)?,
+        lu: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// Repository for the handover messages left between shifts
+pub struct MessageRepository;
+
+impl MessageRepository {
+    /// Post a message to a user, to a batiment, or to a user about a batiment
+    pub fn create(
+        conn: &Connection,
+        auteur_id: i64,
+        message: &CreateMessage,
+    ) -> Result<Message, AppError> {
+        let contenu = message.contenu.trim();
+        if contenu.is_empty() {
+            return Err(AppError::validation_error("contenu", "Le message ne peut pas être vide"));
+        }
+        if message.destinataire_id.is_none() && message.batiment_id.is_none() {
+            return Err(AppError::validation_error(
+                "destinataire_id",
+                "Le message doit être adressé à un utilisateur ou à un bâtiment",
+            ));
+        }
+
+        if let Some(destinataire_id) = message.destinataire_id {
+            let user_exists: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM users WHERE id = ?1",
+                [destinataire_id],
+                |row| row.get(0),
+            )?;
+            if user_exists == 0 {
+                return Err(AppError::not_found("User", destinataire_id));
+            }
+        }
+        if let Some(batiment_id) = message.batiment_id {
+            let batiment_exists: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM batiments WHERE id = ?1",
+                [batiment_id],
+                |row| row.get(0),
+            )?;
+            if batiment_exists == 0 {
+                return Err(AppError::not_found("Batiment", batiment_id));
+            }
+        }
+
+        conn.execute(
+            "INSERT INTO messages (auteur_id, destinataire_id, batiment_id, contenu) VALUES (?1, ?2, ?3, ?4)",
+            params![auteur_id, message.destinataire_id, message.batiment_id, contenu],
+        )?;
+
+        Self::find(conn, conn.last_insert_rowid())
+    }
+
+    /// List the unread messages for a user, oldest first
+    ///
+    /// A user sees the messages addressed to them and the batiment messages
+    /// without recipient posted by someone else, optionally limited to one batiment.
+    pub fn get_unread(
+        conn: &Connection,
+        user_id: i64,
+        batiment_id: Option<i64>,
+    ) -> Result<Vec<Message>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE m.lu = 0
+               AND (m.destinataire_id = ?1
+                    OR (m.destinataire_id IS NULL AND (m.auteur_id IS NULL OR m.auteur_id != ?1)))
+               AND (?2 IS NULL OR m.batiment_id = ?2)
+             ORDER BY m.created_at, m.id",
+            MESSAGE_SELECT
+        ))?;
+
+        let messages = stmt.query_map(params![user_id, batiment_id], map_message_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(messages)
+    }
+
+    /// Mark a message as read
+    ///
+    /// A message addressed to a user can only be marked as read by that user.
+    pub fn mark_read(conn: &Connection, id: i64, user_id: i64) -> Result<Message, AppError> {
+        let message = Self::find(conn, id)?;
+        if message.destinataire_id.is_some_and(|destinataire_id| destinataire_id != user_id) {
+            return Err(AppError::business_logic("Ce message est adressé à un autre utilisateur"));
+        }
+
+        conn.execute("UPDATE messages SET lu = 1 WHERE id = ?1", [id])?;
+
+        Self::find(conn, id)
+    }
+
+    fn find(conn: &Connection, id: i64) -> Result<Message, AppError> {
+        conn.query_row(&format!("{} WHERE m.id = ?1", MESSAGE_SELECT), [id], map_message_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Message", id),
+                _ => AppError::from(e),
+            })
+    }
+}
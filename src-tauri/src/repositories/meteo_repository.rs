@@ -0,0 +1,124 @@
+use crate::error::AppError;
+use crate::models::{MeteoJour, MortaliteMeteoJour, SEUIL_VAGUE_CHALEUR_C};
+use rusqlite::Connection;
+
+/// Repository for daily weather readings per ferme
+pub struct MeteoRepository;
+
+impl MeteoRepository {
+    /// Get the fermes with coordinates: (id, nom, latitude, longitude)
+    pub fn get_fermes_geolocalisees(
+        conn: &Connection,
+    ) -> Result<Vec<(i64, String, Option<f64>, Option<f64>)>, AppError> {
+        let mut stmt = conn.prepare("SELECT id, nom, latitude, longitude FROM fermes ORDER BY nom")?;
+        let fermes = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(fermes)
+    }
+
+    /// Create or replace the reading of a day
+    pub fn upsert(
+        conn: &Connection,
+        meteo: &MeteoJour,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO meteo_quotidienne
+                (ferme_id, date, temperature_min, temperature_max, temperature_moyenne, humidite_moyenne)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(ferme_id, date) DO UPDATE SET
+                temperature_min = excluded.temperature_min,
+                temperature_max = excluded.temperature_max,
+                temperature_moyenne = excluded.temperature_moyenne,
+                humidite_moyenne = excluded.humidite_moyenne,
+                updated_at = CURRENT_TIMESTAMP",
+            rusqlite::params![
+                meteo.ferme_id,
+                meteo.date,
+                meteo.temperature_min,
+                meteo.temperature_max,
+                meteo.temperature_moyenne,
+                meteo.humidite_moyenne,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the readings of a ferme, optionally within a date range
+    pub fn get_by_ferme(
+        conn: &Connection,
+        ferme_id: i64,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<MeteoJour>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT ferme_id, date, temperature_min, temperature_max, temperature_moyenne, humidite_moyenne
+             FROM meteo_quotidienne
+             WHERE ferme_id = ?1
+               AND (?2 IS NULL OR date >= ?2)
+               AND (?3 IS NULL OR date <= ?3)
+             ORDER BY date"
+        )?;
+
+        let releves = stmt.query_map(rusqlite::params![ferme_id, date_from, date_to], |row| {
+            Ok(MeteoJour {
+                ferme_id: row.get(0)?,
+                date: row.get(1)?,
+                temperature_min: row.get(2)?,
+                temperature_max: row.get(3)?,
+                temperature_moyenne: row.get(4)?,
+                humidite_moyenne: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(releves)
+    }
+
+    /// Get the daily deaths of a ferme alongside the weather of the same day
+    ///
+    /// The calendar day of a suivi row is the bande entry date plus `age - 1` days.
+    pub fn get_mortalite_meteo(
+        conn: &Connection,
+        ferme_id: i64,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<MortaliteMeteoJour>, AppError> {
+        let mut stmt = conn.prepare(
+            "WITH deces_jour AS (
+                SELECT date(bd.date_entree, '+' || (sq.age - 1) || ' days') AS jour,
+                       SUM(COALESCE(sq.deces_par_jour, 0)) AS deces,
+                       SUM(b.quantite) AS effectif
+                FROM suivi_quotidien sq
+                JOIN semaines s ON sq.semaine_id = s.id
+                JOIN batiments b ON s.batiment_id = b.id
+                JOIN bandes bd ON b.bande_id = bd.id
+                WHERE bd.ferme_id = ?1
+                GROUP BY jour
+             )
+             SELECT d.jour, d.deces, d.effectif, m.temperature_max, m.humidite_moyenne
+             FROM deces_jour d
+             LEFT JOIN meteo_quotidienne m ON m.ferme_id = ?1 AND m.date = d.jour
+             WHERE (?2 IS NULL OR d.jour >= ?2)
+               AND (?3 IS NULL OR d.jour <= ?3)
+             ORDER BY d.jour"
+        )?;
+
+        let jours = stmt.query_map(rusqlite::params![ferme_id, date_from, date_to], |row| {
+            let deces: i64 = row.get(1)?;
+            let effectif: i64 = row.get(2)?;
+            let temperature_max: Option<f64> = row.get(3)?;
+            Ok(MortaliteMeteoJour {
+                date: row.get(0)?,
+                deces,
+                effectif,
+                mortalite_pourcentage: (effectif > 0).then(|| deces as f64 * 100.0 / effectif as f64),
+                temperature_max,
+                humidite_moyenne: row.get(4)?,
+                vague_de_chaleur: temperature_max.is_some_and(|t| t >= SEUIL_VAGUE_CHALEUR_C),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(jours)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::error::AppError;
+use crate::models::MetriqueCommande;
+use rusqlite::{params, Connection, Row};
+
+/// Repository for the local command metrics
+pub struct MetriqueRepository;
+
+impl MetriqueRepository {
+    /// Add measured executions to the totals of a command
+    ///
+    /// `mesures` holds the calls, failures and durations measured since the
+    /// last write; its average is ignored.
+    pub fn add(conn: &Connection, mesures: &MetriqueCommande) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO metriques_commandes
+                 (commande, appels, echecs, duree_totale_ms, duree_max_ms, derniere_execution)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(commande) DO UPDATE SET
+                 appels = appels + excluded.appels,
+                 echecs = echecs + excluded.echecs,
+                 duree_totale_ms = duree_totale_ms + excluded.duree_totale_ms,
+                 duree_max_ms = MAX(duree_max_ms, excluded.duree_max_ms),
+                 derniere_execution = COALESCE(excluded.derniere_execution, derniere_execution)",
+            params![
+                mesures.commande,
+                mesures.appels,
+                mesures.echecs,
+                mesures.duree_totale_ms,
+                mesures.duree_max_ms,
+                mesures.derniere_execution,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// All measured commands, slowest on average first
+    pub fn get_all(conn: &Connection) -> Result<Vec<MetriqueCommande>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT commande, appels, echecs, duree_totale_ms, duree_max_ms, derniere_execution
+             FROM metriques_commandes
+             ORDER BY CAST(duree_totale_ms AS REAL) / MAX(appels, 1) DESC, commande",
+        )?;
+        let metriques = stmt.query_map([], map_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(metriques)
+    }
+}
+
+fn map_row(row: &Row) -> rusqlite::Result<MetriqueCommande> {
+    let appels: i64 = row.get(1)?;
+    let duree_totale_ms: i64 = row.get(3)?;
+    Ok(MetriqueCommande {
+        commande: row.get(0)?,
+        appels,
+        echecs: row.get(2)?,
+        duree_totale_ms,
+        duree_moyenne_ms: if appels > 0 { duree_totale_ms as f64 / appels as f64 } else { 0.0 },
+        duree_max_ms: row.get(4)?,
+        derniere_execution: row.get(5)?,
+    })
+}
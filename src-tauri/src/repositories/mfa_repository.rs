@@ -0,0 +1,63 @@
+use crate::error::AppError;
+use crate::models::UserMfa;
+use rusqlite::{Connection, OptionalExtension};
+
+/// Repository for the users' second authentication factor
+pub struct MfaRepository;
+
+impl MfaRepository {
+    /// Get the second factor of a user
+    pub fn get(conn: &Connection, user_id: i64) -> Result<Option<UserMfa>, AppError> {
+        Ok(conn
+            .query_row(
+                "SELECT user_id, secret, enabled, last_used_step FROM user_mfa WHERE user_id = ?1",
+                [user_id],
+                |row| Ok(UserMfa {
+                    user_id: row.get(0)?,
+                    secret: row.get(1)?,
+                    enabled: row.get(2)?,
+                    last_used_step: row.get(3)?,
+                }),
+            )
+            .optional()?)
+    }
+
+    /// Start (or restart) an enrollment with a new secret, not yet enforced at login
+    pub fn save_pending(conn: &Connection, user_id: i64, secret: &str) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO user_mfa (user_id, secret, enabled) VALUES (?1, ?2, 0)
+             ON CONFLICT(user_id) DO UPDATE SET secret = excluded.secret, enabled = 0,
+                 last_used_step = NULL, enabled_at = NULL, created_at = CURRENT_TIMESTAMP",
+            rusqlite::params![user_id, secret],
+        )?;
+        Ok(())
+    }
+
+    /// Enable the second factor once the enrollment is confirmed
+    pub fn enable(conn: &Connection, user_id: i64, step: i64) -> Result<(), AppError> {
+        conn.execute(
+            "UPDATE user_mfa SET enabled = 1, enabled_at = CURRENT_TIMESTAMP, last_used_step = ?2 WHERE user_id = ?1",
+            rusqlite::params![user_id, step],
+        )?;
+        Ok(())
+    }
+
+    /// Record the time step of an accepted code so it cannot be replayed
+    ///
+    /// The step only moves forward: returns `false` when a code of this step (or a
+    /// later one) was already accepted, e.g. by a concurrent login with the same code.
+    pub fn record_step(conn: &Connection, user_id: i64, step: i64) -> Result<bool, AppError> {
+        let changed = conn.execute(
+            "UPDATE user_mfa SET last_used_step = ?2
+             WHERE user_id = ?1 AND (last_used_step IS NULL OR last_used_step < ?2)",
+            rusqlite::params![user_id, step],
+        )?;
+        Ok(changed == 1)
+    }
+
+    /// Remove the second factor of a user
+    pub fn delete(conn: &Connection, user_id: i64) -> Result<(), AppError> {
+        conn.execute("DELETE FROM user_mfa WHERE user_id = ?1", [user_id])?;
+        Ok(())
+    }
+}
@@ -2,6 +2,21 @@
 /// 
 /// This module contains all repository traits and implementations
 /// following the clean architecture principles specified in the instructions.
+///
+/// Repositories come in two forms, which differ in who owns the transaction:
+///
+/// * Stateful repositories (`FermeRepository`, `SemaineRepository`...) hold an
+///   `Arc<dyn Storage>` and implement a `*RepositoryTrait`. Each method is its
+///   own unit of work: reads use `get_connection`, writes open their own
+///   transaction through `Storage::write`. They cannot join a caller's transaction.
+/// * Stateless repositories (`PrixRepository`, `MfaRepository`, `TagRepository`...)
+///   are unit structs, or borrow a connection like `UserRepository<'a>`. Their
+///   functions take a `&Connection` and never open a connection or a transaction
+///   themselves: the caller (service or command) runs writes inside
+///   `storage.write(|tx| ...)` so that several of them commit or roll back together.
+///
+/// New repositories use the stateless form; writes through a pooled connection
+/// outside `Storage::write` bypass the write serialization of `DatabaseManager`.
 
 pub mod ferme_repository;
 pub mod personnel_repository;
@@ -14,6 +29,39 @@ pub mod user_repository;
 pub mod alimentation_repository;
 pub mod maladie_repository;
 pub mod poussin_repository;
+pub mod programme_alimentation_repository;
+pub mod analyse_repository;
+pub mod note_batiment_repository;
+pub mod message_repository;
+pub mod litiere_repository;
+pub mod vide_sanitaire_repository;
+pub mod immobilisation_repository;
+pub mod equipement_repository;
+pub mod activite_repository;
+pub mod audit_repository;
+pub mod parametre_repository;
+pub mod plan_ferme_repository;
+pub mod meteo_repository;
+pub mod capteur_repository;
+pub mod invitation_repository;
+pub mod mfa_repository;
+pub mod preference_repository;
+pub mod alerte_repository;
+pub mod abonnement_alerte_repository;
+pub mod archive_repository;
+pub mod export_repository;
+pub mod verrouillage_repository;
+pub mod plan_soins_repository;
+pub mod prix_repository;
+pub mod budget_repository;
+pub mod contrat_repository;
+pub mod rapport_repository;
+pub mod export_programme_repository;
+pub mod webhook_repository;
+pub mod metrique_repository;
+pub mod champ_personnalise_repository;
+pub mod tag_repository;
+pub mod filtre_enregistre_repository;
 
 // Re-export all repositories for easy access
 pub use ferme_repository::*;
@@ -27,3 +75,36 @@ pub use user_repository::*;
 pub use alimentation_repository::*;
 pub use maladie_repository::*;
 pub use poussin_repository::*;
+pub use programme_alimentation_repository::*;
+pub use analyse_repository::*;
+pub use note_batiment_repository::*;
+pub use message_repository::*;
+pub use litiere_repository::*;
+pub use vide_sanitaire_repository::*;
+pub use immobilisation_repository::*;
+pub use equipement_repository::*;
+pub use activite_repository::*;
+pub use audit_repository::*;
+pub use parametre_repository::*;
+pub use plan_ferme_repository::*;
+pub use meteo_repository::*;
+pub use capteur_repository::*;
+pub use invitation_repository::*;
+pub use mfa_repository::*;
+pub use preference_repository::*;
+pub use alerte_repository::*;
+pub use abonnement_alerte_repository::*;
+pub use archive_repository::*;
+pub use export_repository::*;
+pub use verrouillage_repository::*;
+pub use plan_soins_repository::*;
+pub use prix_repository::*;
+pub use budget_repository::*;
+pub use contrat_repository::*;
+pub use rapport_repository::*;
+pub use export_programme_repository::*;
+pub use webhook_repository::*;
+pub use metrique_repository::*;
+pub use champ_personnalise_repository::*;
+pub use tag_repository::*;
+pub use filtre_enregistre_repository::*;
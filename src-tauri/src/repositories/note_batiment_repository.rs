@@ -0,0 +1,85 @@
+use crate::error::AppError;
+use crate::models::{CreateNoteBatiment, NoteBatiment};
+use rusqlite::{params, Connection, Row};
+
+fn map_note_row(row: &Row) -> rusqlite::Result<NoteBatiment> {
+    Ok(NoteBatiment {
+        id: row.get(0)?,
+        batiment_id: row.get(1)?,
+        auteur: row.get(2)?,
+        contenu: row.get(3)?,
+        tag: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+/// Repository for the per-batiment notes timeline
+pub struct NoteBatimentRepository;
+
+impl NoteBatimentRepository {
+    /// Append a note to the timeline of a batiment
+    ///
+    /// Tags are stored lowercased so that filtering is case-insensitive.
+    pub fn create(
+        conn: &Connection,
+        note: &CreateNoteBatiment,
+    ) -> Result<NoteBatiment, AppError> {
+        let auteur = note.auteur.trim();
+        let contenu = note.contenu.trim();
+        let tag = note
+            .tag
+            .as_ref()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty());
+
+        if auteur.is_empty() {
+            return Err(AppError::validation_error("auteur", "L'auteur de la note est obligatoire"));
+        }
+        if contenu.is_empty() {
+            return Err(AppError::validation_error("contenu", "La note ne peut pas être vide"));
+        }
+
+        let batiment_exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM batiments WHERE id = ?1",
+            [note.batiment_id],
+            |row| row.get(0),
+        )?;
+        if batiment_exists == 0 {
+            return Err(AppError::not_found("Batiment", note.batiment_id));
+        }
+
+        conn.execute(
+            "INSERT INTO notes_batiment (batiment_id, auteur, contenu, tag) VALUES (?1, ?2, ?3, ?4)",
+            params![note.batiment_id, auteur, contenu, tag],
+        )?;
+
+        let note = conn.query_row(
+            "SELECT id, batiment_id, auteur, contenu, tag, created_at FROM notes_batiment WHERE id = ?1",
+            [conn.last_insert_rowid()],
+            map_note_row,
+        )?;
+
+        Ok(note)
+    }
+
+    /// List the notes of a batiment in chronological order, optionally filtered by tag
+    pub fn get_by_batiment(
+        conn: &Connection,
+        batiment_id: i64,
+        tag: Option<&str>,
+    ) -> Result<Vec<NoteBatiment>, AppError> {
+        let tag = tag.map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty());
+
+        let mut stmt = conn.prepare(
+            "SELECT id, batiment_id, auteur, contenu, tag, created_at
+             FROM notes_batiment
+             WHERE batiment_id = ?1 AND (?2 IS NULL OR tag = ?2)
+             ORDER BY created_at, id",
+        )?;
+
+        let notes = stmt.query_map(params![batiment_id, tag], map_note_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::error::AppError;
+use rusqlite::{Connection, OptionalExtension};
+
+/// Repository for application settings (key/value)
+pub struct ParametreRepository;
+
+impl ParametreRepository {
+    /// Get a setting value, `None` when it has never been set
+    pub fn get(
+        conn: &Connection,
+        cle: &str,
+    ) -> Result<Option<String>, AppError> {
+        let valeur = conn
+            .query_row("SELECT valeur FROM parametres WHERE cle = ?1", [cle], |row| row.get(0))
+            .optional()?;
+        Ok(valeur)
+    }
+
+    /// Create or replace a setting value
+    pub fn set(
+        conn: &Connection,
+        cle: &str,
+        valeur: &str,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO parametres (cle, valeur) VALUES (?1, ?2)
+             ON CONFLICT(cle) DO UPDATE SET valeur = excluded.valeur",
+            [cle, valeur],
+        )?;
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
-use crate::database::DatabaseManager;
+use crate::database::{erreur_mise_a_jour, nom_existe, normaliser_nom, Storage};
 use crate::error::{AppError, AppResult};
 use crate::models::{Personnel, CreatePersonnel, UpdatePersonnel, PaginatedPersonnel};
+use rusqlite::{Connection, OptionalExtension};
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
 
@@ -24,44 +25,56 @@ pub trait PersonnelRepositoryTrait: Send + Sync {
 
 /// Personnel repository implementation
 pub struct PersonnelRepository {
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl PersonnelRepository {
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
         Self { db }
     }
+
+    /// Check that a personnel exists (used to validate `batiments.personnel_id`)
+    pub fn exists(conn: &Connection, id: i64) -> AppResult<bool> {
+        let exists = conn.query_row("SELECT EXISTS(SELECT 1 FROM personnel WHERE id = ?1)", [id], |row| row.get(0))?;
+        Ok(exists)
+    }
 }
 
 impl PersonnelRepositoryTrait for PersonnelRepository {
     async fn create(&self, personnel: CreatePersonnel) -> AppResult<Personnel> {
-        let conn = self.db.get_connection()?;
+        self.db.write(|tx| {
+            // Names are unique regardless of case and accents
+            if nom_existe(tx, "personnel", &personnel.nom, None)? {
+                return Err(AppError::validation_error("nom", "Un membre du personnel avec ce nom existe déjà"));
+            }
         
-        conn.execute(
-            "INSERT INTO personnel (nom, telephone) VALUES (?1, ?2)",
-            [&personnel.nom, &personnel.telephone],
-        )?;
-
-        let id = conn.last_insert_rowid();
-
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM personnel WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([id], |row| {
-            Ok(row.get(0)?)
-        })?;
-
-        // Parse the timestamp using NaiveDateTime first, then convert to UTC
-        let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+            tx.execute(
+                "INSERT INTO personnel (nom, nom_normalise, telephone) VALUES (?1, ?2, ?3)",
+                [&personnel.nom, &normaliser_nom(&personnel.nom), &personnel.telephone],
+            )?;
+
+            let id = tx.last_insert_rowid();
+
+            // Get the created_at timestamp from the database
+            let mut stmt = tx.prepare("SELECT created_at FROM personnel WHERE id = ?1")?;
+            let created_at: String = stmt.query_row([id], |row| {
+                Ok(row.get(0)?)
             })?;
-        let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
 
-        Ok(Personnel {
-            id: Some(id),
-            nom: personnel.nom,
-            telephone: personnel.telephone,
-            created_at,
+            // Parse the timestamp using NaiveDateTime first, then convert to UTC
+            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?;
+            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
+
+            Ok(Personnel {
+                id: Some(id),
+                nom: personnel.nom,
+                telephone: personnel.telephone,
+                created_at,
+                version: 1,
+            })
         })
     }
 
@@ -112,7 +125,7 @@ impl PersonnelRepositoryTrait for PersonnelRepository {
         
         // Get paginated data
         let data_query = format!(
-            "SELECT id, nom, telephone, created_at FROM personnel {} ORDER BY nom LIMIT ? OFFSET ?",
+            "SELECT id, nom, telephone, created_at, version FROM personnel {} ORDER BY nom LIMIT ? OFFSET ?",
             where_clause
         );
         
@@ -139,6 +152,7 @@ impl PersonnelRepositoryTrait for PersonnelRepository {
                     nom: row.get(1)?,
                     telephone: row.get(2)?,
                     created_at,
+                    version: row.get(4)?,
                 })
             }
         )?.collect::<Result<Vec<_>, _>>()?;
@@ -155,57 +169,63 @@ impl PersonnelRepositoryTrait for PersonnelRepository {
     }
 
     async fn update(&self, personnel: UpdatePersonnel) -> AppResult<Personnel> {
-        let conn = self.db.get_connection()?;
+        self.db.write(|tx| {
+            if nom_existe(tx, "personnel", &personnel.nom, Some(personnel.id))? {
+                return Err(AppError::validation_error("nom", "Un autre membre du personnel avec ce nom existe déjà"));
+            }
         
-        let rows_affected = conn.execute(
-            "UPDATE personnel SET nom = ?1, telephone = ?2 WHERE id = ?3",
-            [&personnel.nom, &personnel.telephone, &personnel.id.to_string()],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Personnel", personnel.id));
-        }
-
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM personnel WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([personnel.id], |row| {
-            Ok(row.get(0)?)
-        })?;
-
-        // Parse the timestamp using NaiveDateTime first, then convert to UTC
-        let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
-            })?;
-        let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
+            // Refusée si le membre a été modifié depuis son chargement
+            let (created_at, version): (String, i64) = tx.query_row(
+                "UPDATE personnel SET nom = ?1, nom_normalise = ?2, telephone = ?3, version = version + 1
+                 WHERE id = ?4 AND (?5 IS NULL OR version = ?5)
+                 RETURNING created_at, version",
+                rusqlite::params![
+                    personnel.nom,
+                    normaliser_nom(&personnel.nom),
+                    personnel.telephone,
+                    personnel.id,
+                    personnel.version,
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional()?
+            .ok_or_else(|| erreur_mise_a_jour(tx, "personnel", "Personnel", personnel.id, personnel.version))?;
+
+            // Parse the timestamp using NaiveDateTime first, then convert to UTC
+            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?;
+            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
 
-        Ok(Personnel {
-            id: Some(personnel.id),
-            nom: personnel.nom,
-            telephone: personnel.telephone,
-            created_at,
+            Ok(Personnel {
+                id: Some(personnel.id),
+                nom: personnel.nom,
+                telephone: personnel.telephone,
+                created_at,
+                version,
+            })
         })
     }
 
     async fn delete(&self, id: i64) -> AppResult<()> {
-        let conn = self.db.get_connection()?;
-        
-        let rows_affected = conn.execute(
-            "DELETE FROM personnel WHERE id = ?1",
-            [id],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Personnel", id));
-        }
+        self.db.write(|tx| {
+            let rows_affected = tx.execute(
+                "DELETE FROM personnel WHERE id = ?1",
+                [id],
+            )?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Personnel", id));
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     async fn get_personnel_list(&self) -> AppResult<Vec<Personnel>> {
         let conn = self.db.get_connection()?;
         
-        let mut stmt = conn.prepare("SELECT id, nom, telephone, created_at FROM personnel ORDER BY nom")?;
+        let mut stmt = conn.prepare("SELECT id, nom, telephone, created_at, version FROM personnel ORDER BY nom")?;
         let personnel_list = stmt.query_map([], |row| {
             let created_at_str: String = row.get(3)?;
             
@@ -221,6 +241,7 @@ impl PersonnelRepositoryTrait for PersonnelRepository {
                 nom: row.get(1)?,
                 telephone: row.get(2)?,
                 created_at,
+                version: row.get(4)?,
             })
         })?.collect::<Result<Vec<_>, _>>()?;
         
@@ -0,0 +1,199 @@
+use crate::error::AppError;
+use crate::models::{IntervalleOccupation, OccupationBatiment, PositionBatiment, SEMAINES_SUIVI};
+use crate::repositories::BANDE_ACTIVE_CONDITION;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+/// Repository for the site map of a ferme (coordinates and building positions)
+pub struct PlanFermeRepository;
+
+impl PlanFermeRepository {
+    /// Get the name, number of buildings and coordinates of a ferme
+    pub fn get_ferme(
+        conn: &Connection,
+        ferme_id: i64,
+    ) -> Result<(String, i32, Option<f64>, Option<f64>), AppError> {
+        conn.query_row(
+            "SELECT nom, nbr_meuble, latitude, longitude FROM fermes WHERE id = ?1",
+            [ferme_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Ferme", ferme_id),
+            _ => AppError::from(e),
+        })
+    }
+
+    /// Update the coordinates of a ferme
+    pub fn update_coordonnees(
+        conn: &Connection,
+        ferme_id: i64,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute(
+            "UPDATE fermes SET latitude = ?1, longitude = ?2 WHERE id = ?3",
+            rusqlite::params![latitude, longitude, ferme_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("Ferme", ferme_id));
+        }
+
+        Ok(())
+    }
+
+    /// Get the building positions of a ferme
+    pub fn get_positions(
+        conn: &Connection,
+        ferme_id: i64,
+    ) -> Result<Vec<PositionBatiment>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT ferme_id, numero_batiment, plan_x, plan_y, largeur, hauteur, latitude, longitude
+             FROM positions_batiments
+             WHERE ferme_id = ?1
+             ORDER BY CAST(numero_batiment AS INTEGER), numero_batiment"
+        )?;
+
+        let positions = stmt.query_map([ferme_id], |row| {
+            Ok(PositionBatiment {
+                ferme_id: row.get(0)?,
+                numero_batiment: row.get(1)?,
+                plan_x: row.get(2)?,
+                plan_y: row.get(3)?,
+                largeur: row.get(4)?,
+                hauteur: row.get(5)?,
+                latitude: row.get(6)?,
+                longitude: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(positions)
+    }
+
+    /// Create or replace the position of a building
+    pub fn upsert_position(
+        conn: &Connection,
+        position: &PositionBatiment,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO positions_batiments
+                (ferme_id, numero_batiment, plan_x, plan_y, largeur, hauteur, latitude, longitude)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(ferme_id, numero_batiment) DO UPDATE SET
+                plan_x = excluded.plan_x, plan_y = excluded.plan_y,
+                largeur = excluded.largeur, hauteur = excluded.hauteur,
+                latitude = excluded.latitude, longitude = excluded.longitude",
+            rusqlite::params![
+                position.ferme_id,
+                position.numero_batiment,
+                position.plan_x,
+                position.plan_y,
+                position.largeur,
+                position.hauteur,
+                position.latitude,
+                position.longitude,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the buildings of the ferme used by a bande in progress, keyed by building number
+    ///
+    /// When two bandes in progress share a building number, the most recent comes first.
+    pub fn get_occupations(
+        conn: &Connection,
+        ferme_id: i64,
+    ) -> Result<Vec<(String, OccupationBatiment)>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT b.numero_batiment, b.id, bd.id,
+                    COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)),
+                    CAST(julianday(date('now', 'localtime')) - julianday(bd.date_entree) AS INTEGER) + 1,
+                    b.quantite,
+                    COALESCE((SELECT SUM(sq.deces_par_jour) FROM suivi_quotidien sq
+                              JOIN semaines s ON sq.semaine_id = s.id
+                              WHERE s.batiment_id = b.id), 0)
+             FROM batiments b
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE bd.ferme_id = ?1 AND {}
+             ORDER BY bd.date_entree DESC, bd.id DESC",
+            BANDE_ACTIVE_CONDITION
+        ))?;
+
+        let occupations = stmt.query_map([ferme_id], |row| {
+            let effectif_initial: i64 = row.get(5)?;
+            let deces: i64 = row.get(6)?;
+            Ok((
+                row.get(0)?,
+                OccupationBatiment {
+                    batiment_id: row.get(1)?,
+                    bande_id: row.get(2)?,
+                    numero_bande: row.get(3)?,
+                    age_jours: row.get(4)?,
+                    effectif_initial,
+                    deces,
+                    effectif_vivant: (effectif_initial - deces).max(0),
+                    mortalite_pourcentage: if effectif_initial > 0 {
+                        deces as f64 * 100.0 / effectif_initial as f64
+                    } else {
+                        0.0
+                    },
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(occupations)
+    }
+
+    /// Get the occupancy intervals of the buildings of a ferme overlapping a period, keyed by building number
+    ///
+    /// Intervals are sorted by building number, then by entry date.
+    pub fn get_intervalles_occupation(
+        conn: &Connection,
+        ferme_id: i64,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> Result<Vec<(String, IntervalleOccupation)>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT numero_batiment, batiment_id, bande_id, numero_bande, statut, date_debut, date_fin
+             FROM (
+                SELECT b.numero_batiment, b.id AS batiment_id, bd.id AS bande_id,
+                       COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)) AS numero_bande,
+                       bd.statut, bd.date_entree AS date_debut,
+                       MIN(
+                           date(bd.date_entree, '+' || (COALESCE(
+                               (SELECT MAX(s.numero_semaine) FROM semaines s WHERE s.batiment_id = b.id),
+                               ?4) * 7 - 1) || ' days'),
+                           COALESCE(MAX(bd.date_cloture, bd.date_entree), '9999-12-31')
+                       ) AS date_fin
+                FROM batiments b
+                JOIN bandes bd ON b.bande_id = bd.id
+                WHERE bd.ferme_id = ?1
+             )
+             WHERE date_debut <= ?3 AND date_fin >= ?2
+             ORDER BY CAST(numero_batiment AS INTEGER), numero_batiment, date_debut, bande_id",
+        )?;
+
+        let intervalles = stmt.query_map(
+            rusqlite::params![ferme_id, date_from, date_to, SEMAINES_SUIVI],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    IntervalleOccupation {
+                        batiment_id: row.get(1)?,
+                        bande_id: row.get(2)?,
+                        numero_bande: row.get(3)?,
+                        statut: row.get(4)?,
+                        date_debut: row.get(5)?,
+                        date_fin: row.get(6)?,
+                        chevauchement: false,
+                    },
+                ))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(intervalles)
+    }
+}
@@ -0,0 +1,251 @@
+use crate::error::AppError;
+use crate::models::plan_soins::{CreatePlanSoin, PlanSoin, UpdatePlanSoin};
+use crate::repositories::structurer_quantites_soins;
+use rusqlite::{params, Connection, Row};
+
+const SELECT_PLAN_SOIN: &str =
+    "SELECT ps.id, ps.poussin_id, ps.soin_id, s.nom, ps.jour_debut, ps.jour_fin, ps.quantite, ps.unit
+     FROM plan_soins ps
+     JOIN soins s ON ps.soin_id = s.id";
+
+fn map_plan_soin_row(row: &Row) -> rusqlite::Result<PlanSoin> {
+    Ok(PlanSoin {
+        id: Some(row.get(0)?),
+        poussin_id: row.get(1)?,
+        soin_id: row.get(2)?,
+        soin_nom: row.get(3)?,
+        jour_debut: row.get(4)?,
+        jour_fin: row.get(5)?,
+        quantite: row.get(6)?,
+        unit: row.get(7)?,
+    })
+}
+
+/// Trim an optional text field, an empty value being stored as NULL
+fn texte(valeur: &Option<String>) -> Option<&str> {
+    valeur.as_deref().map(str::trim).filter(|valeur| !valeur.is_empty())
+}
+
+/// Repository for managing daily treatment plans (soins per age range and poussin type)
+pub struct PlanSoinsRepository;
+
+impl PlanSoinsRepository {
+    /// Validate a plan entry and make sure the same soin is not planned twice on a day
+    fn validate_plan_soin(
+        conn: &Connection,
+        poussin_id: i64,
+        soin_id: i64,
+        jour_debut: i32,
+        jour_fin: i32,
+        exclude_id: Option<i64>,
+    ) -> Result<(), AppError> {
+        if jour_debut < 1 || jour_fin < jour_debut {
+            return Err(AppError::validation_error(
+                "jour_fin",
+                "Le soin doit commencer au jour 1 au plus tôt et finir après son début",
+            ));
+        }
+
+        let soin_exists: i64 = conn.query_row("SELECT COUNT(*) FROM soins WHERE id = ?1", [soin_id], |row| row.get(0))?;
+        if soin_exists == 0 {
+            return Err(AppError::validation_error("soin_id", "Le soin spécifié n'existe pas"));
+        }
+
+        let overlapping: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM plan_soins
+             WHERE poussin_id = ?1 AND soin_id = ?2 AND jour_debut <= ?4 AND jour_fin >= ?3
+               AND (?5 IS NULL OR id != ?5)",
+            params![poussin_id, soin_id, jour_debut, jour_fin, exclude_id],
+            |row| row.get(0),
+        )?;
+        if overlapping > 0 {
+            return Err(AppError::validation_error(
+                "jour_debut",
+                "Ce soin est déjà prévu sur une partie de ces jours",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Add a soin to the treatment plan of a poussin type
+    pub fn create(
+        conn: &Connection,
+        plan_soin: &CreatePlanSoin,
+    ) -> Result<PlanSoin, AppError> {
+        let poussin_exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM poussins WHERE id = ?1",
+            [plan_soin.poussin_id],
+            |row| row.get(0),
+        )?;
+        if poussin_exists == 0 {
+            return Err(AppError::validation_error("poussin_id", "Le poussin spécifié n'existe pas"));
+        }
+
+        Self::validate_plan_soin(
+            conn,
+            plan_soin.poussin_id,
+            plan_soin.soin_id,
+            plan_soin.jour_debut,
+            plan_soin.jour_fin,
+            None,
+        )?;
+
+        conn.execute(
+            "INSERT INTO plan_soins (poussin_id, soin_id, jour_debut, jour_fin, quantite, unit)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                plan_soin.poussin_id,
+                plan_soin.soin_id,
+                plan_soin.jour_debut,
+                plan_soin.jour_fin,
+                texte(&plan_soin.quantite),
+                texte(&plan_soin.unit),
+            ],
+        )?;
+
+        Self::get_by_id(conn, conn.last_insert_rowid())
+    }
+
+    /// Get a plan entry by id
+    pub fn get_by_id(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<PlanSoin, AppError> {
+        conn.query_row(&format!("{} WHERE ps.id = ?1", SELECT_PLAN_SOIN), [id], map_plan_soin_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Soin du plan", id),
+                _ => AppError::from(e),
+            })
+    }
+
+    /// Get the treatment plan of a poussin type, ordered by start day
+    pub fn get_by_poussin(
+        conn: &Connection,
+        poussin_id: i64,
+    ) -> Result<Vec<PlanSoin>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE ps.poussin_id = ?1 ORDER BY ps.jour_debut, s.nom",
+            SELECT_PLAN_SOIN
+        ))?;
+
+        let plan = stmt.query_map([poussin_id], map_plan_soin_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(plan)
+    }
+
+    /// Update a plan entry
+    ///
+    /// Soins already copied into the daily follow-up are left unchanged.
+    pub fn update(
+        conn: &Connection,
+        plan_soin: &UpdatePlanSoin,
+    ) -> Result<PlanSoin, AppError> {
+        let poussin_id: i64 = conn.query_row(
+            "SELECT poussin_id FROM plan_soins WHERE id = ?1",
+            [plan_soin.id],
+            |row| row.get(0),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Soin du plan", plan_soin.id),
+            _ => AppError::from(e),
+        })?;
+
+        Self::validate_plan_soin(
+            conn,
+            poussin_id,
+            plan_soin.soin_id,
+            plan_soin.jour_debut,
+            plan_soin.jour_fin,
+            Some(plan_soin.id),
+        )?;
+
+        conn.execute(
+            "UPDATE plan_soins
+             SET soin_id = ?1, jour_debut = ?2, jour_fin = ?3, quantite = ?4, unit = ?5
+             WHERE id = ?6",
+            params![
+                plan_soin.soin_id,
+                plan_soin.jour_debut,
+                plan_soin.jour_fin,
+                texte(&plan_soin.quantite),
+                texte(&plan_soin.unit),
+                plan_soin.id,
+            ],
+        )?;
+
+        Self::get_by_id(conn, plan_soin.id)
+    }
+
+    /// Delete a plan entry
+    pub fn delete(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute("DELETE FROM plan_soins WHERE id = ?1", [id])?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("Soin du plan", id));
+        }
+
+        Ok(())
+    }
+
+    /// Copy the treatment plan of the batiment's poussin type into the days of a new semaine
+    ///
+    /// Missing suivi_quotidien days are created; a soin already recorded on a day
+    /// is not added twice. Returns the number of soins added.
+    pub fn apply_to_semaine(
+        conn: &Connection,
+        semaine_id: i64,
+        created_by: Option<i64>,
+    ) -> Result<usize, AppError> {
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE jours(age, fin) AS (
+                 SELECT (numero_semaine - 1) * 7 + 1, numero_semaine * 7 FROM semaines WHERE id = ?1
+                 UNION ALL
+                 SELECT age + 1, fin FROM jours WHERE age < fin
+             )
+             SELECT j.age, ps.soin_id, ps.quantite, COALESCE(ps.unit, so.unit)
+             FROM semaines s
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN plan_soins ps ON ps.poussin_id = b.poussin_id
+             JOIN soins so ON ps.soin_id = so.id
+             JOIN jours j ON j.age BETWEEN ps.jour_debut AND ps.jour_fin
+             WHERE s.id = ?1
+             ORDER BY j.age, ps.jour_debut, ps.id",
+        )?;
+        let soins = stmt.query_map([semaine_id], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut ajoutes = 0;
+        for (age, soin_id, quantite, unit) in soins {
+            conn.execute(
+                "INSERT INTO suivi_quotidien (semaine_id, age, created_at, updated_at, created_by)
+                 VALUES (?1, ?2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?3)
+                 ON CONFLICT(semaine_id, age) DO NOTHING",
+                params![semaine_id, age, created_by],
+            )?;
+            let ajoute = conn.execute(
+                "INSERT INTO suivi_soins (suivi_id, soin_id, quantite, unit)
+                 SELECT sq.id, ?3, ?4, ?5 FROM suivi_quotidien sq
+                 WHERE sq.semaine_id = ?1 AND sq.age = ?2
+                   AND NOT EXISTS (SELECT 1 FROM suivi_soins ss WHERE ss.suivi_id = sq.id AND ss.soin_id = ?3)",
+                params![semaine_id, age, soin_id, quantite, unit],
+            )?;
+            if ajoute > 0 {
+                structurer_quantites_soins(conn, "ss.id = ?1", &[&conn.last_insert_rowid()])?;
+            }
+            ajoutes += ajoute;
+        }
+
+        Ok(ajoutes)
+    }
+}
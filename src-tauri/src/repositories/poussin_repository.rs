@@ -1,6 +1,7 @@
-use crate::database::DatabaseManager;
+use crate::database::{nom_existe, normaliser_nom, Storage};
 use crate::error::{AppError, AppResult};
 use crate::models::{Poussin, CreatePoussin, UpdatePoussin, PaginatedPoussin};
+use rusqlite::Connection;
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
 
@@ -24,43 +25,54 @@ pub trait PoussinRepositoryTrait: Send + Sync {
 
 /// Poussin repository implementation
 pub struct PoussinRepository {
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl PoussinRepository {
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
         Self { db }
     }
+
+    /// Check that a poussin exists (used to validate `batiments.poussin_id`)
+    pub fn exists(conn: &Connection, id: i64) -> AppResult<bool> {
+        let exists = conn.query_row("SELECT EXISTS(SELECT 1 FROM poussins WHERE id = ?1)", [id], |row| row.get(0))?;
+        Ok(exists)
+    }
 }
 
 impl PoussinRepositoryTrait for PoussinRepository {
     async fn create(&self, poussin: CreatePoussin) -> AppResult<Poussin> {
-        let conn = self.db.get_connection()?;
+        self.db.write(|tx| {
+            // Names are unique regardless of case and accents
+            if nom_existe(tx, "poussins", &poussin.nom, None)? {
+                return Err(AppError::validation_error("nom", "Un poussin avec ce nom existe déjà"));
+            }
         
-        conn.execute(
-            "INSERT INTO poussins (nom) VALUES (?1)",
-            [&poussin.nom],
-        )?;
-
-        let id = conn.last_insert_rowid();
-
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM poussins WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([id], |row| {
-            Ok(row.get(0)?)
-        })?;
-
-        // Parse the timestamp using NaiveDateTime first, then convert to UTC
-        let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+            tx.execute(
+                "INSERT INTO poussins (nom, nom_normalise) VALUES (?1, ?2)",
+                [&poussin.nom, &normaliser_nom(&poussin.nom)],
+            )?;
+
+            let id = tx.last_insert_rowid();
+
+            // Get the created_at timestamp from the database
+            let mut stmt = tx.prepare("SELECT created_at FROM poussins WHERE id = ?1")?;
+            let created_at: String = stmt.query_row([id], |row| {
+                Ok(row.get(0)?)
             })?;
-        let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
 
-        Ok(Poussin {
-            id: Some(id),
-            nom: poussin.nom,
-            created_at,
+            // Parse the timestamp using NaiveDateTime first, then convert to UTC
+            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?;
+            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
+
+            Ok(Poussin {
+                id: Some(id),
+                nom: poussin.nom,
+                created_at,
+            })
         })
     }
 
@@ -145,50 +157,54 @@ impl PoussinRepositoryTrait for PoussinRepository {
     }
 
     async fn update(&self, poussin: UpdatePoussin) -> AppResult<Poussin> {
-        let conn = self.db.get_connection()?;
+        self.db.write(|tx| {
+            if nom_existe(tx, "poussins", &poussin.nom, Some(poussin.id))? {
+                return Err(AppError::validation_error("nom", "Un autre poussin avec ce nom existe déjà"));
+            }
         
-        let rows_affected = conn.execute(
-            "UPDATE poussins SET nom = ?1 WHERE id = ?2",
-            [&poussin.nom, &poussin.id.to_string()],
-        )?;
+            let rows_affected = tx.execute(
+                "UPDATE poussins SET nom = ?1, nom_normalise = ?2 WHERE id = ?3",
+                [&poussin.nom, &normaliser_nom(&poussin.nom), &poussin.id.to_string()],
+            )?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Poussin", poussin.id));
-        }
-
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM poussins WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([poussin.id], |row| {
-            Ok(row.get(0)?)
-        })?;
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Poussin", poussin.id));
+            }
 
-        // Parse the timestamp using NaiveDateTime first, then convert to UTC
-        let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+            // Get the created_at timestamp from the database
+            let mut stmt = tx.prepare("SELECT created_at FROM poussins WHERE id = ?1")?;
+            let created_at: String = stmt.query_row([poussin.id], |row| {
+                Ok(row.get(0)?)
             })?;
-        let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
 
-        Ok(Poussin {
-            id: Some(poussin.id),
-            nom: poussin.nom,
-            created_at,
+            // Parse the timestamp using NaiveDateTime first, then convert to UTC
+            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?;
+            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
+
+            Ok(Poussin {
+                id: Some(poussin.id),
+                nom: poussin.nom,
+                created_at,
+            })
         })
     }
 
     async fn delete(&self, id: i64) -> AppResult<()> {
-        let conn = self.db.get_connection()?;
-        
-        let rows_affected = conn.execute(
-            "DELETE FROM poussins WHERE id = ?1",
-            [id],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Poussin", id));
-        }
+        self.db.write(|tx| {
+            let rows_affected = tx.execute(
+                "DELETE FROM poussins WHERE id = ?1",
+                [id],
+            )?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Poussin", id));
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     async fn get_poussin_list(&self) -> AppResult<Vec<Poussin>> {
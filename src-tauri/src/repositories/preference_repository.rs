@@ -0,0 +1,32 @@
+use crate::error::AppError;
+use rusqlite::{Connection, OptionalExtension};
+
+/// Repository for the users' UI preferences (one JSON document per user)
+pub struct PreferenceRepository;
+
+impl PreferenceRepository {
+    /// Get the preferences document of a user, `None` when nothing was saved yet
+    pub fn get(
+        conn: &Connection,
+        user_id: i64,
+    ) -> Result<Option<String>, AppError> {
+        let preferences = conn
+            .query_row("SELECT preferences FROM user_preferences WHERE user_id = ?1", [user_id], |row| row.get(0))
+            .optional()?;
+        Ok(preferences)
+    }
+
+    /// Create or replace the preferences document of a user
+    pub fn set(
+        conn: &Connection,
+        user_id: i64,
+        preferences: &str,
+    ) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO user_preferences (user_id, preferences, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+             ON CONFLICT(user_id) DO UPDATE SET preferences = excluded.preferences, updated_at = CURRENT_TIMESTAMP",
+            rusqlite::params![user_id, preferences],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error::AppError;
+use crate::models::prix::{
+    trouver_devise, CreatePrixHistorique, Devise, PrixHistorique, ARTICLE_ALIMENT, ARTICLE_POUSSIN, DEVISES,
+    PARAMETRE_DEVISE,
+};
+use crate::models::{SaisieTva, MODES_TVA, MODE_TVA_HT, MODE_TVA_TTC};
+use crate::repositories::ParametreRepository;
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+fn map_prix_row(row: &Row) -> rusqlite::Result<PrixHistorique> {
+    Ok(PrixHistorique {
+        id: row.get(0)?,
+        article: row.get(1)?,
+        poussin_id: row.get(2)?,
+        prix_unitaire: row.get(3)?,
+        devise: row.get(4)?,
+        date_debut: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+/// VAT of a purchase after validation, with its amount before tax
+pub struct TvaAchat {
+    /// Amount (or unit price) before tax, `None` when no amount was entered
+    pub montant_ht: Option<f64>,
+    pub taux_tva: Option<f64>,
+    /// VAT amount entered explicitly; otherwise it is computed from the rate when read
+    pub montant_tva: Option<f64>,
+}
+
+/// Validate the VAT entered with a purchase and convert its amount to before tax
+///
+/// An amount entered tax included needs a VAT rate.
+pub fn ventiler_tva(montant: Option<f64>, tva: &SaisieTva) -> Result<TvaAchat, AppError> {
+    let mode = tva.mode_tva.as_deref().map(|mode| mode.trim().to_lowercase());
+    let mode = mode.as_deref().unwrap_or(MODE_TVA_HT);
+    if !MODES_TVA.contains(&mode) {
+        return Err(AppError::validation_error(
+            "mode_tva",
+            &format!("Le mode de saisie doit être {} ou {}", MODE_TVA_HT, MODE_TVA_TTC),
+        ));
+    }
+    if tva.taux_tva.is_some_and(|taux| !(0.0..=100.0).contains(&taux)) {
+        return Err(AppError::validation_error("taux_tva", "Le taux de TVA doit être compris entre 0 et 100 %"));
+    }
+    if tva.montant_tva.is_some_and(|montant| !montant.is_finite()) {
+        return Err(AppError::validation_error("montant_tva", "Le montant de TVA est invalide"));
+    }
+
+    let montant_ht = match (mode, tva.taux_tva) {
+        (MODE_TVA_TTC, Some(taux)) => montant.map(|montant| montant / (1.0 + taux / 100.0)),
+        (MODE_TVA_TTC, None) => {
+            return Err(AppError::validation_error("taux_tva", "Un montant TTC demande un taux de TVA"));
+        }
+        _ => montant,
+    };
+
+    Ok(TvaAchat { montant_ht, taux_tva: tva.taux_tva, montant_tva: tva.montant_tva })
+}
+
+/// Repository for the price history of feed (per kg) and chicks (per bird and poussin type)
+///
+/// Deliveries and batiments copy the price in effect when they are recorded,
+/// so later price changes do not alter the cost of past bandes. Each price
+/// keeps the currency configured when it was recorded.
+pub struct PrixRepository;
+
+impl PrixRepository {
+    /// Record a new price for an article from `date_debut`
+    pub fn create(
+        conn: &Connection,
+        prix: &CreatePrixHistorique,
+    ) -> Result<PrixHistorique, AppError> {
+        let poussin_id = match prix.article.as_str() {
+            ARTICLE_ALIMENT => None,
+            ARTICLE_POUSSIN => {
+                let poussin_id = prix.poussin_id.ok_or_else(|| {
+                    AppError::validation_error("poussin_id", "Le type de poussin est obligatoire pour un prix de poussin")
+                })?;
+                let poussin_exists: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM poussins WHERE id = ?1",
+                    [poussin_id],
+                    |row| row.get(0),
+                )?;
+                if poussin_exists == 0 {
+                    return Err(AppError::validation_error("poussin_id", "Le poussin spécifié n'existe pas"));
+                }
+                Some(poussin_id)
+            }
+            _ => {
+                return Err(AppError::validation_error(
+                    "article",
+                    &format!("L'article doit être {} ou {}", ARTICLE_ALIMENT, ARTICLE_POUSSIN),
+                ))
+            }
+        };
+
+        if !prix.prix_unitaire.is_finite() || prix.prix_unitaire < 0.0 {
+            return Err(AppError::validation_error("prix_unitaire", "Le prix ne peut pas être négatif"));
+        }
+        let date_debut = NaiveDate::parse_from_str(prix.date_debut.trim(), "%Y-%m-%d")
+            .map_err(|_| AppError::validation_error("date_debut", "La date doit être au format AAAA-MM-JJ"))?;
+
+        let devise = Self::get_devise(conn)?;
+        conn.execute(
+            "INSERT INTO historique_prix (article, poussin_id, prix_unitaire, devise, date_debut)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![prix.article, poussin_id, prix.prix_unitaire, devise.code, date_debut.to_string()],
+        )?;
+
+        let prix = conn.query_row(
+            "SELECT id, article, poussin_id, prix_unitaire, devise, date_debut, created_at
+             FROM historique_prix WHERE id = ?1",
+            [conn.last_insert_rowid()],
+            map_prix_row,
+        )?;
+        Ok(prix)
+    }
+
+    /// Get the price history of an article, most recent first
+    pub fn get_historique(
+        conn: &Connection,
+        article: &str,
+        poussin_id: Option<i64>,
+    ) -> Result<Vec<PrixHistorique>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT id, article, poussin_id, prix_unitaire, devise, date_debut, created_at
+             FROM historique_prix
+             WHERE article = ?1 AND poussin_id IS ?2
+             ORDER BY date_debut DESC, id DESC",
+        )?;
+
+        let historique = stmt.query_map(params![article, poussin_id], map_prix_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(historique)
+    }
+
+    /// Delete a price of the history
+    ///
+    /// Prices already copied to deliveries and batiments are kept.
+    pub fn delete(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute("DELETE FROM historique_prix WHERE id = ?1", [id])?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("Prix", id));
+        }
+
+        Ok(())
+    }
+
+    /// Price of an article in effect on a date (`date` is YYYY-MM-DD or a datetime), if any
+    ///
+    /// Only prices recorded in the current currency apply: after a currency
+    /// change, older prices are not copied under the new currency.
+    pub fn prix_en_vigueur(
+        conn: &Connection,
+        article: &str,
+        poussin_id: Option<i64>,
+        date: &str,
+    ) -> Result<Option<f64>, AppError> {
+        let devise = Self::get_devise(conn)?;
+        let prix = conn.query_row(
+            "SELECT prix_unitaire FROM historique_prix
+             WHERE article = ?1 AND poussin_id IS ?2 AND date_debut <= date(?3) AND devise = ?4
+             ORDER BY date_debut DESC, id DESC
+             LIMIT 1",
+            params![article, poussin_id, date, devise.code],
+            |row| row.get(0),
+        ).optional()?;
+
+        Ok(prix)
+    }
+
+    /// Currency of the prices entered from now on
+    pub fn get_devise(conn: &Connection) -> Result<Devise, AppError> {
+        let devise = ParametreRepository::get(conn, PARAMETRE_DEVISE)?
+            .and_then(|code| trouver_devise(&code))
+            .unwrap_or(DEVISES[0]);
+        Ok(devise)
+    }
+
+    /// Set the currency of the prices entered from now on
+    ///
+    /// Prices already recorded keep their currency.
+    pub fn set_devise(conn: &Connection, code: &str) -> Result<Devise, AppError> {
+        let devise = trouver_devise(code).ok_or_else(|| {
+            let codes: Vec<&str> = DEVISES.iter().map(|devise| devise.code).collect();
+            AppError::validation_error("devise", &format!("La devise doit être l'une de: {}", codes.join(", ")))
+        })?;
+        ParametreRepository::set(conn, PARAMETRE_DEVISE, devise.code)?;
+        Ok(devise)
+    }
+}
@@ -0,0 +1,462 @@
+use crate::error::AppError;
+use crate::models::programme_alimentation::{
+    BesoinAlimentSemaine, ConformiteAlimentationJour, ConformitePhase, ConformiteProgrammeAlimentation,
+    CreatePhaseAlimentation, PhaseAlimentation, PrevisionAliment, PrevisionAlimentBatiment, UpdatePhaseAlimentation,
+    JOURS_REFERENCE_PREVISION, KG_PAR_SACHET, PARAMETRE_KG_PAR_SACHET, PLAGE_KG_PAR_SACHET,
+};
+use crate::models::SEMAINES_SUIVI;
+use crate::repositories::ParametreRepository;
+use chrono::{Days, NaiveDate};
+use rusqlite::{params, Connection, Row};
+use std::collections::BTreeMap;
+
+fn map_phase_row(row: &Row) -> rusqlite::Result<PhaseAlimentation> {
+    Ok(PhaseAlimentation {
+        id: Some(row.get(0)?),
+        poussin_id: row.get(1)?,
+        nom_phase: row.get(2)?,
+        jour_debut: row.get(3)?,
+        jour_fin: row.get(4)?,
+        grammes_par_sujet: row.get(5)?,
+    })
+}
+
+/// Écart en % du réel par rapport à l'attendu
+fn ecart(reel: f64, attendu: f64) -> Option<f64> {
+    if attendu > 0.0 {
+        Some((reel - attendu) / attendu * 100.0)
+    } else {
+        None
+    }
+}
+
+/// Repository for managing feed programs (phases per poussin type)
+pub struct ProgrammeAlimentationRepository;
+
+impl ProgrammeAlimentationRepository {
+    /// Weight of a feed sachet in kg, `KG_PAR_SACHET` until the setting is defined
+    pub fn get_kg_par_sachet(conn: &Connection) -> Result<f64, AppError> {
+        let kg_par_sachet = ParametreRepository::get(conn, PARAMETRE_KG_PAR_SACHET)?
+            .and_then(|valeur| valeur.parse::<f64>().ok())
+            .filter(|kg| PLAGE_KG_PAR_SACHET.contains(kg))
+            .unwrap_or(KG_PAR_SACHET);
+        Ok(kg_par_sachet)
+    }
+
+    /// Set the weight of a feed sachet in kg
+    ///
+    /// Only the daily feed entered afterwards is converted with the new weight:
+    /// the kg already deducted from each bande's `alimentation_contour` are kept.
+    pub fn set_kg_par_sachet(conn: &Connection, kg_par_sachet: f64) -> Result<f64, AppError> {
+        if !PLAGE_KG_PAR_SACHET.contains(&kg_par_sachet) {
+            return Err(AppError::validation_error(
+                "kg_par_sachet",
+                &format!(
+                    "Le poids d'un sachet doit être compris entre {} et {} kg",
+                    PLAGE_KG_PAR_SACHET.start(),
+                    PLAGE_KG_PAR_SACHET.end()
+                ),
+            ));
+        }
+        ParametreRepository::set(conn, PARAMETRE_KG_PAR_SACHET, &kg_par_sachet.to_string())?;
+        Self::get_kg_par_sachet(conn)
+    }
+
+    /// Validate a phase and make sure it does not overlap another phase of the same program
+    fn validate_phase(
+        conn: &Connection,
+        poussin_id: i64,
+        nom_phase: &str,
+        jour_debut: i32,
+        jour_fin: i32,
+        grammes_par_sujet: f64,
+        exclude_id: Option<i64>,
+    ) -> Result<(), AppError> {
+        if nom_phase.trim().is_empty() {
+            return Err(AppError::validation_error("nom_phase", "Le nom de la phase est obligatoire"));
+        }
+        if jour_debut < 1 || jour_fin < jour_debut {
+            return Err(AppError::validation_error(
+                "jour_fin",
+                "La phase doit commencer au jour 1 au plus tôt et finir après son début",
+            ));
+        }
+        if grammes_par_sujet < 0.0 {
+            return Err(AppError::validation_error(
+                "grammes_par_sujet",
+                "La consommation attendue ne peut pas être négative",
+            ));
+        }
+
+        let overlapping: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM phases_alimentation
+             WHERE poussin_id = ?1 AND jour_debut <= ?3 AND jour_fin >= ?2
+               AND (?4 IS NULL OR id != ?4)",
+            params![poussin_id, jour_debut, jour_fin, exclude_id],
+            |row| row.get(0),
+        )?;
+        if overlapping > 0 {
+            return Err(AppError::validation_error(
+                "jour_debut",
+                "La phase chevauche une autre phase du programme",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Add a phase to the feed program of a poussin type
+    ///
+    /// Without `jour_debut`, the phase starts the day after the last existing phase.
+    pub fn create(
+        conn: &Connection,
+        phase: &CreatePhaseAlimentation,
+    ) -> Result<PhaseAlimentation, AppError> {
+        let poussin_exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM poussins WHERE id = ?1",
+            [phase.poussin_id],
+            |row| row.get(0),
+        )?;
+        if poussin_exists == 0 {
+            return Err(AppError::validation_error("poussin_id", "Le poussin spécifié n'existe pas"));
+        }
+
+        let jour_debut = match phase.jour_debut {
+            Some(jour) => jour,
+            None => conn.query_row(
+                "SELECT COALESCE(MAX(jour_fin), 0) + 1 FROM phases_alimentation WHERE poussin_id = ?1",
+                [phase.poussin_id],
+                |row| row.get(0),
+            )?,
+        };
+        let nom_phase = phase.nom_phase.trim();
+
+        Self::validate_phase(
+            conn,
+            phase.poussin_id,
+            nom_phase,
+            jour_debut,
+            phase.jour_fin,
+            phase.grammes_par_sujet,
+            None,
+        )?;
+
+        conn.execute(
+            "INSERT INTO phases_alimentation (poussin_id, nom_phase, jour_debut, jour_fin, grammes_par_sujet)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![phase.poussin_id, nom_phase, jour_debut, phase.jour_fin, phase.grammes_par_sujet],
+        )?;
+
+        Ok(PhaseAlimentation {
+            id: Some(conn.last_insert_rowid()),
+            poussin_id: phase.poussin_id,
+            nom_phase: nom_phase.to_string(),
+            jour_debut,
+            jour_fin: phase.jour_fin,
+            grammes_par_sujet: phase.grammes_par_sujet,
+        })
+    }
+
+    /// Get the feed program of a poussin type, ordered by start day
+    pub fn get_by_poussin(
+        conn: &Connection,
+        poussin_id: i64,
+    ) -> Result<Vec<PhaseAlimentation>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT id, poussin_id, nom_phase, jour_debut, jour_fin, grammes_par_sujet
+             FROM phases_alimentation
+             WHERE poussin_id = ?1
+             ORDER BY jour_debut",
+        )?;
+
+        let phases = stmt.query_map([poussin_id], map_phase_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(phases)
+    }
+
+    /// Update a phase of a feed program
+    pub fn update(
+        conn: &Connection,
+        phase: &UpdatePhaseAlimentation,
+    ) -> Result<PhaseAlimentation, AppError> {
+        let poussin_id: i64 = conn.query_row(
+            "SELECT poussin_id FROM phases_alimentation WHERE id = ?1",
+            [phase.id],
+            |row| row.get(0),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Phase d'alimentation", phase.id),
+            _ => AppError::from(e),
+        })?;
+        let nom_phase = phase.nom_phase.trim();
+
+        Self::validate_phase(
+            conn,
+            poussin_id,
+            nom_phase,
+            phase.jour_debut,
+            phase.jour_fin,
+            phase.grammes_par_sujet,
+            Some(phase.id),
+        )?;
+
+        conn.execute(
+            "UPDATE phases_alimentation
+             SET nom_phase = ?1, jour_debut = ?2, jour_fin = ?3, grammes_par_sujet = ?4
+             WHERE id = ?5",
+            params![nom_phase, phase.jour_debut, phase.jour_fin, phase.grammes_par_sujet, phase.id],
+        )?;
+
+        Ok(PhaseAlimentation {
+            id: Some(phase.id),
+            poussin_id,
+            nom_phase: nom_phase.to_string(),
+            jour_debut: phase.jour_debut,
+            jour_fin: phase.jour_fin,
+            grammes_par_sujet: phase.grammes_par_sujet,
+        })
+    }
+
+    /// Delete a phase of a feed program
+    pub fn delete(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute("DELETE FROM phases_alimentation WHERE id = ?1", [id])?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("Phase d'alimentation", id));
+        }
+
+        Ok(())
+    }
+
+    /// Compare the daily feed of a batiment against the program of its poussin type
+    ///
+    /// Daily consumption is entered in sachets; it is converted to grams per live
+    /// bird, the flock size being the initial quantity minus the deaths and culls
+    /// recorded on the previous days. Only days with a feed entry are reported.
+    pub fn get_compliance(
+        conn: &Connection,
+        batiment_id: i64,
+    ) -> Result<ConformiteProgrammeAlimentation, AppError> {
+        let (poussin_id, quantite): (i64, i64) = conn.query_row(
+            "SELECT poussin_id, quantite FROM batiments WHERE id = ?1",
+            [batiment_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Batiment", batiment_id),
+            _ => AppError::from(e),
+        })?;
+
+        let programme = Self::get_by_poussin(conn, poussin_id)?;
+        let kg_par_sachet = Self::get_kg_par_sachet(conn)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT sq.age, COALESCE(sq.deces_par_jour, 0) + COALESCE(sq.elimines_par_jour, 0),
+                    sq.alimentation_par_jour
+             FROM suivi_quotidien sq
+             JOIN semaines s ON sq.semaine_id = s.id
+             WHERE s.batiment_id = ?1
+             ORDER BY sq.age",
+        )?;
+        let suivis = stmt.query_map([batiment_id], |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, i64>(1)?, row.get::<_, Option<f64>>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut jours = Vec::new();
+        let mut effectif = quantite;
+
+        for (age, sorties, alimentation) in suivis {
+            if let Some(sachets) = alimentation {
+                let phase = programme.iter().find(|p| p.jour_debut <= age && age <= p.jour_fin);
+                let grammes_attendus = phase.map(|p| p.grammes_par_sujet);
+                let grammes_reels = if effectif > 0 {
+                    Some(sachets * kg_par_sachet * 1000.0 / effectif as f64)
+                } else {
+                    None
+                };
+                let ecart_pourcentage = match (grammes_reels, grammes_attendus) {
+                    (Some(reel), Some(attendu)) => ecart(reel, attendu),
+                    _ => None,
+                };
+
+                jours.push(ConformiteAlimentationJour {
+                    age,
+                    nom_phase: phase.map(|p| p.nom_phase.clone()),
+                    effectif,
+                    grammes_attendus,
+                    grammes_reels,
+                    ecart_pourcentage,
+                });
+            }
+
+            effectif -= sorties;
+        }
+
+        let phases = programme
+            .iter()
+            .map(|phase| {
+                let reels: Vec<f64> = jours
+                    .iter()
+                    .filter(|j| phase.jour_debut <= j.age && j.age <= phase.jour_fin)
+                    .filter_map(|j| j.grammes_reels)
+                    .collect();
+                let grammes_reels_moyens = if reels.is_empty() {
+                    None
+                } else {
+                    Some(reels.iter().sum::<f64>() / reels.len() as f64)
+                };
+
+                ConformitePhase {
+                    nom_phase: phase.nom_phase.clone(),
+                    jour_debut: phase.jour_debut,
+                    jour_fin: phase.jour_fin,
+                    grammes_attendus: phase.grammes_par_sujet,
+                    grammes_reels_moyens,
+                    ecart_pourcentage: grammes_reels_moyens.and_then(|reel| ecart(reel, phase.grammes_par_sujet)),
+                    jours_saisis: reels.len() as u32,
+                }
+            })
+            .collect();
+
+        Ok(ConformiteProgrammeAlimentation {
+            batiment_id,
+            poussin_id,
+            phases,
+            jours,
+        })
+    }
+
+    /// Project the feed needed by a bande until its catch date and compare it to its stock
+    ///
+    /// For each batiment, the program is scaled by the ratio between actual and
+    /// expected intake over the last `JOURS_REFERENCE_PREVISION` entered days;
+    /// days outside the program use the current intake per bird. The catch is
+    /// planned at the end of the last week (at least `SEMAINES_SUIVI` weeks).
+    pub fn forecast_requirements(
+        conn: &Connection,
+        bande_id: i64,
+    ) -> Result<PrevisionAliment, AppError> {
+        let (date_entree, stock_kg): (NaiveDate, f64) = conn.query_row(
+            "SELECT date_entree, alimentation_contour FROM bandes WHERE id = ?1",
+            [bande_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Bande", bande_id),
+            _ => AppError::from(e),
+        })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT b.id, b.numero_batiment, b.poussin_id,
+                    b.quantite - COALESCE((
+                        SELECT SUM(COALESCE(sq.deces_par_jour, 0) + COALESCE(sq.elimines_par_jour, 0))
+                        FROM suivi_quotidien sq JOIN semaines s ON sq.semaine_id = s.id
+                        WHERE s.batiment_id = b.id
+                    ), 0),
+                    MAX(?2, COALESCE((SELECT MAX(numero_semaine) FROM semaines WHERE batiment_id = b.id), 0)) * 7
+             FROM batiments b
+             WHERE b.bande_id = ?1
+             ORDER BY b.numero_batiment",
+        )?;
+        let batiments = stmt.query_map(params![bande_id, SEMAINES_SUIVI], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i32>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut besoins_par_age: BTreeMap<i32, f64> = BTreeMap::new();
+        let mut previsions = Vec::new();
+
+        for (batiment_id, numero_batiment, poussin_id, effectif, age_enlevement) in batiments {
+            let programme = Self::get_by_poussin(conn, poussin_id)?;
+            let conformite = Self::get_compliance(conn, batiment_id)?;
+            let recents = &conformite.jours[conformite.jours.len().saturating_sub(JOURS_REFERENCE_PREVISION)..];
+
+            let moyenne = |valeurs: Vec<f64>| {
+                (!valeurs.is_empty()).then(|| valeurs.iter().sum::<f64>() / valeurs.len() as f64)
+            };
+            let facteur_consommation = moyenne(
+                recents
+                    .iter()
+                    .filter_map(|j| match (j.grammes_reels, j.grammes_attendus) {
+                        (Some(reel), Some(attendu)) if attendu > 0.0 => Some(reel / attendu),
+                        _ => None,
+                    })
+                    .collect(),
+            );
+            let grammes_actuels = moyenne(recents.iter().filter_map(|j| j.grammes_reels).collect());
+
+            let age_depart = conformite.jours.last().map_or(1, |jour| jour.age + 1);
+            let mut besoin_kg = 0.0;
+            let mut jours_sans_estimation = 0;
+            if effectif > 0 {
+                for age in age_depart..=age_enlevement {
+                    let grammes = programme
+                        .iter()
+                        .find(|p| p.jour_debut <= age && age <= p.jour_fin)
+                        .map(|p| p.grammes_par_sujet * facteur_consommation.unwrap_or(1.0))
+                        .or(grammes_actuels);
+                    match grammes {
+                        Some(grammes) => {
+                            let kg = grammes * effectif as f64 / 1000.0;
+                            besoin_kg += kg;
+                            *besoins_par_age.entry(age).or_default() += kg;
+                        }
+                        None => jours_sans_estimation += 1,
+                    }
+                }
+            }
+
+            previsions.push(PrevisionAlimentBatiment {
+                batiment_id,
+                numero_batiment,
+                effectif,
+                age_depart,
+                age_enlevement,
+                facteur_consommation,
+                besoin_kg,
+                jours_sans_estimation,
+            });
+        }
+
+        let date_du_jour = |age: i32| date_entree + Days::new((age - 1) as u64);
+        let mut semaines: Vec<BesoinAlimentSemaine> = Vec::new();
+        let mut cumul = 0.0;
+        let mut date_rupture = None;
+        for (&age, &kg) in &besoins_par_age {
+            let numero_semaine = (age - 1) / 7 + 1;
+            match semaines.last_mut() {
+                Some(semaine) if semaine.numero_semaine == numero_semaine => semaine.besoin_kg += kg,
+                _ => semaines.push(BesoinAlimentSemaine {
+                    numero_semaine,
+                    date_debut: date_du_jour((numero_semaine - 1) * 7 + 1).to_string(),
+                    besoin_kg: kg,
+                }),
+            }
+
+            cumul += kg;
+            if date_rupture.is_none() && cumul > stock_kg {
+                date_rupture = Some(date_du_jour(age).to_string());
+            }
+        }
+
+        let besoin_kg: f64 = previsions.iter().map(|p| p.besoin_kg).sum();
+        Ok(PrevisionAliment {
+            bande_id,
+            stock_kg,
+            besoin_kg,
+            besoin_sachets: besoin_kg / Self::get_kg_par_sachet(conn)?,
+            manque_kg: (besoin_kg - stock_kg.max(0.0)).max(0.0),
+            date_rupture,
+            semaines,
+            batiments: previsions,
+        })
+    }
+}
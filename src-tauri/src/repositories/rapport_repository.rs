@@ -0,0 +1,335 @@
+use crate::error::AppError;
+use crate::models::{
+    CreateRapportPersonnalise, DefinitionRapport, EntiteRapport, RapportPersonnalise, ResultatRapport,
+    LIGNES_MAX_RAPPORT,
+};
+use rusqlite::types::{Value as ValeurSql, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde_json::{Map, Value};
+
+/// Entities available to the report builder: (name, FROM clause, fields as (name, SQL expression))
+///
+/// Only these names are accepted in a definition: the SQL text of a report
+/// is assembled from this table, user values are always bound parameters.
+const ENTITES: [(&str, &str, &[(&str, &str)]); 4] = [
+    (
+        "bandes",
+        "bandes bd JOIN fermes f ON bd.ferme_id = f.id",
+        &[
+            ("bande_id", "bd.id"),
+            ("numero_bande", "bd.numero_bande"),
+            ("ferme_id", "bd.ferme_id"),
+            ("ferme", "f.nom"),
+            ("date_entree", "bd.date_entree"),
+            ("statut", "bd.statut"),
+            ("date_cloture", "bd.date_cloture"),
+        ],
+    ),
+    (
+        "batiments",
+        "batiments b
+         JOIN bandes bd ON b.bande_id = bd.id
+         JOIN fermes f ON bd.ferme_id = f.id
+         LEFT JOIN poussins p ON b.poussin_id = p.id
+         LEFT JOIN personnel pe ON b.personnel_id = pe.id",
+        &[
+            ("batiment_id", "b.id"),
+            ("numero_batiment", "b.numero_batiment"),
+            ("bande_id", "bd.id"),
+            ("numero_bande", "bd.numero_bande"),
+            ("ferme_id", "bd.ferme_id"),
+            ("ferme", "f.nom"),
+            ("date_entree", "bd.date_entree"),
+            ("souche", "p.nom"),
+            ("technicien", "pe.nom"),
+            ("quantite", "b.quantite"),
+            ("prix_poussin", "b.prix_poussin"),
+        ],
+    ),
+    (
+        "suivi_quotidien",
+        "suivi_quotidien sq
+         JOIN semaines s ON sq.semaine_id = s.id
+         JOIN batiments b ON s.batiment_id = b.id
+         JOIN bandes bd ON b.bande_id = bd.id
+         JOIN fermes f ON bd.ferme_id = f.id",
+        &[
+            ("bande_id", "bd.id"),
+            ("numero_bande", "bd.numero_bande"),
+            ("ferme_id", "bd.ferme_id"),
+            ("ferme", "f.nom"),
+            ("batiment_id", "b.id"),
+            ("numero_batiment", "b.numero_batiment"),
+            ("numero_semaine", "s.numero_semaine"),
+            ("age", "sq.age"),
+            ("date", "date(bd.date_entree, '+' || (sq.age - 1) || ' days')"),
+            ("deces", "sq.deces_par_jour"),
+            ("elimines", "sq.elimines_par_jour"),
+            ("alimentation_sachets", "sq.alimentation_par_jour"),
+            ("poids_semaine", "s.poids"),
+        ],
+    ),
+    (
+        "alimentation",
+        "alimentation_history a
+         JOIN bandes bd ON a.bande_id = bd.id
+         JOIN fermes f ON bd.ferme_id = f.id",
+        &[
+            ("bande_id", "bd.id"),
+            ("numero_bande", "bd.numero_bande"),
+            ("ferme_id", "bd.ferme_id"),
+            ("ferme", "f.nom"),
+            ("date", "date(a.created_at)"),
+            ("quantite_kg", "a.quantite"),
+            ("fournisseur", "a.fournisseur"),
+            ("type_aliment", "a.type_aliment"),
+            ("prix_unitaire", "a.prix_unitaire"),
+            ("devise", "a.devise"),
+        ],
+    ),
+];
+
+/// Aggregates accepted in a column: (name, SQL function)
+const AGREGATS: [(&str, &str); 5] = [("somme", "SUM"), ("moyenne", "AVG"), ("min", "MIN"), ("max", "MAX"), ("nombre", "COUNT")];
+
+/// Filter operators: (name, SQL operator)
+const OPERATEURS: [(&str, &str); 7] =
+    [("=", "="), ("!=", "<>"), ("<", "<"), ("<=", "<="), (">", ">"), (">=", ">="), ("contient", "LIKE")];
+
+/// Repository for the saved report definitions and their execution
+pub struct RapportRepository;
+
+impl RapportRepository {
+    /// Entities and fields available to the report builder
+    pub fn entites() -> Vec<EntiteRapport> {
+        ENTITES
+            .iter()
+            .map(|(entite, _, champs)| EntiteRapport {
+                entite: entite.to_string(),
+                champs: champs.iter().map(|(champ, _)| champ.to_string()).collect(),
+            })
+            .collect()
+    }
+
+    /// Save a report definition after checking that it can be built
+    pub fn create(
+        conn: &Connection,
+        rapport: &CreateRapportPersonnalise,
+        created_by: Option<i64>,
+    ) -> Result<RapportPersonnalise, AppError> {
+        let nom = rapport.nom.trim();
+        if nom.is_empty() {
+            return Err(AppError::validation_error("nom", "Le nom du rapport est obligatoire"));
+        }
+        let existe: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM report_definitions WHERE nom = ?1 COLLATE NOCASE)",
+            [nom],
+            |row| row.get(0),
+        )?;
+        if existe {
+            return Err(AppError::validation_error("nom", "Un rapport avec ce nom existe déjà"));
+        }
+        let (sql, _, _) = construire_requete(&rapport.definition, None)?;
+        conn.prepare(&sql)?;
+
+        conn.execute(
+            "INSERT INTO report_definitions (nom, definition, created_by) VALUES (?1, ?2, ?3)",
+            params![nom, serde_json::to_string(&rapport.definition)?, created_by],
+        )?;
+        Self::get_by_id(conn, conn.last_insert_rowid())?
+            .ok_or_else(|| AppError::business_logic("Le rapport enregistré est introuvable"))
+    }
+
+    /// Get a report definition by ID
+    pub fn get_by_id(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<Option<RapportPersonnalise>, AppError> {
+        let rapport = conn
+            .query_row(
+                "SELECT id, nom, definition, created_by, created_at FROM report_definitions WHERE id = ?1",
+                [id],
+                map_row,
+            )
+            .optional()?;
+        rapport.map(rapport_from_ligne).transpose()
+    }
+
+    /// Get all report definitions, ordered by name
+    pub fn get_all(conn: &Connection) -> Result<Vec<RapportPersonnalise>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT id, nom, definition, created_by, created_at FROM report_definitions ORDER BY nom COLLATE NOCASE",
+        )?;
+        let rapports = stmt.query_map([], map_row)?.collect::<Result<Vec<_>, _>>()?;
+        rapports.into_iter().map(rapport_from_ligne).collect()
+    }
+
+    /// Delete a report definition
+    pub fn delete(conn: &Connection, id: i64) -> Result<(), AppError> {
+        if conn.execute("DELETE FROM report_definitions WHERE id = ?1", [id])? == 0 {
+            return Err(AppError::not_found("Rapport", id));
+        }
+        Ok(())
+    }
+
+    /// Run a report definition
+    ///
+    /// `parametres` provides the values of the filters declared with a `parametre` name.
+    pub fn executer(
+        conn: &Connection,
+        definition: &DefinitionRapport,
+        parametres: &Map<String, Value>,
+    ) -> Result<ResultatRapport, AppError> {
+        let (sql, valeurs, colonnes) = construire_requete(definition, Some(parametres))?;
+        let mut stmt = conn.prepare(&format!("{} LIMIT {}", sql, LIGNES_MAX_RAPPORT + 1))?;
+        let mut lignes = stmt
+            .query_map(params_from_iter(valeurs), |row| {
+                (0..colonnes.len()).map(|i| row.get_ref(i).map(valeur_json)).collect::<Result<Vec<_>, _>>()
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tronque = lignes.len() > LIGNES_MAX_RAPPORT;
+        lignes.truncate(LIGNES_MAX_RAPPORT);
+        Ok(ResultatRapport { colonnes, lignes, tronque })
+    }
+}
+
+/// Saved row: (id, nom, definition JSON, created_by, created_at)
+type LigneRapport = (i64, String, String, Option<i64>, String);
+
+fn map_row(row: &Row) -> rusqlite::Result<LigneRapport> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+}
+
+fn rapport_from_ligne((id, nom, definition, created_by, created_at): LigneRapport) -> Result<RapportPersonnalise, AppError> {
+    Ok(RapportPersonnalise { id, nom, definition: serde_json::from_str(&definition)?, created_by, created_at })
+}
+
+/// Build the SQL of a definition
+///
+/// Without `parametres`, the filters' parameters are bound to NULL: the query
+/// is only checked, not run.
+///
+/// # Returns
+/// The SQL text, its bound values and the names of the result columns
+fn construire_requete(
+    definition: &DefinitionRapport,
+    parametres: Option<&Map<String, Value>>,
+) -> Result<(String, Vec<ValeurSql>, Vec<String>), AppError> {
+    let (_, source, champs) = ENTITES
+        .iter()
+        .find(|(entite, _, _)| *entite == definition.entite)
+        .ok_or_else(|| AppError::validation_error("entite", &format!("Entité inconnue: {}", definition.entite)))?;
+    let expression = |champ: &str| {
+        champs
+            .iter()
+            .find(|(nom, _)| *nom == champ)
+            .map(|(_, expression)| *expression)
+            .ok_or_else(|| AppError::validation_error("champ", &format!("Champ inconnu pour {}: {}", definition.entite, champ)))
+    };
+
+    if definition.colonnes.is_empty() {
+        return Err(AppError::validation_error("colonnes", "Le rapport doit avoir au moins une colonne"));
+    }
+    let regroupement = definition.regroupement.iter().map(|champ| expression(champ)).collect::<Result<Vec<_>, _>>()?;
+
+    let mut selection = Vec::new();
+    let mut colonnes = Vec::new();
+    for colonne in &definition.colonnes {
+        let champ = expression(&colonne.champ)?;
+        match colonne.agregat.as_deref() {
+            Some(agregat) => {
+                let (_, fonction) = AGREGATS
+                    .iter()
+                    .find(|(nom, _)| *nom == agregat)
+                    .ok_or_else(|| AppError::validation_error("agregat", &format!("Agrégat inconnu: {}", agregat)))?;
+                selection.push(format!("{}({})", fonction, champ));
+                colonnes.push(format!("{}_{}", agregat, colonne.champ));
+            }
+            None => {
+                let agrege = !definition.regroupement.is_empty()
+                    || definition.colonnes.iter().any(|autre| autre.agregat.is_some());
+                if agrege && !definition.regroupement.contains(&colonne.champ) {
+                    return Err(AppError::validation_error(
+                        "colonnes",
+                        &format!("La colonne {} doit être agrégée ou faire partie du regroupement", colonne.champ),
+                    ));
+                }
+                selection.push(champ.to_string());
+                colonnes.push(colonne.champ.clone());
+            }
+        }
+    }
+
+    let mut conditions = Vec::new();
+    let mut valeurs = Vec::new();
+    for filtre in &definition.filtres {
+        let champ = expression(&filtre.champ)?;
+        let (_, operateur) = OPERATEURS
+            .iter()
+            .find(|(nom, _)| *nom == filtre.operateur)
+            .ok_or_else(|| AppError::validation_error("operateur", &format!("Opérateur inconnu: {}", filtre.operateur)))?;
+        let valeur = match (&filtre.valeur, &filtre.parametre, parametres) {
+            (Some(valeur), None, _) => valeur.clone(),
+            (None, Some(_), None) => Value::Null,
+            (None, Some(parametre), Some(parametres)) => parametres.get(parametre).cloned().ok_or_else(|| {
+                AppError::validation_error("params", &format!("Paramètre manquant: {}", parametre))
+            })?,
+            _ => {
+                return Err(AppError::validation_error(
+                    "filtres",
+                    &format!("Le filtre sur {} doit avoir une valeur ou un paramètre", filtre.champ),
+                ))
+            }
+        };
+
+        let valeur = valeur_sql(&valeur, &filtre.champ)?;
+        if *operateur == "LIKE" {
+            // `contient` cherche le texte tel quel: ses jokers sont échappés
+            valeurs.push(match valeur {
+                ValeurSql::Text(texte) => ValeurSql::Text(echapper_like(&texte)),
+                autre => autre,
+            });
+            conditions.push(format!("{} LIKE '%' || ?{} || '%' ESCAPE '\\'", champ, valeurs.len()));
+        } else {
+            valeurs.push(valeur);
+            conditions.push(format!("{} {} ?{}", champ, operateur, valeurs.len()));
+        }
+    }
+
+    let mut sql = format!("SELECT {} FROM {}", selection.join(", "), source);
+    if !conditions.is_empty() {
+        sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+    }
+    if !regroupement.is_empty() {
+        sql.push_str(&format!(" GROUP BY {0} ORDER BY {0}", regroupement.join(", ")));
+    }
+    Ok((sql, valeurs, colonnes))
+}
+
+/// Escape the LIKE wildcards (`%`, `_`) and the escape character itself
+fn echapper_like(texte: &str) -> String {
+    texte.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn valeur_sql(valeur: &Value, champ: &str) -> Result<ValeurSql, AppError> {
+    match valeur {
+        Value::Null => Ok(ValeurSql::Null),
+        Value::Bool(booleen) => Ok(ValeurSql::Integer(*booleen as i64)),
+        Value::Number(nombre) => Ok(match nombre.as_i64() {
+            Some(entier) => ValeurSql::Integer(entier),
+            None => ValeurSql::Real(nombre.as_f64().unwrap_or_default()),
+        }),
+        Value::String(texte) => Ok(ValeurSql::Text(texte.clone())),
+        _ => Err(AppError::validation_error(champ, "La valeur d'un filtre doit être un texte, un nombre ou un booléen")),
+    }
+}
+
+fn valeur_json(valeur: ValueRef) -> Value {
+    match valeur {
+        ValueRef::Integer(entier) => Value::from(entier),
+        ValueRef::Real(reel) => Value::from(reel),
+        ValueRef::Text(texte) => Value::from(String::from_utf8_lossy(texte).into_owned()),
+        ValueRef::Null | ValueRef::Blob(_) => Value::Null,
+    }
+}
@@ -1,9 +1,89 @@
 // Placeholder for semaine repository - will be implemented after services
-use crate::database::DatabaseManager;
+use crate::database::{erreur_mise_a_jour, Storage};
 use crate::error::{AppError, AppResult};
-use crate::models::{Semaine, CreateSemaine, UpdateSemaine};
+use crate::models::{
+    AvancementSemaine, Semaine, CreateSemaine, UpdateSemaine, PARAMETRE_SEMAINES_MAX, PARAMETRE_UNITE_POIDS,
+    PLAGE_SEMAINES_MAX, SEMAINES_MAX_DEFAUT, UNITES_POIDS, UNITE_POIDS_DEFAUT,
+};
+use crate::repositories::{
+    read_tracabilite, BandeRepository, ParametreRepository, PlanSoinsRepository, VerrouillageRepository,
+};
+use rusqlite::{Connection, OptionalExtension};
 use std::sync::Arc;
 
+/// Colonnes lues par `read_tracabilite`, à la suite des colonnes de la semaine
+const COLONNES_TRACABILITE: &str =
+    "created_at, updated_at, created_by, (SELECT username FROM users WHERE id = semaines.created_by)";
+
+/// Insère une semaine et les soins prévus par le plan de son type de poussin
+///
+/// # Returns
+/// L'ID de la semaine créée
+fn inserer_semaine(tx: &Connection, semaine: &CreateSemaine, created_by: Option<i64>) -> AppResult<i64> {
+    // Vérifier que le bâtiment existe
+    let batiment_exists: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM batiments WHERE id = ?1",
+        [semaine.batiment_id],
+        |row| row.get(0),
+    )?;
+
+    if batiment_exists == 0 {
+        return Err(AppError::validation_error(
+            "batiment_id",
+            "Le bâtiment spécifié n'existe pas"
+        ));
+    }
+    BandeRepository::ensure_batiment_modifiable(tx, semaine.batiment_id)?;
+    ensure_semaine_unique(tx, semaine.batiment_id, semaine.numero_semaine, None)?;
+
+    // Insertion de la semaine
+    tx.execute(
+        "INSERT INTO semaines (batiment_id, numero_semaine, poids, created_at, updated_at, created_by)
+         VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?4)",
+        rusqlite::params![
+            semaine.batiment_id,
+            semaine.numero_semaine,
+            semaine.poids,
+            created_by,
+        ],
+    )?;
+
+    let id = tx.last_insert_rowid();
+
+    // Soins prévus par le plan du type de poussin pour les jours de la semaine
+    PlanSoinsRepository::apply_to_semaine(tx, id, created_by)?;
+
+    Ok(id)
+}
+
+/// ID de la semaine d'un bâtiment portant ce numéro
+fn find_semaine_id(conn: &Connection, batiment_id: i64, numero_semaine: i32) -> AppResult<Option<i64>> {
+    let id = conn.query_row(
+        "SELECT id FROM semaines WHERE batiment_id = ?1 AND numero_semaine = ?2",
+        rusqlite::params![batiment_id, numero_semaine],
+        |row| row.get(0),
+    ).optional()?;
+    Ok(id)
+}
+
+/// Refuse une semaine dont le numéro est déjà pris dans le bâtiment
+/// (hors la semaine `exclue`, pour une mise à jour)
+fn ensure_semaine_unique(
+    conn: &Connection,
+    batiment_id: i64,
+    numero_semaine: i32,
+    exclue: Option<i64>,
+) -> AppResult<()> {
+    match find_semaine_id(conn, batiment_id, numero_semaine)? {
+        Some(id) if Some(id) != exclue => Err(AppError::duplicate(
+            "Semaine",
+            id,
+            &format!("La semaine {} existe déjà pour ce bâtiment", numero_semaine)
+        )),
+        _ => Ok(()),
+    }
+}
+
 pub trait SemaineRepositoryTrait: Send + Sync {
     async fn create(&self, semaine: CreateSemaine) -> AppResult<Semaine>;
     async fn get_all(&self) -> AppResult<Vec<Semaine>>;
@@ -11,60 +91,104 @@ pub trait SemaineRepositoryTrait: Send + Sync {
     async fn update(&self, semaine: UpdateSemaine) -> AppResult<Semaine>;
     async fn delete(&self, id: i64) -> AppResult<()>;
     async fn get_by_batiment(&self, batiment_id: i64) -> AppResult<Vec<Semaine>>;
+
+    /// Retourne la semaine d'un bâtiment portant ce numéro, créée si elle n'existe pas
+    ///
+    /// La recherche et la création sont faites dans la même écriture: deux
+    /// appels simultanés retournent la même semaine.
+    async fn get_or_create_semaine(&self, batiment_id: i64, numero_semaine: i32) -> AppResult<Semaine>;
+
+    /// Crée la semaine qui suit la dernière semaine d'un bâtiment
+    ///
+    /// La dernière semaine doit être complète (décès et alimentation saisis
+    /// pour ses 7 jours) et la nouvelle semaine ne peut pas dépasser le nombre
+    /// maximal de semaines. Avec `creer_jours`, les 7 jours de suivi de la
+    /// nouvelle semaine sont créés vides.
+    async fn advance_to_next_week(&self, batiment_id: i64, creer_jours: bool) -> AppResult<AvancementSemaine>;
 }
 
 pub struct SemaineRepository {
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
+    created_by: Option<i64>,
 }
 
 impl SemaineRepository {
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db, created_by: None }
     }
-}
 
-impl SemaineRepositoryTrait for SemaineRepository {
-    async fn create(&self, semaine: CreateSemaine) -> AppResult<Semaine> {
-        let conn = self.db.get_connection()?;
-        
-        // Vérifier que le bâtiment existe
-        let batiment_exists: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM batiments WHERE id = ?1",
-            [semaine.batiment_id],
-            |row| row.get(0),
-        )?;
-
-        if batiment_exists == 0 {
+    /// Enregistre `user_id` comme auteur des semaines créées par ce repository
+    pub fn with_author(mut self, user_id: Option<i64>) -> Self {
+        self.created_by = user_id;
+        self
+    }
+
+    /// Unité de saisie des poids hebdomadaires ("g" ou "kg")
+    pub fn get_poids_unit(conn: &Connection) -> AppResult<String> {
+        let unite = ParametreRepository::get(conn, PARAMETRE_UNITE_POIDS)?
+            .filter(|unite| UNITES_POIDS.iter().any(|(nom, _)| nom == unite))
+            .unwrap_or_else(|| UNITE_POIDS_DEFAUT.to_string());
+        Ok(unite)
+    }
+
+    /// Définit l'unité de saisie des poids hebdomadaires
+    pub fn set_poids_unit(conn: &Connection, unite: &str) -> AppResult<String> {
+        let unite = unite.trim().to_lowercase();
+        if !UNITES_POIDS.iter().any(|(nom, _)| *nom == unite) {
+            return Err(AppError::validation_error("unite", "L'unité de poids doit être \"g\" ou \"kg\""));
+        }
+        ParametreRepository::set(conn, PARAMETRE_UNITE_POIDS, &unite)?;
+        Self::get_poids_unit(conn)
+    }
+
+    /// Nombre maximal de semaines de suivi d'un bâtiment
+    pub fn get_semaines_max(conn: &Connection) -> AppResult<i32> {
+        let semaines_max = ParametreRepository::get(conn, PARAMETRE_SEMAINES_MAX)?
+            .and_then(|valeur| valeur.parse::<i32>().ok())
+            .filter(|semaines| PLAGE_SEMAINES_MAX.contains(semaines))
+            .unwrap_or(SEMAINES_MAX_DEFAUT);
+        Ok(semaines_max)
+    }
+
+    /// Définit le nombre maximal de semaines de suivi d'un bâtiment
+    pub fn set_semaines_max(conn: &Connection, semaines_max: i32) -> AppResult<i32> {
+        if !PLAGE_SEMAINES_MAX.contains(&semaines_max) {
             return Err(AppError::validation_error(
-                "batiment_id",
-                "Le bâtiment spécifié n'existe pas"
+                "semaines_max",
+                &format!(
+                    "Le nombre maximal de semaines doit être compris entre {} et {}",
+                    PLAGE_SEMAINES_MAX.start(),
+                    PLAGE_SEMAINES_MAX.end()
+                )
             ));
         }
+        ParametreRepository::set(conn, PARAMETRE_SEMAINES_MAX, &semaines_max.to_string())?;
+        Self::get_semaines_max(conn)
+    }
 
-        // Insertion de la semaine
-        conn.execute(
-            "INSERT INTO semaines (batiment_id, numero_semaine, poids) VALUES (?1, ?2, ?3)",
-            rusqlite::params![
-                semaine.batiment_id,
-                semaine.numero_semaine,
-                semaine.poids,
-            ],
-        )?;
-
-        let id = conn.last_insert_rowid();
-
-        Ok(Semaine {
-            id: Some(id),
-            batiment_id: semaine.batiment_id,
-            numero_semaine: semaine.numero_semaine,
-            poids: semaine.poids,
-        })
+    /// Signale ou lève le signalement du poids d'une semaine à vérifier
+    pub fn set_poids_a_verifier(conn: &Connection, semaine_id: i64, a_verifier: bool) -> AppResult<()> {
+        conn.execute("UPDATE semaines SET poids_a_verifier = ?1 WHERE id = ?2", rusqlite::params![a_verifier, semaine_id])?;
+        Ok(())
+    }
+}
+
+impl SemaineRepositoryTrait for SemaineRepository {
+    async fn create(&self, semaine: CreateSemaine) -> AppResult<Semaine> {
+        // La semaine et les soins de son plan sont créés dans la même écriture
+        let id = self.db.write(|tx| inserer_semaine(tx, &semaine, self.created_by))?;
+
+        self.get_by_id(id).await
     }
 
     async fn get_all(&self) -> AppResult<Vec<Semaine>> {
         let conn = self.db.get_connection()?;
         
-        let mut stmt = conn.prepare("SELECT id, batiment_id, numero_semaine, poids FROM semaines ORDER BY batiment_id, numero_semaine")?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, batiment_id, numero_semaine, poids, version, poids_a_verifier, {}
+             FROM semaines ORDER BY batiment_id, numero_semaine",
+            COLONNES_TRACABILITE
+        ))?;
         
         let semaines = stmt.query_map([], |row| {
             Ok(Semaine {
@@ -72,6 +196,9 @@ impl SemaineRepositoryTrait for SemaineRepository {
                 batiment_id: row.get(1)?,
                 numero_semaine: row.get(2)?,
                 poids: row.get(3)?,
+                version: row.get(4)?,
+                tracabilite: read_tracabilite(row, 6)?,
+                poids_a_verifier: row.get(5)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -83,13 +210,19 @@ impl SemaineRepositoryTrait for SemaineRepository {
         let conn = self.db.get_connection()?;
         
         let semaine = conn.query_row(
-            "SELECT id, batiment_id, numero_semaine, poids FROM semaines WHERE id = ?1",
+            &format!(
+                "SELECT id, batiment_id, numero_semaine, poids, version, poids_a_verifier, {} FROM semaines WHERE id = ?1",
+                COLONNES_TRACABILITE
+            ),
             [id],
             |row| Ok(Semaine {
                 id: Some(row.get(0)?),
                 batiment_id: row.get(1)?,
                 numero_semaine: row.get(2)?,
                 poids: row.get(3)?,
+                version: row.get(4)?,
+                tracabilite: read_tracabilite(row, 6)?,
+                poids_a_verifier: row.get(5)?,
             }),
         ).map_err(|e| match e {
             rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Semaine", id),
@@ -100,67 +233,74 @@ impl SemaineRepositoryTrait for SemaineRepository {
     }
 
     async fn update(&self, semaine: UpdateSemaine) -> AppResult<Semaine> {
-        let conn = self.db.get_connection()?;
-        
-        // Vérifier que le bâtiment existe
-        let batiment_exists: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM batiments WHERE id = ?1",
-            [semaine.batiment_id],
-            |row| row.get(0),
-        )?;
-
-        if batiment_exists == 0 {
-            return Err(AppError::validation_error(
-                "batiment_id",
-                "Le bâtiment spécifié n'existe pas"
-            ));
-        }
+        self.db.write(|tx| {
+            // Vérifier que le bâtiment existe
+            let batiment_exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM batiments WHERE id = ?1",
+                [semaine.batiment_id],
+                |row| row.get(0),
+            )?;
 
-        // Mise à jour de la semaine
-        let rows_affected = conn.execute(
-            "UPDATE semaines SET batiment_id = ?1, numero_semaine = ?2, poids = ?3 WHERE id = ?4",
-            rusqlite::params![
-                semaine.batiment_id,
-                semaine.numero_semaine,
-                semaine.poids,
-                semaine.id,
-            ],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Semaine", semaine.id));
-        }
+            if batiment_exists == 0 {
+                return Err(AppError::validation_error(
+                    "batiment_id",
+                    "Le bâtiment spécifié n'existe pas"
+                ));
+            }
+            BandeRepository::ensure_semaine_modifiable(tx, semaine.id)?;
+            BandeRepository::ensure_batiment_modifiable(tx, semaine.batiment_id)?;
+            VerrouillageRepository::ensure_semaine_modifiable(tx, semaine.id)?;
+            ensure_semaine_unique(tx, semaine.batiment_id, semaine.numero_semaine, Some(semaine.id))?;
 
-        Ok(Semaine {
-            id: Some(semaine.id),
-            batiment_id: semaine.batiment_id,
-            numero_semaine: semaine.numero_semaine,
-            poids: semaine.poids,
-        })
+            // Mise à jour de la semaine, refusée si elle a été modifiée depuis son chargement
+            tx.query_row(
+                "UPDATE semaines SET batiment_id = ?1, numero_semaine = ?2, poids = ?3, version = version + 1,
+                        updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?4 AND (?5 IS NULL OR version = ?5)
+                 RETURNING id",
+                rusqlite::params![
+                    semaine.batiment_id,
+                    semaine.numero_semaine,
+                    semaine.poids,
+                    semaine.id,
+                    semaine.version,
+                ],
+                |row| row.get::<_, i64>(0),
+            ).optional()?
+            .ok_or_else(|| erreur_mise_a_jour(tx, "semaines", "Semaine", semaine.id, semaine.version))?;
+            Ok(())
+        })?;
+
+        self.get_by_id(semaine.id).await
     }
 
     async fn delete(&self, id: i64) -> AppResult<()> {
-        let conn = self.db.get_connection()?;
-        
-        // La suppression cascade est gérée par les contraintes FK
-        let rows_affected = conn.execute(
-            "DELETE FROM semaines WHERE id = ?1",
-            [id],
-        )?;
+        self.db.write(|tx| {
+            BandeRepository::ensure_semaine_modifiable(tx, id)?;
+            VerrouillageRepository::ensure_semaine_modifiable(tx, id)?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Semaine", id));
-        }
+            // La suppression cascade est gérée par les contraintes FK
+            let rows_affected = tx.execute(
+                "DELETE FROM semaines WHERE id = ?1",
+                [id],
+            )?;
 
-        Ok(())
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Semaine", id));
+            }
+
+            Ok(())
+        })
     }
 
     async fn get_by_batiment(&self, batiment_id: i64) -> AppResult<Vec<Semaine>> {
         let conn = self.db.get_connection()?;
         
-        let mut stmt = conn.prepare(
-            "SELECT id, batiment_id, numero_semaine, poids FROM semaines WHERE batiment_id = ?1 ORDER BY numero_semaine"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, batiment_id, numero_semaine, poids, version, poids_a_verifier, {}
+             FROM semaines WHERE batiment_id = ?1 ORDER BY numero_semaine",
+            COLONNES_TRACABILITE
+        ))?;
         
         let semaines = stmt.query_map([batiment_id], |row| {
             Ok(Semaine {
@@ -168,10 +308,98 @@ impl SemaineRepositoryTrait for SemaineRepository {
                 batiment_id: row.get(1)?,
                 numero_semaine: row.get(2)?,
                 poids: row.get(3)?,
+                version: row.get(4)?,
+                tracabilite: read_tracabilite(row, 6)?,
+                poids_a_verifier: row.get(5)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
         Ok(semaines)
     }
+
+    async fn get_or_create_semaine(&self, batiment_id: i64, numero_semaine: i32) -> AppResult<Semaine> {
+        let id = self.db.write(|tx| match find_semaine_id(tx, batiment_id, numero_semaine)? {
+            Some(id) => Ok(id),
+            None => inserer_semaine(tx, &CreateSemaine { batiment_id, numero_semaine, poids: None }, self.created_by),
+        })?;
+
+        self.get_by_id(id).await
+    }
+
+    async fn advance_to_next_week(&self, batiment_id: i64, creer_jours: bool) -> AppResult<AvancementSemaine> {
+        let (id, jours_crees) = self.db.write(|tx| {
+            let derniere_semaine: Option<i32> = tx
+                .query_row(
+                    "SELECT (SELECT MAX(numero_semaine) FROM semaines WHERE batiment_id = b.id)
+                     FROM batiments b WHERE b.id = ?1",
+                    [batiment_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| AppError::not_found("Batiment", batiment_id))?;
+            let derniere_semaine = derniere_semaine.unwrap_or(0);
+
+            // Jours de la dernière semaine sans décès ou sans alimentation saisis
+            if derniere_semaine > 0 {
+                let mut stmt = tx.prepare(
+                    "WITH RECURSIVE jours(age) AS (
+                         SELECT (?2 - 1) * 7 + 1
+                         UNION ALL
+                         SELECT age + 1 FROM jours WHERE age < ?2 * 7
+                     )
+                     SELECT j.age
+                     FROM jours j
+                     LEFT JOIN semaines s ON s.batiment_id = ?1 AND s.numero_semaine = ?2
+                     LEFT JOIN suivi_quotidien sq ON sq.semaine_id = s.id AND sq.age = j.age
+                     WHERE sq.deces_par_jour IS NULL OR sq.alimentation_par_jour IS NULL
+                     ORDER BY j.age",
+                )?;
+                let incomplets = stmt
+                    .query_map(rusqlite::params![batiment_id, derniere_semaine], |row| row.get::<_, i32>(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !incomplets.is_empty() {
+                    let ages: Vec<String> = incomplets.iter().map(i32::to_string).collect();
+                    return Err(AppError::business_logic(&format!(
+                        "La semaine {} n'est pas complète: décès ou alimentation manquants pour les jours {}",
+                        derniere_semaine,
+                        ages.join(", ")
+                    )));
+                }
+            }
+
+            let semaines_max = Self::get_semaines_max(tx)?;
+            if derniere_semaine >= semaines_max {
+                return Err(AppError::business_logic(&format!(
+                    "Le bâtiment a déjà atteint le nombre maximal de {} semaines",
+                    semaines_max
+                )));
+            }
+
+            let semaine = CreateSemaine { batiment_id, numero_semaine: derniere_semaine + 1, poids: None };
+            let id = inserer_semaine(tx, &semaine, self.created_by)?;
+
+            if creer_jours {
+                let premier_jour = derniere_semaine * 7 + 1;
+                for age in premier_jour..premier_jour + 7 {
+                    tx.execute(
+                        "INSERT INTO suivi_quotidien (semaine_id, age, created_at, updated_at, created_by)
+                         VALUES (?1, ?2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?3)
+                         ON CONFLICT(semaine_id, age) DO NOTHING",
+                        rusqlite::params![id, age, self.created_by],
+                    )?;
+                }
+            }
+
+            // La semaine est neuve: tous ses jours ont été créés par cette écriture
+            let jours_crees = tx
+                .prepare("SELECT age FROM suivi_quotidien WHERE semaine_id = ?1 ORDER BY age")?
+                .query_map([id], |row| row.get(0))?
+                .collect::<Result<Vec<i32>, _>>()?;
+
+            Ok((id, jours_crees))
+        })?;
+
+        Ok(AvancementSemaine { semaine: self.get_by_id(id).await?, jours_crees })
+    }
 }
@@ -1,9 +1,34 @@
-use crate::database::DatabaseManager;
+use crate::database::{nom_existe, normaliser_nom, Storage};
 use crate::error::{AppError, AppResult};
-use crate::models::{Soin, CreateSoin, UpdateSoin, PaginatedSoin};
+use crate::models::{Soin, CreateSoin, UpdateSoin, PaginatedSoin, CATEGORIES_SOIN};
+use rusqlite::Row;
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
 
+/// Colonnes lues pour un `Soin`, dans l'ordre attendu par `map_soin_row`
+const SOIN_COLUMNS: &str = "id, nom, unit, categorie, delai_attente_jours, created_at, prix_unitaire";
+
+fn map_soin_row(row: &Row) -> rusqlite::Result<Soin> {
+    let created_at_str: String = row.get(5)?;
+
+    // Parse using NaiveDateTime first, then convert to UTC
+    let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
+        .map_err(|e| {
+            rusqlite::Error::ToSqlConversionFailure(Box::new(e))
+        })?;
+    let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
+
+    Ok(Soin {
+        id: Some(row.get(0)?),
+        nom: row.get(1)?,
+        unit: row.get(2)?,
+        categorie: row.get(3)?,
+        delai_attente_jours: row.get(4)?,
+        created_at,
+        prix_unitaire: row.get(6)?,
+    })
+}
+
 /// Trait pour les opérations sur les soins
 /// 
 /// Définit l'interface pour toutes les opérations CRUD
@@ -69,11 +94,11 @@ pub trait SoinRepositoryTrait: Send + Sync {
 
 /// Repository implementation for soins
 pub struct SoinRepository {
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl SoinRepository {
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
         Self { db }
     }
     
@@ -96,71 +121,125 @@ impl SoinRepository {
 
         Ok(())
     }
-}
 
-impl SoinRepositoryTrait for SoinRepository {
-    async fn create(&self, soin: CreateSoin) -> AppResult<Soin> {
-        let conn = self.db.get_connection()?;
-        
-        // Validation des données d'entrée
-        if soin.nom.trim().is_empty() {
+    /// Valide et normalise la catégorie d'un soin
+    /// 
+    /// # Arguments
+    /// * `categorie` - La catégorie saisie (vide = aucune)
+    /// 
+    /// # Returns
+    /// La catégorie en minuscules, ou `None` si elle n'est pas renseignée
+    fn validate_categorie(categorie: &Option<String>) -> AppResult<Option<String>> {
+        let categorie = match categorie.as_deref().map(str::trim) {
+            None | Some("") => return Ok(None),
+            Some(c) => c.to_lowercase(),
+        };
+
+        if !CATEGORIES_SOIN.contains(&categorie.as_str()) {
             return Err(AppError::validation_error(
-                "nom", 
-                "Le nom du soin ne peut pas être vide"
+                "categorie",
+                &format!("Catégorie non reconnue. Catégories valides: {}", CATEGORIES_SOIN.join(", "))
             ));
         }
 
-        if soin.unit.trim().is_empty() {
+        Ok(Some(categorie))
+    }
+
+    /// Valide le prix unitaire d'un soin (absent si inconnu)
+    fn validate_prix_unitaire(prix_unitaire: Option<f64>) -> AppResult<()> {
+        if prix_unitaire.is_some_and(|prix| !prix.is_finite() || prix < 0.0) {
             return Err(AppError::validation_error(
-                "unit", 
-                "L'unité par défaut ne peut pas être vide"
+                "prix_unitaire",
+                "Le prix unitaire ne peut pas être négatif"
             ));
         }
 
-        self.validate_unit(&soin.unit)?;
+        Ok(())
+    }
 
-        // Vérifier que le nom n'existe pas déjà
-        let existing: Result<i64, _> = conn.query_row(
-            "SELECT COUNT(*) FROM soins WHERE nom = ?1",
-            [&soin.nom],
-            |row| row.get(0),
-        );
+    /// Valide le délai d'attente (en jours) d'un soin
+    fn validate_delai_attente(delai_attente_jours: i32) -> AppResult<()> {
+        if delai_attente_jours < 0 {
+            return Err(AppError::validation_error(
+                "delai_attente_jours",
+                "Le délai d'attente ne peut pas être négatif"
+            ));
+        }
+
+        Ok(())
+    }
+}
 
-        if let Ok(count) = existing {
-            if count > 0 {
+impl SoinRepositoryTrait for SoinRepository {
+    async fn create(&self, soin: CreateSoin) -> AppResult<Soin> {
+        self.db.write(|tx| {
+            // Validation des données d'entrée
+            if soin.nom.trim().is_empty() {
                 return Err(AppError::validation_error(
-                    "nom",
-                    "Un soin avec ce nom existe déjà"
+                    "nom", 
+                    "Le nom du soin ne peut pas être vide"
                 ));
             }
-        }
 
-        // Insertion du nouveau soin
-        conn.execute(
-            "INSERT INTO soins (nom, unit) VALUES (?1, ?2)",
-            [&soin.nom, &soin.unit],
-        )?;
+            if soin.unit.trim().is_empty() {
+                return Err(AppError::validation_error(
+                    "unit", 
+                    "L'unité par défaut ne peut pas être vide"
+                ));
+            }
 
-        let id = conn.last_insert_rowid();
+            self.validate_unit(&soin.unit)?;
 
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM soins WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([id], |row| {
-            Ok(row.get(0)?)
-        })?;
+            // Vérifier que le nom n'existe pas déjà (sans tenir compte de la casse ni des accents)
+            if nom_existe(tx, "soins", &soin.nom, None)? {
+                return Err(AppError::validation_error(
+                    "nom",
+                    "Un soin avec ce nom existe déjà"
+                ));
+            }
 
-        // Parse the timestamp using NaiveDateTime first, then convert to UTC
-        let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+            let categorie = Self::validate_categorie(&soin.categorie)?;
+            Self::validate_delai_attente(soin.delai_attente_jours)?;
+            Self::validate_prix_unitaire(soin.prix_unitaire)?;
+
+            // Insertion du nouveau soin
+            tx.execute(
+                "INSERT INTO soins (nom, nom_normalise, unit, categorie, delai_attente_jours, prix_unitaire)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    soin.nom,
+                    normaliser_nom(&soin.nom),
+                    soin.unit,
+                    categorie,
+                    soin.delai_attente_jours,
+                    soin.prix_unitaire,
+                ],
+            )?;
+
+            let id = tx.last_insert_rowid();
+
+            // Get the created_at timestamp from the database
+            let mut stmt = tx.prepare("SELECT created_at FROM soins WHERE id = ?1")?;
+            let created_at: String = stmt.query_row([id], |row| {
+                Ok(row.get(0)?)
             })?;
-        let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
 
-        Ok(Soin {
-            id: Some(id),
-            nom: soin.nom,
-            unit: soin.unit,
-            created_at,
+            // Parse the timestamp using NaiveDateTime first, then convert to UTC
+            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?;
+            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
+
+            Ok(Soin {
+                id: Some(id),
+                nom: soin.nom,
+                unit: soin.unit,
+                categorie,
+                delai_attente_jours: soin.delai_attente_jours,
+                created_at,
+                prix_unitaire: soin.prix_unitaire,
+            })
         })
     }
 
@@ -203,8 +282,8 @@ impl SoinRepositoryTrait for SoinRepository {
         
         // Get paginated data
         let data_query = format!(
-            "SELECT id, nom, unit, created_at FROM soins {} ORDER BY nom LIMIT ? OFFSET ?",
-            where_clause
+            "SELECT {} FROM soins {} ORDER BY nom LIMIT ? OFFSET ?",
+            SOIN_COLUMNS, where_clause
         );
         
         // Prepare all parameters: search params + pagination params
@@ -215,23 +294,7 @@ impl SoinRepositoryTrait for SoinRepository {
         let mut stmt = conn.prepare(&data_query)?;
         let soins_list = stmt.query_map(
             rusqlite::params_from_iter(all_params.iter()),
-            |row| {
-                let created_at_str: String = row.get(3)?;
-                
-                // Parse using NaiveDateTime first, then convert to UTC
-                let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
-                    .map_err(|e| {
-                        rusqlite::Error::ToSqlConversionFailure(Box::new(e))
-                    })?;
-                let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
-                
-                Ok(Soin {
-                    id: Some(row.get(0)?),
-                    nom: row.get(1)?,
-                    unit: row.get(2)?,
-                    created_at,
-                })
-            }
+            map_soin_row
         )?.collect::<Result<Vec<_>, _>>()?;
         
         Ok(PaginatedSoin {
@@ -248,24 +311,8 @@ impl SoinRepositoryTrait for SoinRepository {
     async fn get_by_id(&self, id: i64) -> AppResult<Soin> {
         let conn = self.db.get_connection()?;
         
-        let mut stmt = conn.prepare("SELECT id, nom, unit, created_at FROM soins WHERE id = ?1")?;
-        let soin = stmt.query_row([id], |row| {
-            let created_at_str: String = row.get(3)?;
-            
-            // Parse using NaiveDateTime first, then convert to UTC
-            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
-                .map_err(|e| {
-                    rusqlite::Error::ToSqlConversionFailure(Box::new(e))
-                })?;
-            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
-            
-            Ok(Soin {
-                id: Some(row.get(0)?),
-                nom: row.get(1)?,
-                unit: row.get(2)?,
-                created_at,
-            })
-        }).map_err(|e| {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM soins WHERE id = ?1", SOIN_COLUMNS))?;
+        let soin = stmt.query_row([id], map_soin_row).map_err(|e| {
             match e {
                 rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Soin", id),
                 _ => e.into(),
@@ -276,99 +323,108 @@ impl SoinRepositoryTrait for SoinRepository {
     }
 
     async fn update(&self, soin: UpdateSoin) -> AppResult<Soin> {
-        let conn = self.db.get_connection()?;
-        
-        // Validation des données d'entrée
-        if soin.nom.trim().is_empty() {
-            return Err(AppError::validation_error(
-                "nom", 
-                "Le nom du soin ne peut pas être vide"
-            ));
-        }
-
-        if soin.unit.trim().is_empty() {
-            return Err(AppError::validation_error(
-                "unitt", 
-                "L'unité par défaut ne peut pas être vide"
-            ));
-        }
+        self.db.write(|tx| {
+            // Validation des données d'entrée
+            if soin.nom.trim().is_empty() {
+                return Err(AppError::validation_error(
+                    "nom", 
+                    "Le nom du soin ne peut pas être vide"
+                ));
+            }
 
-        self.validate_unit(&soin.unit)?;
+            if soin.unit.trim().is_empty() {
+                return Err(AppError::validation_error(
+                    "unitt", 
+                    "L'unité par défaut ne peut pas être vide"
+                ));
+            }
 
-        // Vérifier que le nom n'existe pas déjà pour un autre soin
-        let existing: Result<i64, _> = conn.query_row(
-            "SELECT COUNT(*) FROM soins WHERE nom = ?1 AND id != ?2",
-            [&soin.nom, &soin.id.to_string()],
-            |row| row.get(0),
-        );
+            self.validate_unit(&soin.unit)?;
 
-        if let Ok(count) = existing {
-            if count > 0 {
+            // Vérifier que le nom n'existe pas déjà pour un autre soin
+            if nom_existe(tx, "soins", &soin.nom, Some(soin.id))? {
                 return Err(AppError::validation_error(
                     "nom",
                     "Un autre soin avec ce nom existe déjà"
                 ));
             }
-        }
-
-        // Mise à jour du soin
-        let rows_affected = conn.execute(
-            "UPDATE soins SET nom = ?1, unit = ?2 WHERE id = ?3",
-            [&soin.nom, &soin.unit, &soin.id.to_string()],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Soin", soin.id));
-        }
 
-        // Get the created_at timestamp from the database
-        let mut stmt = conn.prepare("SELECT created_at FROM soins WHERE id = ?1")?;
-        let created_at: String = stmt.query_row([soin.id], |row| {
-            Ok(row.get(0)?)
-        })?;
+            let categorie = Self::validate_categorie(&soin.categorie)?;
+            Self::validate_delai_attente(soin.delai_attente_jours)?;
+            Self::validate_prix_unitaire(soin.prix_unitaire)?;
+
+            // Mise à jour du soin
+            let rows_affected = tx.execute(
+                "UPDATE soins SET nom = ?1, nom_normalise = ?2, unit = ?3, categorie = ?4, delai_attente_jours = ?5,
+                        prix_unitaire = ?6
+                 WHERE id = ?7",
+                rusqlite::params![
+                    soin.nom,
+                    normaliser_nom(&soin.nom),
+                    soin.unit,
+                    categorie,
+                    soin.delai_attente_jours,
+                    soin.prix_unitaire,
+                    soin.id,
+                ],
+            )?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Soin", soin.id));
+            }
 
-        // Parse the timestamp using NaiveDateTime first, then convert to UTC
-        let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| {
-                AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+            // Get the created_at timestamp from the database
+            let mut stmt = tx.prepare("SELECT created_at FROM soins WHERE id = ?1")?;
+            let created_at: String = stmt.query_row([soin.id], |row| {
+                Ok(row.get(0)?)
             })?;
-        let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
 
-        Ok(Soin {
-            id: Some(soin.id),
-            nom: soin.nom,
-            unit: soin.unit,
-            created_at,
+            // Parse the timestamp using NaiveDateTime first, then convert to UTC
+            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|e| {
+                    AppError::validation_error("created_at", &format!("Failed to parse date '{}': {}", created_at, e))
+                })?;
+            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
+
+            Ok(Soin {
+                id: Some(soin.id),
+                nom: soin.nom,
+                unit: soin.unit,
+                categorie,
+                delai_attente_jours: soin.delai_attente_jours,
+                created_at,
+                prix_unitaire: soin.prix_unitaire,
+            })
         })
     }
 
     async fn delete(&self, id: i64) -> AppResult<()> {
-        let conn = self.db.get_connection()?;
-        
-        // Vérifier s'il y a des entrées de suivi quotidien qui utilisent ce soin
-        let usage_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM suivi_quotidien WHERE soins_id = ?1",
-            [id],
-            |row| row.get(0),
-        )?;
-
-        if usage_count > 0 {
-            return Err(AppError::constraint_violation(
-                "Impossible de supprimer le soin car il est utilisé dans le suivi quotidien"
-            ));
-        }
+        self.db.write(|tx| {
+            // Vérifier s'il y a des entrées de suivi quotidien qui utilisent ce soin
+            let usage_count: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM suivi_soins WHERE soin_id = ?1",
+                [id],
+                |row| row.get(0),
+            )?;
+
+            if usage_count > 0 {
+                return Err(AppError::constraint_violation(
+                    "Impossible de supprimer le soin car il est utilisé dans le suivi quotidien"
+                ));
+            }
 
-        // Suppression du soin
-        let rows_affected = conn.execute(
-            "DELETE FROM soins WHERE id = ?1",
-            [id],
-        )?;
+            // Suppression du soin
+            let rows_affected = tx.execute(
+                "DELETE FROM soins WHERE id = ?1",
+                [id],
+            )?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("Soin", id));
-        }
+            if rows_affected == 0 {
+                return Err(AppError::not_found("Soin", id));
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
     
     async fn search_by_name(&self, nom: &str) -> AppResult<Vec<Soin>> {
@@ -376,26 +432,10 @@ impl SoinRepositoryTrait for SoinRepository {
         
         let search_pattern = format!("%{}%", nom);
         let mut stmt = conn.prepare(
-            "SELECT id, nom, unit, created_at FROM soins WHERE nom LIKE ?1 ORDER BY nom"
+            &format!("SELECT {} FROM soins WHERE nom LIKE ?1 ORDER BY nom", SOIN_COLUMNS)
         )?;
         
-        let soins = stmt.query_map([search_pattern], |row| {
-            let created_at_str: String = row.get(3)?;
-            
-            // Parse using NaiveDateTime first, then convert to UTC
-            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
-                .map_err(|e| {
-                    rusqlite::Error::ToSqlConversionFailure(Box::new(e))
-                })?;
-            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
-            
-            Ok(Soin {
-                id: Some(row.get(0)?),
-                nom: row.get(1)?,
-                unit: row.get(2)?,
-                created_at,
-            })
-        })?
+        let soins = stmt.query_map([search_pattern], map_soin_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
         Ok(soins)
@@ -405,31 +445,16 @@ impl SoinRepositoryTrait for SoinRepository {
         let conn = self.db.get_connection()?;
         
         let mut stmt = conn.prepare(
-            "SELECT s.id, s.nom, s.unit, s.created_at, COUNT(sq.soins_id) as usage_count
+            "SELECT s.id, s.nom, s.unit, s.categorie, s.delai_attente_jours, s.created_at, s.prix_unitaire,
+                    COUNT(ss.id) as usage_count
              FROM soins s
-             LEFT JOIN suivi_quotidien sq ON s.id = sq.soins_id
-             GROUP BY s.id, s.nom, s.unit, s.created_at
+             LEFT JOIN suivi_soins ss ON s.id = ss.soin_id
+             GROUP BY s.id
              ORDER BY usage_count DESC, s.nom
              LIMIT ?1"
         )?;
         
-        let soins = stmt.query_map([limit], |row| {
-            let created_at_str: String = row.get(3)?;
-            
-            // Parse using NaiveDateTime first, then convert to UTC
-            let naive_dt = chrono::NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
-                .map_err(|e| {
-                    rusqlite::Error::ToSqlConversionFailure(Box::new(e))
-                })?;
-            let created_at = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
-            
-            Ok(Soin {
-                id: Some(row.get(0)?),
-                nom: row.get(1)?,
-                unit: row.get(2)?,
-                created_at,
-            })
-        })?
+        let soins = stmt.query_map([limit], map_soin_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
         Ok(soins)
@@ -1,7 +1,19 @@
 // Placeholder for suivi quotidien repository - will be implemented after services
-use crate::database::DatabaseManager;
+use crate::database::{erreur_mise_a_jour, Storage};
 use crate::error::{AppError, AppResult};
-use crate::models::{SuiviQuotidien, SuiviQuotidienWithDetails, CreateSuiviQuotidien, UpdateSuiviQuotidien};
+use crate::models::{
+    SuiviQuotidien, SuiviQuotidienWithDetails, CreateSuiviQuotidien, UpdateSuiviQuotidien,
+    SuiviSoin, CreateSuiviSoin, UpdateSuiviSoin, QuantiteSoin,
+    CompletudeBatiment, CompletudeSaisie, JourSaisie, SEMAINES_SUIVI, AUDIT_ENTITE_SUIVI, AUDIT_SAISIE_SUIVI,
+    LigneGrilleSuivi, ResultatCelluleGrille,
+};
+use crate::repositories::{
+    read_tracabilite, AuditRepository, BandeRepository, ProgrammeAlimentationRepository, VerrouillageRepository,
+};
+use rusqlite::{Connection, OptionalExtension, Row, ToSql};
+use chrono::{Days, NaiveDate};
+use rusqlite::types::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub trait SuiviQuotidienRepositoryTrait: Send + Sync {
@@ -11,233 +23,797 @@ pub trait SuiviQuotidienRepositoryTrait: Send + Sync {
     async fn update(&self, suivi: UpdateSuiviQuotidien) -> AppResult<SuiviQuotidien>;
     async fn delete(&self, id: i64) -> AppResult<()>;
     async fn get_by_semaine(&self, semaine_id: i64) -> AppResult<Vec<SuiviQuotidienWithDetails>>;
+
+    /// Crée ou met à jour un seul champ du suivi d'un jour donné
+    /// 
+    /// L'écriture passe par `INSERT ... ON CONFLICT(semaine_id, age) DO UPDATE`,
+    /// ce qui évite la violation UNIQUE lorsque deux saisies rapides visent la même cellule.
+    /// Les champs `soins_id` et `soins_quantite` portent sur le premier soin du jour.
+    async fn upsert_field(&self, semaine_id: i64, age: i32, field: &str, value: &str) -> AppResult<SuiviQuotidien>;
+
+    /// Enregistre en une seule écriture les cellules modifiées de la grille d'une semaine
+    ///
+    /// Chaque cellule est traitée comme par `upsert_field`. Une cellule refusée
+    /// (champ inconnu, jour verrouillé...) est annulée seule et son erreur est
+    /// retournée dans son résultat; les autres cellules sont enregistrées.
+    /// Une semaine inexistante ou non modifiable fait échouer toute la grille.
+    async fn save_grid(&self, semaine_id: i64, lignes: Vec<LigneGrilleSuivi>) -> AppResult<Vec<ResultatCelluleGrille>>;
+
+    /// Ajoute un soin à un jour de suivi (le jour est créé s'il n'existe pas)
+    async fn add_soin(&self, soin: CreateSuiviSoin) -> AppResult<SuiviSoin>;
+
+    /// Modifie un soin d'un jour de suivi
+    async fn update_soin(&self, soin: UpdateSuiviSoin) -> AppResult<SuiviSoin>;
+
+    /// Retire un soin d'un jour de suivi
+    async fn delete_soin(&self, id: i64) -> AppResult<()>;
+
+    /// Liste les soins d'un jour de suivi, dans l'ordre de saisie
+    async fn get_soins(&self, suivi_id: i64) -> AppResult<Vec<SuiviSoin>>;
+
+    /// Supprime les jours de suivi vides (toutes les données à NULL, sans soin)
+    /// 
+    /// Les jours sont créés à la première saisie d'une cellule et restent
+    /// lorsque l'utilisateur efface ses valeurs. Les bandes clôturées ne sont
+    /// pas modifiées. Retourne le nombre de jours supprimés.
+    async fn cleanup_empty(&self, bande_id: Option<i64>) -> AppResult<i64>;
+
+    /// Indique pour chaque bâtiment et chaque jour jusqu'à `jusqu_au` si les
+    /// décès et l'alimentation ont été saisis
+    async fn get_entry_completeness(&self, bande_id: i64, jusqu_au: NaiveDate) -> AppResult<CompletudeSaisie>;
 }
 
-pub struct SuiviQuotidienRepository {
-    db: Arc<DatabaseManager>,
+/// Colonnes lues pour un `SuiviQuotidienWithDetails`, le premier soin du jour
+/// alimentant les champs `soins_*`
+const DETAILS_SELECT: &str =
+    "SELECT sq.id, sq.semaine_id, sq.age, sq.deces_par_jour,
+            sq.alimentation_par_jour, ss.soin_id,
+            s.nom as soins_nom, COALESCE(ss.unit, s.unit) as soins_unit, ss.quantite, sq.analyses, sq.remarques, sq.version,
+            sq.created_at, sq.updated_at, sq.created_by, (SELECT username FROM users WHERE id = sq.created_by),
+            sq.elimines_par_jour
+     FROM suivi_quotidien sq
+     LEFT JOIN suivi_soins ss ON ss.id = (SELECT MIN(id) FROM suivi_soins WHERE suivi_id = sq.id)
+     LEFT JOIN soins s ON ss.soin_id = s.id";
+
+fn map_details_row(row: &Row) -> rusqlite::Result<SuiviQuotidienWithDetails> {
+    Ok(SuiviQuotidienWithDetails {
+        id: Some(row.get(0)?),
+        semaine_id: row.get(1)?,
+        age: row.get(2)?,
+        deces_par_jour: row.get(3)?,
+        elimines_par_jour: row.get(16)?,
+        alimentation_par_jour: row.get(4)?,
+        soins_id: row.get(5)?,
+        soins_nom: row.get(6)?,
+        soins_unit: row.get(7)?,
+        soins_quantite: row.get(8)?,
+        analyses: row.get(9)?,
+        remarques: row.get(10)?,
+        version: row.get(11)?,
+        tracabilite: read_tracabilite(row, 12)?,
+        soins: Vec::new(),
+    })
 }
 
-impl SuiviQuotidienRepository {
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
-        Self { db }
+/// Charge la liste complète des soins de chaque suivi
+fn attach_soins(conn: &Connection, suivis: &mut [SuiviQuotidienWithDetails]) -> AppResult<()> {
+    for suivi in suivis.iter_mut() {
+        if let Some(id) = suivi.id {
+            suivi.soins = load_soins(conn, id)?;
+        }
     }
+    Ok(())
 }
 
-impl SuiviQuotidienRepositoryTrait for SuiviQuotidienRepository {
-    async fn create(&self, suivi: CreateSuiviQuotidien) -> AppResult<SuiviQuotidien> {
-        let conn = self.db.get_connection()?;
-        
-        // Vérifier que la semaine existe
-        let semaine_exists: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM semaines WHERE id = ?1",
-            [suivi.semaine_id],
+fn load_soins(conn: &Connection, suivi_id: i64) -> AppResult<Vec<SuiviSoin>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT ss.id, ss.suivi_id, ss.soin_id, s.nom, COALESCE(ss.unit, s.unit), ss.quantite,
+                ss.quantite_valeur, ss.quantite_unite
+         FROM suivi_soins ss
+         LEFT JOIN soins s ON ss.soin_id = s.id
+         WHERE ss.suivi_id = ?1
+         ORDER BY ss.id",
+    )?;
+    let soins = stmt.query_map([suivi_id], map_soin_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(soins)
+}
+
+fn load_soin(conn: &Connection, id: i64) -> AppResult<SuiviSoin> {
+    conn.query_row(
+        "SELECT ss.id, ss.suivi_id, ss.soin_id, s.nom, COALESCE(ss.unit, s.unit), ss.quantite,
+                ss.quantite_valeur, ss.quantite_unite
+         FROM suivi_soins ss
+         LEFT JOIN soins s ON ss.soin_id = s.id
+         WHERE ss.id = ?1",
+        [id],
+        map_soin_row,
+    ).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => AppError::not_found("SuiviSoin", id),
+        _ => AppError::from(e),
+    })
+}
+
+fn map_soin_row(row: &Row) -> rusqlite::Result<SuiviSoin> {
+    Ok(SuiviSoin {
+        id: Some(row.get(0)?),
+        suivi_id: row.get(1)?,
+        soin_id: row.get(2)?,
+        soin_nom: row.get(3)?,
+        unit: row.get(4)?,
+        quantite: row.get(5)?,
+        quantite_valeur: row.get(6)?,
+        quantite_unite: row.get(7)?,
+    })
+}
+
+/// Renseigne la quantité structurée des soins administrés correspondant au filtre
+///
+/// La saisie libre est analysée avec, à défaut d'unité dans le texte, l'unité
+/// de l'administration puis celle du soin. Une saisie sans nombre remet la
+/// quantité structurée à NULL.
+///
+/// # Arguments
+/// * `condition` - Le filtre SQL sur `suivi_soins ss`
+///
+/// # Returns
+/// Le nombre de lignes mises à jour
+pub fn structurer_quantites_soins(conn: &Connection, condition: &str, params: &[&dyn ToSql]) -> AppResult<usize> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT ss.id, ss.quantite, COALESCE(ss.unit, so.unit)
+         FROM suivi_soins ss
+         LEFT JOIN soins so ON ss.soin_id = so.id
+         WHERE {}",
+        condition
+    ))?;
+    let lignes = stmt.query_map(params, |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, Option<String>>(2)?))
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let mut update = conn.prepare_cached("UPDATE suivi_soins SET quantite_valeur = ?1, quantite_unite = ?2 WHERE id = ?3")?;
+    for (id, quantite, unite) in &lignes {
+        let structuree = quantite.as_deref().and_then(|texte| QuantiteSoin::parse(texte, unite.as_deref()));
+        update.execute(rusqlite::params![
+            structuree.as_ref().map(|q| q.valeur),
+            structuree.as_ref().map(|q| q.unite.as_str()),
+            id
+        ])?;
+    }
+    Ok(lignes.len())
+}
+
+/// Vérifie que le soin référencé existe
+fn ensure_soin_exists(conn: &Connection, soin_id: Option<i64>) -> AppResult<()> {
+    if let Some(soin_id) = soin_id {
+        let soin_exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM soins WHERE id = ?1",
+            [soin_id],
             |row| row.get(0),
         )?;
 
-        if semaine_exists == 0 {
+        if soin_exists == 0 {
             return Err(AppError::validation_error(
-                "semaine_id",
-                "La semaine spécifiée n'existe pas"
+                "soins_id",
+                &format!("Le soin avec l'ID {} n'existe pas", soin_id)
             ));
         }
+    }
+    Ok(())
+}
 
-        // Insertion du suivi quotidien
-        conn.execute(
-            "INSERT INTO suivi_quotidien (
-                semaine_id, age, deces_par_jour, 
-                alimentation_par_jour, 
-                soins_id, soins_quantite, analyses, remarques
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![
-                suivi.semaine_id,
-                suivi.age,
-                suivi.deces_par_jour,
-                suivi.alimentation_par_jour,
-                suivi.soins_id,
-                suivi.soins_quantite,
-                suivi.analyses,
-                suivi.remarques,
-            ],
-        )?;
+/// Remplace le premier soin d'un jour (champs `soins_id`/`soins_quantite`)
+///
+/// Le soin est supprimé lorsque les deux valeurs sont vides. Seule la table
+/// `suivi_soins` est écrite: l'appelant qui ne met pas déjà à jour le jour de
+/// suivi appelle `marquer_soins_modifies` lorsque la fonction renvoie vrai.
+///
+/// # Returns
+/// Vrai si les soins du jour ont changé
+fn set_premier_soin(
+    conn: &Connection,
+    suivi_id: i64,
+    soin_id: Option<i64>,
+    quantite: Option<String>,
+) -> AppResult<bool> {
+    ensure_soin_exists(conn, soin_id)?;
+
+    let premier: Option<(i64, Option<i64>, Option<String>)> = conn.query_row(
+        "SELECT id, soin_id, quantite FROM suivi_soins
+         WHERE id = (SELECT MIN(id) FROM suivi_soins WHERE suivi_id = ?1)",
+        [suivi_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).optional()?;
+
+    match (premier, soin_id.is_none() && quantite.is_none()) {
+        (Some((id, _, _)), true) => {
+            conn.execute("DELETE FROM suivi_soins WHERE id = ?1", [id])?;
+        }
+        (Some((_, actuel_soin, actuelle_quantite)), false) if actuel_soin == soin_id && actuelle_quantite == quantite => {
+            return Ok(false);
+        }
+        (Some((id, _, _)), false) => {
+            conn.execute(
+                "UPDATE suivi_soins SET soin_id = ?1, quantite = ?2 WHERE id = ?3",
+                rusqlite::params![soin_id, quantite, id],
+            )?;
+            structurer_quantites_soins(conn, "ss.id = ?1", &[&id])?;
+        }
+        (None, false) => {
+            conn.execute(
+                "INSERT INTO suivi_soins (suivi_id, soin_id, quantite) VALUES (?1, ?2, ?3)",
+                rusqlite::params![suivi_id, soin_id, quantite],
+            )?;
+            structurer_quantites_soins(conn, "ss.id = ?1", &[&conn.last_insert_rowid()])?;
+        }
+        (None, true) => return Ok(false),
+    }
+
+    Ok(true)
+}
+
+/// Incrémente la version d'un jour de suivi dont les soins changent
+///
+/// Les soins font partie du jour de suivi: comme après la saisie de ses autres
+/// champs, un formulaire ouvert avant leur modification devient périmé et
+/// `update` le refuse. `update` contrôle et incrémente lui-même la version.
+fn marquer_soins_modifies(conn: &Connection, suivi_id: i64) -> AppResult<()> {
+    conn.execute(
+        "UPDATE suivi_quotidien SET version = version + 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        [suivi_id],
+    )?;
+    Ok(())
+}
+
+/// Crée ou met à jour une cellule du suivi d'un jour (voir `upsert_field`)
+///
+/// L'ancienne valeur d'alimentation est lue puis réécrite: l'appelant doit
+/// exécuter la fonction dans une écriture pour garder le contour cohérent.
+fn upsert_cellule(
+    tx: &Connection,
+    semaine_id: i64,
+    age: i32,
+    field: &str,
+    value: &str,
+    created_by: Option<i64>,
+) -> AppResult<SuiviQuotidien> {
+    // Vérifier que la semaine existe et récupérer la bande associée
+    let bande_id: i64 = tx.query_row(
+        "SELECT b.bande_id FROM semaines s
+         JOIN batiments b ON s.batiment_id = b.id
+         WHERE s.id = ?1",
+        [semaine_id],
+        |row| row.get(0),
+    ).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => AppError::validation_error(
+            "semaine_id",
+            &format!("La semaine avec l'ID {} n'existe pas", semaine_id)
+        ),
+        _ => AppError::from(e),
+    })?;
+    BandeRepository::ensure_semaine_modifiable(tx, semaine_id)?;
+    VerrouillageRepository::ensure_jour_modifiable(tx, semaine_id, age)?;
+
+    match field {
+        // Les champs du soin portent sur le premier soin du jour (table suivi_soins)
+        "soins_id" | "soins_quantite" => {
+            tx.execute(
+                "INSERT INTO suivi_quotidien (semaine_id, age, created_at, updated_at, created_by)
+                 VALUES (?1, ?2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?3)
+                 ON CONFLICT(semaine_id, age) DO NOTHING",
+                rusqlite::params![semaine_id, age, created_by],
+            )?;
+            let suivi_id: i64 = tx.query_row(
+                "SELECT id FROM suivi_quotidien WHERE semaine_id = ?1 AND age = ?2",
+                rusqlite::params![semaine_id, age],
+                |row| row.get(0),
+            )?;
+
+            let (mut soin_id, mut quantite): (Option<i64>, Option<String>) = tx.query_row(
+                "SELECT soin_id, quantite FROM suivi_soins
+                 WHERE id = (SELECT MIN(id) FROM suivi_soins WHERE suivi_id = ?1)",
+                [suivi_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional()?.unwrap_or((None, None));
+
+            if field == "soins_id" {
+                soin_id = value.parse::<i64>().ok();
+            } else {
+                quantite = if value.is_empty() { None } else { Some(value.to_string()) };
+            }
+
+            if set_premier_soin(tx, suivi_id, soin_id, quantite)? {
+                marquer_soins_modifies(tx, suivi_id)?;
+            }
+        }
+        _ => {
+            // Convertir la valeur saisie vers la colonne ciblée (liste blanche des colonnes)
+            let (column, new_value) = match field {
+                "deces_par_jour" | "elimines_par_jour" => (
+                    field,
+                    value.parse::<i64>().map(Value::Integer).unwrap_or(Value::Null),
+                ),
+                "alimentation_par_jour" => (
+                    "alimentation_par_jour",
+                    if value.is_empty() { Value::Null } else { Value::Real(value.parse().unwrap_or(0.0)) },
+                ),
+                "analyses" | "remarques" => (
+                    field,
+                    if value.is_empty() { Value::Null } else { Value::Text(value.to_string()) },
+                ),
+                _ => return Err(AppError::validation_error(
+                    "field",
+                    &format!("Champ inconnu: {}", field)
+                )),
+            };
+
+            // Ancienne alimentation du jour (avant écriture) pour ajuster le contour
+            let old_alimentation: Option<f64> = if column == "alimentation_par_jour" {
+                tx.query_row(
+                    "SELECT alimentation_par_jour FROM suivi_quotidien WHERE semaine_id = ?1 AND age = ?2",
+                    rusqlite::params![semaine_id, age],
+                    |row| row.get::<_, Option<f64>>(0),
+                ).optional()?.flatten()
+            } else {
+                None
+            };
+
+            tx.execute(
+                &format!(
+                    "INSERT INTO suivi_quotidien (semaine_id, age, {column}, created_at, updated_at, created_by)
+                     VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?4)
+                     ON CONFLICT(semaine_id, age) DO UPDATE SET {column} = excluded.{column}"
+                ),
+                rusqlite::params![semaine_id, age, new_value, created_by],
+            )?;
+
+            if column == "alimentation_par_jour" {
+                let new_alimentation = match new_value {
+                    Value::Real(v) => v,
+                    _ => 0.0,
+                };
+
+                // Ajuster alimentation_contour de la différence en kg (sachets × poids d'un sachet),
+                // soustraite car il s'agit d'une consommation
+                let difference_kg = (new_alimentation - old_alimentation.unwrap_or(0.0))
+                    * ProgrammeAlimentationRepository::get_kg_par_sachet(tx)?;
+                if difference_kg != 0.0 {
+                    tx.execute(
+                        "UPDATE bandes SET alimentation_contour = alimentation_contour - ?1 WHERE id = ?2",
+                        rusqlite::params![difference_kg, bande_id],
+                    )?;
+                }
+            }
+        }
+    }
+
+    let suivi = tx.query_row(
+        "SELECT sq.id, sq.semaine_id, sq.age, sq.deces_par_jour, sq.alimentation_par_jour,
+                ss.soin_id, ss.quantite, sq.analyses, sq.remarques, sq.version, sq.elimines_par_jour
+         FROM suivi_quotidien sq
+         LEFT JOIN suivi_soins ss ON ss.id = (SELECT MIN(id) FROM suivi_soins WHERE suivi_id = sq.id)
+         WHERE sq.semaine_id = ?1 AND sq.age = ?2",
+        rusqlite::params![semaine_id, age],
+        |row| Ok(SuiviQuotidien {
+            id: Some(row.get(0)?),
+            semaine_id: row.get(1)?,
+            age: row.get(2)?,
+            deces_par_jour: row.get(3)?,
+            elimines_par_jour: row.get(10)?,
+            alimentation_par_jour: row.get(4)?,
+            soins_id: row.get(5)?,
+            soins_quantite: row.get(6)?,
+            analyses: row.get(7)?,
+            remarques: row.get(8)?,
+            version: row.get(9)?,
+        }),
+    )?;
+
+    // Saisie enregistrée au journal d'audit pour le suivi de l'activité des utilisateurs
+    if let (Some(user_id), Some(suivi_id)) = (created_by, suivi.id) {
+        AuditRepository::log(tx, user_id, AUDIT_SAISIE_SUIVI, AUDIT_ENTITE_SUIVI, suivi_id, Some(field))?;
+    }
+
+    Ok(suivi)
+}
+
+pub struct SuiviQuotidienRepository {
+    db: Arc<dyn Storage>,
+    created_by: Option<i64>,
+}
+
+impl SuiviQuotidienRepository {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db, created_by: None }
+    }
+
+    /// Enregistre `user_id` comme auteur des jours de suivi créés par ce repository
+    ///
+    /// Les cellules saisies avec `upsert_field` sont alors aussi inscrites au
+    /// journal d'audit à son nom.
+    pub fn with_author(mut self, user_id: Option<i64>) -> Self {
+        self.created_by = user_id;
+        self
+    }
+}
+
+impl SuiviQuotidienRepositoryTrait for SuiviQuotidienRepository {
+    async fn create(&self, suivi: CreateSuiviQuotidien) -> AppResult<SuiviQuotidien> {
+        self.db.write(|tx| {
+            // Vérifier que la semaine existe
+            let semaine_exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM semaines WHERE id = ?1",
+                [suivi.semaine_id],
+                |row| row.get(0),
+            )?;
+
+            if semaine_exists == 0 {
+                return Err(AppError::validation_error(
+                    "semaine_id",
+                    "La semaine spécifiée n'existe pas"
+                ));
+            }
+            BandeRepository::ensure_semaine_modifiable(tx, suivi.semaine_id)?;
+            VerrouillageRepository::ensure_jour_modifiable(tx, suivi.semaine_id, suivi.age)?;
+
+            // Insertion du suivi quotidien
+            tx.execute(
+                "INSERT INTO suivi_quotidien (
+                    semaine_id, age, deces_par_jour,
+                    alimentation_par_jour, analyses, remarques,
+                    created_at, updated_at, created_by, elimines_par_jour
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?7, ?8)",
+                rusqlite::params![
+                    suivi.semaine_id,
+                    suivi.age,
+                    suivi.deces_par_jour,
+                    suivi.alimentation_par_jour,
+                    suivi.analyses,
+                    suivi.remarques,
+                    self.created_by,
+                    suivi.elimines_par_jour,
+                ],
+            )?;
 
-        let id = conn.last_insert_rowid();
-
-        Ok(SuiviQuotidien {
-            id: Some(id),
-            semaine_id: suivi.semaine_id,
-            age: suivi.age,
-            deces_par_jour: suivi.deces_par_jour,
-            alimentation_par_jour: suivi.alimentation_par_jour,
-            soins_id: suivi.soins_id,
-            soins_quantite: suivi.soins_quantite,
-            analyses: suivi.analyses,
-            remarques: suivi.remarques,
+            let id = tx.last_insert_rowid();
+            set_premier_soin(tx, id, suivi.soins_id, suivi.soins_quantite.clone())?;
+
+            Ok(SuiviQuotidien {
+                id: Some(id),
+                semaine_id: suivi.semaine_id,
+                age: suivi.age,
+                deces_par_jour: suivi.deces_par_jour,
+                elimines_par_jour: suivi.elimines_par_jour,
+                alimentation_par_jour: suivi.alimentation_par_jour,
+                soins_id: suivi.soins_id,
+                soins_quantite: suivi.soins_quantite,
+                analyses: suivi.analyses,
+                remarques: suivi.remarques,
+                version: 1,
+            })
         })
     }
 
     async fn get_all(&self) -> AppResult<Vec<SuiviQuotidienWithDetails>> {
         let conn = self.db.get_connection()?;
-        
-        let mut stmt = conn.prepare(
-            "SELECT sq.id, sq.semaine_id, sq.age, sq.deces_par_jour,
-                    sq.alimentation_par_jour, sq.soins_id, 
-                    s.nom as soins_nom, s.unit as soins_unit, sq.soins_quantite, sq.analyses, sq.remarques
-             FROM suivi_quotidien sq
-             LEFT JOIN soins s ON sq.soins_id = s.id
-             ORDER BY sq.semaine_id, sq.age"
-        )?;
-        
-        let suivis = stmt.query_map([], |row| {
-            Ok(SuiviQuotidienWithDetails {
-                id: Some(row.get(0)?),
-                semaine_id: row.get(1)?,
-                age: row.get(2)?,
-                deces_par_jour: row.get(3)?,
-                alimentation_par_jour: row.get(4)?,
-                soins_id: row.get(5)?,
-                soins_nom: row.get(6)?,
-                soins_unit: row.get(7)?,
-                soins_quantite: row.get(8)?,
-                analyses: row.get(9)?,
-                remarques: row.get(10)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(&format!("{} ORDER BY sq.semaine_id, sq.age", DETAILS_SELECT))?;
+
+        let mut suivis = stmt.query_map([], map_details_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        attach_soins(&conn, &mut suivis)?;
 
         Ok(suivis)
     }
 
     async fn get_by_id(&self, id: i64) -> AppResult<SuiviQuotidienWithDetails> {
         let conn = self.db.get_connection()?;
-        
-        let suivi = conn.query_row(
-            "SELECT sq.id, sq.semaine_id, sq.age, sq.deces_par_jour,
-                    sq.alimentation_par_jour, sq.soins_id, 
-                    s.nom as soins_nom, s.unit as soins_unit, sq.soins_quantite, sq.analyses, sq.remarques
-             FROM suivi_quotidien sq
-             LEFT JOIN soins s ON sq.soins_id = s.id
-             WHERE sq.id = ?1",
+
+        let mut suivi = conn.query_row(
+            &format!("{} WHERE sq.id = ?1", DETAILS_SELECT),
             [id],
-            |row| Ok(SuiviQuotidienWithDetails {
-                id: Some(row.get(0)?),
-                semaine_id: row.get(1)?,
-                age: row.get(2)?,
-                deces_par_jour: row.get(3)?,
-                alimentation_par_jour: row.get(4)?,
-                soins_id: row.get(5)?,
-                soins_nom: row.get(6)?,
-                soins_unit: row.get(7)?,
-                soins_quantite: row.get(8)?,
-                analyses: row.get(9)?,
-                remarques: row.get(10)?,
-            }),
+            map_details_row,
         ).map_err(|e| match e {
             rusqlite::Error::QueryReturnedNoRows => AppError::not_found("SuiviQuotidien", id),
             _ => AppError::from(e),
         })?;
+        suivi.soins = load_soins(&conn, id)?;
 
         Ok(suivi)
     }
 
     async fn update(&self, suivi: UpdateSuiviQuotidien) -> AppResult<SuiviQuotidien> {
-        let conn = self.db.get_connection()?;
-        
-        // Vérifier que la semaine existe
-        let semaine_exists: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM semaines WHERE id = ?1",
-            [suivi.semaine_id],
-            |row| row.get(0),
-        )?;
+        self.db.write(|tx| {
+            // Vérifier que la semaine existe
+            let semaine_exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM semaines WHERE id = ?1",
+                [suivi.semaine_id],
+                |row| row.get(0),
+            )?;
 
-        if semaine_exists == 0 {
-            return Err(AppError::validation_error(
-                "semaine_id",
-                "La semaine spécifiée n'existe pas"
-            ));
-        }
+            if semaine_exists == 0 {
+                return Err(AppError::validation_error(
+                    "semaine_id",
+                    "La semaine spécifiée n'existe pas"
+                ));
+            }
+            BandeRepository::ensure_suivi_modifiable(tx, suivi.id)?;
+            BandeRepository::ensure_semaine_modifiable(tx, suivi.semaine_id)?;
+            VerrouillageRepository::ensure_suivi_modifiable(tx, suivi.id)?;
+            VerrouillageRepository::ensure_jour_modifiable(tx, suivi.semaine_id, suivi.age)?;
 
-        // Mise à jour du suivi quotidien
-        let rows_affected = conn.execute(
-            "UPDATE suivi_quotidien SET 
-                semaine_id = ?1, age = ?2, deces_par_jour = ?3,
-                alimentation_par_jour = ?4,
-                soins_id = ?5, soins_quantite = ?6, analyses = ?7, remarques = ?8
-             WHERE id = ?9",
-            rusqlite::params![
-                suivi.semaine_id,
-                suivi.age,
-                suivi.deces_par_jour,
-                suivi.alimentation_par_jour,
-                suivi.soins_id,
-                suivi.soins_quantite,
-                suivi.analyses,
-                suivi.remarques,
-                suivi.id,
-            ],
-        )?;
+            // Mise à jour du suivi quotidien, refusée s'il a été modifié depuis son chargement
+            let version: i64 = tx.query_row(
+                "UPDATE suivi_quotidien SET
+                    semaine_id = ?1, age = ?2, deces_par_jour = ?3,
+                    alimentation_par_jour = ?4, analyses = ?5, remarques = ?6, elimines_par_jour = ?9,
+                    version = version + 1, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?7 AND (?8 IS NULL OR version = ?8)
+                 RETURNING version",
+                rusqlite::params![
+                    suivi.semaine_id,
+                    suivi.age,
+                    suivi.deces_par_jour,
+                    suivi.alimentation_par_jour,
+                    suivi.analyses,
+                    suivi.remarques,
+                    suivi.id,
+                    suivi.version,
+                    suivi.elimines_par_jour,
+                ],
+                |row| row.get(0),
+            ).optional()?
+            .ok_or_else(|| erreur_mise_a_jour(tx, "suivi_quotidien", "SuiviQuotidien", suivi.id, suivi.version))?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("SuiviQuotidien", suivi.id));
-        }
+            // La version du jour vient d'être contrôlée et incrémentée, soins compris
+            set_premier_soin(tx, suivi.id, suivi.soins_id, suivi.soins_quantite.clone())?;
 
-        Ok(SuiviQuotidien {
-            id: Some(suivi.id),
-            semaine_id: suivi.semaine_id,
-            age: suivi.age,
-            deces_par_jour: suivi.deces_par_jour,
-            alimentation_par_jour: suivi.alimentation_par_jour,
-            soins_id: suivi.soins_id,
-            soins_quantite: suivi.soins_quantite,
-            analyses: suivi.analyses,
-            remarques: suivi.remarques,
+            Ok(SuiviQuotidien {
+                id: Some(suivi.id),
+                semaine_id: suivi.semaine_id,
+                age: suivi.age,
+                deces_par_jour: suivi.deces_par_jour,
+                elimines_par_jour: suivi.elimines_par_jour,
+                alimentation_par_jour: suivi.alimentation_par_jour,
+                soins_id: suivi.soins_id,
+                soins_quantite: suivi.soins_quantite,
+                analyses: suivi.analyses,
+                remarques: suivi.remarques,
+                version,
+            })
         })
     }
 
     async fn delete(&self, id: i64) -> AppResult<()> {
-        let conn = self.db.get_connection()?;
-        
-        let rows_affected = conn.execute(
-            "DELETE FROM suivi_quotidien WHERE id = ?1",
-            [id],
-        )?;
+        self.db.write(|tx| {
+            BandeRepository::ensure_suivi_modifiable(tx, id)?;
+            VerrouillageRepository::ensure_suivi_modifiable(tx, id)?;
 
-        if rows_affected == 0 {
-            return Err(AppError::not_found("SuiviQuotidien", id));
-        }
+            let rows_affected = tx.execute(
+                "DELETE FROM suivi_quotidien WHERE id = ?1",
+                [id],
+            )?;
 
-        Ok(())
+            if rows_affected == 0 {
+                return Err(AppError::not_found("SuiviQuotidien", id));
+            }
+
+            Ok(())
+        })
     }
 
     async fn get_by_semaine(&self, semaine_id: i64) -> AppResult<Vec<SuiviQuotidienWithDetails>> {
         let conn = self.db.get_connection()?;
-        
-        let mut stmt = conn.prepare(
-            "SELECT sq.id, sq.semaine_id, sq.age, sq.deces_par_jour,
-                    sq.alimentation_par_jour, sq.soins_id, 
-                    s.nom as soins_nom, s.unit as soins_unit, sq.soins_quantite, sq.analyses, sq.remarques
-             FROM suivi_quotidien sq
-             LEFT JOIN soins s ON sq.soins_id = s.id
-             WHERE sq.semaine_id = ?1
-             ORDER BY sq.age"
-        )?;
-        
-        let suivis = stmt.query_map([semaine_id], |row| {
-            Ok(SuiviQuotidienWithDetails {
-                id: Some(row.get(0)?),
-                semaine_id: row.get(1)?,
-                age: row.get(2)?,
-                deces_par_jour: row.get(3)?,
-                alimentation_par_jour: row.get(4)?,
-                soins_id: row.get(5)?,
-                soins_nom: row.get(6)?,
-                soins_unit: row.get(7)?,
-                soins_quantite: row.get(8)?,
-                analyses: row.get(9)?,
-                remarques: row.get(10)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(&format!("{} WHERE sq.semaine_id = ?1 ORDER BY sq.age", DETAILS_SELECT))?;
+
+        let mut suivis = stmt.query_map([semaine_id], map_details_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        attach_soins(&conn, &mut suivis)?;
 
         Ok(suivis)
     }
+
+    async fn upsert_field(&self, semaine_id: i64, age: i32, field: &str, value: &str) -> AppResult<SuiviQuotidien> {
+        self.db.write(|tx| upsert_cellule(tx, semaine_id, age, field, value, self.created_by))
+    }
+
+    async fn save_grid(&self, semaine_id: i64, lignes: Vec<LigneGrilleSuivi>) -> AppResult<Vec<ResultatCelluleGrille>> {
+        self.db.write(|tx| {
+            let existe: i64 = tx.query_row("SELECT COUNT(*) FROM semaines WHERE id = ?1", [semaine_id], |row| row.get(0))?;
+            if existe == 0 {
+                return Err(AppError::not_found("Semaine", semaine_id));
+            }
+            BandeRepository::ensure_semaine_modifiable(tx, semaine_id)?;
+
+            let mut resultats = Vec::new();
+            for ligne in &lignes {
+                for (field, value) in &ligne.champs {
+                    // Un point de sauvegarde par cellule: une cellule refusée est
+                    // annulée sans défaire les cellules déjà enregistrées
+                    tx.execute_batch("SAVEPOINT cellule_grille")?;
+                    let (suivi, erreur) = match upsert_cellule(tx, semaine_id, ligne.age, field, value, self.created_by) {
+                        Ok(suivi) => {
+                            tx.execute_batch("RELEASE cellule_grille")?;
+                            (Some(suivi), None)
+                        }
+                        Err(e) => {
+                            tx.execute_batch("ROLLBACK TO cellule_grille; RELEASE cellule_grille")?;
+                            (None, Some(e.to_string()))
+                        }
+                    };
+                    resultats.push(ResultatCelluleGrille { age: ligne.age, field: field.clone(), suivi, erreur });
+                }
+            }
+
+            Ok(resultats)
+        })
+    }
+
+    async fn add_soin(&self, soin: CreateSuiviSoin) -> AppResult<SuiviSoin> {
+        self.db.write(|tx| {
+            let semaine_exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM semaines WHERE id = ?1",
+                [soin.semaine_id],
+                |row| row.get(0),
+            )?;
+
+            if semaine_exists == 0 {
+                return Err(AppError::validation_error(
+                    "semaine_id",
+                    "La semaine spécifiée n'existe pas"
+                ));
+            }
+            BandeRepository::ensure_semaine_modifiable(tx, soin.semaine_id)?;
+            VerrouillageRepository::ensure_jour_modifiable(tx, soin.semaine_id, soin.age)?;
+            ensure_soin_exists(tx, soin.soin_id)?;
+            tx.execute(
+                "INSERT INTO suivi_quotidien (semaine_id, age, created_at, updated_at, created_by)
+                 VALUES (?1, ?2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?3)
+                 ON CONFLICT(semaine_id, age) DO NOTHING",
+                rusqlite::params![soin.semaine_id, soin.age, self.created_by],
+            )?;
+            let suivi_id: i64 = tx.query_row(
+                "SELECT id FROM suivi_quotidien WHERE semaine_id = ?1 AND age = ?2",
+                rusqlite::params![soin.semaine_id, soin.age],
+                |row| row.get(0),
+            )?;
+            tx.execute(
+                "INSERT INTO suivi_soins (suivi_id, soin_id, quantite, unit) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![suivi_id, soin.soin_id, soin.quantite, soin.unit],
+            )?;
+            let id = tx.last_insert_rowid();
+            structurer_quantites_soins(tx, "ss.id = ?1", &[&id])?;
+            marquer_soins_modifies(tx, suivi_id)?;
+
+            load_soin(tx, id)
+        })
+    }
+
+    async fn update_soin(&self, soin: UpdateSuiviSoin) -> AppResult<SuiviSoin> {
+        self.db.write(|tx| {
+            BandeRepository::ensure_suivi_soin_modifiable(tx, soin.id)?;
+            VerrouillageRepository::ensure_suivi_soin_modifiable(tx, soin.id)?;
+            ensure_soin_exists(tx, soin.soin_id)?;
+
+            let rows_affected = tx.execute(
+                "UPDATE suivi_soins SET soin_id = ?1, quantite = ?2, unit = ?3 WHERE id = ?4",
+                rusqlite::params![soin.soin_id, soin.quantite, soin.unit, soin.id],
+            )?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("SuiviSoin", soin.id));
+            }
+            structurer_quantites_soins(tx, "ss.id = ?1", &[&soin.id])?;
+            let soin_modifie = load_soin(tx, soin.id)?;
+            marquer_soins_modifies(tx, soin_modifie.suivi_id)?;
+
+            Ok(soin_modifie)
+        })
+    }
+
+    async fn delete_soin(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| {
+            BandeRepository::ensure_suivi_soin_modifiable(tx, id)?;
+            VerrouillageRepository::ensure_suivi_soin_modifiable(tx, id)?;
+
+            let suivi_id: i64 = tx.query_row("SELECT suivi_id FROM suivi_soins WHERE id = ?1", [id], |row| row.get(0))
+                .optional()?
+                .ok_or_else(|| AppError::not_found("SuiviSoin", id))?;
+            tx.execute("DELETE FROM suivi_soins WHERE id = ?1", [id])?;
+            marquer_soins_modifies(tx, suivi_id)?;
+
+            Ok(())
+        })
+    }
+
+    async fn get_soins(&self, suivi_id: i64) -> AppResult<Vec<SuiviSoin>> {
+        let conn = self.db.get_connection()?;
+        load_soins(&conn, suivi_id)
+    }
+
+    async fn cleanup_empty(&self, bande_id: Option<i64>) -> AppResult<i64> {
+        self.db.write(|tx| {
+            let supprimes = tx.execute(
+                "DELETE FROM suivi_quotidien
+                 WHERE deces_par_jour IS NULL
+                   AND elimines_par_jour IS NULL
+                   AND alimentation_par_jour IS NULL
+                   AND soins_id IS NULL
+                   AND soins_quantite IS NULL
+                   AND analyses IS NULL
+                   AND remarques IS NULL
+                   AND NOT EXISTS (SELECT 1 FROM suivi_soins ss WHERE ss.suivi_id = suivi_quotidien.id)
+                   AND semaine_id IN (
+                       SELECT s.id FROM semaines s
+                       JOIN batiments b ON s.batiment_id = b.id
+                       JOIN bandes bd ON b.bande_id = bd.id
+                       WHERE bd.statut != 'cloturee' AND (?1 IS NULL OR bd.id = ?1)
+                   )",
+                [bande_id],
+            )?;
+            Ok(supprimes as i64)
+        })
+    }
+
+    async fn get_entry_completeness(&self, bande_id: i64, jusqu_au: NaiveDate) -> AppResult<CompletudeSaisie> {
+        let conn = self.db.get_connection()?;
+        let (ferme_id, date_entree, date_cloture): (i64, NaiveDate, Option<NaiveDate>) = conn
+            .query_row(
+                "SELECT ferme_id, date_entree, date_cloture FROM bandes WHERE id = ?1",
+                [bande_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::not_found("Bande", bande_id))?;
+        let fin = date_cloture.map_or(jusqu_au, |cloture| cloture.min(jusqu_au));
+        let dernier_age = (fin - date_entree).num_days() + 1;
+
+        let batiments: Vec<(i64, String, i32)> = conn
+            .prepare(
+                "SELECT b.id, b.numero_batiment, COALESCE(MAX(s.numero_semaine), 0)
+                 FROM batiments b
+                 LEFT JOIN semaines s ON s.batiment_id = b.id
+                 WHERE b.bande_id = ?1
+                 GROUP BY b.id
+                 ORDER BY b.id",
+            )?
+            .query_map([bande_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // (batiment, âge) -> (décès saisis, alimentation saisie)
+        let saisies: HashMap<(i64, i32), (bool, bool)> = conn
+            .prepare(
+                "SELECT s.batiment_id, sq.age, sq.deces_par_jour IS NOT NULL, sq.alimentation_par_jour IS NOT NULL
+                 FROM suivi_quotidien sq
+                 JOIN semaines s ON sq.semaine_id = s.id
+                 JOIN batiments b ON s.batiment_id = b.id
+                 WHERE b.bande_id = ?1",
+            )?
+            .query_map([bande_id], |row| Ok(((row.get(0)?, row.get(1)?), (row.get(2)?, row.get(3)?))))?
+            .collect::<Result<_, _>>()?;
+
+        let batiments = batiments
+            .into_iter()
+            .map(|(batiment_id, numero_batiment, derniere_semaine)| {
+                let dernier_jour = dernier_age.min(i64::from(derniere_semaine.max(SEMAINES_SUIVI) * 7));
+                let jours: Vec<JourSaisie> = (1..=dernier_jour as i32)
+                    .map(|age| {
+                        let (deces_saisi, alimentation_saisie) =
+                            saisies.get(&(batiment_id, age)).copied().unwrap_or((false, false));
+                        JourSaisie {
+                            age,
+                            date: date_entree + Days::new(age as u64 - 1),
+                            deces_saisi,
+                            alimentation_saisie,
+                        }
+                    })
+                    .collect();
+                let jours_incomplets = jours.iter().filter(|jour| !jour.deces_saisi || !jour.alimentation_saisie).count() as i32;
+                CompletudeBatiment { batiment_id, numero_batiment, jours, jours_incomplets }
+            })
+            .collect();
+
+        Ok(CompletudeSaisie { bande_id, ferme_id, date_entree, jusqu_au: fin, batiments })
+    }
 }
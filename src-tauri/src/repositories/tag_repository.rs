@@ -0,0 +1,183 @@
+use crate::error::AppError;
+use crate::models::{CreateTag, Tag};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+fn map_tag_row(row: &Row) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        nom: row.get(1)?,
+        couleur: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+/// Repository for bande tags
+pub struct TagRepository;
+
+impl TagRepository {
+    /// Create a tag
+    ///
+    /// Names are unique regardless of case.
+    pub fn create(
+        conn: &Connection,
+        tag: &CreateTag,
+    ) -> Result<Tag, AppError> {
+        let (nom, couleur) = Self::validate(conn, None, tag)?;
+
+        conn.execute(
+            "INSERT INTO tags (nom, couleur) VALUES (?1, ?2)",
+            params![nom, couleur],
+        )?;
+
+        Self::find(conn, conn.last_insert_rowid())
+    }
+
+    /// Rename a tag or change its color
+    pub fn update(
+        conn: &Connection,
+        id: i64,
+        tag: &CreateTag,
+    ) -> Result<Tag, AppError> {
+        Self::find(conn, id)?;
+        let (nom, couleur) = Self::validate(conn, Some(id), tag)?;
+
+        conn.execute(
+            "UPDATE tags SET nom = ?1, couleur = ?2 WHERE id = ?3",
+            params![nom, couleur, id],
+        )?;
+
+        Self::find(conn, id)
+    }
+
+    /// List all tags by name
+    pub fn get_all(conn: &Connection) -> Result<Vec<Tag>, AppError> {
+        let mut stmt = conn.prepare("SELECT id, nom, couleur, created_at FROM tags ORDER BY nom COLLATE NOCASE")?;
+
+        let tags = stmt.query_map([], map_tag_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// Delete a tag, removing it from every bande
+    pub fn delete(
+        conn: &Connection,
+        id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute("DELETE FROM tags WHERE id = ?1", [id])?;
+
+        if rows_affected == 0 {
+            return Err(AppError::not_found("Tag", id));
+        }
+
+        Ok(())
+    }
+
+    /// Put a tag on a bande (no effect if the bande already has it)
+    pub fn add_to_bande(
+        conn: &Connection,
+        bande_id: i64,
+        tag_id: i64,
+    ) -> Result<(), AppError> {
+        let bande_exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM bandes WHERE id = ?1",
+            [bande_id],
+            |row| row.get(0),
+        )?;
+        if bande_exists == 0 {
+            return Err(AppError::not_found("Bande", bande_id));
+        }
+        Self::find(conn, tag_id)?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO bande_tags (bande_id, tag_id) VALUES (?1, ?2)",
+            [bande_id, tag_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove a tag from a bande
+    pub fn remove_from_bande(
+        conn: &Connection,
+        bande_id: i64,
+        tag_id: i64,
+    ) -> Result<(), AppError> {
+        let rows_affected = conn.execute(
+            "DELETE FROM bande_tags WHERE bande_id = ?1 AND tag_id = ?2",
+            [bande_id, tag_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(AppError::business_logic("Cette étiquette n'est pas posée sur la bande"));
+        }
+
+        Ok(())
+    }
+
+    /// Tags of a bande, by name
+    pub fn get_by_bande(
+        conn: &Connection,
+        bande_id: i64,
+    ) -> Result<Vec<Tag>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.nom, t.couleur, t.created_at
+             FROM tags t
+             JOIN bande_tags bt ON bt.tag_id = t.id
+             WHERE bt.bande_id = ?1
+             ORDER BY t.nom COLLATE NOCASE",
+        )?;
+
+        let tags = stmt.query_map([bande_id], map_tag_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// Check the name (required, unique) and color (#RRGGBB) of a tag
+    ///
+    /// # Returns
+    /// The trimmed name and the color in lowercase
+    fn validate(
+        conn: &Connection,
+        id: Option<i64>,
+        tag: &CreateTag,
+    ) -> Result<(String, Option<String>), AppError> {
+        let nom = tag.nom.trim().to_string();
+        if nom.is_empty() {
+            return Err(AppError::validation_error("nom", "Le nom de l'étiquette est obligatoire"));
+        }
+
+        let couleur = tag.couleur.as_ref().map(|c| c.trim().to_lowercase()).filter(|c| !c.is_empty());
+        if let Some(couleur) = &couleur {
+            let valide = couleur.len() == 7
+                && couleur.starts_with('#')
+                && couleur[1..].chars().all(|c| c.is_ascii_hexdigit());
+            if !valide {
+                return Err(AppError::validation_error("couleur", "La couleur doit être au format #RRGGBB"));
+            }
+        }
+
+        let existant: Option<i64> = conn.query_row(
+            "SELECT id FROM tags WHERE nom = ?1 COLLATE NOCASE AND (?2 IS NULL OR id != ?2)",
+            params![nom, id],
+            |row| row.get(0),
+        ).optional()?;
+        if existant.is_some() {
+            return Err(AppError::validation_error(
+                "nom",
+                &format!("L'étiquette « {} » existe déjà", nom)
+            ));
+        }
+
+        Ok((nom, couleur))
+    }
+
+    fn find(conn: &Connection, id: i64) -> Result<Tag, AppError> {
+        conn.query_row("SELECT id, nom, couleur, created_at FROM tags WHERE id = ?1", [id], map_tag_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Tag", id),
+                _ => AppError::from(e),
+            })
+    }
+}
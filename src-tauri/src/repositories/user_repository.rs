@@ -1,4 +1,4 @@
-use crate::models::{User, CreateUser, LoginUser, UserPublic};
+use crate::models::{User, CreateUser, LoginUser, UserPublic, ROLE_ADMIN};
 use crate::commands::auth_commands::{UpdateProfileData, UpdatePasswordData};
 use crate::error::AppError;
 use rusqlite::{params, Connection, Result as SqliteResult};
@@ -6,13 +6,14 @@ use bcrypt::{hash, verify, DEFAULT_COST};
 
 /// Trait définissant les opérations sur les utilisateurs
 pub trait UserRepositoryTrait {
-    fn create_user(&self, user: CreateUser) -> Result<User, AppError>;
+    fn create_user(&self, user: CreateUser, role: &str) -> Result<User, AppError>;
     fn authenticate_user(&self, login: LoginUser) -> Result<Option<User>, AppError>;
     fn get_user_by_id(&self, id: i64) -> Result<Option<User>, AppError>;
     fn get_user_by_username(&self, username: &str) -> Result<Option<User>, AppError>;
     fn user_exists(&self, username: &str, email: &str) -> Result<bool, AppError>;
     fn update_user_profile(&self, profile_data: UpdateProfileData) -> Result<User, AppError>;
     fn update_user_password(&self, password_data: UpdatePasswordData) -> Result<(), AppError>;
+    fn reset_user_password(&self, user_id: i64, temporary_password: &str) -> Result<(), AppError>;
 }
 
 /// Implémentation du repository pour les utilisateurs
@@ -39,17 +40,23 @@ impl<'a> UserRepository<'a> {
 }
 
 impl<'a> UserRepositoryTrait for UserRepository<'a> {
-    fn create_user(&self, user: CreateUser) -> Result<User, AppError> {
+    fn create_user(&self, user: CreateUser, role: &str) -> Result<User, AppError> {
         // Hash le mot de passe
         let password_hash = self.hash_password(&user.password)?;
         
+        // Le premier compte créé administre l'application, quel que soit le rôle demandé
+        let admin_exists: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM users WHERE role = ?1", [ROLE_ADMIN], |row| row.get(0))
+            .map_err(AppError::from)?;
+        let role = if admin_exists == 0 { ROLE_ADMIN } else { role };
+
         let sql = r#"
-            INSERT INTO users (username, email, password_hash, created_at, updated_at)
-            VALUES (?1, ?2, ?3, datetime('now'), datetime('now'))
+            INSERT INTO users (username, email, password_hash, role, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, datetime('now'), datetime('now'))
         "#;
 
         self.conn
-            .execute(sql, params![user.username, user.email, password_hash])
+            .execute(sql, params![user.username, user.email, password_hash, role])
             .map_err(AppError::from)?;
 
         let user_id = self.conn.last_insert_rowid();
@@ -75,7 +82,7 @@ impl<'a> UserRepositoryTrait for UserRepository<'a> {
 
     fn get_user_by_id(&self, id: i64) -> Result<Option<User>, AppError> {
         let sql = r#"
-            SELECT id, username, email, password_hash, created_at, updated_at
+            SELECT id, username, email, password_hash, role, must_change_password, created_at, updated_at
             FROM users
             WHERE id = ?1
         "#;
@@ -88,8 +95,10 @@ impl<'a> UserRepositoryTrait for UserRepository<'a> {
                 username: row.get(1)?,
                 email: row.get(2)?,
                 password_hash: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
+                role: row.get(4)?,
+                must_change_password: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
             })
         }).map_err(AppError::from)?;
 
@@ -102,7 +111,7 @@ impl<'a> UserRepositoryTrait for UserRepository<'a> {
 
     fn get_user_by_username(&self, username: &str) -> Result<Option<User>, AppError> {
         let sql = r#"
-            SELECT id, username, email, password_hash, created_at, updated_at
+            SELECT id, username, email, password_hash, role, must_change_password, created_at, updated_at
             FROM users
             WHERE username = ?1
         "#;
@@ -115,8 +124,10 @@ impl<'a> UserRepositoryTrait for UserRepository<'a> {
                 username: row.get(1)?,
                 email: row.get(2)?,
                 password_hash: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
+                role: row.get(4)?,
+                must_change_password: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
             })
         }).map_err(AppError::from)?;
 
@@ -174,10 +185,10 @@ impl<'a> UserRepositoryTrait for UserRepository<'a> {
         // Hash le nouveau mot de passe
         let new_password_hash = self.hash_password(&password_data.new_password)?;
         
-        // Met à jour le mot de passe pour cet utilisateur
+        // Met à jour le mot de passe pour cet utilisateur, ce qui lève l'obligation de le changer
         let sql_update = r#"
             UPDATE users 
-            SET password_hash = ?1, updated_at = datetime('now')
+            SET password_hash = ?1, must_change_password = 0, updated_at = datetime('now')
             WHERE id = ?2
         "#;
 
@@ -187,4 +198,25 @@ impl<'a> UserRepositoryTrait for UserRepository<'a> {
 
         Ok(())
     }
+
+    fn reset_user_password(&self, user_id: i64, temporary_password: &str) -> Result<(), AppError> {
+        let password_hash = self.hash_password(temporary_password)?;
+
+        // Le mot de passe temporaire devra être changé à la prochaine connexion
+        let sql = r#"
+            UPDATE users
+            SET password_hash = ?1, must_change_password = 1, updated_at = datetime('now')
+            WHERE id = ?2
+        "#;
+
+        let affected_rows = self.conn
+            .execute(sql, params![password_hash, user_id])
+            .map_err(AppError::from)?;
+
+        if affected_rows == 0 {
+            return Err(AppError::not_found("User", user_id));
+        }
+
+        Ok(())
+    }
 }
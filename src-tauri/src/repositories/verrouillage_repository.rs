@@ -0,0 +1,148 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{DeverrouillagePeriode, FenetreSaisie, DUREE_DEVERROUILLAGE_HEURES, PARAMETRE_FENETRE_SAISIE_JOURS};
+use crate::repositories::ParametreRepository;
+use rusqlite::{Connection, OptionalExtension, Params};
+
+/// Repository for the edit window of the daily follow-up
+///
+/// Days older than the configured window are read-only unless an admin has
+/// unlocked a period of their bande. Each `ensure_*` query selects the bande
+/// (`bande_id`) and the date of the edited day (`date_jour`).
+pub struct VerrouillageRepository;
+
+impl VerrouillageRepository {
+    /// Get the edit window (`None` when locking is disabled)
+    pub fn get_fenetre(conn: &Connection) -> AppResult<FenetreSaisie> {
+        let jours = ParametreRepository::get(conn, PARAMETRE_FENETRE_SAISIE_JOURS)?
+            .and_then(|valeur| valeur.parse::<i32>().ok())
+            .filter(|&jours| jours > 0);
+        Ok(FenetreSaisie { jours })
+    }
+
+    /// Set the edit window, `None` disables locking
+    pub fn set_fenetre(conn: &Connection, jours: Option<i32>) -> AppResult<FenetreSaisie> {
+        ParametreRepository::set(conn, PARAMETRE_FENETRE_SAISIE_JOURS, &jours.unwrap_or(0).to_string())?;
+        Self::get_fenetre(conn)
+    }
+
+    /// Unlock the days `date_debut..=date_fin` of a bande for `DUREE_DEVERROUILLAGE_HEURES`
+    pub fn unlock(
+        conn: &Connection,
+        bande_id: i64,
+        date_debut: &str,
+        date_fin: &str,
+        user_id: i64,
+    ) -> AppResult<DeverrouillagePeriode> {
+        conn.execute(
+            "INSERT INTO deverrouillages_periode (bande_id, date_debut, date_fin, expire_le, user_id)
+             VALUES (?1, ?2, ?3, datetime('now', '+' || ?4 || ' hours'), ?5)",
+            rusqlite::params![bande_id, date_debut, date_fin, DUREE_DEVERROUILLAGE_HEURES, user_id],
+        )?;
+        let id = conn.last_insert_rowid();
+        let deverrouillage = conn.query_row(
+            "SELECT id, bande_id, date_debut, date_fin, expire_le, user_id FROM deverrouillages_periode WHERE id = ?1",
+            [id],
+            |row| {
+                Ok(DeverrouillagePeriode {
+                    id: row.get(0)?,
+                    bande_id: row.get(1)?,
+                    date_debut: row.get(2)?,
+                    date_fin: row.get(3)?,
+                    expire_le: row.get(4)?,
+                    user_id: row.get(5)?,
+                })
+            },
+        )?;
+        Ok(deverrouillage)
+    }
+
+    /// Reject writes on the day `age` of a semaine when it is outside the edit window
+    pub fn ensure_jour_modifiable(conn: &Connection, semaine_id: i64, age: i32) -> AppResult<()> {
+        Self::ensure_dans_fenetre(
+            conn,
+            "SELECT b.bande_id, date(bd.date_entree, '+' || (?2 - 1) || ' days') AS date_jour
+             FROM semaines s
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE s.id = ?1",
+            rusqlite::params![semaine_id, age],
+        )
+    }
+
+    /// Reject writes on a suivi_quotidien row outside the edit window
+    pub fn ensure_suivi_modifiable(conn: &Connection, suivi_id: i64) -> AppResult<()> {
+        Self::ensure_dans_fenetre(
+            conn,
+            "SELECT b.bande_id, date(bd.date_entree, '+' || (sq.age - 1) || ' days') AS date_jour
+             FROM suivi_quotidien sq
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE sq.id = ?1",
+            [suivi_id],
+        )
+    }
+
+    /// Reject writes on a suivi_soins row whose day is outside the edit window
+    pub fn ensure_suivi_soin_modifiable(conn: &Connection, suivi_soin_id: i64) -> AppResult<()> {
+        Self::ensure_dans_fenetre(
+            conn,
+            "SELECT b.bande_id, date(bd.date_entree, '+' || (sq.age - 1) || ' days') AS date_jour
+             FROM suivi_soins ss
+             JOIN suivi_quotidien sq ON ss.suivi_id = sq.id
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE ss.id = ?1",
+            [suivi_soin_id],
+        )
+    }
+
+    /// Reject writes on a semaine whose last day is outside the edit window
+    pub fn ensure_semaine_modifiable(conn: &Connection, semaine_id: i64) -> AppResult<()> {
+        Self::ensure_dans_fenetre(
+            conn,
+            "SELECT b.bande_id, date(bd.date_entree, '+' || (s.numero_semaine * 7 - 1) || ' days') AS date_jour
+             FROM semaines s
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE s.id = ?1",
+            [semaine_id],
+        )
+    }
+
+    /// Fail when the day selected by `jour_query` is older than the window and not unlocked
+    ///
+    /// A missing row is left to the caller's own existence checks.
+    fn ensure_dans_fenetre<P: Params>(conn: &Connection, jour_query: &str, params: P) -> AppResult<()> {
+        let Some(jours) = Self::get_fenetre(conn)?.jours else {
+            return Ok(());
+        };
+
+        let verrouille: Option<(String, bool)> = conn
+            .query_row(
+                &format!(
+                    "SELECT j.date_jour,
+                            j.date_jour < date('now', 'localtime', '-{jours} days')
+                            AND NOT EXISTS (
+                                SELECT 1 FROM deverrouillages_periode d
+                                WHERE d.bande_id = j.bande_id
+                                  AND j.date_jour BETWEEN d.date_debut AND d.date_fin
+                                  AND d.expire_le > datetime('now')
+                            )
+                     FROM ({jour_query}) j"
+                ),
+                params,
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        match verrouille {
+            Some((date_jour, true)) => Err(AppError::business_logic(&format!(
+                "Le jour du {} n'est plus modifiable (fenêtre de saisie de {} jours): un administrateur doit déverrouiller la période",
+                date_jour, jours
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateVideSanitaire, StatistiquesVideSanitaire, UpdateVideSanitaire, VideSanitaire};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row, ToSql};
+use std::sync::Arc;
+
+/// Colonnes lues pour un `VideSanitaire`, dans l'ordre attendu par `map_vide_row`
+///
+/// La remise en place est la première entrée d'une bande dans le bâtiment
+/// à partir du début du nettoyage.
+const VIDE_SELECT: &str =
+    "SELECT id, ferme_id, numero_batiment, date_debut, date_fin, produits, date_remise_en_place,
+            CAST(julianday(COALESCE(date_remise_en_place, date('now', 'localtime'))) - julianday(date_debut) AS INTEGER) AS jours_vide,
+            created_at
+     FROM (
+        SELECT v.*,
+               (SELECT MIN(bd.date_entree) FROM batiments b
+                JOIN bandes bd ON b.bande_id = bd.id
+                WHERE bd.ferme_id = v.ferme_id AND b.numero_batiment = v.numero_batiment
+                  AND bd.date_entree >= v.date_debut) AS date_remise_en_place
+        FROM vides_sanitaires v
+     )";
+
+fn map_vide_row(row: &Row) -> rusqlite::Result<VideSanitaire> {
+    Ok(VideSanitaire {
+        id: row.get(0)?,
+        ferme_id: row.get(1)?,
+        numero_batiment: row.get(2)?,
+        date_debut: row.get(3)?,
+        date_fin: row.get(4)?,
+        produits: row.get(5)?,
+        date_remise_en_place: row.get(6)?,
+        jours_vide: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// Trait pour les opérations sur les vides sanitaires
+pub trait VideSanitaireRepositoryTrait: Send + Sync {
+    /// Enregistre le nettoyage et la désinfection d'un bâtiment
+    ///
+    /// # Arguments
+    /// * `vide` - Les données du vide sanitaire
+    ///
+    /// # Returns
+    /// Le vide sanitaire créé avec son ID généré
+    async fn create(&self, vide: CreateVideSanitaire) -> AppResult<VideSanitaire>;
+
+    /// Liste les vides sanitaires d'une ferme, du plus récent au plus ancien
+    async fn get_by_ferme(&self, ferme_id: i64) -> AppResult<Vec<VideSanitaire>>;
+
+    /// Met à jour un vide sanitaire (fin du nettoyage, produits utilisés)
+    async fn update(&self, vide: UpdateVideSanitaire) -> AppResult<VideSanitaire>;
+
+    /// Supprime un vide sanitaire
+    async fn delete(&self, id: i64) -> AppResult<()>;
+
+    /// Durée moyenne des vides sanitaires terminés d'une ferme
+    async fn get_statistics(&self, ferme_id: i64) -> AppResult<StatistiquesVideSanitaire>;
+}
+
+/// Repository implementation for cleaning and disinfection periods
+pub struct VideSanitaireRepository {
+    db: Arc<dyn Storage>,
+}
+
+impl VideSanitaireRepository {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Valide les dates du nettoyage: format AAAA-MM-JJ, fin après le début
+    fn validate_dates(date_debut: &str, date_fin: Option<&str>) -> AppResult<()> {
+        let debut = NaiveDate::parse_from_str(date_debut, "%Y-%m-%d").map_err(|_| {
+            AppError::validation_error("date_debut", "La date de début doit être au format AAAA-MM-JJ")
+        })?;
+        if let Some(date_fin) = date_fin {
+            let fin = NaiveDate::parse_from_str(date_fin, "%Y-%m-%d").map_err(|_| {
+                AppError::validation_error("date_fin", "La date de fin doit être au format AAAA-MM-JJ")
+            })?;
+            if fin < debut {
+                return Err(AppError::validation_error(
+                    "date_fin",
+                    "La fin du nettoyage ne peut pas précéder son début"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Vérifie que le numéro désigne un bâtiment de la ferme (1 à `nbr_meuble`)
+    pub(crate) fn validate_batiment(conn: &Connection, ferme_id: i64, numero_batiment: &str) -> AppResult<()> {
+        let nbr_meuble: i64 = conn.query_row(
+            "SELECT nbr_meuble FROM fermes WHERE id = ?1",
+            [ferme_id],
+            |row| row.get(0),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Ferme", ferme_id),
+            _ => AppError::from(e),
+        })?;
+
+        match numero_batiment.parse::<i64>() {
+            Ok(numero) if (1..=nbr_meuble).contains(&numero) => Ok(()),
+            _ => Err(AppError::validation_error(
+                "numero_batiment",
+                &format!("Le bâtiment doit être compris entre 1 et {}", nbr_meuble)
+            )),
+        }
+    }
+
+    fn query(conn: &Connection, condition: &str, order: &str, params: &[&dyn ToSql]) -> AppResult<Vec<VideSanitaire>> {
+        let mut stmt = conn.prepare(&format!("{} WHERE {} ORDER BY {}", VIDE_SELECT, condition, order))?;
+        let vides = stmt.query_map(params, map_vide_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(vides)
+    }
+
+    fn find(conn: &Connection, id: i64) -> AppResult<VideSanitaire> {
+        conn.query_row(&format!("{} WHERE id = ?1", VIDE_SELECT), [id], map_vide_row)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("VideSanitaire", id),
+                _ => AppError::from(e),
+            })
+    }
+}
+
+/// Supprime les espaces superflus, une saisie vide devenant `None`
+fn clean_text(value: &Option<String>) -> Option<String> {
+    value.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+impl VideSanitaireRepositoryTrait for VideSanitaireRepository {
+    async fn create(&self, vide: CreateVideSanitaire) -> AppResult<VideSanitaire> {
+        self.db.write(|tx| {
+            let numero_batiment = vide.numero_batiment.trim();
+            Self::validate_batiment(tx, vide.ferme_id, numero_batiment)?;
+            Self::validate_dates(&vide.date_debut, vide.date_fin.as_deref())?;
+
+            tx.execute(
+                "INSERT INTO vides_sanitaires (ferme_id, numero_batiment, date_debut, date_fin, produits)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![vide.ferme_id, numero_batiment, vide.date_debut, vide.date_fin, clean_text(&vide.produits)],
+            )?;
+
+            Self::find(tx, tx.last_insert_rowid())
+        })
+    }
+
+    async fn get_by_ferme(&self, ferme_id: i64) -> AppResult<Vec<VideSanitaire>> {
+        let conn = self.db.get_connection()?;
+        Self::query(&conn, "ferme_id = ?1", "date_debut DESC, id DESC", &[&ferme_id])
+    }
+
+    async fn update(&self, vide: UpdateVideSanitaire) -> AppResult<VideSanitaire> {
+        self.db.write(|tx| {
+            Self::find(tx, vide.id)?;
+            Self::validate_dates(&vide.date_debut, vide.date_fin.as_deref())?;
+
+            tx.execute(
+                "UPDATE vides_sanitaires SET date_debut = ?1, date_fin = ?2, produits = ?3 WHERE id = ?4",
+                params![vide.date_debut, vide.date_fin, clean_text(&vide.produits), vide.id],
+            )?;
+
+            Self::find(tx, vide.id)
+        })
+    }
+
+    async fn delete(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| {
+            let rows_affected = tx.execute("DELETE FROM vides_sanitaires WHERE id = ?1", [id])?;
+
+            if rows_affected == 0 {
+                return Err(AppError::not_found("VideSanitaire", id));
+            }
+
+            Ok(())
+        })
+    }
+
+    async fn get_statistics(&self, ferme_id: i64) -> AppResult<StatistiquesVideSanitaire> {
+        let conn = self.db.get_connection()?;
+        let (nombre_vides, duree_moyenne_jours) = conn.query_row(
+            &format!(
+                "SELECT COUNT(*), AVG(jours_vide)
+                 FROM ({}) WHERE ferme_id = ?1 AND date_remise_en_place IS NOT NULL",
+                VIDE_SELECT
+            ),
+            [ferme_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(StatistiquesVideSanitaire { ferme_id, nombre_vides, duree_moyenne_jours })
+    }
+}
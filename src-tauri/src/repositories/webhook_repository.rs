@@ -0,0 +1,130 @@
+use crate::error::AppError;
+use crate::models::{EvenementSortant, PARAMETRE_WEBHOOK_URL, TENTATIVES_WEBHOOK_MAX};
+use crate::repositories::ParametreRepository;
+use rusqlite::{params, Connection, Row};
+
+const EVENEMENT_SORTANT_COLUMNS: &str =
+    "id, type_evenement, contenu, created_at, tentatives, prochaine_tentative, envoye_le, derniere_erreur";
+
+/// Longest delay between two delivery attempts, in minutes
+const DELAI_TENTATIVE_MAX_MINUTES: i64 = 360;
+
+/// Repository for the webhook outbox
+///
+/// Events are queued in the same transaction as the change they describe, so
+/// a notification is never sent for a change that was rolled back.
+pub struct WebhookRepository;
+
+impl WebhookRepository {
+    /// Webhook URL, `None` when notifications are disabled
+    pub fn get_url(conn: &Connection) -> Result<Option<String>, AppError> {
+        Ok(ParametreRepository::get(conn, PARAMETRE_WEBHOOK_URL)?.filter(|url| !url.trim().is_empty()))
+    }
+
+    /// Queue an event for delivery, if a webhook URL is configured
+    ///
+    /// # Returns
+    /// The ID of the queued event, `None` when notifications are disabled
+    pub fn enqueue(
+        conn: &Connection,
+        type_evenement: &str,
+        contenu: &serde_json::Value,
+    ) -> Result<Option<i64>, AppError> {
+        if Self::get_url(conn)?.is_none() {
+            return Ok(None);
+        }
+        let corps = serde_json::json!({ "evenement": type_evenement, "donnees": contenu });
+        conn.execute(
+            "INSERT INTO webhook_outbox (type_evenement, contenu, created_at, prochaine_tentative)
+             VALUES (?1, ?2, datetime('now', 'localtime'), datetime('now', 'localtime'))",
+            params![type_evenement, corps.to_string()],
+        )?;
+        Ok(Some(conn.last_insert_rowid()))
+    }
+
+    /// Events due for delivery, oldest first
+    pub fn get_due(conn: &Connection, limit: i64) -> Result<Vec<EvenementSortant>, AppError> {
+        Self::query(
+            conn,
+            "envoye_le IS NULL AND prochaine_tentative <= datetime('now', 'localtime') ORDER BY id LIMIT ?1",
+            params![limit],
+        )
+    }
+
+    /// Latest events, most recent first
+    pub fn get_recent(conn: &Connection, limit: i64) -> Result<Vec<EvenementSortant>, AppError> {
+        Self::query(conn, "1 = 1 ORDER BY id DESC LIMIT ?1", params![limit])
+    }
+
+    /// Mark an event as delivered
+    pub fn mark_sent(conn: &Connection, id: i64) -> Result<(), AppError> {
+        conn.execute(
+            "UPDATE webhook_outbox
+             SET envoye_le = datetime('now', 'localtime'), tentatives = tentatives + 1,
+                 prochaine_tentative = NULL, derniere_erreur = NULL
+             WHERE id = ?1",
+            [id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a failed attempt and schedule the next one
+    ///
+    /// The delay doubles after each attempt; the event is given up after
+    /// `TENTATIVES_WEBHOOK_MAX` attempts.
+    pub fn mark_failed(conn: &Connection, evenement: &EvenementSortant, erreur: &str) -> Result<(), AppError> {
+        let tentatives = evenement.tentatives + 1;
+        let delai = if tentatives >= TENTATIVES_WEBHOOK_MAX {
+            None
+        } else {
+            Some(format!("+{} minutes", (1i64 << (tentatives - 1).min(16)).min(DELAI_TENTATIVE_MAX_MINUTES)))
+        };
+        conn.execute(
+            "UPDATE webhook_outbox
+             SET tentatives = ?2, derniere_erreur = ?3,
+                 prochaine_tentative = CASE WHEN ?4 IS NULL THEN NULL ELSE datetime('now', 'localtime', ?4) END
+             WHERE id = ?1",
+            params![evenement.id, tentatives, erreur, delai],
+        )?;
+        Ok(())
+    }
+
+    /// Schedule an undelivered event for immediate delivery, with a fresh attempt count
+    pub fn retry(conn: &Connection, id: i64) -> Result<EvenementSortant, AppError> {
+        let evenement = Self::query(conn, "id = ?1", params![id])?
+            .pop()
+            .ok_or_else(|| AppError::not_found("EvenementSortant", id))?;
+        if evenement.envoye_le.is_some() {
+            return Err(AppError::business_logic("Cet événement a déjà été envoyé"));
+        }
+        conn.execute(
+            "UPDATE webhook_outbox SET tentatives = 0, prochaine_tentative = datetime('now', 'localtime') WHERE id = ?1",
+            [id],
+        )?;
+        Self::query(conn, "id = ?1", params![id])?
+            .pop()
+            .ok_or_else(|| AppError::not_found("EvenementSortant", id))
+    }
+
+    fn query(conn: &Connection, condition: &str, params: impl rusqlite::Params) -> Result<Vec<EvenementSortant>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM webhook_outbox WHERE {}",
+            EVENEMENT_SORTANT_COLUMNS, condition
+        ))?;
+        let evenements = stmt.query_map(params, map_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(evenements)
+    }
+}
+
+fn map_row(row: &Row) -> rusqlite::Result<EvenementSortant> {
+    Ok(EvenementSortant {
+        id: row.get(0)?,
+        type_evenement: row.get(1)?,
+        contenu: row.get(2)?,
+        created_at: row.get(3)?,
+        tentatives: row.get(4)?,
+        prochaine_tentative: row.get(5)?,
+        envoye_le: row.get(6)?,
+        derniere_erreur: row.get(7)?,
+    })
+}
@@ -0,0 +1,419 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    gravite_alerte, AbonnementAlertes, Alerte, UpdateAbonnementAlertes, ALERTE_DELAI_ATTENTE, ALERTE_ENTRETIEN_EQUIPEMENT,
+    ALERTE_SAISIE_MANQUANTE, ALERTE_VIDE_SANITAIRE, DELAI_RAPPEL_ENTRETIEN_JOURS, DUREE_VIDE_SANITAIRE_JOURS, GRAVITES_ALERTE, HISTORIQUE_ALERTES_MAX, SEMAINES_SUIVI,
+    TYPES_ALERTE,
+};
+use crate::repositories::{
+    AbonnementAlerteRepository, AlerteRepository, EquipementRepository, SuiviQuotidienRepository,
+    SuiviQuotidienRepositoryTrait,
+};
+use crate::services::AuthService;
+use chrono::{Days, Local};
+use rusqlite::{Connection, ToSql};
+use std::sync::Arc;
+
+/// Moteur d'alertes
+///
+/// Calcule à la demande les alertes à partir des données de suivi.
+pub struct AlerteService {
+    db: Arc<dyn Storage>,
+}
+
+impl AlerteService {
+    /// Crée une nouvelle instance du moteur d'alertes
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Alertes d'une bande, qu'elle soit en cours ou déjà enlevée
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    ///
+    /// # Returns
+    /// Les alertes de la bande triées par date
+    pub async fn get_bande_alertes(&self, bande_id: i64) -> AppResult<Vec<Alerte>> {
+        let conn = self.db.get_connection()?;
+        let mut alertes = alertes_delai_attente(&conn, "bd.id = ?1", &[&bande_id])?;
+        let en_cours = bandes_en_cours(&conn, Some(bande_id))?;
+        alertes.extend(self.alertes_saisie_manquante(&en_cours).await?);
+        alertes.sort_by(|a, b| a.date.cmp(&b.date));
+        Ok(alertes)
+    }
+
+    /// Alertes en cours sur toutes les bandes dont l'enlèvement n'est pas passé,
+    /// ainsi que les bâtiments vides prêts à recevoir une nouvelle bande et
+    /// les entretiens d'équipements à prévoir
+    ///
+    /// Les alertes sont inscrites dans l'historique; celles déjà acquittées ne
+    /// sont plus renvoyées. Une alerte escaladée est renvoyée quel que soit
+    /// l'abonnement de l'utilisateur.
+    ///
+    /// # Arguments
+    /// * `user_id` - L'utilisateur dont l'abonnement filtre les alertes, `None` pour toutes
+    ///
+    /// # Returns
+    /// Les alertes triées par date
+    pub async fn get_pending_alerts(&self, user_id: Option<i64>) -> AppResult<Vec<Alerte>> {
+        let conn = self.db.get_connection()?;
+        let mut alertes = alertes_delai_attente(
+            &conn,
+            "date(bd.date_entree, '+' || (sem.derniere_semaine * 7 - 1) || ' days') >= date('now', 'localtime')",
+            &[],
+        )?;
+        alertes.extend(alertes_vide_sanitaire(&conn)?);
+        alertes.extend(alertes_entretien_equipement(&conn)?);
+        let en_cours = bandes_en_cours(&conn, None)?;
+        alertes.extend(self.alertes_saisie_manquante(&en_cours).await?);
+
+        let mut alertes = self.db.write(|tx| AlerteRepository::record(tx, &alertes))?;
+        alertes.retain(|alerte| alerte.acquittee_le.is_none());
+        if let Some(user_id) = user_id {
+            let abonnement = AbonnementAlerteRepository::get(&conn, user_id)?;
+            alertes.retain(|alerte| abonnement.accepte(alerte));
+        }
+        alertes.sort_by(|a, b| a.date.cmp(&b.date));
+        Ok(alertes)
+    }
+
+    /// Acquitte une alerte au nom de l'utilisateur connecté
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de l'alerte dans l'historique
+    /// * `token` - Le token de session de l'utilisateur
+    ///
+    /// # Returns
+    /// L'alerte acquittée, ou une erreur si elle l'était déjà
+    pub async fn acknowledge_alert(&self, id: i64, token: &str) -> AppResult<Alerte> {
+        let user = AuthService::new(self.db.clone()).current_user(token).await?;
+        self.db.ensure_writable()?;
+        self.db.write(|tx| AlerteRepository::acknowledge(tx, id, user.id))
+    }
+
+    /// Historique des alertes détectées, acquittées ou non
+    ///
+    /// # Arguments
+    /// * `ferme_id` - La ferme dont les alertes sont renvoyées, `None` pour toutes
+    /// * `token` - Le token de session de l'utilisateur
+    ///
+    /// # Returns
+    /// Au plus `HISTORIQUE_ALERTES_MAX` alertes, de la plus récente à la plus ancienne
+    pub async fn get_alert_history(&self, ferme_id: Option<i64>, token: &str) -> AppResult<Vec<Alerte>> {
+        AuthService::new(self.db.clone()).current_user(token).await?;
+        let conn = self.db.get_connection()?;
+        AlerteRepository::get_history(&conn, ferme_id, HISTORIQUE_ALERTES_MAX)
+    }
+
+    /// Abonnement aux alertes de l'utilisateur connecté
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session de l'utilisateur
+    ///
+    /// # Returns
+    /// L'abonnement enregistré, toutes les alertes si rien n'a été enregistré
+    pub async fn get_alert_subscription(&self, token: &str) -> AppResult<AbonnementAlertes> {
+        let user = AuthService::new(self.db.clone()).current_user(token).await?;
+        let conn = self.db.get_connection()?;
+        AbonnementAlerteRepository::get(&conn, user.id)
+    }
+
+    /// Enregistre l'abonnement aux alertes de l'utilisateur connecté
+    ///
+    /// # Arguments
+    /// * `abonnement` - Types d'alertes, fermes et gravité minimale retenus
+    /// * `token` - Le token de session de l'utilisateur
+    ///
+    /// # Returns
+    /// L'abonnement enregistré
+    pub async fn set_alert_subscription(
+        &self,
+        abonnement: UpdateAbonnementAlertes,
+        token: &str,
+    ) -> AppResult<AbonnementAlertes> {
+        let user = AuthService::new(self.db.clone()).current_user(token).await?;
+
+        let mut types_alerte = Vec::new();
+        for type_alerte in &abonnement.types_alerte {
+            let type_alerte = type_alerte.trim().to_lowercase();
+            if !TYPES_ALERTE.contains(&type_alerte.as_str()) {
+                return Err(AppError::validation_error(
+                    "types_alerte",
+                    &format!("Type d'alerte non reconnu. Types valides: {}", TYPES_ALERTE.join(", "))
+                ));
+            }
+            if !types_alerte.contains(&type_alerte) {
+                types_alerte.push(type_alerte);
+            }
+        }
+
+        let gravite_min = abonnement.gravite_min.trim().to_lowercase();
+        if !GRAVITES_ALERTE.contains(&gravite_min.as_str()) {
+            return Err(AppError::validation_error(
+                "gravite_min",
+                &format!("Gravité non reconnue. Gravités valides: {}", GRAVITES_ALERTE.join(", "))
+            ));
+        }
+
+        self.db.write(|tx| {
+            let mut ferme_ids = Vec::new();
+            for &ferme_id in &abonnement.ferme_ids {
+                let ferme_exists: i64 = tx.query_row(
+                    "SELECT COUNT(*) FROM fermes WHERE id = ?1",
+                    [ferme_id],
+                    |row| row.get(0),
+                )?;
+                if ferme_exists == 0 {
+                    return Err(AppError::not_found("Ferme", ferme_id));
+                }
+                if !ferme_ids.contains(&ferme_id) {
+                    ferme_ids.push(ferme_id);
+                }
+            }
+
+            let abonnement = AbonnementAlertes { user_id: user.id, types_alerte, ferme_ids, gravite_min };
+            AbonnementAlerteRepository::set(tx, &abonnement)?;
+            Ok(abonnement)
+        })
+    }
+
+    /// Une alerte par bâtiment dont des jours passés n'ont pas de décès ou
+    /// d'alimentation saisis (le jour en cours peut encore être saisi)
+    async fn alertes_saisie_manquante(&self, bande_ids: &[i64]) -> AppResult<Vec<Alerte>> {
+        let hier = Local::now().date_naive() - Days::new(1);
+        let repository = SuiviQuotidienRepository::new(self.db.clone());
+        let mut alertes = Vec::new();
+
+        for &bande_id in bande_ids {
+            let completude = repository.get_entry_completeness(bande_id, hier).await?;
+            for batiment in completude.batiments {
+                let manquants: Vec<_> = batiment
+                    .jours
+                    .iter()
+                    .filter(|jour| !jour.deces_saisi || !jour.alimentation_saisie)
+                    .collect();
+                let (Some(premier), Some(dernier)) = (manquants.first(), manquants.last()) else {
+                    continue;
+                };
+                alertes.push(Alerte {
+                    type_alerte: ALERTE_SAISIE_MANQUANTE.to_string(),
+                    gravite: gravite_alerte(ALERTE_SAISIE_MANQUANTE).to_string(),
+                    ferme_id: completude.ferme_id,
+                    bande_id: Some(bande_id),
+                    batiment_id: Some(batiment.batiment_id),
+                    message: format!(
+                        "Bâtiment {}: {} jour(s) sans décès ou alimentation saisis entre le {} et le {}",
+                        batiment.numero_batiment,
+                        manquants.len(),
+                        premier.date,
+                        dernier.date
+                    ),
+                    date: dernier.date.to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        Ok(alertes)
+    }
+}
+
+/// Bandes actives dont le suivi n'est pas terminé (au moins `SEMAINES_SUIVI` semaines)
+fn bandes_en_cours(conn: &Connection, bande_id: Option<i64>) -> AppResult<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT bd.id FROM bandes bd
+         WHERE bd.statut = 'active' AND (?1 IS NULL OR bd.id = ?1)
+           AND date(bd.date_entree, '+' || (MAX(?2, COALESCE((
+                   SELECT MAX(s.numero_semaine) FROM semaines s
+                   JOIN batiments b ON s.batiment_id = b.id
+                   WHERE b.bande_id = bd.id
+               ), 0)) * 7 - 1) || ' days') >= date('now', 'localtime')
+         ORDER BY bd.id",
+    )?;
+    let ids = stmt
+        .query_map(rusqlite::params![bande_id, SEMAINES_SUIVI], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Bâtiments prêts à recevoir une nouvelle bande
+///
+/// Le dernier vide sanitaire d'un bâtiment déclenche l'alerte lorsque le
+/// nettoyage est terminé, qu'au moins `DUREE_VIDE_SANITAIRE_JOURS` jours se
+/// sont écoulés depuis son début et qu'aucune bande n'y est entrée depuis.
+/// L'alerte est rattachée à la dernière bande qui a occupé le bâtiment; un
+/// bâtiment jamais occupé n'en déclenche pas.
+fn alertes_vide_sanitaire(conn: &Connection) -> AppResult<Vec<Alerte>> {
+    let mut stmt = conn.prepare(
+        "SELECT v.ferme_id, precedent.bande_id, precedent.batiment_id, v.numero_batiment, v.date_fin,
+                date(v.date_debut, '+' || ?1 || ' days') AS date_disponible
+         FROM vides_sanitaires v
+         JOIN (
+            SELECT bd.ferme_id, b.numero_batiment, bd.id AS bande_id, b.id AS batiment_id, bd.date_entree
+            FROM batiments b
+            JOIN bandes bd ON b.bande_id = bd.id
+         ) precedent ON precedent.ferme_id = v.ferme_id
+                    AND precedent.numero_batiment = v.numero_batiment
+                    AND precedent.date_entree < v.date_debut
+         WHERE v.date_fin IS NOT NULL
+           AND date(v.date_debut, '+' || ?1 || ' days') <= date('now', 'localtime')
+           AND NOT EXISTS (
+               SELECT 1 FROM vides_sanitaires suivant
+               WHERE suivant.ferme_id = v.ferme_id AND suivant.numero_batiment = v.numero_batiment
+                 AND (suivant.date_debut > v.date_debut OR (suivant.date_debut = v.date_debut AND suivant.id > v.id))
+           )
+           AND NOT EXISTS (
+               SELECT 1 FROM batiments b
+               JOIN bandes bd ON b.bande_id = bd.id
+               WHERE bd.ferme_id = v.ferme_id AND b.numero_batiment = v.numero_batiment
+                 AND bd.date_entree >= v.date_debut
+           )
+           AND NOT EXISTS (
+               SELECT 1 FROM batiments b
+               JOIN bandes bd ON b.bande_id = bd.id
+               WHERE bd.ferme_id = v.ferme_id AND b.numero_batiment = v.numero_batiment
+                 AND bd.date_entree < v.date_debut
+                 AND (bd.date_entree > precedent.date_entree
+                      OR (bd.date_entree = precedent.date_entree AND b.id > precedent.batiment_id))
+           )
+         ORDER BY date_disponible, v.numero_batiment",
+    )?;
+
+    let alertes = stmt.query_map([DUREE_VIDE_SANITAIRE_JOURS], |row| {
+        let numero_batiment: String = row.get(3)?;
+        let date_fin: String = row.get(4)?;
+        let date_disponible: String = row.get(5)?;
+
+        Ok(Alerte {
+            type_alerte: ALERTE_VIDE_SANITAIRE.to_string(),
+            gravite: gravite_alerte(ALERTE_VIDE_SANITAIRE).to_string(),
+            ferme_id: row.get(0)?,
+            bande_id: row.get(1)?,
+            batiment_id: Some(row.get(2)?),
+            message: format!(
+                "Bâtiment {}: nettoyage terminé le {}, vide sanitaire de {} jours atteint le {}, le bâtiment peut recevoir une nouvelle bande",
+                numero_batiment, date_fin, DUREE_VIDE_SANITAIRE_JOURS, date_disponible
+            ),
+            date: date_disponible,
+            ..Default::default()
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(alertes)
+}
+
+/// Équipements dont l'entretien est dû dans les `DELAI_RAPPEL_ENTRETIEN_JOURS` prochains jours ou en retard
+///
+/// Un équipement appartient à la ferme et non à une bande: l'alerte est
+/// rattachée à la ferme seule (sans bande ni bâtiment), qu'une bande y soit
+/// en cours ou non. Les entretiens dus le même jour dans la même ferme sont
+/// regroupés dans une seule alerte.
+fn alertes_entretien_equipement(conn: &Connection) -> AppResult<Vec<Alerte>> {
+    let aujourd_hui = Local::now().date_naive();
+    let date_limite = (aujourd_hui + Days::new(DELAI_RAPPEL_ENTRETIEN_JOURS as u64)).format("%Y-%m-%d").to_string();
+    let aujourd_hui = aujourd_hui.format("%Y-%m-%d").to_string();
+    let equipements = EquipementRepository::entretiens_a_prevoir(conn, &date_limite)?;
+
+    let mut alertes: Vec<Alerte> = Vec::new();
+    for equipement in equipements {
+        let Some(echeance) = equipement.prochain_entretien else {
+            continue;
+        };
+
+        let emplacement = match &equipement.numero_batiment {
+            Some(numero) => format!("Bâtiment {}, {}", numero, equipement.nom),
+            None => equipement.nom.clone(),
+        };
+        let etat = if echeance < aujourd_hui {
+            "en retard depuis le"
+        } else {
+            "prévu le"
+        };
+        let message = format!("{}: entretien {} {}", emplacement, etat, echeance);
+
+        match alertes
+            .iter_mut()
+            .find(|a| a.ferme_id == equipement.ferme_id && a.date == echeance)
+        {
+            Some(alerte) => alerte.message = format!("{}; {}", alerte.message, message),
+            None => alertes.push(Alerte {
+                type_alerte: ALERTE_ENTRETIEN_EQUIPEMENT.to_string(),
+                gravite: gravite_alerte(ALERTE_ENTRETIEN_EQUIPEMENT).to_string(),
+                ferme_id: equipement.ferme_id,
+                bande_id: None,
+                batiment_id: None,
+                message,
+                date: echeance,
+                ..Default::default()
+            }),
+        }
+    }
+
+    Ok(alertes)
+}
+
+/// Enlèvements prévus pendant le délai d'attente d'un soin
+///
+/// L'enlèvement d'un bâtiment est projeté au dernier jour de sa dernière
+/// semaine (jour 1 = date d'entrée de la bande). Pour chaque soin à délai
+/// d'attente, seule la dernière administration du bâtiment est retenue: elle
+/// déclenche l'alerte si l'abattage n'est autorisé qu'après l'enlèvement.
+fn alertes_delai_attente(conn: &Connection, condition: &str, params: &[&dyn ToSql]) -> AppResult<Vec<Alerte>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT ferme_id, bande_id, batiment_id, numero_batiment, soin_nom, delai_attente_jours,
+                derniere_administration, date_autorisee, date_enlevement
+         FROM (
+            SELECT bd.ferme_id, bd.id AS bande_id, b.id AS batiment_id, b.numero_batiment,
+                   so.nom AS soin_nom, so.delai_attente_jours,
+                   date(bd.date_entree, '+' || (MAX(sq.age) - 1) || ' days') AS derniere_administration,
+                   date(bd.date_entree, '+' || (MAX(sq.age) - 1 + so.delai_attente_jours) || ' days') AS date_autorisee,
+                   date(bd.date_entree, '+' || (sem.derniere_semaine * 7 - 1) || ' days') AS date_enlevement
+            FROM suivi_soins ss
+            JOIN soins so ON ss.soin_id = so.id
+            JOIN suivi_quotidien sq ON ss.suivi_id = sq.id
+            JOIN semaines s ON sq.semaine_id = s.id
+            JOIN batiments b ON s.batiment_id = b.id
+            JOIN bandes bd ON b.bande_id = bd.id
+            JOIN (
+                SELECT batiment_id, MAX(numero_semaine) AS derniere_semaine
+                FROM semaines GROUP BY batiment_id
+            ) sem ON sem.batiment_id = b.id
+            WHERE so.delai_attente_jours > 0 AND {}
+            GROUP BY b.id, so.id
+         )
+         WHERE date_autorisee > date_enlevement
+         ORDER BY date_enlevement, numero_batiment, soin_nom",
+        condition
+    ))?;
+
+    let alertes = stmt.query_map(params, |row| {
+        let numero_batiment: String = row.get(3)?;
+        let soin_nom: String = row.get(4)?;
+        let delai: i32 = row.get(5)?;
+        let derniere_administration: String = row.get(6)?;
+        let date_autorisee: String = row.get(7)?;
+        let date_enlevement: String = row.get(8)?;
+
+        Ok(Alerte {
+            type_alerte: ALERTE_DELAI_ATTENTE.to_string(),
+            gravite: gravite_alerte(ALERTE_DELAI_ATTENTE).to_string(),
+            ferme_id: row.get(0)?,
+            bande_id: row.get(1)?,
+            batiment_id: Some(row.get(2)?),
+            message: format!(
+                "Bâtiment {}: {} administré le {} (délai d'attente {} jours, abattage autorisé à partir du {}) alors que l'enlèvement est prévu le {}",
+                numero_batiment, soin_nom, derniere_administration, delai, date_autorisee, date_enlevement
+            ),
+            date: date_enlevement,
+            ..Default::default()
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(alertes)
+}
@@ -0,0 +1,91 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::Analyse;
+use crate::repositories::{AnalyseRepository, AnalyseRepositoryTrait};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Service de gestion des comptes rendus d'analyses
+/// 
+/// Les PDF sont copiés dans un dossier de l'application afin que l'analyse
+/// reste consultable même si le fichier d'origine est déplacé ou supprimé.
+pub struct AnalyseService {
+    repo: AnalyseRepository,
+}
+
+impl AnalyseService {
+    /// Crée une nouvelle instance du service d'analyses
+    /// 
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { repo: AnalyseRepository::new(db) }
+    }
+
+    /// Joint un compte rendu PDF à une analyse
+    /// 
+    /// Le fichier est copié sous `dossier/analyse-{id}.pdf`, remplaçant un
+    /// éventuel compte rendu précédent.
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'analyse
+    /// * `source` - Le chemin du PDF choisi par l'utilisateur
+    /// * `dossier` - Le dossier de stockage des comptes rendus
+    /// 
+    /// # Returns
+    /// L'analyse mise à jour
+    pub async fn attach_pdf(&self, id: i64, source: &Path, dossier: &Path) -> AppResult<Analyse> {
+        let is_pdf = source
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("pdf"))
+            .unwrap_or(false);
+        if !is_pdf {
+            return Err(AppError::validation_error("fichier_pdf", "Le compte rendu doit être un fichier PDF"));
+        }
+        if !source.is_file() {
+            return Err(AppError::validation_error("fichier_pdf", "Le fichier sélectionné est introuvable"));
+        }
+
+        // Vérifie que l'analyse existe avant de copier le fichier
+        self.repo.get_by_id(id).await?;
+
+        std::fs::create_dir_all(dossier)?;
+        let destination: PathBuf = dossier.join(format!("analyse-{}.pdf", id));
+        std::fs::copy(source, &destination)?;
+
+        self.repo
+            .set_fichier_pdf(id, Some(destination.to_string_lossy().into_owned()))
+            .await
+    }
+
+    /// Retire le compte rendu PDF d'une analyse et supprime la copie
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'analyse
+    /// 
+    /// # Returns
+    /// L'analyse mise à jour
+    pub async fn detach_pdf(&self, id: i64) -> AppResult<Analyse> {
+        let analyse = self.repo.get_by_id(id).await?;
+        remove_file(analyse.fichier_pdf.as_deref());
+        self.repo.set_fichier_pdf(id, None).await
+    }
+
+    /// Supprime une analyse et sa copie du compte rendu
+    /// 
+    /// # Arguments
+    /// * `id` - L'ID de l'analyse à supprimer
+    pub async fn delete_analyse(&self, id: i64) -> AppResult<()> {
+        let analyse = self.repo.get_by_id(id).await?;
+        self.repo.delete(id).await?;
+        remove_file(analyse.fichier_pdf.as_deref());
+        Ok(())
+    }
+}
+
+/// Supprime une copie de compte rendu; un fichier déjà absent n'est pas une erreur
+fn remove_file(path: Option<&str>) {
+    if let Some(path) = path {
+        let _ = std::fs::remove_file(path);
+    }
+}
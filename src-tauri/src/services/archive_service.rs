@@ -0,0 +1,101 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    BandeArchivee, ResultatArchivage, ARCHIVAGE_ANNEES_DEFAUT, AUDIT_ARCHIVAGE_BANDE, AUDIT_ENTITE_BANDE,
+    AUDIT_RESTAURATION_BANDE, PARAMETRE_ARCHIVAGE_ANNEES,
+};
+use crate::repositories::{ArchiveRepository, AuditRepository, ParametreRepository};
+use crate::services::AuthService;
+use chrono::{Local, Months, NaiveDate};
+use std::sync::Arc;
+
+/// Service d'archivage des anciennes bandes
+///
+/// Les bandes clôturées depuis plus de N années sont déplacées, avec leurs
+/// bâtiments, semaines, suivis, livraisons, analyses, notes, litières et
+/// toutes les autres lignes qui en dépendent, dans une base d'archive voisine
+/// de la base principale. La base principale reste ainsi petite; les bandes
+/// archivées restent consultables en lecture seule et peuvent être restaurées.
+pub struct ArchiveService {
+    db: Arc<dyn Storage>,
+}
+
+impl ArchiveService {
+    /// Crée une nouvelle instance du service d'archivage
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Archive les bandes clôturées depuis plus de `annees` années (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `annees` - L'ancienneté en années, enregistrée pour les archivages suivants;
+    ///   l'ancienneté enregistrée (3 ans par défaut) si elle est absente
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// L'ancienneté appliquée, la date limite et les IDs des bandes archivées
+    pub async fn archive_old_bandes(&self, annees: Option<i32>, token: &str) -> AppResult<ResultatArchivage> {
+        let admin = AuthService::new(self.db.clone()).require_admin(token).await?;
+        if annees.is_some_and(|annees| annees < 1) {
+            return Err(AppError::validation_error("annees", "L'ancienneté doit être d'au moins un an"));
+        }
+
+        self.db.write(|tx| {
+            let annees = match annees {
+                Some(annees) => {
+                    ParametreRepository::set(tx, PARAMETRE_ARCHIVAGE_ANNEES, &annees.to_string())?;
+                    annees
+                }
+                None => ParametreRepository::get(tx, PARAMETRE_ARCHIVAGE_ANNEES)?
+                    .and_then(|valeur| valeur.parse().ok())
+                    .unwrap_or(ARCHIVAGE_ANNEES_DEFAUT),
+            };
+            let date_limite = date_limite(Local::now().date_naive(), annees);
+
+            let bandes_archivees = ArchiveRepository::archive_bandes(tx, date_limite)?;
+            for &bande_id in &bandes_archivees {
+                AuditRepository::log(tx, admin.id, AUDIT_ARCHIVAGE_BANDE, AUDIT_ENTITE_BANDE, bande_id, None)?;
+            }
+
+            Ok(ResultatArchivage { annees, date_limite, bandes_archivees })
+        })
+    }
+
+    /// Ramène une bande archivée dans la base principale (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande archivée
+    /// * `token` - Le token de session d'un administrateur
+    pub async fn restore_archived_bande(&self, bande_id: i64, token: &str) -> AppResult<()> {
+        let admin = AuthService::new(self.db.clone()).require_admin(token).await?;
+        self.db.write(|tx| {
+            ArchiveRepository::restore_bande(tx, bande_id)?;
+            AuditRepository::log(tx, admin.id, AUDIT_RESTAURATION_BANDE, AUDIT_ENTITE_BANDE, bande_id, None)
+        })
+    }
+
+    /// Liste les bandes archivées
+    ///
+    /// # Arguments
+    /// * `ferme_id` - Limite la liste à une ferme si renseigné
+    /// * `token` - Le token de session de l'utilisateur
+    ///
+    /// # Returns
+    /// Le résumé des bandes archivées, des plus récentes aux plus anciennes
+    pub async fn query_archive(&self, ferme_id: Option<i64>, token: &str) -> AppResult<Vec<BandeArchivee>> {
+        AuthService::new(self.db.clone()).current_user(token).await?;
+        let conn = self.db.get_connection()?;
+        ArchiveRepository::get_bandes(&conn, ferme_id)
+    }
+}
+
+/// Date avant laquelle une bande clôturée est archivée
+fn date_limite(aujourd_hui: NaiveDate, annees: i32) -> NaiveDate {
+    aujourd_hui
+        .checked_sub_months(Months::new(12 * annees as u32))
+        .unwrap_or(NaiveDate::MIN)
+}
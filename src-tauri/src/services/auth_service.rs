@@ -1,49 +1,69 @@
-use crate::database::DatabaseManager;
-use crate::models::{User, CreateUser, LoginUser, UserPublic, AuthResponse};
-use crate::repositories::{UserRepository, UserRepositoryTrait};
+use crate::database::Storage;
+use crate::models::{User, CreateUser, LoginUser, UserPublic, AuthResponse, CreateInvitation, Invitation, MfaEnrollment, UserMfa, ROLE_ADMIN, ROLE_TECHNICIEN};
+use crate::repositories::{InvitationRepository, MfaRepository, UserRepository, UserRepositoryTrait};
+use crate::services::totp;
 use crate::commands::auth_commands::{UpdateProfileData, UpdatePasswordData};
 use crate::error::AppError;
+use crate::models::{ActiviteUtilisateur, AUDIT_CONNEXION, AUDIT_ENTITE_UTILISATEUR, AUDIT_REINITIALISATION_MOT_DE_PASSE};
+use crate::repositories::AuditRepository;
 use std::sync::Arc;
+use chrono::{Local, NaiveDate, Utc};
+use rusqlite::{Connection, OptionalExtension};
 use uuid::Uuid;
-use std::collections::HashMap;
-use std::sync::Mutex;
+
+/// Nombre de bandes les plus saisies listées par utilisateur dans son activité
+const BANDES_ACTIVITE_MAX: usize = 3;
 
 /// Service pour la gestion de l'authentification
+///
+/// Les tokens sont enregistrés dans la table `sessions`: une instance du
+/// service est créée par commande, ils doivent survivre à celle-ci (et au
+/// redémarrage de l'application).
 pub struct AuthService {
-    db_manager: Arc<DatabaseManager>,
-    // Stockage simple en mémoire des tokens (pour une vraie app, utiliser Redis ou JWT)
-    active_tokens: Arc<Mutex<HashMap<String, i64>>>, // token -> user_id
+    db_manager: Arc<dyn Storage>,
 }
 
 impl AuthService {
-    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
-        Self {
-            db_manager,
-            active_tokens: Arc::new(Mutex::new(HashMap::new())),
-        }
+    pub fn new(db_manager: Arc<dyn Storage>) -> Self {
+        Self { db_manager }
     }
 
-    /// Enregistre un nouvel utilisateur avec un code de registration
+    /// Enregistre un nouvel utilisateur avec un code d'invitation
+    ///
+    /// Le premier compte de l'application n'a pas besoin d'invitation et en
+    /// devient l'administrateur; les suivants reçoivent le rôle de leur invitation.
     pub async fn register(&self, user_data: CreateUser) -> Result<AuthResponse, AppError> {
-        // Vérifie le code de registration (code secret hardcodé pour simplifier)
-        const SECRET_CODE: &str = "FERME2024";
-        if user_data.registration_code != SECRET_CODE {
-            return Err(AppError::validation_error("registration_code", "Code d'enregistrement invalide"));
-        }
-
-        let conn = self.db_manager.get_connection()?;
-        let repository = UserRepository::new(&conn);
-
-        // Vérifie si l'utilisateur existe déjà
-        if repository.user_exists(&user_data.username, &user_data.email)? {
-            return Err(AppError::validation_error("user", "Un utilisateur avec ce nom d'utilisateur ou cet email existe déjà"));
-        }
-
         // Valide les données
         self.validate_user_data(&user_data)?;
 
-        // Crée l'utilisateur
-        let user = repository.create_user(user_data)?;
+        // Le code est consommé dans la même écriture que la création du compte,
+        // un code à usage unique ne peut donc servir qu'une fois
+        let user = self.db_manager.write(|tx| {
+            let repository = UserRepository::new(tx);
+
+            // Vérifie si l'utilisateur existe déjà
+            if repository.user_exists(&user_data.username, &user_data.email)? {
+                return Err(AppError::validation_error("user", "Un utilisateur avec ce nom d'utilisateur ou cet email existe déjà"));
+            }
+
+            let user_count: i64 = tx.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
+            let invitation = if user_count == 0 {
+                None
+            } else {
+                let code = user_data.registration_code.trim();
+                let invitation = InvitationRepository::find_usable(tx, code, Local::now().date_naive())?
+                    .ok_or_else(|| AppError::validation_error("registration_code", "Code d'invitation invalide, expiré ou déjà utilisé"))?;
+                Some(invitation)
+            };
+
+            // Crée l'utilisateur
+            let role = invitation.as_ref().map_or(ROLE_ADMIN, |invitation| invitation.role.as_str());
+            let user = repository.create_user(user_data, role)?;
+            if let Some(invitation) = &invitation {
+                InvitationRepository::mark_used(tx, invitation.id, user.id)?;
+            }
+            Ok(user)
+        })?;
 
         // Génère un token
         let token = self.generate_token(&user)?;
@@ -56,46 +76,259 @@ impl AuthService {
 
     /// Authentifie un utilisateur
     pub async fn login(&self, login_data: LoginUser) -> Result<AuthResponse, AppError> {
-        let conn = self.db_manager.get_connection()?;
-        let repository = UserRepository::new(&conn);
+        let totp_code = login_data.totp_code.clone();
 
-        // Authentifie l'utilisateur
-        match repository.authenticate_user(login_data)? {
-            Some(user) => {
-                let token = self.generate_token(&user)?;
-                Ok(AuthResponse {
-                    user: user.into(),
-                    token,
-                })
-            }
-            None => Err(AppError::validation_error("credentials", "Nom d'utilisateur ou mot de passe incorrect")),
-        }
+        // Authentifie l'utilisateur (lecture seule: la vérification du mot de
+        // passe est lente et ne doit pas bloquer les écritures)
+        let user = {
+            let conn = self.db_manager.get_connection()?;
+            UserRepository::new(&conn).authenticate_user(login_data)?
+        };
+        let user = user
+            .ok_or_else(|| AppError::validation_error("credentials", "Nom d'utilisateur ou mot de passe incorrect"))?;
+
+        self.verify_second_factor(&user, totp_code.as_deref())?;
+        let token = self.db_manager.write(|tx| {
+            let token = Self::create_session(tx, user.id)?;
+            AuditRepository::log(tx, user.id, AUDIT_CONNEXION, AUDIT_ENTITE_UTILISATEUR, user.id, None)?;
+            Ok(token)
+        })?;
+        Ok(AuthResponse {
+            user: user.into(),
+            token,
+        })
     }
 
     /// Déconnecte un utilisateur
     pub async fn logout(&self, token: &str) -> Result<(), AppError> {
-        let mut tokens = self.active_tokens.lock()
-            .map_err(|_| AppError::business_logic("Failed to lock tokens"))?;
-        
-        tokens.remove(token);
-        Ok(())
+        self.db_manager.write(|tx| {
+            tx.execute("DELETE FROM sessions WHERE token = ?1", [token])?;
+            Ok(())
+        })
     }
 
     /// Vérifie si un token est valide
     pub async fn verify_token(&self, token: &str) -> Result<Option<UserPublic>, AppError> {
-        let tokens = self.active_tokens.lock()
-            .map_err(|_| AppError::business_logic("Failed to lock tokens"))?;
+        Ok(self.find_session_user(token)?.map(UserPublic::from))
+    }
 
-        if let Some(&user_id) = tokens.get(token) {
-            let conn = self.db_manager.get_connection()?;
-            let repository = UserRepository::new(&conn);
-            
-            if let Some(user) = repository.get_user_by_id(user_id)? {
-                return Ok(Some(user.into()));
+    /// Récupère l'utilisateur connecté avec ce token
+    ///
+    /// Un utilisateur dont le mot de passe a été réinitialisé doit d'abord le
+    /// changer: ses opérations sont refusées jusque-là.
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session transmis par le frontend
+    ///
+    /// # Returns
+    /// L'utilisateur, ou une erreur si la session n'existe pas (ou plus)
+    pub async fn current_user(&self, token: &str) -> Result<User, AppError> {
+        let user = self.find_session_user(token)?
+            .ok_or_else(|| AppError::validation_error("token", "Session invalide ou expirée, veuillez vous reconnecter"))?;
+        if user.must_change_password {
+            return Err(AppError::validation_error(
+                "password",
+                "Votre mot de passe a été réinitialisé, veuillez le changer avant de continuer",
+            ));
+        }
+        Ok(user)
+    }
+
+    /// Récupère l'ID de l'auteur d'une écriture, enregistré dans `created_by`
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session transmis par le frontend, s'il y en a un
+    ///
+    /// # Returns
+    /// `None` sans token, une erreur si la session n'existe pas (ou plus)
+    pub async fn author_id(&self, token: Option<&str>) -> Result<Option<i64>, AppError> {
+        match token {
+            Some(token) => Ok(Some(self.current_user(token).await?.id)),
+            None => Ok(None),
+        }
+    }
+
+    /// Récupère l'utilisateur connecté et vérifie qu'il est administrateur
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session transmis par le frontend
+    ///
+    /// # Returns
+    /// L'administrateur, ou une erreur si l'utilisateur n'a pas ce rôle
+    pub async fn require_admin(&self, token: &str) -> Result<User, AppError> {
+        let user = self.current_user(token).await?;
+        if !user.is_admin() {
+            return Err(AppError::business_logic("Cette opération est réservée aux administrateurs"));
+        }
+        Ok(user)
+    }
+
+    /// Réinitialise le mot de passe d'un utilisateur qui l'a oublié
+    ///
+    /// Un mot de passe temporaire est généré et l'utilisateur devra le changer
+    /// après sa connexion; ses sessions en cours sont fermées. La réinitialisation
+    /// est enregistrée dans le journal d'audit.
+    ///
+    /// # Arguments
+    /// * `user_id` - L'ID de l'utilisateur
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// Le mot de passe temporaire, à transmettre à l'utilisateur
+    pub async fn reset_user_password(&self, user_id: i64, token: &str) -> Result<String, AppError> {
+        let admin = self.require_admin(token).await?;
+        let temporary_password = Uuid::new_v4().simple().to_string()[..10].to_string();
+
+        self.db_manager.write(|tx| {
+            UserRepository::new(tx).reset_user_password(user_id, &temporary_password)?;
+            tx.execute("DELETE FROM sessions WHERE user_id = ?1", [user_id])?;
+            AuditRepository::log(tx, admin.id, AUDIT_REINITIALISATION_MOT_DE_PASSE, AUDIT_ENTITE_UTILISATEUR, user_id, None)
+        })?;
+
+        Ok(temporary_password)
+    }
+
+    /// Crée un code d'invitation (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `invitation` - Le code (généré s'il est absent), le rôle attribué, l'expiration et l'usage unique
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// L'invitation créée
+    pub async fn create_invitation(&self, invitation: CreateInvitation, token: &str) -> Result<Invitation, AppError> {
+        let admin = self.require_admin(token).await?;
+
+        if invitation.role != ROLE_ADMIN && invitation.role != ROLE_TECHNICIEN {
+            return Err(AppError::validation_error("role", "Le rôle doit être 'admin' ou 'technicien'"));
+        }
+        if invitation.expires_on.is_some_and(|date| date < Local::now().date_naive()) {
+            return Err(AppError::validation_error("expires_on", "La date d'expiration est déjà passée"));
+        }
+        let code = match invitation.code.as_deref().map(str::trim) {
+            Some(code) if !code.is_empty() => code.to_string(),
+            _ => Uuid::new_v4().simple().to_string()[..8].to_uppercase(),
+        };
+        if code.len() < 6 {
+            return Err(AppError::validation_error("code", "Le code d'invitation doit contenir au moins 6 caractères"));
+        }
+
+        self.db_manager.write(|tx| {
+            let exists: i64 = tx.query_row("SELECT COUNT(*) FROM invitations WHERE code = ?1", [&code], |row| row.get(0))?;
+            if exists > 0 {
+                return Err(AppError::validation_error("code", "Ce code d'invitation existe déjà"));
+            }
+            InvitationRepository::create(tx, &code, &invitation.role, invitation.expires_on, invitation.single_use, admin.id)
+        })
+    }
+
+    /// Révoque un code d'invitation (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de l'invitation
+    /// * `token` - Le token de session d'un administrateur
+    pub async fn revoke_invitation(&self, id: i64, token: &str) -> Result<(), AppError> {
+        self.require_admin(token).await?;
+        self.db_manager.write(|tx| InvitationRepository::revoke(tx, id))
+    }
+
+    /// Liste les codes d'invitation, du plus récent au plus ancien (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session d'un administrateur
+    pub async fn get_invitations(&self, token: &str) -> Result<Vec<Invitation>, AppError> {
+        self.require_admin(token).await?;
+        let conn = self.db_manager.get_connection()?;
+        InvitationRepository::get_all(&conn)
+    }
+
+    /// Résume l'activité de chaque utilisateur sur une période (réservé aux administrateurs)
+    ///
+    /// Compte les cellules du suivi quotidien saisies par chaque utilisateur,
+    /// avec sa dernière connexion et les bandes où il a le plus saisi.
+    ///
+    /// # Arguments
+    /// * `date_from` - Début de la période, `None` pour ne pas la limiter
+    /// * `date_to` - Fin de la période (incluse), `None` pour ne pas la limiter
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// L'activité de chaque utilisateur, du plus actif au moins actif
+    pub async fn get_user_activity(
+        &self,
+        date_from: Option<NaiveDate>,
+        date_to: Option<NaiveDate>,
+        token: &str,
+    ) -> Result<Vec<ActiviteUtilisateur>, AppError> {
+        self.require_admin(token).await?;
+        if let (Some(date_from), Some(date_to)) = (date_from, date_to) {
+            if date_from > date_to {
+                return Err(AppError::validation_error("date_to", "La fin de la période ne peut pas précéder son début"));
             }
         }
+        let conn = self.db_manager.get_connection()?;
+        AuditRepository::get_user_activity(&conn, date_from, date_to, BANDES_ACTIVITE_MAX)
+    }
 
-        Ok(None)
+    /// Démarre l'enrôlement du second facteur (TOTP) d'un administrateur
+    ///
+    /// Le code n'est exigé à la connexion qu'après confirmation de l'enrôlement
+    /// avec `confirm_mfa_enrollment`.
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session de l'administrateur
+    ///
+    /// # Returns
+    /// Le secret et l'URI à afficher sous forme de QR code
+    pub async fn start_mfa_enrollment(&self, token: &str) -> Result<MfaEnrollment, AppError> {
+        let user = self.require_admin(token).await?;
+        let secret = totp::generer_secret();
+
+        self.db_manager.write(|tx| {
+            if MfaRepository::get(tx, user.id)?.is_some_and(|mfa| mfa.enabled) {
+                return Err(AppError::business_logic("Le second facteur est déjà activé, désactivez-le avant d'en enregistrer un nouveau"));
+            }
+            MfaRepository::save_pending(tx, user.id, &secret)
+        })?;
+
+        Ok(MfaEnrollment {
+            provisioning_uri: totp::uri_provisioning(&secret, &user.username),
+            secret,
+        })
+    }
+
+    /// Confirme l'enrôlement du second facteur avec un premier code
+    ///
+    /// # Arguments
+    /// * `code` - Le code affiché par l'application d'authentification
+    /// * `token` - Le token de session de l'administrateur
+    pub async fn confirm_mfa_enrollment(&self, code: &str, token: &str) -> Result<(), AppError> {
+        let user = self.require_admin(token).await?;
+
+        self.db_manager.write(|tx| {
+            let mfa = MfaRepository::get(tx, user.id)?
+                .filter(|mfa| !mfa.enabled)
+                .ok_or_else(|| AppError::business_logic("Aucun enrôlement du second facteur n'est en cours"))?;
+            let pas = Self::check_totp_code(&mfa, code)?;
+            MfaRepository::enable(tx, user.id, pas)
+        })
+    }
+
+    /// Désactive le second facteur de l'utilisateur connecté
+    ///
+    /// # Arguments
+    /// * `code` - Un code valide, pour prouver la possession du second facteur
+    /// * `token` - Le token de session de l'utilisateur
+    pub async fn disable_mfa(&self, code: &str, token: &str) -> Result<(), AppError> {
+        let user = self.current_user(token).await?;
+
+        self.db_manager.write(|tx| {
+            let mfa = MfaRepository::get(tx, user.id)?
+                .filter(|mfa| mfa.enabled)
+                .ok_or_else(|| AppError::business_logic("Le second facteur n'est pas activé"))?;
+            Self::check_totp_code(&mfa, code)?;
+            MfaRepository::delete(tx, user.id)
+        })
     }
 
     /// Met à jour le profil utilisateur
@@ -134,15 +367,63 @@ impl AuthService {
 
     /// Génère un token pour un utilisateur
     fn generate_token(&self, user: &User) -> Result<String, AppError> {
+        self.db_manager.write(|tx| Self::create_session(tx, user.id))
+    }
+
+    /// Ouvre une session dans une écriture en cours et retourne son token
+    fn create_session(conn: &Connection, user_id: i64) -> Result<String, AppError> {
         let token = Uuid::new_v4().to_string();
-        
-        let mut tokens = self.active_tokens.lock()
-            .map_err(|_| AppError::business_logic("Failed to lock tokens"))?;
-        
-        tokens.insert(token.clone(), user.id);
+        conn.execute(
+            "INSERT INTO sessions (token, user_id) VALUES (?1, ?2)",
+            rusqlite::params![token, user_id],
+        )?;
         Ok(token)
     }
 
+    /// Vérifie le code TOTP d'un utilisateur qui a activé le second facteur
+    fn verify_second_factor(&self, user: &User, totp_code: Option<&str>) -> Result<(), AppError> {
+        let conn = self.db_manager.get_connection()?;
+        let mfa = match MfaRepository::get(&conn, user.id)? {
+            Some(mfa) if mfa.enabled => mfa,
+            _ => return Ok(()),
+        };
+        drop(conn);
+
+        let code = totp_code
+            .filter(|code| !code.trim().is_empty())
+            .ok_or_else(|| AppError::validation_error("totp_code", "Code de vérification requis"))?;
+        let pas = Self::check_totp_code(&mfa, code)?;
+        // Le pas est consommé dans l'écriture: de deux connexions simultanées
+        // avec le même code, une seule l'enregistre
+        self.db_manager.write(|tx| match MfaRepository::record_step(tx, user.id, pas)? {
+            true => Ok(()),
+            false => Err(AppError::validation_error("totp_code", "Code de vérification invalide")),
+        })
+    }
+
+    /// Vérifie un code TOTP et retourne son pas de temps
+    ///
+    /// Un code déjà utilisé (ou antérieur au dernier code accepté) est refusé.
+    fn check_totp_code(mfa: &UserMfa, code: &str) -> Result<i64, AppError> {
+        match totp::verifier(&mfa.secret, code, Utc::now().timestamp())? {
+            Some(pas) if mfa.last_used_step.is_none_or(|dernier| pas > dernier) => Ok(pas),
+            _ => Err(AppError::validation_error("totp_code", "Code de vérification invalide")),
+        }
+    }
+
+    /// Charge l'utilisateur d'une session
+    fn find_session_user(&self, token: &str) -> Result<Option<User>, AppError> {
+        let conn = self.db_manager.get_connection()?;
+        let user_id: Option<i64> = conn
+            .query_row("SELECT user_id FROM sessions WHERE token = ?1", [token], |row| row.get(0))
+            .optional()?;
+
+        match user_id {
+            Some(user_id) => UserRepository::new(&conn).get_user_by_id(user_id),
+            None => Ok(None),
+        }
+    }
+
     /// Valide les données utilisateur
     fn validate_user_data(&self, user_data: &CreateUser) -> Result<(), AppError> {
         // Validation du nom d'utilisateur
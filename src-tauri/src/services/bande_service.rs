@@ -1,17 +1,31 @@
-use crate::database::DatabaseManager;
+use crate::database::numerotation::{
+    valider_format_numero_bande, FORMAT_NUMERO_BANDE_DEFAUT, PARAMETRE_FORMAT_NUMERO_BANDE,
+};
+use crate::database::Storage;
 use crate::error::{AppError, AppResult};
 use crate::models::{
-    Bande, BandeWithDetails, CreateBande, UpdateBande,
-    Batiment, CreateBatiment,
-    Semaine, CreateSemaine,
-    SuiviQuotidien, CreateSuiviQuotidien
+    Bande, BandeLoadOptions, ChronologieBande, JalonBande, JALON_ENLEVEMENT, JALON_PHASE_ALIMENTATION, JALON_SOIN, BandeWithDetails, CreateBande, UpdateBande,
+    CreateBatiment,
+    EntreeAudit, ProblemeCreationBande, ValidationCreationBande, AUDIT_CLOTURE_BANDE, AUDIT_ENTITE_BANDE,
+    AUDIT_REOUVERTURE_BANDE, EVENEMENT_BANDE_CLOTUREE, NIVEAU_AVERTISSEMENT, NIVEAU_ERREUR, SEMAINES_SUIVI,
+    STATUT_BANDE_CLOTUREE,
 };
 use crate::repositories::{
+    AuditRepository,
     BandeRepository,
     BatimentRepository,
-    SemaineRepository, SemaineRepositoryTrait,
-    SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait
+    ParametreRepository,
+    PersonnelRepository,
+    PlanFermeRepository,
+    PlanSoinsRepository,
+    PoussinRepository,
+    ProgrammeAlimentationRepository,
+    WebhookRepository,
 };
+use crate::services::AuthService;
+use chrono::{Days, Local, NaiveDate};
+use rusqlite::OptionalExtension;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// Service pour la gestion des bandes avec création automatique des semaines et suivi quotidien
@@ -19,9 +33,7 @@ use std::sync::Arc;
 /// Ce service encapsule la logique métier complexe pour créer une bande
 /// avec ses bâtiments, semaines et suivi quotidien.
 pub struct BandeService {
-    semaine_repo: Arc<SemaineRepository>,
-    suivi_repo: Arc<SuiviQuotidienRepository>,
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl BandeService {
@@ -29,15 +41,8 @@ impl BandeService {
     /// 
     /// # Arguments
     /// * `db` - Le gestionnaire de base de données partagé
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
-        let semaine_repo = Arc::new(SemaineRepository::new(db.clone()));
-        let suivi_repo = Arc::new(SuiviQuotidienRepository::new(db.clone()));
-        
-        Self { 
-            semaine_repo,
-            suivi_repo,
-            db,
-        }
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
     }
 
     /// Crée une nouvelle bande avec sa première semaine et 7 jours de suivi
@@ -45,6 +50,7 @@ impl BandeService {
     /// # Arguments
     /// * `create_bande` - Les données de la bande à créer
     /// * `batiments` - Liste des bâtiments à créer pour cette bande
+    /// * `token` - Le token de session de l'auteur, enregistré sur les lignes créées
     /// 
     /// # Returns
     /// La bande créée avec son ID généré
@@ -58,7 +64,8 @@ impl BandeService {
     pub async fn create_bande_with_batiments_and_first_week(
         &self, 
         create_bande: CreateBande,
-        batiments: Vec<CreateBatiment>
+        batiments: Vec<CreateBatiment>,
+        token: Option<&str>,
     ) -> AppResult<Bande> {
         // Validation des données
         if batiments.is_empty() {
@@ -68,20 +75,7 @@ impl BandeService {
             ));
         }
 
-        // Utiliser une transaction pour garantir la cohérence
-        let conn = self.db.get_connection()?;
-        let tx = conn.unchecked_transaction()?;
-
-        // 1. Créer la bande
-        let bande = BandeRepository::create(&conn, &create_bande)?;
-        let bande_id = bande.id.ok_or_else(|| {
-            AppError::business_logic("La bande créée n'a pas d'ID")
-        })?;
-
-        // 2. Créer chaque bâtiment
-        for mut batiment_data in batiments {
-            batiment_data.bande_id = bande_id;
-            
+        for batiment_data in &batiments {
             // Validation des données du bâtiment
             if batiment_data.quantite <= 0 {
                 return Err(AppError::validation_error(
@@ -97,57 +91,216 @@ impl BandeService {
                 ));
             }
 
-            if batiment_data.poussin_id < 0 {
+            if batiment_data.poussin_id <= 0 {
                 return Err(AppError::validation_error(
                     "poussin_id",
                     "Un poussin valide doit être sélectionné"
                 ));
             }
+        }
+
+        let created_by = AuthService::new(self.db.clone()).author_id(token).await?;
 
-            let batiment = BatimentRepository::create(&conn, &batiment_data)?;
-            let batiment_id = batiment.id.ok_or_else(|| {
-                AppError::business_logic("Le bâtiment créé n'a pas d'ID")
+        // Toutes les écritures passent par la même transaction
+        self.db.write(|tx| {
+            // 1. Créer la bande
+            let bande = BandeRepository::create(tx, &create_bande, created_by)?;
+            let bande_id = bande.id.ok_or_else(|| {
+                AppError::business_logic("La bande créée n'a pas d'ID")
             })?;
 
-            // 3. Créer la première semaine pour ce bâtiment
-            let create_semaine = CreateSemaine {
-                batiment_id,
-                numero_semaine: 1,
-                poids: None, // Sera rempli plus tard
-            };
+            // 2. Créer chaque bâtiment
+            for mut batiment_data in batiments {
+                batiment_data.bande_id = bande_id;
 
-            let semaine = self.semaine_repo.create(create_semaine).await?;
-            let semaine_id = semaine.id.ok_or_else(|| {
-                AppError::business_logic("La semaine créée n'a pas d'ID")
-            })?;
+                if !PoussinRepository::exists(tx, batiment_data.poussin_id)? {
+                    return Err(AppError::validation_error(
+                        "poussin_id",
+                        "Le poussin sélectionné n'existe pas"
+                    ));
+                }
 
-            // 4. Créer les 7 jours de suivi quotidien pour cette semaine
-            for age in 1..=7 {
-                let create_suivi = CreateSuiviQuotidien {
-                    semaine_id,
-                    age,
-                    deces_par_jour: None,
-                    alimentation_par_jour: None,
-                    soins_id: None,
-                    soins_quantite: None,
-                    analyses: None,
-                    remarques: None,
-                };
+                let batiment = BatimentRepository::create(tx, &batiment_data, created_by)?;
+                let batiment_id = batiment.id.ok_or_else(|| {
+                    AppError::business_logic("Le bâtiment créé n'a pas d'ID")
+                })?;
+
+                // 3. Créer la première semaine pour ce bâtiment (le poids sera rempli plus tard)
+                tx.execute(
+                    "INSERT INTO semaines (batiment_id, numero_semaine, poids, created_at, updated_at, created_by)
+                     VALUES (?1, 1, NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?2)",
+                    rusqlite::params![batiment_id, created_by],
+                )?;
+                let semaine_id = tx.last_insert_rowid();
+
+                // 4. Créer les 7 jours de suivi quotidien pour cette semaine
+                for age in 1..=7 {
+                    tx.execute(
+                        "INSERT INTO suivi_quotidien (semaine_id, age, created_at, updated_at, created_by)
+                         VALUES (?1, ?2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?3)",
+                        rusqlite::params![semaine_id, age, created_by],
+                    )?;
+                }
+
+                // 5. Pré-remplir les soins prévus par le plan du type de poussin
+                PlanSoinsRepository::apply_to_semaine(tx, semaine_id, created_by)?;
+            }
+
+            Ok(bande)
+        })
+    }
+
+    /// Vérifie une création de bande sans rien enregistrer
+    ///
+    /// Toutes les vérifications sont faites et tous les problèmes sont
+    /// renvoyés, pas seulement le premier: l'assistant de création peut
+    /// valider chaque étape et afficher les messages à côté des champs.
+    ///
+    /// # Arguments
+    /// * `bande` - Les données de la bande
+    /// * `batiments` - Les bâtiments prévus, `None` pour ne vérifier que la bande
+    ///
+    /// # Returns
+    /// Les erreurs et avertissements relevés et le numéro que recevrait la bande
+    pub async fn validate_bande_creation(
+        &self,
+        bande: CreateBande,
+        batiments: Option<Vec<CreateBatiment>>,
+    ) -> AppResult<ValidationCreationBande> {
+        let conn = self.db.get_connection()?;
+        let mut problemes = Vec::new();
+        let mut probleme = |niveau: &str, champ: &str, batiment: Option<usize>, message: String| {
+            problemes.push(ProblemeCreationBande {
+                niveau: niveau.to_string(),
+                champ: champ.to_string(),
+                batiment,
+                message,
+            });
+        };
 
-                self.suivi_repo.create(create_suivi).await?;
+        // Bâtiments de la ferme (1 à nbr_meuble), bâtiments encore occupés par une bande active,
+        // bâtiments d'une bande en cours et occupations prévues pendant les semaines de suivi
+        // de la nouvelle bande
+        let date_fin_prevue = bande.date_entree + Days::new(SEMAINES_SUIVI as u64 * 7 - 1);
+        let ferme = match BandeRepository::get_available_batiments(&conn, bande.ferme_id, true) {
+            Ok(disponibles) => Some((
+                disponibles,
+                BandeRepository::get_occupied_batiments(&conn, bande.ferme_id)?,
+                BandeRepository::get_batiments_en_cours(&conn, bande.ferme_id, None)?,
+                PlanFermeRepository::get_intervalles_occupation(&conn, bande.ferme_id, bande.date_entree, date_fin_prevue)?,
+            )),
+            Err(AppError::NotFound { .. }) => {
+                probleme(NIVEAU_ERREUR, "ferme_id", None, "La ferme spécifiée n'existe pas".to_string());
+                None
             }
+            Err(e) => return Err(e),
+        };
+
+        if bande.date_entree > Local::now().date_naive() {
+            probleme(NIVEAU_AVERTISSEMENT, "date_entree", None, "La date d'entrée est dans le futur".to_string());
         }
 
-        // Valider la transaction
-        tx.commit()?;
+        let numero_affiche = match ferme {
+            Some(_) => {
+                let (numero_affiche, deja_utilise) =
+                    BandeRepository::preview_numero_affiche(&conn, bande.ferme_id, bande.date_entree)?;
+                if deja_utilise {
+                    probleme(
+                        NIVEAU_AVERTISSEMENT,
+                        "numero_affiche",
+                        None,
+                        format!("Le numéro {} est déjà utilisé par une autre bande de la ferme", numero_affiche),
+                    );
+                }
+                Some(numero_affiche)
+            }
+            None => None,
+        };
+
+        if let Some(batiments) = batiments {
+            if batiments.is_empty() {
+                probleme(NIVEAU_ERREUR, "batiments", None, "Au moins un bâtiment doit être spécifié".to_string());
+            }
+            if let Some((disponibles, _, _, _)) = &ferme {
+                if batiments.len() > disponibles.len() {
+                    probleme(
+                        NIVEAU_ERREUR,
+                        "batiments",
+                        None,
+                        format!("La ferme ne compte que {} bâtiments", disponibles.len()),
+                    );
+                }
+            }
+
+            for (rang, batiment) in batiments.iter().enumerate() {
+                let numero = batiment.numero_batiment.trim();
+                if numero.is_empty() {
+                    probleme(NIVEAU_ERREUR, "numero_batiment", Some(rang), "Le numéro de bâtiment ne peut pas être vide".to_string());
+                } else if batiments[..rang].iter().any(|autre| autre.numero_batiment.trim() == numero) {
+                    probleme(NIVEAU_ERREUR, "numero_batiment", Some(rang), format!("Le bâtiment {} est saisi deux fois", numero));
+                } else if let Some((disponibles, occupes, en_cours, intervalles)) = &ferme {
+                    let occupant = en_cours
+                        .iter()
+                        .find(|(occupe, _)| occupe == numero)
+                        .filter(|_| !batiment.autoriser_cohabitation);
+                    let double_reservation = intervalles.iter().find(|(occupe, _)| occupe == numero);
+                    if !disponibles.iter().any(|disponible| disponible == numero) {
+                        probleme(
+                            NIVEAU_ERREUR,
+                            "numero_batiment",
+                            Some(rang),
+                            format!("Le bâtiment {} n'existe pas dans cette ferme", numero),
+                        );
+                    } else if let Some((_, numero_bande)) = occupant {
+                        probleme(
+                            NIVEAU_ERREUR,
+                            "numero_batiment",
+                            Some(rang),
+                            format!("Le bâtiment {} est occupé par la bande {} en cours", numero, numero_bande),
+                        );
+                    } else if let Some((_, intervalle)) = double_reservation {
+                        probleme(
+                            NIVEAU_AVERTISSEMENT,
+                            "numero_batiment",
+                            Some(rang),
+                            format!(
+                                "Le bâtiment {} est occupé par la bande {} du {} au {}",
+                                numero, intervalle.numero_bande, intervalle.date_debut, intervalle.date_fin
+                            ),
+                        );
+                    } else if occupes.iter().any(|occupe| occupe == numero) {
+                        probleme(
+                            NIVEAU_AVERTISSEMENT,
+                            "numero_batiment",
+                            Some(rang),
+                            format!("Le bâtiment {} est encore occupé par une bande active", numero),
+                        );
+                    }
+                }
+
+                if batiment.quantite <= 0 {
+                    probleme(NIVEAU_ERREUR, "quantite", Some(rang), "La quantité doit être supérieure à 0".to_string());
+                }
+                if !PoussinRepository::exists(&conn, batiment.poussin_id)? {
+                    probleme(NIVEAU_ERREUR, "poussin_id", Some(rang), "Le poussin sélectionné n'existe pas".to_string());
+                }
+                if !PersonnelRepository::exists(&conn, batiment.personnel_id)? {
+                    probleme(NIVEAU_ERREUR, "personnel_id", Some(rang), "Le personnel spécifié n'existe pas".to_string());
+                }
+            }
+        }
 
-        Ok(bande)
+        Ok(ValidationCreationBande {
+            valide: !problemes.iter().any(|probleme| probleme.niveau == NIVEAU_ERREUR),
+            numero_affiche,
+            problemes,
+        })
     }
 
     /// Récupère toutes les bandes avec leurs détails
     pub async fn get_all_bandes(&self) -> AppResult<Vec<BandeWithDetails>> {
         let conn = self.db.get_connection()?;
-        BandeRepository::get_all_list(&conn).map_err(AppError::from)
+        BandeRepository::get_all_list(&conn, &BandeLoadOptions::default()).map_err(AppError::from)
     }
 
     /// Récupère une bande par son ID
@@ -160,7 +313,7 @@ impl BandeService {
         }
 
         let conn = self.db.get_connection()?;
-        BandeRepository::get_by_id(&conn, id).map_err(AppError::from)
+        BandeRepository::get_by_id(&conn, id, &BandeLoadOptions::default()).map_err(AppError::from)
     }
 
     /// Récupère toutes les bandes d'une ferme
@@ -173,7 +326,7 @@ impl BandeService {
         }
 
         let conn = self.db.get_connection()?;
-        BandeRepository::get_by_ferme(&conn, ferme_id).map_err(AppError::from)
+        BandeRepository::get_by_ferme(&conn, ferme_id, &BandeLoadOptions::default()).map_err(AppError::from)
     }
 
     /// Met à jour une bande existante
@@ -186,8 +339,7 @@ impl BandeService {
             ));
         }
 
-        let conn = self.db.get_connection()?;
-        BandeRepository::update(&conn, id, &update_bande).map_err(AppError::from)
+        self.db.write(|tx| BandeRepository::update(tx, id, &update_bande))
     }
 
     /// Supprime une bande et toutes ses données associées
@@ -199,15 +351,194 @@ impl BandeService {
             ));
         }
 
-        let mut conn = self.db.get_connection()?;
-        
-        // Vérifier que la bande existe
-        let bande = BandeRepository::get_by_id(&conn, id)?;
-        if bande.is_none() {
-            return Err(AppError::not_found("Bande", id));
+        // Les données associées sont supprimées en cascade par le schéma
+        self.db.write(|tx| BandeRepository::delete(tx, id))
+    }
+
+    /// Clôture une bande: ses semaines et son suivi quotidien deviennent non modifiables
+    ///
+    /// La clôture est notifiée au webhook de la coopérative, s'il est configuré.
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de la bande
+    /// * `token` - Le token de session de l'utilisateur, enregistré dans le journal d'audit
+    pub async fn close_bande(&self, id: i64, token: &str) -> AppResult<()> {
+        let user = AuthService::new(self.db.clone()).current_user(token).await?;
+
+        self.db.write(|tx| {
+            if BandeRepository::get_statut(tx, id)? == STATUT_BANDE_CLOTUREE {
+                return Err(AppError::business_logic("Cette bande est déjà clôturée"));
+            }
+
+            BandeRepository::close(tx, id)?;
+            let evenement = tx.query_row(
+                "SELECT bd.ferme_id, f.nom, COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)),
+                        bd.date_entree, bd.date_cloture,
+                        (SELECT COALESCE(SUM(b.quantite), 0) FROM batiments b WHERE b.bande_id = bd.id)
+                 FROM bandes bd
+                 JOIN fermes f ON bd.ferme_id = f.id
+                 WHERE bd.id = ?1",
+                [id],
+                |row| {
+                    Ok(serde_json::json!({
+                        "bande_id": id,
+                        "ferme_id": row.get::<_, i64>(0)?,
+                        "ferme": row.get::<_, String>(1)?,
+                        "numero": row.get::<_, String>(2)?,
+                        "date_entree": row.get::<_, String>(3)?,
+                        "date_cloture": row.get::<_, String>(4)?,
+                        "effectif_initial": row.get::<_, i64>(5)?,
+                    }))
+                },
+            )?;
+            WebhookRepository::enqueue(tx, EVENEMENT_BANDE_CLOTUREE, &evenement)?;
+            AuditRepository::log(tx, user.id, AUDIT_CLOTURE_BANDE, AUDIT_ENTITE_BANDE, id, None)
+        })
+    }
+
+    /// Rouvre une bande clôturée (réservé aux administrateurs)
+    ///
+    /// La bande repasse au statut actif, ce qui permet de nouveau la saisie des
+    /// semaines et du suivi quotidien; le motif est enregistré dans le journal d'audit.
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de la bande
+    /// * `reason` - Le motif de la réouverture (obligatoire)
+    /// * `token` - Le token de session d'un administrateur
+    pub async fn reopen_bande(&self, id: i64, reason: &str, token: &str) -> AppResult<()> {
+        let user = AuthService::new(self.db.clone()).require_admin(token).await?;
+
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(AppError::validation_error(
+                "reason",
+                "Le motif de la réouverture est obligatoire"
+            ));
+        }
+
+        self.db.write(|tx| {
+            if BandeRepository::get_statut(tx, id)? != STATUT_BANDE_CLOTUREE {
+                return Err(AppError::business_logic("Seule une bande clôturée peut être rouverte"));
+            }
+
+            BandeRepository::reopen(tx, id)?;
+            AuditRepository::log(tx, user.id, AUDIT_REOUVERTURE_BANDE, AUDIT_ENTITE_BANDE, id, Some(reason))
+        })
+    }
+
+    /// Récupère le journal d'audit d'une bande, du plus récent au plus ancien
+    pub async fn get_bande_audit_log(&self, id: i64) -> AppResult<Vec<EntreeAudit>> {
+        let conn = self.db.get_connection()?;
+        AuditRepository::get_by_entite(&conn, AUDIT_ENTITE_BANDE, id)
+    }
+
+    /// Calcule la chronologie d'une bande: âge, semaine en cours et étapes prévues
+    ///
+    /// Les étapes viennent du plan de soins et du programme d'alimentation du
+    /// type de poussin de chaque bâtiment; l'enlèvement d'un bâtiment est
+    /// projeté à la fin de sa dernière semaine (au moins `SEMAINES_SUIVI`).
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `aujourd_hui` - La date du jour
+    ///
+    /// # Returns
+    /// La chronologie, étapes triées par âge
+    pub async fn get_bande_timeline(&self, bande_id: i64, aujourd_hui: NaiveDate) -> AppResult<ChronologieBande> {
+        let conn = self.db.get_connection()?;
+        let (date_entree, date_cloture): (NaiveDate, Option<NaiveDate>) = conn
+            .query_row(
+                "SELECT date_entree, date_cloture FROM bandes WHERE id = ?1",
+                [bande_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::not_found("Bande", bande_id))?;
+
+        let date_reference = date_cloture.map_or(aujourd_hui, |cloture| cloture.min(aujourd_hui));
+        let age_jours = ((date_reference - date_entree).num_days() + 1).max(0) as i32;
+        let semaine_courante = if age_jours > 0 { (age_jours - 1) / 7 + 1 } else { 0 };
+
+        let batiments: Vec<(i64, i64, i32)> = conn
+            .prepare(
+                "SELECT b.id, b.poussin_id,
+                        MAX(?2, COALESCE((SELECT MAX(numero_semaine) FROM semaines WHERE batiment_id = b.id), 0)) * 7
+                 FROM batiments b
+                 WHERE b.bande_id = ?1
+                 ORDER BY b.id",
+            )?
+            .query_map(rusqlite::params![bande_id, SEMAINES_SUIVI], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // (âge, type, libellé) -> bâtiments concernés
+        let mut etapes: BTreeMap<(i32, &str, String), Vec<i64>> = BTreeMap::new();
+        for &(batiment_id, poussin_id, age_enlevement) in &batiments {
+            for soin in PlanSoinsRepository::get_by_poussin(&conn, poussin_id)? {
+                let libelle = if soin.jour_fin > soin.jour_debut {
+                    format!("{} (J{} à J{})", soin.soin_nom, soin.jour_debut, soin.jour_fin)
+                } else {
+                    soin.soin_nom
+                };
+                etapes.entry((soin.jour_debut, JALON_SOIN, libelle)).or_default().push(batiment_id);
+            }
+            for phase in ProgrammeAlimentationRepository::get_by_poussin(&conn, poussin_id)? {
+                let libelle = format!("Phase {}", phase.nom_phase);
+                etapes.entry((phase.jour_debut, JALON_PHASE_ALIMENTATION, libelle)).or_default().push(batiment_id);
+            }
+            etapes
+                .entry((age_enlevement, JALON_ENLEVEMENT, "Enlèvement prévu".to_string()))
+                .or_default()
+                .push(batiment_id);
         }
 
-        // La suppression cascade est gérée par les contraintes FK
-        BandeRepository::delete(&mut conn, id).map_err(AppError::from)
+        let date_du_jour = |age: i32| date_entree + Days::new((age.max(1) - 1) as u64);
+        let jalons = etapes
+            .into_iter()
+            .map(|((age, type_jalon, libelle), batiment_ids)| JalonBande {
+                type_jalon: type_jalon.to_string(),
+                age,
+                date: date_du_jour(age),
+                libelle,
+                batiment_ids,
+                passe: age < age_jours,
+            })
+            .collect();
+
+        let age_enlevement = batiments.iter().map(|&(_, _, age)| age).max().unwrap_or(SEMAINES_SUIVI * 7);
+        Ok(ChronologieBande {
+            bande_id,
+            date_entree,
+            date_reference,
+            age_jours,
+            semaine_courante,
+            age_enlevement,
+            date_enlevement: date_du_jour(age_enlevement),
+            jalons,
+        })
+    }
+
+    /// Récupère le format des numéros de bande (`{seq}` par défaut)
+    pub async fn get_numbering_pattern(&self) -> AppResult<String> {
+        let conn = self.db.get_connection()?;
+        Ok(ParametreRepository::get(&conn, PARAMETRE_FORMAT_NUMERO_BANDE)?
+            .unwrap_or_else(|| FORMAT_NUMERO_BANDE_DEFAUT.to_string()))
+    }
+
+    /// Enregistre le format des numéros de bande
+    ///
+    /// Le format s'applique aux bandes créées ensuite: les bandes existantes
+    /// gardent leur numéro affiché.
+    ///
+    /// # Arguments
+    /// * `pattern` - Le format, ex: `{year}-{ferme_code}-{seq:03}`
+    ///
+    /// # Returns
+    /// Le format enregistré
+    pub async fn set_numbering_pattern(&self, pattern: &str) -> AppResult<String> {
+        let pattern = pattern.trim();
+        valider_format_numero_bande(pattern)?;
+
+        self.db.write(|tx| ParametreRepository::set(tx, PARAMETRE_FORMAT_NUMERO_BANDE, pattern))?;
+        Ok(pattern.to_string())
     }
 }
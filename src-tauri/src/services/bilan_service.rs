@@ -0,0 +1,360 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    BilanFinancierBande, BudgetBande, ContratBande, CoutSoinsJour, EcartBudgetBande, EcartPoste, ReglementContrat,
+    POSTE_ALIMENT_KG, POSTE_COUT_ALIMENT, POSTE_COUT_POUSSINS, POSTE_COUT_SOINS, POSTE_COUT_TOTAL,
+    POSTE_MORTALITE, STATUT_BANDE_CLOTUREE,
+};
+use crate::repositories::{
+    BandeRepository, BudgetRepository, ContratRepository, ImmobilisationRepository, ProgrammeAlimentationRepository,
+};
+use crate::services::soin_service::cout_administration;
+use crate::services::ComparaisonService;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Service du bilan financier des bandes
+pub struct BilanService {
+    db: Arc<dyn Storage>,
+}
+
+impl BilanService {
+    /// Crée une nouvelle instance du service de bilan
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Calcule le bilan financier d'une bande
+    ///
+    /// Le coût des soins est calculé à partir des quantités saisies dans le
+    /// suivi quotidien et du prix unitaire de chaque soin; celui de l'aliment
+    /// et des poussins à partir des prix enregistrés avec les livraisons et
+    /// les bâtiments, celui de la litière à partir des opérations saisies.
+    /// S'y ajoute la part de l'amortissement des immobilisations de la ferme.
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    ///
+    /// # Returns
+    /// Les coûts de l'aliment, des poussins, de la litière et des soins (détaillés par jour)
+    pub async fn get_bande_financial_summary(&self, bande_id: i64) -> AppResult<BilanFinancierBande> {
+        let conn = self.db.get_connection()?;
+        BandeRepository::get_statut(&conn, bande_id)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT sq.age, date(bd.date_entree, '+' || (sq.age - 1) || ' days'),
+                    ss.quantite_valeur, ss.quantite_unite, so.unit, so.prix_unitaire
+             FROM suivi_soins ss
+             JOIN soins so ON ss.soin_id = so.id
+             JOIN suivi_quotidien sq ON ss.suivi_id = sq.id
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE bd.id = ?1",
+        )?;
+        let administrations = stmt.query_map([bande_id], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<f64>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<f64>>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut par_jour: BTreeMap<i32, CoutSoinsJour> = BTreeMap::new();
+        let mut soins_non_valorises = 0;
+        for (age, date, valeur, unite, unit_soin, prix_unitaire) in administrations {
+            match cout_administration(valeur, unite.as_deref(), &unit_soin, prix_unitaire) {
+                Some(cout) => par_jour.entry(age).or_insert(CoutSoinsJour { age, date, cout: 0.0 }).cout += cout,
+                None => soins_non_valorises += 1,
+            }
+        }
+
+        let (cout_aliment, livraisons_non_valorisees): (f64, u32) = conn.query_row(
+            "SELECT COALESCE(SUM(quantite * prix_unitaire), 0), COUNT(*) - COUNT(prix_unitaire)
+             FROM alimentation_history WHERE bande_id = ?1",
+            [bande_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let (cout_poussins, batiments_non_valorises): (f64, u32) = conn.query_row(
+            "SELECT COALESCE(SUM(quantite * prix_poussin), 0), COUNT(*) - COUNT(prix_poussin)
+             FROM batiments WHERE bande_id = ?1",
+            [bande_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let (cout_litiere, litieres_non_valorisees): (f64, u32) = conn.query_row(
+            "SELECT COALESCE(SUM(l.cout), 0), COUNT(*) - COUNT(l.cout)
+             FROM litieres l
+             JOIN batiments b ON l.batiment_id = b.id
+             WHERE b.bande_id = ?1",
+            [bande_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let cout_amortissement = cout_amortissement(&conn, bande_id)?;
+
+        let cout_soins_par_jour: Vec<CoutSoinsJour> = par_jour.into_values().collect();
+        let cout_soins: f64 = cout_soins_par_jour.iter().map(|jour| jour.cout).sum();
+        Ok(BilanFinancierBande {
+            bande_id,
+            cout_soins,
+            cout_soins_par_jour,
+            soins_non_valorises,
+            cout_aliment,
+            livraisons_non_valorisees,
+            cout_poussins,
+            batiments_non_valorises,
+            cout_litiere,
+            litieres_non_valorisees,
+            cout_amortissement,
+            cout_total: cout_aliment + cout_poussins + cout_soins + cout_litiere + cout_amortissement,
+        })
+    }
+
+    /// Récupère le budget prévisionnel d'une bande
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    ///
+    /// # Returns
+    /// Le budget, ou `None` si aucun budget n'a été défini
+    pub async fn get_bande_budget(&self, bande_id: i64) -> AppResult<Option<BudgetBande>> {
+        let conn = self.db.get_connection()?;
+        BandeRepository::get_statut(&conn, bande_id)?;
+        BudgetRepository::get(&conn, bande_id)
+    }
+
+    /// Définit ou remplace le budget prévisionnel d'une bande
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `budget` - Les prévisions (aliment, mortalité, coûts)
+    ///
+    /// # Returns
+    /// Le budget enregistré
+    pub async fn set_bande_budget(&self, bande_id: i64, budget: BudgetBande) -> AppResult<BudgetBande> {
+        self.db.write(|tx| {
+            BandeRepository::get_statut(tx, bande_id)?;
+            BudgetRepository::set(tx, bande_id, &budget)
+        })
+    }
+
+    /// Compare le budget d'une bande à son réalisé
+    ///
+    /// L'aliment réalisé est la consommation saisie dans le suivi quotidien,
+    /// la mortalité le total des décès rapporté aux poussins placés et les
+    /// coûts ceux du bilan financier. Les valeurs réalisées sont cumulées à
+    /// ce jour: l'écart n'est définitif qu'à la clôture de la bande.
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    ///
+    /// # Returns
+    /// Le prévu, le réalisé et l'écart de chaque poste
+    pub async fn get_budget_variance(&self, bande_id: i64) -> AppResult<EcartBudgetBande> {
+        let bilan = self.get_bande_financial_summary(bande_id).await?;
+        let conn = self.db.get_connection()?;
+        let budget = BudgetRepository::get(&conn, bande_id)?;
+
+        let (sachets, deces, age_atteint): (f64, i64, i32) = conn.query_row(
+            "SELECT COALESCE(SUM(sq.alimentation_par_jour), 0), COALESCE(SUM(sq.deces_par_jour), 0),
+                    COALESCE(MAX(sq.age), 0)
+             FROM suivi_quotidien sq
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             WHERE b.bande_id = ?1",
+            [bande_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        let poussins: i64 = conn.query_row(
+            "SELECT COALESCE(SUM(quantite), 0) FROM batiments WHERE bande_id = ?1",
+            [bande_id],
+            |row| row.get(0),
+        )?;
+        let mortalite = if poussins > 0 { deces as f64 / poussins as f64 * 100.0 } else { 0.0 };
+
+        let prevu = budget.clone().unwrap_or_default();
+        let cout_total_prevu = match (prevu.cout_aliment_prevu, prevu.cout_poussins_prevu, prevu.cout_soins_prevu) {
+            (None, None, None) => None,
+            (aliment, poussins, soins) => Some(aliment.unwrap_or(0.0) + poussins.unwrap_or(0.0) + soins.unwrap_or(0.0)),
+        };
+
+        Ok(EcartBudgetBande {
+            bande_id,
+            budget,
+            age_atteint,
+            postes: vec![
+                EcartPoste::calculer(POSTE_ALIMENT_KG, prevu.aliment_prevu_kg, sachets * ProgrammeAlimentationRepository::get_kg_par_sachet(&conn)?),
+                EcartPoste::calculer(POSTE_MORTALITE, prevu.mortalite_prevue_pourcentage, mortalite),
+                EcartPoste::calculer(POSTE_COUT_ALIMENT, prevu.cout_aliment_prevu, bilan.cout_aliment),
+                EcartPoste::calculer(POSTE_COUT_POUSSINS, prevu.cout_poussins_prevu, bilan.cout_poussins),
+                EcartPoste::calculer(POSTE_COUT_SOINS, prevu.cout_soins_prevu, bilan.cout_soins),
+                EcartPoste::calculer(POSTE_COUT_TOTAL, cout_total_prevu, bilan.cout_intrants()),
+            ],
+        })
+    }
+
+    /// Récupère le contrat d'élevage d'une bande
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    ///
+    /// # Returns
+    /// Le contrat, ou `None` si la bande n'est pas sous contrat
+    pub async fn get_bande_contract(&self, bande_id: i64) -> AppResult<Option<ContratBande>> {
+        let conn = self.db.get_connection()?;
+        BandeRepository::get_statut(&conn, bande_id)?;
+        ContratRepository::get(&conn, bande_id)
+    }
+
+    /// Définit ou remplace le contrat d'élevage d'une bande
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `contrat` - Le prix garanti, les cibles et les primes
+    ///
+    /// # Returns
+    /// Le contrat enregistré
+    pub async fn set_bande_contract(&self, bande_id: i64, contrat: ContratBande) -> AppResult<ContratBande> {
+        self.db.write(|tx| {
+            BandeRepository::get_statut(tx, bande_id)?;
+            ContratRepository::set(tx, bande_id, &contrat)
+        })
+    }
+
+    /// Calcule le règlement attendu d'une bande sous contrat
+    ///
+    /// Le poids vif est celui des sujets restants des bâtiments pesés, au
+    /// poids de leur dernière pesée. La prime sur l'indice de consommation est
+    /// versée par kg de poids vif pour chaque centième d'écart à la cible,
+    /// celle sur la mortalité pour chaque point d'écart; un résultat moins bon
+    /// que la cible donne une pénalité. Tant que la bande n'est pas clôturée,
+    /// le règlement est une estimation à ce jour.
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    ///
+    /// # Returns
+    /// Le décompte du règlement, ou une erreur si la bande n'est pas sous contrat
+    pub async fn compute_settlement(&self, bande_id: i64) -> AppResult<ReglementContrat> {
+        let (statut, contrat) = {
+            let conn = self.db.get_connection()?;
+            let statut = BandeRepository::get_statut(&conn, bande_id)?;
+            let contrat = ContratRepository::get(&conn, bande_id)?
+                .ok_or_else(|| AppError::business_logic("La bande n'est pas sous contrat"))?;
+            (statut, contrat)
+        };
+
+        let indice = ComparaisonService::new(self.db.clone()).get_production_index(bande_id).await?;
+        let mut poids_vif_kg = 0.0;
+        let mut batiments_non_peses = 0;
+        for batiment in &indice.batiments {
+            let indicateurs = &batiment.indicateurs;
+            match (indicateurs.poids_moyen, indicateurs.viabilite_pourcentage) {
+                (Some(poids), Some(viabilite)) => {
+                    poids_vif_kg += indicateurs.poussins_places as f64 * viabilite / 100.0 * poids;
+                }
+                _ => batiments_non_peses += 1,
+            }
+        }
+        let indice_consommation = indice.indicateurs.indice_consommation;
+        let mortalite_pourcentage = indice.indicateurs.mortalite_pourcentage;
+
+        let montant_base = poids_vif_kg * contrat.prix_garanti_kg;
+        let prime_ic = match (contrat.ic_cible, contrat.prime_ic_par_centieme, indice_consommation) {
+            (Some(cible), Some(prime), Some(ic)) => (cible - ic) * 100.0 * prime * poids_vif_kg,
+            _ => 0.0,
+        };
+        let prime_mortalite =
+            match (contrat.mortalite_cible_pourcentage, contrat.prime_mortalite_par_point, mortalite_pourcentage) {
+                (Some(cible), Some(prime), Some(mortalite)) => (cible - mortalite) * prime,
+                _ => 0.0,
+            };
+        let intrants_deduits = if contrat.deduire_intrants {
+            self.get_bande_financial_summary(bande_id).await?.cout_intrants()
+        } else {
+            0.0
+        };
+
+        Ok(ReglementContrat {
+            bande_id,
+            contrat,
+            poids_vif_kg,
+            batiments_non_peses,
+            indice_consommation,
+            mortalite_pourcentage,
+            montant_base,
+            prime_ic,
+            prime_mortalite,
+            intrants_deduits,
+            montant_net: montant_base + prime_ic + prime_mortalite - intrants_deduits,
+            definitif: statut == STATUT_BANDE_CLOTUREE,
+        })
+    }
+}
+
+/// Part de l'amortissement des immobilisations de la ferme imputée à une bande
+///
+/// Une bande est présente de sa date d'entrée à sa clôture ou, tant qu'elle
+/// est active, jusqu'au dernier jour de son suivi quotidien. Chaque
+/// jour, la dotation de la ferme est partagée entre les bandes présentes au
+/// prorata de leurs poussins mis en place.
+fn cout_amortissement(conn: &Connection, bande_id: i64) -> AppResult<f64> {
+    let ferme_id: i64 = conn.query_row("SELECT ferme_id FROM bandes WHERE id = ?1", [bande_id], |row| row.get(0))?;
+    let immobilisations = ImmobilisationRepository::list_by_ferme(conn, ferme_id)?;
+    if immobilisations.is_empty() {
+        return Ok(0.0);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT bd.id, bd.date_entree,
+                COALESCE(bd.date_cloture, date(bd.date_entree, '+' || (COALESCE(
+                    (SELECT MAX(sq.age) FROM suivi_quotidien sq
+                     JOIN semaines s ON sq.semaine_id = s.id
+                     JOIN batiments b ON s.batiment_id = b.id
+                     WHERE b.bande_id = bd.id), 1) - 1) || ' days')),
+                (SELECT COALESCE(SUM(quantite), 0) FROM batiments WHERE bande_id = bd.id)
+         FROM bandes bd WHERE bd.ferme_id = ?1",
+    )?;
+    let presences = stmt.query_map([ferme_id], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?))
+    })?
+    .collect::<Result<Vec<_>, _>>()?
+    .into_iter()
+    .filter_map(|(id, entree, sortie, poussins)| {
+        let entree = NaiveDate::parse_from_str(&entree, "%Y-%m-%d").ok()?;
+        let sortie = NaiveDate::parse_from_str(&sortie, "%Y-%m-%d").ok()?;
+        Some((id, entree, sortie.max(entree), poussins))
+    })
+    .collect::<Vec<_>>();
+
+    let Some(&(_, entree, sortie, poussins)) = presences.iter().find(|(id, ..)| *id == bande_id) else {
+        return Ok(0.0);
+    };
+    if poussins <= 0 {
+        return Ok(0.0);
+    }
+
+    let mut cout = 0.0;
+    for jour in entree.iter_days().take_while(|jour| *jour <= sortie) {
+        let poussins_presents: i64 = presences
+            .iter()
+            .filter(|(_, debut, fin, _)| *debut <= jour && jour <= *fin)
+            .map(|(.., poussins)| poussins)
+            .sum();
+        let dotation: f64 = immobilisations
+            .iter()
+            .map(|immobilisation| ImmobilisationRepository::dotation_periode(immobilisation, jour, jour))
+            .sum();
+        cout += dotation * poussins as f64 / poussins_presents as f64;
+    }
+    Ok(cout)
+}
@@ -0,0 +1,466 @@
+use crate::database::{normaliser_nom, Storage};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    AgregatCapteurs, LigneRejetee, MesureCapteur, ResultatImportCapteurs, FORMATS_CAPTEURS, GRANULARITES_CAPTEURS,
+};
+use crate::repositories::{CapteurRepository, PlanFermeRepository};
+use chrono::{DateTime, NaiveDateTime};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Nombre maximal de lignes refusées détaillées dans le résultat d'un import
+const LIGNES_REJETEES_MAX: usize = 50;
+
+/// Plages de valeurs plausibles pour un bâtiment d'élevage
+const PLAGE_TEMPERATURE: (f64, f64) = (-30.0, 70.0);
+const PLAGE_HUMIDITE: (f64, f64) = (0.0, 100.0);
+const PLAGE_CO2: (f64, f64) = (0.0, 50_000.0);
+
+/// Noms de colonnes (ou de champs JSON) reconnus, après normalisation
+const COLONNES_HORODATAGE: [&str; 6] = ["horodatage", "timestamp", "date_heure", "datetime", "date", "time"];
+const COLONNES_TEMPERATURE: [&str; 3] = ["temperature", "temp", "t"];
+const COLONNES_HUMIDITE: [&str; 5] = ["humidite", "humidity", "hr", "rh", "humidite_relative"];
+const COLONNES_CO2: [&str; 2] = ["co2", "co2_ppm"];
+const COLONNES_BATIMENT: [&str; 4] = ["batiment", "numero_batiment", "building", "house"];
+
+/// Mesure lue dans un export, avant validation
+#[derive(Debug, Clone, PartialEq)]
+struct MesureLue {
+    numero_batiment: Option<String>,
+    horodatage: NaiveDateTime,
+    temperature: Option<f64>,
+    humidite: Option<f64>,
+    co2: Option<f64>,
+}
+
+/// Résultat de lecture d'une ligne: la mesure ou le motif du refus
+type LigneLue = (usize, Result<MesureLue, String>);
+
+/// Service d'import des mesures des contrôleurs d'ambiance
+///
+/// Les exports CSV ou JSON des contrôleurs (température, humidité, CO₂
+/// horodatés par bâtiment) sont enregistrés mesure par mesure, puis agrégés
+/// par heure pour les graphiques.
+pub struct CapteurService {
+    db: Arc<dyn Storage>,
+}
+
+impl CapteurService {
+    /// Crée une nouvelle instance du service des capteurs
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Importe un export de contrôleur d'ambiance
+    ///
+    /// Les lignes illisibles ou hors plage sont refusées sans bloquer les
+    /// autres; les mesures déjà importées au même horodatage sont remplacées.
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `numero_batiment` - Bâtiment par défaut, pour les exports sans colonne bâtiment
+    /// * `format` - "csv" ou "json"
+    /// * `contenu` - Le contenu de l'export
+    ///
+    /// # Returns
+    /// Le nombre de mesures importées et les lignes refusées
+    pub async fn import_mesures(
+        &self,
+        ferme_id: i64,
+        numero_batiment: Option<String>,
+        format: &str,
+        contenu: &str,
+    ) -> AppResult<ResultatImportCapteurs> {
+        let lignes = match format.trim().to_lowercase().as_str() {
+            "csv" => lire_csv(contenu)?,
+            "json" => lire_json(contenu)?,
+            _ => {
+                return Err(AppError::validation_error(
+                    "format",
+                    &format!("Format non reconnu. Formats valides: {}", FORMATS_CAPTEURS.join(", ")),
+                ))
+            }
+        };
+
+        let conn = self.db.get_connection()?;
+        let (_, nbr_meuble, _, _) = PlanFermeRepository::get_ferme(&conn, ferme_id)?;
+        let batiment_defaut = numero_batiment.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        let lignes_lues = lignes.len();
+        let mut mesures = Vec::with_capacity(lignes_lues);
+        let mut rejets = Vec::new();
+        for (ligne, lue) in lignes {
+            match lue.and_then(|mesure| valider_mesure(mesure, batiment_defaut.as_deref(), nbr_meuble)) {
+                Ok(mesure) => mesures.push(mesure),
+                Err(motif) => rejets.push(LigneRejetee { ligne, motif }),
+            }
+        }
+
+        // Heures touchées par l'import, par bâtiment, pour recalculer les agrégats
+        let mut heures: BTreeMap<&str, (String, String)> = BTreeMap::new();
+        for mesure in &mesures {
+            let heure = format!("{}:00", &mesure.horodatage[..13]);
+            heures
+                .entry(mesure.numero_batiment.as_str())
+                .and_modify(|(debut, fin)| {
+                    if heure < *debut {
+                        *debut = heure.clone();
+                    }
+                    if heure > *fin {
+                        *fin = heure.clone();
+                    }
+                })
+                .or_insert_with(|| (heure.clone(), heure.clone()));
+        }
+
+        self.db.write(|tx| {
+            CapteurRepository::upsert_mesures(tx, ferme_id, &mesures)?;
+            for (numero, (debut, fin)) in &heures {
+                CapteurRepository::refresh_agregats(tx, ferme_id, numero, debut, fin)?;
+            }
+            Ok(())
+        })?;
+
+        let nombre_lignes_rejetees = rejets.len();
+        rejets.truncate(LIGNES_REJETEES_MAX);
+        Ok(ResultatImportCapteurs {
+            lignes_lues,
+            mesures_importees: mesures.len(),
+            lignes_rejetees: rejets,
+            nombre_lignes_rejetees,
+        })
+    }
+
+    /// Récupère les mesures agrégées d'un bâtiment pour les graphiques
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `numero_batiment` - Le numéro du bâtiment physique
+    /// * `date_from` - Premier jour inclus ("YYYY-MM-DD")
+    /// * `date_to` - Dernier jour inclus ("YYYY-MM-DD")
+    /// * `granularite` - "hour" ou "day" ("hour" par défaut)
+    ///
+    /// # Returns
+    /// Les agrégats triés par période
+    pub async fn get_agregats(
+        &self,
+        ferme_id: i64,
+        numero_batiment: &str,
+        date_from: Option<String>,
+        date_to: Option<String>,
+        granularite: Option<String>,
+    ) -> AppResult<Vec<AgregatCapteurs>> {
+        let par_jour = match granularite.as_deref().map(str::trim) {
+            None | Some("hour") => false,
+            Some("day") => true,
+            Some(_) => {
+                return Err(AppError::validation_error(
+                    "granularite",
+                    &format!("Granularité non reconnue. Granularités valides: {}", GRANULARITES_CAPTEURS.join(", ")),
+                ))
+            }
+        };
+
+        let conn = self.db.get_connection()?;
+        CapteurRepository::get_agregats(
+            &conn,
+            ferme_id,
+            numero_batiment.trim(),
+            par_jour,
+            date_from.as_deref(),
+            date_to.as_deref(),
+        )
+    }
+}
+
+/// Vérifie le bâtiment et les plages de valeurs d'une mesure lue
+fn valider_mesure(mesure: MesureLue, batiment_defaut: Option<&str>, nbr_meuble: i32) -> Result<MesureCapteur, String> {
+    let numero_batiment = mesure
+        .numero_batiment
+        .as_deref()
+        .or(batiment_defaut)
+        .ok_or("Bâtiment non précisé (ni dans la ligne, ni pour l'import)")?
+        .to_string();
+    if !numero_batiment.parse::<i32>().is_ok_and(|n| (1..=nbr_meuble).contains(&n)) {
+        return Err(format!(
+            "Bâtiment {} inconnu: le numéro doit être compris entre 1 et {}",
+            numero_batiment, nbr_meuble
+        ));
+    }
+
+    if mesure.temperature.is_none() && mesure.humidite.is_none() && mesure.co2.is_none() {
+        return Err("Aucune valeur de température, d'humidité ou de CO₂".to_string());
+    }
+    for (valeur, (min, max), libelle) in [
+        (mesure.temperature, PLAGE_TEMPERATURE, "Température"),
+        (mesure.humidite, PLAGE_HUMIDITE, "Humidité"),
+        (mesure.co2, PLAGE_CO2, "CO₂"),
+    ] {
+        if let Some(valeur) = valeur {
+            if !(min..=max).contains(&valeur) {
+                return Err(format!("{} hors plage ({} à {}): {}", libelle, min, max, valeur));
+            }
+        }
+    }
+
+    Ok(MesureCapteur {
+        numero_batiment,
+        horodatage: mesure.horodatage.format("%Y-%m-%d %H:%M:%S").to_string(),
+        temperature: mesure.temperature,
+        humidite: mesure.humidite,
+        co2: mesure.co2,
+    })
+}
+
+/// Normalise un nom de colonne: minuscules, sans accents ni unité entre parenthèses
+///
+/// "Température (°C)" donne "temperature", "CO₂ ppm" donne "co2_ppm".
+pub(crate) fn normaliser_colonne(nom: &str) -> String {
+    let sans_unite = nom.split(['(', '[']).next().unwrap_or(nom).replace('₂', "2");
+    normaliser_nom(&sans_unite)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|mot| !mot.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn est_colonne(nom: &str, alias: &[&str]) -> bool {
+    alias.contains(&nom)
+}
+
+/// Lit un export CSV avec en-tête (séparateur `;`, tabulation ou `,`)
+fn lire_csv(contenu: &str) -> AppResult<Vec<LigneLue>> {
+    let mut lignes = contenu
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .map(|(i, ligne)| (i + 1, ligne.trim()))
+        .filter(|(_, ligne)| !ligne.is_empty());
+
+    let (_, entete) = lignes
+        .next()
+        .ok_or_else(|| AppError::validation_error("contenu", "L'export est vide"))?;
+    let separateur = [';', '\t', ',']
+        .into_iter()
+        .find(|s| entete.contains(*s))
+        .unwrap_or(';');
+    let colonnes: Vec<String> = entete.split(separateur).map(normaliser_colonne).collect();
+    let position = |alias: &[&str]| colonnes.iter().position(|c| est_colonne(c, alias));
+
+    let horodatage = position(&COLONNES_HORODATAGE).ok_or_else(|| {
+        AppError::validation_error("contenu", "Colonne d'horodatage introuvable (horodatage, timestamp ou date)")
+    })?;
+    let temperature = position(&COLONNES_TEMPERATURE);
+    let humidite = position(&COLONNES_HUMIDITE);
+    let co2 = position(&COLONNES_CO2);
+    let batiment = position(&COLONNES_BATIMENT);
+    if temperature.is_none() && humidite.is_none() && co2.is_none() {
+        return Err(AppError::validation_error(
+            "contenu",
+            "Aucune colonne de température, d'humidité ou de CO₂ dans l'en-tête",
+        ));
+    }
+
+    Ok(lignes
+        .map(|(numero, ligne)| {
+            let champs: Vec<&str> = ligne.split(separateur).map(|c| c.trim().trim_matches('"')).collect();
+            let champ = |index: Option<usize>| index.and_then(|i| champs.get(i).copied()).filter(|c| !c.is_empty());
+            let valeur = |index: Option<usize>, libelle: &str| {
+                champ(index)
+                    .map(|c| lire_nombre(c).ok_or_else(|| format!("{} illisible: {}", libelle, c)))
+                    .transpose()
+            };
+
+            let lue = (|| {
+                let brut = champ(Some(horodatage)).ok_or("Horodatage manquant")?;
+                Ok(MesureLue {
+                    numero_batiment: champ(batiment).map(str::to_string),
+                    horodatage: lire_horodatage(brut).ok_or_else(|| format!("Horodatage illisible: {}", brut))?,
+                    temperature: valeur(temperature, "Température")?,
+                    humidite: valeur(humidite, "Humidité")?,
+                    co2: valeur(co2, "CO₂")?,
+                })
+            })();
+            (numero, lue)
+        })
+        .collect())
+}
+
+/// Lit un export JSON: un tableau de mesures, ou un objet contenant ce tableau
+/// sous `mesures`, `data` ou `readings`
+fn lire_json(contenu: &str) -> AppResult<Vec<LigneLue>> {
+    let document: Value = serde_json::from_str(contenu.trim_start_matches('\u{feff}'))
+        .map_err(|e| AppError::validation_error("contenu", &format!("JSON invalide: {}", e)))?;
+    let mesures = match &document {
+        Value::Array(mesures) => mesures,
+        Value::Object(objet) => ["mesures", "data", "readings"]
+            .iter()
+            .find_map(|cle| objet.get(*cle).and_then(Value::as_array))
+            .ok_or_else(|| {
+                AppError::validation_error("contenu", "Tableau de mesures introuvable (mesures, data ou readings)")
+            })?,
+        _ => return Err(AppError::validation_error("contenu", "Le JSON doit contenir un tableau de mesures")),
+    };
+
+    Ok(mesures
+        .iter()
+        .enumerate()
+        .map(|(i, mesure)| (i + 1, lire_mesure_json(mesure)))
+        .collect())
+}
+
+fn lire_mesure_json(mesure: &Value) -> Result<MesureLue, String> {
+    let objet = mesure.as_object().ok_or("La mesure n'est pas un objet")?;
+    let champs: Vec<(String, &Value)> = objet
+        .iter()
+        .filter(|(_, valeur)| !valeur.is_null())
+        .map(|(cle, valeur)| (normaliser_colonne(cle), valeur))
+        .collect();
+    let champ = |alias: &[&str]| champs.iter().find(|(cle, _)| est_colonne(cle, alias)).map(|(_, v)| *v);
+    let texte = |valeur: &Value| match valeur {
+        Value::String(s) => s.trim().to_string(),
+        autre => autre.to_string(),
+    };
+    let valeur = |alias: &[&str], libelle: &str| {
+        champ(alias)
+            .map(|v| match v {
+                Value::Number(n) => n.as_f64().ok_or_else(|| format!("{} illisible: {}", libelle, n)),
+                autre => lire_nombre(&texte(autre)).ok_or_else(|| format!("{} illisible: {}", libelle, autre)),
+            })
+            .transpose()
+    };
+
+    let brut = champ(&COLONNES_HORODATAGE).map(texte).ok_or("Horodatage manquant")?;
+    Ok(MesureLue {
+        numero_batiment: champ(&COLONNES_BATIMENT).map(texte).filter(|n| !n.is_empty()),
+        horodatage: lire_horodatage(&brut).ok_or_else(|| format!("Horodatage illisible: {}", brut))?,
+        temperature: valeur(&COLONNES_TEMPERATURE, "Température")?,
+        humidite: valeur(&COLONNES_HUMIDITE, "Humidité")?,
+        co2: valeur(&COLONNES_CO2, "CO₂")?,
+    })
+}
+
+/// Lit un nombre, avec virgule ou point décimal
+pub(crate) fn lire_nombre(texte: &str) -> Option<f64> {
+    texte.trim().replace(',', ".").parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Lit un horodatage de contrôleur
+///
+/// Formats acceptés: RFC 3339 (le décalage horaire est ignoré, l'heure locale
+/// du contrôleur est conservée), "YYYY-MM-DD HH:MM[:SS]" (ou avec `T`),
+/// "DD/MM/YYYY HH:MM[:SS]" et les secondes Unix.
+fn lire_horodatage(texte: &str) -> Option<NaiveDateTime> {
+    let texte = texte.trim();
+    if let Ok(horodatage) = DateTime::parse_from_rfc3339(texte) {
+        return Some(horodatage.naive_local());
+    }
+    const FORMATS: [&str; 6] = [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%d/%m/%Y %H:%M:%S",
+        "%d/%m/%Y %H:%M",
+    ];
+    if let Some(horodatage) = FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(texte, format).ok())
+    {
+        return Some(horodatage);
+    }
+    texte
+        .parse::<i64>()
+        .ok()
+        .and_then(|secondes| DateTime::from_timestamp(secondes, 0))
+        .map(|horodatage| horodatage.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn horodatage(texte: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(texte, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn controller_timestamps_are_parsed() {
+        let attendu = horodatage("2024-07-01 14:30:00");
+        for texte in [
+            "2024-07-01 14:30:00",
+            "2024-07-01T14:30",
+            "2024-07-01T14:30:00+01:00",
+            "01/07/2024 14:30",
+            "1719844200",
+        ] {
+            assert_eq!(lire_horodatage(texte), Some(attendu), "{}", texte);
+        }
+        assert_eq!(lire_horodatage("hier"), None);
+    }
+
+    #[test]
+    fn csv_export_with_units_and_decimal_commas_is_read() {
+        let lignes = lire_csv(
+            "Date;Bâtiment;Température (°C);HR (%);CO₂ (ppm)\n\
+             01/07/2024 14:00;2;31,5;62;1800\n\
+             01/07/2024 14:10;2;;;\n\
+             pas une date;2;30;60;1500\n",
+        )
+        .unwrap();
+
+        assert_eq!(lignes.len(), 3);
+        let premiere = lignes[0].1.as_ref().unwrap();
+        assert_eq!(premiere.numero_batiment.as_deref(), Some("2"));
+        assert_eq!(premiere.temperature, Some(31.5));
+        assert_eq!(premiere.humidite, Some(62.0));
+        assert_eq!(premiere.co2, Some(1800.0));
+        assert_eq!(lignes[1].1.as_ref().unwrap().temperature, None);
+        assert_eq!(lignes[2].0, 4);
+        assert!(lignes[2].1.is_err());
+    }
+
+    #[test]
+    fn csv_export_without_value_columns_is_refused() {
+        assert!(lire_csv("timestamp,batiment\n2024-07-01 14:00,1\n").is_err());
+        assert!(lire_csv("temperature,humidite\n30,60\n").is_err());
+    }
+
+    #[test]
+    fn json_export_in_an_object_is_read() {
+        let lignes = lire_json(
+            r#"{"readings": [
+                {"timestamp": "2024-07-01T14:00:00", "building": 1, "temp": 29.4, "rh": "58,5", "co2_ppm": 1200},
+                {"timestamp": "2024-07-01T14:10:00", "temp": "chaud"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let premiere = lignes[0].1.as_ref().unwrap();
+        assert_eq!(premiere.numero_batiment.as_deref(), Some("1"));
+        assert_eq!(premiere.humidite, Some(58.5));
+        assert_eq!(premiere.co2, Some(1200.0));
+        assert!(lignes[1].1.is_err());
+    }
+
+    #[test]
+    fn out_of_range_values_and_unknown_buildings_are_rejected() {
+        let mesure = MesureLue {
+            numero_batiment: None,
+            horodatage: horodatage("2024-07-01 14:00:00"),
+            temperature: Some(31.0),
+            humidite: Some(140.0),
+            co2: None,
+        };
+        assert!(valider_mesure(mesure.clone(), Some("1"), 4).unwrap_err().contains("Humidité"));
+
+        let mesure = MesureLue { humidite: Some(60.0), ..mesure };
+        assert!(valider_mesure(mesure.clone(), None, 4).is_err());
+        assert!(valider_mesure(mesure.clone(), Some("5"), 4).is_err());
+        assert_eq!(valider_mesure(mesure, Some("1"), 4).unwrap().horodatage, "2024-07-01 14:00:00");
+    }
+}
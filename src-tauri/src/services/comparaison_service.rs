@@ -0,0 +1,383 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    indice_production, saison, ComparaisonPerformance, IndiceProductionBande, IndicateursPerformance,
+    PerformancePeriode, StatistiquesSaisonnieres, SAISONS,
+};
+use crate::repositories::{BandeRepository, ProgrammeAlimentationRepository};
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+/// Résultats d'un bâtiment, base de tous les rapports de comparaison
+struct PerformanceBatiment {
+    batiment_id: i64,
+    numero_batiment: String,
+    ferme_id: i64,
+    bande_id: i64,
+    personnel_id: i64,
+    poussin_id: i64,
+    quantite: i64,
+    deces: i64,
+    elimines: i64,
+    aliment_kg: f64,
+    poids_final: Option<f64>,
+    /// Âge (jours) en fin de la dernière semaine pesée
+    age_final: Option<i32>,
+    poids_35j: Option<f64>,
+    /// Mois (1 à 12) de la date d'entrée de la bande
+    mois_entree: u32,
+    bande_cloturee: bool,
+}
+
+/// Cumuls d'un groupe de bâtiments avant calcul des ratios
+#[derive(Default)]
+struct Cumul {
+    bandes: HashSet<i64>,
+    batiments: i64,
+    poussins_places: i64,
+    deces: i64,
+    elimines: i64,
+    aliment_kg: f64,
+    // Seuls les bâtiments pesés entrent dans le poids moyen, l'indice de consommation
+    // et l'indice de production
+    poussins_peses: i64,
+    vivants_peses: f64,
+    age_vivants_peses: f64,
+    poids_vif_kg: f64,
+    aliment_kg_peses: f64,
+    vivants_35j: f64,
+    poids_vif_35j_kg: f64,
+}
+
+impl Cumul {
+    fn ajouter(&mut self, batiment: &PerformanceBatiment) {
+        self.bandes.insert(batiment.bande_id);
+        self.batiments += 1;
+        self.poussins_places += batiment.quantite;
+        self.deces += batiment.deces;
+        self.elimines += batiment.elimines;
+        self.aliment_kg += batiment.aliment_kg;
+
+        let vivants = (batiment.quantite - batiment.deces - batiment.elimines).max(0) as f64;
+        if let (Some(poids), Some(age)) = (batiment.poids_final, batiment.age_final) {
+            self.poussins_peses += batiment.quantite;
+            self.vivants_peses += vivants;
+            self.age_vivants_peses += vivants * age as f64;
+            self.poids_vif_kg += vivants * poids;
+            self.aliment_kg_peses += batiment.aliment_kg;
+        }
+
+        if let Some(poids) = batiment.poids_35j {
+            self.vivants_35j += vivants;
+            self.poids_vif_35j_kg += vivants * poids;
+        }
+    }
+
+    fn indicateurs(&self) -> IndicateursPerformance {
+        let indice_consommation = (self.poids_vif_kg > 0.0 && self.aliment_kg_peses > 0.0)
+            .then(|| self.aliment_kg_peses / self.poids_vif_kg);
+        let poids_moyen = (self.vivants_peses > 0.0).then(|| self.poids_vif_kg / self.vivants_peses);
+        let indice_production = match (poids_moyen, indice_consommation) {
+            (Some(poids), Some(ic)) if self.poussins_peses > 0 => indice_production(
+                self.vivants_peses * 100.0 / self.poussins_peses as f64,
+                poids,
+                self.age_vivants_peses / self.vivants_peses,
+                ic,
+            ),
+            _ => None,
+        };
+        let pourcentage =
+            |sujets: i64| (self.poussins_places > 0).then(|| sujets as f64 * 100.0 / self.poussins_places as f64);
+
+        IndicateursPerformance {
+            nombre_bandes: self.bandes.len() as i64,
+            nombre_batiments: self.batiments,
+            poussins_places: self.poussins_places,
+            total_deces: self.deces,
+            mortalite_pourcentage: pourcentage(self.deces),
+            total_elimines: self.elimines,
+            elimines_pourcentage: pourcentage(self.elimines),
+            viabilite_pourcentage: pourcentage((self.poussins_places - self.deces - self.elimines).max(0)),
+            aliment_kg: self.aliment_kg,
+            indice_consommation,
+            poids_moyen,
+            poids_35j: (self.vivants_35j > 0.0).then(|| self.poids_vif_35j_kg / self.vivants_35j),
+            cout_par_kg: None,
+            indice_production,
+        }
+    }
+}
+
+/// Rapports de comparaison des performances
+///
+/// Les bandes retenues sont celles entrées sur la période demandée; leurs
+/// résultats sont agrégés bâtiment par bâtiment.
+pub struct ComparaisonService {
+    db: Arc<dyn Storage>,
+}
+
+impl ComparaisonService {
+    /// Crée une nouvelle instance du service de comparaison
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Compare les indicateurs de toutes les fermes sur une période
+    ///
+    /// # Arguments
+    /// * `date_from` - Début de période inclus ("YYYY-MM-DD"), sur la date d'entrée des bandes
+    /// * `date_to` - Fin de période incluse ("YYYY-MM-DD")
+    ///
+    /// # Returns
+    /// Une ligne par ferme, triée par nom, y compris les fermes sans bande sur la période
+    pub async fn compare_fermes(
+        &self,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> AppResult<Vec<ComparaisonPerformance>> {
+        let conn = self.db.get_connection()?;
+        comparer(
+            &conn,
+            "SELECT id, nom FROM fermes ORDER BY nom COLLATE NOCASE",
+            |batiment| batiment.ferme_id,
+            &date_from,
+            &date_to,
+        )
+    }
+
+    /// Compare les résultats des bâtiments suivis par chaque technicien
+    ///
+    /// # Arguments
+    /// * `date_from` - Début de période inclus ("YYYY-MM-DD"), sur la date d'entrée des bandes
+    /// * `date_to` - Fin de période incluse ("YYYY-MM-DD")
+    ///
+    /// # Returns
+    /// Une ligne par membre du personnel, triée par nom
+    pub async fn compare_personnel(
+        &self,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> AppResult<Vec<ComparaisonPerformance>> {
+        let conn = self.db.get_connection()?;
+        comparer(
+            &conn,
+            "SELECT id, nom FROM personnel ORDER BY nom COLLATE NOCASE",
+            |batiment| batiment.personnel_id,
+            &date_from,
+            &date_to,
+        )
+    }
+
+    /// Compare la mortalité et la croissance par type de poussin (souche, couvoir)
+    ///
+    /// # Arguments
+    /// * `date_from` - Début de période inclus ("YYYY-MM-DD"), sur la date d'entrée des bandes
+    /// * `date_to` - Fin de période incluse ("YYYY-MM-DD")
+    ///
+    /// # Returns
+    /// Une ligne par type de poussin, triée par nom
+    pub async fn compare_poussins(
+        &self,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> AppResult<Vec<ComparaisonPerformance>> {
+        let conn = self.db.get_connection()?;
+        comparer(
+            &conn,
+            "SELECT id, nom FROM poussins ORDER BY nom COLLATE NOCASE",
+            |batiment| batiment.poussin_id,
+            &date_from,
+            &date_to,
+        )
+    }
+
+    /// Calcule l'indice de production européen (EPEF) d'une bande
+    ///
+    /// L'indice de chaque bâtiment utilise sa viabilité, son poids et son âge
+    /// à la dernière pesée et son indice de consommation; celui de la bande
+    /// agrège ses bâtiments pesés.
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    ///
+    /// # Returns
+    /// Les indicateurs de la bande et de chaque bâtiment, triés par numéro
+    pub async fn get_production_index(&self, bande_id: i64) -> AppResult<IndiceProductionBande> {
+        let conn = self.db.get_connection()?;
+        BandeRepository::get_statut(&conn, bande_id)?;
+
+        let mut bande = Cumul::default();
+        let mut batiments = Vec::new();
+        for batiment in performances_batiments(&conn, &None, &None, Some(bande_id))? {
+            bande.ajouter(&batiment);
+            let mut cumul = Cumul::default();
+            cumul.ajouter(&batiment);
+            batiments.push(ComparaisonPerformance {
+                id: batiment.batiment_id,
+                nom: batiment.numero_batiment,
+                indicateurs: cumul.indicateurs(),
+            });
+        }
+
+        Ok(IndiceProductionBande {
+            bande_id,
+            indicateurs: bande.indicateurs(),
+            batiments,
+        })
+    }
+
+    /// Compare les bandes clôturées selon leur mois et leur saison d'entrée
+    ///
+    /// Mesure l'effet de la saison (chaleur estivale notamment) sur la
+    /// mortalité, l'indice de consommation et le poids.
+    ///
+    /// # Arguments
+    /// * `ferme_id` - Restreint la comparaison à une ferme, toutes les fermes si absente
+    ///
+    /// # Returns
+    /// Les indicateurs de chaque mois puis de chaque saison
+    pub async fn get_seasonal_statistics(&self, ferme_id: Option<i64>) -> AppResult<StatistiquesSaisonnieres> {
+        let conn = self.db.get_connection()?;
+        if let Some(ferme_id) = ferme_id {
+            let ferme_exists: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM fermes WHERE id = ?1",
+                [ferme_id],
+                |row| row.get(0),
+            )?;
+            if ferme_exists == 0 {
+                return Err(AppError::not_found("Ferme", ferme_id));
+            }
+        }
+
+        let mut mois: BTreeMap<u32, Cumul> = BTreeMap::new();
+        let mut saisons: BTreeMap<&str, Cumul> = BTreeMap::new();
+        for batiment in performances_batiments(&conn, &None, &None, None)? {
+            if !batiment.bande_cloturee || ferme_id.is_some_and(|id| id != batiment.ferme_id) {
+                continue;
+            }
+            mois.entry(batiment.mois_entree).or_default().ajouter(&batiment);
+            saisons.entry(saison(batiment.mois_entree)).or_default().ajouter(&batiment);
+        }
+
+        Ok(StatistiquesSaisonnieres {
+            ferme_id,
+            mois: (1..=12)
+                .map(|numero| PerformancePeriode {
+                    periode: format!("{:02}", numero),
+                    indicateurs: mois.get(&numero).map(Cumul::indicateurs).unwrap_or_default(),
+                })
+                .collect(),
+            saisons: SAISONS
+                .iter()
+                .map(|nom| PerformancePeriode {
+                    periode: nom.to_string(),
+                    indicateurs: saisons.get(nom).map(Cumul::indicateurs).unwrap_or_default(),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Agrège les bâtiments de la période par groupe
+///
+/// # Arguments
+/// * `groupes_sql` - Requête listant `id, nom` des groupes à comparer, dans l'ordre du rapport
+/// * `groupe` - Identifiant du groupe auquel appartient un bâtiment
+fn comparer(
+    conn: &Connection,
+    groupes_sql: &str,
+    groupe: impl Fn(&PerformanceBatiment) -> i64,
+    date_from: &Option<String>,
+    date_to: &Option<String>,
+) -> AppResult<Vec<ComparaisonPerformance>> {
+    let mut cumuls: BTreeMap<i64, Cumul> = BTreeMap::new();
+    for batiment in performances_batiments(conn, date_from, date_to, None)? {
+        cumuls.entry(groupe(&batiment)).or_default().ajouter(&batiment);
+    }
+
+    let mut stmt = conn.prepare(groupes_sql)?;
+    let groupes = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(groupes
+        .into_iter()
+        .map(|(id, nom)| ComparaisonPerformance {
+            id,
+            nom,
+            indicateurs: cumuls.get(&id).map(Cumul::indicateurs).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Lit les résultats de chaque bâtiment des bandes entrées sur la période
+///
+/// Le poids final est celui de la dernière semaine pesée du bâtiment, le
+/// poids à 35 jours celui de la semaine 5.
+///
+/// # Arguments
+/// * `bande_id` - Restreint la lecture aux bâtiments d'une bande
+fn performances_batiments(
+    conn: &Connection,
+    date_from: &Option<String>,
+    date_to: &Option<String>,
+    bande_id: Option<i64>,
+) -> AppResult<Vec<PerformanceBatiment>> {
+    let kg_par_sachet = ProgrammeAlimentationRepository::get_kg_par_sachet(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT bd.ferme_id, bd.id, b.personnel_id, b.poussin_id, b.quantite,
+                COALESCE(suivi.deces, 0), COALESCE(suivi.sachets, 0),
+                (SELECT s.poids FROM semaines s
+                 WHERE s.batiment_id = b.id AND s.poids IS NOT NULL
+                 ORDER BY s.numero_semaine DESC LIMIT 1),
+                (SELECT s.poids FROM semaines s WHERE s.batiment_id = b.id AND s.numero_semaine = 5),
+                b.id, b.numero_batiment,
+                (SELECT s.numero_semaine * 7 FROM semaines s
+                 WHERE s.batiment_id = b.id AND s.poids IS NOT NULL
+                 ORDER BY s.numero_semaine DESC LIMIT 1),
+                COALESCE(suivi.elimines, 0),
+                CAST(strftime('%m', bd.date_entree) AS INTEGER), bd.statut = 'cloturee'
+         FROM batiments b
+         JOIN bandes bd ON b.bande_id = bd.id
+         LEFT JOIN (
+            SELECT s.batiment_id,
+                   SUM(COALESCE(sq.deces_par_jour, 0)) AS deces,
+                   SUM(COALESCE(sq.elimines_par_jour, 0)) AS elimines,
+                   SUM(COALESCE(sq.alimentation_par_jour, 0)) AS sachets
+            FROM suivi_quotidien sq
+            JOIN semaines s ON sq.semaine_id = s.id
+            GROUP BY s.batiment_id
+         ) suivi ON suivi.batiment_id = b.id
+         WHERE (?1 IS NULL OR bd.date_entree >= date(?1))
+           AND (?2 IS NULL OR bd.date_entree <= date(?2))
+           AND (?3 IS NULL OR bd.id = ?3)
+         ORDER BY CAST(b.numero_batiment AS INTEGER), b.numero_batiment",
+    )?;
+
+    let batiments = stmt.query_map(rusqlite::params![date_from, date_to, bande_id], |row| {
+        let sachets: f64 = row.get(6)?;
+        Ok(PerformanceBatiment {
+            batiment_id: row.get(9)?,
+            numero_batiment: row.get(10)?,
+            ferme_id: row.get(0)?,
+            bande_id: row.get(1)?,
+            personnel_id: row.get(2)?,
+            poussin_id: row.get(3)?,
+            quantite: row.get(4)?,
+            deces: row.get(5)?,
+            elimines: row.get(12)?,
+            aliment_kg: sachets * kg_par_sachet,
+            poids_final: row.get(7)?,
+            age_final: row.get(11)?,
+            poids_35j: row.get(8)?,
+            mois_entree: row.get(13)?,
+            bande_cloturee: row.get(14)?,
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(batiments)
+}
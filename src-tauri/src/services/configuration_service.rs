@@ -0,0 +1,77 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{ConfigurationAppliquee, ConfigurationInitiale, CreateFerme};
+use crate::repositories::{FermeRepository, ProgrammeAlimentationRepository, SemaineRepository};
+use std::sync::Arc;
+
+/// Service de configuration initiale de l'application
+pub struct ConfigurationService {
+    db: Arc<dyn Storage>,
+}
+
+impl ConfigurationService {
+    /// Crée une nouvelle instance du service de configuration
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Indique si l'assistant de premier démarrage doit être proposé
+    ///
+    /// # Returns
+    /// Vrai tant qu'aucune ferme n'a été créée
+    pub async fn needs_first_run_setup(&self) -> AppResult<bool> {
+        let conn = self.db.get_connection()?;
+        let fermes: i64 = conn.query_row("SELECT COUNT(*) FROM fermes", [], |row| row.get(0))?;
+        Ok(fermes == 0)
+    }
+
+    /// Enregistre la configuration saisie dans l'assistant de premier démarrage
+    ///
+    /// Les fermes et les paramètres sont enregistrés dans une seule
+    /// transaction: une donnée refusée n'en laisse aucune autre en place.
+    /// L'assistant n'est accepté que sur une base sans ferme.
+    ///
+    /// # Arguments
+    /// * `configuration` - Les fermes, le nombre de semaines et le poids d'un sachet
+    ///
+    /// # Returns
+    /// Les fermes créées et les paramètres en vigueur
+    pub async fn first_run_setup(&self, configuration: ConfigurationInitiale) -> AppResult<ConfigurationAppliquee> {
+        if configuration.fermes.is_empty() {
+            return Err(AppError::validation_error("fermes", "Au moins une ferme doit être créée"));
+        }
+
+        self.db.write(|tx| {
+            let fermes_existantes: i64 = tx.query_row("SELECT COUNT(*) FROM fermes", [], |row| row.get(0))?;
+            if fermes_existantes > 0 {
+                return Err(AppError::business_logic(
+                    "L'application est déjà configurée: l'assistant de premier démarrage n'est plus disponible",
+                ));
+            }
+
+            let fermes = configuration
+                .fermes
+                .iter()
+                .map(|ferme| {
+                    FermeRepository::inserer(
+                        tx,
+                        CreateFerme { nom: ferme.nom.trim().to_string(), nbr_meuble: ferme.nbr_meuble },
+                    )
+                })
+                .collect::<AppResult<Vec<_>>>()?;
+            let semaines_max = match configuration.semaines_max {
+                Some(semaines_max) => SemaineRepository::set_semaines_max(tx, semaines_max)?,
+                None => SemaineRepository::get_semaines_max(tx)?,
+            };
+            let kg_par_sachet = match configuration.kg_par_sachet {
+                Some(kg_par_sachet) => ProgrammeAlimentationRepository::set_kg_par_sachet(tx, kg_par_sachet)?,
+                None => ProgrammeAlimentationRepository::get_kg_par_sachet(tx)?,
+            };
+
+            Ok(ConfigurationAppliquee { fermes, semaines_max, kg_par_sachet })
+        })
+    }
+}
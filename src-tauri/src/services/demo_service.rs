@@ -0,0 +1,325 @@
+use crate::database::{normaliser_nom, Storage};
+use crate::error::{AppError, AppResult};
+use crate::repositories::structurer_quantites_soins;
+use chrono::{Duration, Local, NaiveDate};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Nombre maximal de fermes générées en un appel
+const MAX_SCALE: u32 = 50;
+
+/// Bandes générées par ferme
+const BANDES_PAR_FERME: i64 = 3;
+
+/// Poids moyen de référence (kg) en fin de semaine 1 à 8
+const POIDS_REFERENCE: [f64; 8] = [0.18, 0.47, 0.90, 1.40, 1.95, 2.50, 3.00, 3.40];
+
+/// Résumé des données de démonstration créées
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoDataSummary {
+    pub fermes: i64,
+    pub bandes: i64,
+    pub batiments: i64,
+    pub semaines: i64,
+    pub suivis: i64,
+}
+
+/// Générateur pseudo-aléatoire déterministe (xorshift)
+///
+/// Suffisant pour varier les courbes de démonstration sans dépendance
+/// supplémentaire, et reproductible d'une génération à l'autre.
+struct DemoRng(u64);
+
+impl DemoRng {
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Valeur uniforme dans `[min, max)`
+    fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+}
+
+/// Service de génération de données de démonstration
+///
+/// Crée des fermes, bandes, bâtiments et 8 semaines de suivi quotidien
+/// avec des courbes de mortalité et de consommation plausibles, pour les
+/// démonstrations et pour tester les performances des listes.
+pub struct DemoService {
+    db: Arc<dyn Storage>,
+}
+
+impl DemoService {
+    /// Crée une nouvelle instance du service de démonstration
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Génère `scale` fermes de démonstration avec leurs bandes et leur suivi
+    ///
+    /// Chaque ferme reçoit 3 bandes successives de 2 à 4 bâtiments; chaque
+    /// bâtiment a ses 8 semaines (poids compris) et 56 jours de suivi. Les
+    /// livraisons d'aliment couvrent la consommation afin que le contour de
+    /// chaque bande reste positif. Tout est créé dans une seule transaction.
+    ///
+    /// # Arguments
+    /// * `scale` - Le nombre de fermes à créer (1 à 50)
+    ///
+    /// # Returns
+    /// Le nombre d'éléments créés par type
+    pub async fn generate_demo_data(&self, scale: u32) -> AppResult<DemoDataSummary> {
+        if scale == 0 || scale > MAX_SCALE {
+            return Err(AppError::validation_error(
+                "scale",
+                &format!("L'échelle doit être comprise entre 1 et {}", MAX_SCALE),
+            ));
+        }
+
+        self.db.write(|tx| {
+            // Référentiels partagés, réutilisés s'ils existent déjà
+            for (nom, telephone) in [
+                ("Technicien démo A", "0600000001"),
+                ("Technicien démo B", "0600000002"),
+                ("Technicien démo C", "0600000003"),
+            ] {
+                tx.execute(
+                    "INSERT OR IGNORE INTO personnel (nom, nom_normalise, telephone) VALUES (?1, ?2, ?3)",
+                    params![nom, normaliser_nom(nom), telephone],
+                )?;
+            }
+            for nom in ["Cobb 500", "Ross 308", "Arbor Acres"] {
+                tx.execute(
+                    "INSERT OR IGNORE INTO poussins (nom, nom_normalise) VALUES (?1, ?2)",
+                    [nom, &normaliser_nom(nom)],
+                )?;
+            }
+            for (nom, unit, categorie, delai_attente_jours) in [
+                ("Vitamine AD3E", "ml", "vitamine", 0),
+                ("Anticoccidien", "g", "anticoccidien", 5),
+                ("Vaccin Gumboro", "dose", "vaccin", 0),
+            ] {
+                tx.execute(
+                    "INSERT OR IGNORE INTO soins (nom, nom_normalise, unit, categorie, delai_attente_jours) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![nom, normaliser_nom(nom), unit, categorie, delai_attente_jours],
+                )?;
+            }
+
+            // Recherche par nom normalisé: un référentiel saisi avec une autre casse est réutilisé
+            let personnel_ids = select_ids(tx, "SELECT id FROM personnel WHERE nom_normalise LIKE 'technicien demo %' ORDER BY id")?;
+            let poussin_ids = select_ids(tx, "SELECT id FROM poussins WHERE nom_normalise IN ('cobb 500', 'ross 308', 'arbor acres') ORDER BY id")?;
+            let soin_ids = select_ids(tx, "SELECT id FROM soins WHERE nom_normalise IN ('vitamine ad3e', 'anticoccidien', 'vaccin gumboro') ORDER BY id")?;
+
+            // Numéroter après le plus grand numéro de ferme de démonstration existant:
+            // un comptage réutiliserait un nom encore pris après une suppression
+            let existing: i64 = tx.query_row(
+                "SELECT COALESCE(MAX(CAST(SUBSTR(nom_normalise, 12) AS INTEGER)), 0)
+                 FROM fermes WHERE nom_normalise LIKE 'ferme demo %'",
+                [],
+                |row| row.get(0),
+            )?;
+
+            let mut rng = DemoRng(0x9E37_79B9_7F4A_7C15 ^ (existing as u64 + 1));
+            let mut summary = DemoDataSummary { fermes: 0, bandes: 0, batiments: 0, semaines: 0, suivis: 0 };
+            let today = Local::now().date_naive();
+
+            let mut insert_suivi = tx.prepare(
+                "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, alimentation_par_jour, remarques, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+            )?;
+            let mut insert_soin = tx.prepare(
+                "INSERT INTO suivi_soins (suivi_id, soin_id, quantite) VALUES (?1, ?2, ?3)",
+            )?;
+
+            for f in 0..scale as i64 {
+                let nbr_meuble = rng.range(2.0, 6.0) as i32;
+                let nom_ferme = format!("Ferme démo {}", existing + f + 1);
+                tx.execute(
+                    "INSERT INTO fermes (nom, nom_normalise, nbr_meuble) VALUES (?1, ?2, ?3)",
+                    params![nom_ferme, normaliser_nom(&nom_ferme), nbr_meuble],
+                )?;
+                let ferme_id = tx.last_insert_rowid();
+                summary.fermes += 1;
+
+                for b in 0..BANDES_PAR_FERME {
+                    // Bandes successives: la plus récente a démarré il y a environ 8 semaines
+                    let date_entree: NaiveDate = today
+                        - Duration::days(56 + (BANDES_PAR_FERME - 1 - b) * 70 + rng.range(0.0, 7.0) as i64);
+                    tx.execute(
+                        "INSERT INTO bandes (numero_bande, date_entree, ferme_id, notes, created_at, updated_at)
+                         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        params![b + 1, date_entree.to_string(), ferme_id, "Données de démonstration"],
+                    )?;
+                    let bande_id = tx.last_insert_rowid();
+                    summary.bandes += 1;
+
+                    let nb_batiments = rng.range(2.0, 5.0) as i64;
+                    let mut consommation_sachets = 0.0;
+
+                    for n in 1..=nb_batiments {
+                        let quantite = (rng.range(4.0, 12.0) as i32) * 1000;
+                        let poussin_id = poussin_ids[(b as usize + n as usize) % poussin_ids.len()];
+                        let personnel_id = personnel_ids[(f as usize + n as usize) % personnel_ids.len()];
+                        tx.execute(
+                            "INSERT INTO batiments (bande_id, numero_batiment, poussin_id, personnel_id, quantite, created_at, updated_at)
+                             VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                            params![bande_id, n.to_string(), poussin_id, personnel_id, quantite],
+                        )?;
+                        let batiment_id = tx.last_insert_rowid();
+                        summary.batiments += 1;
+
+                        let performance = rng.range(0.92, 1.06);
+                        let mut effectif = quantite as f64;
+
+                        for numero_semaine in 1..=8i32 {
+                            let poids = (POIDS_REFERENCE[numero_semaine as usize - 1] * performance * 100.0).round() / 100.0;
+                            tx.execute(
+                                "INSERT INTO semaines (batiment_id, numero_semaine, poids, created_at, updated_at)
+                                 VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                                params![batiment_id, numero_semaine, poids],
+                            )?;
+                            let semaine_id = tx.last_insert_rowid();
+                            summary.semaines += 1;
+
+                            for day in 0..7 {
+                                let age = (numero_semaine - 1) * 7 + 1 + day;
+
+                                // Mortalité élevée la première semaine puis stabilisée autour de 0,05 %/jour
+                                let taux = 0.0005 + 0.004 * (-(age as f64) / 4.0).exp();
+                                let deces = (effectif * taux * rng.range(0.5, 1.5)).round();
+                                effectif -= deces;
+
+                                // Consommation en g/sujet/jour, convertie en sachets de 50 kg
+                                let grammes = (12.0 + 4.0 * age as f64).min(210.0) * rng.range(0.95, 1.05);
+                                let sachets = (effectif * grammes / 1000.0 / 50.0 * 2.0).round() / 2.0;
+                                consommation_sachets += sachets;
+
+                                // Vitamines au démarrage, anticoccidien en semaine 3 et vaccin (avec vitamines) à J14
+                                let (soins, remarques) = match age {
+                                    1..=3 => (vec![(soin_ids[0], "1 ml/L".to_string())], None),
+                                    14 => (
+                                        vec![
+                                            (soin_ids[2], format!("{} dose", effectif as i64)),
+                                            (soin_ids[0], "1 ml/L".to_string()),
+                                        ],
+                                        Some("Vaccination"),
+                                    ),
+                                    18..=20 => (vec![(soin_ids[1], "0,5 g/L".to_string())], None),
+                                    _ => (Vec::new(), None),
+                                };
+
+                                insert_suivi.execute(params![
+                                    semaine_id,
+                                    age,
+                                    deces as i32,
+                                    sachets,
+                                    remarques,
+                                ])?;
+                                let suivi_id = tx.last_insert_rowid();
+                                for (soin_id, quantite) in soins {
+                                    insert_soin.execute(params![suivi_id, soin_id, quantite])?;
+                                }
+                                summary.suivis += 1;
+                            }
+                        }
+                    }
+
+                    // Livraisons couvrant la consommation, avec une marge de stock
+                    let besoin_kg = consommation_sachets * 50.0;
+                    let livraisons = [(0.35, "démarrage"), (0.40, "croissance"), (0.35, "finition")];
+                    for (i, (part, type_aliment)) in livraisons.iter().enumerate() {
+                        let quantite = (besoin_kg * part / 50.0).round() * 50.0;
+                        let date = date_entree + Duration::days(i as i64 * 18);
+                        tx.execute(
+                            "INSERT INTO alimentation_history (bande_id, quantite, created_at, fournisseur, type_aliment, numero_lot)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                            params![
+                                bande_id,
+                                quantite,
+                                format!("{} 08:00:00", date),
+                                "Provenderie démo",
+                                type_aliment,
+                                format!("LOT-{}-{}", bande_id, i + 1),
+                            ],
+                        )?;
+                        tx.execute(
+                            "UPDATE bandes SET alimentation_contour = alimentation_contour + ?1 WHERE id = ?2",
+                            params![quantite, bande_id],
+                        )?;
+                    }
+                    tx.execute(
+                        "UPDATE bandes SET alimentation_contour = alimentation_contour - ?1 WHERE id = ?2",
+                        params![besoin_kg, bande_id],
+                    )?;
+                }
+            }
+
+            drop(insert_soin);
+            drop(insert_suivi);
+            structurer_quantites_soins(tx, "ss.quantite_valeur IS NULL AND ss.quantite IS NOT NULL", &[])?;
+
+            Ok(summary)
+        })
+    }
+}
+
+/// Exécute une requête retournant une colonne d'IDs
+fn select_ids(conn: &rusqlite::Connection, sql: &str) -> AppResult<Vec<i64>> {
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MemoryStorage;
+
+    #[tokio::test]
+    async fn generated_data_is_consistent() {
+        let storage = MemoryStorage::new();
+        let service = DemoService::new(storage.clone());
+
+        let summary = service.generate_demo_data(2).await.unwrap();
+        assert_eq!(summary.fermes, 2);
+        assert_eq!(summary.bandes, 6);
+        assert_eq!(summary.semaines, summary.batiments * 8);
+        assert_eq!(summary.suivis, summary.batiments * 56);
+
+        // Une seconde génération ne heurte pas les noms uniques
+        service.generate_demo_data(1).await.unwrap();
+
+        // Ni après le renommage d'une ferme de démonstration
+        let conn = storage.get_connection().unwrap();
+        conn.execute("UPDATE fermes SET nom = 'Ferme du Nord', nom_normalise = 'ferme du nord' WHERE nom = 'Ferme démo 1'", [])
+            .unwrap();
+        drop(conn);
+        service.generate_demo_data(1).await.unwrap();
+
+        let conn = storage.get_connection().unwrap();
+        let dernier: String = conn
+            .query_row("SELECT nom FROM fermes ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(dernier, "Ferme démo 4");
+        let negative_contours: i64 = conn
+            .query_row("SELECT COUNT(*) FROM bandes WHERE alimentation_contour < 0", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(negative_contours, 0);
+    }
+
+    #[tokio::test]
+    async fn scale_is_bounded() {
+        let service = DemoService::new(MemoryStorage::new());
+        assert!(service.generate_demo_data(0).await.is_err());
+        assert!(service.generate_demo_data(MAX_SCALE + 1).await.is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use crate::database::{ActionModification, DatabaseManager, LigneModifiee};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, PoisonError, RwLock};
+use tauri::{AppHandle, Emitter, Runtime, Wry};
+
+/// Nom de l'événement Tauri émis après chaque modification de données
+pub const EVENEMENT_ENTITE_MODIFIEE: &str = "entity://changed";
+
+/// Entité signalée lorsqu'une autre base de données est ouverte: toutes les
+/// vues doivent être rechargées
+pub const ENTITE_PROFIL: &str = "profil";
+
+/// Au-delà de ce nombre de lignes d'une même entité modifiées par une écriture,
+/// un seul événement sans ID est émis (suppression en cascade, import...)
+const LIGNES_PAR_EVENEMENT_MAX: usize = 20;
+
+/// Tables suivies et nom de l'entité correspondante dans les événements
+///
+/// Les tables techniques de `TABLES_NON_SUIVIES` ne sont pas signalées; une
+/// table ajoutée au schéma doit figurer dans l'une des deux listes.
+const ENTITES_SUIVIES: [(&str, &str); 38] = [
+    ("fermes", "ferme"),
+    ("bandes", "bande"),
+    ("batiments", "batiment"),
+    ("semaines", "semaine"),
+    ("suivi_quotidien", "suivi_quotidien"),
+    ("suivi_soins", "suivi_soin"),
+    ("alimentation_history", "alimentation"),
+    ("personnel", "personnel"),
+    ("soins", "soin"),
+    ("poussins", "poussin"),
+    ("phases_alimentation", "phase_alimentation"),
+    ("maladies", "maladie"),
+    ("batiment_maladies", "batiment_maladie"),
+    ("analyses", "analyse"),
+    ("notes_batiment", "note_batiment"),
+    ("litieres", "litiere"),
+    ("messages", "message"),
+    ("vides_sanitaires", "vide_sanitaire"),
+    ("immobilisations", "immobilisation"),
+    ("equipements", "equipement"),
+    ("entretiens_equipement", "entretien_equipement"),
+    ("positions_batiments", "position_batiment"),
+    ("meteo_quotidienne", "meteo"),
+    ("mesures_capteurs", "mesure_capteur"),
+    ("champs_personnalises", "champ_personnalise"),
+    ("valeurs_champs_personnalises", "valeur_champ_personnalise"),
+    ("tags", "tag"),
+    ("bande_tags", "bande_tag"),
+    ("plan_soins", "plan_soin"),
+    ("historique_prix", "prix"),
+    ("budgets_bande", "budget_bande"),
+    ("contrats_bande", "contrat_bande"),
+    ("deverrouillages_periode", "deverrouillage_periode"),
+    ("alertes", "alerte"),
+    ("abonnements_alertes", "abonnement_alerte"),
+    ("exports_programmes", "export_programme"),
+    ("report_definitions", "rapport"),
+    ("filtres_enregistres", "filtre_enregistre"),
+];
+
+/// Tables techniques dont les modifications ne sont pas signalées: comptes,
+/// sessions et second facteur, journal d'audit, paramètres et préférences,
+/// agrégats et files d'envoi
+const TABLES_NON_SUIVIES: [&str; 10] = [
+    "users",
+    "invitations",
+    "sessions",
+    "user_mfa",
+    "audit_log",
+    "parametres",
+    "user_preferences",
+    "metriques_commandes",
+    "mesures_capteurs_horaires",
+    "webhook_outbox",
+];
+
+/// Événement émis après la modification d'une entité
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntityChanged {
+    /// Type d'entité, ex: "bande", "suivi_quotidien"
+    pub entity: String,
+    /// ID de l'entité, `None` lorsque plusieurs lignes ont été modifiées à la fois
+    pub id: Option<i64>,
+    /// "created", "updated" ou "deleted", ou "opened" pour l'entité `profil`
+    pub action: String,
+}
+
+/// Destinataire des événements de modification
+pub trait EventSink: Send + Sync {
+    fn emit(&self, event: &EntityChanged);
+}
+
+/// Destinataire qui émet les événements vers toutes les fenêtres de l'application
+pub struct TauriEventSink<R: Runtime = Wry> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> TauriEventSink<R> {
+    pub fn new(app: AppHandle<R>) -> Self {
+        Self { app }
+    }
+}
+
+impl<R: Runtime> EventSink for TauriEventSink<R> {
+    fn emit(&self, event: &EntityChanged) {
+        // Aucune fenêtre à l'écoute n'est pas une erreur
+        let _ = self.app.emit(EVENEMENT_ENTITE_MODIFIEE, event.clone());
+    }
+}
+
+/// Bus d'événements de modification des données
+///
+/// Reçoit les lignes modifiées de chaque écriture validée et les publie sous
+/// forme d'événements `entity://changed`, afin que les vues ouvertes dans
+/// plusieurs fenêtres se rafraîchissent sans interroger la base.
+#[derive(Default)]
+pub struct EventBus {
+    sinks: RwLock<Vec<Arc<dyn EventSink>>>,
+}
+
+impl EventBus {
+    /// Crée un bus sans destinataire
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Ajoute un destinataire des événements
+    pub fn subscribe(&self, sink: Arc<dyn EventSink>) {
+        self.sinks.write().unwrap_or_else(PoisonError::into_inner).push(sink);
+    }
+
+    /// Publie les événements correspondant aux écritures validées du gestionnaire
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données dont les écritures sont suivies
+    pub fn attach(self: &Arc<Self>, db: &DatabaseManager) {
+        let bus = self.clone();
+        db.subscribe_changes(Arc::new(move |lignes: &[LigneModifiee]| {
+            for event in evenements(lignes) {
+                bus.publish(&event);
+            }
+        }));
+    }
+
+    /// Signale l'ouverture de la base d'un autre profil
+    pub fn publish_profile_opened(&self) {
+        self.publish(&EntityChanged { entity: ENTITE_PROFIL.to_string(), id: None, action: "opened".to_string() });
+    }
+
+    /// Transmet un événement à tous les destinataires
+    pub fn publish(&self, event: &EntityChanged) {
+        for sink in self.sinks.read().unwrap_or_else(PoisonError::into_inner).iter() {
+            sink.emit(event);
+        }
+    }
+}
+
+/// Regroupe les lignes modifiées d'une écriture en événements, par entité et action
+fn evenements(lignes: &[LigneModifiee]) -> Vec<EntityChanged> {
+    let mut groupes: BTreeMap<(&str, &str), Vec<i64>> = BTreeMap::new();
+    for ligne in lignes {
+        let Some((_, entity)) = ENTITES_SUIVIES.iter().find(|(table, _)| *table == ligne.table) else {
+            continue;
+        };
+        let action = match ligne.action {
+            ActionModification::Insertion => "created",
+            ActionModification::MiseAJour => "updated",
+            ActionModification::Suppression => "deleted",
+        };
+        let ids = groupes.entry((entity, action)).or_default();
+        if !ids.contains(&ligne.id) {
+            ids.push(ligne.id);
+        }
+    }
+
+    let mut evenements = Vec::new();
+    for ((entity, action), ids) in groupes {
+        let evenement = |id| EntityChanged { entity: entity.to_string(), id, action: action.to_string() };
+        if ids.len() > LIGNES_PAR_EVENEMENT_MAX {
+            evenements.push(evenement(None));
+        } else {
+            evenements.extend(ids.into_iter().map(|id| evenement(Some(id))));
+        }
+    }
+    evenements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Storage;
+    use crate::test_support::MemoryStorage;
+
+    fn ligne(table: &str, id: i64, action: ActionModification) -> LigneModifiee {
+        LigneModifiee { table: table.to_string(), id, action }
+    }
+
+    #[test]
+    fn changes_are_grouped_per_entity_and_technical_tables_ignored() {
+        let mut lignes = vec![
+            ligne("bandes", 3, ActionModification::MiseAJour),
+            ligne("bandes", 3, ActionModification::MiseAJour),
+            ligne("sessions", 1, ActionModification::Insertion),
+            ligne("audit_log", 9, ActionModification::Insertion),
+        ];
+        lignes.extend((1..=30).map(|id| ligne("suivi_quotidien", id, ActionModification::Suppression)));
+
+        let evenements = evenements(&lignes);
+        assert_eq!(
+            evenements,
+            vec![
+                EntityChanged { entity: "bande".to_string(), id: Some(3), action: "updated".to_string() },
+                EntityChanged { entity: "suivi_quotidien".to_string(), id: None, action: "deleted".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn every_table_of_the_schema_is_tracked_or_declared_technical() {
+        let storage = MemoryStorage::new();
+        let conn = storage.get_connection().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .unwrap();
+        let tables: Vec<String> = stmt.query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+
+        let oubliees: Vec<&String> = tables
+            .iter()
+            .filter(|table| {
+                !ENTITES_SUIVIES.iter().any(|(suivie, _)| suivie == table) && !TABLES_NON_SUIVIES.contains(&table.as_str())
+            })
+            .collect();
+        assert!(oubliees.is_empty(), "tables ni suivies ni déclarées techniques: {:?}", oubliees);
+
+        // Pas de table disparue du schéma dans les listes
+        for table in ENTITES_SUIVIES.iter().map(|(table, _)| *table).chain(TABLES_NON_SUIVIES) {
+            assert!(tables.iter().any(|nom| nom == table), "table inconnue: {}", table);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CreateExportProgramme, ExportProgramme, EXPORT_COMPTABILITE, EXPORT_INSTANTANE, EXPORT_RAPPORT_HEBDOMADAIRE,
+    EXPORT_SAUVEGARDE, EXPORT_TVA, FREQUENCES_EXPORT, FREQUENCE_HEBDOMADAIRE, FREQUENCE_MENSUELLE, FREQUENCE_QUOTIDIENNE,
+    STATUT_EXPORT_ECHEC, STATUT_EXPORT_SUCCES, TYPES_EXPORT_PROGRAMME,
+};
+use crate::repositories::{ExportProgrammeRepository, ExportRepository};
+use crate::services::{AuthService, ExportService};
+use chrono::{DateTime, Datelike, Duration as Jours, Local, Months, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Intervalle de vérification des exports programmés en arrière-plan
+pub const INTERVALLE_VERIFICATION_EXPORTS: Duration = Duration::from_secs(300);
+
+/// Format des dates d'exécution des exports programmés, enregistrées en UTC
+/// comme les `created_at` (`CURRENT_TIMESTAMP`)
+const FORMAT_DATE_EXPORT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Service des exports programmés
+///
+/// Chaque programmation lance un export (instantané de reporting, rapport
+/// hebdomadaire CSV, journal comptable CSV, récapitulatif de TVA CSV ou
+/// sauvegarde de la base) vers un dossier choisi par l'utilisateur,
+/// typiquement un partage réseau. La tâche
+/// de fond lance les exports arrivés à échéance et enregistre le statut de
+/// leur dernière exécution.
+pub struct ExportProgrammeService {
+    db: Arc<dyn Storage>,
+}
+
+impl ExportProgrammeService {
+    /// Crée une nouvelle instance du service des exports programmés
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Programme un export (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `export` - Le type d'export, le dossier de destination et la fréquence
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// L'export programmé
+    pub async fn create_scheduled_export(
+        &self,
+        mut export: CreateExportProgramme,
+        token: &str,
+    ) -> AppResult<ExportProgramme> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+
+        if !TYPES_EXPORT_PROGRAMME.contains(&export.type_export.as_str()) {
+            return Err(AppError::validation_error(
+                "type_export",
+                &format!("Type d'export inconnu, attendu: {}", TYPES_EXPORT_PROGRAMME.join(", ")),
+            ));
+        }
+        if !FREQUENCES_EXPORT.contains(&export.frequence.as_str()) {
+            return Err(AppError::validation_error(
+                "frequence",
+                &format!("Fréquence inconnue, attendue: {}", FREQUENCES_EXPORT.join(", ")),
+            ));
+        }
+        if export.heure > 23 {
+            return Err(AppError::validation_error("heure", "L'heure doit être comprise entre 0 et 23"));
+        }
+        match export.frequence.as_str() {
+            FREQUENCE_HEBDOMADAIRE if !matches!(export.jour, Some(1..=7)) => {
+                return Err(AppError::validation_error("jour", "Le jour de la semaine doit être compris entre 1 (lundi) et 7"));
+            }
+            FREQUENCE_MENSUELLE if !matches!(export.jour, Some(1..=28)) => {
+                return Err(AppError::validation_error("jour", "Le jour du mois doit être compris entre 1 et 28"));
+            }
+            FREQUENCE_QUOTIDIENNE => export.jour = None,
+            _ => {}
+        }
+        if !Path::new(export.dossier.trim()).is_dir() {
+            return Err(AppError::validation_error("dossier", "Le dossier de destination n'existe pas"));
+        }
+
+        self.db.write(|tx| ExportProgrammeRepository::create(tx, &export))
+    }
+
+    /// Liste les exports programmés avec le statut de leur dernière exécution
+    pub async fn get_scheduled_exports(&self) -> AppResult<Vec<ExportProgramme>> {
+        let conn = self.db.get_connection()?;
+        ExportProgrammeRepository::get_all(&conn)
+    }
+
+    /// Supprime un export programmé (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de l'export programmé
+    /// * `token` - Le token de session d'un administrateur
+    pub async fn delete_scheduled_export(&self, id: i64, token: &str) -> AppResult<()> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        self.db.write(|tx| ExportProgrammeRepository::delete(tx, id))
+    }
+
+    /// Lance un export programmé sans attendre son échéance (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de l'export programmé
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// L'export programmé avec le statut de cette exécution
+    pub async fn run_scheduled_export(&self, id: i64, token: &str) -> AppResult<ExportProgramme> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        let export = {
+            let conn = self.db.get_connection()?;
+            ExportProgrammeRepository::get_by_id(&conn, id)?.ok_or_else(|| AppError::not_found("ExportProgramme", id))?
+        };
+        self.lancer(&export).await?;
+
+        let conn = self.db.get_connection()?;
+        ExportProgrammeRepository::get_by_id(&conn, id)?.ok_or_else(|| AppError::not_found("ExportProgramme", id))
+    }
+
+    /// Tâche de fond: lance les exports arrivés à échéance
+    ///
+    /// # Arguments
+    /// * `intervalle` - Délai entre deux vérifications
+    pub async fn run_scheduled_exports(self, intervalle: Duration) {
+        let mut horloge = tokio::time::interval(intervalle);
+        loop {
+            horloge.tick().await;
+            // Une erreur passagère (base occupée) sera retentée au prochain tour
+            if let Ok(exports) = self.get_scheduled_exports().await {
+                let maintenant = Local::now();
+                for export in exports.iter().filter(|export| export_du(export, maintenant)) {
+                    let _ = self.lancer(export).await;
+                }
+            }
+        }
+    }
+
+    /// Exécute un export et enregistre son statut, qu'il réussisse ou non
+    async fn lancer(&self, export: &ExportProgramme) -> AppResult<()> {
+        let executee_le = Local::now();
+        let (statut, message) = match self.executer(export, executee_le.naive_local()).await {
+            Ok(chemin) => (STATUT_EXPORT_SUCCES, chemin),
+            Err(e) => (STATUT_EXPORT_ECHEC, e.to_string()),
+        };
+        self.db.write(|tx| {
+            ExportProgrammeRepository::record_run(
+                tx,
+                export.id,
+                &executee_le.naive_utc().format(FORMAT_DATE_EXPORT).to_string(),
+                statut,
+                &message,
+            )
+        })
+    }
+
+    /// Crée le fichier de l'export dans son dossier
+    ///
+    /// # Returns
+    /// Le chemin du fichier créé
+    async fn executer(&self, export: &ExportProgramme, executee_le: NaiveDateTime) -> AppResult<String> {
+        let extension = if export.type_export == EXPORT_INSTANTANE || export.type_export == EXPORT_SAUVEGARDE {
+            "db"
+        } else {
+            "csv"
+        };
+        let destination = Path::new(&export.dossier)
+            .join(format!("{}_{}.{}", export.type_export, executee_le.format("%Y%m%d_%H%M%S"), extension));
+
+        match export.type_export.as_str() {
+            EXPORT_INSTANTANE => {
+                ExportService::new(self.db.clone()).export_reporting_snapshot(&destination.to_string_lossy()).await?;
+            }
+            EXPORT_RAPPORT_HEBDOMADAIRE => {
+                let conn = self.db.get_connection()?;
+                let lignes = ExportRepository::weekly_report(&conn, &executee_le.date().format("%Y-%m-%d").to_string())?;
+                ecrire_csv(&destination, &lignes)?;
+            }
+            EXPORT_COMPTABILITE => {
+                let conn = self.db.get_connection()?;
+                let lignes = ExportRepository::accounting_ledger(&conn)?;
+                ecrire_csv(&destination, &lignes)?;
+            }
+            EXPORT_TVA => {
+                let conn = self.db.get_connection()?;
+                let lignes = ExportRepository::vat_summary_rows(&conn, executee_le.year())?;
+                ecrire_csv(&destination, &lignes)?;
+            }
+            EXPORT_SAUVEGARDE => {
+                ExportService::new(self.db.clone()).backup_database(&destination.to_string_lossy()).await?;
+            }
+            autre => return Err(AppError::validation_error("type_export", &format!("Type d'export inconnu: {}", autre))),
+        }
+
+        Ok(destination.to_string_lossy().into_owned())
+    }
+}
+
+/// Vrai si la dernière échéance de l'export est postérieure à sa dernière
+/// exécution (ou à sa programmation s'il n'a jamais été lancé)
+///
+/// Les échéances sont des heures locales, les dates enregistrées sont en UTC:
+/// la comparaison se fait entre instants, l'échéance étant convertie en UTC.
+fn export_du<Tz: TimeZone>(export: &ExportProgramme, maintenant: DateTime<Tz>) -> bool {
+    let fuseau = maintenant.timezone();
+    let Some(echeance) = derniere_echeance(maintenant.naive_local(), &export.frequence, export.heure, export.jour) else {
+        return false;
+    };
+    // Heure sautée au passage à l'heure d'été: l'échéance passe à l'heure suivante
+    let Some(echeance) = fuseau
+        .from_local_datetime(&echeance)
+        .earliest()
+        .or_else(|| fuseau.from_local_datetime(&(echeance + Jours::hours(1))).earliest())
+    else {
+        return false;
+    };
+    let reference = export.derniere_execution.as_deref().unwrap_or(&export.created_at);
+    match NaiveDateTime::parse_from_str(reference, FORMAT_DATE_EXPORT) {
+        Ok(reference) => echeance.with_timezone(&Utc) > Utc.from_utc_datetime(&reference),
+        Err(_) => true,
+    }
+}
+
+/// Dernière échéance d'une programmation à `maintenant` ou avant
+///
+/// `jour` est le jour de la semaine (1 = lundi) pour une fréquence
+/// hebdomadaire, le jour du mois pour une fréquence mensuelle.
+pub fn derniere_echeance(maintenant: NaiveDateTime, frequence: &str, heure: u32, jour: Option<u32>) -> Option<NaiveDateTime> {
+    let heure = NaiveTime::from_hms_opt(heure, 0, 0)?;
+    let aujourd_hui = maintenant.date();
+    match frequence {
+        FREQUENCE_QUOTIDIENNE => {
+            let echeance = aujourd_hui.and_time(heure);
+            Some(if echeance > maintenant { echeance - Jours::days(1) } else { echeance })
+        }
+        FREQUENCE_HEBDOMADAIRE => {
+            let jour = jour.filter(|jour| (1..=7).contains(jour))?;
+            let ecart = (aujourd_hui.weekday().number_from_monday() + 7 - jour) % 7;
+            let echeance = (aujourd_hui - Jours::days(ecart as i64)).and_time(heure);
+            Some(if echeance > maintenant { echeance - Jours::days(7) } else { echeance })
+        }
+        FREQUENCE_MENSUELLE => {
+            let echeance = aujourd_hui.with_day(jour?)?.and_time(heure);
+            if echeance > maintenant {
+                echeance.checked_sub_months(Months::new(1))
+            } else {
+                Some(echeance)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Écrit l'export dans un fichier provisoire puis le renomme: un export
+/// interrompu ne laisse pas de fichier incomplet dans le dossier partagé
+pub(crate) fn ecrire(destination: &Path, creer: impl FnOnce(&Path) -> AppResult<()>) -> AppResult<()> {
+    let mut provisoire = destination.as_os_str().to_owned();
+    provisoire.push("-export");
+    let provisoire = PathBuf::from(provisoire);
+    let _ = fs::remove_file(&provisoire);
+
+    if let Err(e) = creer(&provisoire) {
+        let _ = fs::remove_file(&provisoire);
+        return Err(e);
+    }
+    fs::rename(&provisoire, destination)
+        .map_err(|e| AppError::business_logic(&format!("Impossible d'écrire le fichier d'export: {}", e)))
+}
+
+pub(crate) fn ecrire_csv(destination: &Path, lignes: &[Vec<String>]) -> AppResult<()> {
+    ecrire(destination, |provisoire| {
+        fs::write(provisoire, csv(lignes))
+            .map_err(|e| AppError::business_logic(&format!("Impossible d'écrire le fichier d'export: {}", e)))
+    })
+}
+
+/// Met des lignes au format CSV (séparateur `;`, lu directement par Excel en français)
+fn csv(lignes: &[Vec<String>]) -> String {
+    let mut contenu = String::new();
+    for ligne in lignes {
+        let champs: Vec<String> = ligne
+            .iter()
+            .map(|champ| {
+                if champ.contains([';', '"', '\n', '\r']) {
+                    format!("\"{}\"", champ.replace('"', "\"\""))
+                } else {
+                    champ.clone()
+                }
+            })
+            .collect();
+        contenu.push_str(&champs.join(";"));
+        contenu.push_str("\r\n");
+    }
+    contenu
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(texte: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(texte, FORMAT_DATE_EXPORT).unwrap()
+    }
+
+    #[test]
+    fn last_due_date_follows_the_frequency() {
+        // Mercredi 13 mars 2024, 10h
+        let maintenant = date("2024-03-13 10:00:00");
+        assert_eq!(derniere_echeance(maintenant, FREQUENCE_QUOTIDIENNE, 6, None), Some(date("2024-03-13 06:00:00")));
+        assert_eq!(derniere_echeance(maintenant, FREQUENCE_QUOTIDIENNE, 22, None), Some(date("2024-03-12 22:00:00")));
+        assert_eq!(derniere_echeance(maintenant, FREQUENCE_HEBDOMADAIRE, 8, Some(1)), Some(date("2024-03-11 08:00:00")));
+        assert_eq!(derniere_echeance(maintenant, FREQUENCE_HEBDOMADAIRE, 12, Some(3)), Some(date("2024-03-06 12:00:00")));
+        assert_eq!(derniere_echeance(maintenant, FREQUENCE_MENSUELLE, 0, Some(20)), Some(date("2024-02-20 00:00:00")));
+        assert_eq!(derniere_echeance(maintenant, FREQUENCE_HEBDOMADAIRE, 8, None), None);
+    }
+
+    #[test]
+    fn due_dates_in_local_time_are_compared_with_utc_timestamps() {
+        let export = |created_at: &str, derniere_execution: Option<&str>| ExportProgramme {
+            id: 1,
+            type_export: EXPORT_COMPTABILITE.to_string(),
+            dossier: "/exports".to_string(),
+            frequence: FREQUENCE_QUOTIDIENNE.to_string(),
+            heure: 9,
+            jour: None,
+            created_at: created_at.to_string(),
+            derniere_execution: derniere_execution.map(str::to_string),
+            dernier_statut: None,
+            dernier_message: None,
+        };
+        // UTC+2: l'échéance de 9h locale tombe à 7h UTC
+        let fuseau = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        let maintenant = fuseau.from_local_datetime(&date("2024-03-13 10:45:00")).unwrap();
+
+        // Programmé à 10h30 locale (8h30 UTC), après l'échéance du jour
+        assert!(!export_du(&export("2024-03-13 08:30:00", None), maintenant));
+        // Programmé à 8h30 locale (6h30 UTC), avant l'échéance du jour
+        assert!(export_du(&export("2024-03-13 06:30:00", None), maintenant));
+        // Lancé à 9h05 locale (7h05 UTC): pas de nouvelle échéance depuis
+        assert!(!export_du(&export("2024-03-01 06:30:00", Some("2024-03-13 07:05:00")), maintenant));
+        assert!(export_du(&export("2024-03-01 06:30:00", Some("2024-03-12 07:05:00")), maintenant));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        let lignes = vec![vec!["ferme".to_string(), "note".to_string()], vec!["Nord".to_string(), "a;\"b\"".to_string()]];
+        assert_eq!(csv(&lignes), "ferme;note\r\nNord;\"a;\"\"b\"\"\"\r\n");
+    }
+}
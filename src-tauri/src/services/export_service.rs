@@ -0,0 +1,195 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{EchangeBande, RapportExport, TvaTrimestre, FORMAT_ECHANGE_BANDE, VERSION_ECHANGE_BANDE};
+use crate::repositories::ExportRepository;
+use crate::services::export_programme_service::{ecrire, ecrire_csv};
+use chrono::Local;
+use rusqlite::Connection;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Nom sous lequel le fichier d'export est attaché pendant sa création
+const SCHEMA_EXPORT: &str = "instantane";
+
+/// Service d'export des données pour les outils de reporting
+///
+/// L'export est un fichier SQLite indépendant, non chiffré, contenant des
+/// tables dénormalisées (`bande_facts`, `daily_facts`): il peut être ouvert
+/// dans Excel ou Power BI sans accéder à la base de l'application. Le
+/// récapitulatif de TVA est exporté en CSV, et une bande peut être exportée
+/// seule dans le format d'échange JSON (`EchangeBande`).
+pub struct ExportService {
+    db: Arc<dyn Storage>,
+}
+
+impl ExportService {
+    /// Crée une nouvelle instance du service d'export
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Exporte un instantané des bandes et du suivi quotidien
+    ///
+    /// Le fichier est d'abord écrit à côté de la destination puis renommé: un
+    /// export interrompu ne laisse pas de fichier incomplet, et un export
+    /// précédent au même chemin est remplacé.
+    ///
+    /// # Arguments
+    /// * `path` - Le chemin du fichier SQLite à créer
+    ///
+    /// # Returns
+    /// Le chemin du fichier et le nombre de lignes exportées
+    pub async fn export_reporting_snapshot(&self, path: &str) -> AppResult<RapportExport> {
+        let destination = destination_export(path)?;
+
+        let mut provisoire = destination.as_os_str().to_owned();
+        provisoire.push("-export");
+        let provisoire = PathBuf::from(provisoire);
+        supprimer_si_present(&provisoire)?;
+
+        let conn = self.db.get_connection()?;
+        // `KEY ''`: le fichier d'export n'est pas chiffré, même si la base l'est
+        conn.execute(
+            &format!("ATTACH DATABASE ?1 AS {} KEY ''", SCHEMA_EXPORT),
+            [provisoire.to_string_lossy()],
+        )?;
+        let resultat = creer_tables(&conn);
+        conn.execute(&format!("DETACH DATABASE {}", SCHEMA_EXPORT), [])?;
+        let (bandes, jours) = match resultat {
+            Ok(lignes) => lignes,
+            Err(e) => {
+                let _ = supprimer_si_present(&provisoire);
+                return Err(e);
+            }
+        };
+
+        fs::rename(&provisoire, &destination)
+            .map_err(|e| AppError::business_logic(&format!("Impossible d'écrire le fichier d'export: {}", e)))?;
+
+        Ok(RapportExport {
+            chemin: destination.to_string_lossy().into_owned(),
+            bandes,
+            jours,
+            genere_le: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        })
+    }
+
+    /// Sauvegarde la base de données dans un fichier SQLite compacté
+    ///
+    /// La copie est cohérente même si l'application écrit pendant la
+    /// sauvegarde; elle est chiffrée comme la base.
+    ///
+    /// # Arguments
+    /// * `path` - Le chemin du fichier de sauvegarde (remplacé s'il existe)
+    ///
+    /// # Returns
+    /// Le chemin du fichier créé
+    pub async fn backup_database(&self, path: &str) -> AppResult<String> {
+        let destination = destination_export(path)?;
+        let conn = self.db.get_connection()?;
+        ecrire(&destination, |provisoire| {
+            conn.execute("VACUUM INTO ?1", [provisoire.to_string_lossy()])?;
+            Ok(())
+        })?;
+        Ok(destination.to_string_lossy().into_owned())
+    }
+
+    /// Récapitule la TVA payée sur les achats d'une année, par trimestre et par taux
+    ///
+    /// # Arguments
+    /// * `annee` - L'année civile
+    ///
+    /// # Returns
+    /// Une ligne par trimestre et par taux, triées par trimestre
+    pub async fn get_vat_summary(&self, annee: i32) -> AppResult<Vec<TvaTrimestre>> {
+        let conn = self.db.get_connection()?;
+        ExportRepository::vat_summary(&conn, annee)
+    }
+
+    /// Exporte le récapitulatif de TVA d'une année dans un fichier CSV
+    ///
+    /// # Arguments
+    /// * `annee` - L'année civile
+    /// * `path` - Le chemin du fichier CSV à créer (remplacé s'il existe)
+    ///
+    /// # Returns
+    /// Le chemin du fichier créé
+    pub async fn export_vat_summary(&self, annee: i32, path: &str) -> AppResult<String> {
+        let destination = destination_export(path)?;
+        let conn = self.db.get_connection()?;
+        let lignes = ExportRepository::vat_summary_rows(&conn, annee)?;
+        ecrire_csv(&destination, &lignes)?;
+        Ok(destination.to_string_lossy().into_owned())
+    }
+
+    /// Exporte une bande dans le format d'échange JSON versionné
+    ///
+    /// Le document peut être lu par un autre logiciel d'élevage, envoyé au
+    /// portail de l'intégrateur, ou réimporté avec `import_bande_json`.
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `path` - Le chemin du fichier JSON à créer (remplacé s'il existe)
+    ///
+    /// # Returns
+    /// Le chemin du fichier créé
+    pub async fn export_bande_json(&self, bande_id: i64, path: &str) -> AppResult<String> {
+        let destination = destination_export(path)?;
+        let conn = self.db.get_connection()?;
+        // Transaction de lecture: bâtiments, semaines et jours reflètent le même état
+        let tx = conn.unchecked_transaction()?;
+        let bande = ExportRepository::exchange_bande(&tx, bande_id)?;
+        drop(tx);
+
+        let document = EchangeBande {
+            format: FORMAT_ECHANGE_BANDE.to_string(),
+            version: VERSION_ECHANGE_BANDE,
+            exporte_le: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            bande,
+        };
+        let contenu = serde_json::to_string_pretty(&document)
+            .map_err(|e| AppError::business_logic(&format!("Impossible de générer le document JSON: {}", e)))?;
+        ecrire(&destination, |provisoire| {
+            fs::write(provisoire, contenu)
+                .map_err(|e| AppError::business_logic(&format!("Impossible d'écrire le fichier d'export: {}", e)))
+        })?;
+        Ok(destination.to_string_lossy().into_owned())
+    }
+}
+
+/// Chemin de destination d'un export, dont le dossier doit exister
+fn destination_export(path: &str) -> AppResult<PathBuf> {
+    let destination = PathBuf::from(path.trim());
+    if path.trim().is_empty() {
+        return Err(AppError::validation_error("path", "Le chemin du fichier d'export est obligatoire"));
+    }
+    if let Some(dossier) = destination.parent().filter(|dossier| !dossier.as_os_str().is_empty()) {
+        if !dossier.is_dir() {
+            return Err(AppError::validation_error("path", "Le dossier de destination n'existe pas"));
+        }
+    }
+    Ok(destination)
+}
+
+/// Crée les tables de l'export dans une transaction de lecture: les deux
+/// tables reflètent le même état des données
+fn creer_tables(conn: &Connection) -> AppResult<(i64, i64)> {
+    conn.execute_batch(&format!("PRAGMA {}.journal_mode = DELETE;", SCHEMA_EXPORT))?;
+    let tx = conn.unchecked_transaction()?;
+    let lignes = ExportRepository::create_reporting_tables(&tx, SCHEMA_EXPORT)?;
+    tx.commit()?;
+    Ok(lignes)
+}
+
+fn supprimer_si_present(chemin: &Path) -> AppResult<()> {
+    match fs::remove_file(chemin) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(AppError::business_logic(&format!("Impossible de supprimer {}: {}", chemin.display(), e)))
+        }
+        _ => Ok(()),
+    }
+}
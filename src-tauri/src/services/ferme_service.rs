@@ -1,7 +1,7 @@
-use crate::database::DatabaseManager;
+use crate::database::Storage;
 use crate::error::{AppError, AppResult};
 use crate::models::{Ferme, CreateFerme, UpdateFerme};
-use crate::repositories::{FermeRepository, FermeRepositoryTrait, GlobalStatistics, BandeDeathData};
+use crate::repositories::{FermeRepository, FermeRepositoryTrait, GlobalStatistics, BandeDeathData, FermeBreakdown};
 use std::sync::Arc;
 
 /// Service pour la gestion des fermes
@@ -18,7 +18,7 @@ impl FermeService {
     /// 
     /// # Arguments
     /// * `db` - Le gestionnaire de base de données partagé
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
         let repository = Arc::new(FermeRepository::new(db));
         Self { repository }
     }
@@ -78,6 +78,7 @@ impl FermeService {
             id: ferme.id,
             nom: ferme.nom.trim().to_string(),
             nbr_meuble: ferme.nbr_meuble,
+            version: ferme.version,
         };
 
         self.repository.update(cleaned_ferme).await
@@ -131,16 +132,33 @@ impl FermeService {
 
     /// Obtient des statistiques sur les fermes
     /// 
+    /// Une bande est active entre sa date d'entrée et son enlèvement prévu.
+    /// La mortalité moyenne est pondérée par le nombre de sujets mis en place.
+    /// 
     /// # Returns
-    /// Un objet contenant les statistiques des fermes
+    /// Un objet contenant les statistiques des fermes et leur détail par ferme
     pub async fn get_ferme_statistics(&self) -> AppResult<FermeStatistics> {
-        let fermes = self.repository.get_all().await?;
-        
+        let fermes = self.repository.get_ferme_breakdown().await?;
+
+        let total_fermes = fermes.len() as i32;
+        let total_bandes: i32 = fermes.iter().map(|f| f.total_bandes).sum();
+        let poussins_places: i64 = fermes.iter().map(|f| f.poussins_places).sum();
+        let total_deaths: i64 = fermes.iter().map(|f| f.total_deaths).sum();
+
         Ok(FermeStatistics {
-            total_fermes: fermes.len() as i32,
-            // TODO: Ajouter plus de statistiques quand les bandes seront implémentées
-            fermes_with_active_bandes: 0, // Placeholder
-            average_bandes_per_ferme: 0.0, // Placeholder
+            total_fermes,
+            fermes_with_active_bandes: fermes.iter().filter(|f| f.active_bandes > 0).count() as i32,
+            average_bandes_per_ferme: if total_fermes > 0 {
+                total_bandes as f64 / total_fermes as f64
+            } else {
+                0.0
+            },
+            average_mortality_rate: if poussins_places > 0 {
+                total_deaths as f64 / poussins_places as f64 * 100.0
+            } else {
+                0.0
+            },
+            fermes,
         })
     }
 
@@ -220,6 +238,9 @@ pub struct FermeStatistics {
     pub total_fermes: i32,
     pub fermes_with_active_bandes: i32,
     pub average_bandes_per_ferme: f64,
+    /// Mortalité moyenne en % des sujets mis en place
+    pub average_mortality_rate: f64,
+    pub fermes: Vec<FermeBreakdown>,
 }
 
 /// Statistiques détaillées pour une ferme spécifique
@@ -236,6 +257,86 @@ pub struct FermeDetailedStatistics {
     pub bande_deaths_data: Vec<BandeDeathData>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MemoryStorage, seed_bande, seed_batiment, seed_ferme};
+
+    #[tokio::test]
+    async fn create_ferme_trims_name_and_rejects_duplicates() {
+        let storage = MemoryStorage::new();
+        let service = FermeService::new(storage);
 
+        let ferme = service.create_ferme(CreateFerme { nom: "  Ferme A  ".to_string(), nbr_meuble: 4 }).await.unwrap();
+        assert_eq!(ferme.nom, "Ferme A");
 
+        let duplicate = service.create_ferme(CreateFerme { nom: "Ferme A".to_string(), nbr_meuble: 2 }).await;
+        assert!(matches!(duplicate, Err(AppError::ValidationError { .. })));
+    }
+
+    #[tokio::test]
+    async fn delete_ferme_with_bandes_is_refused() {
+        let storage = MemoryStorage::new();
+        let ferme_id = seed_ferme(storage.as_ref(), "Ferme B", 2);
+        seed_bande(storage.as_ref(), ferme_id, 1, "2024-01-10");
+        let service = FermeService::new(storage);
+
+        let result = service.delete_ferme(ferme_id).await;
+        assert!(matches!(result, Err(AppError::ConstraintViolation { .. })));
+    }
 
+    #[tokio::test]
+    async fn detailed_statistics_sum_deaths_per_bande() {
+        let storage = MemoryStorage::new();
+        let ferme_id = seed_ferme(storage.as_ref(), "Ferme C", 2);
+        let bande_id = seed_bande(storage.as_ref(), ferme_id, 1, "2024-03-01");
+        let batiment_id = seed_batiment(storage.as_ref(), bande_id, "1", 1000);
+        {
+            let conn = storage.get_connection().unwrap();
+            conn.execute("INSERT INTO semaines (batiment_id, numero_semaine) VALUES (?1, 1)", [batiment_id]).unwrap();
+            let semaine_id = conn.last_insert_rowid();
+            conn.execute(
+                "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, 1, 3), (?1, 2, 4)",
+                [semaine_id],
+            ).unwrap();
+        }
+        let service = FermeService::new(storage);
+
+        let stats = service.get_ferme_detailed_statistics(ferme_id).await.unwrap();
+        assert_eq!(stats.total_bandes, 1);
+        assert_eq!(stats.total_deaths, 7);
+        assert_eq!(stats.bandes_with_deaths, 1);
+    }
+
+    #[tokio::test]
+    async fn statistics_count_active_bandes_and_mortality() {
+        let storage = MemoryStorage::new();
+        let ferme_a = seed_ferme(storage.as_ref(), "Ferme A", 2);
+        let ferme_b = seed_ferme(storage.as_ref(), "Ferme B", 2);
+        let en_cours = (chrono::Local::now().date_naive() - chrono::Duration::days(3)).to_string();
+        let bande_active = seed_bande(storage.as_ref(), ferme_a, 1, &en_cours);
+        seed_bande(storage.as_ref(), ferme_a, 2, "2023-01-10");
+        let bande_terminee = seed_bande(storage.as_ref(), ferme_b, 1, "2023-05-01");
+        let batiment_id = seed_batiment(storage.as_ref(), bande_active, "1", 1000);
+        seed_batiment(storage.as_ref(), bande_terminee, "1", 3000);
+        {
+            let conn = storage.get_connection().unwrap();
+            conn.execute("INSERT INTO semaines (batiment_id, numero_semaine) VALUES (?1, 1)", [batiment_id]).unwrap();
+            let semaine_id = conn.last_insert_rowid();
+            conn.execute(
+                "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, 1, 30), (?1, 2, 10)",
+                [semaine_id],
+            ).unwrap();
+        }
+        let service = FermeService::new(storage);
+
+        let stats = service.get_ferme_statistics().await.unwrap();
+        assert_eq!(stats.total_fermes, 2);
+        assert_eq!(stats.fermes_with_active_bandes, 1);
+        assert_eq!(stats.average_bandes_per_ferme, 1.5);
+        assert_eq!(stats.average_mortality_rate, 1.0);
+        assert_eq!(stats.fermes[0].active_bandes, 1);
+        assert_eq!(stats.fermes[0].mortality_rate, Some(4.0));
+        assert_eq!(stats.fermes[1].active_bandes, 0);
+    }
+}
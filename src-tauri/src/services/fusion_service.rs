@@ -0,0 +1,116 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{ResultatFusion, ENTITES_FUSION};
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::Arc;
+
+/// Fusion des doublons
+///
+/// Une faute de frappe à la saisie crée un second membre du personnel, soin,
+/// type de poussin ou maladie qui accumule son propre historique. La fusion
+/// rattache toutes les références du doublon à l'entité conservée, puis
+/// supprime le doublon, en une seule transaction.
+pub struct FusionService {
+    db: Arc<dyn Storage>,
+}
+
+impl FusionService {
+    /// Crée une nouvelle instance du service
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Fusionne un doublon dans l'entité conservée
+    ///
+    /// # Arguments
+    /// * `entity` - Le type d'entité: "personnel", "soin", "poussin" ou "maladie"
+    /// * `source_id` - L'ID du doublon à supprimer
+    /// * `target_id` - L'ID de l'entité conservée
+    ///
+    /// # Returns
+    /// Le nombre de lignes rattachées à l'entité conservée
+    pub async fn merge_entities(&self, entity: &str, source_id: i64, target_id: i64) -> AppResult<ResultatFusion> {
+        let entity = entity.trim().to_lowercase();
+        let (table, libelle) = match entity.as_str() {
+            "personnel" => ("personnel", "Personnel"),
+            "soin" => ("soins", "Soin"),
+            "poussin" => ("poussins", "Poussin"),
+            "maladie" => ("maladies", "Maladie"),
+            _ => {
+                return Err(AppError::validation_error(
+                    "entity",
+                    &format!("Entité non reconnue. Entités valides: {}", ENTITES_FUSION.join(", ")),
+                ))
+            }
+        };
+
+        if source_id == target_id {
+            return Err(AppError::validation_error(
+                "target_id",
+                "Le doublon et l'entité conservée doivent être différents",
+            ));
+        }
+
+        let (nom_conserve, lignes_rattachees) = self.db.write(|tx| {
+            find_nom(tx, table, libelle, source_id)?;
+            let nom_conserve = find_nom(tx, table, libelle, target_id)?;
+
+            let ids = [target_id, source_id];
+            let lignes_rattachees = match entity.as_str() {
+                "personnel" => tx.execute("UPDATE batiments SET personnel_id = ?1 WHERE personnel_id = ?2", ids)?,
+                "soin" => {
+                    tx.execute("UPDATE suivi_soins SET soin_id = ?1 WHERE soin_id = ?2", ids)?
+                        + tx.execute("UPDATE suivi_quotidien SET soins_id = ?1 WHERE soins_id = ?2", ids)?
+                        + tx.execute("UPDATE OR IGNORE plan_soins SET soin_id = ?1 WHERE soin_id = ?2", ids)?
+                }
+                "poussin" => {
+                    // Le programme d'alimentation du doublon n'est repris que si
+                    // l'entité conservée n'en a pas encore
+                    let phases_cible: i64 = tx.query_row(
+                        "SELECT COUNT(*) FROM phases_alimentation WHERE poussin_id = ?1",
+                        [target_id],
+                        |row| row.get(0),
+                    )?;
+                    let phases = if phases_cible == 0 {
+                        tx.execute("UPDATE phases_alimentation SET poussin_id = ?1 WHERE poussin_id = ?2", ids)?
+                    } else {
+                        0
+                    };
+                    tx.execute("UPDATE batiments SET poussin_id = ?1 WHERE poussin_id = ?2", ids)?
+                        + tx.execute("UPDATE OR IGNORE plan_soins SET poussin_id = ?1 WHERE poussin_id = ?2", ids)?
+                        + tx.execute("UPDATE historique_prix SET poussin_id = ?1 WHERE poussin_id = ?2", ids)?
+                        + phases
+                }
+                _ => {
+                    // Un bâtiment déjà touché par les deux maladies ne garde qu'un
+                    // épisode: celui du doublon est supprimé avec lui (cascade)
+                    let episodes = tx.execute(
+                        "UPDATE OR IGNORE batiment_maladies SET maladie_id = ?1 WHERE maladie_id = ?2",
+                        ids,
+                    )?;
+                    episodes + tx.execute("UPDATE analyses SET maladie_id = ?1 WHERE maladie_id = ?2", ids)?
+                }
+            };
+
+            tx.execute(&format!("DELETE FROM {} WHERE id = ?1", table), [source_id])?;
+            Ok((nom_conserve, lignes_rattachees))
+        })?;
+
+        Ok(ResultatFusion {
+            entity,
+            source_id,
+            target_id,
+            nom_conserve,
+            lignes_rattachees: lignes_rattachees as i64,
+        })
+    }
+}
+
+fn find_nom(conn: &Connection, table: &str, libelle: &str, id: i64) -> AppResult<String> {
+    conn.query_row(&format!("SELECT nom FROM {} WHERE id = ?1", table), [id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| AppError::not_found(libelle, id))
+}
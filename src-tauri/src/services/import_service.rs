@@ -0,0 +1,725 @@
+use crate::database::{normaliser_nom, Storage};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    BandeEchangee, CreateBande, CreateBatiment, EchangeBande, ErreurImport, FeuilleClasseur, RapportFeuilleImport,
+    RapportImportBande, RapportImportClasseur, FORMAT_ECHANGE_BANDE, STATUT_BANDE_CLOTUREE, VERSION_ECHANGE_BANDE,
+};
+use crate::repositories::{
+    structurer_quantites_soins, BandeRepository, BatimentRepository, SemaineRepository, VideSanitaireRepository,
+};
+use crate::services::capteur_service::{lire_nombre, normaliser_colonne};
+use crate::services::AuthService;
+use chrono::{Days, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::sync::Arc;
+
+/// Libellés reconnus dans l'en-tête d'une feuille, après normalisation
+const CLES_FERME: [&str; 2] = ["ferme", "nom_de_la_ferme"];
+const CLES_DATE_ENTREE: [&str; 4] = ["date_d_entree", "date_entree", "entree", "date_de_mise_en_place"];
+const CLES_SOUCHE: [&str; 4] = ["souche", "poussin", "type_de_poussin", "race"];
+const CLES_TECHNICIEN: [&str; 3] = ["technicien", "personnel", "responsable"];
+const CLES_EFFECTIF: [&str; 4] = ["effectif", "quantite", "poussins_places", "mise_en_place"];
+
+/// Colonnes reconnues dans le tableau de suivi, après normalisation
+const COLONNES_BATIMENT: [&str; 3] = ["batiment", "batiments", "bat"];
+const COLONNES_AGE: [&str; 2] = ["age", "jour"];
+const COLONNES_DECES: [&str; 3] = ["deces", "morts", "mortalite"];
+const COLONNES_ELIMINES: [&str; 2] = ["elimines", "eliminations"];
+const COLONNES_ALIMENT: [&str; 3] = ["aliment", "alimentation", "sachets"];
+const COLONNES_POIDS: [&str; 2] = ["poids", "poids_moyen"];
+const COLONNES_REMARQUES: [&str; 3] = ["remarques", "remarque", "observations"];
+
+/// Position des colonnes du tableau de suivi
+struct Colonnes {
+    batiment: usize,
+    age: usize,
+    deces: Option<usize>,
+    elimines: Option<usize>,
+    aliment: Option<usize>,
+    poids: Option<usize>,
+    remarques: Option<usize>,
+}
+
+/// Jour de suivi lu dans le tableau
+struct SuiviLu {
+    numero_batiment: String,
+    age: i32,
+    deces: Option<i64>,
+    elimines: Option<i64>,
+    /// Aliment distribué (sachets)
+    aliment: Option<f64>,
+    /// Poids moyen (kg), reporté sur la semaine du jour
+    poids: Option<f64>,
+    remarques: Option<String>,
+}
+
+/// Bande lue dans une feuille sans erreur, prête à être enregistrée
+struct BandeLue {
+    feuille: String,
+    ferme_id: i64,
+    date_entree: NaiveDate,
+    poussin_id: i64,
+    personnel_id: i64,
+    /// Numéro et effectif de chaque bâtiment
+    batiments: Vec<(String, i32)>,
+    suivis: Vec<SuiviLu>,
+}
+
+/// Service d'import des classeurs Excel de l'ancien système de suivi et
+/// des documents d'échange JSON (`EchangeBande`)
+///
+/// Chaque feuille du classeur décrit une bande. L'en-tête donne, une ligne
+/// par information (libellé en colonne A, valeur à côté): la ferme, la date
+/// d'entrée, la souche, le technicien, puis une ligne « Bâtiment » listant
+/// les bâtiments et une ligne « Effectif » avec les poussins placés dans la
+/// même colonne. Le tableau de suivi commence à la ligne d'en-tête contenant
+/// les colonnes « Bâtiment » et « Âge »; les colonnes « Décès », « Éliminés »,
+/// « Aliment » (sachets), « Poids » (kg) et « Remarques » sont facultatives.
+pub struct ImportService {
+    db: Arc<dyn Storage>,
+}
+
+impl ImportService {
+    /// Crée une nouvelle instance du service d'import
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Importe les bandes d'un classeur de l'ancien système
+    ///
+    /// Un essai lit toutes les feuilles et renvoie le rapport sans rien
+    /// enregistrer. L'import réel est refusé tant qu'une feuille contient une
+    /// erreur; il crée alors toutes les bandes dans une seule transaction,
+    /// avec leurs bâtiments, leurs semaines et leurs jours de suivi.
+    ///
+    /// # Arguments
+    /// * `feuilles` - Les feuilles du classeur, une par bande
+    /// * `essai` - Vrai pour seulement contrôler le classeur
+    /// * `token` - Le token de session de l'auteur de l'import, s'il y en a un
+    ///
+    /// # Returns
+    /// Le rapport des lignes lues, ignorées et en erreur, feuille par feuille
+    pub async fn import_legacy_workbook(
+        &self,
+        feuilles: Vec<FeuilleClasseur>,
+        essai: bool,
+        token: Option<&str>,
+    ) -> AppResult<RapportImportClasseur> {
+        if feuilles.is_empty() {
+            return Err(AppError::validation_error("feuilles", "Le classeur ne contient aucune feuille"));
+        }
+
+        let conn = self.db.get_connection()?;
+        let semaines_max = SemaineRepository::get_semaines_max(&conn)?;
+        let mut bandes_lues = HashSet::new();
+        let mut rapports = Vec::with_capacity(feuilles.len());
+        let mut bandes = Vec::new();
+        for feuille in &feuilles {
+            let (rapport, bande) = lire_feuille(&conn, feuille, semaines_max, &mut bandes_lues)?;
+            rapports.push(rapport);
+            bandes.extend(bande);
+        }
+        drop(conn);
+
+        let mut rapport = RapportImportClasseur {
+            essai,
+            lignes_lues: rapports.iter().map(|r| r.lignes_lues).sum(),
+            lignes_ignorees: rapports.iter().map(|r| r.lignes_ignorees).sum(),
+            lignes_en_erreur: rapports.iter().map(|r| r.lignes_en_erreur).sum(),
+            feuilles: rapports,
+            bandes_creees: 0,
+        };
+        if essai {
+            return Ok(rapport);
+        }
+
+        let feuilles_en_erreur = rapport.feuilles.iter().filter(|f| !f.erreurs.is_empty()).count();
+        if feuilles_en_erreur > 0 {
+            return Err(AppError::business_logic(&format!(
+                "{} feuille(s) du classeur contiennent des erreurs: corrigez-les (voir l'essai) avant d'importer",
+                feuilles_en_erreur
+            )));
+        }
+
+        let created_by = AuthService::new(self.db.clone()).author_id(token).await?;
+        let bande_ids = self.db.write(|tx| {
+            bandes.iter().map(|bande| inserer_bande(tx, bande, created_by)).collect::<AppResult<Vec<_>>>()
+        })?;
+
+        for (feuille, bande_id) in rapport.feuilles.iter_mut().zip(bande_ids) {
+            feuille.bande_id = Some(bande_id);
+        }
+        rapport.bandes_creees = bandes.len();
+        Ok(rapport)
+    }
+
+    /// Importe une bande d'un document d'échange JSON
+    ///
+    /// Le document doit être au format `FORMAT_ECHANGE_BANDE`, dans une
+    /// version du schéma prise en charge. La ferme, les souches, les
+    /// techniciens et les soins sont retrouvés par leur nom et doivent déjà
+    /// exister. La bande est créée avec ses bâtiments, ses semaines, ses jours
+    /// de suivi et ses soins dans une seule transaction; une bande clôturée
+    /// dans le document l'est aussi à l'import.
+    ///
+    /// # Arguments
+    /// * `path` - Le chemin du fichier JSON
+    /// * `token` - Le token de session de l'auteur de l'import, s'il y en a un
+    ///
+    /// # Returns
+    /// La bande créée et le nombre de bâtiments, semaines, jours et soins importés
+    pub async fn import_bande_json(&self, path: &str, token: Option<&str>) -> AppResult<RapportImportBande> {
+        let contenu = fs::read_to_string(path.trim()).map_err(|e| {
+            AppError::validation_error("path", &format!("Impossible de lire le fichier {}: {}", path.trim(), e))
+        })?;
+        let document: EchangeBande = serde_json::from_str(&contenu)
+            .map_err(|e| AppError::validation_error("path", &format!("Document d'échange invalide: {}", e)))?;
+        if document.format != FORMAT_ECHANGE_BANDE {
+            return Err(AppError::validation_error(
+                "format",
+                &format!("Format « {} » inconnu: le fichier n'est pas une bande exportée", document.format),
+            ));
+        }
+        if document.version == 0 || document.version > VERSION_ECHANGE_BANDE {
+            return Err(AppError::validation_error(
+                "version",
+                &format!(
+                    "Version {} du schéma non prise en charge (version {} au plus)",
+                    document.version, VERSION_ECHANGE_BANDE
+                ),
+            ));
+        }
+
+        let created_by = AuthService::new(self.db.clone()).author_id(token).await?;
+        let mut rapport = self.db.write(|tx| importer_bande_echangee(tx, &document.bande, created_by))?;
+        rapport.version = document.version;
+        Ok(rapport)
+    }
+}
+
+/// Cellule non vide d'une ligne
+fn cellule(ligne: &[String], colonne: usize) -> Option<&str> {
+    ligne.get(colonne).map(|c| c.trim()).filter(|c| !c.is_empty())
+}
+
+/// Numéro de bâtiment saisi comme nombre ("1" ou "1.0") ou comme texte
+fn numero_batiment(texte: &str) -> String {
+    match lire_nombre(texte) {
+        Some(numero) if numero.fract() == 0.0 => format!("{}", numero as i64),
+        _ => texte.trim().to_string(),
+    }
+}
+
+/// Lit une date au format de la cellule (AAAA-MM-JJ, JJ/MM/AAAA) ou un numéro de série Excel
+fn lire_date(texte: &str) -> Option<NaiveDate> {
+    let texte = texte.trim();
+    let jour = texte.split([' ', 'T']).next().unwrap_or(texte);
+    for format in ["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(jour, format) {
+            return Some(date);
+        }
+    }
+    // Les jours Excel sont comptés à partir du 30 décembre 1899
+    let serie = lire_nombre(texte).filter(|n| *n >= 1.0 && n.fract() == 0.0)?;
+    NaiveDate::from_ymd_opt(1899, 12, 30)?.checked_add_days(Days::new(serie as u64))
+}
+
+/// Recherche l'ID d'un référentiel par son nom normalisé
+fn id_par_nom(conn: &Connection, table: &str, nom: &str) -> AppResult<Option<i64>> {
+    let id = conn
+        .query_row(&format!("SELECT id FROM {} WHERE nom_normalise = ?1", table), [normaliser_nom(nom)], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    Ok(id)
+}
+
+/// Lit une feuille et contrôle ses données
+///
+/// # Returns
+/// Le rapport de la feuille et, si elle ne contient aucune erreur, la bande à enregistrer
+fn lire_feuille(
+    conn: &Connection,
+    feuille: &FeuilleClasseur,
+    semaines_max: i32,
+    bandes_lues: &mut HashSet<(i64, NaiveDate)>,
+) -> AppResult<(RapportFeuilleImport, Option<BandeLue>)> {
+    let mut rapport = RapportFeuilleImport {
+        feuille: feuille.nom.trim().to_string(),
+        ferme: None,
+        date_entree: None,
+        batiments: 0,
+        lignes_lues: 0,
+        lignes_ignorees: 0,
+        lignes_en_erreur: 0,
+        erreurs: Vec::new(),
+        bande_id: None,
+    };
+    let mut erreurs = Vec::new();
+    let mut lignes = feuille.lignes.iter().enumerate().map(|(i, ligne)| (i + 1, ligne.as_slice()));
+
+    // En-tête: une information par ligne, jusqu'à l'en-tête du tableau de suivi
+    let mut infos: BTreeMap<&str, (usize, &[String])> = BTreeMap::new();
+    let mut colonnes = None;
+    for (numero, ligne) in lignes.by_ref() {
+        let noms: Vec<String> = ligne.iter().map(|c| normaliser_colonne(c)).collect();
+        let position = |alias: &[&str]| noms.iter().position(|nom| alias.contains(&nom.as_str()));
+        if let (Some(batiment), Some(age)) = (position(&COLONNES_BATIMENT), position(&COLONNES_AGE)) {
+            colonnes = Some(Colonnes {
+                batiment,
+                age,
+                deces: position(&COLONNES_DECES),
+                elimines: position(&COLONNES_ELIMINES),
+                aliment: position(&COLONNES_ALIMENT),
+                poids: position(&COLONNES_POIDS),
+                remarques: position(&COLONNES_REMARQUES),
+            });
+            break;
+        }
+        let Some(cle) = noms.first() else { continue };
+        for (info, alias) in [
+            ("ferme", &CLES_FERME[..]),
+            ("date_entree", &CLES_DATE_ENTREE[..]),
+            ("souche", &CLES_SOUCHE[..]),
+            ("technicien", &CLES_TECHNICIEN[..]),
+            ("batiments", &COLONNES_BATIMENT[..]),
+            ("effectifs", &CLES_EFFECTIF[..]),
+        ] {
+            if alias.contains(&cle.as_str()) {
+                infos.entry(info).or_insert((numero, ligne));
+            }
+        }
+    }
+
+    let valeur = |info: &str| {
+        infos.get(info).and_then(|(numero, ligne)| cellule(ligne, 1).map(|valeur| (*numero, valeur)))
+    };
+
+    let ferme = match valeur("ferme") {
+        Some((numero, nom)) => {
+            rapport.ferme = Some(nom.to_string());
+            match id_par_nom(conn, "fermes", nom)? {
+                Some(id) => Some(id),
+                None => {
+                    erreurs.push(ErreurImport { ligne: numero, motif: format!("Ferme inconnue: {}", nom) });
+                    None
+                }
+            }
+        }
+        None => {
+            manquant(&mut erreurs, "Ferme");
+            None
+        }
+    };
+    let date_entree = match valeur("date_entree") {
+        Some((numero, texte)) => match lire_date(texte) {
+            Some(date) => {
+                rapport.date_entree = Some(date.format("%Y-%m-%d").to_string());
+                Some(date)
+            }
+            None => {
+                erreurs.push(ErreurImport { ligne: numero, motif: format!("Date d'entrée illisible: {}", texte) });
+                None
+            }
+        },
+        None => {
+            manquant(&mut erreurs, "Date d'entrée");
+            None
+        }
+    };
+    let referentiel = |info: &str, libelle: &str, table: &str, erreurs: &mut Vec<ErreurImport>| -> AppResult<Option<i64>> {
+        let Some((numero, nom)) = valeur(info) else {
+            manquant(erreurs, libelle);
+            return Ok(None);
+        };
+        let id = id_par_nom(conn, table, nom)?;
+        if id.is_none() {
+            erreurs.push(ErreurImport { ligne: numero, motif: format!("{} inconnu: {}", libelle, nom) });
+        }
+        Ok(id)
+    };
+    let poussin_id = referentiel("souche", "Souche", "poussins", &mut erreurs)?;
+    let personnel_id = referentiel("technicien", "Technicien", "personnel", &mut erreurs)?;
+
+    if let (Some(ferme_id), Some(date_entree)) = (ferme, date_entree) {
+        let existe: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM bandes WHERE ferme_id = ?1 AND date_entree = ?2)",
+            params![ferme_id, date_entree.format("%Y-%m-%d").to_string()],
+            |row| row.get(0),
+        )?;
+        if existe || !bandes_lues.insert((ferme_id, date_entree)) {
+            erreurs.push(ErreurImport {
+                ligne: 0,
+                motif: format!("Une bande de cette ferme est déjà entrée le {}", date_entree.format("%d/%m/%Y")),
+            });
+        }
+    }
+
+    // Bâtiments et effectifs, alignés par colonne
+    let mut batiments: Vec<(String, i32)> = Vec::new();
+    match (infos.get("batiments"), infos.get("effectifs")) {
+        (Some((numero, ligne)), Some((numero_effectifs, effectifs))) => {
+            for colonne in 1..ligne.len() {
+                let Some(texte) = cellule(ligne, colonne) else { continue };
+                let numero_batiment = numero_batiment(texte);
+                if let Some(ferme_id) = ferme {
+                    if let Err(e) = VideSanitaireRepository::validate_batiment(conn, ferme_id, &numero_batiment) {
+                        erreurs.push(ErreurImport { ligne: *numero, motif: format!("Bâtiment {}: {}", numero_batiment, e) });
+                        continue;
+                    }
+                }
+                if batiments.iter().any(|(existant, _)| *existant == numero_batiment) {
+                    erreurs.push(ErreurImport { ligne: *numero, motif: format!("Bâtiment {} en double", numero_batiment) });
+                    continue;
+                }
+                match cellule(effectifs, colonne).and_then(lire_nombre) {
+                    Some(effectif) if effectif >= 1.0 && effectif.fract() == 0.0 && effectif <= i32::MAX as f64 => {
+                        batiments.push((numero_batiment, effectif as i32));
+                    }
+                    _ => erreurs.push(ErreurImport {
+                        ligne: *numero_effectifs,
+                        motif: format!("Effectif du bâtiment {} manquant ou invalide", numero_batiment),
+                    }),
+                }
+            }
+            if batiments.is_empty() && erreurs.is_empty() {
+                erreurs.push(ErreurImport { ligne: *numero, motif: "Aucun bâtiment dans l'en-tête".to_string() });
+            }
+        }
+        (None, _) => manquant(&mut erreurs, "Ligne des bâtiments"),
+        (Some(_), None) => manquant(&mut erreurs, "Ligne des effectifs"),
+    }
+    rapport.batiments = batiments.len();
+
+    // Tableau de suivi
+    let mut suivis: Vec<SuiviLu> = Vec::new();
+    match colonnes {
+        Some(colonnes) => {
+            let age_max = semaines_max * 7;
+            for (numero, ligne) in lignes {
+                if ligne.iter().all(|c| c.trim().is_empty()) {
+                    continue;
+                }
+                rapport.lignes_lues += 1;
+                let donnees = [colonnes.deces, colonnes.elimines, colonnes.aliment, colonnes.poids, colonnes.remarques];
+                if donnees.iter().all(|colonne| colonne.and_then(|c| cellule(ligne, c)).is_none()) {
+                    rapport.lignes_ignorees += 1;
+                    continue;
+                }
+
+                match lire_suivi(ligne, &colonnes, age_max, &batiments) {
+                    Ok(suivi) if suivis.iter().any(|s| s.numero_batiment == suivi.numero_batiment && s.age == suivi.age) => {
+                        rapport.lignes_en_erreur += 1;
+                        erreurs.push(ErreurImport {
+                            ligne: numero,
+                            motif: format!("Jour {} du bâtiment {} en double", suivi.age, suivi.numero_batiment),
+                        });
+                    }
+                    Ok(suivi) => suivis.push(suivi),
+                    Err(motif) => {
+                        rapport.lignes_en_erreur += 1;
+                        erreurs.push(ErreurImport { ligne: numero, motif });
+                    }
+                }
+            }
+        }
+        None => manquant(&mut erreurs, "En-tête du tableau de suivi (colonnes Bâtiment et Âge)"),
+    }
+
+    rapport.erreurs = erreurs;
+    let bande = match (ferme, date_entree, poussin_id, personnel_id) {
+        (Some(ferme_id), Some(date_entree), Some(poussin_id), Some(personnel_id)) if rapport.erreurs.is_empty() => {
+            Some(BandeLue {
+                feuille: rapport.feuille.clone(),
+                ferme_id,
+                date_entree,
+                poussin_id,
+                personnel_id,
+                batiments,
+                suivis,
+            })
+        }
+        _ => None,
+    };
+    Ok((rapport, bande))
+}
+
+/// Signale une information introuvable dans la feuille
+fn manquant(erreurs: &mut Vec<ErreurImport>, libelle: &str) {
+    erreurs.push(ErreurImport { ligne: 0, motif: format!("{} introuvable dans la feuille", libelle) });
+}
+
+/// Lit une ligne du tableau de suivi
+fn lire_suivi(ligne: &[String], colonnes: &Colonnes, age_max: i32, batiments: &[(String, i32)]) -> Result<SuiviLu, String> {
+    let numero_batiment = cellule(ligne, colonnes.batiment).map(numero_batiment).ok_or("Bâtiment manquant")?;
+    if !batiments.iter().any(|(numero, _)| *numero == numero_batiment) {
+        return Err(format!("Bâtiment {} absent de l'en-tête", numero_batiment));
+    }
+    let texte_age = cellule(ligne, colonnes.age).ok_or("Âge manquant")?;
+    let age = lire_nombre(texte_age)
+        .filter(|age| age.fract() == 0.0 && (1.0..=age_max as f64).contains(age))
+        .ok_or_else(|| format!("Âge invalide (1 à {} jours): {}", age_max, texte_age))? as i32;
+
+    let nombre = |colonne: Option<usize>, libelle: &str| {
+        colonne
+            .and_then(|c| cellule(ligne, c))
+            .map(|texte| {
+                lire_nombre(texte)
+                    .filter(|n| *n >= 0.0)
+                    .ok_or_else(|| format!("{} invalide: {}", libelle, texte))
+            })
+            .transpose()
+    };
+    let entier = |colonne: Option<usize>, libelle: &str| {
+        nombre(colonne, libelle)?
+            .map(|n| if n.fract() == 0.0 { Ok(n as i64) } else { Err(format!("{} non entier: {}", libelle, n)) })
+            .transpose()
+    };
+
+    Ok(SuiviLu {
+        numero_batiment,
+        age,
+        deces: entier(colonnes.deces, "Décès")?,
+        elimines: entier(colonnes.elimines, "Éliminés")?,
+        aliment: nombre(colonnes.aliment, "Aliment")?,
+        poids: nombre(colonnes.poids, "Poids")?.filter(|poids| *poids > 0.0),
+        remarques: colonnes.remarques.and_then(|c| cellule(ligne, c)).map(str::to_string),
+    })
+}
+
+/// Enregistre une bande lue, avec ses bâtiments, ses semaines et ses jours de suivi
+///
+/// Chaque bâtiment reçoit les semaines couvertes par le tableau (au moins
+/// une) et tous leurs jours, vides lorsque la feuille ne les renseigne pas.
+fn inserer_bande(tx: &Connection, bande: &BandeLue, created_by: Option<i64>) -> AppResult<i64> {
+    let contexte = |e: AppError| AppError::business_logic(&format!("Feuille {}: {}", bande.feuille, e));
+    let creee = BandeRepository::create(
+        tx,
+        &CreateBande {
+            date_entree: bande.date_entree,
+            ferme_id: bande.ferme_id,
+            notes: Some(format!("Importée de l'ancien classeur (feuille {})", bande.feuille)),
+            champs_personnalises: Default::default(),
+        },
+        created_by,
+    )
+    .map_err(contexte)?;
+    let bande_id = creee.id.ok_or_else(|| AppError::business_logic("La bande créée n'a pas d'ID"))?;
+
+    for (numero, quantite) in &bande.batiments {
+        // Bande passée: l'occupation actuelle des bâtiments ne la concerne pas
+        let batiment = BatimentRepository::create(
+            tx,
+            &CreateBatiment {
+                bande_id,
+                numero_batiment: numero.clone(),
+                poussin_id: bande.poussin_id,
+                personnel_id: bande.personnel_id,
+                quantite: *quantite,
+                autoriser_cohabitation: true,
+                champs_personnalises: Default::default(),
+            },
+            created_by,
+        )
+        .map_err(contexte)?;
+        let batiment_id = batiment.id.ok_or_else(|| AppError::business_logic("Le bâtiment créé n'a pas d'ID"))?;
+
+        let suivis: BTreeMap<i32, &SuiviLu> = bande
+            .suivis
+            .iter()
+            .filter(|suivi| suivi.numero_batiment == *numero)
+            .map(|suivi| (suivi.age, suivi))
+            .collect();
+        let semaines = suivis.keys().next_back().map_or(1, |age| (age - 1) / 7 + 1);
+
+        for numero_semaine in 1..=semaines {
+            let ages = (numero_semaine - 1) * 7 + 1..=numero_semaine * 7;
+            // Poids de la dernière pesée de la semaine
+            let poids = suivis.range(ages.clone()).rev().find_map(|(_, suivi)| suivi.poids);
+            tx.execute(
+                "INSERT INTO semaines (batiment_id, numero_semaine, poids, created_at, updated_at, created_by)
+                 VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?4)",
+                params![batiment_id, numero_semaine, poids, created_by],
+            )?;
+            let semaine_id = tx.last_insert_rowid();
+
+            for age in ages {
+                let suivi = suivis.get(&age);
+                tx.execute(
+                    "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, elimines_par_jour, alimentation_par_jour,
+                                                  remarques, created_at, updated_at, created_by)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?7)",
+                    params![
+                        semaine_id,
+                        age,
+                        suivi.and_then(|s| s.deces),
+                        suivi.and_then(|s| s.elimines),
+                        suivi.and_then(|s| s.aliment),
+                        suivi.and_then(|s| s.remarques.as_deref()),
+                        created_by
+                    ],
+                )?;
+            }
+        }
+    }
+
+    Ok(bande_id)
+}
+
+/// Contrôle et enregistre la bande d'un document d'échange
+fn importer_bande_echangee(
+    tx: &Connection,
+    bande: &BandeEchangee,
+    created_by: Option<i64>,
+) -> AppResult<RapportImportBande> {
+    let date_iso = |champ: &str, texte: &str| {
+        NaiveDate::parse_from_str(texte.trim(), "%Y-%m-%d").map_err(|_| {
+            AppError::validation_error(champ, &format!("La date « {} » doit être au format AAAA-MM-JJ", texte))
+        })
+    };
+    let referentiel = |table: &str, libelle: &str, nom: &str| {
+        id_par_nom(tx, table, nom)?.ok_or_else(|| {
+            AppError::validation_error(table, &format!("{} « {} » introuvable: créez-le avant l'import", libelle, nom.trim()))
+        })
+    };
+
+    let ferme_id = id_par_nom(tx, "fermes", &bande.ferme)?.ok_or_else(|| {
+        AppError::validation_error("ferme", &format!("La ferme « {} » n'existe pas", bande.ferme.trim()))
+    })?;
+    let date_entree = date_iso("date_entree", &bande.date_entree)?;
+    let date_cloture = bande.date_cloture.as_deref().map(|date| date_iso("date_cloture", date)).transpose()?;
+    if date_cloture.is_some_and(|cloture| cloture < date_entree) {
+        return Err(AppError::validation_error("date_cloture", "La date de clôture précède la date d'entrée"));
+    }
+    if bande.batiments.is_empty() {
+        return Err(AppError::validation_error("batiments", "La bande ne contient aucun bâtiment"));
+    }
+    let existe: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM bandes WHERE ferme_id = ?1 AND date_entree = ?2)",
+        params![ferme_id, date_entree.format("%Y-%m-%d").to_string()],
+        |row| row.get(0),
+    )?;
+    if existe {
+        return Err(AppError::business_logic(&format!(
+            "Une bande de cette ferme est déjà entrée le {}",
+            date_entree.format("%d/%m/%Y")
+        )));
+    }
+
+    let creee = BandeRepository::create(
+        tx,
+        &CreateBande {
+            date_entree,
+            ferme_id,
+            notes: bande.notes.clone(),
+            champs_personnalises: Default::default(),
+        },
+        created_by,
+    )?;
+    let bande_id = creee.id.ok_or_else(|| AppError::business_logic("La bande créée n'a pas d'ID"))?;
+
+    let semaines_max = SemaineRepository::get_semaines_max(tx)?;
+    let mut rapport = RapportImportBande { bande_id, version: 0, batiments: 0, semaines: 0, jours: 0, soins: 0 };
+    let mut numeros = HashSet::new();
+    for batiment in &bande.batiments {
+        let numero = numero_batiment(&batiment.numero_batiment);
+        let contexte = |motif: &str| AppError::validation_error("batiments", &format!("Bâtiment {}: {}", numero, motif));
+        if !numeros.insert(numero.clone()) {
+            return Err(contexte("présent plusieurs fois dans le document"));
+        }
+        VideSanitaireRepository::validate_batiment(tx, ferme_id, &numero)?;
+        if batiment.effectif < 1 {
+            return Err(contexte("l'effectif doit être d'au moins un poussin"));
+        }
+
+        // Bande importée: l'occupation actuelle des bâtiments ne la concerne pas
+        let cree = BatimentRepository::create(
+            tx,
+            &CreateBatiment {
+                bande_id,
+                numero_batiment: numero.clone(),
+                poussin_id: referentiel("poussins", "Souche", &batiment.souche)?,
+                personnel_id: referentiel("personnel", "Technicien", &batiment.technicien)?,
+                quantite: batiment.effectif,
+                autoriser_cohabitation: true,
+                champs_personnalises: Default::default(),
+            },
+            created_by,
+        )?;
+        let batiment_id = cree.id.ok_or_else(|| AppError::business_logic("Le bâtiment créé n'a pas d'ID"))?;
+        rapport.batiments += 1;
+
+        let mut numeros_semaine = HashSet::new();
+        for semaine in &batiment.semaines {
+            let n = semaine.numero_semaine;
+            if n < 1 || n > semaines_max {
+                return Err(contexte(&format!("la semaine {} n'est pas entre 1 et {}", n, semaines_max)));
+            }
+            if !numeros_semaine.insert(n) {
+                return Err(contexte(&format!("la semaine {} est présente plusieurs fois", n)));
+            }
+            if semaine.poids.is_some_and(|poids| !poids.is_finite() || poids < 0.0) {
+                return Err(contexte(&format!("le poids de la semaine {} est invalide", n)));
+            }
+            tx.execute(
+                "INSERT INTO semaines (batiment_id, numero_semaine, poids, created_at, updated_at, created_by)
+                 VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?4)",
+                params![batiment_id, n, semaine.poids, created_by],
+            )?;
+            let semaine_id = tx.last_insert_rowid();
+            rapport.semaines += 1;
+
+            let ages = (n - 1) * 7 + 1..=n * 7;
+            let mut ages_lus = HashSet::new();
+            for jour in &semaine.jours {
+                if !ages.contains(&jour.age) || !ages_lus.insert(jour.age) {
+                    return Err(contexte(&format!("l'âge {} est invalide ou répété en semaine {}", jour.age, n)));
+                }
+                if jour.deces.is_some_and(|v| v < 0)
+                    || jour.elimines.is_some_and(|v| v < 0)
+                    || jour.aliment.is_some_and(|v| !v.is_finite() || v < 0.0)
+                {
+                    return Err(contexte(&format!("valeur négative au jour {}", jour.age)));
+                }
+                tx.execute(
+                    "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, elimines_par_jour, alimentation_par_jour,
+                                                  remarques, created_at, updated_at, created_by)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, ?7)",
+                    params![semaine_id, jour.age, jour.deces, jour.elimines, jour.aliment, jour.remarques, created_by],
+                )?;
+                let suivi_id = tx.last_insert_rowid();
+                rapport.jours += 1;
+
+                for soin in &jour.soins {
+                    tx.execute(
+                        "INSERT INTO suivi_soins (suivi_id, soin_id, quantite, unit) VALUES (?1, ?2, ?3, ?4)",
+                        params![suivi_id, referentiel("soins", "Soin", &soin.soin)?, soin.quantite, soin.unite],
+                    )?;
+                    rapport.soins += 1;
+                }
+            }
+        }
+    }
+
+    structurer_quantites_soins(
+        tx,
+        "ss.suivi_id IN (SELECT sq.id FROM suivi_quotidien sq
+                         JOIN semaines s ON sq.semaine_id = s.id
+                         JOIN batiments b ON s.batiment_id = b.id
+                         WHERE b.bande_id = ?1)",
+        &[&bande_id],
+    )?;
+    if let Some(date_cloture) = date_cloture {
+        tx.execute(
+            "UPDATE bandes SET statut = ?1, date_cloture = ?2 WHERE id = ?3",
+            params![STATUT_BANDE_CLOTUREE, date_cloture.format("%Y-%m-%d").to_string(), bande_id],
+        )?;
+    }
+
+    Ok(rapport)
+}
@@ -0,0 +1,146 @@
+use crate::database::Storage;
+use crate::error::AppResult;
+use crate::models::{JourneeBatiment, SaisieJour, VueJournee, STATUT_BANDE_ACTIVE};
+use crate::repositories::{PlanSoinsRepository, ProgrammeAlimentationRepository};
+use crate::services::AlerteService;
+use chrono::{Days, NaiveDate};
+use rusqlite::OptionalExtension;
+use std::sync::Arc;
+
+/// Service de la vue du jour, point de départ de la journée du technicien
+pub struct JourneeService {
+    db: Arc<dyn Storage>,
+}
+
+impl JourneeService {
+    /// Crée une nouvelle instance du service de la vue du jour
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Vue du jour de chaque bâtiment des bandes actives déjà entrées
+    ///
+    /// Pour chaque bâtiment: la saisie de la veille, les soins du plan prévus
+    /// aujourd'hui, les tâches du jour et les alertes en cours (filtrées par
+    /// l'abonnement de l'utilisateur, comme `get_pending_alerts`).
+    ///
+    /// # Arguments
+    /// * `aujourd_hui` - La date du jour
+    /// * `user_id` - L'utilisateur dont l'abonnement filtre les alertes, `None` pour toutes
+    ///
+    /// # Returns
+    /// Les bâtiments triés par ferme, bande et numéro de bâtiment
+    pub async fn get_today_overview(&self, aujourd_hui: NaiveDate, user_id: Option<i64>) -> AppResult<VueJournee> {
+        let alertes = AlerteService::new(self.db.clone()).get_pending_alerts(user_id).await?;
+        let conn = self.db.get_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.nom, bd.id, COALESCE(bd.numero_affiche, CAST(bd.numero_bande AS TEXT)),
+                    b.id, b.numero_batiment, b.poussin_id, bd.date_entree
+             FROM batiments b
+             JOIN bandes bd ON b.bande_id = bd.id
+             JOIN fermes f ON bd.ferme_id = f.id
+             WHERE bd.statut = ?1 AND bd.date_entree <= ?2
+             ORDER BY f.nom, bd.date_entree, bd.id, b.numero_batiment",
+        )?;
+        let lignes = stmt
+            .query_map(rusqlite::params![STATUT_BANDE_ACTIVE, aujourd_hui], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, i64>(6)?,
+                    row.get::<_, NaiveDate>(7)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut batiments = Vec::with_capacity(lignes.len());
+        for (ferme_id, ferme_nom, bande_id, numero_bande, batiment_id, numero_batiment, poussin_id, date_entree) in lignes {
+            let age_jours = (aujourd_hui - date_entree).num_days() as i32 + 1;
+
+            let hier = if age_jours > 1 {
+                let age = age_jours - 1;
+                let (deces_par_jour, elimines_par_jour, alimentation_par_jour) = conn
+                    .query_row(
+                        "SELECT sq.deces_par_jour, sq.elimines_par_jour, sq.alimentation_par_jour
+                         FROM suivi_quotidien sq
+                         JOIN semaines s ON sq.semaine_id = s.id
+                         WHERE s.batiment_id = ?1 AND sq.age = ?2",
+                        rusqlite::params![batiment_id, age],
+                        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                    )
+                    .optional()?
+                    .unwrap_or((None, None, None));
+                Some(SaisieJour {
+                    age,
+                    date: aujourd_hui - Days::new(1),
+                    deces_par_jour,
+                    elimines_par_jour,
+                    alimentation_par_jour,
+                })
+            } else {
+                None
+            };
+            let saisie_hier_manquante = hier
+                .as_ref()
+                .is_some_and(|jour| jour.deces_par_jour.is_none() || jour.alimentation_par_jour.is_none());
+
+            let soins_prevus = PlanSoinsRepository::get_by_poussin(&conn, poussin_id)?
+                .into_iter()
+                .filter(|soin| soin.jour_debut <= age_jours && age_jours <= soin.jour_fin)
+                .collect();
+
+            let mut taches = Vec::new();
+            if age_jours % 7 == 0 {
+                let numero_semaine = age_jours / 7;
+                let pese: bool = conn
+                    .query_row(
+                        "SELECT poids IS NOT NULL FROM semaines WHERE batiment_id = ?1 AND numero_semaine = ?2",
+                        rusqlite::params![batiment_id, numero_semaine],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .unwrap_or(false);
+                if !pese {
+                    taches.push(format!("Pesée de la semaine {}", numero_semaine));
+                }
+            }
+            for phase in ProgrammeAlimentationRepository::get_by_poussin(&conn, poussin_id)? {
+                if phase.jour_debut == age_jours && age_jours > 1 {
+                    taches.push(format!("Passage à l'aliment {}", phase.nom_phase));
+                }
+            }
+
+            let alertes = alertes.iter().filter(|alerte| alerte.batiment_id == Some(batiment_id)).cloned().collect();
+
+            batiments.push(JourneeBatiment {
+                ferme_id,
+                ferme_nom,
+                bande_id,
+                numero_bande,
+                batiment_id,
+                numero_batiment,
+                age_jours,
+                hier,
+                saisie_hier_manquante,
+                soins_prevus,
+                taches,
+                alertes,
+            });
+        }
+
+        let autres_alertes = alertes
+            .into_iter()
+            .filter(|alerte| !batiments.iter().any(|b| alerte.batiment_id == Some(b.batiment_id)))
+            .collect();
+
+        Ok(VueJournee { date: aujourd_hui, batiments, autres_alertes })
+    }
+}
@@ -0,0 +1,213 @@
+use crate::database::archive::SCHEMA_ARCHIVE;
+use crate::database::Storage;
+use crate::error::AppResult;
+use crate::models::{
+    ProgrammationMaintenance, RapportIntegrite, RapportMaintenance, PARAMETRE_DERNIERE_MAINTENANCE,
+    PARAMETRE_MAINTENANCE_MENSUELLE,
+};
+use crate::repositories::ParametreRepository;
+use crate::services::AuthService;
+use chrono::{Local, Months, NaiveDateTime};
+use rusqlite::Connection;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Intervalle de vérification de la maintenance mensuelle en arrière-plan
+pub const INTERVALLE_VERIFICATION_MAINTENANCE: Duration = Duration::from_secs(3600);
+
+/// Format de `PARAMETRE_DERNIERE_MAINTENANCE`
+const FORMAT_DATE_MAINTENANCE: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Service de maintenance de la base de données
+///
+/// La maintenance reconstruit les index plein texte, compacte la base et son
+/// archive (VACUUM) puis met à jour les statistiques de l'optimiseur (ANALYZE).
+/// Elle peut être lancée à la demande ou chaque mois par une tâche de fond.
+pub struct MaintenanceService {
+    db: Arc<dyn Storage>,
+}
+
+impl MaintenanceService {
+    /// Crée une nouvelle instance du service de maintenance
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Lance la maintenance de la base de données (réservé aux administrateurs)
+    ///
+    /// Les écritures des autres commandes attendent la fin de la maintenance.
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// La taille de la base avant et après, les index reconstruits et la durée
+    pub async fn run_database_maintenance(&self, token: &str) -> AppResult<RapportMaintenance> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        self.executer()
+    }
+
+    /// Récupère la programmation de la maintenance automatique
+    pub async fn get_maintenance_schedule(&self) -> AppResult<ProgrammationMaintenance> {
+        let conn = self.db.get_connection()?;
+        lire_programmation(&conn)
+    }
+
+    /// Active ou désactive la maintenance mensuelle (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `mensuelle` - Vrai pour lancer la maintenance chaque mois
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// La programmation enregistrée
+    pub async fn set_maintenance_schedule(&self, mensuelle: bool, token: &str) -> AppResult<ProgrammationMaintenance> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        self.db.write(|tx| {
+            ParametreRepository::set(tx, PARAMETRE_MAINTENANCE_MENSUELLE, if mensuelle { "1" } else { "0" })?;
+            lire_programmation(tx)
+        })
+    }
+
+    /// Vérifie l'intégrité de la base de données sans la modifier
+    ///
+    /// Contrôle la structure des fichiers (pages, index) puis les clés
+    /// étrangères, qui ne sont pas vérifiées pour les lignes écrites avant
+    /// leur activation.
+    ///
+    /// # Returns
+    /// Les problèmes relevés; `integre` est vrai s'il n'y en a aucun
+    pub async fn check_integrity(&self) -> AppResult<RapportIntegrite> {
+        let conn = self.db.get_connection()?;
+        let mut erreurs = Vec::new();
+        let mut references_orphelines = Vec::new();
+        for schema in schemas(&conn)? {
+            let mut stmt = conn.prepare(&format!("PRAGMA {}.integrity_check", schema))?;
+            let lignes = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+            erreurs.extend(
+                lignes.into_iter().filter(|ligne| ligne != "ok").map(|ligne| format!("{}: {}", schema, ligne)),
+            );
+
+            let mut stmt = conn.prepare(&format!("PRAGMA {}.foreign_key_check", schema))?;
+            let orphelines = stmt
+                .query_map([], |row| {
+                    Ok(format!(
+                        "{}.{} ligne {}: référence absente dans {}",
+                        schema,
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<i64>>(1)?.map_or("?".to_string(), |id| id.to_string()),
+                        row.get::<_, String>(2)?
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            references_orphelines.extend(orphelines);
+        }
+
+        Ok(RapportIntegrite {
+            integre: erreurs.is_empty() && references_orphelines.is_empty(),
+            erreurs,
+            references_orphelines,
+            verifiee_le: Local::now().format(FORMAT_DATE_MAINTENANCE).to_string(),
+        })
+    }
+
+    /// Tâche de fond: lance la maintenance lorsqu'elle est programmée et que
+    /// la dernière date d'au moins un mois
+    ///
+    /// # Arguments
+    /// * `intervalle` - Délai entre deux vérifications
+    pub async fn run_scheduled_maintenance(self, intervalle: Duration) {
+        let mut horloge = tokio::time::interval(intervalle);
+        loop {
+            horloge.tick().await;
+            // Une erreur passagère (base occupée) sera retentée au prochain tour
+            if let Ok(true) = self.maintenance_due() {
+                let _ = self.executer();
+            }
+        }
+    }
+
+    fn maintenance_due(&self) -> AppResult<bool> {
+        let conn = self.db.get_connection()?;
+        let programmation = lire_programmation(&conn)?;
+        if !programmation.mensuelle {
+            return Ok(false);
+        }
+        let derniere = programmation
+            .derniere_maintenance
+            .and_then(|date| NaiveDateTime::parse_from_str(&date, FORMAT_DATE_MAINTENANCE).ok());
+        Ok(match derniere.and_then(|date| date.checked_add_months(Months::new(1))) {
+            Some(prochaine) => Local::now().naive_local() >= prochaine,
+            None => true,
+        })
+    }
+
+    fn executer(&self) -> AppResult<RapportMaintenance> {
+        let debut = Instant::now();
+        self.db.maintenance(|conn| {
+            let schemas = schemas(conn)?;
+            let taille_avant = taille(conn, &schemas)?;
+
+            let index_reconstruits = index_plein_texte(conn)?;
+            for index in &index_reconstruits {
+                conn.execute(&format!("INSERT INTO \"{index}\"(\"{index}\") VALUES ('rebuild')"), [])?;
+            }
+            for schema in &schemas {
+                conn.execute_batch(&format!("VACUUM {}", schema))?;
+            }
+            conn.execute_batch("ANALYZE; PRAGMA wal_checkpoint(TRUNCATE);")?;
+
+            let effectuee_le = Local::now().format(FORMAT_DATE_MAINTENANCE).to_string();
+            ParametreRepository::set(conn, PARAMETRE_DERNIERE_MAINTENANCE, &effectuee_le)?;
+
+            Ok(RapportMaintenance {
+                taille_avant,
+                taille_apres: taille(conn, &schemas)?,
+                index_reconstruits,
+                duree_ms: debut.elapsed().as_millis() as i64,
+                effectuee_le,
+            })
+        })
+    }
+}
+
+fn lire_programmation(conn: &Connection) -> AppResult<ProgrammationMaintenance> {
+    Ok(ProgrammationMaintenance {
+        mensuelle: ParametreRepository::get(conn, PARAMETRE_MAINTENANCE_MENSUELLE)?.as_deref() == Some("1"),
+        derniere_maintenance: ParametreRepository::get(conn, PARAMETRE_DERNIERE_MAINTENANCE)?,
+    })
+}
+
+/// Bases à entretenir: la base principale et l'archive si elle est attachée
+fn schemas(conn: &Connection) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA database_list")?;
+    let noms = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(noms.into_iter().filter(|nom| nom == "main" || nom == SCHEMA_ARCHIVE).collect())
+}
+
+/// Taille des bases en octets (pages allouées, hors journal WAL)
+fn taille(conn: &Connection, schemas: &[String]) -> AppResult<i64> {
+    let mut total = 0;
+    for schema in schemas {
+        let pages: i64 = conn.query_row(&format!("PRAGMA {}.page_count", schema), [], |row| row.get(0))?;
+        let taille_page: i64 = conn.query_row(&format!("PRAGMA {}.page_size", schema), [], |row| row.get(0))?;
+        total += pages * taille_page;
+    }
+    Ok(total)
+}
+
+/// Tables virtuelles FTS4/FTS5 de la base principale
+fn index_plein_texte(conn: &Connection) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND sql LIKE 'CREATE VIRTUAL TABLE%USING fts%'
+         ORDER BY name",
+    )?;
+    let noms = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(noms)
+}
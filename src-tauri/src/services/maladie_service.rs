@@ -1,5 +1,5 @@
-use crate::database::DatabaseManager;
-use crate::models::{Maladie, CreateMaladie, UpdateMaladie, PaginatedMaladies};
+use crate::database::Storage;
+use crate::models::{Maladie, CreateMaladie, UpdateMaladie, PaginatedMaladies, TendanceMaladie};
 use crate::repositories::{MaladieRepository, MaladieRepositoryTrait};
 use std::sync::Arc;
 
@@ -8,7 +8,7 @@ pub struct MaladieService {
 }
 
 impl MaladieService {
-    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+    pub fn new(db_manager: Arc<dyn Storage>) -> Self {
         Self { 
             repository: Arc::new(MaladieRepository::new(db_manager)),
         }
@@ -25,11 +25,9 @@ impl MaladieService {
             return Err("Le nom de la maladie ne peut pas dépasser 255 caractères".to_string());
         }
 
-        // Check if maladie with same name already exists
-        if let Ok(existing_maladies) = self.repository.get_maladies_list().await {
-            if existing_maladies.iter().any(|m| m.nom.to_lowercase() == maladie.nom.trim().to_lowercase()) {
-                return Err("Une maladie avec ce nom existe déjà".to_string());
-            }
+        // Check if maladie with same name already exists (ignoring case and accents)
+        if self.repository.exists_by_nom(&maladie.nom, None).await.map_err(|e| e.to_string())? {
+            return Err("Une maladie avec ce nom existe déjà".to_string());
         }
 
         self.repository.create(maladie).await
@@ -60,10 +58,8 @@ impl MaladieService {
         }
 
         // Check if another maladie with same name already exists (excluding current one)
-        if let Ok(existing_maladies) = self.repository.get_maladies_list().await {
-            if existing_maladies.iter().any(|m| m.id != maladie.id && m.nom.to_lowercase() == maladie.nom.trim().to_lowercase()) {
-                return Err("Une autre maladie avec ce nom existe déjà".to_string());
-            }
+        if self.repository.exists_by_nom(&maladie.nom, Some(maladie.id)).await.map_err(|e| e.to_string())? {
+            return Err("Une autre maladie avec ce nom existe déjà".to_string());
         }
 
         self.repository.update(maladie).await
@@ -75,4 +71,10 @@ impl MaladieService {
         self.repository.delete(id).await
             .map_err(|e| format!("Erreur lors de la suppression de la maladie: {}", e))
     }
+
+    /// Gets the number of affected batiments per maladie and per month or quarter
+    pub async fn get_maladie_trends(&self, ferme_id: Option<i64>, granularity: &str) -> Result<Vec<TendanceMaladie>, String> {
+        self.repository.get_trends(ferme_id, &granularity.trim().to_lowercase()).await
+            .map_err(|e| format!("Erreur lors du calcul des tendances des maladies: {}", e))
+    }
 }
@@ -0,0 +1,140 @@
+use crate::database::Storage;
+use crate::error::AppResult;
+use crate::models::MetriqueCommande;
+use crate::repositories::MetriqueRepository;
+use chrono::Local;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Intervalle d'enregistrement des mesures en attente par la tâche de fond
+pub const INTERVALLE_ENREGISTREMENT_METRIQUES: Duration = Duration::from_secs(60);
+
+/// Mesures locales des commandes (nombre d'appels, échecs et durées)
+///
+/// Toutes les commandes sont définies avec la macro `commande_mesuree!`
+/// (voir `commands`), qui passe par `mesurer`. Les mesures sont cumulées
+/// en mémoire puis ajoutées à la table `metriques_commandes` par une tâche de
+/// fond: une écriture après chaque commande invaliderait les caches à chaque
+/// consultation (voir `Storage::data_version`). Rien n'est envoyé hors du poste.
+#[derive(Default)]
+pub struct MetriquesCommandes {
+    en_attente: Mutex<BTreeMap<String, MetriqueCommande>>,
+}
+
+impl MetriquesCommandes {
+    /// Crée un compteur sans mesure
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Exécute une commande en mesurant sa durée
+    ///
+    /// # Arguments
+    /// * `commande` - Le nom de la commande Tauri
+    /// * `operation` - Le corps de la commande
+    ///
+    /// # Returns
+    /// Le résultat de `operation`, inchangé
+    pub async fn mesurer<T, E>(
+        &self,
+        commande: &str,
+        operation: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let debut = Instant::now();
+        let resultat = operation.await;
+        self.enregistrer(commande, debut.elapsed(), resultat.is_ok());
+        resultat
+    }
+
+    /// Ajoute une exécution aux mesures en attente
+    ///
+    /// # Arguments
+    /// * `commande` - Le nom de la commande Tauri
+    /// * `duree` - La durée de l'exécution
+    /// * `succes` - Faux si la commande s'est terminée par une erreur
+    pub fn enregistrer(&self, commande: &str, duree: Duration, succes: bool) {
+        let duree_ms = i64::try_from(duree.as_millis()).unwrap_or(i64::MAX);
+        self.cumuler(MetriqueCommande {
+            commande: commande.to_string(),
+            appels: 1,
+            echecs: if succes { 0 } else { 1 },
+            duree_totale_ms: duree_ms,
+            duree_moyenne_ms: 0.0,
+            duree_max_ms: duree_ms,
+            derniere_execution: Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+        });
+    }
+
+    /// Ajoute les mesures en attente à la base
+    ///
+    /// En cas d'échec (base occupée), elles restent en attente pour le
+    /// prochain enregistrement.
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn flush(&self, db: &Arc<dyn Storage>) -> AppResult<()> {
+        let mesures = std::mem::take(&mut *self.en_attente.lock().unwrap_or_else(PoisonError::into_inner));
+        if mesures.is_empty() {
+            return Ok(());
+        }
+
+        let resultat = db.write(|tx| {
+            for mesure in mesures.values() {
+                MetriqueRepository::add(tx, mesure)?;
+            }
+            Ok(())
+        });
+        if resultat.is_err() {
+            for mesure in mesures.into_values() {
+                self.cumuler(mesure);
+            }
+        }
+        resultat
+    }
+
+    /// Récupère les mesures de toutes les commandes, mesures en attente comprises
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    ///
+    /// # Returns
+    /// Les commandes mesurées, de la plus lente en moyenne à la plus rapide
+    pub fn get_performance_metrics(&self, db: &Arc<dyn Storage>) -> AppResult<Vec<MetriqueCommande>> {
+        self.flush(db)?;
+        let conn = db.get_connection()?;
+        MetriqueRepository::get_all(&conn)
+    }
+
+    /// Ajoute des mesures à celles en attente de la même commande
+    fn cumuler(&self, mesure: MetriqueCommande) {
+        let mut en_attente = self.en_attente.lock().unwrap_or_else(PoisonError::into_inner);
+        match en_attente.get_mut(&mesure.commande) {
+            Some(cumul) => {
+                cumul.appels += mesure.appels;
+                cumul.echecs += mesure.echecs;
+                cumul.duree_totale_ms = cumul.duree_totale_ms.saturating_add(mesure.duree_totale_ms);
+                cumul.duree_max_ms = cumul.duree_max_ms.max(mesure.duree_max_ms);
+                cumul.derniere_execution = cumul.derniere_execution.take().max(mesure.derniere_execution);
+            }
+            None => {
+                en_attente.insert(mesure.commande.clone(), mesure);
+            }
+        }
+    }
+
+    /// Tâche de fond: enregistre périodiquement les mesures en attente
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    /// * `intervalle` - Délai entre deux enregistrements
+    pub async fn run_periodic_flush(self: Arc<Self>, db: Arc<dyn Storage>, intervalle: Duration) {
+        let mut horloge = tokio::time::interval(intervalle);
+        loop {
+            horloge.tick().await;
+            // Base occupée: les mesures restent en attente jusqu'au prochain tour
+            let _ = self.flush(&db);
+        }
+    }
+}
@@ -9,6 +9,33 @@ pub mod bande_service;
 pub mod auth_service;
 pub mod maladie_service;
 pub mod semaine_service;
+pub mod demo_service;
+pub mod alerte_service;
+pub mod analyse_service;
+pub mod comparaison_service;
+pub mod suppression_service;
+pub mod fusion_service;
+pub mod personnel_service;
+pub mod plan_ferme_service;
+pub mod weather_service;
+pub mod capteur_service;
+pub mod statistics_cache;
+pub mod statistics_service;
+pub mod event_bus;
+pub mod totp;
+pub mod preference_service;
+pub mod archive_service;
+pub mod maintenance_service;
+pub mod export_service;
+pub mod verrouillage_service;
+pub mod bilan_service;
+pub mod rapport_service;
+pub mod export_programme_service;
+pub mod journee_service;
+pub mod configuration_service;
+pub mod import_service;
+pub mod webhook_service;
+pub mod metriques_commandes;
 
 // Re-export all services for easy access
 pub use ferme_service::*;
@@ -17,3 +44,29 @@ pub use bande_service::*;
 pub use auth_service::*;
 pub use maladie_service::*;
 pub use semaine_service::*;
+pub use demo_service::*;
+pub use alerte_service::*;
+pub use analyse_service::*;
+pub use comparaison_service::*;
+pub use suppression_service::*;
+pub use fusion_service::*;
+pub use personnel_service::*;
+pub use plan_ferme_service::*;
+pub use weather_service::*;
+pub use capteur_service::*;
+pub use statistics_cache::*;
+pub use statistics_service::*;
+pub use event_bus::*;
+pub use preference_service::*;
+pub use archive_service::*;
+pub use maintenance_service::*;
+pub use export_service::*;
+pub use verrouillage_service::*;
+pub use bilan_service::*;
+pub use rapport_service::*;
+pub use export_programme_service::*;
+pub use journee_service::*;
+pub use configuration_service::*;
+pub use import_service::*;
+pub use webhook_service::*;
+pub use metriques_commandes::*;
@@ -0,0 +1,142 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{Personnel, CreatePersonnel, UpdatePersonnel};
+use crate::repositories::{PersonnelRepository, PersonnelRepositoryTrait};
+use std::sync::Arc;
+
+/// Indicatif téléphonique du Maroc
+const INDICATIF_MAROC: &str = "212";
+
+/// Service de gestion du personnel
+///
+/// Valide et normalise le numéro de téléphone avant l'enregistrement.
+pub struct PersonnelService {
+    repository: PersonnelRepository,
+}
+
+impl PersonnelService {
+    /// Crée une nouvelle instance du service du personnel
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { repository: PersonnelRepository::new(db) }
+    }
+
+    /// Crée un membre du personnel avec un numéro de téléphone normalisé
+    ///
+    /// # Arguments
+    /// * `personnel` - Les données du membre à créer
+    ///
+    /// # Returns
+    /// Le membre créé, son téléphone au format national (`0612345678`)
+    pub async fn create_personnel(&self, mut personnel: CreatePersonnel) -> AppResult<Personnel> {
+        personnel.telephone = normaliser_telephone(&personnel.telephone)?;
+        self.ensure_telephone_unique(&personnel.telephone, None).await?;
+        self.repository.create(personnel).await
+    }
+
+    /// Met à jour un membre du personnel avec un numéro de téléphone normalisé
+    ///
+    /// # Arguments
+    /// * `personnel` - Les nouvelles données du membre
+    ///
+    /// # Returns
+    /// Le membre mis à jour
+    pub async fn update_personnel(&self, mut personnel: UpdatePersonnel) -> AppResult<Personnel> {
+        personnel.telephone = normaliser_telephone(&personnel.telephone)?;
+        self.ensure_telephone_unique(&personnel.telephone, Some(personnel.id)).await?;
+        self.repository.update(personnel).await
+    }
+
+    /// Refuse un numéro déjà attribué à un autre membre du personnel
+    ///
+    /// Les numéros enregistrés avant la normalisation sont normalisés pour la
+    /// comparaison: "06 12 34 56 78" et "+212612345678" sont le même numéro.
+    async fn ensure_telephone_unique(&self, telephone: &str, exclude_id: Option<i64>) -> AppResult<()> {
+        let existing = self.repository.get_personnel_list().await?;
+        let doublon = existing.iter().find(|p| {
+            p.id != exclude_id
+                && normaliser_telephone(&p.telephone).unwrap_or_else(|_| p.telephone.clone()) == telephone
+        });
+
+        if let Some(doublon) = doublon {
+            return Err(AppError::validation_error(
+                "telephone",
+                &format!("Ce numéro de téléphone est déjà attribué à {}", doublon.nom),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Normalise un numéro de téléphone marocain au format national
+///
+/// Accepte les séparateurs usuels (espaces, points, tirets, parenthèses) et
+/// l'indicatif du pays sous la forme `+212`, `00212` ou `212`, avec ou sans
+/// le `0` national: "+212 (0)6 12-34-56-78" donne "0612345678".
+///
+/// # Returns
+/// Le numéro sur 10 chiffres commençant par 05, 06, 07 ou 08
+pub fn normaliser_telephone(telephone: &str) -> AppResult<String> {
+    let brut = telephone.trim();
+    let international = brut.starts_with('+');
+    let chiffres: String = brut
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '.' | '-' | '(' | ')' | '/' | '+'))
+        .collect();
+
+    if chiffres.is_empty() || !chiffres.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::validation_error(
+            "telephone",
+            "Le numéro de téléphone ne doit contenir que des chiffres",
+        ));
+    }
+
+    let national = if let Some(reste) = chiffres.strip_prefix("00") {
+        reste.strip_prefix(INDICATIF_MAROC).ok_or_else(indicatif_non_supporte)?
+    } else if international {
+        chiffres.strip_prefix(INDICATIF_MAROC).ok_or_else(indicatif_non_supporte)?
+    } else if chiffres.len() > 10 {
+        chiffres.strip_prefix(INDICATIF_MAROC).unwrap_or(&chiffres)
+    } else {
+        &chiffres
+    };
+    let abonne = national.strip_prefix('0').unwrap_or(national);
+
+    if abonne.len() != 9 || !matches!(abonne.chars().next(), Some('5'..='8')) {
+        return Err(AppError::validation_error(
+            "telephone",
+            "Numéro marocain invalide: 10 chiffres commençant par 05, 06, 07 ou 08 (ou +212 suivi de 9 chiffres)",
+        ));
+    }
+
+    Ok(format!("0{}", abonne))
+}
+
+fn indicatif_non_supporte() -> AppError {
+    AppError::validation_error("telephone", "Seuls les numéros marocains (+212) sont acceptés")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normaliser_telephone_accepts_moroccan_formats() {
+        assert_eq!(normaliser_telephone("0612345678").unwrap(), "0612345678");
+        assert_eq!(normaliser_telephone("06 12 34 56 78").unwrap(), "0612345678");
+        assert_eq!(normaliser_telephone("06.12.34.56.78").unwrap(), "0612345678");
+        assert_eq!(normaliser_telephone("+212612345678").unwrap(), "0612345678");
+        assert_eq!(normaliser_telephone("+212 (0)6 12-34-56-78").unwrap(), "0612345678");
+        assert_eq!(normaliser_telephone("00212 522 12 34 56").unwrap(), "0522123456");
+        assert_eq!(normaliser_telephone("212712345678").unwrap(), "0712345678");
+    }
+
+    #[test]
+    fn normaliser_telephone_rejects_invalid_numbers() {
+        for invalide in ["", "06123", "0412345678", "06123456789", "+33612345678", "0033612345678", "06 12 AB 56 78"] {
+            assert!(normaliser_telephone(invalide).is_err(), "{} devrait être refusé", invalide);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{BatimentPlan, ChronologieBatiment, IntervalleOccupation, PlanFerme, PositionBatiment};
+use crate::repositories::PlanFermeRepository;
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+/// Service du plan de site des fermes
+///
+/// Gère les coordonnées des fermes et la position de leurs bâtiments, et
+/// assemble le plan avec l'état actuel de chaque bâtiment pour l'affichage.
+pub struct PlanFermeService {
+    db: Arc<dyn Storage>,
+}
+
+impl PlanFermeService {
+    /// Crée une nouvelle instance du service du plan de site
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Récupère le plan du site d'une ferme
+    ///
+    /// Chaque bâtiment physique (1 à `nbr_meuble`) est listé avec sa position
+    /// et, lorsqu'une bande en cours l'occupe, son effectif et sa mortalité.
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    ///
+    /// # Returns
+    /// Le plan de la ferme, bâtiments triés par numéro
+    pub async fn get_ferme_plan(&self, ferme_id: i64) -> AppResult<PlanFerme> {
+        let conn = self.db.get_connection()?;
+        let (ferme_nom, nbr_meuble, latitude, longitude) = PlanFermeRepository::get_ferme(&conn, ferme_id)?;
+        let mut positions = PlanFermeRepository::get_positions(&conn, ferme_id)?;
+        let mut occupations = PlanFermeRepository::get_occupations(&conn, ferme_id)?;
+
+        let mut numeros: Vec<String> = (1..=nbr_meuble).map(|i| i.to_string()).collect();
+        // Bâtiments occupés hors de la numérotation actuelle (nombre de bâtiments réduit depuis)
+        for (numero, _) in &occupations {
+            if !numeros.contains(numero) {
+                numeros.push(numero.clone());
+            }
+        }
+
+        let batiments = numeros
+            .into_iter()
+            .map(|numero| {
+                let position = positions
+                    .iter()
+                    .position(|p| p.numero_batiment == numero)
+                    .map(|i| positions.remove(i));
+                // La bande la plus récente est en tête de liste
+                let occupation = occupations
+                    .iter()
+                    .position(|(n, _)| *n == numero)
+                    .map(|i| occupations.remove(i).1);
+                BatimentPlan { numero_batiment: numero, position, occupation }
+            })
+            .collect();
+
+        Ok(PlanFerme { ferme_id, ferme_nom, latitude, longitude, batiments })
+    }
+
+    /// Récupère l'occupation des bâtiments d'une ferme sur une période
+    ///
+    /// Chaque bâtiment physique (1 à `nbr_meuble`) est listé, même sans
+    /// occupation sur la période, avec les bandes qui l'ont occupé. Deux
+    /// bandes présentes en même temps dans un bâtiment sont signalées par
+    /// `chevauchement`.
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `date_from` - Début de la période
+    /// * `date_to` - Fin de la période (incluse)
+    ///
+    /// # Returns
+    /// La chronologie de chaque bâtiment, triée par numéro
+    pub async fn get_building_occupancy(
+        &self,
+        ferme_id: i64,
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+    ) -> AppResult<Vec<ChronologieBatiment>> {
+        if date_from > date_to {
+            return Err(AppError::validation_error(
+                "date_to",
+                "La fin de la période ne peut pas précéder son début",
+            ));
+        }
+
+        let conn = self.db.get_connection()?;
+        let (_, nbr_meuble, _, _) = PlanFermeRepository::get_ferme(&conn, ferme_id)?;
+        let intervalles = PlanFermeRepository::get_intervalles_occupation(&conn, ferme_id, date_from, date_to)?;
+
+        let mut chronologies: Vec<ChronologieBatiment> = (1..=nbr_meuble)
+            .map(|i| ChronologieBatiment { numero_batiment: i.to_string(), intervalles: Vec::new() })
+            .collect();
+        for (numero, intervalle) in intervalles {
+            match chronologies.iter_mut().find(|c| c.numero_batiment == numero) {
+                Some(chronologie) => chronologie.intervalles.push(intervalle),
+                // Bâtiment hors de la numérotation actuelle (nombre de bâtiments réduit depuis)
+                None => chronologies.push(ChronologieBatiment { numero_batiment: numero, intervalles: vec![intervalle] }),
+            }
+        }
+
+        for chronologie in &mut chronologies {
+            marquer_chevauchements(&mut chronologie.intervalles);
+        }
+
+        Ok(chronologies)
+    }
+
+    /// Met à jour les coordonnées GPS d'une ferme
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `latitude` - Latitude en degrés décimaux, `None` pour effacer
+    /// * `longitude` - Longitude en degrés décimaux, `None` pour effacer
+    pub async fn update_ferme_coordinates(
+        &self,
+        ferme_id: i64,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    ) -> AppResult<()> {
+        valider_coordonnees(latitude, longitude)?;
+
+        self.db.write(|tx| PlanFermeRepository::update_coordonnees(tx, ferme_id, latitude, longitude))
+    }
+
+    /// Enregistre la position d'un bâtiment sur le plan
+    ///
+    /// # Arguments
+    /// * `position` - La position; le numéro doit être celui d'un bâtiment de la ferme
+    ///
+    /// # Returns
+    /// La position enregistrée
+    pub async fn update_batiment_position(&self, mut position: PositionBatiment) -> AppResult<PositionBatiment> {
+        position.numero_batiment = position.numero_batiment.trim().to_string();
+        valider_coordonnees(position.latitude, position.longitude)?;
+
+        for (champ, valeur) in [("plan_x", position.plan_x), ("plan_y", position.plan_y)] {
+            if valeur.is_some_and(|v| !v.is_finite()) {
+                return Err(AppError::validation_error(champ, "La coordonnée sur le plan doit être un nombre"));
+            }
+        }
+        for (champ, valeur) in [("largeur", position.largeur), ("hauteur", position.hauteur)] {
+            if valeur.is_some_and(|v| !v.is_finite() || v <= 0.0) {
+                return Err(AppError::validation_error(champ, "Les dimensions du bâtiment doivent être positives"));
+            }
+        }
+
+        self.db.write(|tx| {
+            let (_, nbr_meuble, _, _) = PlanFermeRepository::get_ferme(tx, position.ferme_id)?;
+            let numero_valide = position
+                .numero_batiment
+                .parse::<i32>()
+                .is_ok_and(|n| (1..=nbr_meuble).contains(&n));
+            if !numero_valide {
+                return Err(AppError::validation_error(
+                    "numero_batiment",
+                    &format!("Le numéro de bâtiment doit être compris entre 1 et {}", nbr_meuble),
+                ));
+            }
+
+            PlanFermeRepository::upsert_position(tx, &position)
+        })?;
+        Ok(position)
+    }
+}
+
+/// Marque les périodes d'un même bâtiment qui se chevauchent
+fn marquer_chevauchements(intervalles: &mut [IntervalleOccupation]) {
+    for i in 0..intervalles.len() {
+        for j in (i + 1)..intervalles.len() {
+            if intervalles[j].date_debut <= intervalles[i].date_fin && intervalles[i].date_debut <= intervalles[j].date_fin {
+                intervalles[i].chevauchement = true;
+                intervalles[j].chevauchement = true;
+            }
+        }
+    }
+}
+
+/// Vérifie qu'une paire latitude/longitude est complète et dans les bornes
+fn valider_coordonnees(latitude: Option<f64>, longitude: Option<f64>) -> AppResult<()> {
+    match (latitude, longitude) {
+        (None, None) => Ok(()),
+        (Some(lat), Some(lon)) => {
+            if !(-90.0..=90.0).contains(&lat) {
+                return Err(AppError::validation_error("latitude", "La latitude doit être comprise entre -90 et 90"));
+            }
+            if !(-180.0..=180.0).contains(&lon) {
+                return Err(AppError::validation_error("longitude", "La longitude doit être comprise entre -180 et 180"));
+            }
+            Ok(())
+        }
+        _ => Err(AppError::validation_error(
+            "latitude",
+            "La latitude et la longitude doivent être renseignées ensemble",
+        )),
+    }
+}
@@ -0,0 +1,95 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::repositories::PreferenceRepository;
+use crate::services::AuthService;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// Taille maximale du document de préférences d'un utilisateur, en octets
+pub const TAILLE_MAX_PREFERENCES: usize = 64 * 1024;
+
+/// Service des préférences d'affichage des utilisateurs
+///
+/// Les préférences (colonnes visibles, ferme par défaut, disposition du
+/// tableau de bord...) sont un document JSON libre rattaché au compte: elles
+/// suivent l'utilisateur d'un poste à l'autre. Leur contenu est défini par le
+/// frontend, le service ne vérifie que la forme et la taille du document.
+pub struct PreferenceService {
+    db: Arc<dyn Storage>,
+}
+
+impl PreferenceService {
+    /// Crée une nouvelle instance du service des préférences
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Récupère les préférences de l'utilisateur connecté
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session de l'utilisateur
+    ///
+    /// # Returns
+    /// Le document de préférences, un objet vide si rien n'a été enregistré
+    pub async fn get_user_preferences(&self, token: &str) -> AppResult<Value> {
+        let user = AuthService::new(self.db.clone()).current_user(token).await?;
+        let conn = self.db.get_connection()?;
+
+        match PreferenceRepository::get(&conn, user.id)? {
+            Some(preferences) => serde_json::from_str(&preferences)
+                .map_err(|e| AppError::business_logic(&format!("Préférences enregistrées illisibles: {}", e))),
+            None => Ok(Value::Object(Map::new())),
+        }
+    }
+
+    /// Enregistre des préférences de l'utilisateur connecté
+    ///
+    /// Les clés reçues remplacent les clés enregistrées, les autres sont
+    /// conservées: chaque écran peut enregistrer ses propres préférences sans
+    /// relire celles des autres. Une clé à `null` est supprimée.
+    ///
+    /// # Arguments
+    /// * `preferences` - Un objet JSON
+    /// * `token` - Le token de session de l'utilisateur
+    ///
+    /// # Returns
+    /// Le document de préférences complet après enregistrement
+    pub async fn set_user_preferences(&self, preferences: Value, token: &str) -> AppResult<Value> {
+        let user = AuthService::new(self.db.clone()).current_user(token).await?;
+        let Value::Object(modifications) = preferences else {
+            return Err(AppError::validation_error("preferences", "Les préférences doivent être un objet JSON"));
+        };
+
+        self.db.write(|tx| {
+            let mut document = match PreferenceRepository::get(tx, user.id)? {
+                Some(enregistrees) => match serde_json::from_str(&enregistrees) {
+                    Ok(Value::Object(document)) => document,
+                    // Un document illisible est remplacé plutôt que de bloquer l'utilisateur
+                    _ => Map::new(),
+                },
+                None => Map::new(),
+            };
+            for (cle, valeur) in modifications {
+                if valeur.is_null() {
+                    document.remove(&cle);
+                } else {
+                    document.insert(cle, valeur);
+                }
+            }
+
+            let document = Value::Object(document);
+            let serialise = document.to_string();
+            if serialise.len() > TAILLE_MAX_PREFERENCES {
+                return Err(AppError::validation_error(
+                    "preferences",
+                    &format!("Les préférences ne peuvent pas dépasser {} Ko", TAILLE_MAX_PREFERENCES / 1024),
+                ));
+            }
+            PreferenceRepository::set(tx, user.id, &serialise)?;
+            Ok(document)
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateRapportPersonnalise, EntiteRapport, RapportPersonnalise, ResultatRapport};
+use crate::repositories::RapportRepository;
+use crate::services::AuthService;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// Service des rapports personnalisés
+///
+/// Un rapport est une description JSON (entité, colonnes, filtres,
+/// regroupement) enregistrée sous un nom. Seuls les entités et champs du
+/// catalogue sont acceptés: la requête est construite par le repository et
+/// les valeurs des filtres sont toujours passées en paramètres.
+pub struct RapportService {
+    db: Arc<dyn Storage>,
+}
+
+impl RapportService {
+    /// Crée une nouvelle instance du service des rapports
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Liste les entités et les champs utilisables dans un rapport
+    pub fn get_report_entities(&self) -> Vec<EntiteRapport> {
+        RapportRepository::entites()
+    }
+
+    /// Enregistre une définition de rapport
+    ///
+    /// # Arguments
+    /// * `rapport` - Le nom et la description du rapport
+    /// * `token` - Le token de session de l'auteur, s'il y en a un
+    ///
+    /// # Returns
+    /// La définition enregistrée, ou une erreur si elle ne peut pas être construite
+    pub async fn create_report_definition(
+        &self,
+        rapport: CreateRapportPersonnalise,
+        token: Option<&str>,
+    ) -> AppResult<RapportPersonnalise> {
+        let created_by = AuthService::new(self.db.clone()).author_id(token).await?;
+        self.db.write(|tx| RapportRepository::create(tx, &rapport, created_by))
+    }
+
+    /// Liste les définitions de rapport enregistrées
+    pub async fn get_report_definitions(&self) -> AppResult<Vec<RapportPersonnalise>> {
+        let conn = self.db.get_connection()?;
+        RapportRepository::get_all(&conn)
+    }
+
+    /// Supprime une définition de rapport
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de la définition
+    pub async fn delete_report_definition(&self, id: i64) -> AppResult<()> {
+        self.db.write(|tx| RapportRepository::delete(tx, id))
+    }
+
+    /// Exécute une définition de rapport
+    ///
+    /// # Arguments
+    /// * `definition_id` - L'ID de la définition
+    /// * `params` - Les valeurs des filtres paramétrés, par nom de paramètre
+    ///
+    /// # Returns
+    /// Les noms des colonnes et les lignes du rapport
+    pub async fn run_report(&self, definition_id: i64, params: Map<String, Value>) -> AppResult<ResultatRapport> {
+        let conn = self.db.get_connection()?;
+        let rapport = RapportRepository::get_by_id(&conn, definition_id)?
+            .ok_or_else(|| AppError::not_found("Rapport", definition_id))?;
+        RapportRepository::executer(&conn, &rapport.definition, &params)
+    }
+}
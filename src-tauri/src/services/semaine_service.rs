@@ -1,7 +1,12 @@
-use crate::database::DatabaseManager;
-use crate::error::AppResult;
-use crate::models::{Semaine, CreateSemaine, SuiviQuotidienWithDetails, Maladie};
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    Semaine, SuiviQuotidienWithDetails, Maladie, Tracabilite, SEMAINES_SUIVI,
+    NormalisationPoids, PoidsReleve, SerieAlimentationSujet, SerieCroissance, poids_en_kg, poids_plausible, CROISSANCE_MAX_HEBDOMADAIRE,
+    POIDS_MAX_KG, POIDS_MIN_KG, UNITES_POIDS,
+};
 use crate::repositories::batiment_repository::BatimentRepository;
+use crate::repositories::programme_alimentation_repository::ProgrammeAlimentationRepository;
 use crate::repositories::semaine_repository::{SemaineRepository, SemaineRepositoryTrait};
 use crate::repositories::suivi_quotidien_repository::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
 use serde::{Deserialize, Serialize};
@@ -15,17 +20,100 @@ pub struct SemaineWithDetails {
     pub batiment_id: i64,
     pub numero_semaine: i32,
     pub poids: Option<f64>,
+    /// Dates de création et de modification, et auteur
+    #[serde(flatten)]
+    pub tracabilite: Tracabilite,
+    /// Poids signalé comme suspect par la normalisation, à vérifier
+    #[serde(default)]
+    pub poids_a_verifier: bool,
     pub suivi_quotidien: Vec<SuiviQuotidienWithDetails>,
+    /// Totaux de la semaine (pied du tableau hebdomadaire et rapports)
+    #[serde(default)]
+    pub totaux: TotauxSemaine,
+}
+
+/// Totaux calculés d'une semaine de suivi
+///
+/// Les pourcentages sont rapportés à l'effectif initial du bâtiment et valent
+/// `None` lorsque le bâtiment n'a pas d'effectif.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TotauxSemaine {
+    pub total_deces: i64,
+    /// Sujets éliminés volontairement (tri, réforme), hors mortalité
+    #[serde(default)]
+    pub total_elimines: i64,
+    /// Somme des alimentations journalières saisies, en kg
+    pub total_alimentation: f64,
+    /// Sujets vivants au début de la semaine (décès et éliminations précédents déduits)
+    pub effectif_debut: i64,
+    /// Alimentation moyenne par sujet et par jour, en grammes, sur les jours
+    /// où l'alimentation est saisie
+    pub alimentation_par_sujet_par_jour: Option<f64>,
+    /// Mortalité de la semaine en % de l'effectif initial
+    pub mortalite_pourcentage: Option<f64>,
+    /// Mortalité cumulée depuis le premier jour en % de l'effectif initial
+    pub mortalite_cumulee_pourcentage: Option<f64>,
+    /// Éliminations de la semaine en % de l'effectif initial
+    #[serde(default)]
+    pub elimines_pourcentage: Option<f64>,
+    /// Sujets vivants en fin de semaine en % de l'effectif initial
+    #[serde(default)]
+    pub viabilite_pourcentage: Option<f64>,
+    /// Gain de poids depuis la pesée précédente ramené à une semaine, en kg
+    /// (voir `SerieCroissance`)
+    #[serde(default)]
+    pub gain_hebdomadaire: Option<f64>,
+    /// Gain moyen quotidien depuis la pesée précédente, en g/jour
+    #[serde(default)]
+    pub gmq: Option<f64>,
+}
+
+impl TotauxSemaine {
+    /// Calcule les totaux d'une semaine
+    ///
+    /// # Arguments
+    /// * `suivis` - Les suivis quotidiens de la semaine (jours virtuels compris)
+    /// * `effectif_initial` - La quantité de poussins du bâtiment
+    /// * `deces_precedents` - Les décès des semaines précédentes
+    /// * `elimines_precedents` - Les éliminations des semaines précédentes
+    pub fn calculer(
+        suivis: &[SuiviQuotidienWithDetails],
+        effectif_initial: i64,
+        deces_precedents: i64,
+        elimines_precedents: i64,
+    ) -> Self {
+        let total_deces: i64 = suivis.iter().filter_map(|s| s.deces_par_jour).map(i64::from).sum();
+        let total_elimines: i64 = suivis.iter().filter_map(|s| s.elimines_par_jour).map(i64::from).sum();
+        let alimentations: Vec<f64> = suivis.iter().filter_map(|s| s.alimentation_par_jour).collect();
+        let total_alimentation: f64 = alimentations.iter().sum();
+        let effectif_debut = (effectif_initial - deces_precedents - elimines_precedents).max(0);
+        let pourcentage = |deces: i64| (effectif_initial > 0).then(|| deces as f64 * 100.0 / effectif_initial as f64);
+
+        Self {
+            total_deces,
+            total_elimines,
+            total_alimentation,
+            effectif_debut,
+            alimentation_par_sujet_par_jour: (effectif_debut > 0 && !alimentations.is_empty())
+                .then(|| total_alimentation * 1000.0 / (effectif_debut as f64 * alimentations.len() as f64)),
+            mortalite_pourcentage: pourcentage(total_deces),
+            mortalite_cumulee_pourcentage: pourcentage(deces_precedents + total_deces),
+            elimines_pourcentage: pourcentage(total_elimines),
+            viabilite_pourcentage: pourcentage((effectif_debut - total_deces - total_elimines).max(0)),
+            gain_hebdomadaire: None,
+            gmq: None,
+        }
+    }
 }
 
 /// Service pour la gestion des semaines avec logique métier complexe
 pub struct SemaineService {
-    db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl SemaineService {
     /// Créer une nouvelle instance du service semaine
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
+    pub fn new(db: Arc<dyn Storage>) -> Self {
         Self { db }
     }
 
@@ -45,6 +133,12 @@ impl SemaineService {
         
         // Récupérer les semaines existantes
         let existing_semaines = semaine_repo.get_by_batiment(batiment_id).await?;
+        let effectif_initial = {
+            let conn = self.db.get_connection()?;
+            BatimentRepository::get_by_id(&conn, batiment_id)?.map_or(0, |batiment| batiment.quantite as i64)
+        };
+        let mut deces_precedents = 0;
+        let mut elimines_precedents = 0;
         
         let mut result = Vec::new();
         
@@ -55,17 +149,13 @@ impl SemaineService {
         }
         
         // Créer ou récupérer les 8 semaines
-        for numero_semaine in 1..=8 {
+        for numero_semaine in 1..=SEMAINES_SUIVI {
             let semaine = if let Some(existing) = semaines_map.get(&numero_semaine) {
                 existing.clone()
             } else {
-                // Créer une nouvelle semaine si elle n'existe pas
-                let create_semaine = CreateSemaine {
-                    batiment_id,
-                    numero_semaine,
-                    poids: None,
-                };
-                let new_semaine = semaine_repo.create(create_semaine).await?;
+                // Créer la semaine si elle n'existe pas (ou la relire si une autre
+                // fenêtre vient de la créer)
+                let new_semaine = semaine_repo.get_or_create_semaine(batiment_id, numero_semaine).await?;
                 // Ajouter à la map pour éviter les doublons
                 semaines_map.insert(numero_semaine, new_semaine.clone());
                 new_semaine
@@ -94,6 +184,7 @@ impl SemaineService {
                                 semaine_id: semaine_id,
                                 age,
                                 deces_par_jour: None,
+                                elimines_par_jour: None,
                                 alimentation_par_jour: None,
                                 soins_id: None,
                                 soins_nom: None,
@@ -101,6 +192,9 @@ impl SemaineService {
                                 soins_quantite: None,
                                 analyses: None,
                                 remarques: None,
+                                version: 0,
+                                tracabilite: Tracabilite::default(),
+                                soins: Vec::new(),
                             }
                         });
                     
@@ -108,20 +202,85 @@ impl SemaineService {
                 }
             }
             
+            let totaux = TotauxSemaine::calculer(&suivis_quotidiens, effectif_initial, deces_precedents, elimines_precedents);
+            deces_precedents += totaux.total_deces;
+            elimines_precedents += totaux.total_elimines;
+            
             let semaine_with_details = SemaineWithDetails {
                 id: semaine.id,
                 batiment_id: semaine.batiment_id,
                 numero_semaine: semaine.numero_semaine,
                 poids: semaine.poids,
+                tracabilite: semaine.tracabilite,
+                poids_a_verifier: semaine.poids_a_verifier,
                 suivi_quotidien: suivis_quotidiens,
+                totaux,
             };
             
             result.push(semaine_with_details);
         }
+
+        // Gain de poids des semaines pesées (les semaines projetées ne sont pas reprises)
+        let pesees: Vec<(i32, Option<f64>)> = result.iter().map(|s| (s.numero_semaine, s.poids)).collect();
+        let serie = SerieCroissance::calculer(batiment_id, &pesees, SEMAINES_SUIVI);
+        for (semaine, point) in result.iter_mut().zip(serie.points.iter().filter(|point| !point.projete)) {
+            semaine.totaux.gain_hebdomadaire = point.gain_hebdomadaire;
+            semaine.totaux.gmq = point.gmq;
+        }
         
         Ok(result)
     }
 
+    /// Courbe de croissance d'un bâtiment: gain hebdomadaire et GMQ entre
+    /// pesées successives, et projection des semaines suivant la dernière pesée
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// 
+    /// # Returns
+    /// Un point par semaine de suivi (semaines supplémentaires comprises)
+    pub async fn get_weight_gain_series(&self, batiment_id: i64) -> AppResult<SerieCroissance> {
+        let conn = self.db.get_connection()?;
+        if BatimentRepository::get_by_id(&conn, batiment_id)?.is_none() {
+            return Err(AppError::not_found("Batiment", batiment_id));
+        }
+        let semaines = SemaineRepository::new(self.db.clone()).get_by_batiment(batiment_id).await?;
+        let derniere_semaine = semaines.iter().map(|s| s.numero_semaine).max().unwrap_or(0).max(SEMAINES_SUIVI);
+        let pesees: Vec<(i32, Option<f64>)> = semaines.iter().map(|s| (s.numero_semaine, s.poids)).collect();
+        Ok(SerieCroissance::calculer(batiment_id, &pesees, derniere_semaine))
+    }
+
+    /// Consommation d'aliment par sujet et par jour d'un bâtiment
+    /// 
+    /// Chaque jour saisi est rapporté aux sujets vivants ce jour-là (décès et
+    /// éliminations des jours précédents déduits).
+    /// 
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    /// 
+    /// # Returns
+    /// Un point par jour de suivi enregistré, par âge croissant
+    pub async fn get_feed_per_bird_series(&self, batiment_id: i64) -> AppResult<SerieAlimentationSujet> {
+        let conn = self.db.get_connection()?;
+        let batiment = BatimentRepository::get_by_id(&conn, batiment_id)?
+            .ok_or_else(|| AppError::not_found("Batiment", batiment_id))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT sq.age, COALESCE(sq.deces_par_jour, 0) + COALESCE(sq.elimines_par_jour, 0),
+                    sq.alimentation_par_jour
+             FROM suivi_quotidien sq
+             JOIN semaines s ON sq.semaine_id = s.id
+             WHERE s.batiment_id = ?1
+             ORDER BY sq.age",
+        )?;
+        let jours = stmt
+            .query_map([batiment_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let kg_par_sachet = ProgrammeAlimentationRepository::get_kg_par_sachet(&conn)?;
+        Ok(SerieAlimentationSujet::calculer(batiment_id, batiment.quantite as i64, &jours, kg_par_sachet))
+    }
+
     /// Retourne les semaines complètes et les maladies liées au bâtiment
     pub async fn get_full_semaines_with_maladies_by_batiment(
         &self,
@@ -135,14 +294,30 @@ impl SemaineService {
 
     /// Met à jour le poids d'une semaine
     /// 
+    /// Le poids est saisi dans l'unité donnée ou, à défaut, dans l'unité
+    /// paramétrée, puis enregistré en kg. Un poids hors de la plage plausible
+    /// est refusé: c'est le plus souvent une saisie dans la mauvaise unité.
+    /// La saisie lève le signalement éventuel du poids à vérifier.
+    /// 
     /// # Arguments
     /// * `semaine_id` - L'ID de la semaine
     /// * `poids` - Le nouveau poids
+    /// * `unite` - L'unité de la saisie ("g" ou "kg"), l'unité paramétrée si absente
     /// 
     /// # Returns
     /// Un `AppResult<Semaine>` contenant la semaine mise à jour
-    pub async fn update_semaine_poids(&self, semaine_id: i64, poids: Option<f64>) -> AppResult<Semaine> {
+    pub async fn update_semaine_poids(&self, semaine_id: i64, poids: Option<f64>, unite: Option<String>) -> AppResult<Semaine> {
         let semaine_repo = SemaineRepository::new(self.db.clone());
+        let poids = match poids {
+            Some(poids) => {
+                let unite = match unite {
+                    Some(unite) => unite.trim().to_lowercase(),
+                    None => SemaineRepository::get_poids_unit(&*self.db.get_connection()?)?,
+                };
+                Some(normaliser_saisie_poids(poids, &unite)?)
+            }
+            None => None,
+        };
         
         // Récupérer la semaine existante
         let existing_semaine = semaine_repo.get_by_id(semaine_id).await?;
@@ -153,9 +328,102 @@ impl SemaineService {
             batiment_id: existing_semaine.batiment_id,
             numero_semaine: existing_semaine.numero_semaine,
             poids,
+            // Saisie d'un seul champ: pas de contrôle de version
+            version: None,
         };
         
-        semaine_repo.update(update_semaine).await
+        let mut semaine = semaine_repo.update(update_semaine).await?;
+        if semaine.poids_a_verifier {
+            self.db.write(|tx| SemaineRepository::set_poids_a_verifier(tx, semaine_id, false))?;
+            semaine.poids_a_verifier = false;
+        }
+        Ok(semaine)
+    }
+
+    /// Unité de saisie des poids hebdomadaires ("g" ou "kg")
+    pub async fn get_poids_unit(&self) -> AppResult<String> {
+        let conn = self.db.get_connection()?;
+        SemaineRepository::get_poids_unit(&conn)
+    }
+
+    /// Définit l'unité de saisie des poids hebdomadaires
+    /// 
+    /// # Arguments
+    /// * `unite` - "g" ou "kg"
+    /// 
+    /// # Returns
+    /// L'unité enregistrée
+    pub async fn set_poids_unit(&self, unite: &str) -> AppResult<String> {
+        self.db.write(|tx| SemaineRepository::set_poids_unit(tx, unite))
+    }
+
+    /// Nombre maximal de semaines de suivi d'un bâtiment
+    pub async fn get_semaines_max(&self) -> AppResult<i32> {
+        let conn = self.db.get_connection()?;
+        SemaineRepository::get_semaines_max(&conn)
+    }
+
+    /// Définit le nombre maximal de semaines de suivi d'un bâtiment
+    /// 
+    /// # Arguments
+    /// * `semaines_max` - Entre 5 et 9 semaines
+    /// 
+    /// # Returns
+    /// Le nombre enregistré
+    pub async fn set_semaines_max(&self, semaines_max: i32) -> AppResult<i32> {
+        self.db.write(|tx| SemaineRepository::set_semaines_max(tx, semaines_max))
+    }
+
+    /// Normalise les poids déjà enregistrés
+    /// 
+    /// Les poids sont relus bâtiment par bâtiment, dans l'ordre des semaines:
+    /// un poids hors de la plage plausible qui le devient une fois divisé par
+    /// 1000 a été saisi en grammes et est converti en kg; un poids toujours
+    /// hors plage, en baisse par rapport à la semaine pesée précédente ou en
+    /// hausse anormale est signalé pour vérification.
+    /// 
+    /// # Arguments
+    /// * `appliquer` - `false` pour un simple aperçu, sans modification
+    /// 
+    /// # Returns
+    /// Les poids convertis et les poids à vérifier
+    pub async fn normalize_semaine_poids(&self, appliquer: bool) -> AppResult<NormalisationPoids> {
+        self.db.write(|tx| {
+            let mut stmt = tx.prepare(
+                "SELECT s.id, b.bande_id, b.id, b.numero_batiment, s.numero_semaine, s.poids
+                 FROM semaines s
+                 JOIN batiments b ON s.batiment_id = b.id
+                 WHERE s.poids IS NOT NULL
+                 ORDER BY b.id, s.numero_semaine",
+            )?;
+            let poids = stmt.query_map([], |row| {
+                Ok(PoidsReleve {
+                    semaine_id: row.get(0)?,
+                    bande_id: row.get(1)?,
+                    batiment_id: row.get(2)?,
+                    numero_batiment: row.get(3)?,
+                    numero_semaine: row.get(4)?,
+                    poids: row.get(5)?,
+                    poids_normalise: None,
+                    motif: String::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+            let (convertis, a_verifier) = relever_poids(poids);
+            if appliquer {
+                for releve in &convertis {
+                    tx.execute(
+                        "UPDATE semaines SET poids = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                        rusqlite::params![releve.poids_normalise, releve.semaine_id],
+                    )?;
+                }
+                for releve in &a_verifier {
+                    SemaineRepository::set_poids_a_verifier(tx, releve.semaine_id, true)?;
+                }
+            }
+            Ok(NormalisationPoids { appliquee: appliquer, convertis, a_verifier })
+        })
     }
 
     /// Initialise toutes les semaines vides pour un bâtiment si elles n'existent pas
@@ -173,15 +441,9 @@ impl SemaineService {
         let mut result = existing_semaines.clone();
         
         // Créer les semaines manquantes
-        for numero_semaine in 1..=8 {
+        for numero_semaine in 1..=SEMAINES_SUIVI {
             if !existing_semaines.iter().any(|s| s.numero_semaine == numero_semaine) {
-                let create_semaine = CreateSemaine {
-                    batiment_id,
-                    numero_semaine,
-                    poids: None,
-                };
-                
-                let new_semaine = semaine_repo.create(create_semaine).await?;
+                let new_semaine = semaine_repo.get_or_create_semaine(batiment_id, numero_semaine).await?;
                 result.push(new_semaine);
             }
         }
@@ -192,3 +454,240 @@ impl SemaineService {
         Ok(result)
     }
 }
+
+/// Convertit en kg un poids saisi dans l'unité donnée et vérifie qu'il est plausible
+fn normaliser_saisie_poids(poids: f64, unite: &str) -> AppResult<f64> {
+    if !poids.is_finite() || poids < 0.0 {
+        return Err(AppError::validation_error("poids", "Le poids ne peut pas être négatif"));
+    }
+    let poids_kg = poids_en_kg(poids, unite)
+        .ok_or_else(|| AppError::validation_error("unite", "L'unité de poids doit être \"g\" ou \"kg\""))?;
+    if poids_plausible(poids_kg) {
+        return Ok(poids_kg);
+    }
+
+    let plage = format!("{} à {} kg", POIDS_MIN_KG, POIDS_MAX_KG);
+    let autre_unite = UNITES_POIDS
+        .iter()
+        .map(|(nom, _)| *nom)
+        .find(|nom| *nom != unite && poids_en_kg(poids, nom).is_some_and(poids_plausible));
+    Err(AppError::validation_error(
+        "poids",
+        &match autre_unite {
+            Some(autre) => format!(
+                "Le poids {} {} est hors de la plage plausible ({}): s'agit-il de {} {} ?",
+                poids, unite, plage, poids, autre
+            ),
+            None => format!("Le poids {} {} est hors de la plage plausible ({})", poids, unite, plage),
+        },
+    ))
+}
+
+/// Classe les poids enregistrés (triés par bâtiment puis semaine) en poids
+/// convertis depuis les grammes et poids à vérifier
+fn relever_poids(poids: Vec<PoidsReleve>) -> (Vec<PoidsReleve>, Vec<PoidsReleve>) {
+    let mut convertis = Vec::new();
+    let mut a_verifier = Vec::new();
+    // Dernière semaine pesée plausible du bâtiment: (bâtiment, numéro de semaine, poids en kg)
+    let mut precedent: Option<(i64, i32, f64)> = None;
+
+    for mut releve in poids {
+        if precedent.is_some_and(|(batiment_id, ..)| batiment_id != releve.batiment_id) {
+            precedent = None;
+        }
+
+        let poids_kg = if poids_plausible(releve.poids) {
+            releve.poids
+        } else if poids_plausible(releve.poids / 1000.0) {
+            releve.poids_normalise = Some(releve.poids / 1000.0);
+            releve.motif = "Poids saisi en grammes, converti en kg".to_string();
+            convertis.push(releve.clone());
+            releve.poids / 1000.0
+        } else {
+            releve.motif = format!("Poids hors de la plage plausible ({} à {} kg)", POIDS_MIN_KG, POIDS_MAX_KG);
+            a_verifier.push(releve);
+            continue;
+        };
+
+        if let Some((_, semaine_precedente, poids_precedent)) = precedent {
+            let ecart_semaines = releve.numero_semaine - semaine_precedente;
+            if poids_kg < poids_precedent {
+                releve.motif = format!("Poids inférieur à celui de la semaine {}", semaine_precedente);
+                a_verifier.push(releve.clone());
+            } else if poids_kg > poids_precedent * CROISSANCE_MAX_HEBDOMADAIRE.powi(ecart_semaines) {
+                releve.motif = format!("Progression anormale depuis la semaine {}", semaine_precedente);
+                a_verifier.push(releve.clone());
+            }
+        }
+        precedent = Some((releve.batiment_id, releve.numero_semaine, poids_kg));
+    }
+
+    (convertis, a_verifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MemoryStorage, seed_bande, seed_batiment, seed_ferme};
+
+    #[tokio::test]
+    async fn full_semaines_are_created_with_virtual_days() {
+        let storage = MemoryStorage::new();
+        let ferme_id = seed_ferme(storage.as_ref(), "Ferme A", 2);
+        let bande_id = seed_bande(storage.as_ref(), ferme_id, 1, "2024-01-10");
+        let batiment_id = seed_batiment(storage.as_ref(), bande_id, "1", 1000);
+        let service = SemaineService::new(storage);
+
+        let semaines = service.get_full_semaines_by_batiment(batiment_id).await.unwrap();
+        assert_eq!(semaines.len(), 8);
+        assert!(semaines.iter().all(|s| s.suivi_quotidien.len() == 7));
+        assert_eq!(semaines[1].suivi_quotidien[0].age, 8);
+        assert!(semaines[0].suivi_quotidien.iter().all(|s| s.id.is_none()));
+
+        // Un second appel ne doit pas recréer de semaines
+        let again = service.initialize_batiment_semaines(batiment_id).await.unwrap();
+        assert_eq!(again.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn update_semaine_poids_keeps_other_fields() {
+        let storage = MemoryStorage::new();
+        let ferme_id = seed_ferme(storage.as_ref(), "Ferme A", 2);
+        let bande_id = seed_bande(storage.as_ref(), ferme_id, 1, "2024-01-10");
+        let batiment_id = seed_batiment(storage.as_ref(), bande_id, "1", 1000);
+        let service = SemaineService::new(storage);
+
+        let semaines = service.initialize_batiment_semaines(batiment_id).await.unwrap();
+        let updated = service.update_semaine_poids(semaines[2].id.unwrap(), Some(0.85), None).await.unwrap();
+        assert_eq!(updated.numero_semaine, 3);
+        assert_eq!(updated.batiment_id, batiment_id);
+        assert_eq!(updated.poids, Some(0.85));
+    }
+
+    #[tokio::test]
+    async fn update_semaine_poids_normalizes_to_kilograms() {
+        let storage = MemoryStorage::new();
+        let ferme_id = seed_ferme(storage.as_ref(), "Ferme A", 2);
+        let bande_id = seed_bande(storage.as_ref(), ferme_id, 1, "2024-01-10");
+        let batiment_id = seed_batiment(storage.as_ref(), bande_id, "1", 1000);
+        let service = SemaineService::new(storage);
+        let semaine_id = service.initialize_batiment_semaines(batiment_id).await.unwrap()[2].id.unwrap();
+
+        let saisie_g = service.update_semaine_poids(semaine_id, Some(850.0), Some("g".to_string())).await.unwrap();
+        assert_eq!(saisie_g.poids, Some(0.85));
+
+        // 850 "kg": saisie en grammes dans l'unité par défaut
+        let erreur = service.update_semaine_poids(semaine_id, Some(850.0), None).await.unwrap_err().to_string();
+        assert!(erreur.contains("850 g"), "{}", erreur);
+        assert!(service.update_semaine_poids(semaine_id, Some(-1.0), None).await.is_err());
+        assert!(service.update_semaine_poids(semaine_id, Some(1.0), Some("lb".to_string())).await.is_err());
+
+        assert_eq!(service.set_poids_unit("G").await.unwrap(), "g");
+        let saisie = service.update_semaine_poids(semaine_id, Some(1200.0), None).await.unwrap();
+        assert_eq!(saisie.poids, Some(1.2));
+        assert!(service.set_poids_unit("livre").await.is_err());
+    }
+
+    #[test]
+    fn weight_gain_series_uses_previous_weighing_and_projects_the_rest() {
+        let serie = SerieCroissance::calculer(7, &[(1, Some(0.18)), (2, None), (3, Some(0.88)), (4, Some(1.37))], 6);
+        assert_eq!(serie.points.len(), 6);
+        let arrondi = |valeur: Option<f64>| valeur.map(|v| (v * 1000.0).round() / 1000.0);
+
+        // Semaine 1 depuis le poussin d'un jour: (180 - 40) g / 7 jours
+        assert_eq!(arrondi(serie.points[0].gmq), Some(20.0));
+        assert_eq!(arrondi(serie.points[0].gain_hebdomadaire), Some(0.14));
+        // Semaine 2 non pesée, semaine 3 depuis la semaine 1: 700 g / 14 jours
+        assert_eq!(serie.points[1].gmq, None);
+        assert_eq!(arrondi(serie.points[2].gmq), Some(50.0));
+        assert_eq!(arrondi(serie.points[3].gmq), Some(70.0));
+
+        // Projection avec le GMQ moyen des deux derniers intervalles (60 g/jour)
+        assert_eq!(arrondi(serie.gmq_projection), Some(60.0));
+        assert!(serie.points[4].projete && serie.points[5].projete);
+        assert_eq!(arrondi(serie.points[5].poids), Some(2.21));
+        assert_eq!(arrondi(serie.gmq_moyen), Some(47.5));
+
+        let vide = SerieCroissance::calculer(7, &[], 8);
+        assert!(vide.points.iter().all(|point| point.poids.is_none() && !point.projete));
+        assert_eq!(vide.gmq_moyen, None);
+    }
+
+    #[tokio::test]
+    async fn full_semaines_carry_weekly_gain() {
+        let storage = MemoryStorage::new();
+        let ferme_id = seed_ferme(storage.as_ref(), "Ferme A", 2);
+        let bande_id = seed_bande(storage.as_ref(), ferme_id, 1, "2024-01-10");
+        let batiment_id = seed_batiment(storage.as_ref(), bande_id, "1", 1000);
+        let service = SemaineService::new(storage);
+        let semaines = service.initialize_batiment_semaines(batiment_id).await.unwrap();
+        service.update_semaine_poids(semaines[0].id.unwrap(), Some(0.18), None).await.unwrap();
+        service.update_semaine_poids(semaines[1].id.unwrap(), Some(0.46), None).await.unwrap();
+
+        let full = service.get_full_semaines_by_batiment(batiment_id).await.unwrap();
+        assert!((full[1].totaux.gmq.unwrap() - 40.0).abs() < 1e-9);
+        assert!((full[1].totaux.gain_hebdomadaire.unwrap() - 0.28).abs() < 1e-9);
+        assert_eq!(full[2].totaux.gmq, None);
+
+        let serie = service.get_weight_gain_series(batiment_id).await.unwrap();
+        assert_eq!(serie.points.len(), 8);
+        assert!(serie.points[7].projete);
+        assert!(service.get_weight_gain_series(batiment_id + 100).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn feed_per_bird_series_follows_live_count() {
+        let storage = MemoryStorage::new();
+        let ferme_id = seed_ferme(storage.as_ref(), "Ferme A", 2);
+        let bande_id = seed_bande(storage.as_ref(), ferme_id, 1, "2024-01-10");
+        let batiment_id = seed_batiment(storage.as_ref(), bande_id, "1", 1000);
+        let service = SemaineService::new(storage.clone());
+        let semaines = service.initialize_batiment_semaines(batiment_id).await.unwrap();
+        {
+            let conn = storage.get_connection().unwrap();
+            conn.execute(
+                "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, alimentation_par_jour)
+                 VALUES (?1, 2, NULL, 1.0), (?1, 1, 200, 0.5), (?1, 3, 300, NULL)",
+                [semaines[0].id.unwrap()],
+            ).unwrap();
+        }
+
+        let serie = service.get_feed_per_bird_series(batiment_id).await.unwrap();
+        let ages: Vec<i32> = serie.points.iter().map(|point| point.age).collect();
+        assert_eq!(ages, vec![1, 2, 3]);
+        assert_eq!(serie.points[0].grammes_par_sujet, Some(25.0));
+        // Jour 2: un sachet de 50 kg pour les 800 sujets restants
+        assert_eq!(serie.points[1].effectif, 800);
+        assert_eq!(serie.points[1].grammes_par_sujet, Some(62.5));
+        assert_eq!(serie.points[2].grammes_par_sujet, None);
+        assert!(service.get_feed_per_bird_series(batiment_id + 100).await.is_err());
+    }
+
+    #[test]
+    fn relever_poids_converts_grams_and_flags_outliers() {
+        let releve = |batiment_id: i64, numero_semaine: i32, poids: f64| PoidsReleve {
+            semaine_id: batiment_id * 10 + numero_semaine as i64,
+            bande_id: 1,
+            batiment_id,
+            numero_batiment: batiment_id.to_string(),
+            numero_semaine,
+            poids,
+            poids_normalise: None,
+            motif: String::new(),
+        };
+        let (convertis, a_verifier) = relever_poids(vec![
+            releve(1, 1, 0.18),
+            releve(1, 2, 470.0),
+            releve(1, 3, 0.4),
+            releve(1, 5, 9000.0),
+            releve(2, 1, 0.2),
+            releve(2, 2, 1.5),
+        ]);
+
+        assert_eq!(convertis.len(), 1);
+        assert_eq!(convertis[0].semaine_id, 12);
+        assert_eq!(convertis[0].poids_normalise, Some(0.47));
+        let suspects: Vec<i64> = a_verifier.iter().map(|r| r.semaine_id).collect();
+        assert_eq!(suspects, vec![13, 15, 22]);
+    }
+}
@@ -1,15 +1,17 @@
-use crate::database::DatabaseManager;
+use crate::database::Storage;
 use crate::error::AppResult;
-use crate::models::{Soin, CreateSoin, UpdateSoin};
+use crate::models::{convertir_quantite, Soin, CreateSoin, UpdateSoin, SoinUsage};
+use rusqlite::ToSql;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 pub struct SoinService {
-    _db: Arc<DatabaseManager>,
+    db: Arc<dyn Storage>,
 }
 
 impl SoinService {
-    pub fn new(db: Arc<DatabaseManager>) -> Self {
-        Self { _db: db }
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
     }
 
     pub async fn create_soin(&self, _soin: CreateSoin) -> AppResult<Soin> {
@@ -19,4 +21,163 @@ impl SoinService {
     pub async fn get_all_soins(&self) -> AppResult<Vec<Soin>> {
         todo!("Implementation will follow")
     }
+
+    /// Consommation des soins d'une bande, tous bâtiments confondus
+    /// 
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// 
+    /// # Returns
+    /// Les quantités agrégées par soin, triées par nom
+    pub async fn get_soins_usage(&self, bande_id: i64) -> AppResult<Vec<SoinUsage>> {
+        self.query_usage("b.bande_id = ?1", &[&bande_id])
+    }
+
+    /// Consommation des soins d'une ferme sur une période
+    /// 
+    /// La date d'un suivi est déduite de la date d'entrée de la bande et de
+    /// l'âge des sujets (jour 1 = date d'entrée).
+    /// 
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `date_from` - Début de période inclus ("YYYY-MM-DD")
+    /// * `date_to` - Fin de période incluse ("YYYY-MM-DD")
+    /// 
+    /// # Returns
+    /// Les quantités agrégées par soin, triées par nom
+    pub async fn get_soins_usage_by_ferme(
+        &self,
+        ferme_id: i64,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> AppResult<Vec<SoinUsage>> {
+        self.query_usage(
+            "bd.ferme_id = ?1
+             AND (?2 IS NULL OR date(bd.date_entree, '+' || (sq.age - 1) || ' days') >= date(?2))
+             AND (?3 IS NULL OR date(bd.date_entree, '+' || (sq.age - 1) || ' days') <= date(?3))",
+            &[&ferme_id, &date_from, &date_to],
+        )
+    }
+
+    /// Lit les administrations de soins correspondant au filtre et les agrège par soin
+    /// 
+    /// Les quantités structurées sont converties dans l'unité du soin; une
+    /// quantité dans une unité non convertible donne une ligne pour cette unité.
+    fn query_usage(&self, condition: &str, params: &[&dyn ToSql]) -> AppResult<Vec<SoinUsage>> {
+        let conn = self.db.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT so.id, so.nom, COALESCE(ss.unit, so.unit), ss.quantite_valeur, ss.quantite_unite,
+                    so.unit, so.prix_unitaire
+             FROM suivi_soins ss
+             JOIN soins so ON ss.soin_id = so.id
+             JOIN suivi_quotidien sq ON ss.suivi_id = sq.id
+             JOIN semaines s ON sq.semaine_id = s.id
+             JOIN batiments b ON s.batiment_id = b.id
+             JOIN bandes bd ON b.bande_id = bd.id
+             WHERE {}",
+            condition
+        ))?;
+
+        let rows = stmt.query_map(params, |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<f64>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Option<f64>>(6)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut usage: BTreeMap<(i64, String), SoinUsage> = BTreeMap::new();
+        for (soin_id, soin_nom, unit_saisie, valeur, unite, unit_soin, prix_unitaire) in rows {
+            let cout = cout_administration(valeur, unite.as_deref(), &unit_soin, prix_unitaire);
+            let convertie = valeur
+                .zip(unite.as_deref())
+                .and_then(|(valeur, unite)| convertir_quantite(valeur, unite, &unit_soin));
+            let (unit, quantite) = match (convertie, valeur, unite) {
+                (Some(quantite), _, _) => (unit_soin, Some(quantite)),
+                (None, Some(valeur), Some(unite)) => (unite, Some(valeur)),
+                _ => (unit_saisie, None),
+            };
+            let entry = usage.entry((soin_id, unit.clone())).or_insert_with(|| SoinUsage {
+                soin_id,
+                soin_nom,
+                unit,
+                total_quantite: 0.0,
+                nombre_administrations: 0,
+                saisies_non_numeriques: 0,
+                cout: None,
+            });
+            entry.nombre_administrations += 1;
+            match quantite {
+                Some(value) => entry.total_quantite += value,
+                None => entry.saisies_non_numeriques += 1,
+            }
+            if let Some(cout) = cout {
+                entry.cout = Some(entry.cout.unwrap_or(0.0) + cout);
+            }
+        }
+
+        let mut result: Vec<SoinUsage> = usage.into_values().collect();
+        result.sort_by(|a, b| {
+            a.soin_nom.to_lowercase().cmp(&b.soin_nom.to_lowercase()).then_with(|| a.unit.cmp(&b.unit))
+        });
+        Ok(result)
+    }
+}
+
+/// Coût d'une administration de soin
+/// 
+/// La quantité structurée est convertie dans l'unité du prix du soin; elle
+/// n'est valorisée que si la conversion est possible et que le prix est connu.
+/// 
+/// # Arguments
+/// * `valeur` - La valeur de la quantité structurée
+/// * `unite` - L'unité normalisée de la quantité
+/// * `unit_soin` - L'unité du prix du soin
+/// * `prix_unitaire` - Le prix d'une unité du soin
+pub fn cout_administration(valeur: Option<f64>, unite: Option<&str>, unit_soin: &str, prix_unitaire: Option<f64>) -> Option<f64> {
+    Some(convertir_quantite(valeur?, unite?, unit_soin)? * prix_unitaire?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QuantiteSoin;
+
+    #[test]
+    fn quantite_soin_reads_leading_number_and_unit() {
+        let parse = |texte: &str, defaut: Option<&str>| {
+            QuantiteSoin::parse(texte, defaut).map(|q| (q.valeur, q.unite))
+        };
+        assert_eq!(parse("250", Some("ml")), Some((250.0, "ml".to_string())));
+        assert_eq!(parse("5l", Some("ml")), Some((5.0, "l".to_string())));
+        assert_eq!(parse(" 0,5 ml/L", None), Some((0.5, "ml/l".to_string())));
+        assert_eq!(parse("1.25 Kilos", Some("g")), Some((1.25, "kg".to_string())));
+        assert_eq!(parse("2", None), None);
+        assert_eq!(parse("selon notice", Some("ml")), None);
+        assert_eq!(parse("", Some("ml")), None);
+    }
+
+    #[test]
+    fn quantities_convert_within_a_dimension() {
+        assert_eq!(convertir_quantite(0.5, "L", "ml"), Some(500.0));
+        assert_eq!(convertir_quantite(250.0, "mg", "g"), Some(0.25));
+        assert_eq!(convertir_quantite(2.0, "Doses", "dose"), Some(2.0));
+        assert_eq!(convertir_quantite(3.0, "ml/L", "ml/l"), Some(3.0));
+        assert_eq!(convertir_quantite(1.0, "kg", "l"), None);
+        assert_eq!(convertir_quantite(1.0, "ml/l", "ml"), None);
+    }
+
+    #[test]
+    fn cout_administration_converts_to_the_priced_unit() {
+        assert_eq!(cout_administration(Some(2.5), Some("l"), "L", Some(4.0)), Some(10.0));
+        assert_eq!(cout_administration(Some(250.0), Some("ml"), "L", Some(4.0)), Some(1.0));
+        assert_eq!(cout_administration(Some(2.0), Some("kg"), "L", Some(4.0)), None);
+        assert_eq!(cout_administration(None, None, "L", Some(4.0)), None);
+        assert_eq!(cout_administration(Some(2.0), Some("l"), "L", None), None);
+    }
 }
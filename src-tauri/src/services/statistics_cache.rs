@@ -0,0 +1,104 @@
+use crate::database::Storage;
+use crate::error::AppResult;
+use crate::repositories::GlobalStatistics;
+use crate::services::FermeService;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Intervalle de vérification du rafraîchissement en arrière-plan
+pub const INTERVALLE_RAFRAICHISSEMENT_STATISTIQUES: Duration = Duration::from_secs(30);
+
+/// Statistiques calculées pour une version des données
+struct EntreeCache {
+    data_version: u64,
+    calcule_le: DateTime<Utc>,
+    statistiques: GlobalStatistics,
+}
+
+/// Cache des statistiques globales du tableau de bord
+///
+/// Les statistiques sont recalculées seulement lorsque des données ont été
+/// modifiées depuis le dernier calcul (voir `Storage::data_version`); une
+/// tâche de fond les recalcule après chaque modification pour que le tableau
+/// de bord n'attende pas le calcul.
+pub struct StatisticsCache {
+    global: Mutex<Option<EntreeCache>>,
+}
+
+impl Default for StatisticsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatisticsCache {
+    /// Crée un cache vide
+    pub fn new() -> Self {
+        Self { global: Mutex::new(None) }
+    }
+
+    /// Récupère les statistiques globales, depuis le cache si les données n'ont pas changé
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    ///
+    /// # Returns
+    /// Les statistiques, avec leur date de calcul et l'âge du cache
+    pub async fn get_global_statistics(&self, db: Arc<dyn Storage>) -> AppResult<GlobalStatistics> {
+        let mut global = self.global.lock().await;
+        let version = db.data_version();
+
+        if let (Some(entree), Some(version)) = (global.as_ref(), version) {
+            if entree.data_version == version {
+                let mut statistiques = entree.statistiques.clone();
+                statistiques.cache_age_seconds = (Utc::now() - entree.calcule_le).num_seconds().max(0);
+                return Ok(statistiques);
+            }
+        }
+
+        let statistiques = FermeService::new(db).get_global_statistics().await?;
+        if let Some(version) = version {
+            *global = Some(EntreeCache { data_version: version, calcule_le: Utc::now(), statistiques: statistiques.clone() });
+        }
+        Ok(statistiques)
+    }
+
+    /// Recalcule les statistiques si les données ont changé depuis le dernier calcul
+    ///
+    /// # Returns
+    /// `true` si les statistiques ont été recalculées
+    pub async fn refresh_if_stale(&self, db: Arc<dyn Storage>) -> AppResult<bool> {
+        let a_jour = match (self.global.lock().await.as_ref(), db.data_version()) {
+            (Some(entree), Some(version)) => entree.data_version == version,
+            (_, None) => true,
+            (None, Some(_)) => false,
+        };
+        if a_jour {
+            return Ok(false);
+        }
+
+        self.get_global_statistics(db).await?;
+        Ok(true)
+    }
+
+    /// Vide le cache, par exemple lorsqu'une autre base de données est ouverte
+    pub async fn clear(&self) {
+        *self.global.lock().await = None;
+    }
+
+    /// Tâche de fond: vérifie périodiquement le cache et le recalcule après une modification
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    /// * `intervalle` - Délai entre deux vérifications
+    pub async fn run_background_refresh(self: Arc<Self>, db: Arc<dyn Storage>, intervalle: Duration) {
+        let mut horloge = tokio::time::interval(intervalle);
+        loop {
+            horloge.tick().await;
+            // Une erreur passagère (base occupée) sera retentée au prochain tour
+            let _ = self.refresh_if_stale(db.clone()).await;
+        }
+    }
+}
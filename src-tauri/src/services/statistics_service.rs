@@ -0,0 +1,336 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    FacteurSante, PointSerie, SanteBatiment, SerieGraphique, FACTEUR_MALADIES, FACTEUR_MORTALITE,
+    FACTEUR_RATIO_EAU_ALIMENT, FACTEUR_TENDANCE_ALIMENTATION, FACTEUR_TENDANCE_MORTALITE, JOURS_FENETRE_SANTE,
+    LISSAGE_MAX, METRIQUE_ALIMENTATION, METRIQUE_MORTALITE, METRIQUE_POIDS, METRIQUE_TEMPERATURE,
+    SEMAINES_SUIVI,
+};
+use crate::repositories::ProgrammeAlimentationRepository;
+use rusqlite::{Connection, ToSql};
+use std::sync::Arc;
+
+/// Points retirés par point de pourcentage de mortalité sur la fenêtre récente
+const POINTS_PAR_POURCENT_MORTALITE: f64 = 10.0;
+const POINTS_MAX_MORTALITE: f64 = 30.0;
+/// Points retirés par point de pourcentage de mortalité en plus par rapport à la fenêtre précédente
+const POINTS_PAR_POURCENT_HAUSSE_MORTALITE: f64 = 20.0;
+const POINTS_MAX_TENDANCE_MORTALITE: f64 = 20.0;
+/// Points retirés par pourcentage de baisse de la consommation d'aliment
+const POINTS_MAX_TENDANCE_ALIMENTATION: f64 = 20.0;
+const POINTS_PAR_MALADIE: f64 = 15.0;
+const POINTS_MAX_MALADIES: f64 = 30.0;
+
+/// Service de statistiques calculées à la demande sur les données de suivi
+pub struct StatisticsService {
+    db: Arc<dyn Storage>,
+}
+
+/// Saisie d'un jour de suivi d'un bâtiment
+struct JourSuivi {
+    age: i32,
+    pertes: i64,
+    alimentation: Option<f64>,
+}
+
+impl StatisticsService {
+    /// Crée une nouvelle instance du service de statistiques
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Score de santé d'un bâtiment et facteurs qui y contribuent
+    ///
+    /// Combine la mortalité récente, l'évolution de la mortalité et de la
+    /// consommation d'aliment entre les deux dernières fenêtres de
+    /// `JOURS_FENETRE_SANTE` jours, et les maladies déclarées sur le bâtiment.
+    /// La consommation d'eau n'étant pas saisie, le ratio eau/aliment est
+    /// signalé sans retirer de points.
+    ///
+    /// # Arguments
+    /// * `batiment_id` - L'ID du bâtiment
+    ///
+    /// # Returns
+    /// Le score (0 à 100) avec le détail des facteurs
+    pub async fn get_batiment_health(&self, batiment_id: i64) -> AppResult<SanteBatiment> {
+        let conn = self.db.get_connection()?;
+        let (bande_id, numero_batiment, quantite): (i64, String, i64) = conn
+            .query_row(
+                "SELECT bande_id, numero_batiment, quantite FROM batiments WHERE id = ?1",
+                [batiment_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Batiment", batiment_id),
+                _ => AppError::from(e),
+            })?;
+
+        let jours = jours_suivi(&conn, batiment_id)?;
+        let age_reference = jours.last().map(|jour| jour.age);
+        let maladies: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM batiment_maladies WHERE batiment_id = ?1",
+            [batiment_id],
+            |row| row.get(0),
+        )?;
+
+        let mut facteurs = Vec::new();
+        let (recents, precedents, effectif_recent, effectif_precedent) = match age_reference {
+            Some(age_reference) => {
+                let debut_recent = age_reference - JOURS_FENETRE_SANTE + 1;
+                let debut_precedent = debut_recent - JOURS_FENETRE_SANTE;
+                let pertes_avant = |age: i32| jours.iter().filter(|j| j.age < age).map(|j| j.pertes).sum::<i64>();
+                (
+                    jours.iter().filter(|j| j.age >= debut_recent).collect::<Vec<_>>(),
+                    jours.iter().filter(|j| j.age >= debut_precedent && j.age < debut_recent).collect::<Vec<_>>(),
+                    quantite - pertes_avant(debut_recent),
+                    quantite - pertes_avant(debut_precedent),
+                )
+            }
+            None => (Vec::new(), Vec::new(), quantite, quantite),
+        };
+
+        let taux = |fenetre: &[&JourSuivi], effectif: i64| {
+            (!fenetre.is_empty() && effectif > 0)
+                .then(|| fenetre.iter().map(|j| j.pertes).sum::<i64>() as f64 * 100.0 / effectif as f64)
+        };
+        let taux_recent = taux(&recents, effectif_recent);
+        let taux_precedent = taux(&precedents, effectif_precedent);
+
+        facteurs.push(match taux_recent {
+            Some(taux) => FacteurSante {
+                facteur: FACTEUR_MORTALITE.to_string(),
+                valeur: Some(taux),
+                points_perdus: (taux * POINTS_PAR_POURCENT_MORTALITE).min(POINTS_MAX_MORTALITE),
+                description: format!("{:.2}% de pertes sur les {} derniers jours", taux, JOURS_FENETRE_SANTE),
+            },
+            None => non_mesurable(FACTEUR_MORTALITE, "Aucune mortalité saisie"),
+        });
+
+        facteurs.push(match (taux_recent, taux_precedent) {
+            (Some(recent), Some(precedent)) => {
+                let hausse = recent - precedent;
+                FacteurSante {
+                    facteur: FACTEUR_TENDANCE_MORTALITE.to_string(),
+                    valeur: Some(hausse),
+                    points_perdus: (hausse * POINTS_PAR_POURCENT_HAUSSE_MORTALITE).clamp(0.0, POINTS_MAX_TENDANCE_MORTALITE),
+                    description: format!(
+                        "Mortalité de {:.2}% contre {:.2}% les {} jours précédents",
+                        recent, precedent, JOURS_FENETRE_SANTE
+                    ),
+                }
+            }
+            _ => non_mesurable(FACTEUR_TENDANCE_MORTALITE, "Pas assez de jours saisis pour comparer la mortalité"),
+        });
+
+        let moyenne = |fenetre: &[&JourSuivi]| {
+            let valeurs: Vec<f64> = fenetre.iter().filter_map(|j| j.alimentation).collect();
+            (!valeurs.is_empty()).then(|| valeurs.iter().sum::<f64>() / valeurs.len() as f64)
+        };
+        facteurs.push(match (moyenne(&recents), moyenne(&precedents)) {
+            (Some(recente), Some(precedente)) if precedente > 0.0 => {
+                let variation = (recente - precedente) * 100.0 / precedente;
+                FacteurSante {
+                    facteur: FACTEUR_TENDANCE_ALIMENTATION.to_string(),
+                    valeur: Some(variation),
+                    points_perdus: (-variation).clamp(0.0, POINTS_MAX_TENDANCE_ALIMENTATION),
+                    description: format!(
+                        "Consommation d'aliment de {:+.1}% par rapport aux {} jours précédents",
+                        variation, JOURS_FENETRE_SANTE
+                    ),
+                }
+            }
+            _ => non_mesurable(FACTEUR_TENDANCE_ALIMENTATION, "Pas assez de jours saisis pour comparer l'alimentation"),
+        });
+
+        facteurs.push(non_mesurable(FACTEUR_RATIO_EAU_ALIMENT, "La consommation d'eau n'est pas saisie"));
+
+        facteurs.push(FacteurSante {
+            facteur: FACTEUR_MALADIES.to_string(),
+            valeur: Some(maladies as f64),
+            points_perdus: (maladies as f64 * POINTS_PAR_MALADIE).min(POINTS_MAX_MALADIES),
+            description: format!("{} maladie(s) déclarée(s) sur le bâtiment", maladies),
+        });
+
+        let points_perdus: f64 = facteurs.iter().map(|f| f.points_perdus).sum();
+        Ok(SanteBatiment {
+            batiment_id,
+            bande_id,
+            numero_batiment,
+            age_reference,
+            score: (100.0 - points_perdus).max(0.0),
+            facteurs,
+        })
+    }
+
+    /// Série quotidienne ou hebdomadaire d'une métrique, prête à tracer
+    ///
+    /// La série porte sur un bâtiment ou sur tous les bâtiments d'une bande:
+    /// décès et éliminés (sujets), aliment consommé (kg) et température
+    /// moyenne (°C) par jour, poids moyen (kg) en fin de semaine. La
+    /// température est celle des capteurs des bâtiments physiques pendant la bande.
+    ///
+    /// # Arguments
+    /// * `metrique` - `mortalite`, `alimentation`, `poids` ou `temperature`
+    /// * `bande_id` - La bande, si la série ne porte pas sur un bâtiment
+    /// * `batiment_id` - Le bâtiment, si la série ne porte pas sur une bande
+    /// * `lissage` - Nombre de points de la moyenne mobile, sans lissage si absent
+    ///
+    /// # Returns
+    /// Les points de la série triés par date
+    pub async fn get_chart_series(
+        &self,
+        metrique: &str,
+        bande_id: Option<i64>,
+        batiment_id: Option<i64>,
+        lissage: Option<u32>,
+    ) -> AppResult<SerieGraphique> {
+        let conn = self.db.get_connection()?;
+        match (bande_id, batiment_id) {
+            (Some(bande_id), None) => {
+                let existe: i64 = conn.query_row("SELECT COUNT(*) FROM bandes WHERE id = ?1", [bande_id], |row| row.get(0))?;
+                if existe == 0 {
+                    return Err(AppError::not_found("Bande", bande_id));
+                }
+            }
+            (None, Some(batiment_id)) => {
+                let existe: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM batiments WHERE id = ?1", [batiment_id], |row| row.get(0))?;
+                if existe == 0 {
+                    return Err(AppError::not_found("Batiment", batiment_id));
+                }
+            }
+            _ => {
+                return Err(AppError::validation_error(
+                    "bande_id",
+                    "La série doit porter sur une bande ou sur un bâtiment",
+                ))
+            }
+        }
+        if lissage.is_some_and(|fenetre| fenetre == 0 || fenetre > LISSAGE_MAX) {
+            return Err(AppError::validation_error(
+                "lissage",
+                &format!("Le lissage doit être compris entre 1 et {} points", LISSAGE_MAX),
+            ));
+        }
+
+        let (unite, sql) = match metrique {
+            METRIQUE_MORTALITE => (
+                "sujets",
+                "SELECT date(bd.date_entree, '+' || (sq.age - 1) || ' days'),
+                        SUM(COALESCE(sq.deces_par_jour, 0) + COALESCE(sq.elimines_par_jour, 0))
+                 FROM suivi_quotidien sq
+                 JOIN semaines s ON sq.semaine_id = s.id
+                 JOIN batiments b ON s.batiment_id = b.id
+                 JOIN bandes bd ON b.bande_id = bd.id
+                 WHERE (?1 IS NULL OR b.bande_id = ?1) AND (?2 IS NULL OR b.id = ?2)
+                   AND (sq.deces_par_jour IS NOT NULL OR sq.elimines_par_jour IS NOT NULL)
+                 GROUP BY sq.age ORDER BY sq.age",
+            ),
+            METRIQUE_ALIMENTATION => (
+                "kg",
+                "SELECT date(bd.date_entree, '+' || (sq.age - 1) || ' days'), SUM(sq.alimentation_par_jour) * ?3
+                 FROM suivi_quotidien sq
+                 JOIN semaines s ON sq.semaine_id = s.id
+                 JOIN batiments b ON s.batiment_id = b.id
+                 JOIN bandes bd ON b.bande_id = bd.id
+                 WHERE (?1 IS NULL OR b.bande_id = ?1) AND (?2 IS NULL OR b.id = ?2)
+                   AND sq.alimentation_par_jour IS NOT NULL
+                 GROUP BY sq.age ORDER BY sq.age",
+            ),
+            METRIQUE_POIDS => (
+                "kg",
+                "SELECT date(bd.date_entree, '+' || (s.numero_semaine * 7 - 1) || ' days'), AVG(s.poids)
+                 FROM semaines s
+                 JOIN batiments b ON s.batiment_id = b.id
+                 JOIN bandes bd ON b.bande_id = bd.id
+                 WHERE (?1 IS NULL OR b.bande_id = ?1) AND (?2 IS NULL OR b.id = ?2)
+                   AND s.poids IS NOT NULL
+                 GROUP BY s.numero_semaine ORDER BY s.numero_semaine",
+            ),
+            METRIQUE_TEMPERATURE => (
+                "°C",
+                "SELECT substr(h.heure, 1, 10) AS jour, SUM(h.temperature_somme) / SUM(h.temperature_nombre)
+                 FROM mesures_capteurs_horaires h
+                 JOIN batiments b ON h.numero_batiment = b.numero_batiment
+                 JOIN bandes bd ON b.bande_id = bd.id AND h.ferme_id = bd.ferme_id
+                 WHERE (?1 IS NULL OR b.bande_id = ?1) AND (?2 IS NULL OR b.id = ?2)
+                   AND substr(h.heure, 1, 10) BETWEEN bd.date_entree
+                       AND date(bd.date_entree, '+' || (MAX(?4, COALESCE((
+                               SELECT MAX(numero_semaine) FROM semaines WHERE batiment_id = b.id
+                           ), 0)) * 7 - 1) || ' days')
+                 GROUP BY jour HAVING SUM(h.temperature_nombre) > 0 ORDER BY jour",
+            ),
+            _ => {
+                return Err(AppError::validation_error(
+                    "metrique",
+                    "Métrique non reconnue. Métriques valides: mortalite, alimentation, poids, temperature",
+                ))
+            }
+        };
+
+        let mut stmt = conn.prepare(sql)?;
+        let nombre_parametres = stmt.parameter_count();
+        let kg_par_sachet = ProgrammeAlimentationRepository::get_kg_par_sachet(&conn)?;
+        let valeurs: [&dyn ToSql; 4] = [&bande_id, &batiment_id, &kg_par_sachet, &SEMAINES_SUIVI];
+        let points = stmt
+            .query_map(&valeurs[..nombre_parametres], |row| Ok(PointSerie { x: row.get(0)?, y: row.get(1)? }))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SerieGraphique {
+            metrique: metrique.to_string(),
+            unite: unite.to_string(),
+            lissage,
+            points: match lissage {
+                Some(fenetre) => moyenne_mobile(&points, fenetre as usize),
+                None => points,
+            },
+        })
+    }
+}
+
+/// Moyenne mobile des `fenetre` derniers points (moins en début de série)
+fn moyenne_mobile(points: &[PointSerie], fenetre: usize) -> Vec<PointSerie> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let debut = (i + 1).saturating_sub(fenetre);
+            let valeurs = &points[debut..=i];
+            PointSerie {
+                x: point.x.clone(),
+                y: valeurs.iter().map(|p| p.y).sum::<f64>() / valeurs.len() as f64,
+            }
+        })
+        .collect()
+}
+
+/// Facteur sans mesure, qui ne retire aucun point
+fn non_mesurable(facteur: &str, description: &str) -> FacteurSante {
+    FacteurSante {
+        facteur: facteur.to_string(),
+        valeur: None,
+        points_perdus: 0.0,
+        description: description.to_string(),
+    }
+}
+
+/// Jours saisis d'un bâtiment (décès ou alimentation renseignés), par âge
+fn jours_suivi(conn: &Connection, batiment_id: i64) -> AppResult<Vec<JourSuivi>> {
+    let mut stmt = conn.prepare(
+        "SELECT sq.age, COALESCE(sq.deces_par_jour, 0) + COALESCE(sq.elimines_par_jour, 0), sq.alimentation_par_jour
+         FROM suivi_quotidien sq
+         JOIN semaines s ON sq.semaine_id = s.id
+         WHERE s.batiment_id = ?1
+           AND (sq.deces_par_jour IS NOT NULL OR sq.alimentation_par_jour IS NOT NULL)
+         ORDER BY sq.age",
+    )?;
+    let jours = stmt
+        .query_map([batiment_id], |row| {
+            Ok(JourSuivi { age: row.get(0)?, pertes: row.get(1)?, alimentation: row.get(2)? })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(jours)
+}
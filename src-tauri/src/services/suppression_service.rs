@@ -0,0 +1,158 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{DependanceSuppression, ImpactSuppression, ENTITES_SUPPRESSION};
+use rusqlite::OptionalExtension;
+use std::sync::Arc;
+
+/// Dépendance à compter: clé, libellé, requête de comptage (paramètre `?1` = ID) et caractère bloquant
+type Dependance = (&'static str, &'static str, &'static str, bool);
+
+const DEPENDANCES_FERME: [Dependance; 10] = [
+    ("bandes", "Bandes", "SELECT COUNT(*) FROM bandes WHERE ferme_id = ?1", true),
+    (
+        "batiments",
+        "Bâtiments",
+        "SELECT COUNT(*) FROM batiments b JOIN bandes bd ON b.bande_id = bd.id WHERE bd.ferme_id = ?1",
+        false,
+    ),
+    (
+        "suivis_quotidiens",
+        "Jours de suivi quotidien",
+        "SELECT COUNT(*) FROM suivi_quotidien sq
+         JOIN semaines s ON sq.semaine_id = s.id
+         JOIN batiments b ON s.batiment_id = b.id
+         JOIN bandes bd ON b.bande_id = bd.id
+         WHERE bd.ferme_id = ?1",
+        false,
+    ),
+    (
+        "livraisons_aliment",
+        "Livraisons d'aliment",
+        "SELECT COUNT(*) FROM alimentation_history ah JOIN bandes bd ON ah.bande_id = bd.id WHERE bd.ferme_id = ?1",
+        false,
+    ),
+    ("positions_batiments", "Positions de bâtiments sur le plan", "SELECT COUNT(*) FROM positions_batiments WHERE ferme_id = ?1", false),
+    ("vides_sanitaires", "Vides sanitaires", "SELECT COUNT(*) FROM vides_sanitaires WHERE ferme_id = ?1", false),
+    ("immobilisations", "Immobilisations", "SELECT COUNT(*) FROM immobilisations WHERE ferme_id = ?1", false),
+    ("equipements", "Équipements", "SELECT COUNT(*) FROM equipements WHERE ferme_id = ?1", false),
+    ("releves_meteo", "Relevés météo", "SELECT COUNT(*) FROM meteo_quotidienne WHERE ferme_id = ?1", false),
+    ("mesures_capteurs", "Mesures des capteurs", "SELECT COUNT(*) FROM mesures_capteurs WHERE ferme_id = ?1", false),
+];
+
+const DEPENDANCES_PERSONNEL: [Dependance; 3] = [
+    ("batiments", "Bâtiments suivis", "SELECT COUNT(*) FROM batiments WHERE personnel_id = ?1", true),
+    (
+        "bandes",
+        "Bandes concernées",
+        "SELECT COUNT(DISTINCT bande_id) FROM batiments WHERE personnel_id = ?1",
+        false,
+    ),
+    (
+        "suivis_quotidiens",
+        "Jours de suivi quotidien",
+        "SELECT COUNT(*) FROM suivi_quotidien sq
+         JOIN semaines s ON sq.semaine_id = s.id
+         JOIN batiments b ON s.batiment_id = b.id
+         WHERE b.personnel_id = ?1",
+        false,
+    ),
+];
+
+const DEPENDANCES_SOIN: [Dependance; 4] = [
+    ("administrations", "Administrations", "SELECT COUNT(*) FROM suivi_soins WHERE soin_id = ?1", true),
+    (
+        "suivis_quotidiens",
+        "Jours de suivi quotidien",
+        "SELECT COUNT(DISTINCT suivi_id) FROM suivi_soins WHERE soin_id = ?1",
+        false,
+    ),
+    (
+        "bandes",
+        "Bandes concernées",
+        "SELECT COUNT(DISTINCT b.bande_id) FROM suivi_soins ss
+         JOIN suivi_quotidien sq ON ss.suivi_id = sq.id
+         JOIN semaines s ON sq.semaine_id = s.id
+         JOIN batiments b ON s.batiment_id = b.id
+         WHERE ss.soin_id = ?1",
+        false,
+    ),
+    ("plan_soins", "Étapes du plan de soins", "SELECT COUNT(*) FROM plan_soins WHERE soin_id = ?1", false),
+];
+
+const DEPENDANCES_POUSSIN: [Dependance; 4] = [
+    ("batiments", "Bâtiments", "SELECT COUNT(*) FROM batiments WHERE poussin_id = ?1", true),
+    (
+        "phases_alimentation",
+        "Phases du programme d'alimentation",
+        "SELECT COUNT(*) FROM phases_alimentation WHERE poussin_id = ?1",
+        false,
+    ),
+    ("plan_soins", "Étapes du plan de soins", "SELECT COUNT(*) FROM plan_soins WHERE poussin_id = ?1", false),
+    ("historique_prix", "Prix enregistrés", "SELECT COUNT(*) FROM historique_prix WHERE poussin_id = ?1", false),
+];
+
+/// Aperçu des suppressions
+///
+/// Les suppressions de fermes, de personnel, de soins et de types de poussin
+/// sont refusées tant que des données en dépendent: ce service détaille ces
+/// données pour que la confirmation affichée à l'utilisateur soit explicite.
+pub struct SuppressionService {
+    db: Arc<dyn Storage>,
+}
+
+impl SuppressionService {
+    /// Crée une nouvelle instance du service
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Détaille les lignes qui dépendent d'une entité avant sa suppression
+    ///
+    /// # Arguments
+    /// * `entity` - Le type d'entité: "ferme", "personnel", "soin" ou "poussin"
+    /// * `id` - L'ID de l'entité
+    ///
+    /// # Returns
+    /// Les dépendances de l'entité et si sa suppression est possible
+    pub async fn get_delete_impact(&self, entity: &str, id: i64) -> AppResult<ImpactSuppression> {
+        let entity = entity.trim().to_lowercase();
+        let (table, libelle_entite, dependances): (&str, &str, &[Dependance]) = match entity.as_str() {
+            "ferme" => ("fermes", "Ferme", &DEPENDANCES_FERME),
+            "personnel" => ("personnel", "Personnel", &DEPENDANCES_PERSONNEL),
+            "soin" => ("soins", "Soin", &DEPENDANCES_SOIN),
+            "poussin" => ("poussins", "Poussin", &DEPENDANCES_POUSSIN),
+            _ => {
+                return Err(AppError::validation_error(
+                    "entity",
+                    &format!("Entité non reconnue. Entités valides: {}", ENTITES_SUPPRESSION.join(", ")),
+                ))
+            }
+        };
+
+        let conn = self.db.get_connection()?;
+        let nom: String = conn
+            .query_row(&format!("SELECT nom FROM {} WHERE id = ?1", table), [id], |row| row.get(0))
+            .optional()?
+            .ok_or_else(|| AppError::not_found(libelle_entite, id))?;
+
+        let dependances = dependances
+            .iter()
+            .map(|(cle, libelle, sql, bloquante)| {
+                let nombre: i64 = conn.query_row(sql, [id], |row| row.get(0))?;
+                Ok(DependanceSuppression {
+                    cle: cle.to_string(),
+                    libelle: libelle.to_string(),
+                    nombre,
+                    bloquante: *bloquante,
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let suppression_possible = dependances.iter().all(|d| !d.bloquante || d.nombre == 0);
+
+        Ok(ImpactSuppression { entity, id, nom, dependances, suppression_possible })
+    }
+}
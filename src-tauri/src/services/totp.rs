@@ -0,0 +1,140 @@
+use crate::error::{AppError, AppResult};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+/// Durée de validité d'un code, en secondes
+pub const TOTP_PERIODE: i64 = 30;
+
+/// Nombre de chiffres d'un code
+pub const TOTP_CHIFFRES: u32 = 6;
+
+/// Nom de l'application affiché par les applications d'authentification
+pub const TOTP_EMETTEUR: &str = "Geema";
+
+/// Génère un secret TOTP aléatoire de 160 bits, encodé en base32
+pub fn generer_secret() -> String {
+    BASE32_NOPAD.encode(&rand::random::<[u8; 20]>())
+}
+
+/// URI `otpauth://` à afficher sous forme de QR code pour l'enrôlement
+///
+/// # Arguments
+/// * `secret` - Le secret en base32
+/// * `compte` - Le nom du compte affiché dans l'application d'authentification
+pub fn uri_provisioning(secret: &str, compte: &str) -> String {
+    format!(
+        "otpauth://totp/{emetteur}:{compte}?secret={secret}&issuer={emetteur}&algorithm=SHA1&digits={chiffres}&period={periode}",
+        emetteur = TOTP_EMETTEUR,
+        compte = encoder_uri(compte),
+        secret = secret,
+        chiffres = TOTP_CHIFFRES,
+        periode = TOTP_PERIODE,
+    )
+}
+
+/// Calcule le code d'un pas de temps (RFC 6238, HMAC-SHA1)
+///
+/// # Arguments
+/// * `secret` - Le secret en base32
+/// * `pas` - Le numéro du pas de temps (`timestamp / TOTP_PERIODE`)
+pub fn code(secret: &str, pas: i64) -> AppResult<String> {
+    let cle = BASE32_NOPAD
+        .decode(secret.as_bytes())
+        .map_err(|_| AppError::business_logic("Secret TOTP invalide"))?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&cle)
+        .map_err(|_| AppError::business_logic("Secret TOTP invalide"))?;
+    mac.update(&pas.to_be_bytes());
+    let empreinte = mac.finalize().into_bytes();
+
+    // Troncature dynamique (RFC 4226, section 5.3)
+    let decalage = (empreinte[empreinte.len() - 1] & 0x0f) as usize;
+    let valeur = u32::from_be_bytes([
+        empreinte[decalage] & 0x7f,
+        empreinte[decalage + 1],
+        empreinte[decalage + 2],
+        empreinte[decalage + 3],
+    ]);
+    Ok(format!("{:0width$}", valeur % 10u32.pow(TOTP_CHIFFRES), width = TOTP_CHIFFRES as usize))
+}
+
+/// Vérifie un code saisi par l'utilisateur
+///
+/// Les codes du pas précédent et du suivant sont acceptés pour tolérer un
+/// léger décalage d'horloge du téléphone.
+///
+/// # Arguments
+/// * `secret` - Le secret en base32
+/// * `saisi` - Le code saisi (les espaces sont ignorés)
+/// * `timestamp` - L'heure de la vérification, en secondes Unix
+///
+/// # Returns
+/// Le pas de temps du code s'il est valide, pour refuser sa réutilisation
+pub fn verifier(secret: &str, saisi: &str, timestamp: i64) -> AppResult<Option<i64>> {
+    let saisi: String = saisi.chars().filter(|c| !c.is_whitespace()).collect();
+    let pas_actuel = timestamp / TOTP_PERIODE;
+    // Les trois pas sont toujours comparés, pour ne rien révéler par la durée
+    let mut valide = None;
+    for pas in [pas_actuel - 1, pas_actuel, pas_actuel + 1] {
+        if egaux(code(secret, pas)?.as_bytes(), saisi.as_bytes()) && valide.is_none() {
+            valide = Some(pas);
+        }
+    }
+    Ok(valide)
+}
+
+/// Compare deux codes en temps constant (indépendant de la position de la première différence)
+fn egaux(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |difference, (x, y)| difference | (x ^ y)) == 0
+}
+
+fn encoder_uri(texte: &str) -> String {
+    texte
+        .bytes()
+        .map(|octet| match octet {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (octet as char).to_string(),
+            _ => format!("%{:02X}", octet),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vecteurs de test SHA1 de la RFC 6238 (annexe B), tronqués à 6 chiffres
+    #[test]
+    fn codes_match_rfc_6238_test_vectors() {
+        let secret = BASE32_NOPAD.encode(b"12345678901234567890");
+        assert_eq!(code(&secret, 59 / TOTP_PERIODE).unwrap(), "287082");
+        assert_eq!(code(&secret, 1111111109 / TOTP_PERIODE).unwrap(), "081804");
+        assert_eq!(code(&secret, 1234567890 / TOTP_PERIODE).unwrap(), "005924");
+    }
+
+    #[test]
+    fn verification_tolerates_one_step_of_clock_drift() {
+        let secret = generer_secret();
+        let maintenant = 1_700_000_000;
+        let precedent = code(&secret, maintenant / TOTP_PERIODE - 1).unwrap();
+        assert_eq!(verifier(&secret, &precedent, maintenant).unwrap(), Some(maintenant / TOTP_PERIODE - 1));
+
+        let trop_ancien = code(&secret, maintenant / TOTP_PERIODE - 2).unwrap();
+        assert_eq!(verifier(&secret, &trop_ancien, maintenant).unwrap(), None);
+    }
+
+    #[test]
+    fn codes_are_compared_byte_for_byte() {
+        assert!(egaux(b"123456", b"123456"));
+        assert!(!egaux(b"123456", b"123457"));
+        assert!(!egaux(b"123456", b"12345"));
+    }
+
+    #[test]
+    fn provisioning_uri_encodes_the_account_name() {
+        let uri = uri_provisioning("ABC", "chef équipe");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Geema:chef%20%C3%A9quipe?secret=ABC&issuer=Geema&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
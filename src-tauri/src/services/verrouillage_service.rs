@@ -0,0 +1,93 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{DeverrouillagePeriode, FenetreSaisie, AUDIT_DEVERROUILLAGE_PERIODE, AUDIT_ENTITE_BANDE};
+use crate::repositories::{AuditRepository, BandeRepository, VerrouillageRepository};
+use crate::services::AuthService;
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+/// Service de verrouillage des saisies anciennes
+///
+/// Passé la fenêtre de saisie, les jours de suivi et les semaines terminées
+/// ne sont plus modifiables: une correction demande qu'un administrateur
+/// déverrouille la période, ce qui est enregistré dans le journal d'audit.
+pub struct VerrouillageService {
+    db: Arc<dyn Storage>,
+}
+
+impl VerrouillageService {
+    /// Crée une nouvelle instance du service de verrouillage
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self { db }
+    }
+
+    /// Récupère la fenêtre de saisie
+    pub async fn get_edit_window(&self) -> AppResult<FenetreSaisie> {
+        let conn = self.db.get_connection()?;
+        VerrouillageRepository::get_fenetre(&conn)
+    }
+
+    /// Modifie la fenêtre de saisie (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `jours` - Le nombre de jours modifiables, `None` pour désactiver le verrouillage
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// La fenêtre enregistrée
+    pub async fn set_edit_window(&self, jours: Option<i32>, token: &str) -> AppResult<FenetreSaisie> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        if jours.is_some_and(|jours| jours < 1) {
+            return Err(AppError::validation_error("jours", "La fenêtre de saisie doit être d'au moins un jour"));
+        }
+        self.db.write(|tx| VerrouillageRepository::set_fenetre(tx, jours))
+    }
+
+    /// Déverrouille une période d'une bande (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `bande_id` - L'ID de la bande
+    /// * `date_debut` - Le premier jour déverrouillé
+    /// * `date_fin` - Le dernier jour déverrouillé
+    /// * `motif` - La raison de la correction, enregistrée dans le journal d'audit
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// Le déverrouillage, valable `DUREE_DEVERROUILLAGE_HEURES` heures
+    pub async fn unlock_period(
+        &self,
+        bande_id: i64,
+        date_debut: NaiveDate,
+        date_fin: NaiveDate,
+        motif: Option<&str>,
+        token: &str,
+    ) -> AppResult<DeverrouillagePeriode> {
+        let admin = AuthService::new(self.db.clone()).require_admin(token).await?;
+        if date_fin < date_debut {
+            return Err(AppError::validation_error("date_fin", "La fin de la période doit suivre son début"));
+        }
+
+        self.db.write(|tx| {
+            BandeRepository::get_statut(tx, bande_id)?;
+            let deverrouillage = VerrouillageRepository::unlock(
+                tx,
+                bande_id,
+                &date_debut.to_string(),
+                &date_fin.to_string(),
+                admin.id,
+            )?;
+
+            let periode = format!("{} au {}", date_debut, date_fin);
+            let details = match motif.map(str::trim).filter(|motif| !motif.is_empty()) {
+                Some(motif) => format!("{}: {}", periode, motif),
+                None => periode,
+            };
+            AuditRepository::log(tx, admin.id, AUDIT_DEVERROUILLAGE_PERIODE, AUDIT_ENTITE_BANDE, bande_id, Some(&details))?;
+
+            Ok(deverrouillage)
+        })
+    }
+}
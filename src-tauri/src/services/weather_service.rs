@@ -0,0 +1,254 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{MeteoJour, MortaliteMeteoJour, SynchronisationMeteo};
+use crate::repositories::MeteoRepository;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Nombre de jours synchronisés par défaut
+const JOURS_SYNCHRONISATION_DEFAUT: u32 = 7;
+
+/// Historique maximal proposé par l'API de prévision d'Open-Meteo
+const JOURS_SYNCHRONISATION_MAX: u32 = 92;
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// Relevé journalier renvoyé par une source météo
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleveMeteo {
+    pub date: String, // YYYY-MM-DD
+    pub temperature_min: Option<f64>,
+    pub temperature_max: Option<f64>,
+    pub temperature_moyenne: Option<f64>,
+    pub humidite_moyenne: Option<f64>,
+}
+
+/// Source des relevés météo journaliers
+///
+/// Les appels sont bloquants: le service les exécute hors du runtime async.
+pub trait SourceMeteo: Send + Sync {
+    /// Relevés des `jours` jours précédant aujourd'hui aux coordonnées données
+    fn releves_journaliers(&self, latitude: f64, longitude: f64, jours: u32) -> AppResult<Vec<ReleveMeteo>>;
+}
+
+/// Source météo Open-Meteo (gratuite, sans clé d'API)
+pub struct OpenMeteo;
+
+#[derive(Debug, Deserialize)]
+struct ReponseOpenMeteo {
+    daily: JoursOpenMeteo,
+}
+
+#[derive(Debug, Deserialize)]
+struct JoursOpenMeteo {
+    time: Vec<String>,
+    #[serde(default)]
+    temperature_2m_min: Vec<Option<f64>>,
+    #[serde(default)]
+    temperature_2m_max: Vec<Option<f64>>,
+    #[serde(default)]
+    temperature_2m_mean: Vec<Option<f64>>,
+    #[serde(default)]
+    relative_humidity_2m_mean: Vec<Option<f64>>,
+}
+
+impl JoursOpenMeteo {
+    fn into_releves(self) -> Vec<ReleveMeteo> {
+        let valeur = |serie: &[Option<f64>], i: usize| serie.get(i).copied().flatten();
+        self.time
+            .iter()
+            .enumerate()
+            .map(|(i, date)| ReleveMeteo {
+                date: date.clone(),
+                temperature_min: valeur(&self.temperature_2m_min, i),
+                temperature_max: valeur(&self.temperature_2m_max, i),
+                temperature_moyenne: valeur(&self.temperature_2m_mean, i),
+                humidite_moyenne: valeur(&self.relative_humidity_2m_mean, i),
+            })
+            .collect()
+    }
+}
+
+impl SourceMeteo for OpenMeteo {
+    fn releves_journaliers(&self, latitude: f64, longitude: f64, jours: u32) -> AppResult<Vec<ReleveMeteo>> {
+        let reponse: ReponseOpenMeteo = ureq::get(OPEN_METEO_URL)
+            .timeout(Duration::from_secs(15))
+            .query("latitude", &latitude.to_string())
+            .query("longitude", &longitude.to_string())
+            .query(
+                "daily",
+                "temperature_2m_min,temperature_2m_max,temperature_2m_mean,relative_humidity_2m_mean",
+            )
+            .query("past_days", &jours.to_string())
+            .query("forecast_days", "0")
+            .query("timezone", "auto")
+            .call()
+            .map_err(|e| AppError::business_logic(&format!("Service météo indisponible: {}", e)))?
+            .into_json()
+            .map_err(|e| AppError::business_logic(&format!("Réponse du service météo illisible: {}", e)))?;
+
+        Ok(reponse.daily.into_releves())
+    }
+}
+
+/// Service de relevés météo des fermes
+///
+/// Récupère, lorsque l'application est en ligne, la température et l'humidité
+/// journalières aux coordonnées de chaque ferme, afin de rapprocher les pics
+/// de mortalité des vagues de chaleur.
+pub struct WeatherService {
+    db: Arc<dyn Storage>,
+    source: Arc<dyn SourceMeteo>,
+}
+
+impl WeatherService {
+    /// Crée une nouvelle instance du service météo (source Open-Meteo)
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self::with_source(db, Arc::new(OpenMeteo))
+    }
+
+    /// Crée une instance du service météo avec une autre source de relevés
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    /// * `source` - La source des relevés
+    pub fn with_source(db: Arc<dyn Storage>, source: Arc<dyn SourceMeteo>) -> Self {
+        Self { db, source }
+    }
+
+    /// Synchronise les relevés météo de toutes les fermes géolocalisées
+    ///
+    /// Une ferme sans coordonnées ou dont la récupération échoue (hors ligne)
+    /// est signalée dans le résultat sans interrompre les autres. Les jours
+    /// déjà enregistrés sont mis à jour.
+    ///
+    /// # Arguments
+    /// * `jours` - Nombre de jours passés à récupérer (7 par défaut, 92 au plus)
+    ///
+    /// # Returns
+    /// Le résultat de la synchronisation de chaque ferme
+    pub async fn sync_weather(&self, jours: Option<u32>) -> AppResult<Vec<SynchronisationMeteo>> {
+        let jours = jours.unwrap_or(JOURS_SYNCHRONISATION_DEFAUT);
+        if !(1..=JOURS_SYNCHRONISATION_MAX).contains(&jours) {
+            return Err(AppError::validation_error(
+                "jours",
+                &format!("Le nombre de jours doit être compris entre 1 et {}", JOURS_SYNCHRONISATION_MAX),
+            ));
+        }
+
+        let fermes = {
+            let conn = self.db.get_connection()?;
+            MeteoRepository::get_fermes_geolocalisees(&conn)?
+        };
+
+        let mut resultats = Vec::with_capacity(fermes.len());
+        for (ferme_id, ferme_nom, latitude, longitude) in fermes {
+            let (jours_enregistres, erreur) = match (latitude, longitude) {
+                (Some(latitude), Some(longitude)) => match self.synchroniser_ferme(ferme_id, latitude, longitude, jours).await {
+                    Ok(nombre) => (nombre, None),
+                    Err(e) => (0, Some(e.to_string())),
+                },
+                _ => (0, Some("Coordonnées de la ferme non renseignées".to_string())),
+            };
+            resultats.push(SynchronisationMeteo { ferme_id, ferme_nom, jours_enregistres, erreur });
+        }
+
+        Ok(resultats)
+    }
+
+    /// Récupère les relevés météo enregistrés d'une ferme
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `date_from` - Premier jour inclus ("YYYY-MM-DD")
+    /// * `date_to` - Dernier jour inclus ("YYYY-MM-DD")
+    pub async fn get_weather_by_ferme(
+        &self,
+        ferme_id: i64,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> AppResult<Vec<MeteoJour>> {
+        let conn = self.db.get_connection()?;
+        MeteoRepository::get_by_ferme(&conn, ferme_id, date_from.as_deref(), date_to.as_deref())
+    }
+
+    /// Rapproche la mortalité journalière d'une ferme de sa météo
+    ///
+    /// # Arguments
+    /// * `ferme_id` - L'ID de la ferme
+    /// * `date_from` - Premier jour inclus ("YYYY-MM-DD")
+    /// * `date_to` - Dernier jour inclus ("YYYY-MM-DD")
+    ///
+    /// # Returns
+    /// Les décès de chaque jour suivi, avec la température maximale, l'humidité
+    /// et l'indicateur de vague de chaleur lorsque la météo du jour est connue
+    pub async fn get_mortalite_meteo(
+        &self,
+        ferme_id: i64,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> AppResult<Vec<MortaliteMeteoJour>> {
+        let conn = self.db.get_connection()?;
+        MeteoRepository::get_mortalite_meteo(&conn, ferme_id, date_from.as_deref(), date_to.as_deref())
+    }
+
+    /// Récupère et enregistre les relevés d'une ferme, retourne le nombre de jours enregistrés
+    async fn synchroniser_ferme(&self, ferme_id: i64, latitude: f64, longitude: f64, jours: u32) -> AppResult<i64> {
+        let source = self.source.clone();
+        let releves = tokio::task::spawn_blocking(move || source.releves_journaliers(latitude, longitude, jours))
+            .await
+            .map_err(|e| AppError::business_logic(&format!("Synchronisation météo interrompue: {}", e)))??;
+
+        self.db.write(|tx| {
+            for releve in &releves {
+                MeteoRepository::upsert(
+                    tx,
+                    &MeteoJour {
+                        ferme_id,
+                        date: releve.date.clone(),
+                        temperature_min: releve.temperature_min,
+                        temperature_max: releve.temperature_max,
+                        temperature_moyenne: releve.temperature_moyenne,
+                        humidite_moyenne: releve.humidite_moyenne,
+                    },
+                )?;
+            }
+            Ok(())
+        })?;
+
+        Ok(releves.len() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_meteo_daily_response_is_parsed() {
+        let reponse: ReponseOpenMeteo = serde_json::from_str(
+            r#"{
+                "latitude": 33.56, "longitude": -7.6,
+                "daily": {
+                    "time": ["2024-07-01", "2024-07-02"],
+                    "temperature_2m_min": [21.4, null],
+                    "temperature_2m_max": [34.2, 29.0],
+                    "temperature_2m_mean": [27.5, 25.1],
+                    "relative_humidity_2m_mean": [61.0, 70.0]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let releves = reponse.daily.into_releves();
+        assert_eq!(releves.len(), 2);
+        assert_eq!(releves[0].temperature_max, Some(34.2));
+        assert_eq!(releves[1].date, "2024-07-02");
+        assert_eq!(releves[1].temperature_min, None);
+        assert_eq!(releves[1].humidite_moyenne, Some(70.0));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use crate::models::{EvenementSortant, RapportEnvoiWebhook, PARAMETRE_WEBHOOK_URL};
+use crate::repositories::{ParametreRepository, WebhookRepository};
+use crate::services::AuthService;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Intervalle d'envoi des événements en attente par la tâche de fond
+pub const INTERVALLE_ENVOI_WEBHOOK: Duration = Duration::from_secs(60);
+
+/// Nombre d'événements envoyés au plus à chaque passage
+const EVENEMENTS_PAR_ENVOI: i64 = 50;
+
+/// Nombre d'événements renvoyés par `get_webhook_outbox`
+const HISTORIQUE_OUTBOX_MAX: i64 = 200;
+
+/// Client HTTP chargé de poster un événement au webhook
+///
+/// Les appels sont bloquants: le service les exécute hors du runtime async.
+pub trait ClientWebhook: Send + Sync {
+    /// Poste le corps JSON à l'URL; une réponse autre que 2xx est une erreur
+    fn poster(&self, url: &str, corps: &str) -> AppResult<()>;
+}
+
+/// Client webhook HTTP(S)
+pub struct ClientHttp;
+
+impl ClientWebhook for ClientHttp {
+    fn poster(&self, url: &str, corps: &str) -> AppResult<()> {
+        ureq::post(url)
+            .timeout(Duration::from_secs(15))
+            .set("Content-Type", "application/json")
+            .send_string(corps)
+            .map_err(|e| AppError::business_logic(&format!("Webhook injoignable: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Service de notification du système de la coopérative
+///
+/// Les événements importants (bande clôturée, alerte critique) sont mis en
+/// file dans la base, dans la transaction qui les produit, puis postés au
+/// webhook configuré par une tâche de fond. Hors ligne, les envois échouent
+/// et sont retentés plus tard: aucun événement n'est perdu.
+pub struct WebhookService {
+    db: Arc<dyn Storage>,
+    client: Arc<dyn ClientWebhook>,
+}
+
+impl WebhookService {
+    /// Crée une nouvelle instance du service de notification (client HTTP)
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    pub fn new(db: Arc<dyn Storage>) -> Self {
+        Self::with_client(db, Arc::new(ClientHttp))
+    }
+
+    /// Crée une instance du service de notification avec un autre client
+    ///
+    /// # Arguments
+    /// * `db` - Le gestionnaire de base de données partagé
+    /// * `client` - Le client qui poste les événements
+    pub fn with_client(db: Arc<dyn Storage>, client: Arc<dyn ClientWebhook>) -> Self {
+        Self { db, client }
+    }
+
+    /// Récupère l'URL du webhook (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// L'URL configurée, `None` si les notifications sont désactivées
+    pub async fn get_webhook_url(&self, token: &str) -> AppResult<Option<String>> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        let conn = self.db.get_connection()?;
+        WebhookRepository::get_url(&conn)
+    }
+
+    /// Configure l'URL du webhook (réservé aux administrateurs)
+    ///
+    /// Sans URL, les nouveaux événements ne sont plus mis en file; ceux déjà
+    /// en file restent en attente.
+    ///
+    /// # Arguments
+    /// * `url` - L'URL http(s) du webhook, `None` ou vide pour désactiver les notifications
+    /// * `token` - Le token de session d'un administrateur
+    pub async fn set_webhook_url(&self, url: Option<String>, token: &str) -> AppResult<Option<String>> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        let url = url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+        if let Some(url) = &url {
+            if !(url.starts_with("https://") || url.starts_with("http://")) || url.contains(char::is_whitespace) {
+                return Err(AppError::validation_error("url", "L'URL du webhook doit commencer par http:// ou https://"));
+            }
+        }
+        self.db.write(|tx| {
+            ParametreRepository::set(tx, PARAMETRE_WEBHOOK_URL, url.as_deref().unwrap_or(""))?;
+            WebhookRepository::get_url(tx)
+        })
+    }
+
+    /// Liste les derniers événements de la file, envoyés ou non (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// Au plus `HISTORIQUE_OUTBOX_MAX` événements, du plus récent au plus ancien
+    pub async fn get_webhook_outbox(&self, token: &str) -> AppResult<Vec<EvenementSortant>> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        let conn = self.db.get_connection()?;
+        WebhookRepository::get_recent(&conn, HISTORIQUE_OUTBOX_MAX)
+    }
+
+    /// Relance l'envoi d'un événement non envoyé, même abandonné (réservé aux administrateurs)
+    ///
+    /// # Arguments
+    /// * `id` - L'ID de l'événement
+    /// * `token` - Le token de session d'un administrateur
+    ///
+    /// # Returns
+    /// L'événement, à envoyer au prochain passage
+    pub async fn retry_webhook_event(&self, id: i64, token: &str) -> AppResult<EvenementSortant> {
+        AuthService::new(self.db.clone()).require_admin(token).await?;
+        self.db.write(|tx| WebhookRepository::retry(tx, id))
+    }
+
+    /// Poste au webhook les événements arrivés à échéance
+    ///
+    /// Un événement refusé ou non remis est reprogrammé selon le délai de
+    /// `WebhookRepository::mark_failed`.
+    ///
+    /// # Returns
+    /// Le nombre d'événements envoyés et en échec
+    pub async fn deliver_pending(&self) -> AppResult<RapportEnvoiWebhook> {
+        let (url, evenements) = {
+            let conn = self.db.get_connection()?;
+            match WebhookRepository::get_url(&conn)? {
+                Some(url) => (url, WebhookRepository::get_due(&conn, EVENEMENTS_PAR_ENVOI)?),
+                None => return Ok(RapportEnvoiWebhook::default()),
+            }
+        };
+
+        let mut rapport = RapportEnvoiWebhook::default();
+        for evenement in evenements {
+            let client = self.client.clone();
+            let (url, corps) = (url.clone(), evenement.contenu.clone());
+            let resultat = tokio::task::spawn_blocking(move || client.poster(&url, &corps))
+                .await
+                .map_err(|e| AppError::business_logic(&format!("Envoi au webhook interrompu: {}", e)))?;
+            match resultat {
+                Ok(()) => {
+                    self.db.write(|tx| WebhookRepository::mark_sent(tx, evenement.id))?;
+                    rapport.envoyes += 1;
+                }
+                Err(e) => {
+                    self.db.write(|tx| WebhookRepository::mark_failed(tx, &evenement, &e.to_string()))?;
+                    rapport.echecs += 1;
+                }
+            }
+        }
+        Ok(rapport)
+    }
+
+    /// Tâche de fond: envoie les événements en attente
+    ///
+    /// # Arguments
+    /// * `intervalle` - Délai entre deux passages
+    pub async fn run_webhook_delivery(self, intervalle: Duration) {
+        let mut horloge = tokio::time::interval(intervalle);
+        loop {
+            horloge.tick().await;
+            // Hors ligne ou base occupée: les événements seront retentés au prochain tour
+            let _ = self.deliver_pending().await;
+        }
+    }
+}
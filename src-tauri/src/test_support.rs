@@ -0,0 +1,92 @@
+/// Outils de test partagés par les tests unitaires des services
+///
+/// Fournit un `Storage` en mémoire (aucun fichier SQLite n'est créé) et des
+/// fonctions de création de données de test (fixtures).
+
+use crate::database::Storage;
+use crate::error::{AppError, AppResult};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OpenFlags;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Stockage SQLite en mémoire pour les tests
+///
+/// Chaque instance utilise une base nommée unique en cache partagé, de sorte
+/// que toutes les connexions du pool voient les mêmes données tout en restant
+/// isolées des autres tests exécutés en parallèle.
+pub struct MemoryStorage {
+    pool: Pool<SqliteConnectionManager>,
+}
+
+impl MemoryStorage {
+    /// Crée un stockage en mémoire avec le schéma complet de l'application
+    pub fn new() -> Arc<Self> {
+        let uri = format!("file:memdb-{}?mode=memory&cache=shared", Uuid::new_v4());
+        let manager = SqliteConnectionManager::file(uri)
+            .with_flags(
+                OpenFlags::SQLITE_OPEN_READ_WRITE
+                    | OpenFlags::SQLITE_OPEN_CREATE
+                    | OpenFlags::SQLITE_OPEN_URI,
+            )
+            .with_init(|conn| conn.execute_batch("PRAGMA foreign_keys = ON;"));
+
+        // Une connexion reste toujours ouverte: la base en mémoire disparaît
+        // dès que la dernière connexion est fermée
+        let pool = Pool::builder()
+            .max_size(4)
+            .min_idle(Some(1))
+            .build(manager)
+            .expect("Impossible de créer le pool en mémoire");
+
+        let storage = Arc::new(Self { pool });
+        storage.initialize_schema().expect("Impossible d'initialiser le schéma de test");
+        storage
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn get_connection(&self) -> AppResult<PooledConnection<SqliteConnectionManager>> {
+        self.pool.get().map_err(AppError::from)
+    }
+}
+
+/// Insère une ferme et retourne son ID
+pub fn seed_ferme(storage: &dyn Storage, nom: &str, nbr_meuble: i32) -> i64 {
+    let conn = storage.get_connection().unwrap();
+    conn.execute(
+        "INSERT INTO fermes (nom, nom_normalise, nbr_meuble) VALUES (?1, ?2, ?3)",
+        rusqlite::params![nom, crate::database::normaliser_nom(nom), nbr_meuble],
+    ).unwrap();
+    conn.last_insert_rowid()
+}
+
+/// Insère une bande pour une ferme et retourne son ID
+pub fn seed_bande(storage: &dyn Storage, ferme_id: i64, numero_bande: i32, date_entree: &str) -> i64 {
+    let conn = storage.get_connection().unwrap();
+    conn.execute(
+        "INSERT INTO bandes (numero_bande, date_entree, ferme_id) VALUES (?1, ?2, ?3)",
+        rusqlite::params![numero_bande, date_entree, ferme_id],
+    ).unwrap();
+    conn.last_insert_rowid()
+}
+
+/// Insère un bâtiment (avec son personnel et son poussin) et retourne son ID
+pub fn seed_batiment(storage: &dyn Storage, bande_id: i64, numero_batiment: &str, quantite: i32) -> i64 {
+    let conn = storage.get_connection().unwrap();
+    conn.execute(
+        "INSERT OR IGNORE INTO personnel (nom, telephone) VALUES ('Technicien test', '0600000000')",
+        [],
+    ).unwrap();
+    conn.execute("INSERT OR IGNORE INTO poussins (nom) VALUES ('Cobb 500')", []).unwrap();
+    conn.execute(
+        "INSERT INTO batiments (bande_id, numero_batiment, poussin_id, personnel_id, quantite)
+         VALUES (?1, ?2,
+                 (SELECT id FROM poussins WHERE nom = 'Cobb 500'),
+                 (SELECT id FROM personnel WHERE nom = 'Technicien test'),
+                 ?3)",
+        rusqlite::params![bande_id, numero_batiment, quantite],
+    ).unwrap();
+    conn.last_insert_rowid()
+}
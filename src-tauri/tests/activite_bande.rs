@@ -0,0 +1,71 @@
+//! Fil d'activité d'une bande
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::{
+    CreateAlimentationHistory, CreateAnalyse, CreateMaladie, CreateNoteBatiment,
+    ACTIVITE_ANALYSE, ACTIVITE_LIVRAISON_ALIMENT,
+};
+use tauri_app_lib::repositories::{
+    ActiviteRepository, AlimentationRepository, AnalyseRepository, AnalyseRepositoryTrait,
+    BatimentRepository, MaladieRepository, MaladieRepositoryTrait, NoteBatimentRepository,
+};
+
+#[tokio::test]
+async fn feed_merges_sources_most_recent_first() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    AlimentationRepository::create(&conn, &CreateAlimentationHistory {
+        bande_id: fixtures.bande_id,
+        quantite: 1500.0,
+        created_at: "2024-03-01 08:00:00".to_string(),
+        type_aliment: Some("démarrage".to_string()),
+        ..Default::default()
+    }).unwrap();
+    // Les corrections négatives ne sont pas des livraisons
+    AlimentationRepository::create(&conn, &CreateAlimentationHistory {
+        bande_id: fixtures.bande_id,
+        quantite: -50.0,
+        created_at: "2024-03-02 08:00:00".to_string(),
+        ..Default::default()
+    }).unwrap();
+
+    AnalyseRepository::new(test_db.storage()).create(CreateAnalyse {
+        batiment_id: fixtures.batiment_ids[1],
+        type_analyse: "eau".to_string(),
+        date_prelevement: "2024-03-10".to_string(),
+        resultat: Some("Conforme".to_string()),
+        ..Default::default()
+    }).await.unwrap();
+
+    let maladie = MaladieRepository::new(test_db.storage())
+        .create(CreateMaladie { nom: "Colibacillose".to_string() })
+        .await
+        .unwrap();
+    BatimentRepository::add_maladie_to_batiment(&conn, fixtures.batiment_ids[0], maladie.id).unwrap();
+    NoteBatimentRepository::create(&conn, &CreateNoteBatiment {
+        batiment_id: fixtures.batiment_ids[0],
+        auteur: "Technicien".to_string(),
+        contenu: "Ventilation réglée".to_string(),
+        tag: None,
+    }).unwrap();
+
+    let page = ActiviteRepository::get_by_bande(&conn, fixtures.bande_id, 1, 3).unwrap();
+    assert_eq!(page.total, 4);
+    assert_eq!(page.total_pages, 2);
+    assert!(page.has_next);
+
+    let derniere = ActiviteRepository::get_by_bande(&conn, fixtures.bande_id, 2, 3).unwrap();
+    assert_eq!(derniere.data.len(), 1);
+    assert_eq!(derniere.data[0].type_activite, ACTIVITE_LIVRAISON_ALIMENT);
+    assert_eq!(derniere.data[0].description, "1500 kg démarrage");
+
+    let analyse = page.data.iter().find(|a| a.type_activite == ACTIVITE_ANALYSE).unwrap();
+    assert_eq!(analyse.numero_batiment.as_deref(), Some("2"));
+    assert_eq!(analyse.description, "eau: Conforme");
+
+    assert!(ActiviteRepository::get_by_bande(&conn, fixtures.bande_id + 1000, 1, 10).is_err());
+}
@@ -0,0 +1,320 @@
+//! Moteur d'alertes: délai d'attente des soins et saisies manquantes
+
+mod common;
+
+use chrono::{Days, Local};
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{
+    CreateBande, CreateBatiment, CreateSoin, CreateSuiviSoin, CreateUser, UpdateAbonnementAlertes,
+    ALERTE_DELAI_ATTENTE, ALERTE_SAISIE_MANQUANTE, ALERTE_VIDE_SANITAIRE, DELAI_ESCALADE_HEURES, GRAVITE_CRITICAL,
+    GRAVITE_WARNING,
+};
+use tauri_app_lib::repositories::{
+    SoinRepository, SoinRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{AlerteService, AuthService, BandeService};
+
+#[tokio::test]
+async fn withdrawal_period_overlapping_catch_raises_alert() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let antibiotique = soin_repo.create(CreateSoin {
+        nom: "Enrofloxacine".to_string(),
+        unit: "ml".to_string(),
+        categorie: Some("Antibiotique".to_string()),
+        delai_attente_jours: 7,
+        prix_unitaire: None,
+    }).await.unwrap();
+    assert_eq!(antibiotique.categorie.as_deref(), Some("antibiotique"));
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let administrer = |batiment_id: i64, numero_semaine: i32, age: i32| CreateSuiviSoin {
+        semaine_id: semaine_id(&test_db, batiment_id, numero_semaine),
+        age,
+        soin_id: antibiotique.id,
+        quantite: Some("100".to_string()),
+        unit: None,
+    };
+
+    // Bâtiment 1 traité tôt: l'abattage est autorisé bien avant l'enlèvement (J56)
+    suivi_repo.add_soin(administrer(fixtures.batiment_ids[0], 2, 10)).await.unwrap();
+    // Bâtiment 2 traité à J52: autorisé à J59, après l'enlèvement
+    suivi_repo.add_soin(administrer(fixtures.batiment_ids[1], 8, 52)).await.unwrap();
+
+    let service = AlerteService::new(test_db.storage());
+    let alertes = service.get_bande_alertes(fixtures.bande_id).await.unwrap();
+    assert_eq!(alertes.len(), 1);
+    assert_eq!(alertes[0].type_alerte, ALERTE_DELAI_ATTENTE);
+    assert_eq!(alertes[0].batiment_id, Some(fixtures.batiment_ids[1]));
+    // Bande entrée le 2024-03-01: J56 = 2024-04-25
+    assert_eq!(alertes[0].date, "2024-04-25");
+
+    // La bande de test est enlevée depuis longtemps
+    assert!(service.get_pending_alerts(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn soin_rejects_unknown_categorie_and_negative_delay() {
+    let test_db = TestDb::new();
+    let soin_repo = SoinRepository::new(test_db.storage());
+
+    let categorie_inconnue = soin_repo.create(CreateSoin {
+        nom: "Produit".to_string(),
+        unit: "ml".to_string(),
+        categorie: Some("homéopathie".to_string()),
+        delai_attente_jours: 0,
+        prix_unitaire: None,
+    }).await;
+    assert!(categorie_inconnue.is_err());
+
+    let delai_negatif = soin_repo.create(CreateSoin {
+        nom: "Produit".to_string(),
+        unit: "ml".to_string(),
+        categorie: None,
+        delai_attente_jours: -1,
+        prix_unitaire: None,
+    }).await;
+    assert!(delai_negatif.is_err());
+}
+
+#[tokio::test]
+async fn missing_daily_entries_are_reported_per_batiment() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let aujourd_hui = Local::now().date_naive();
+
+    // Bande entrée il y a 4 jours: âges 1 à 5 jusqu'à aujourd'hui
+    let bande = BandeService::new(test_db.storage())
+        .create_bande_with_batiments_and_first_week(
+            CreateBande { date_entree: aujourd_hui - Days::new(4), ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "7".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 1000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    let bande_id = bande.id.unwrap();
+    let batiment_id: i64 = test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .query_row("SELECT id FROM batiments WHERE bande_id = ?1", [bande_id], |row| row.get(0))
+        .unwrap();
+    let semaine = semaine_id(&test_db, batiment_id, 1);
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    for (age, field, value) in [(1, "deces_par_jour", "3"), (1, "alimentation_par_jour", "20"), (2, "deces_par_jour", "0")] {
+        suivi_repo.upsert_field(semaine, age, field, value).await.unwrap();
+    }
+
+    let completude = suivi_repo.get_entry_completeness(bande_id, aujourd_hui).await.unwrap();
+    let jours = &completude.batiments[0].jours;
+    assert_eq!(jours.len(), 5);
+    assert!(jours[0].deces_saisi && jours[0].alimentation_saisie);
+    assert!(jours[1].deces_saisi && !jours[1].alimentation_saisie);
+    assert_eq!(jours[4].date, aujourd_hui);
+    assert_eq!(completude.batiments[0].jours_incomplets, 4);
+
+    // Les jours 2 à 4 sont signalés; le jour en cours peut encore être saisi
+    let service = AlerteService::new(test_db.storage());
+    let alertes = service.get_bande_alertes(bande_id).await.unwrap();
+    assert_eq!(alertes.len(), 1);
+    assert_eq!(alertes[0].type_alerte, ALERTE_SAISIE_MANQUANTE);
+    assert_eq!(alertes[0].batiment_id, Some(batiment_id));
+    assert_eq!(alertes[0].date, (aujourd_hui - Days::new(1)).to_string());
+    assert!(alertes[0].message.contains("3 jour(s)"), "{}", alertes[0].message);
+
+    // La bande de test, enlevée depuis longtemps, n'est pas concernée
+    let en_cours = service.get_pending_alerts(None).await.unwrap();
+    assert!(en_cours.iter().all(|alerte| alerte.bande_id == Some(bande_id)));
+    assert_eq!(en_cours.len(), 1);
+}
+
+#[tokio::test]
+async fn pending_alerts_follow_the_user_subscription() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let aujourd_hui = Local::now().date_naive();
+
+    // Bande entrée il y a 3 jours sans aucune saisie: une alerte de saisie manquante
+    BandeService::new(test_db.storage())
+        .create_bande_with_batiments_and_first_week(
+            CreateBande { date_entree: aujourd_hui - Days::new(3), ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "3".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 1000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let user = AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "technicien".to_string(),
+            email: "technicien@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap();
+    let service = AlerteService::new(test_db.storage());
+
+    // Sans abonnement enregistré, l'utilisateur reçoit toutes les alertes
+    let abonnement = service.get_alert_subscription(&user.token).await.unwrap();
+    assert!(abonnement.types_alerte.is_empty() && abonnement.ferme_ids.is_empty());
+    let alertes = service.get_pending_alerts(Some(user.user.id)).await.unwrap();
+    assert_eq!(alertes.len(), 1);
+    assert_eq!(alertes[0].gravite, GRAVITE_WARNING);
+
+    let abonner = |types: &[&str], ferme_ids: Vec<i64>, gravite: &str| UpdateAbonnementAlertes {
+        types_alerte: types.iter().map(|t| t.to_string()).collect(),
+        ferme_ids,
+        gravite_min: gravite.to_string(),
+    };
+
+    let enregistre = service
+        .set_alert_subscription(abonner(&[" Saisie_Manquante ", ALERTE_SAISIE_MANQUANTE], vec![fixtures.ferme_id], "warning"), &user.token)
+        .await
+        .unwrap();
+    assert_eq!(enregistre.types_alerte, vec![ALERTE_SAISIE_MANQUANTE.to_string()]);
+    assert_eq!(service.get_pending_alerts(Some(user.user.id)).await.unwrap().len(), 1);
+
+    service.set_alert_subscription(abonner(&[], vec![], GRAVITE_CRITICAL), &user.token).await.unwrap();
+    assert!(service.get_pending_alerts(Some(user.user.id)).await.unwrap().is_empty());
+
+    service.set_alert_subscription(abonner(&[ALERTE_VIDE_SANITAIRE], vec![], "info"), &user.token).await.unwrap();
+    assert!(service.get_pending_alerts(Some(user.user.id)).await.unwrap().is_empty());
+    assert_eq!(service.get_alert_subscription(&user.token).await.unwrap().types_alerte, vec![ALERTE_VIDE_SANITAIRE.to_string()]);
+
+    // Sans utilisateur, aucune alerte n'est filtrée
+    assert_eq!(service.get_pending_alerts(None).await.unwrap().len(), 1);
+
+    assert!(service.set_alert_subscription(abonner(&["inconnue"], vec![], "info"), &user.token).await.is_err());
+    assert!(service.set_alert_subscription(abonner(&[], vec![9999], "info"), &user.token).await.is_err());
+    assert!(service.set_alert_subscription(abonner(&[], vec![], "urgent"), &user.token).await.is_err());
+    assert!(service.set_alert_subscription(abonner(&[], vec![], "info"), "token-invalide").await.is_err());
+}
+
+#[tokio::test]
+async fn acknowledged_alerts_leave_the_pending_list_and_critical_ones_escalate() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let aujourd_hui = Local::now().date_naive();
+
+    // Bande entrée il y a 3 jours, traitée à J2 avec un délai d'attente de 30 jours
+    let bande = BandeService::new(test_db.storage())
+        .create_bande_with_batiments_and_first_week(
+            CreateBande { date_entree: aujourd_hui - Days::new(3), ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "3".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 1000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    let batiment_id: i64 = test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .query_row("SELECT id FROM batiments WHERE bande_id = ?1", [bande.id.unwrap()], |row| row.get(0))
+        .unwrap();
+    let antibiotique = SoinRepository::new(test_db.storage()).create(CreateSoin {
+        nom: "Colistine".to_string(),
+        unit: "ml".to_string(),
+        categorie: Some("antibiotique".to_string()),
+        delai_attente_jours: 30,
+        prix_unitaire: None,
+    }).await.unwrap();
+    SuiviQuotidienRepository::new(test_db.storage()).add_soin(CreateSuiviSoin {
+        semaine_id: semaine_id(&test_db, batiment_id, 1),
+        age: 2,
+        soin_id: antibiotique.id,
+        quantite: Some("50".to_string()),
+        unit: None,
+    }).await.unwrap();
+
+    let auth = AuthService::new(test_db.storage());
+    let user = auth
+        .register(CreateUser {
+            username: "technicien".to_string(),
+            email: "technicien@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap();
+    let service = AlerteService::new(test_db.storage());
+
+    let alertes = service.get_pending_alerts(None).await.unwrap();
+    assert_eq!(alertes.len(), 2);
+    let critique = alertes.iter().find(|a| a.type_alerte == ALERTE_DELAI_ATTENTE).unwrap();
+    assert_eq!(critique.gravite, GRAVITE_CRITICAL);
+    assert!(critique.id.is_some() && critique.detectee_le.is_some());
+    assert!(!critique.escalade);
+    let critique_id = critique.id.unwrap();
+
+    // Abonné aux seuls bâtiments prêts, l'utilisateur ne voit rien...
+    service
+        .set_alert_subscription(
+            UpdateAbonnementAlertes {
+                types_alerte: vec![ALERTE_VIDE_SANITAIRE.to_string()],
+                ferme_ids: vec![],
+                gravite_min: "info".to_string(),
+            },
+            &user.token,
+        )
+        .await
+        .unwrap();
+    assert!(service.get_pending_alerts(Some(user.user.id)).await.unwrap().is_empty());
+
+    // ...jusqu'à ce que l'alerte critique reste sans acquittement trop longtemps
+    test_db.storage().write(|tx| {
+        tx.execute(
+            "UPDATE alertes SET detectee_le = datetime('now', '-' || ?1 || ' hours') WHERE id = ?2",
+            rusqlite::params![DELAI_ESCALADE_HEURES + 1, critique_id],
+        )?;
+        Ok(())
+    }).unwrap();
+    let escaladees = service.get_pending_alerts(Some(user.user.id)).await.unwrap();
+    assert_eq!(escaladees.len(), 1);
+    assert_eq!(escaladees[0].id, Some(critique_id));
+    assert!(escaladees[0].escalade);
+
+    let acquittee = service.acknowledge_alert(critique_id, &user.token).await.unwrap();
+    assert_eq!(acquittee.acquittee_par, Some(user.user.id));
+    assert!(acquittee.acquittee_le.is_some());
+    assert!(!acquittee.escalade);
+    assert!(service.acknowledge_alert(critique_id, &user.token).await.is_err());
+    assert!(service.acknowledge_alert(9999, &user.token).await.is_err());
+
+    let restantes = service.get_pending_alerts(None).await.unwrap();
+    assert_eq!(restantes.len(), 1);
+    assert_eq!(restantes[0].type_alerte, ALERTE_SAISIE_MANQUANTE);
+
+    // Chaque alerte n'est inscrite qu'une fois dans l'historique
+    let historique = service.get_alert_history(Some(fixtures.ferme_id), &user.token).await.unwrap();
+    assert_eq!(historique.len(), 2);
+    let entree = historique.iter().find(|a| a.id == Some(critique_id)).unwrap();
+    assert_eq!(entree.acquittee_par_nom.as_deref(), Some("technicien"));
+    assert!(service.get_alert_history(Some(9999), &user.token).await.unwrap().is_empty());
+    assert!(service.get_alert_history(None, "token-invalide").await.is_err());
+}
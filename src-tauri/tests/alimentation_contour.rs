@@ -0,0 +1,130 @@
+//! Calcul du contour d'alimentation d'une bande
+//!
+//! Le contour augmente avec les livraisons et diminue de 50 kg par sachet
+//! consommé saisi dans le suivi quotidien.
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::error::AppError;
+use tauri_app_lib::models::{CreateAlimentationHistory, UpdateAlimentationHistory};
+use tauri_app_lib::repositories::{
+    AlimentationRepository, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+
+fn contour(test_db: &TestDb, bande_id: i64) -> f64 {
+    let conn = test_db.db.get_connection().unwrap();
+    AlimentationRepository::get_contour(&conn, bande_id).unwrap()
+}
+
+#[tokio::test]
+async fn deliveries_adjust_contour_on_create_update_and_delete() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    let livraison = AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 1000.0,
+            created_at: "2024-03-01 08:00:00".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 250.0,
+            created_at: "2024-03-05 08:00:00".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 1250.0);
+
+    AlimentationRepository::update(
+        &conn,
+        livraison.id.unwrap(),
+        &UpdateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 800.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 1050.0);
+
+    AlimentationRepository::delete(&conn, livraison.id.unwrap()).unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 250.0);
+}
+
+#[tokio::test]
+async fn daily_consumption_is_subtracted_in_sachets_of_50_kg() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+    AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 1000.0,
+            created_at: "2024-03-01 08:00:00".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    drop(conn);
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_1_bis = semaine_id(&test_db, fixtures.batiment_ids[1], 1);
+
+    suivi_repo.upsert_field(semaine_1, 1, "alimentation_par_jour", "4").await.unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 800.0);
+
+    // Les deux bâtiments consomment sur le même contour de bande
+    suivi_repo.upsert_field(semaine_1_bis, 1, "alimentation_par_jour", "2").await.unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 700.0);
+
+    // Une correction n'applique que la différence
+    suivi_repo.upsert_field(semaine_1, 1, "alimentation_par_jour", "3").await.unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 750.0);
+
+    // Effacer la saisie restitue la consommation
+    suivi_repo.upsert_field(semaine_1, 1, "alimentation_par_jour", "").await.unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 900.0);
+
+    // Modifier un autre champ ne touche pas au contour
+    suivi_repo.upsert_field(semaine_1_bis, 1, "deces_par_jour", "5").await.unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 900.0);
+}
+
+#[tokio::test]
+async fn same_delivery_saved_twice_in_a_row_needs_a_confirmation() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+    let livraison = CreateAlimentationHistory {
+        bande_id: fixtures.bande_id,
+        quantite: 1000.0,
+        created_at: "2024-03-01 08:00:00".to_string(),
+        ..Default::default()
+    };
+
+    AlimentationRepository::create(&conn, &livraison).unwrap();
+    let erreur = AlimentationRepository::create(&conn, &livraison).unwrap_err();
+    assert!(matches!(erreur, AppError::ConfirmationRequired { .. }), "{}", erreur);
+    // Une autre quantité n'est pas un doublon
+    AlimentationRepository::create(&conn, &CreateAlimentationHistory { quantite: 500.0, ..livraison.clone() }).unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 1500.0);
+
+    AlimentationRepository::create(&conn, &CreateAlimentationHistory { confirmer_doublon: true, ..livraison.clone() }).unwrap();
+    assert_eq!(contour(&test_db, fixtures.bande_id), 2500.0);
+
+    assert_eq!(AlimentationRepository::set_duplicate_window(&conn, 0).unwrap(), 0);
+    AlimentationRepository::create(&conn, &livraison).unwrap();
+    assert!(AlimentationRepository::set_duplicate_window(&conn, -1).is_err());
+}
@@ -0,0 +1,145 @@
+//! Consultation de l'historique des livraisons d'aliment
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::{AlimentationHistoryFilters, CreateAlimentationHistory};
+use tauri_app_lib::repositories::AlimentationRepository;
+
+#[tokio::test]
+async fn bande_history_is_paginated_with_totals_over_filter_window() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    for (jour, quantite) in [(1, 1000.0), (5, 500.0), (12, 750.0), (20, 250.0), (28, -100.0)] {
+        AlimentationRepository::create(
+            &conn,
+            &CreateAlimentationHistory {
+                bande_id: fixtures.bande_id,
+                quantite,
+                created_at: format!("2024-03-{:02} 08:00:00", jour),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+
+    let page = AlimentationRepository::get_by_bande_paginated(&conn, fixtures.bande_id, 1, 2, None, None).unwrap();
+    assert_eq!(page.total, 5);
+    assert_eq!(page.total_pages, 3);
+    assert!(page.has_next && !page.has_prev);
+    assert_eq!(page.data.len(), 2);
+    assert_eq!(page.data[0].created_at, "2024-03-28 08:00:00");
+    assert_eq!(page.total_quantite, 2400.0);
+
+    let filtered = AlimentationRepository::get_by_bande_paginated(
+        &conn,
+        fixtures.bande_id,
+        1,
+        10,
+        Some("2024-03-05".to_string()),
+        Some("2024-03-20".to_string()),
+    )
+    .unwrap();
+    assert_eq!(filtered.total, 3);
+    assert_eq!(filtered.total_quantite, 1500.0);
+    assert!(!filtered.has_next);
+}
+
+#[tokio::test]
+async fn global_history_joins_bande_and_ferme_names() {
+    let test_db = TestDb::new();
+    let first = seed(&test_db).await;
+    let second = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    for (bande_id, quantite, jour) in [
+        (first.bande_id, 1000.0, 1),
+        (first.bande_id, -50.0, 3),
+        (second.bande_id, 600.0, 2),
+    ] {
+        AlimentationRepository::create(
+            &conn,
+            &CreateAlimentationHistory {
+                bande_id,
+                quantite,
+                created_at: format!("2024-04-{:02} 08:00:00", jour),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+
+    let all = AlimentationRepository::get_global_paginated(&conn, 1, 10, &AlimentationHistoryFilters::default()).unwrap();
+    assert_eq!(all.total, 3);
+    assert_eq!(all.total_quantite, 1550.0);
+    assert!(all.data.iter().all(|a| a.ferme_nom.starts_with("Ferme ")));
+
+    let deliveries = AlimentationRepository::get_global_paginated(
+        &conn,
+        1,
+        10,
+        &AlimentationHistoryFilters { deliveries_only: Some(true), ..Default::default() },
+    )
+    .unwrap();
+    assert_eq!(deliveries.total, 2);
+    assert_eq!(deliveries.total_quantite, 1600.0);
+
+    let by_ferme = AlimentationRepository::get_global_paginated(
+        &conn,
+        1,
+        10,
+        &AlimentationHistoryFilters { ferme_id: Some(second.ferme_id), ..Default::default() },
+    )
+    .unwrap();
+    assert_eq!(by_ferme.total, 1);
+    assert_eq!(by_ferme.data[0].bande_id, second.bande_id);
+    assert_eq!(by_ferme.data[0].numero_bande, 1);
+}
+
+#[tokio::test]
+async fn delivery_metadata_is_validated_and_totalled_per_feed_type() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    for (type_aliment, quantite) in [("Démarrage", 800.0), ("croissance", 1500.0), ("croissance", 1200.0)] {
+        let livraison = AlimentationRepository::create(
+            &conn,
+            &CreateAlimentationHistory {
+                bande_id: fixtures.bande_id,
+                quantite,
+                created_at: "2024-03-01 08:00:00".to_string(),
+                fournisseur: Some("  Provenderie du Sud ".to_string()),
+                type_aliment: Some(type_aliment.to_string()),
+                numero_lot: Some("L-2024-031".to_string()),
+                prix_unitaire: None,
+                tva: Default::default(),
+                confirmer_doublon: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(livraison.fournisseur.as_deref(), Some("Provenderie du Sud"));
+    }
+
+    let invalid = AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 100.0,
+            created_at: "2024-03-02 08:00:00".to_string(),
+            type_aliment: Some("granulé".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(invalid.is_err());
+
+    let totals = AlimentationRepository::get_totals_by_type(&conn, Some(fixtures.bande_id)).unwrap();
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals[0].type_aliment.as_deref(), Some("démarrage"));
+    assert_eq!(totals[0].total_quantite, 800.0);
+    assert_eq!(totals[1].type_aliment.as_deref(), Some("croissance"));
+    assert_eq!(totals[1].total_quantite, 2700.0);
+    assert_eq!(totals[1].nombre_livraisons, 2);
+}
@@ -0,0 +1,113 @@
+//! Analyses de laboratoire structurées
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::{CreateAnalyse, CreateMaladie, UpdateAnalyse};
+use tauri_app_lib::repositories::{
+    AnalyseRepository, AnalyseRepositoryTrait, MaladieRepository, MaladieRepositoryTrait,
+};
+use tauri_app_lib::services::AnalyseService;
+
+#[tokio::test]
+async fn analyses_are_linked_to_maladie_episodes_and_filterable() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let maladie = MaladieRepository::new(test_db.storage())
+        .create(CreateMaladie { nom: "Coccidiose".to_string() })
+        .await
+        .unwrap();
+
+    let repo = AnalyseRepository::new(test_db.storage());
+    let coproscopie = repo.create(CreateAnalyse {
+        batiment_id: fixtures.batiment_ids[0],
+        maladie_id: Some(maladie.id),
+        type_analyse: "Parasitologie".to_string(),
+        laboratoire: Some("  Labo régional ".to_string()),
+        date_prelevement: "2024-03-20".to_string(),
+        resultat: Some("Oocystes +++".to_string()),
+    }).await.unwrap();
+    assert_eq!(coproscopie.type_analyse, "parasitologie");
+    assert_eq!(coproscopie.laboratoire.as_deref(), Some("Labo régional"));
+    assert_eq!(coproscopie.maladie_nom.as_deref(), Some("Coccidiose"));
+
+    // L'analyse déclare l'épisode de maladie du bâtiment
+    assert_eq!(
+        test_db.count(
+            "batiment_maladies",
+            &format!("batiment_id = {} AND maladie_id = {}", fixtures.batiment_ids[0], maladie.id),
+        ),
+        1
+    );
+
+    repo.create(CreateAnalyse {
+        batiment_id: fixtures.batiment_ids[1],
+        type_analyse: "eau".to_string(),
+        date_prelevement: "2024-03-05".to_string(),
+        ..Default::default()
+    }).await.unwrap();
+
+    let bande = repo.get_by_bande(fixtures.bande_id, None).await.unwrap();
+    assert_eq!(bande.len(), 2);
+    assert_eq!(bande[0].date_prelevement, "2024-03-05");
+    let parasitologie = repo.get_by_bande(fixtures.bande_id, Some("parasitologie")).await.unwrap();
+    assert_eq!(parasitologie.len(), 1);
+
+    let episode = repo.get_by_maladie(fixtures.batiment_ids[0], maladie.id).await.unwrap();
+    assert_eq!(episode.len(), 1);
+
+    let controle = repo.update(UpdateAnalyse {
+        id: coproscopie.id.unwrap(),
+        maladie_id: Some(maladie.id),
+        type_analyse: "parasitologie".to_string(),
+        laboratoire: None,
+        date_prelevement: "2024-03-21".to_string(),
+        resultat: Some("Oocystes +".to_string()),
+    }).await.unwrap();
+    assert_eq!(controle.date_prelevement, "2024-03-21");
+    assert_eq!(controle.laboratoire, None);
+
+    let invalide = repo.create(CreateAnalyse {
+        batiment_id: fixtures.batiment_ids[0],
+        type_analyse: "astrologie".to_string(),
+        date_prelevement: "2024-03-05".to_string(),
+        ..Default::default()
+    }).await;
+    assert!(invalide.is_err());
+    let date_invalide = repo.create(CreateAnalyse {
+        batiment_id: fixtures.batiment_ids[0],
+        type_analyse: "eau".to_string(),
+        date_prelevement: "05/03/2024".to_string(),
+        ..Default::default()
+    }).await;
+    assert!(date_invalide.is_err());
+}
+
+#[tokio::test]
+async fn pdf_report_is_copied_and_removed_with_the_analyse() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let repo = AnalyseRepository::new(test_db.storage());
+    let analyse = repo.create(CreateAnalyse {
+        batiment_id: fixtures.batiment_ids[0],
+        type_analyse: "autopsie".to_string(),
+        date_prelevement: "2024-03-10".to_string(),
+        ..Default::default()
+    }).await.unwrap();
+    let id = analyse.id.unwrap();
+
+    let source = test_db.dir().join("rapport.PDF");
+    std::fs::write(&source, b"%PDF-1.4").unwrap();
+    let dossier = test_db.dir().join("analyses");
+
+    let service = AnalyseService::new(test_db.storage());
+    assert!(service.attach_pdf(id, &test_db.dir().join("rapport.txt"), &dossier).await.is_err());
+
+    let analyse = service.attach_pdf(id, &source, &dossier).await.unwrap();
+    let copie = std::path::PathBuf::from(analyse.fichier_pdf.unwrap());
+    assert!(copie.is_file());
+
+    service.delete_analyse(id).await.unwrap();
+    assert!(!copie.exists());
+    assert!(repo.get_by_id(id).await.is_err());
+}
@@ -0,0 +1,183 @@
+//! Archivage des anciennes bandes dans la base d'archive attachée
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::database::archive::TABLES_ARCHIVEES;
+use tauri_app_lib::models::CreateUser;
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::{ArchiveService, AuthService};
+
+async fn admin(test_db: &TestDb) -> String {
+    AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap()
+        .token
+}
+
+#[tokio::test]
+async fn old_closed_bandes_move_to_the_archive_with_their_children() {
+    let test_db = TestDb::new();
+    let ancienne = seed(&test_db).await;
+    let recente = seed(&test_db).await;
+    let token = admin(&test_db).await;
+    let suivis = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, ancienne.batiment_ids[0], 1);
+    for age in 1..=3 {
+        suivis.upsert_field(semaine, age, "deces_par_jour", "4").await.unwrap();
+    }
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute(
+            "UPDATE bandes SET statut = 'cloturee', date_cloture = '2020-05-01' WHERE id = ?1",
+            [ancienne.bande_id],
+        )
+        .unwrap();
+    }
+
+    let service = ArchiveService::new(test_db.storage());
+    let resultat = service.archive_old_bandes(None, &token).await.unwrap();
+    assert_eq!(resultat.annees, 3);
+    assert_eq!(resultat.bandes_archivees, vec![ancienne.bande_id]);
+
+    // La bande et ses lignes ont quitté la base principale
+    assert_eq!(test_db.count("bandes", &format!("id = {}", ancienne.bande_id)), 0);
+    assert_eq!(test_db.count("batiments", &format!("bande_id = {}", ancienne.bande_id)), 0);
+    assert_eq!(test_db.count("archive.batiments", &format!("bande_id = {}", ancienne.bande_id)), 2);
+    assert_eq!(test_db.count("suivi_quotidien", &format!("semaine_id = {}", semaine)), 0);
+    assert_eq!(test_db.count("archive.suivi_quotidien", &format!("semaine_id = {}", semaine)), 3);
+    // La bande active est conservée
+    assert_eq!(test_db.count("bandes", &format!("id = {}", recente.bande_id)), 1);
+
+    let archivees = service.query_archive(None, &token).await.unwrap();
+    assert_eq!(archivees.len(), 1);
+    assert_eq!(archivees[0].id, ancienne.bande_id);
+    assert_eq!(archivees[0].nombre_batiments, 2);
+    assert_eq!(archivees[0].total_deces, 12);
+    assert!(archivees[0].ferme_nom.is_some());
+    assert!(service.query_archive(Some(recente.ferme_id), &token).await.unwrap().is_empty());
+
+    // Un second archivage ne trouve plus rien à déplacer
+    let resultat = service.archive_old_bandes(Some(10), &token).await.unwrap();
+    assert_eq!(resultat.annees, 10);
+    assert!(resultat.bandes_archivees.is_empty());
+    assert_eq!(test_db.count("archive.bandes", "1"), 1);
+}
+
+#[tokio::test]
+async fn archive_is_empty_until_the_first_archival() {
+    let test_db = TestDb::new();
+    let token = admin(&test_db).await;
+    let service = ArchiveService::new(test_db.storage());
+
+    assert!(service.query_archive(None, &token).await.unwrap().is_empty());
+    assert!(service.archive_old_bandes(Some(0), &token).await.is_err());
+}
+
+/// Nombre de lignes de chaque table dépendante de la bande, dans `schema`
+fn lignes_dependantes(test_db: &TestDb, schema: &str, bande_id: i64, batiment_id: i64) -> Vec<(&'static str, i64)> {
+    [
+        ("alertes", format!("bande_id = {}", bande_id)),
+        ("messages", format!("batiment_id = {}", batiment_id)),
+        ("deverrouillages_periode", format!("bande_id = {}", bande_id)),
+        ("valeurs_champs_personnalises", format!("bande_id = {} OR batiment_id = {}", bande_id, batiment_id)),
+        ("bande_tags", format!("bande_id = {}", bande_id)),
+        ("budgets_bande", format!("bande_id = {}", bande_id)),
+        ("contrats_bande", format!("bande_id = {}", bande_id)),
+    ]
+    .into_iter()
+    .map(|(table, condition)| (table, test_db.count(&format!("{}.{}", schema, table), &condition)))
+    .collect()
+}
+
+#[tokio::test]
+async fn archived_bandes_are_restored_with_all_their_dependent_rows() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let token = admin(&test_db).await;
+    let (bande_id, batiment_id) = (fixtures.bande_id, fixtures.batiment_ids[0]);
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute_batch(&format!(
+            "INSERT INTO alertes (type_alerte, gravite, ferme_id, bande_id, batiment_id, date_alerte, message)
+             VALUES ('mortalite', 'critical', {ferme}, {bande}, {batiment}, '2020-03-10', 'Pic de mortalité');
+             INSERT INTO messages (batiment_id, contenu) VALUES ({batiment}, 'Vérifier les abreuvoirs');
+             INSERT INTO deverrouillages_periode (bande_id, date_debut, date_fin, expire_le)
+             VALUES ({bande}, '2020-03-01', '2020-03-07', '2020-03-08 00:00:00');
+             INSERT INTO champs_personnalises (entite, cle, libelle, type_valeur) VALUES ('bande', 'lot', 'Lot', 'texte');
+             INSERT INTO champs_personnalises (entite, cle, libelle, type_valeur)
+             VALUES ('batiment', 'ventilation', 'Ventilation', 'texte');
+             INSERT INTO valeurs_champs_personnalises (champ_id, bande_id, valeur)
+             SELECT id, {bande}, 'L-42' FROM champs_personnalises WHERE cle = 'lot';
+             INSERT INTO valeurs_champs_personnalises (champ_id, batiment_id, valeur)
+             SELECT id, {batiment}, 'tunnel' FROM champs_personnalises WHERE cle = 'ventilation';
+             INSERT INTO tags (nom) VALUES ('contrat-export');
+             INSERT INTO bande_tags (bande_id, tag_id) SELECT {bande}, id FROM tags WHERE nom = 'contrat-export';
+             INSERT INTO budgets_bande (bande_id, aliment_prevu_kg, mortalite_prevue_pourcentage, cout_aliment_prevu)
+             VALUES ({bande}, 42000, 4.5, 630000);
+             INSERT INTO contrats_bande (bande_id, prix_garanti_kg, ic_cible) VALUES ({bande}, 10.0, 1.6);
+             UPDATE bandes SET statut = 'cloturee', date_cloture = '2020-05-01' WHERE id = {bande};",
+            ferme = fixtures.ferme_id,
+            bande = bande_id,
+            batiment = batiment_id,
+        ))
+        .unwrap();
+    }
+    let avant = lignes_dependantes(&test_db, "main", bande_id, batiment_id);
+    assert!(avant.iter().all(|&(_, lignes)| lignes > 0), "{:?}", avant);
+
+    let service = ArchiveService::new(test_db.storage());
+    service.archive_old_bandes(None, &token).await.unwrap();
+    // Rien n'est perdu: les lignes dépendantes sont dans l'archive
+    assert_eq!(lignes_dependantes(&test_db, "archive", bande_id, batiment_id), avant);
+    assert!(lignes_dependantes(&test_db, "main", bande_id, batiment_id).iter().all(|&(_, lignes)| lignes == 0));
+
+    assert!(service.restore_archived_bande(bande_id, "token-invalide").await.is_err());
+    service.restore_archived_bande(bande_id, &token).await.unwrap();
+    assert_eq!(lignes_dependantes(&test_db, "main", bande_id, batiment_id), avant);
+    assert!(lignes_dependantes(&test_db, "archive", bande_id, batiment_id).iter().all(|&(_, lignes)| lignes == 0));
+    assert_eq!(test_db.count("bandes", &format!("id = {}", bande_id)), 1);
+    assert_eq!(test_db.count("batiments", &format!("bande_id = {}", bande_id)), 2);
+    assert_eq!(test_db.count("archive.bandes", "1"), 0);
+    assert!(service.query_archive(None, &token).await.unwrap().is_empty());
+
+    // Une bande qui n'est pas dans l'archive ne peut pas être restaurée
+    assert!(service.restore_archived_bande(bande_id, &token).await.is_err());
+}
+
+#[test]
+fn every_table_deleted_in_cascade_with_a_bande_is_archived() {
+    let test_db = TestDb::new();
+    let conn = test_db.db.get_connection().unwrap();
+    let tables: Vec<String> = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    for table in &tables {
+        let cascades: Vec<String> = conn
+            .prepare(&format!("SELECT \"table\" FROM pragma_foreign_key_list('{}') WHERE on_delete = 'CASCADE'", table))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for parent in cascades.iter().filter(|parent| TABLES_ARCHIVEES.contains(&parent.as_str())) {
+            assert!(
+                TABLES_ARCHIVEES.contains(&table.as_str()),
+                "{} est supprimée en cascade avec {} mais n'est pas archivée",
+                table,
+                parent
+            );
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Budget prévisionnel des bandes et écart avec le réalisé
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{BudgetBande, POSTE_ALIMENT_KG, POSTE_COUT_SOINS, POSTE_MORTALITE};
+use tauri_app_lib::services::BilanService;
+
+#[tokio::test]
+async fn budget_variance_compares_forecast_to_actuals() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = BilanService::new(test_db.storage());
+
+    let sans_budget = service.get_budget_variance(fixtures.bande_id).await.unwrap();
+    assert!(sans_budget.budget.is_none());
+    assert!(sans_budget.postes.iter().all(|poste| poste.ecart.is_none()));
+
+    let invalide = BudgetBande { mortalite_prevue_pourcentage: Some(150.0), ..Default::default() };
+    assert!(service.set_bande_budget(fixtures.bande_id, invalide).await.is_err());
+    assert!(service.set_bande_budget(9999, BudgetBande::default()).await.is_err());
+
+    let budget = BudgetBande {
+        aliment_prevu_kg: Some(800.0),
+        mortalite_prevue_pourcentage: Some(2.0),
+        ..Default::default()
+    };
+    service.set_bande_budget(fixtures.bande_id, budget).await.unwrap();
+    assert_eq!(service.get_bande_budget(fixtures.bande_id).await.unwrap().unwrap().aliment_prevu_kg, Some(800.0));
+
+    // 10 000 poussins placés: 100 décès et 20 sachets de 50 kg
+    for batiment_id in &fixtures.batiment_ids {
+        test_db
+            .db
+            .get_connection()
+            .unwrap()
+            .execute(
+                "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, alimentation_par_jour) VALUES (?1, 3, 50, 10)",
+                [semaine_id(&test_db, *batiment_id, 1)],
+            )
+            .unwrap();
+    }
+
+    let ecart = service.get_budget_variance(fixtures.bande_id).await.unwrap();
+    assert_eq!(ecart.age_atteint, 3);
+    let poste = |nom: &str| ecart.postes.iter().find(|poste| poste.poste == nom).unwrap();
+
+    assert_eq!(poste(POSTE_ALIMENT_KG).reel, 1000.0);
+    assert_eq!(poste(POSTE_ALIMENT_KG).ecart, Some(200.0));
+    assert_eq!(poste(POSTE_ALIMENT_KG).ecart_pourcentage, Some(25.0));
+    assert_eq!(poste(POSTE_MORTALITE).reel, 1.0);
+    assert_eq!(poste(POSTE_MORTALITE).ecart, Some(-1.0));
+    assert_eq!(poste(POSTE_COUT_SOINS).prevu, None);
+    assert_eq!(poste(POSTE_COUT_SOINS).ecart, None);
+}
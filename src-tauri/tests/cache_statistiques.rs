@@ -0,0 +1,35 @@
+//! Cache des statistiques globales invalidé par les écritures
+
+mod common;
+
+use common::{seed, TestDb};
+use std::sync::Arc;
+use tauri_app_lib::models::CreateFerme;
+use tauri_app_lib::services::{FermeService, StatisticsCache};
+
+#[tokio::test]
+async fn global_statistics_are_cached_until_data_changes() {
+    let test_db = TestDb::new();
+    seed(&test_db).await;
+    let cache = Arc::new(StatisticsCache::new());
+
+    let premieres = cache.get_global_statistics(test_db.storage()).await.unwrap();
+    assert_eq!(premieres.total_fermes, 1);
+    assert_eq!(premieres.cache_age_seconds, 0);
+
+    // Sans modification, le calcul n'est pas refait
+    let depuis_cache = cache.get_global_statistics(test_db.storage()).await.unwrap();
+    assert_eq!(depuis_cache.computed_at, premieres.computed_at);
+    assert!(!cache.refresh_if_stale(test_db.storage()).await.unwrap());
+
+    // Une écriture invalide le cache; le rafraîchissement de fond recalcule
+    FermeService::new(test_db.storage())
+        .create_ferme(CreateFerme { nom: "Ferme du Cache".to_string(), nbr_meuble: 2 })
+        .await
+        .unwrap();
+    assert!(cache.refresh_if_stale(test_db.storage()).await.unwrap());
+
+    let apres_ecriture = cache.get_global_statistics(test_db.storage()).await.unwrap();
+    assert_eq!(apres_ecriture.total_fermes, 2);
+    assert!(!cache.refresh_if_stale(test_db.storage()).await.unwrap());
+}
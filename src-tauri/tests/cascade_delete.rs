@@ -0,0 +1,147 @@
+//! Suppressions en cascade: aucune donnée orpheline ne doit subsister
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateAlimentationHistory, CreateAnalyse, CreateMaladie, CreateNoteBatiment};
+use tauri_app_lib::repositories::{
+    AlimentationRepository, AnalyseRepository, AnalyseRepositoryTrait, BandeRepository, BatimentRepository,
+    MaladieRepository, MaladieRepositoryTrait, NoteBatimentRepository, SuiviQuotidienRepository,
+    SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::FermeService;
+
+/// Ajoute un suivi, une maladie, une analyse, une note et une livraison d'aliment sur les fixtures
+async fn add_activity(test_db: &TestDb, bande_id: i64, batiment_id: i64) {
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(test_db, batiment_id, 1);
+    suivi_repo.upsert_field(semaine, 1, "deces_par_jour", "12").await.unwrap();
+    suivi_repo.upsert_field(semaine, 2, "remarques", "RAS").await.unwrap();
+
+    let maladie = MaladieRepository::new(test_db.storage())
+        .create(CreateMaladie { nom: format!("Maladie {}", batiment_id) })
+        .await
+        .unwrap();
+
+    AnalyseRepository::new(test_db.storage())
+        .create(CreateAnalyse {
+            batiment_id,
+            maladie_id: Some(maladie.id),
+            type_analyse: "Sérologie".to_string(),
+            laboratoire: None,
+            date_prelevement: "2024-03-05".to_string(),
+            resultat: None,
+        })
+        .await
+        .unwrap();
+
+    let conn = test_db.db.get_connection().unwrap();
+    BatimentRepository::add_maladie_to_batiment(&conn, batiment_id, maladie.id).unwrap();
+    NoteBatimentRepository::create(
+        &conn,
+        &CreateNoteBatiment {
+            batiment_id,
+            auteur: "Technicien".to_string(),
+            contenu: "Litière humide".to_string(),
+            tag: None,
+        },
+    )
+    .unwrap();
+    AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id,
+            quantite: 500.0,
+            created_at: "2024-03-01 08:00:00".to_string(),
+            // Appelé pour chaque bâtiment d'une même bande: la livraison répétée est voulue
+            confirmer_doublon: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+}
+
+#[tokio::test]
+async fn deleting_bande_removes_all_dependent_rows() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    for batiment_id in &fixtures.batiment_ids {
+        add_activity(&test_db, fixtures.bande_id, *batiment_id).await;
+    }
+
+    test_db.storage().write(|tx| BandeRepository::delete(tx, fixtures.bande_id)).unwrap();
+
+    assert_eq!(test_db.count("bandes", "1 = 1"), 0);
+    assert_eq!(test_db.count("batiments", "1 = 1"), 0);
+    assert_eq!(test_db.count("semaines", "1 = 1"), 0);
+    assert_eq!(test_db.count("suivi_quotidien", "1 = 1"), 0);
+    assert_eq!(test_db.count("batiment_maladies", "1 = 1"), 0);
+    assert_eq!(test_db.count("alimentation_history", "1 = 1"), 0);
+    assert_eq!(test_db.count("analyses", "1 = 1"), 0);
+    assert_eq!(test_db.count("notes_batiment", "1 = 1"), 0);
+
+    // Les référentiels partagés ne sont pas touchés
+    assert_eq!(test_db.count("maladies", "1 = 1"), 2);
+    assert_eq!(test_db.count("personnel", "1 = 1"), 1);
+    assert_eq!(test_db.count("fermes", "1 = 1"), 1);
+}
+
+#[tokio::test]
+async fn deleting_batiment_keeps_sibling_batiment_data() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let (deleted, kept) = (fixtures.batiment_ids[0], fixtures.batiment_ids[1]);
+    add_activity(&test_db, fixtures.bande_id, deleted).await;
+    add_activity(&test_db, fixtures.bande_id, kept).await;
+
+    test_db.storage().write(|tx| BatimentRepository::delete(tx, deleted)).unwrap();
+
+    let deleted_semaines = format!("semaine_id IN (SELECT id FROM semaines WHERE batiment_id = {})", deleted);
+    assert_eq!(test_db.count("semaines", &format!("batiment_id = {}", deleted)), 0);
+    assert_eq!(test_db.count("suivi_quotidien", &deleted_semaines), 0);
+    assert_eq!(test_db.count("batiment_maladies", &format!("batiment_id = {}", deleted)), 0);
+    assert_eq!(test_db.count("analyses", &format!("batiment_id = {}", deleted)), 0);
+    assert_eq!(test_db.count("notes_batiment", &format!("batiment_id = {}", deleted)), 0);
+
+    assert_eq!(test_db.count("semaines", &format!("batiment_id = {}", kept)), 8);
+    assert_eq!(test_db.count("suivi_quotidien", "1 = 1"), 2);
+    assert_eq!(test_db.count("batiment_maladies", &format!("batiment_id = {}", kept)), 1);
+
+    // Aucun suivi ne pointe vers une semaine inexistante
+    assert_eq!(
+        test_db.count("suivi_quotidien", "semaine_id NOT IN (SELECT id FROM semaines)"),
+        0
+    );
+}
+
+#[tokio::test]
+async fn deleting_ferme_with_bandes_is_refused() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+
+    let result = FermeService::new(test_db.storage()).delete_ferme(fixtures.ferme_id).await;
+
+    assert!(result.is_err());
+    assert_eq!(test_db.count("fermes", "1 = 1"), 1);
+    assert_eq!(test_db.count("batiments", "1 = 1"), 2);
+}
+
+#[tokio::test]
+async fn deleting_maladie_removes_its_batiment_links_and_keeps_analyses() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    add_activity(&test_db, fixtures.bande_id, batiment_id).await;
+    let maladie_id: i64 = test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .query_row("SELECT maladie_id FROM batiment_maladies WHERE batiment_id = ?1", [batiment_id], |row| row.get(0))
+        .unwrap();
+
+    MaladieRepository::new(test_db.storage()).delete(maladie_id).await.unwrap();
+
+    assert_eq!(test_db.count("batiment_maladies", "1 = 1"), 0);
+    // L'analyse reste rattachée au bâtiment, sans maladie
+    assert_eq!(test_db.count("analyses", &format!("batiment_id = {} AND maladie_id IS NULL", batiment_id)), 1);
+}
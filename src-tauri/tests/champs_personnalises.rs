@@ -0,0 +1,115 @@
+//! Champs personnalisés des bandes et des bâtiments
+
+mod common;
+
+use common::{seed, TestDb};
+use rusqlite::Connection;
+use tauri_app_lib::models::{
+    BandeLoadOptions, CreateChampPersonnalise, UpdateBande, UpdateBatiment, ValeursChamps, ENTITE_CHAMP_BANDE,
+    ENTITE_CHAMP_BATIMENT,
+};
+use tauri_app_lib::repositories::{BandeRepository, BatimentRepository, ChampPersonnaliseRepository};
+use tauri_app_lib::services::{BandeService, ExportService};
+
+fn valeurs(paires: &[(&str, &str)]) -> ValeursChamps {
+    paires.iter().map(|(cle, valeur)| (cle.to_string(), valeur.to_string())).collect()
+}
+
+#[tokio::test]
+async fn custom_fields_are_saved_with_bandes_and_batiments_and_exported() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    let champ = |entite: &str, cle: &str, type_valeur: &str| CreateChampPersonnalise {
+        entite: entite.to_string(),
+        cle: cle.to_string(),
+        libelle: cle.replace('_', " "),
+        type_valeur: type_valeur.to_string(),
+    };
+    let age_parentaux = ChampPersonnaliseRepository::create(&conn, &champ("bande", "Age_Parentaux", "nombre")).unwrap();
+    assert_eq!(age_parentaux.cle, "age_parentaux");
+    ChampPersonnaliseRepository::create(&conn, &champ("bande", "transporteur", "texte")).unwrap();
+    ChampPersonnaliseRepository::create(&conn, &champ("batiment", "brumisation", "booleen")).unwrap();
+
+    assert!(ChampPersonnaliseRepository::create(&conn, &champ("bande", "transporteur", "texte")).is_err());
+    assert!(ChampPersonnaliseRepository::create(&conn, &champ("semaine", "poids_cible", "nombre")).is_err());
+    assert!(ChampPersonnaliseRepository::create(&conn, &champ("bande", "âge", "nombre")).is_err());
+    assert!(ChampPersonnaliseRepository::create(&conn, &champ("bande", "couleur", "liste")).is_err());
+    assert_eq!(ChampPersonnaliseRepository::get_all(&conn, Some(ENTITE_CHAMP_BANDE)).unwrap().len(), 2);
+    assert_eq!(ChampPersonnaliseRepository::get_all(&conn, None).unwrap().len(), 3);
+    drop(conn);
+
+    let service = BandeService::new(test_db.storage());
+    let bande = service.get_bande_by_id(fixtures.bande_id).await.unwrap().unwrap();
+    assert!(bande.champs_personnalises.is_empty());
+
+    let modification = |champs: ValeursChamps| UpdateBande {
+        id: fixtures.bande_id,
+        numero_bande: bande.numero_bande,
+        date_entree: bande.date_entree,
+        ferme_id: bande.ferme_id,
+        notes: None,
+        version: None,
+        champs_personnalises: champs,
+    };
+    service
+        .update_bande(fixtures.bande_id, modification(valeurs(&[("age_parentaux", "42,5"), ("transporteur", " Trans Atlas ")])))
+        .await
+        .unwrap();
+
+    // Valeur invalide ou champ inconnu: rien n'est enregistré
+    assert!(service.update_bande(fixtures.bande_id, modification(valeurs(&[("age_parentaux", "vieux")]))).await.is_err());
+    assert!(service.update_bande(fixtures.bande_id, modification(valeurs(&[("brumisation", "oui")]))).await.is_err());
+
+    let bande = service.get_bande_by_id(fixtures.bande_id).await.unwrap().unwrap();
+    assert_eq!(bande.champs_personnalises, valeurs(&[("age_parentaux", "42.5"), ("transporteur", "Trans Atlas")]));
+    assert_eq!(bande.version, 2);
+
+    let conn = test_db.db.get_connection().unwrap();
+    let batiment = BatimentRepository::get_by_id(&conn, fixtures.batiment_ids[0]).unwrap().unwrap();
+    BatimentRepository::update(
+        &conn,
+        fixtures.batiment_ids[0],
+        &UpdateBatiment {
+            id: fixtures.batiment_ids[0],
+            bande_id: batiment.bande_id,
+            numero_batiment: batiment.numero_batiment.clone(),
+            poussin_id: batiment.poussin_id,
+            personnel_id: batiment.personnel_id,
+            quantite: batiment.quantite,
+            version: None,
+            champs_personnalises: valeurs(&[("brumisation", "Oui")]),
+        },
+    )
+    .unwrap();
+    let details = BandeRepository::get_by_id(&conn, fixtures.bande_id, &BandeLoadOptions::default()).unwrap().unwrap();
+    let batiment = details.batiments.iter().find(|b| b.id == Some(fixtures.batiment_ids[0])).unwrap();
+    assert_eq!(batiment.champs_personnalises, valeurs(&[("brumisation", "true")]));
+    assert_eq!(
+        ChampPersonnaliseRepository::get_valeurs(&conn, ENTITE_CHAMP_BATIMENT, fixtures.batiment_ids[1]).unwrap(),
+        ValeursChamps::new()
+    );
+    drop(conn);
+
+    let chemin = test_db.dir().join("reporting.db");
+    ExportService::new(test_db.storage()).export_reporting_snapshot(&chemin.to_string_lossy()).await.unwrap();
+    let export = Connection::open(&chemin).unwrap();
+    let champs: String = export
+        .query_row("SELECT champs_personnalises FROM bande_facts WHERE bande_id = ?1", [fixtures.bande_id], |row| row.get(0))
+        .unwrap();
+    assert_eq!(champs, r#"{"age_parentaux":"42.5","transporteur":"Trans Atlas"}"#);
+    let champs_batiment: String = export
+        .query_row("SELECT champs_batiment FROM daily_facts WHERE batiment_id = ?1 LIMIT 1", [fixtures.batiment_ids[0]], |row| row.get(0))
+        .unwrap();
+    assert_eq!(champs_batiment, r#"{"brumisation":"true"}"#);
+    drop(export);
+
+    // Une valeur vide retire le champ; supprimer un champ supprime ses valeurs
+    service.update_bande(fixtures.bande_id, modification(valeurs(&[("transporteur", "")]))).await.unwrap();
+    let conn = test_db.db.get_connection().unwrap();
+    assert!(ChampPersonnaliseRepository::get_valeurs(&conn, ENTITE_CHAMP_BANDE, fixtures.bande_id).unwrap().is_empty());
+    ChampPersonnaliseRepository::delete(&conn, age_parentaux.id).unwrap();
+    assert!(ChampPersonnaliseRepository::delete(&conn, age_parentaux.id).is_err());
+    assert_eq!(test_db.count("valeurs_champs_personnalises", "batiment_id IS NOT NULL"), 1);
+}
@@ -0,0 +1,95 @@
+//! Chiffrement de la base de données avec SQLCipher
+
+mod common;
+
+use common::TestDb;
+use std::time::Duration;
+use tauri_app_lib::database::chiffrement::est_en_clair;
+use tauri_app_lib::database::{DatabaseConfig, DatabaseManager, OuvertureBase, Storage};
+use tauri_app_lib::error::AppError;
+
+fn config(passphrase: &str) -> DatabaseConfig {
+    DatabaseConfig {
+        busy_timeout: Duration::from_secs(5),
+        passphrase: Some(passphrase.to_string()),
+        ..DatabaseConfig::default()
+    }
+}
+
+/// Crée une base en clair contenant une ferme
+fn base_en_clair(test_db: &TestDb) -> std::path::PathBuf {
+    let chemin = test_db.dir().join("en_clair.db");
+    let db = DatabaseManager::new(&chemin).unwrap();
+    db.initialize_schema().unwrap();
+    db.get_connection()
+        .unwrap()
+        .execute("INSERT INTO fermes (nom, nbr_meuble) VALUES ('Ferme Secrète', 2)", [])
+        .unwrap();
+    chemin
+}
+
+#[cfg(not(feature = "chiffrement"))]
+#[test]
+fn passphrase_without_sqlcipher_is_refused_and_leaves_the_file_untouched() {
+    let test_db = TestDb::new();
+    let chemin = base_en_clair(&test_db);
+
+    assert!(DatabaseManager::with_config(&chemin, config("phrase secrète")).is_err());
+    assert!(est_en_clair(&chemin).unwrap());
+}
+
+#[cfg(feature = "chiffrement")]
+#[test]
+fn existing_database_is_encrypted_on_first_open() {
+    let test_db = TestDb::new();
+    let chemin = base_en_clair(&test_db);
+
+    {
+        let db = DatabaseManager::with_config(&chemin, config("phrase secrète")).unwrap();
+        let nom: String = db
+            .get_connection()
+            .unwrap()
+            .query_row("SELECT nom FROM fermes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(nom, "Ferme Secrète");
+    }
+    assert!(!est_en_clair(&chemin).unwrap());
+
+    assert!(matches!(DatabaseManager::with_config(&chemin, config("mauvaise phrase")), Err(AppError::InvalidPassphrase)));
+    assert!(DatabaseManager::new(&chemin).and_then(|db| db.initialize_schema()).is_err());
+}
+
+#[cfg(feature = "chiffrement")]
+#[test]
+fn encrypted_database_stays_locked_until_the_right_passphrase_is_entered() {
+    let test_db = TestDb::new();
+    let chemin = base_en_clair(&test_db);
+    drop(DatabaseManager::with_config(&chemin, config("phrase secrète")).unwrap());
+
+    // Phrase secrète absente de l'environnement
+    let ouverture = OuvertureBase::new(&chemin, DatabaseConfig::default());
+    assert!(matches!(ouverture.ouvrir(), Err(AppError::Locked)));
+    assert!(ouverture.est_verrouillee());
+
+    assert!(matches!(ouverture.deverrouiller("mauvaise phrase"), Err(AppError::InvalidPassphrase)));
+    assert!(ouverture.deverrouiller("").is_err());
+    assert!(ouverture.est_verrouillee());
+
+    let db = ouverture.deverrouiller("phrase secrète").unwrap();
+    let nom: String = db.get_connection().unwrap().query_row("SELECT nom FROM fermes", [], |row| row.get(0)).unwrap();
+    assert_eq!(nom, "Ferme Secrète");
+    assert!(!ouverture.est_verrouillee());
+    assert!(ouverture.deverrouiller("phrase secrète").is_err());
+}
+
+#[test]
+fn unreadable_database_is_reported_as_locked_instead_of_panicking() {
+    let test_db = TestDb::new();
+    let chemin = test_db.dir().join("illisible.db");
+    std::fs::write(&chemin, vec![0x5a; 4096]).unwrap();
+
+    let ouverture = OuvertureBase::new(&chemin, DatabaseConfig::default());
+    assert!(matches!(ouverture.ouvrir(), Err(AppError::Locked)));
+    assert!(ouverture.deverrouiller("phrase secrète").is_err());
+    assert!(ouverture.est_verrouillee());
+}
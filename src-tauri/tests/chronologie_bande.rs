@@ -0,0 +1,85 @@
+//! Chronologie d'une bande: âge du jour, semaine en cours et étapes prévues
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, TestDb};
+use tauri_app_lib::models::{
+    CreatePhaseAlimentation, CreatePlanSoin, CreateSoin, JALON_ENLEVEMENT, JALON_PHASE_ALIMENTATION, JALON_SOIN,
+};
+use tauri_app_lib::repositories::{PlanSoinsRepository, ProgrammeAlimentationRepository, SoinRepository, SoinRepositoryTrait};
+use tauri_app_lib::services::BandeService;
+
+fn date(jour: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(jour, "%Y-%m-%d").unwrap()
+}
+
+#[tokio::test]
+async fn timeline_gives_age_week_and_milestones_shared_by_batiments() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let vaccin = SoinRepository::new(test_db.storage())
+        .create(CreateSoin { nom: "Newcastle".to_string(), unit: "dose".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+
+    let conn = test_db.db.get_connection().unwrap();
+    PlanSoinsRepository::create(
+        &conn,
+        &CreatePlanSoin {
+            poussin_id: fixtures.poussin_id,
+            soin_id: vaccin.id.unwrap(),
+            jour_debut: 7,
+            jour_fin: 7,
+            quantite: None,
+            unit: None,
+        },
+    )
+    .unwrap();
+    for (nom, jour_fin) in [("Démarrage", 10), ("Croissance", 24)] {
+        ProgrammeAlimentationRepository::create(
+            &conn,
+            &CreatePhaseAlimentation {
+                poussin_id: fixtures.poussin_id,
+                nom_phase: nom.to_string(),
+                jour_debut: None,
+                jour_fin,
+                grammes_par_sujet: 50.0,
+            },
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    // Bande entrée le 2024-03-01: le 23 mars est son 23e jour
+    let service = BandeService::new(test_db.storage());
+    let chronologie = service.get_bande_timeline(fixtures.bande_id, date("2024-03-23")).await.unwrap();
+    assert_eq!(chronologie.age_jours, 23);
+    assert_eq!(chronologie.semaine_courante, 4);
+    assert_eq!(chronologie.age_enlevement, 56);
+    assert_eq!(chronologie.date_enlevement, date("2024-04-25"));
+
+    let jalons: Vec<_> = chronologie
+        .jalons
+        .iter()
+        .map(|j| (j.age, j.type_jalon.as_str(), j.libelle.as_str(), j.passe))
+        .collect();
+    assert_eq!(
+        jalons,
+        vec![
+            (1, JALON_PHASE_ALIMENTATION, "Phase Démarrage", true),
+            (7, JALON_SOIN, "Newcastle", true),
+            (11, JALON_PHASE_ALIMENTATION, "Phase Croissance", true),
+            (56, JALON_ENLEVEMENT, "Enlèvement prévu", false),
+        ]
+    );
+    assert!(chronologie.jalons.iter().all(|j| j.batiment_ids == fixtures.batiment_ids));
+    assert_eq!(chronologie.jalons[1].date, date("2024-03-07"));
+
+    // Avant l'entrée de la bande
+    let chronologie = service.get_bande_timeline(fixtures.bande_id, date("2024-02-20")).await.unwrap();
+    assert_eq!((chronologie.age_jours, chronologie.semaine_courante), (0, 0));
+    assert!(chronologie.jalons.iter().all(|j| !j.passe));
+
+    assert!(service.get_bande_timeline(9999, date("2024-03-23")).await.is_err());
+}
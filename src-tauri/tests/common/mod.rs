@@ -0,0 +1,173 @@
+//! Outils partagés par les tests d'intégration
+//!
+//! Chaque test ouvre sa propre base SQLite dans un fichier temporaire, avec
+//! la même configuration que l'application (WAL, clés étrangères), puis la
+//! remplit via les services et repositories publics.
+
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri_app_lib::database::{DatabaseManager, Storage};
+use tauri_app_lib::models::{
+    CreateBande, CreateBatiment, CreateFerme, CreateInvitation, CreatePersonnel, CreatePoussin,
+    ROLE_TECHNICIEN,
+};
+use tauri_app_lib::repositories::{
+    BandeRepository, BatimentRepository, PersonnelRepository, PersonnelRepositoryTrait,
+    PoussinRepository, PoussinRepositoryTrait,
+};
+use tauri_app_lib::services::{AuthService, FermeService, SemaineService};
+use uuid::Uuid;
+
+/// Base de données temporaire supprimée à la fin du test
+pub struct TestDb {
+    pub db: Arc<DatabaseManager>,
+    dir: PathBuf,
+}
+
+impl TestDb {
+    /// Crée un fichier SQLite dans un répertoire temporaire unique et initialise le schéma
+    pub fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("geema-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("Impossible de créer le répertoire de test");
+        let db = Arc::new(
+            DatabaseManager::new(dir.join("geema.db")).expect("Impossible d'ouvrir la base de test"),
+        );
+        db.initialize_schema().expect("Impossible d'initialiser le schéma");
+        Self { db, dir }
+    }
+
+    /// Retourne le stockage sous forme de trait objet, comme l'attendent les services
+    pub fn storage(&self) -> Arc<dyn Storage> {
+        self.db.clone()
+    }
+
+    /// Répertoire temporaire du test, supprimé avec la base
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Compte les lignes d'une table correspondant à une condition SQL
+    pub fn count(&self, table: &str, condition: &str) -> i64 {
+        let conn = self.db.get_connection().unwrap();
+        conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE {}", table, condition),
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+}
+
+impl Drop for TestDb {
+    // Le répertoire entier est supprimé: le pool peut encore ouvrir des
+    // connexions en arrière-plan, qui échoueront au lieu de recréer la base
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Identifiants des données créées par `seed`
+pub struct Fixtures {
+    pub ferme_id: i64,
+    pub bande_id: i64,
+    pub batiment_ids: Vec<i64>,
+    pub personnel_id: i64,
+    pub poussin_id: i64,
+}
+
+/// Crée une ferme avec une bande de deux bâtiments et leurs 8 semaines de suivi
+pub async fn seed(test_db: &TestDb) -> Fixtures {
+    let storage = test_db.storage();
+
+    // Noms uniques: `seed` peut être appelé plusieurs fois sur la même base
+    let suffix = Uuid::new_v4().simple().to_string();
+
+    let ferme = FermeService::new(storage.clone())
+        .create_ferme(CreateFerme { nom: format!("Ferme {}", suffix), nbr_meuble: 4 })
+        .await
+        .unwrap();
+    let ferme_id = ferme.id.unwrap();
+
+    let personnel = PersonnelRepository::new(storage.clone())
+        .create(CreatePersonnel { nom: format!("Technicien {}", suffix), telephone: "0612345678".to_string() })
+        .await
+        .unwrap();
+    let poussin = PoussinRepository::new(storage.clone())
+        .create(CreatePoussin { nom: format!("Cobb 500 {}", suffix) })
+        .await
+        .unwrap();
+    let personnel_id = personnel.id.unwrap();
+    let poussin_id = poussin.id.unwrap();
+
+    let conn = test_db.db.get_connection().unwrap();
+    let bande = BandeRepository::create(
+        &conn,
+        &CreateBande {
+            date_entree: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            ferme_id,
+            notes: None,
+            champs_personnalises: Default::default(),
+        },
+        None,
+    )
+    .unwrap();
+    let bande_id = bande.id.unwrap();
+
+    let mut batiment_ids = Vec::new();
+    for numero in ["1", "2"] {
+        let batiment = BatimentRepository::create(
+            &conn,
+            &CreateBatiment {
+                bande_id,
+                numero_batiment: numero.to_string(),
+                poussin_id,
+                personnel_id,
+                quantite: 5000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            },
+            None,
+        )
+        .unwrap();
+        batiment_ids.push(batiment.id.unwrap());
+    }
+    drop(conn);
+
+    let semaine_service = SemaineService::new(storage);
+    for batiment_id in &batiment_ids {
+        semaine_service.initialize_batiment_semaines(*batiment_id).await.unwrap();
+    }
+
+    Fixtures { ferme_id, bande_id, batiment_ids, personnel_id, poussin_id }
+}
+
+/// Retourne l'ID de la semaine `numero_semaine` d'un bâtiment
+pub fn semaine_id(test_db: &TestDb, batiment_id: i64, numero_semaine: i32) -> i64 {
+    let conn = test_db.db.get_connection().unwrap();
+    conn.query_row(
+        "SELECT id FROM semaines WHERE batiment_id = ?1 AND numero_semaine = ?2",
+        [batiment_id, numero_semaine as i64],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+/// Crée un code d'invitation à usage unique pour un compte technicien
+pub async fn invitation(test_db: &TestDb, admin_token: &str) -> String {
+    AuthService::new(test_db.storage())
+        .create_invitation(
+            CreateInvitation {
+                code: None,
+                role: ROLE_TECHNICIEN.to_string(),
+                expires_on: None,
+                single_use: true,
+            },
+            admin_token,
+        )
+        .await
+        .unwrap()
+        .code
+}
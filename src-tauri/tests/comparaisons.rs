@@ -0,0 +1,211 @@
+//! Rapports de comparaison des performances
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::services::ComparaisonService;
+
+#[tokio::test]
+async fn compare_fermes_aggregates_batiment_results() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let autre = seed(&test_db).await;
+
+    // Bâtiment 1: 100 morts, 196 sachets (9 800 kg), 2 kg en semaine 5
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_5 = semaine_id(&test_db, fixtures.batiment_ids[0], 5);
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, alimentation_par_jour) VALUES (?1, 1, 60, 96), (?1, 2, 40, 100)",
+            [semaine_1],
+        ).unwrap();
+        conn.execute("UPDATE semaines SET poids = 2.0 WHERE id = ?1", [semaine_5]).unwrap();
+    }
+
+    let service = ComparaisonService::new(test_db.storage());
+    let comparaison = service.compare_fermes(None, None).await.unwrap();
+    assert_eq!(comparaison.len(), 2);
+
+    let ferme = comparaison.iter().find(|c| c.id == fixtures.ferme_id).unwrap();
+    let kpi = &ferme.indicateurs;
+    assert_eq!(kpi.nombre_bandes, 1);
+    assert_eq!(kpi.nombre_batiments, 2);
+    assert_eq!(kpi.poussins_places, 10000);
+    assert_eq!(kpi.total_deces, 100);
+    assert!((kpi.mortalite_pourcentage.unwrap() - 1.0).abs() < 1e-9);
+    assert!((kpi.aliment_kg - 9800.0).abs() < 1e-9);
+    // Seul le bâtiment pesé compte: 9 800 kg d'aliment pour 4 900 sujets à 2 kg
+    assert!((kpi.indice_consommation.unwrap() - 1.0).abs() < 1e-9);
+    assert!((kpi.poids_moyen.unwrap() - 2.0).abs() < 1e-9);
+    assert_eq!(kpi.cout_par_kg, None);
+
+    let sans_suivi = comparaison.iter().find(|c| c.id == autre.ferme_id).unwrap();
+    assert_eq!(sans_suivi.indicateurs.mortalite_pourcentage, Some(0.0));
+    assert_eq!(sans_suivi.indicateurs.indice_consommation, None);
+    assert_eq!(sans_suivi.indicateurs.poids_moyen, None);
+
+    // Aucune bande entrée sur la période: les fermes restent listées, sans résultats
+    let periode = service
+        .compare_fermes(Some("2024-04-01".to_string()), Some("2024-06-30".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(periode.len(), 2);
+    assert!(periode.iter().all(|c| c.indicateurs.nombre_batiments == 0 && c.indicateurs.mortalite_pourcentage.is_none()));
+}
+
+#[tokio::test]
+async fn compare_personnel_reports_weight_at_35_days() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let autre = seed(&test_db).await;
+
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        // Le second bâtiment de la première ferme passe au technicien de la seconde
+        conn.execute(
+            "UPDATE batiments SET personnel_id = ?1 WHERE id = ?2",
+            [autre.personnel_id, fixtures.batiment_ids[1]],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, 1, 1000)",
+            [semaine_id(&test_db, fixtures.batiment_ids[0], 1)],
+        ).unwrap();
+        conn.execute("UPDATE semaines SET poids = 1.8 WHERE id = ?1", [semaine_id(&test_db, fixtures.batiment_ids[0], 5)]).unwrap();
+        conn.execute("UPDATE semaines SET poids = 2.1 WHERE id = ?1", [semaine_id(&test_db, autre.batiment_ids[0], 5)]).unwrap();
+        conn.execute("UPDATE semaines SET poids = 2.4 WHERE id = ?1", [semaine_id(&test_db, autre.batiment_ids[0], 6)]).unwrap();
+    }
+
+    let comparaison = ComparaisonService::new(test_db.storage())
+        .compare_personnel(None, None)
+        .await
+        .unwrap();
+    assert_eq!(comparaison.len(), 2);
+
+    let premier = &comparaison.iter().find(|c| c.id == fixtures.personnel_id).unwrap().indicateurs;
+    assert_eq!(premier.nombre_batiments, 1);
+    assert!((premier.mortalite_pourcentage.unwrap() - 20.0).abs() < 1e-9);
+    assert!((premier.poids_35j.unwrap() - 1.8).abs() < 1e-9);
+
+    // Trois bâtiments sur deux bandes; seul le bâtiment pesé à 35 jours compte dans la moyenne
+    let second = &comparaison.iter().find(|c| c.id == autre.personnel_id).unwrap().indicateurs;
+    assert_eq!(second.nombre_bandes, 2);
+    assert_eq!(second.nombre_batiments, 3);
+    assert_eq!(second.total_deces, 0);
+    assert!((second.poids_35j.unwrap() - 2.1).abs() < 1e-9);
+    assert!((second.poids_moyen.unwrap() - 2.4).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn compare_poussins_groups_batiments_by_chick_source() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let autre = seed(&test_db).await;
+
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, 1, 300)",
+            [semaine_id(&test_db, fixtures.batiment_ids[0], 1)],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, 1, 50)",
+            [semaine_id(&test_db, autre.batiment_ids[0], 1)],
+        ).unwrap();
+    }
+
+    let comparaison = ComparaisonService::new(test_db.storage())
+        .compare_poussins(Some("2024-01-01".to_string()), Some("2024-12-31".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(comparaison.len(), 2);
+
+    let premier = &comparaison.iter().find(|c| c.id == fixtures.poussin_id).unwrap().indicateurs;
+    assert_eq!(premier.poussins_places, 10000);
+    assert!((premier.mortalite_pourcentage.unwrap() - 3.0).abs() < 1e-9);
+
+    let second = &comparaison.iter().find(|c| c.id == autre.poussin_id).unwrap().indicateurs;
+    assert!((second.mortalite_pourcentage.unwrap() - 0.5).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn production_index_combines_livability_weight_age_and_fcr() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+
+    // Bâtiment 1: 98 % de viabilité, 2 kg à 35 jours, indice de consommation 1,0
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, alimentation_par_jour) VALUES (?1, 1, 60, 96), (?1, 2, 40, 100)",
+            [semaine_id(&test_db, fixtures.batiment_ids[0], 1)],
+        ).unwrap();
+        conn.execute("UPDATE semaines SET poids = 2.0 WHERE id = ?1", [semaine_id(&test_db, fixtures.batiment_ids[0], 5)]).unwrap();
+    }
+
+    let service = ComparaisonService::new(test_db.storage());
+    let indice = service.get_production_index(fixtures.bande_id).await.unwrap();
+    assert_eq!(indice.batiments.len(), 2);
+
+    // 98 × 2 / (35 × 1,0) × 100
+    let pese = indice.batiments.iter().find(|b| b.id == fixtures.batiment_ids[0]).unwrap();
+    assert!((pese.indicateurs.indice_production.unwrap() - 560.0).abs() < 1e-9);
+    let non_pese = indice.batiments.iter().find(|b| b.id == fixtures.batiment_ids[1]).unwrap();
+    assert_eq!(non_pese.indicateurs.indice_production, None);
+
+    // La bande ne retient que ses bâtiments pesés
+    assert!((indice.indicateurs.indice_production.unwrap() - 560.0).abs() < 1e-9);
+    let fermes = service.compare_fermes(None, None).await.unwrap();
+    assert!((fermes[0].indicateurs.indice_production.unwrap() - 560.0).abs() < 1e-9);
+
+    assert!(service.get_production_index(fixtures.bande_id + 100).await.is_err());
+}
+
+#[tokio::test]
+async fn seasonal_statistics_group_closed_bandes_by_entry_month() {
+    let test_db = TestDb::new();
+    let printemps = seed(&test_db).await;
+    let ete = seed(&test_db).await;
+    let en_cours = seed(&test_db).await;
+
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute(
+            "UPDATE bandes SET statut = 'cloturee', date_cloture = '2024-04-26' WHERE id = ?1",
+            [printemps.bande_id],
+        ).unwrap();
+        conn.execute(
+            "UPDATE bandes SET date_entree = '2024-07-10', statut = 'cloturee', date_cloture = '2024-09-04' WHERE id = ?1",
+            [ete.bande_id],
+        ).unwrap();
+        // Chaleur: 500 morts sur les 10 000 sujets de la bande d'été
+        conn.execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, 30, 500)",
+            [semaine_id(&test_db, ete.batiment_ids[0], 5)],
+        ).unwrap();
+    }
+
+    let service = ComparaisonService::new(test_db.storage());
+    let statistiques = service.get_seasonal_statistics(None).await.unwrap();
+    assert_eq!(statistiques.mois.len(), 12);
+    assert_eq!(statistiques.mois[2].periode, "03");
+    assert_eq!(statistiques.mois[2].indicateurs.nombre_bandes, 1);
+    assert_eq!(statistiques.mois[6].indicateurs.nombre_bandes, 1);
+    assert!((statistiques.mois[6].indicateurs.mortalite_pourcentage.unwrap() - 5.0).abs() < 1e-9);
+
+    let saisons: Vec<(&str, i64)> = statistiques
+        .saisons
+        .iter()
+        .map(|s| (s.periode.as_str(), s.indicateurs.nombre_bandes))
+        .collect();
+    // La bande encore en cours n'est pas comptée
+    assert_eq!(saisons, vec![("hiver", 0), ("printemps", 1), ("ete", 1), ("automne", 0)]);
+    assert_eq!(statistiques.saisons[1].indicateurs.mortalite_pourcentage, Some(0.0));
+
+    let ferme = service.get_seasonal_statistics(Some(ete.ferme_id)).await.unwrap();
+    assert_eq!(ferme.saisons[1].indicateurs.nombre_bandes, 0);
+    assert_eq!(ferme.saisons[2].indicateurs.nombre_bandes, 1);
+    assert!(service.get_seasonal_statistics(Some(en_cours.ferme_id)).await.unwrap()
+        .saisons.iter().all(|s| s.indicateurs.nombre_bandes == 0));
+    assert!(service.get_seasonal_statistics(Some(9999)).await.is_err());
+}
@@ -0,0 +1,102 @@
+//! Contrôle de concurrence optimiste: une modification envoyée avec une version
+//! périmée est refusée
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::error::{AppError, ErreurCommande};
+use tauri_app_lib::models::{BandeLoadOptions, UpdateBande, UpdateSuiviQuotidien};
+use tauri_app_lib::repositories::{BandeRepository, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::BandeService;
+
+#[tokio::test]
+async fn stale_bande_update_is_rejected_with_a_conflict() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = BandeService::new(test_db.storage());
+
+    // Deux utilisateurs ouvrent la même bande
+    let chargee = {
+        let conn = test_db.db.get_connection().unwrap();
+        BandeRepository::get_by_id(&conn, fixtures.bande_id, &BandeLoadOptions::default()).unwrap().unwrap()
+    };
+    let modification = |notes: &str| UpdateBande {
+        id: fixtures.bande_id,
+        numero_bande: chargee.numero_bande,
+        date_entree: chargee.date_entree,
+        ferme_id: chargee.ferme_id,
+        notes: Some(notes.to_string()),
+        version: Some(chargee.version),
+        champs_personnalises: Default::default(),
+    };
+
+    service.update_bande(fixtures.bande_id, modification("Premier")).await.unwrap();
+    let erreur = service.update_bande(fixtures.bande_id, modification("Second")).await.unwrap_err();
+    match erreur {
+        AppError::Conflict { expected, current, .. } => {
+            assert_eq!(expected, chargee.version);
+            assert_eq!(current, chargee.version + 1);
+        }
+        autre => panic!("conflit attendu, obtenu: {}", autre),
+    }
+    assert_eq!(test_db.count("bandes", "notes = 'Premier'"), 1);
+
+    // Le frontend reçoit le conflit sous forme structurée
+    let erreur = service.update_bande(fixtures.bande_id, modification("Second")).await.unwrap_err();
+    let json = serde_json::to_value(ErreurCommande::from(erreur)).unwrap();
+    assert_eq!(json["kind"], "conflict");
+    assert_eq!(json["entity"], "Bande");
+    assert_eq!(json["id"], fixtures.bande_id);
+    assert_eq!(json["expected"], chargee.version);
+    assert_eq!(json["current"], chargee.version + 1);
+
+    // Sans version (anciens écrans), la dernière écriture l'emporte
+    let mut sans_version = modification("Second");
+    sans_version.version = None;
+    service.update_bande(fixtures.bande_id, sans_version).await.unwrap();
+    assert_eq!(test_db.count("bandes", "notes = 'Second'"), 1);
+}
+
+#[tokio::test]
+async fn any_write_to_a_row_makes_older_versions_stale() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let repository = SuiviQuotidienRepository::new(test_db.storage());
+
+    let chargee = repository.upsert_field(semaine, 1, "deces_par_jour", "3").await.unwrap();
+    // Saisie d'une cellule par un autre utilisateur
+    let saisie = repository.upsert_field(semaine, 1, "deces_par_jour", "5").await.unwrap();
+    assert_eq!(saisie.version, chargee.version + 1);
+
+    let modification = |version| UpdateSuiviQuotidien {
+        id: chargee.id.unwrap(),
+        semaine_id: semaine,
+        age: 1,
+        deces_par_jour: Some(4),
+        elimines_par_jour: None,
+        alimentation_par_jour: None,
+        soins_id: None,
+        soins_quantite: None,
+        analyses: None,
+        remarques: None,
+        version: Some(version),
+    };
+
+    let erreur = repository.update(modification(chargee.version)).await.unwrap_err();
+    assert!(matches!(erreur, AppError::Conflict { .. }), "{}", erreur);
+
+    let modifie = repository.update(modification(saisie.version)).await.unwrap();
+    assert_eq!(modifie.version, saisie.version + 1);
+    assert_eq!(modifie.deces_par_jour, Some(4));
+
+    // Sans version, la modification écrase la saisie concurrente
+    let mut sans_version = modification(chargee.version);
+    sans_version.version = None;
+    sans_version.deces_par_jour = Some(6);
+    let ecrase = repository.update(sans_version).await.unwrap();
+    assert_eq!(ecrase.deces_par_jour, Some(6));
+
+    let erreur = ErreurCommande::from(AppError::validation_error("age", "Âge invalide"));
+    assert_eq!((erreur.kind, erreur.message.as_str(), erreur.expected), ("validation", "Âge invalide", None));
+}
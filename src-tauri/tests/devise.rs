@@ -0,0 +1,74 @@
+//! Devise des prix: paramètre, format des montants et devise conservée avec chaque prix
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::{
+    formater_montant, montant_csv, trouver_devise, CreateAlimentationHistory, CreatePrixHistorique, ARTICLE_ALIMENT,
+};
+use tauri_app_lib::repositories::{AlimentationRepository, ExportRepository, PrixRepository};
+
+#[test]
+fn amounts_are_formatted_in_french_with_the_currency_decimals() {
+    let mad = trouver_devise("mad").unwrap();
+    let xof = trouver_devise("XOF").unwrap();
+    assert_eq!(formater_montant(1234.5, &mad), "1\u{a0}234,50\u{a0}DH");
+    assert_eq!(formater_montant(-1234567.891, &mad), "-1\u{a0}234\u{a0}567,89\u{a0}DH");
+    assert_eq!(formater_montant(-0.001, &mad), "0,00\u{a0}DH");
+    assert_eq!(formater_montant(150000.4, &xof), "150\u{a0}000\u{a0}FCFA");
+    assert_eq!(montant_csv(1234.5, &mad), "1234,50");
+    assert!(trouver_devise("GBP").is_none());
+}
+
+#[tokio::test]
+async fn prices_keep_the_currency_they_were_recorded_in() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+    let prix = |prix_unitaire: f64| CreatePrixHistorique {
+        article: ARTICLE_ALIMENT.to_string(),
+        poussin_id: None,
+        prix_unitaire,
+        date_debut: "2024-01-01".to_string(),
+    };
+    let livraison = |created_at: &str| {
+        AlimentationRepository::create(
+            &conn,
+            &CreateAlimentationHistory {
+                bande_id: fixtures.bande_id,
+                quantite: 1000.0,
+                created_at: created_at.to_string(),
+                confirmer_doublon: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+
+    assert_eq!(PrixRepository::get_devise(&conn).unwrap().code, "MAD");
+    assert_eq!(PrixRepository::create(&conn, &prix(5.5)).unwrap().devise, "MAD");
+    let en_dirhams = livraison("2024-03-02 08:00:00");
+    assert_eq!((en_dirhams.prix_unitaire, en_dirhams.devise.as_deref()), (Some(5.5), Some("MAD")));
+
+    assert!(PrixRepository::set_devise(&conn, "GBP").is_err());
+    assert_eq!(PrixRepository::set_devise(&conn, " eur ").unwrap().code, "EUR");
+
+    // Le prix en dirhams ne s'applique plus; l'historique garde sa devise
+    let sans_prix = livraison("2024-03-03 08:00:00");
+    assert_eq!((sans_prix.prix_unitaire, sans_prix.devise), (None, None));
+    PrixRepository::create(&conn, &prix(0.5)).unwrap();
+    let en_euros = livraison("2024-03-04 08:00:00");
+    assert_eq!((en_euros.prix_unitaire, en_euros.devise.as_deref()), (Some(0.5), Some("EUR")));
+    let historique = PrixRepository::get_historique(&conn, ARTICLE_ALIMENT, None).unwrap();
+    assert_eq!(historique.iter().map(|p| p.devise.as_str()).collect::<Vec<_>>(), vec!["EUR", "MAD"]);
+
+    // Le journal comptable donne chaque montant dans sa devise d'origine
+    let journal = ExportRepository::accounting_ledger(&conn).unwrap();
+    assert_eq!(journal[0].last().map(String::as_str), Some("devise"));
+    let montants: Vec<_> = journal[1..]
+        .iter()
+        .filter(|ligne| ligne[3] == "aliment (kg)")
+        .map(|ligne| (ligne[6].as_str(), ligne[7].as_str()))
+        .collect();
+    assert_eq!(montants, vec![("5500,00", "MAD"), ("", ""), ("500,00", "EUR")]);
+}
@@ -0,0 +1,120 @@
+//! Export et import d'une bande au format d'échange JSON
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateSoin, CreateSuiviSoin, EchangeBande, FORMAT_ECHANGE_BANDE, VERSION_ECHANGE_BANDE};
+use tauri_app_lib::repositories::{
+    SoinRepository, SoinRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{ExportService, ImportService};
+
+fn lire(chemin: &std::path::Path) -> EchangeBande {
+    serde_json::from_str(&std::fs::read_to_string(chemin).unwrap()).unwrap()
+}
+
+fn ecrire(chemin: &std::path::Path, document: &EchangeBande) -> String {
+    std::fs::write(chemin, serde_json::to_string(document).unwrap()).unwrap();
+    chemin.to_string_lossy().into_owned()
+}
+
+#[tokio::test]
+async fn exported_bande_round_trips_through_the_exchange_format() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    suivi_repo.upsert_field(semaine, 2, "deces_par_jour", "12").await.unwrap();
+    suivi_repo.upsert_field(semaine, 2, "alimentation_par_jour", "3.5").await.unwrap();
+    let soin = SoinRepository::new(test_db.storage())
+        .create(CreateSoin { nom: "Vitamine AD3E".to_string(), unit: "ml".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    suivi_repo.add_soin(CreateSuiviSoin {
+        semaine_id: semaine,
+        age: 3,
+        soin_id: soin.id,
+        quantite: Some("250".to_string()),
+        unit: None,
+    }).await.unwrap();
+
+    let export = ExportService::new(test_db.storage());
+    let chemin = test_db.dir().join("bande.json");
+    export.export_bande_json(fixtures.bande_id, &chemin.to_string_lossy()).await.unwrap();
+    assert!(export.export_bande_json(9999, &chemin.to_string_lossy()).await.is_err());
+
+    let document = lire(&chemin);
+    assert_eq!((document.format.as_str(), document.version), (FORMAT_ECHANGE_BANDE, VERSION_ECHANGE_BANDE));
+    assert_eq!(document.bande.date_entree, "2024-03-01");
+    assert_eq!(document.bande.batiments.len(), 2);
+    let premiere_semaine = &document.bande.batiments[0].semaines[0];
+    let jour = |age: i32| premiere_semaine.jours.iter().find(|j| j.age == age).unwrap();
+    assert_eq!((jour(2).deces, jour(2).aliment), (Some(12), Some(3.5)));
+    assert_eq!(jour(3).soins[0].soin, "Vitamine AD3E");
+    assert_eq!(jour(3).soins[0].unite.as_deref(), Some("ml"));
+
+    // Même ferme, même date d'entrée: la bande existe déjà
+    let import = ImportService::new(test_db.storage());
+    assert!(import.import_bande_json(&chemin.to_string_lossy(), None).await.is_err());
+
+    let mut copie = document.clone();
+    copie.bande.date_entree = "2024-06-01".to_string();
+    let rapport = import.import_bande_json(&ecrire(&test_db.dir().join("copie.json"), &copie), None).await.unwrap();
+    assert_eq!(rapport.version, VERSION_ECHANGE_BANDE);
+    assert_eq!(rapport.batiments, 2);
+    assert_eq!(rapport.soins, 1);
+
+    let reexport = test_db.dir().join("reexport.json");
+    export.export_bande_json(rapport.bande_id, &reexport.to_string_lossy()).await.unwrap();
+    let reimporte = lire(&reexport);
+    assert_eq!(reimporte.bande.date_entree, "2024-06-01");
+    assert_eq!(
+        serde_json::to_value(&reimporte.bande.batiments).unwrap(),
+        serde_json::to_value(&document.bande.batiments).unwrap()
+    );
+    assert_eq!(test_db.count("suivi_soins", "quantite_valeur = 250"), 2);
+}
+
+#[tokio::test]
+async fn unknown_versions_and_references_are_rejected() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let chemin = test_db.dir().join("bande.json");
+    ExportService::new(test_db.storage())
+        .export_bande_json(fixtures.bande_id, &chemin.to_string_lossy())
+        .await
+        .unwrap();
+    let mut document = lire(&chemin);
+    document.bande.date_entree = "2024-06-01".to_string();
+    let import = ImportService::new(test_db.storage());
+    let importer = |document: &EchangeBande, nom: &str| {
+        let chemin = ecrire(&test_db.dir().join(nom), document);
+        let import = &import;
+        async move { import.import_bande_json(&chemin, None).await }
+    };
+
+    let mut future = document.clone();
+    future.version = VERSION_ECHANGE_BANDE + 1;
+    assert!(importer(&future, "future.json").await.unwrap_err().to_string().contains("Version"));
+
+    let mut autre = document.clone();
+    autre.format = "autre/format".to_string();
+    assert!(importer(&autre, "autre.json").await.is_err());
+
+    let mut souche = document.clone();
+    souche.bande.batiments[1].souche = "Ross 308".to_string();
+    assert!(importer(&souche, "souche.json").await.unwrap_err().to_string().contains("Ross 308"));
+
+    let mut age = document.clone();
+    age.bande.batiments[0].semaines[1].jours.push(serde_json::from_str(r#"{"age": 3}"#).unwrap());
+    assert!(importer(&age, "age.json").await.is_err());
+
+    // Rien n'a été enregistré par les imports refusés
+    assert_eq!(test_db.count("bandes", &format!("ferme_id = {}", fixtures.ferme_id)), 1);
+    std::fs::write(test_db.dir().join("invalide.json"), "{").unwrap();
+    assert!(import.import_bande_json(&test_db.dir().join("invalide.json").to_string_lossy(), None).await.is_err());
+
+    importer(&document, "bande.json").await.unwrap();
+    assert_eq!(test_db.count("bandes", &format!("ferme_id = {}", fixtures.ferme_id)), 2);
+}
@@ -0,0 +1,176 @@
+//! Écritures concurrentes: plusieurs fenêtres partagent le même gestionnaire de base
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, TestDb};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tauri_app_lib::database::{DatabaseConfig, DatabaseManager};
+use tauri_app_lib::error::AppError;
+use tauri_app_lib::models::{CreateBande, CreateBatiment, CreatePersonnel, CreateUser, LoginUser, UpdateFerme};
+use tauri_app_lib::repositories::{
+    FermeRepository, FermeRepositoryTrait, PersonnelRepository, PersonnelRepositoryTrait,
+};
+use tauri_app_lib::services::{AuthService, BandeService};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_bande_creations_never_hit_a_locked_database() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+
+    let creations = (0..12).map(|_| {
+        let service = BandeService::new(test_db.storage());
+        tokio::spawn(async move {
+            service
+                .create_bande_with_batiments_and_first_week(
+                    CreateBande {
+                        date_entree: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+                        ferme_id: fixtures.ferme_id,
+                        notes: None,
+                        champs_personnalises: Default::default(),
+                    },
+                    vec![CreateBatiment {
+                        bande_id: 0,
+                        numero_batiment: "3".to_string(),
+                        poussin_id: fixtures.poussin_id,
+                        personnel_id: fixtures.personnel_id,
+                        quantite: 4000,
+                        autoriser_cohabitation: false,
+                        champs_personnalises: Default::default(),
+                    }],
+                    None,
+                )
+                .await
+        })
+    });
+
+    let mut numeros = HashSet::new();
+    for creation in creations.collect::<Vec<_>>() {
+        let bande = creation.await.unwrap().expect("aucune écriture ne doit échouer");
+        numeros.insert(bande.numero_bande);
+    }
+
+    // Chaque bande a reçu son propre numéro, avec sa semaine 1 et ses 7 jours de suivi
+    assert_eq!(numeros.len(), 12);
+    let nouvelles_semaines = format!("batiment_id IN (SELECT id FROM batiments WHERE bande_id != {})", fixtures.bande_id);
+    assert_eq!(test_db.count("semaines", &nouvelles_semaines), 12);
+    assert_eq!(
+        test_db.count("suivi_quotidien", &format!("semaine_id IN (SELECT id FROM semaines WHERE {})", nouvelles_semaines)),
+        12 * 7
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_updates_and_sessions_are_neither_busy_nor_lost() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap();
+    let meubles_initiaux = FermeRepository::new(test_db.storage()).get_by_id(fixtures.ferme_id).await.unwrap().nbr_meuble;
+
+    // Chaque tâche ajoute un meuble à la ferme en relisant sa version après un
+    // conflit, crée un membre du personnel et ouvre puis ferme une session
+    let taches = (0..8).map(|i| {
+        let storage = test_db.storage();
+        let ferme_id = fixtures.ferme_id;
+        tokio::spawn(async move {
+            let fermes = FermeRepository::new(storage.clone());
+            loop {
+                let ferme = fermes.get_by_id(ferme_id).await?;
+                let mise_a_jour = UpdateFerme {
+                    id: ferme_id,
+                    nom: ferme.nom,
+                    nbr_meuble: ferme.nbr_meuble + 1,
+                    version: Some(ferme.version),
+                };
+                match fermes.update(mise_a_jour).await {
+                    Ok(_) => break,
+                    Err(AppError::Conflict { .. }) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            PersonnelRepository::new(storage.clone())
+                .create(CreatePersonnel { nom: format!("Ouvrier {}", i), telephone: format!("06000000{:02}", i) })
+                .await?;
+
+            let auth = AuthService::new(storage);
+            let session = auth
+                .login(LoginUser { username: "admin".to_string(), password: "motdepasse123".to_string(), totp_code: None })
+                .await?;
+            auth.logout(&session.token).await
+        })
+    });
+
+    for tache in taches.collect::<Vec<_>>() {
+        tache.await.unwrap().expect("aucune écriture ne doit échouer (base verrouillée)");
+    }
+
+    let ferme = FermeRepository::new(test_db.storage()).get_by_id(fixtures.ferme_id).await.unwrap();
+    assert_eq!(ferme.nbr_meuble, meubles_initiaux + 8);
+    assert_eq!(test_db.count("personnel", "nom LIKE 'Ouvrier %'"), 8);
+    assert_eq!(test_db.count("audit_log", "action = 'connexion'"), 8);
+    // Seule la session ouverte à l'inscription reste
+    assert_eq!(test_db.count("sessions", "1"), 1);
+}
+
+#[tokio::test]
+async fn failed_write_is_rolled_back() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+
+    let resultat: Result<(), AppError> = test_db.storage().write(|tx| {
+        tx.execute("DELETE FROM semaines WHERE batiment_id = ?1", [fixtures.batiment_ids[0]])?;
+        Err(AppError::business_logic("Écriture refusée"))
+    });
+
+    assert!(resultat.is_err());
+    assert_eq!(test_db.count("semaines", &format!("batiment_id = {}", fixtures.batiment_ids[0])), 8);
+}
+
+#[test]
+fn busy_timeout_is_configurable() {
+    let test_db = TestDb::new();
+    let db = DatabaseManager::with_config(
+        test_db.dir().join("config.db"),
+        DatabaseConfig { busy_timeout: Duration::from_millis(250), ..DatabaseConfig::default() },
+    )
+    .unwrap();
+
+    let conn = db.get_connection().unwrap();
+    let busy_timeout: i64 = conn.query_row("PRAGMA busy_timeout", [], |row| row.get(0)).unwrap();
+    assert_eq!(busy_timeout, 250);
+}
+
+#[test]
+fn stateless_repositories_leave_the_transaction_to_their_caller() {
+    let dossier = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/repositories");
+    let mut ecarts = Vec::new();
+    for entree in fs::read_dir(&dossier).unwrap() {
+        let chemin = entree.unwrap().path();
+        let source = fs::read_to_string(&chemin).unwrap();
+        // Dépôts sans état: structure unitaire ou connexion empruntée
+        let sans_etat = source
+            .lines()
+            .any(|ligne| (ligne.starts_with("pub struct") && ligne.ends_with("Repository;")) || ligne.trim() == "conn: &'a Connection,");
+        if !sans_etat {
+            continue;
+        }
+        for appel in ["get_connection(", ".write(", ".transaction("] {
+            if source.contains(appel) {
+                ecarts.push(format!("{}: {}", chemin.file_name().unwrap().to_string_lossy(), appel));
+            }
+        }
+    }
+    assert!(ecarts.is_empty(), "dépôt sans état ouvrant sa propre connexion ou transaction: {:?}", ecarts);
+}
@@ -0,0 +1,139 @@
+//! Équipements des fermes et rappels d'entretien
+
+mod common;
+
+use chrono::{Days, Local};
+use common::{seed, TestDb};
+use tauri_app_lib::models::{
+    CreateEntretienEquipement, CreateEquipement, CreateFerme, UpdateEquipement, ALERTE_ENTRETIEN_EQUIPEMENT, GRAVITE_WARNING,
+};
+use tauri_app_lib::repositories::{EquipementRepository, EquipementRepositoryTrait};
+use tauri_app_lib::services::{AlerteService, FermeService};
+
+#[tokio::test]
+async fn overdue_and_upcoming_maintenance_raise_alerts() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let repo = EquipementRepository::new(test_db.storage());
+    let aujourd_hui = Local::now().date_naive();
+    let dans = |jours: u64| (aujourd_hui + Days::new(jours)).format("%Y-%m-%d").to_string();
+
+    let equipement = |nom: &str, type_equipement: &str, numero_batiment: Option<&str>, intervalle: Option<i64>| {
+        CreateEquipement {
+            ferme_id: fixtures.ferme_id,
+            nom: nom.to_string(),
+            type_equipement: type_equipement.to_string(),
+            numero_batiment: numero_batiment.map(str::to_string),
+            date_mise_en_service: "2024-01-01".to_string(),
+            intervalle_entretien_jours: intervalle,
+        }
+    };
+    let groupe = repo.create(equipement("Groupe", "groupe_electrogene", None, Some(90))).await.unwrap();
+    assert_eq!(groupe.prochain_entretien.as_deref(), Some("2024-03-31"));
+    repo.create(equipement("Forage", "abreuvoir", Some(" "), Some(90))).await.unwrap();
+    let ventilateur = repo.create(equipement("Extracteur", "ventilateur", Some("1"), None)).await.unwrap();
+    assert!(ventilateur.prochain_entretien.is_none());
+
+    assert!(repo.create(equipement("X", "tracteur", None, None)).await.is_err());
+    assert!(repo.create(equipement("X", "autre", Some("9"), None)).await.is_err());
+    assert!(repo.create(equipement("X", "autre", None, Some(0))).await.is_err());
+    assert!(repo.create(equipement(" ", "autre", None, None)).await.is_err());
+    assert!(repo.create(CreateEquipement { ferme_id: 9999, ..equipement("X", "autre", None, None) }).await.is_err());
+
+    // Deux entretiens en retard le même jour: une seule alerte, pour la ferme elle-même
+    let service = AlerteService::new(test_db.storage());
+    let alertes = service.get_pending_alerts(None).await.unwrap();
+    assert_eq!(alertes.len(), 1);
+    assert_eq!(alertes[0].type_alerte, ALERTE_ENTRETIEN_EQUIPEMENT);
+    assert_eq!(alertes[0].gravite, GRAVITE_WARNING);
+    assert_eq!(alertes[0].ferme_id, fixtures.ferme_id);
+    assert_eq!((alertes[0].bande_id, alertes[0].batiment_id), (None, None));
+    assert_eq!(alertes[0].date, "2024-03-31");
+    assert!(alertes[0].message.contains("Groupe") && alertes[0].message.contains("Forage"), "{}", alertes[0].message);
+
+    // L'entretien du groupe repousse son échéance
+    let entretenu = repo.add_entretien(CreateEntretienEquipement {
+        equipement_id: groupe.id,
+        date_entretien: dans(0),
+        description: Some("Vidange, filtres".to_string()),
+        cout: Some(850.0),
+    }).await.unwrap();
+    assert_eq!(entretenu.dernier_entretien, Some(dans(0)));
+    assert_eq!(entretenu.prochain_entretien, Some(dans(90)));
+    assert!(repo.add_entretien(CreateEntretienEquipement {
+        equipement_id: groupe.id,
+        date_entretien: dans(0),
+        cout: Some(-1.0),
+        ..Default::default()
+    }).await.is_err());
+
+    let alertes = service.get_pending_alerts(None).await.unwrap();
+    assert_eq!(alertes.len(), 1);
+    assert!(!alertes[0].message.contains("Groupe"), "{}", alertes[0].message);
+
+    // Entretien bientôt dû dans un bâtiment: rappel de la ferme qui nomme le bâtiment
+    let mut modification = UpdateEquipement {
+        id: ventilateur.id,
+        nom: ventilateur.nom.clone(),
+        type_equipement: ventilateur.type_equipement.clone(),
+        numero_batiment: ventilateur.numero_batiment.clone(),
+        date_mise_en_service: dans(0),
+        intervalle_entretien_jours: Some(5),
+    };
+    assert_eq!(repo.update(modification.clone()).await.unwrap().prochain_entretien, Some(dans(5)));
+    let rappel = service.get_pending_alerts(None).await.unwrap().into_iter()
+        .find(|a| a.date == dans(5))
+        .unwrap();
+    assert_eq!((rappel.bande_id, rappel.batiment_id), (None, None));
+    assert!(rappel.message.contains("Bâtiment 1"), "{}", rappel.message);
+    assert!(rappel.message.contains("prévu"), "{}", rappel.message);
+
+    modification.intervalle_entretien_jours = Some(30);
+    repo.update(modification).await.unwrap();
+    assert_eq!(service.get_pending_alerts(None).await.unwrap().len(), 1);
+
+    let entretiens = repo.get_entretiens(groupe.id).await.unwrap();
+    assert_eq!(entretiens.len(), 1);
+    assert_eq!(entretiens[0].cout, Some(850.0));
+    repo.delete_entretien(entretiens[0].id).await.unwrap();
+    assert!(repo.delete_entretien(entretiens[0].id).await.is_err());
+
+    repo.delete(groupe.id).await.unwrap();
+    assert!(repo.get_entretiens(groupe.id).await.is_err());
+    assert_eq!(repo.get_by_ferme(fixtures.ferme_id).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn a_farm_without_bande_gets_its_maintenance_reminder() {
+    let test_db = TestDb::new();
+    let ferme_id = FermeService::new(test_db.storage())
+        .create_ferme(CreateFerme { nom: "Ferme Vide".to_string(), nbr_meuble: 1 })
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    EquipementRepository::new(test_db.storage())
+        .create(CreateEquipement {
+            ferme_id,
+            nom: "Groupe".to_string(),
+            type_equipement: "groupe_electrogene".to_string(),
+            numero_batiment: None,
+            date_mise_en_service: "2024-01-01".to_string(),
+            intervalle_entretien_jours: Some(90),
+        })
+        .await
+        .unwrap();
+
+    let service = AlerteService::new(test_db.storage());
+    let alertes = service.get_pending_alerts(None).await.unwrap();
+    assert_eq!(alertes.len(), 1);
+    assert_eq!(alertes[0].type_alerte, ALERTE_ENTRETIEN_EQUIPEMENT);
+    assert_eq!((alertes[0].ferme_id, alertes[0].bande_id, alertes[0].batiment_id), (ferme_id, None, None));
+    assert!(alertes[0].id.is_some());
+
+    // Recalculée, l'alerte reste la même entrée de l'historique
+    let relue = service.get_pending_alerts(None).await.unwrap();
+    assert_eq!(relue.len(), 1);
+    assert_eq!(relue[0].id, alertes[0].id);
+    assert_eq!(test_db.count("alertes", "bande_id IS NULL"), 1);
+}
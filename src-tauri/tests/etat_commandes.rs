@@ -0,0 +1,206 @@
+//! État géré par l'application et commandes enregistrées
+//!
+//! Tauri ne vérifie le type d'un `State<'_, T>` qu'à l'appel de la commande:
+//! une commande qui attend `State<'_, DatabaseManager>` alors que seul
+//! `Arc<DatabaseManager>` est géré compile, puis échoue à chaque appel.
+//! Ces tests relisent les sources des commandes pour détecter ces écarts, puis
+//! appellent un échantillon de commandes par l'IPC d'une application simulée
+//! construite comme celle de `run()`.
+
+mod common;
+
+use common::TestDb;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tauri::ipc::{CallbackFn, InvokeBody};
+use tauri::test::{get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime, INVOKE_KEY};
+use tauri::webview::InvokeRequest;
+use tauri::{App, Manager, WebviewWindow, WebviewWindowBuilder};
+use tauri_app_lib::database::{DatabaseConfig, OuvertureBase};
+
+/// Types gérés par `enregistrer_commandes()`, `run()` et `demarrer()` (lib.rs)
+const ETATS_GERES: [&str; 5] =
+    ["Arc<DatabaseManager>", "Arc<StatisticsCache>", "Arc<EventBus>", "Arc<MetriquesCommandes>", "OuvertureBase"];
+
+/// Sources des modules de commandes: (nom du fichier, contenu)
+fn sources_commandes() -> Vec<(String, String)> {
+    let dossier = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/commands");
+    let mut sources: Vec<(String, String)> = fs::read_dir(&dossier)
+        .unwrap()
+        .map(|entree| entree.unwrap().path())
+        .filter(|chemin| chemin.extension().is_some_and(|ext| ext == "rs"))
+        .map(|chemin| (chemin.file_name().unwrap().to_string_lossy().into_owned(), fs::read_to_string(&chemin).unwrap()))
+        .collect();
+    sources.sort();
+    sources
+}
+
+/// Types des paramètres `State<'_, T>` d'un fichier source
+fn types_etat(source: &str) -> Vec<String> {
+    let mut types = Vec::new();
+    let mut reste = source;
+    while let Some(debut) = reste.find("State<'_,") {
+        let apres = &reste[debut + "State<'_,".len()..];
+        let mut profondeur = 1;
+        let fin = apres
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '<' => profondeur += 1,
+                    '>' => profondeur -= 1,
+                    _ => {}
+                }
+                profondeur == 0
+            })
+            .map(|(i, _)| i)
+            .unwrap();
+        types.push(apres[..fin].split_whitespace().collect());
+        reste = &apres[fin..];
+    }
+    types
+}
+
+/// Noms des fonctions annotées `#[tauri::command]` d'un fichier source
+fn noms_commandes(source: &str) -> Vec<String> {
+    source
+        .split("#[tauri::command]")
+        .skip(1)
+        .map(|suite| {
+            let signature = &suite[suite.find("fn ").unwrap() + 3..];
+            signature[..signature.find(['(', '<']).unwrap()].trim().to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn commands_only_request_managed_state() {
+    let mut ecarts = Vec::new();
+    for (fichier, source) in sources_commandes() {
+        for type_etat in types_etat(&source) {
+            if !ETATS_GERES.contains(&type_etat.as_str()) {
+                ecarts.push(format!("{}: State<'_, {}>", fichier, type_etat));
+            }
+        }
+    }
+    assert!(ecarts.is_empty(), "état non géré par l'application: {:?}", ecarts);
+}
+
+#[test]
+fn every_command_is_registered_in_the_invoke_handler() {
+    let lib = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/lib.rs")).unwrap();
+    let handler = &lib[lib.find("generate_handler![").unwrap()..];
+    let handler = &handler[..handler.find("])").unwrap()];
+
+    let mut absentes = Vec::new();
+    for (fichier, source) in sources_commandes() {
+        for nom in noms_commandes(&source) {
+            if !handler.contains(&format!("commands::{},", nom)) {
+                absentes.push(format!("{}: {}", fichier, nom));
+            }
+        }
+    }
+    assert!(absentes.is_empty(), "commandes non enregistrées: {:?}", absentes);
+}
+
+#[test]
+fn every_command_is_measured() {
+    // `greet` est définie dans lib.rs
+    let lib = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/lib.rs")).unwrap();
+    let mut non_mesurees = Vec::new();
+    for (fichier, source) in sources_commandes().into_iter().chain([("lib.rs".to_string(), lib)]) {
+        for (position, _) in source.match_indices("#[tauri::command]") {
+            // Dernière ligne non indentée avant l'attribut: l'ouverture de la macro
+            let element = source[..position]
+                .lines()
+                .rfind(|ligne| !ligne.is_empty() && !ligne.starts_with(char::is_whitespace));
+            if element != Some("commande_mesuree! {") {
+                non_mesurees.push(format!("{}: {}", fichier, noms_commandes(&source[position..])[0]));
+            }
+        }
+    }
+    assert!(non_mesurees.is_empty(), "commandes hors de commande_mesuree!: {:?}", non_mesurees);
+}
+
+/// Application simulée avec les commandes de `run()` et sa fenêtre principale
+///
+/// La base est ouverte et les services démarrés par `demarrer()` si `ouverte`,
+/// sinon elle reste verrouillée comme une base chiffrée sans phrase secrète.
+fn application(test_db: &TestDb, ouverte: bool) -> (App<MockRuntime>, WebviewWindow<MockRuntime>) {
+    let app = tauri_app_lib::enregistrer_commandes(mock_builder())
+        .build(mock_context(noop_assets()))
+        .unwrap();
+    let ouverture = OuvertureBase::new(test_db.dir().join("commandes.db"), DatabaseConfig::default());
+    if ouverte {
+        tauri_app_lib::demarrer(app.handle(), ouverture.ouvrir().unwrap());
+    }
+    app.manage(ouverture);
+    let fenetre = WebviewWindowBuilder::new(&app, "main", Default::default()).build().unwrap();
+    (app, fenetre)
+}
+
+/// Appelle une commande comme le frontend (`invoke(commande, arguments)`)
+fn invoquer(fenetre: &WebviewWindow<MockRuntime>, commande: &str, arguments: Value) -> Result<Value, Value> {
+    get_ipc_response(
+        fenetre,
+        InvokeRequest {
+            cmd: commande.to_string(),
+            callback: CallbackFn(0),
+            error: CallbackFn(1),
+            url: "http://tauri.localhost".parse().unwrap(),
+            body: InvokeBody::Json(arguments),
+            headers: Default::default(),
+            invoke_key: INVOKE_KEY.to_string(),
+        },
+    )
+    .map(|reponse| reponse.deserialize::<Value>().unwrap())
+}
+
+#[test]
+fn sample_commands_run_against_the_state_managed_by_the_application() {
+    let test_db = TestDb::new();
+    let (_app, fenetre) = application(&test_db, true);
+
+    assert_eq!(
+        invoquer(&fenetre, "greet", json!({ "name": "Geema" })).unwrap(),
+        json!("Hello, Geema! You've been greeted from Rust!")
+    );
+    assert_eq!(invoquer(&fenetre, "is_database_locked", json!({})).unwrap(), json!(false));
+
+    let ferme = invoquer(&fenetre, "create_ferme", json!({ "ferme": { "nom": "Ferme IPC", "nbr_meuble": 3 } })).unwrap();
+    let fermes = invoquer(&fenetre, "get_all_fermes", json!({})).unwrap();
+    assert!(fermes.as_array().unwrap().iter().any(|f| f["id"] == ferme["id"]));
+    invoquer(&fenetre, "get_ferme_detailed_statistics", json!({ "fermeId": ferme["id"] })).unwrap();
+
+    // Commandes qui demandent aussi le cache des statistiques et les mesures;
+    // chaque commande est mesurée sous son nom
+    invoquer(&fenetre, "get_global_statistics", json!({})).unwrap();
+    let mesures = invoquer(&fenetre, "get_performance_metrics", json!({})).unwrap();
+    assert!(mesures
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|mesure| mesure["commande"] == "get_global_statistics" && mesure["appels"] == 1));
+    // Y compris celles qui ne demandent pas la base
+    assert!(mesures.as_array().unwrap().iter().any(|mesure| mesure["commande"] == "is_database_locked"));
+
+    // Une modification périmée arrive au frontend sous forme structurée
+    let modification = json!({
+        "ferme": { "id": ferme["id"], "nom": "Ferme IPC", "nbr_meuble": 4, "version": ferme["version"] }
+    });
+    invoquer(&fenetre, "update_ferme", modification.clone()).unwrap();
+    let erreur = invoquer(&fenetre, "update_ferme", modification).unwrap_err();
+    assert_eq!(erreur["kind"], "conflict");
+    assert_eq!(erreur["entity"], "Ferme");
+    assert_eq!(erreur["expected"], ferme["version"]);
+}
+
+#[test]
+fn commands_needing_the_database_fail_while_it_is_locked() {
+    let test_db = TestDb::new();
+    let (_app, fenetre) = application(&test_db, false);
+
+    assert_eq!(invoquer(&fenetre, "is_database_locked", json!({})).unwrap(), json!(true));
+    assert!(invoquer(&fenetre, "get_all_fermes", json!({})).is_err());
+    assert!(invoquer(&fenetre, "unlock_database", json!({ "passphrase": "" })).is_err());
+}
@@ -0,0 +1,82 @@
+//! Événements `entity://changed` publiés après les écritures validées
+
+mod common;
+
+use common::TestDb;
+use std::sync::{Arc, Mutex};
+use tauri_app_lib::error::AppError;
+use tauri_app_lib::models::{CreateFerme, CreateUser};
+use tauri_app_lib::services::{AuthService, EntityChanged, EventBus, EventSink, FermeService};
+
+#[derive(Default)]
+struct Collecteur(Mutex<Vec<EntityChanged>>);
+
+impl EventSink for Collecteur {
+    fn emit(&self, event: &EntityChanged) {
+        self.0.lock().unwrap().push(event.clone());
+    }
+}
+
+impl Collecteur {
+    fn vider(&self) -> Vec<EntityChanged> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+fn ecouter(test_db: &TestDb) -> Arc<Collecteur> {
+    let collecteur = Arc::new(Collecteur::default());
+    let bus = EventBus::new();
+    bus.subscribe(collecteur.clone());
+    bus.attach(&test_db.db);
+    collecteur
+}
+
+#[tokio::test]
+async fn committed_writes_emit_one_event_per_entity() {
+    let test_db = TestDb::new();
+    let collecteur = ecouter(&test_db);
+    let service = FermeService::new(test_db.storage());
+
+    let ferme = service
+        .create_ferme(CreateFerme { nom: "Ferme Événements".to_string(), nbr_meuble: 2 })
+        .await
+        .unwrap();
+    let ferme_id = ferme.id.unwrap();
+    assert_eq!(
+        collecteur.vider(),
+        vec![EntityChanged { entity: "ferme".to_string(), id: Some(ferme_id), action: "created".to_string() }]
+    );
+
+    service.delete_ferme(ferme_id).await.unwrap();
+    let evenements = collecteur.vider();
+    assert!(evenements.contains(&EntityChanged {
+        entity: "ferme".to_string(),
+        id: Some(ferme_id),
+        action: "deleted".to_string(),
+    }));
+}
+
+#[tokio::test]
+async fn rolled_back_writes_and_sessions_emit_nothing() {
+    let test_db = TestDb::new();
+    let collecteur = ecouter(&test_db);
+
+    let resultat: Result<(), AppError> = test_db.storage().write(|tx| {
+        tx.execute("INSERT INTO fermes (nom, nbr_meuble) VALUES ('Ferme Annulée', 1)", [])?;
+        Err(AppError::business_logic("annulation"))
+    });
+    assert!(resultat.is_err());
+    assert_eq!(test_db.count("fermes", "nom = 'Ferme Annulée'"), 0);
+
+    AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "technicien".to_string(),
+            email: "technicien@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: "FERME2024".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert!(collecteur.vider().is_empty());
+}
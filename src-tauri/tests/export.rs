@@ -0,0 +1,43 @@
+//! Export d'un instantané dénormalisé pour les outils de reporting
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use rusqlite::Connection;
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::ExportService;
+
+#[tokio::test]
+async fn snapshot_contains_bande_and_daily_facts() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let suivis = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    for age in 1..=3 {
+        suivis.upsert_field(semaine, age, "deces_par_jour", "5").await.unwrap();
+    }
+
+    let chemin = test_db.dir().join("reporting.db");
+    let service = ExportService::new(test_db.storage());
+    let rapport = service.export_reporting_snapshot(&chemin.to_string_lossy()).await.unwrap();
+    assert_eq!(rapport.bandes, 1);
+    assert_eq!(rapport.jours, test_db.count("suivi_quotidien", "1"));
+
+    let export = Connection::open(&chemin).unwrap();
+    let (total_deces, effectif): (i64, i64) = export
+        .query_row("SELECT total_deces, effectif_initial FROM bande_facts WHERE bande_id = ?1", [fixtures.bande_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(total_deces, 15);
+    assert!(effectif > 0);
+    let date: String = export
+        .query_row("SELECT date FROM daily_facts WHERE age = 3 AND batiment_id = ?1", [fixtures.batiment_ids[0]], |row| row.get(0))
+        .unwrap();
+    assert_eq!(date, "2024-03-03");
+    drop(export);
+
+    // Un nouvel export remplace le précédent
+    service.export_reporting_snapshot(&chemin.to_string_lossy()).await.unwrap();
+    assert!(service.export_reporting_snapshot("").await.is_err());
+}
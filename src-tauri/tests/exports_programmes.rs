@@ -0,0 +1,81 @@
+//! Exports programmés vers un dossier et statut de leur dernière exécution
+
+mod common;
+
+use common::{seed, TestDb};
+use std::fs;
+use tauri_app_lib::models::{
+    CreateExportProgramme, CreateUser, EXPORT_COMPTABILITE, EXPORT_RAPPORT_HEBDOMADAIRE, EXPORT_SAUVEGARDE,
+    FREQUENCE_HEBDOMADAIRE, FREQUENCE_QUOTIDIENNE, STATUT_EXPORT_ECHEC, STATUT_EXPORT_SUCCES,
+};
+use tauri_app_lib::services::{AuthService, ExportProgrammeService};
+
+async fn admin(test_db: &TestDb) -> String {
+    AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap()
+        .token
+}
+
+fn programme(type_export: &str, dossier: &str, frequence: &str, jour: Option<u32>) -> CreateExportProgramme {
+    CreateExportProgramme {
+        type_export: type_export.to_string(),
+        dossier: dossier.to_string(),
+        frequence: frequence.to_string(),
+        heure: 6,
+        jour,
+    }
+}
+
+#[tokio::test]
+async fn scheduled_exports_write_files_and_record_their_status() {
+    let test_db = TestDb::new();
+    seed(&test_db).await;
+    let token = admin(&test_db).await;
+    let service = ExportProgrammeService::new(test_db.storage());
+    let dossier = test_db.dir().join("partage");
+    fs::create_dir_all(&dossier).unwrap();
+    let dossier = dossier.to_string_lossy().into_owned();
+
+    assert!(service.create_scheduled_export(programme(EXPORT_SAUVEGARDE, "/dossier/absent", FREQUENCE_QUOTIDIENNE, None), &token).await.is_err());
+    assert!(service.create_scheduled_export(programme(EXPORT_SAUVEGARDE, &dossier, FREQUENCE_HEBDOMADAIRE, None), &token).await.is_err());
+    assert!(service.create_scheduled_export(programme("photos", &dossier, FREQUENCE_QUOTIDIENNE, None), &token).await.is_err());
+    assert!(service.create_scheduled_export(programme(EXPORT_SAUVEGARDE, &dossier, FREQUENCE_QUOTIDIENNE, None), "jeton").await.is_err());
+
+    for type_export in [EXPORT_COMPTABILITE, EXPORT_RAPPORT_HEBDOMADAIRE, EXPORT_SAUVEGARDE] {
+        let export = service
+            .create_scheduled_export(programme(type_export, &dossier, FREQUENCE_HEBDOMADAIRE, Some(1)), &token)
+            .await
+            .unwrap();
+        assert!(export.derniere_execution.is_none());
+
+        let execute = service.run_scheduled_export(export.id, &token).await.unwrap();
+        assert_eq!(execute.dernier_statut.as_deref(), Some(STATUT_EXPORT_SUCCES), "{:?}", execute.dernier_message);
+        let fichier = execute.dernier_message.unwrap();
+        assert!(fs::metadata(&fichier).unwrap().len() > 0);
+        if type_export == EXPORT_COMPTABILITE {
+            let contenu = fs::read_to_string(&fichier).unwrap();
+            assert!(contenu.starts_with("date;ferme;bande;article;quantite;prix_unitaire;montant\r\n"));
+            assert!(contenu.contains("poussins (bâtiment 1)"));
+        }
+    }
+
+    // Un dossier devenu inaccessible est signalé dans le statut
+    let export = service
+        .create_scheduled_export(programme(EXPORT_COMPTABILITE, &dossier, FREQUENCE_QUOTIDIENNE, None), &token)
+        .await
+        .unwrap();
+    fs::remove_dir_all(&dossier).unwrap();
+    let execute = service.run_scheduled_export(export.id, &token).await.unwrap();
+    assert_eq!(execute.dernier_statut.as_deref(), Some(STATUT_EXPORT_ECHEC));
+
+    assert_eq!(service.get_scheduled_exports().await.unwrap().len(), 4);
+    service.delete_scheduled_export(export.id, &token).await.unwrap();
+    assert_eq!(service.get_scheduled_exports().await.unwrap().len(), 3);
+}
@@ -0,0 +1,95 @@
+//! Filtres de liste enregistrés par utilisateur
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, semaine_id, TestDb};
+use serde_json::json;
+use tauri_app_lib::models::{
+    BandeLoadOptions, CreateBande, CreateFiltreEnregistre, CreateUser, FiltreBandes, FILTRE_ENTITE_BANDES,
+};
+use tauri_app_lib::repositories::{
+    BandeRepository, FiltreEnregistreRepository, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::AuthService;
+
+#[tokio::test]
+async fn saved_filters_are_applied_to_paginated_bandes() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let user_id = AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "technicien".to_string(),
+            email: "technicien@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap()
+        .user
+        .id;
+
+    // 60 décès sur les 10 000 poussins de la bande 2024: 0,6%
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    SuiviQuotidienRepository::new(test_db.storage())
+        .upsert_field(semaine, 1, "deces_par_jour", "60")
+        .await
+        .unwrap();
+
+    let conn = test_db.db.get_connection().unwrap();
+    BandeRepository::create(
+        &conn,
+        &CreateBande {
+            date_entree: NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+            ferme_id: fixtures.ferme_id,
+            notes: None,
+            champs_personnalises: Default::default(),
+        },
+        None,
+    )
+    .unwrap();
+
+    let enregistrer = |nom: &str, filtre: serde_json::Value| {
+        FiltreEnregistreRepository::save(&conn, user_id, &CreateFiltreEnregistre {
+            nom: nom.to_string(),
+            entite: FILTRE_ENTITE_BANDES.to_string(),
+            filtre,
+        })
+    };
+    let eleve = enregistrer("Mortalité élevée 2024", json!({"date_from": "2024-01-01", "mortalite_min": 1.0})).unwrap();
+    assert_eq!(eleve.filtre["mortalite_min"], json!(1.0));
+    assert!(enregistrer(" ", json!({})).is_err());
+    assert!(enregistrer("Inconnu", json!({"couleur": "rouge"})).is_err());
+    assert!(enregistrer("Date", json!({"date_from": "01/01/2024"})).is_err());
+    assert!(enregistrer("Statut", json!({"statut": "archivee"})).is_err());
+    assert!(FiltreEnregistreRepository::save(&conn, user_id, &CreateFiltreEnregistre {
+        nom: "Lots".to_string(),
+        entite: "lots".to_string(),
+        filtre: json!({}),
+    })
+    .is_err());
+
+    let page = |filtre: &FiltreBandes| {
+        BandeRepository::get_by_ferme_paginated(&conn, fixtures.ferme_id, 1, 10, filtre, &BandeLoadOptions::default())
+            .unwrap()
+    };
+    let critere = |id: i64| FiltreEnregistreRepository::get_filtre_bandes(&conn, id).unwrap();
+    assert_eq!(page(&FiltreBandes::default()).total, 2);
+    assert_eq!(page(&critere(eleve.id)).total, 0);
+
+    // Un filtre du même nom est remplacé
+    let eleve = enregistrer("Mortalité élevée 2024", json!({"date_from": "2024-01-01", "mortalite_min": 0.5})).unwrap();
+    let resultat = page(&critere(eleve.id));
+    assert_eq!(resultat.total, 1);
+    assert_eq!(resultat.data[0].id, Some(fixtures.bande_id));
+
+    // Les critères explicites priment sur ceux du filtre enregistré
+    let explicite = FiltreBandes { date_from: Some("2025-01-01".to_string()), ..Default::default() };
+    assert_eq!(page(&explicite.ou(critere(eleve.id))).total, 0);
+    assert_eq!(page(&FiltreBandes { statut: Some("active".to_string()), ..Default::default() }).total, 2);
+
+    assert_eq!(FiltreEnregistreRepository::get_by_user(&conn, user_id, None).unwrap().len(), 1);
+    assert!(FiltreEnregistreRepository::delete(&conn, eleve.id, user_id + 1).is_err());
+    FiltreEnregistreRepository::delete(&conn, eleve.id, user_id).unwrap();
+    assert!(FiltreEnregistreRepository::get_filtre_bandes(&conn, eleve.id).is_err());
+}
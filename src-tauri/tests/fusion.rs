@@ -0,0 +1,112 @@
+//! Fusion des doublons
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateAnalyse, CreateMaladie, CreateSoin, CreateSuiviSoin};
+use tauri_app_lib::repositories::{
+    AnalyseRepository, AnalyseRepositoryTrait, SoinRepository, SoinRepositoryTrait,
+    SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{FusionService, MaladieService};
+
+#[tokio::test]
+async fn merge_personnel_and_poussin_repoints_batiments() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let doublon = seed(&test_db).await;
+    let service = FusionService::new(test_db.storage());
+
+    let resultat = service
+        .merge_entities("personnel", doublon.personnel_id, fixtures.personnel_id)
+        .await
+        .unwrap();
+    assert_eq!(resultat.lignes_rattachees, 2);
+    assert_eq!(test_db.count("batiments", &format!("personnel_id = {}", fixtures.personnel_id)), 4);
+    assert_eq!(test_db.count("personnel", &format!("id = {}", doublon.personnel_id)), 0);
+
+    let soin_id = SoinRepository::new(test_db.storage())
+        .create(CreateSoin { nom: "Vaccin Newcastle".to_string(), unit: "dose".to_string(), ..Default::default() })
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "INSERT INTO plan_soins (poussin_id, soin_id, jour_debut, jour_fin) VALUES (?1, ?2, 7, 7)",
+            [doublon.poussin_id, soin_id],
+        )
+        .unwrap();
+    service.merge_entities("poussin", doublon.poussin_id, fixtures.poussin_id).await.unwrap();
+    assert_eq!(test_db.count("batiments", &format!("poussin_id = {}", fixtures.poussin_id)), 4);
+    // Le plan de soins du doublon est repris, pas supprimé en cascade
+    assert_eq!(test_db.count("plan_soins", &format!("poussin_id = {}", fixtures.poussin_id)), 1);
+    assert_eq!(test_db.count("poussins", &format!("id = {}", doublon.poussin_id)), 0);
+
+    assert!(service.merge_entities("personnel", fixtures.personnel_id, fixtures.personnel_id).await.is_err());
+    assert!(service.merge_entities("personnel", doublon.personnel_id, fixtures.personnel_id).await.is_err());
+    assert!(service.merge_entities("ferme", doublon.ferme_id, fixtures.ferme_id).await.is_err());
+}
+
+#[tokio::test]
+async fn merge_soin_and_maladie_keeps_history() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = FusionService::new(test_db.storage());
+
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let soin = |nom: &str| CreateSoin { nom: nom.to_string(), unit: "ml".to_string(), ..Default::default() };
+    let conserve = soin_repo.create(soin("Vitamine C")).await.unwrap().id.unwrap();
+    let faute = soin_repo.create(soin("Vitamin C")).await.unwrap().id.unwrap();
+
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "INSERT INTO plan_soins (poussin_id, soin_id, jour_debut, jour_fin) VALUES (?1, ?2, 1, 5)",
+            [fixtures.poussin_id, faute],
+        )
+        .unwrap();
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    for (soin_id, age) in [(conserve, 1), (faute, 2), (faute, 3)] {
+        suivi_repo.add_soin(CreateSuiviSoin {
+            semaine_id: semaine_id(&test_db, fixtures.batiment_ids[0], 1),
+            age,
+            soin_id: Some(soin_id),
+            quantite: Some("1".to_string()),
+            unit: None,
+        }).await.unwrap();
+    }
+
+    let resultat = service.merge_entities("soin", faute, conserve).await.unwrap();
+    assert_eq!(resultat.nom_conserve, "Vitamine C");
+    assert_eq!(test_db.count("suivi_soins", &format!("soin_id = {}", conserve)), 3);
+    assert_eq!(test_db.count("plan_soins", &format!("soin_id = {}", conserve)), 1);
+    assert_eq!(test_db.count("soins", &format!("id = {}", faute)), 0);
+
+    let maladies = MaladieService::new(test_db.storage());
+    let gumboro = maladies.create_maladie(CreateMaladie { nom: "Gumboro".to_string() }).await.unwrap().id;
+    let gamboro = maladies.create_maladie(CreateMaladie { nom: "Gamboro".to_string() }).await.unwrap().id;
+
+    let analyses = AnalyseRepository::new(test_db.storage());
+    // Bâtiment 1 déclaré sous les deux noms, bâtiment 2 seulement sous le doublon
+    for (batiment_id, maladie_id) in [(fixtures.batiment_ids[0], gumboro), (fixtures.batiment_ids[0], gamboro), (fixtures.batiment_ids[1], gamboro)] {
+        analyses.create(CreateAnalyse {
+            batiment_id,
+            maladie_id: Some(maladie_id),
+            type_analyse: "sérologie".to_string(),
+            date_prelevement: "2024-03-20".to_string(),
+            ..Default::default()
+        }).await.unwrap();
+    }
+
+    service.merge_entities("Maladie", gamboro, gumboro).await.unwrap();
+    assert_eq!(test_db.count("batiment_maladies", &format!("maladie_id = {}", gumboro)), 2);
+    assert_eq!(test_db.count("analyses", &format!("maladie_id = {}", gumboro)), 3);
+    assert_eq!(test_db.count("maladies", &format!("id = {}", gamboro)), 0);
+}
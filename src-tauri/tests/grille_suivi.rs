@@ -0,0 +1,58 @@
+//! Enregistrement groupé de la grille de saisie d'une semaine
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::LigneGrilleSuivi;
+use tauri_app_lib::repositories::{AlimentationRepository, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+
+fn ligne(age: i32, champs: &[(&str, &str)]) -> LigneGrilleSuivi {
+    LigneGrilleSuivi {
+        age,
+        champs: champs.iter().map(|(champ, valeur)| (champ.to_string(), valeur.to_string())).collect(),
+    }
+}
+
+#[tokio::test]
+async fn grid_cells_are_saved_together_and_refused_cells_are_reported() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let repository = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let contour = || {
+        let conn = test_db.db.get_connection().unwrap();
+        AlimentationRepository::get_contour(&conn, fixtures.bande_id).unwrap()
+    };
+    let contour_initial = contour();
+
+    let resultats = repository
+        .save_grid(
+            semaine,
+            vec![
+                ligne(1, &[("deces_par_jour", "3"), ("alimentation_par_jour", "2"), ("remarques", "RAS")]),
+                ligne(2, &[("deces_par_jour", "1"), ("couleur", "jaune")]),
+                // Le soin n'existe pas: le jour créé pour l'occasion est annulé avec la cellule
+                ligne(3, &[("soins_id", "9999")]),
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resultats.len(), 6);
+    let refusees: Vec<_> = resultats.iter().filter(|r| r.erreur.is_some()).map(|r| (r.age, r.field.as_str())).collect();
+    assert_eq!(refusees, vec![(2, "couleur"), (3, "soins_id")]);
+    assert!(resultats.iter().all(|r| r.erreur.is_some() == r.suivi.is_none()));
+
+    // Les cellules d'un jour sont enregistrées par nom de champ: la dernière voit tout le jour
+    let dernier_jour_1 = resultats[2].suivi.as_ref().unwrap();
+    assert_eq!((resultats[2].age, resultats[2].field.as_str()), (1, "remarques"));
+    assert_eq!(dernier_jour_1.deces_par_jour, Some(3));
+    assert_eq!(dernier_jour_1.alimentation_par_jour, Some(2.0));
+    assert_eq!(dernier_jour_1.remarques.as_deref(), Some("RAS"));
+
+    assert_eq!(test_db.count("suivi_quotidien", &format!("semaine_id = {}", semaine)), 2);
+    assert_eq!(contour(), contour_initial - 100.0);
+
+    // Semaine inexistante: toute la grille est refusée
+    assert!(repository.save_grid(9999, vec![ligne(1, &[("deces_par_jour", "1")])]).await.is_err());
+}
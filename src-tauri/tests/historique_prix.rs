@@ -0,0 +1,128 @@
+//! Historique des prix de l'aliment et des poussins, coût des bandes passées
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, TestDb};
+use tauri_app_lib::models::{
+    CreateAlimentationHistory, CreateBande, CreateBatiment, CreatePrixHistorique, ARTICLE_ALIMENT, ARTICLE_POUSSIN,
+};
+use tauri_app_lib::repositories::{AlimentationRepository, PrixRepository};
+use tauri_app_lib::services::{BandeService, BilanService, FusionService};
+
+fn prix(article: &str, poussin_id: Option<i64>, prix_unitaire: f64, date_debut: &str) -> CreatePrixHistorique {
+    CreatePrixHistorique {
+        article: article.to_string(),
+        poussin_id,
+        prix_unitaire,
+        date_debut: date_debut.to_string(),
+    }
+}
+
+/// Bande d'un seul bâtiment de 1 000 poussins entrée le 1er mars 2024
+async fn bande_de_1000(test_db: &TestDb, ferme_id: i64, poussin_id: i64, personnel_id: i64, numero: &str) -> i64 {
+    BandeService::new(test_db.storage())
+        .create_bande_with_batiments_and_first_week(
+            CreateBande { date_entree: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), ferme_id, notes: None, champs_personnalises: Default::default() },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: numero.to_string(),
+                poussin_id,
+                personnel_id,
+                quantite: 1000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+        .await
+        .unwrap()
+        .id
+        .unwrap()
+}
+
+#[tokio::test]
+async fn bande_costs_keep_the_prices_in_effect_when_recorded() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    PrixRepository::create(&conn, &prix(ARTICLE_ALIMENT, None, 0.5, "2024-01-01")).unwrap();
+    PrixRepository::create(&conn, &prix(ARTICLE_ALIMENT, None, 0.6, "2024-03-10")).unwrap();
+    PrixRepository::create(&conn, &prix(ARTICLE_POUSSIN, Some(fixtures.poussin_id), 4.0, "2024-01-01")).unwrap();
+    assert!(PrixRepository::create(&conn, &prix(ARTICLE_POUSSIN, None, 4.0, "2024-01-01")).is_err());
+    assert!(PrixRepository::create(&conn, &prix("paille", None, 1.0, "2024-01-01")).is_err());
+    assert!(PrixRepository::create(&conn, &prix(ARTICLE_ALIMENT, None, -1.0, "2024-01-01")).is_err());
+    assert_eq!(PrixRepository::get_historique(&conn, ARTICLE_ALIMENT, None).unwrap()[0].prix_unitaire, 0.6);
+    drop(conn);
+
+    let bande_id = bande_de_1000(&test_db, fixtures.ferme_id, fixtures.poussin_id, fixtures.personnel_id, "5").await;
+
+    let conn = test_db.db.get_connection().unwrap();
+    for (quantite, created_at) in [(1000.0, "2024-03-01 08:00:00"), (500.0, "2024-03-15 08:00:00")] {
+        AlimentationRepository::create(
+            &conn,
+            &CreateAlimentationHistory { bande_id, quantite, created_at: created_at.to_string(), ..Default::default() },
+        )
+        .unwrap();
+    }
+    let livraison = AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id,
+            quantite: 100.0,
+            created_at: "2024-03-20 08:00:00".to_string(),
+            prix_unitaire: Some(0.7),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(livraison.prix_unitaire, Some(0.7));
+
+    // Une hausse de prix enregistrée après coup ne modifie pas le coût de la bande
+    PrixRepository::create(&conn, &prix(ARTICLE_ALIMENT, None, 0.9, "2024-03-01")).unwrap();
+    PrixRepository::create(&conn, &prix(ARTICLE_POUSSIN, Some(fixtures.poussin_id), 5.0, "2024-02-01")).unwrap();
+    drop(conn);
+
+    let bilan = BilanService::new(test_db.storage()).get_bande_financial_summary(bande_id).await.unwrap();
+    assert!((bilan.cout_aliment - 870.0).abs() < 1e-9);
+    assert_eq!(bilan.livraisons_non_valorisees, 0);
+    assert!((bilan.cout_poussins - 4000.0).abs() < 1e-9);
+    assert!((bilan.cout_total - 4870.0).abs() < 1e-9);
+
+    // Les bâtiments de la bande de test ont été créés sans prix enregistré
+    let bilan = BilanService::new(test_db.storage()).get_bande_financial_summary(fixtures.bande_id).await.unwrap();
+    assert_eq!(bilan.cout_poussins, 0.0);
+    assert_eq!(bilan.batiments_non_valorises, 2);
+}
+
+#[tokio::test]
+async fn merging_poussin_types_keeps_bande_costs_and_price_history() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let doublon = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+    PrixRepository::create(&conn, &prix(ARTICLE_POUSSIN, Some(doublon.poussin_id), 4.0, "2024-01-01")).unwrap();
+    drop(conn);
+
+    let bilans = BilanService::new(test_db.storage());
+    let bande_id = bande_de_1000(&test_db, doublon.ferme_id, doublon.poussin_id, doublon.personnel_id, "5").await;
+    let avant = bilans.get_bande_financial_summary(bande_id).await.unwrap();
+    assert!((avant.cout_poussins - 4000.0).abs() < 1e-9);
+
+    FusionService::new(test_db.storage())
+        .merge_entities("poussin", doublon.poussin_id, fixtures.poussin_id)
+        .await
+        .unwrap();
+    assert_eq!(test_db.count("historique_prix", &format!("poussin_id = {}", fixtures.poussin_id)), 1);
+
+    let apres = bilans.get_bande_financial_summary(bande_id).await.unwrap();
+    assert!((apres.cout_poussins - avant.cout_poussins).abs() < 1e-9);
+    assert!((apres.cout_total - avant.cout_total).abs() < 1e-9);
+    assert_eq!(apres.batiments_non_valorises, 0);
+
+    // Les prix du doublon valent désormais pour le type conservé
+    let nouvelle = bande_de_1000(&test_db, fixtures.ferme_id, fixtures.poussin_id, fixtures.personnel_id, "5").await;
+    let bilan = bilans.get_bande_financial_summary(nouvelle).await.unwrap();
+    assert!((bilan.cout_poussins - 4000.0).abs() < 1e-9);
+}
@@ -0,0 +1,140 @@
+//! Registre des immobilisations et amortissement imputé aux bandes
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateBande, CreateBatiment, CreateImmobilisation, UpdateImmobilisation};
+use tauri_app_lib::repositories::{
+    ImmobilisationRepository, ImmobilisationRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{BandeService, BilanService};
+
+fn proche(valeur: f64, attendu: f64) -> bool {
+    (valeur - attendu).abs() < 1e-6
+}
+
+#[tokio::test]
+async fn depreciation_schedule_is_linear_and_prorated_by_day() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let repo = ImmobilisationRepository::new(test_db.storage());
+
+    // 1096 jours du 2022-01-01 au 2025-01-01: 10 par jour
+    let ventilateurs = repo.create(CreateImmobilisation {
+        ferme_id: fixtures.ferme_id,
+        nom: " Ventilateurs ".to_string(),
+        categorie: "equipement".to_string(),
+        date_achat: "2022-01-01".to_string(),
+        valeur: 10960.0,
+        duree_amortissement_annees: 3,
+    }).await.unwrap();
+    assert_eq!(ventilateurs.nom, "Ventilateurs");
+    assert_eq!(ventilateurs.date_fin_amortissement, "2025-01-01");
+    assert!(proche(ventilateurs.amortissement_annuel, 10960.0 / 3.0));
+
+    let invalide = |modifier: fn(&mut CreateImmobilisation)| {
+        let mut immobilisation = CreateImmobilisation {
+            ferme_id: fixtures.ferme_id,
+            nom: "Groupe électrogène".to_string(),
+            categorie: "equipement".to_string(),
+            date_achat: "2024-01-01".to_string(),
+            valeur: 1000.0,
+            duree_amortissement_annees: 5,
+        };
+        modifier(&mut immobilisation);
+        immobilisation
+    };
+    assert!(repo.create(invalide(|i| i.nom = " ".to_string())).await.is_err());
+    assert!(repo.create(invalide(|i| i.categorie = "terrain".to_string())).await.is_err());
+    assert!(repo.create(invalide(|i| i.date_achat = "01/01/2024".to_string())).await.is_err());
+    assert!(repo.create(invalide(|i| i.valeur = -1.0)).await.is_err());
+    assert!(repo.create(invalide(|i| i.duree_amortissement_annees = 0)).await.is_err());
+    assert!(repo.create(invalide(|i| i.ferme_id = 9999)).await.is_err());
+
+    let tableau = repo.get_depreciation_schedule(fixtures.ferme_id, 2023).await.unwrap();
+    assert_eq!(tableau.lignes.len(), 1);
+    assert!(proche(tableau.total, 3650.0));
+    assert!(proche(tableau.lignes[0].valeur_nette_fin_annee, 3660.0));
+
+    let tableau = repo.get_depreciation_schedule(fixtures.ferme_id, 2024).await.unwrap();
+    assert!(proche(tableau.total, 3660.0));
+    assert!(proche(tableau.lignes[0].valeur_nette_fin_annee, 0.0));
+    assert!(repo.get_depreciation_schedule(fixtures.ferme_id, 2025).await.unwrap().lignes.is_empty());
+
+    // Prolonger la durée étale la valeur restante
+    let prolonge = repo.update(UpdateImmobilisation {
+        id: ventilateurs.id,
+        nom: ventilateurs.nom.clone(),
+        categorie: ventilateurs.categorie.clone(),
+        date_achat: ventilateurs.date_achat.clone(),
+        valeur: ventilateurs.valeur,
+        duree_amortissement_annees: 4,
+    }).await.unwrap();
+    assert_eq!(prolonge.date_fin_amortissement, "2026-01-01");
+    assert_eq!(repo.get_depreciation_schedule(fixtures.ferme_id, 2025).await.unwrap().lignes.len(), 1);
+
+    repo.delete(ventilateurs.id).await.unwrap();
+    assert!(repo.delete(ventilateurs.id).await.is_err());
+    assert!(repo.get_by_ferme(fixtures.ferme_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn bande_cost_includes_its_share_of_farm_depreciation() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let bilan = BilanService::new(test_db.storage());
+    assert_eq!(bilan.get_bande_financial_summary(fixtures.bande_id).await.unwrap().cout_amortissement, 0.0);
+
+    // 2024 compte 366 jours: 100 par jour
+    ImmobilisationRepository::new(test_db.storage())
+        .create(CreateImmobilisation {
+            ferme_id: fixtures.ferme_id,
+            nom: "Bâtiment 1".to_string(),
+            categorie: "batiment".to_string(),
+            date_achat: "2024-01-01".to_string(),
+            valeur: 36600.0,
+            duree_amortissement_annees: 1,
+        })
+        .await
+        .unwrap();
+
+    // Bande présente du 1er au 10 mars (dernier jour de suivi)
+    SuiviQuotidienRepository::new(test_db.storage())
+        .upsert_field(semaine_id(&test_db, fixtures.batiment_ids[0], 2), 10, "deces_par_jour", "0")
+        .await
+        .unwrap();
+    let seule = bilan.get_bande_financial_summary(fixtures.bande_id).await.unwrap();
+    assert!(proche(seule.cout_amortissement, 1000.0));
+    assert!(proche(seule.cout_total, 1000.0));
+    assert!(proche(seule.cout_intrants(), 0.0));
+
+    // Une seconde bande de 5000 poussins, suivie du 6 au 12 mars, partage la dotation
+    let seconde = BandeService::new(test_db.storage())
+        .create_bande_with_batiments_and_first_week(
+            CreateBande {
+                date_entree: NaiveDate::from_ymd_opt(2024, 3, 6).unwrap(),
+                ferme_id: fixtures.ferme_id,
+                notes: None,
+                champs_personnalises: Default::default(),
+            },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "3".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 5000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let premiere = bilan.get_bande_financial_summary(fixtures.bande_id).await.unwrap();
+    assert!(proche(premiere.cout_amortissement, 500.0 + 5.0 * 100.0 * 2.0 / 3.0));
+    let seconde = bilan.get_bande_financial_summary(seconde.id.unwrap()).await.unwrap();
+    assert!(proche(seconde.cout_amortissement, 5.0 * 100.0 / 3.0 + 200.0));
+    assert!(proche(premiere.cout_amortissement + seconde.cout_amortissement, 1200.0));
+}
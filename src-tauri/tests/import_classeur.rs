@@ -0,0 +1,89 @@
+//! Import des classeurs Excel de l'ancien système de suivi
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::FeuilleClasseur;
+use tauri_app_lib::services::ImportService;
+
+fn nom(test_db: &TestDb, table: &str, id: i64) -> String {
+    let conn = test_db.db.get_connection().unwrap();
+    conn.query_row(&format!("SELECT nom FROM {} WHERE id = ?1", table), [id], |row| row.get(0)).unwrap()
+}
+
+fn ligne(cellules: &[&str]) -> Vec<String> {
+    cellules.iter().map(|c| c.to_string()).collect()
+}
+
+#[tokio::test]
+async fn legacy_workbook_is_checked_then_imported() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let ferme = nom(&test_db, "fermes", fixtures.ferme_id);
+    let souche = nom(&test_db, "poussins", fixtures.poussin_id);
+    let technicien = nom(&test_db, "personnel", fixtures.personnel_id);
+
+    let bande = FeuilleClasseur {
+        nom: "Bande janvier".to_string(),
+        lignes: vec![
+            ligne(&["Ferme", &ferme.to_uppercase()]),
+            ligne(&["Date d'entrée", "45292"]), // 2024-01-01
+            ligne(&["Souche", &souche]),
+            ligne(&["Technicien", &technicien]),
+            ligne(&["Bâtiment", "1", "3"]),
+            ligne(&["Effectif", "4000", "3500"]),
+            ligne(&[]),
+            ligne(&["Bâtiment", "Âge (jours)", "Décès", "Éliminés", "Aliment (sachets)", "Poids (kg)", "Remarques"]),
+            ligne(&["1", "1", "12", "0", "8,5", "", ""]),
+            ligne(&["1", "7", "3", "", "10", "0,18", "Pesée"]),
+            ligne(&["1", "9", "2", "", "", "", ""]),
+            ligne(&["1", "10", "", "", "", "", ""]),
+            ligne(&["3", "2", "5", "1", "6", "", ""]),
+        ],
+    };
+    let mut en_erreur = bande.clone();
+    en_erreur.nom = "Bande mars".to_string();
+    en_erreur.lignes[1] = ligne(&["Date d'entrée", "01/03/2024"]);
+    en_erreur.lignes.push(ligne(&["5", "3", "1"]));
+    en_erreur.lignes.push(ligne(&["1", "2", "-4"]));
+    en_erreur.lignes.push(ligne(&["3", "2", "1"]));
+
+    let service = ImportService::new(test_db.storage());
+    let essai = service.import_legacy_workbook(vec![bande.clone(), en_erreur], true, None).await.unwrap();
+    assert!(essai.essai);
+    assert_eq!(essai.feuilles[0].date_entree.as_deref(), Some("2024-01-01"));
+    assert_eq!(essai.feuilles[0].batiments, 2);
+    assert!(essai.feuilles[0].erreurs.is_empty(), "{:?}", essai.feuilles[0].erreurs);
+    assert_eq!((essai.lignes_lues, essai.lignes_ignorees, essai.lignes_en_erreur), (13, 2, 3));
+
+    // La bande du 1er mars existe déjà, le bâtiment 5 n'est pas dans l'en-tête,
+    // les décès ne peuvent pas être négatifs et le jour 2 du bâtiment 3 est en double
+    let motifs: Vec<&str> = essai.feuilles[1].erreurs.iter().map(|e| e.motif.as_str()).collect();
+    assert_eq!(motifs.len(), 4, "{:?}", motifs);
+    assert_eq!(essai.feuilles[1].erreurs[0].ligne, 0);
+    assert_eq!(essai.feuilles[1].erreurs[1].ligne, 14);
+    assert_eq!(test_db.count("bandes", "1"), 1);
+
+    // L'import est refusé tant qu'une feuille contient une erreur
+    let mut sans_entete = bande.clone();
+    sans_entete.lignes.truncate(6);
+    assert!(service.import_legacy_workbook(vec![bande.clone(), sans_entete], false, None).await.is_err());
+    assert_eq!(test_db.count("bandes", "1"), 1);
+
+    let rapport = service.import_legacy_workbook(vec![bande.clone()], false, None).await.unwrap();
+    assert!(!rapport.essai);
+    assert_eq!(rapport.bandes_creees, 1);
+    let bande_id = rapport.feuilles[0].bande_id.unwrap();
+    let condition = |autre: &str| {
+        format!("semaine_id IN (SELECT s.id FROM semaines s JOIN batiments b ON b.id = s.batiment_id WHERE b.bande_id = {}){}", bande_id, autre)
+    };
+    assert_eq!(test_db.count("batiments", &format!("bande_id = {} AND quantite IN (4000, 3500)", bande_id)), 2);
+    // Bâtiment 1 suivi jusqu'au jour 9: deux semaines; bâtiment 3: une semaine
+    assert_eq!(test_db.count("suivi_quotidien", &condition("")), 21);
+    assert_eq!(test_db.count("suivi_quotidien", &condition(" AND age = 1 AND deces_par_jour = 12 AND alimentation_par_jour = 8.5")), 1);
+    assert_eq!(test_db.count("semaines", &format!("numero_semaine = 1 AND poids = 0.18 AND batiment_id IN (SELECT id FROM batiments WHERE bande_id = {})", bande_id)), 1);
+
+    // Réimporter le même classeur est refusé
+    let doublon = service.import_legacy_workbook(vec![bande], true, None).await.unwrap();
+    assert_eq!(doublon.feuilles[0].erreurs.len(), 1);
+}
@@ -0,0 +1,52 @@
+//! Mode lecture seule: consultation sans modification possible
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::database::Storage;
+use tauri_app_lib::error::AppError;
+use tauri_app_lib::models::{CreateFerme, CreateUser, LoginUser};
+use tauri_app_lib::repositories::{FermeRepository, FermeRepositoryTrait};
+use tauri_app_lib::services::{AlerteService, AuthService, FermeService};
+
+#[tokio::test]
+async fn read_only_mode_rejects_every_modification() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let auth = AuthService::new(test_db.storage());
+    auth.register(CreateUser {
+        username: "admin".to_string(),
+        email: "admin@example.com".to_string(),
+        password: "motdepasse123".to_string(),
+        registration_code: String::new(),
+    })
+    .await
+    .unwrap();
+    let fermes = FermeRepository::new(test_db.storage());
+    let nouvelle_ferme = || CreateFerme { nom: "Ferme du Nord".to_string(), nbr_meuble: 2 };
+
+    // Même requête avant et après le passage en lecture seule
+    FermeService::new(test_db.storage()).create_ferme(nouvelle_ferme()).await.unwrap();
+    test_db.db.set_read_only(true);
+    assert!(test_db.db.read_only());
+
+    assert!(matches!(
+        FermeService::new(test_db.storage()).create_ferme(CreateFerme { nom: "Autre".to_string(), nbr_meuble: 1 }).await,
+        Err(AppError::ReadOnly)
+    ));
+    assert!(matches!(fermes.delete(fixtures.ferme_id).await, Err(AppError::ReadOnly)));
+    assert!(matches!(test_db.db.create_profile("demo"), Err(AppError::ReadOnly)));
+    assert_eq!(fermes.get_all().await.unwrap().len(), 2);
+
+    // La consultation reste possible: connexion et calcul des alertes
+    let session = auth
+        .login(LoginUser { username: "admin".to_string(), password: "motdepasse123".to_string(), totp_code: None })
+        .await
+        .unwrap();
+    assert!(auth.verify_token(&session.token).await.unwrap().is_some());
+    AlerteService::new(test_db.storage()).get_pending_alerts(None).await.unwrap();
+
+    test_db.db.set_read_only(false);
+    fermes.delete(fixtures.ferme_id).await.unwrap();
+    assert_eq!(fermes.get_all().await.unwrap().len(), 1);
+}
@@ -0,0 +1,105 @@
+//! Livraisons et changements de litière des bâtiments
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::{CreateLitiere, UpdateLitiere, ACTIVITE_LITIERE};
+use tauri_app_lib::repositories::{ActiviteRepository, LitiereRepository, LitiereRepositoryTrait};
+use tauri_app_lib::services::BilanService;
+
+#[tokio::test]
+async fn litter_records_are_costed_and_shown_in_the_activity_feed() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let repo = LitiereRepository::new(test_db.storage());
+
+    let paille = repo.create(CreateLitiere {
+        batiment_id: fixtures.batiment_ids[0],
+        type_operation: " Livraison ".to_string(),
+        materiau: " Paille ".to_string(),
+        quantite: 40.0,
+        unite: "Botte".to_string(),
+        cout: Some(1200.0),
+        tva: Default::default(),
+        date_operation: "2024-03-01".to_string(),
+    }).await.unwrap();
+    assert_eq!(paille.type_operation, "livraison");
+    assert_eq!(paille.materiau, "Paille");
+    assert_eq!(paille.unite, "botte");
+
+    let changement = repo.create(CreateLitiere {
+        batiment_id: fixtures.batiment_ids[1],
+        type_operation: "changement".to_string(),
+        materiau: "Copeaux".to_string(),
+        quantite: 500.0,
+        unite: "kg".to_string(),
+        date_operation: "2024-03-10".to_string(),
+        ..Default::default()
+    }).await.unwrap();
+
+    let invalide = |modifier: fn(&mut CreateLitiere)| {
+        let mut litiere = CreateLitiere {
+            batiment_id: fixtures.batiment_ids[0],
+            type_operation: "livraison".to_string(),
+            materiau: "Paille".to_string(),
+            quantite: 1.0,
+            unite: "kg".to_string(),
+            cout: None,
+            tva: Default::default(),
+            date_operation: "2024-03-01".to_string(),
+        };
+        modifier(&mut litiere);
+        litiere
+    };
+    assert!(repo.create(invalide(|l| l.type_operation = "vidange".to_string())).await.is_err());
+    assert!(repo.create(invalide(|l| l.materiau = "  ".to_string())).await.is_err());
+    assert!(repo.create(invalide(|l| l.quantite = 0.0)).await.is_err());
+    assert!(repo.create(invalide(|l| l.unite = "tonne".to_string())).await.is_err());
+    assert!(repo.create(invalide(|l| l.cout = Some(-5.0))).await.is_err());
+    assert!(repo.create(invalide(|l| l.date_operation = "01/03/2024".to_string())).await.is_err());
+    assert!(repo.create(invalide(|l| l.batiment_id = 9999)).await.is_err());
+
+    let bande = repo.get_by_bande(fixtures.bande_id).await.unwrap();
+    assert_eq!(bande.iter().map(|l| l.id).collect::<Vec<_>>(), vec![paille.id, changement.id]);
+
+    // Le coût du changement est connu après coup
+    repo.update(UpdateLitiere {
+        id: changement.id.unwrap(),
+        type_operation: changement.type_operation.clone(),
+        materiau: changement.materiau.clone(),
+        quantite: changement.quantite,
+        unite: changement.unite.clone(),
+        cout: Some(300.0),
+        tva: Default::default(),
+        date_operation: changement.date_operation.clone(),
+    }).await.unwrap();
+
+    let bilan = BilanService::new(test_db.storage())
+        .get_bande_financial_summary(fixtures.bande_id)
+        .await
+        .unwrap();
+    assert_eq!(bilan.cout_litiere, 1500.0);
+    assert_eq!(bilan.litieres_non_valorisees, 0);
+    assert_eq!(bilan.cout_total, bilan.cout_aliment + bilan.cout_poussins + bilan.cout_soins + 1500.0);
+
+    let activite = {
+        let conn = test_db.db.get_connection().unwrap();
+        ActiviteRepository::get_by_bande(&conn, fixtures.bande_id, 1, 50).unwrap()
+    };
+    let evenement = activite.data.iter().find(|a| a.type_activite == ACTIVITE_LITIERE && a.reference_id == paille.id.unwrap()).unwrap();
+    assert_eq!(evenement.batiment_id, Some(fixtures.batiment_ids[0]));
+    assert_eq!(evenement.description, "livraison de litière: 40 botte de Paille");
+
+    repo.delete(paille.id.unwrap()).await.unwrap();
+    assert!(repo.delete(paille.id.unwrap()).await.is_err());
+    assert_eq!(repo.get_by_batiment(fixtures.batiment_ids[0]).await.unwrap().len(), 0);
+
+    // Les litières suivent la suppression du bâtiment
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute("DELETE FROM batiments WHERE id = ?1", [fixtures.batiment_ids[1]])
+        .unwrap();
+    assert_eq!(test_db.count("litieres", "1 = 1"), 0);
+}
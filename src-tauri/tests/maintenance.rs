@@ -0,0 +1,86 @@
+//! Maintenance de la base de données (VACUUM, ANALYZE, index plein texte)
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::CreateUser;
+use tauri_app_lib::services::{AuthService, ExportService, MaintenanceService};
+
+async fn admin(test_db: &TestDb) -> String {
+    AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap()
+        .token
+}
+
+#[tokio::test]
+async fn maintenance_compacts_the_database_and_rebuilds_full_text_indexes() {
+    let test_db = TestDb::new();
+    seed(&test_db).await;
+    let token = admin(&test_db).await;
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE recherche_notes USING fts5(contenu);
+             INSERT INTO recherche_notes (contenu) VALUES ('toux dans le bâtiment 2');
+             CREATE TABLE remplissage (donnees BLOB);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 200)
+             INSERT INTO remplissage SELECT zeroblob(4096) FROM n;
+             DROP TABLE remplissage;",
+        )
+        .unwrap();
+    }
+
+    let service = MaintenanceService::new(test_db.storage());
+    let rapport = service.run_database_maintenance(&token).await.unwrap();
+    assert_eq!(rapport.index_reconstruits, vec!["recherche_notes".to_string()]);
+    assert!(rapport.taille_apres < rapport.taille_avant);
+    assert_eq!(test_db.count("recherche_notes", "recherche_notes MATCH 'toux'"), 1);
+
+    let programmation = service.get_maintenance_schedule().await.unwrap();
+    assert!(!programmation.mensuelle);
+    assert_eq!(programmation.derniere_maintenance, Some(rapport.effectuee_le));
+    assert!(service.set_maintenance_schedule(true, &token).await.unwrap().mensuelle);
+    assert!(service.run_database_maintenance("token-invalide").await.is_err());
+}
+
+#[tokio::test]
+async fn integrity_check_reports_orphan_references_and_backup_is_usable() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = MaintenanceService::new(test_db.storage());
+
+    let rapport = service.check_integrity().await.unwrap();
+    assert!(rapport.integre, "{:?}", rapport);
+
+    let sauvegarde = test_db.dir().join("sauvegarde.db");
+    let fichier = ExportService::new(test_db.storage())
+        .backup_database(&sauvegarde.to_string_lossy())
+        .await
+        .unwrap();
+    let copie = rusqlite::Connection::open(&fichier).unwrap();
+    let bandes: i64 = copie.query_row("SELECT COUNT(*) FROM bandes", [], |row| row.get(0)).unwrap();
+    assert_eq!(bandes, 1);
+
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute_batch(&format!(
+            "PRAGMA foreign_keys = OFF;
+             UPDATE batiments SET personnel_id = 999999 WHERE bande_id = {} AND numero_batiment = '1';
+             PRAGMA foreign_keys = ON;",
+            fixtures.bande_id
+        ))
+        .unwrap();
+    }
+    let rapport = service.check_integrity().await.unwrap();
+    assert!(!rapport.integre);
+    assert!(rapport.erreurs.is_empty());
+    assert_eq!(rapport.references_orphelines.len(), 1);
+    assert!(rapport.references_orphelines[0].contains("batiments"));
+}
@@ -0,0 +1,48 @@
+//! Tendances d'apparition des maladies
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::CreateMaladie;
+use tauri_app_lib::services::MaladieService;
+
+#[tokio::test]
+async fn trends_count_affected_batiments_per_period() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let autre = seed(&test_db).await;
+    let service = MaladieService::new(test_db.storage());
+
+    let coccidiose = service.create_maladie(CreateMaladie { nom: "Coccidiose".to_string() }).await.unwrap();
+    let gumboro = service.create_maladie(CreateMaladie { nom: "Gumboro".to_string() }).await.unwrap();
+
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        let declarer = |batiment_id: i64, maladie_id: i64, date: &str| {
+            conn.execute(
+                "INSERT INTO batiment_maladies (batiment_id, maladie_id, created_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![batiment_id, maladie_id, date],
+            ).unwrap();
+        };
+        declarer(fixtures.batiment_ids[0], coccidiose.id, "2024-01-15 08:00:00");
+        declarer(fixtures.batiment_ids[1], coccidiose.id, "2024-01-20 08:00:00");
+        declarer(autre.batiment_ids[0], coccidiose.id, "2024-03-02 08:00:00");
+        declarer(fixtures.batiment_ids[0], gumboro.id, "2024-07-10 08:00:00");
+    }
+
+    let mensuel = service.get_maladie_trends(None, "month").await.unwrap();
+    let lignes: Vec<(&str, &str, i64)> = mensuel
+        .iter()
+        .map(|t| (t.periode.as_str(), t.maladie_nom.as_str(), t.batiments_affectes))
+        .collect();
+    assert_eq!(
+        lignes,
+        vec![("2024-01", "Coccidiose", 2), ("2024-03", "Coccidiose", 1), ("2024-07", "Gumboro", 1)]
+    );
+
+    let trimestriel = service.get_maladie_trends(Some(fixtures.ferme_id), "Quarter").await.unwrap();
+    let lignes: Vec<(&str, i64)> = trimestriel.iter().map(|t| (t.periode.as_str(), t.batiments_affectes)).collect();
+    assert_eq!(lignes, vec![("2024-T1", 2), ("2024-T3", 1)]);
+
+    assert!(service.get_maladie_trends(None, "week").await.is_err());
+}
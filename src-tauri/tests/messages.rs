@@ -0,0 +1,72 @@
+//! Messages de passation entre équipes
+
+mod common;
+
+use common::{invitation, seed, TestDb};
+use tauri_app_lib::models::{CreateMessage, CreateUser};
+use tauri_app_lib::repositories::MessageRepository;
+use tauri_app_lib::services::AuthService;
+
+#[tokio::test]
+async fn handover_messages_are_listed_unread_until_marked_read() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let auth = AuthService::new(test_db.storage());
+    let inscrire = |username: &str, code: &str| CreateUser {
+        username: username.to_string(),
+        email: format!("{}@example.com", username),
+        password: "motdepasse123".to_string(),
+        registration_code: code.to_string(),
+    };
+    let admin = auth.register(inscrire("jour", "")).await.unwrap();
+    let jour = admin.user.id;
+    let code = invitation(&test_db, &admin.token).await;
+    let nuit = auth.register(inscrire("nuit", &code)).await.unwrap().user.id;
+    let conn = test_db.db.get_connection().unwrap();
+
+    // Message de nuit sur le bâtiment 1, pour toute l'équipe de jour
+    let releve = MessageRepository::create(&conn, nuit, &CreateMessage {
+        batiment_id: Some(fixtures.batiment_ids[0]),
+        contenu: "  Chauffage relancé à 3h, surveiller la température  ".to_string(),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(releve.contenu, "Chauffage relancé à 3h, surveiller la température");
+    assert_eq!(releve.auteur.as_deref(), Some("nuit"));
+    assert_eq!(releve.numero_batiment.as_deref(), Some("1"));
+    assert!(!releve.lu);
+
+    let personnel = MessageRepository::create(&conn, nuit, &CreateMessage {
+        destinataire_id: Some(jour),
+        contenu: "Livraison d'aliment prévue ce matin".to_string(),
+        ..Default::default()
+    }).unwrap();
+
+    assert!(MessageRepository::create(&conn, nuit, &CreateMessage {
+        contenu: "Sans destinataire".to_string(),
+        ..Default::default()
+    }).is_err());
+    assert!(MessageRepository::create(&conn, nuit, &CreateMessage {
+        batiment_id: Some(9999),
+        contenu: "Bâtiment inconnu".to_string(),
+        ..Default::default()
+    }).is_err());
+    assert!(MessageRepository::create(&conn, nuit, &CreateMessage {
+        destinataire_id: Some(jour),
+        contenu: "   ".to_string(),
+        ..Default::default()
+    }).is_err());
+
+    let non_lus = MessageRepository::get_unread(&conn, jour, None).unwrap();
+    assert_eq!(non_lus.iter().map(|m| m.id).collect::<Vec<_>>(), vec![releve.id, personnel.id]);
+    let batiment = MessageRepository::get_unread(&conn, jour, Some(fixtures.batiment_ids[0])).unwrap();
+    assert_eq!(batiment.len(), 1);
+
+    // L'auteur ne reçoit pas ses propres messages
+    assert!(MessageRepository::get_unread(&conn, nuit, None).unwrap().is_empty());
+
+    // Un message personnel ne peut être marqué lu que par son destinataire
+    assert!(MessageRepository::mark_read(&conn, personnel.id, nuit).is_err());
+    assert!(MessageRepository::mark_read(&conn, personnel.id, jour).unwrap().lu);
+    MessageRepository::mark_read(&conn, releve.id, jour).unwrap();
+    assert!(MessageRepository::get_unread(&conn, jour, None).unwrap().is_empty());
+}
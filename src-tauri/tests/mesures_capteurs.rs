@@ -0,0 +1,62 @@
+//! Import des exports des contrôleurs d'ambiance et agrégats pour les graphiques
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::services::CapteurService;
+
+#[tokio::test]
+async fn controller_exports_are_imported_idempotently_and_aggregated() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = CapteurService::new(test_db.storage());
+
+    let csv = "timestamp;temperature;humidite;co2\n\
+               2024-07-01 14:00:00;30,0;60;1000\n\
+               2024-07-01 14:30:00;32,0;;1400\n\
+               2024-07-01 15:10:00;34,0;70;2000\n\
+               2024-07-01 15:20:00;95,0;70;2000\n";
+    let resultat = service.import_mesures(fixtures.ferme_id, Some("1".to_string()), "csv", csv).await.unwrap();
+    assert_eq!(resultat.lignes_lues, 4);
+    assert_eq!(resultat.mesures_importees, 3);
+    assert_eq!(resultat.nombre_lignes_rejetees, 1);
+    assert_eq!(resultat.lignes_rejetees[0].ligne, 5);
+
+    // Réimporter le même export ne crée pas de doublons
+    service.import_mesures(fixtures.ferme_id, Some("1".to_string()), "csv", csv).await.unwrap();
+    assert_eq!(test_db.count("mesures_capteurs", "1 = 1"), 3);
+
+    let heures = service.get_agregats(fixtures.ferme_id, "1", None, None, None).await.unwrap();
+    assert_eq!(heures.len(), 2);
+    assert_eq!(heures[0].periode, "2024-07-01 14:00");
+    assert_eq!(heures[0].nombre_mesures, 2);
+    assert_eq!(heures[0].temperature_moyenne, Some(31.0));
+    assert_eq!(heures[0].humidite_moyenne, Some(60.0));
+    assert_eq!(heures[0].co2_max, Some(1400.0));
+
+    // Le JSON indique le bâtiment par mesure et complète l'heure de 15h
+    let json = r#"[
+        {"horodatage": "2024-07-01T15:40:00", "batiment": "1", "temperature": 30.0, "humidite": 50},
+        {"horodatage": "2024-07-02T08:00:00", "batiment": "2", "temperature": 22.0},
+        {"horodatage": "2024-07-02T08:00:00", "batiment": "9", "temperature": 22.0}
+    ]"#;
+    let resultat = service.import_mesures(fixtures.ferme_id, None, "json", json).await.unwrap();
+    assert_eq!(resultat.mesures_importees, 2);
+    assert_eq!(resultat.lignes_rejetees[0].ligne, 3);
+
+    let jours = service
+        .get_agregats(fixtures.ferme_id, "1", Some("2024-07-01".to_string()), Some("2024-07-01".to_string()), Some("day".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(jours.len(), 1);
+    assert_eq!(jours[0].periode, "2024-07-01");
+    assert_eq!(jours[0].nombre_mesures, 4);
+    assert_eq!(jours[0].temperature_min, Some(30.0));
+    assert_eq!(jours[0].temperature_max, Some(34.0));
+    assert_eq!(jours[0].temperature_moyenne, Some(31.5));
+    assert_eq!(jours[0].humidite_moyenne, Some(60.0));
+
+    assert_eq!(service.get_agregats(fixtures.ferme_id, "2", None, None, None).await.unwrap().len(), 1);
+    assert!(service.get_agregats(fixtures.ferme_id, "1", None, None, Some("week".to_string())).await.is_err());
+    assert!(service.import_mesures(fixtures.ferme_id, None, "xml", "<mesures/>").await.is_err());
+}
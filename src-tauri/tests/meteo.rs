@@ -0,0 +1,76 @@
+//! Relevés météo des fermes et rapprochement avec la mortalité
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use std::sync::Arc;
+use tauri_app_lib::error::{AppError, AppResult};
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::{PlanFermeService, ReleveMeteo, SourceMeteo, WeatherService};
+
+/// Source hors ligne: relevés fixes, échec pour les latitudes négatives
+struct SourceFixe;
+
+impl SourceMeteo for SourceFixe {
+    fn releves_journaliers(&self, latitude: f64, _longitude: f64, jours: u32) -> AppResult<Vec<ReleveMeteo>> {
+        if latitude < 0.0 {
+            return Err(AppError::business_logic("Service météo indisponible"));
+        }
+        let releve = |date: &str, temperature_max: f64| ReleveMeteo {
+            date: date.to_string(),
+            temperature_min: Some(18.0),
+            temperature_max: Some(temperature_max),
+            temperature_moyenne: Some(25.0),
+            humidite_moyenne: Some(60.0),
+        };
+        Ok(vec![releve("2024-03-01", 24.0), releve("2024-03-02", 35.5), releve("2024-03-03", 26.0)]
+            .into_iter()
+            .take(jours as usize)
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn weather_is_synced_per_ferme_and_matched_with_daily_deaths() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let sans_coordonnees = seed(&test_db).await;
+    let hors_ligne = seed(&test_db).await;
+    let plan = PlanFermeService::new(test_db.storage());
+    plan.update_ferme_coordinates(fixtures.ferme_id, Some(33.57), Some(-7.59)).await.unwrap();
+    plan.update_ferme_coordinates(hors_ligne.ferme_id, Some(-33.9), Some(18.4)).await.unwrap();
+
+    let service = WeatherService::with_source(test_db.storage(), Arc::new(SourceFixe));
+    assert!(service.sync_weather(Some(0)).await.is_err());
+
+    let resultats = service.sync_weather(Some(3)).await.unwrap();
+    let resultat = |ferme_id: i64| resultats.iter().find(|r| r.ferme_id == ferme_id).unwrap();
+    assert_eq!(resultat(fixtures.ferme_id).jours_enregistres, 3);
+    assert!(resultat(fixtures.ferme_id).erreur.is_none());
+    assert!(resultat(sans_coordonnees.ferme_id).erreur.is_some());
+    assert!(resultat(hors_ligne.ferme_id).erreur.is_some());
+
+    // Une seconde synchronisation met à jour les jours existants
+    service.sync_weather(Some(3)).await.unwrap();
+    let releves = service.get_weather_by_ferme(fixtures.ferme_id, Some("2024-03-02".to_string()), None).await.unwrap();
+    assert_eq!(releves.len(), 2);
+    assert_eq!(releves[0].temperature_max, Some(35.5));
+
+    // Bande entrée le 2024-03-01: l'âge 2 correspond au 2024-03-02
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_1_bis = semaine_id(&test_db, fixtures.batiment_ids[1], 1);
+    suivi_repo.upsert_field(semaine_1, 1, "deces_par_jour", "5").await.unwrap();
+    suivi_repo.upsert_field(semaine_1, 2, "deces_par_jour", "60").await.unwrap();
+    suivi_repo.upsert_field(semaine_1_bis, 2, "deces_par_jour", "40").await.unwrap();
+
+    let jours = service.get_mortalite_meteo(fixtures.ferme_id, None, None).await.unwrap();
+    assert_eq!(jours.len(), 2);
+    assert_eq!(jours[0].date, "2024-03-01");
+    assert!(!jours[0].vague_de_chaleur);
+    assert_eq!(jours[1].date, "2024-03-02");
+    assert_eq!(jours[1].deces, 100);
+    assert_eq!(jours[1].effectif, 10000);
+    assert_eq!(jours[1].mortalite_pourcentage, Some(1.0));
+    assert!(jours[1].vague_de_chaleur);
+}
@@ -0,0 +1,53 @@
+//! Mesures locales des commandes (appels, échecs et durées)
+
+mod common;
+
+use common::TestDb;
+use std::time::Duration;
+use tauri_app_lib::services::MetriquesCommandes;
+
+#[tokio::test]
+async fn command_durations_are_accumulated_and_stored_locally() {
+    let test_db = TestDb::new();
+    let storage = test_db.storage();
+    let metriques = MetriquesCommandes::new();
+
+    let resultat = metriques
+        .mesurer("run_report", async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            Ok::<_, String>(42)
+        })
+        .await;
+    assert_eq!(resultat, Ok(42));
+    let echec = metriques.mesurer("run_report", async { Err::<(), _>("Rapport introuvable".to_string()) }).await;
+    assert_eq!(echec, Err("Rapport introuvable".to_string()));
+    metriques.enregistrer("get_vat_summary", Duration::from_millis(2), true);
+
+    // Rien n'est écrit tant que les mesures ne sont pas enregistrées
+    assert_eq!(test_db.count("metriques_commandes", "1 = 1"), 0);
+    let mesures = metriques.get_performance_metrics(&storage).unwrap();
+    let commandes: Vec<&str> = mesures.iter().map(|m| m.commande.as_str()).collect();
+    assert_eq!(commandes, ["run_report", "get_vat_summary"]);
+    let rapport = &mesures[0];
+    assert_eq!((rapport.appels, rapport.echecs), (2, 1));
+    assert!(rapport.duree_max_ms >= 30);
+    assert_eq!(rapport.duree_moyenne_ms, rapport.duree_totale_ms as f64 / 2.0);
+    assert!(rapport.derniere_execution.is_some());
+
+    // Les mesures suivantes s'ajoutent aux totaux enregistrés
+    metriques.enregistrer("get_vat_summary", Duration::from_millis(8), true);
+    metriques.flush(&storage).unwrap();
+    let tva = metriques
+        .get_performance_metrics(&storage)
+        .unwrap()
+        .into_iter()
+        .find(|m| m.commande == "get_vat_summary")
+        .unwrap();
+    assert_eq!((tva.appels, tva.duree_totale_ms, tva.duree_max_ms), (2, 10, 8));
+
+    // Le mode lecture seule n'empêche pas l'enregistrement des mesures
+    test_db.db.set_read_only(true);
+    metriques.enregistrer("get_vat_summary", Duration::from_millis(1), true);
+    metriques.flush(&storage).unwrap();
+    assert_eq!(test_db.count("metriques_commandes", "commande = 'get_vat_summary' AND appels = 3"), 1);
+}
@@ -0,0 +1,34 @@
+//! Suppression des jours de suivi vidés par l'utilisateur
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+
+#[tokio::test]
+async fn cleared_days_are_removed_and_days_with_data_are_kept() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let autre = seed(&test_db).await;
+    let repository = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_autre = semaine_id(&test_db, autre.batiment_ids[0], 1);
+
+    // Jours 1 et 2 vidés après saisie, jour 3 conservé
+    for age in 1..=3 {
+        repository.upsert_field(semaine, age, "deces_par_jour", "2").await.unwrap();
+    }
+    repository.upsert_field(semaine, 1, "deces_par_jour", "").await.unwrap();
+    repository.upsert_field(semaine, 2, "deces_par_jour", "").await.unwrap();
+    repository.upsert_field(semaine_autre, 1, "remarques", "x").await.unwrap();
+    repository.upsert_field(semaine_autre, 1, "remarques", "").await.unwrap();
+    assert_eq!(test_db.count("suivi_quotidien", &format!("semaine_id = {}", semaine)), 3);
+
+    assert_eq!(repository.cleanup_empty(Some(fixtures.bande_id)).await.unwrap(), 2);
+    assert_eq!(test_db.count("suivi_quotidien", &format!("semaine_id = {}", semaine)), 1);
+    assert_eq!(test_db.count("suivi_quotidien", &format!("semaine_id = {}", semaine_autre)), 1);
+
+    // Sans bande: toutes les bandes actives
+    assert_eq!(repository.cleanup_empty(None).await.unwrap(), 1);
+    assert_eq!(repository.cleanup_empty(None).await.unwrap(), 0);
+}
@@ -0,0 +1,67 @@
+//! Unicité des noms sans tenir compte de la casse ni des accents
+
+mod common;
+
+use common::TestDb;
+use tauri_app_lib::models::{
+    CreateFerme, CreateMaladie, CreatePersonnel, CreatePoussin, CreateSoin, UpdateFerme, UpdatePoussin,
+};
+use tauri_app_lib::repositories::{
+    FermeRepository, FermeRepositoryTrait, PersonnelRepository, PersonnelRepositoryTrait, PoussinRepository,
+    PoussinRepositoryTrait, SoinRepository, SoinRepositoryTrait,
+};
+use tauri_app_lib::services::MaladieService;
+
+#[tokio::test]
+async fn create_rejects_names_differing_only_by_case_or_accents() {
+    let test_db = TestDb::new();
+    let storage = test_db.storage();
+
+    let fermes = FermeRepository::new(storage.clone());
+    fermes.create(CreateFerme { nom: "Ferme A".to_string(), nbr_meuble: 2 }).await.unwrap();
+    for doublon in ["ferme a", "Férme A", "  FERME   A "] {
+        assert!(fermes.create(CreateFerme { nom: doublon.to_string(), nbr_meuble: 2 }).await.is_err());
+    }
+
+    let personnel = PersonnelRepository::new(storage.clone());
+    let technicien = |nom: &str| CreatePersonnel { nom: nom.to_string(), telephone: "0612345678".to_string() };
+    personnel.create(technicien("Hélène Amrani")).await.unwrap();
+    assert!(personnel.create(technicien("helene amrani")).await.is_err());
+
+    let soins = SoinRepository::new(storage.clone());
+    let soin = |nom: &str| CreateSoin { nom: nom.to_string(), unit: "ml".to_string(), ..Default::default() };
+    soins.create(soin("Vitamine AD3E")).await.unwrap();
+    assert!(soins.create(soin("vitamine ad3e")).await.is_err());
+
+    let poussins = PoussinRepository::new(storage.clone());
+    poussins.create(CreatePoussin { nom: "Cobb 500".to_string() }).await.unwrap();
+    assert!(poussins.create(CreatePoussin { nom: "COBB 500".to_string() }).await.is_err());
+
+    let maladies = MaladieService::new(storage);
+    maladies.create_maladie(CreateMaladie { nom: "Maladie de Newcastle".to_string() }).await.unwrap();
+    let erreur = maladies
+        .create_maladie(CreateMaladie { nom: "maladie de newcastle".to_string() })
+        .await
+        .unwrap_err();
+    assert_eq!(erreur, "Une maladie avec ce nom existe déjà");
+}
+
+#[tokio::test]
+async fn update_allows_own_name_but_not_another_one() {
+    let test_db = TestDb::new();
+    let storage = test_db.storage();
+
+    let fermes = FermeRepository::new(storage.clone());
+    let a = fermes.create(CreateFerme { nom: "Ferme A".to_string(), nbr_meuble: 2 }).await.unwrap().id.unwrap();
+    fermes.create(CreateFerme { nom: "Ferme B".to_string(), nbr_meuble: 2 }).await.unwrap();
+
+    // Changer seulement la casse de son propre nom reste possible
+    fermes.update(UpdateFerme { id: a, nom: "FERME A".to_string(), nbr_meuble: 3, version: None }).await.unwrap();
+    assert!(fermes.update(UpdateFerme { id: a, nom: "ferme b".to_string(), nbr_meuble: 3, version: None }).await.is_err());
+
+    let poussins = PoussinRepository::new(storage);
+    let cobb = poussins.create(CreatePoussin { nom: "Cobb 500".to_string() }).await.unwrap().id.unwrap();
+    poussins.create(CreatePoussin { nom: "Ross 308".to_string() }).await.unwrap();
+    assert!(poussins.update(UpdatePoussin { id: cobb, nom: "ROSS 308".to_string() }).await.is_err());
+    assert_eq!(test_db.count("poussins", "nom_normalise = 'cobb 500'"), 1);
+}
@@ -0,0 +1,37 @@
+//! Chronologie des notes par bâtiment
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::CreateNoteBatiment;
+use tauri_app_lib::repositories::NoteBatimentRepository;
+
+#[tokio::test]
+async fn notes_are_listed_chronologically_and_filtered_by_tag() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+    let batiment_id = fixtures.batiment_ids[0];
+
+    let note = |contenu: &str, tag: Option<&str>| CreateNoteBatiment {
+        batiment_id,
+        auteur: "Technicien".to_string(),
+        contenu: contenu.to_string(),
+        tag: tag.map(str::to_string),
+    };
+
+    let premiere = NoteBatimentRepository::create(&conn, &note("Litière humide côté est", Some("Litière"))).unwrap();
+    assert_eq!(premiere.tag.as_deref(), Some("litière"));
+    NoteBatimentRepository::create(&conn, &note("Abreuvoir ligne 3 qui fuit", Some("matériel"))).unwrap();
+    NoteBatimentRepository::create(&conn, &note("Litière changée", Some("litière"))).unwrap();
+
+    let notes = NoteBatimentRepository::get_by_batiment(&conn, batiment_id, None).unwrap();
+    assert_eq!(notes.len(), 3);
+    assert_eq!(notes[0].id, premiere.id);
+
+    let litiere = NoteBatimentRepository::get_by_batiment(&conn, batiment_id, Some("LITIÈRE")).unwrap();
+    assert_eq!(litiere.len(), 2);
+
+    assert!(NoteBatimentRepository::create(&conn, &note("   ", None)).is_err());
+    assert!(NoteBatimentRepository::get_by_batiment(&conn, fixtures.batiment_ids[1], None).unwrap().is_empty());
+}
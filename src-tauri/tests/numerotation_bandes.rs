@@ -0,0 +1,60 @@
+//! Numéros de bande générés selon le format paramétré
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, TestDb};
+use tauri_app_lib::database::numerotation::code_ferme;
+use tauri_app_lib::models::{CreateBande, UpdateBande};
+use tauri_app_lib::repositories::BandeRepository;
+use tauri_app_lib::services::{BandeService, FermeService};
+
+#[tokio::test]
+async fn new_bandes_follow_the_configured_pattern() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = BandeService::new(test_db.storage());
+    let conn = test_db.db.get_connection().unwrap();
+
+    // Sans format enregistré, le numéro affiché reste le numéro séquentiel
+    assert_eq!(service.get_numbering_pattern().await.unwrap(), "{seq}");
+    let premiere = service.get_bande_by_id(fixtures.bande_id).await.unwrap().unwrap();
+    assert_eq!(premiere.numero_affiche, "1");
+
+    assert!(service.set_numbering_pattern("{year}-{lot}").await.is_err());
+    assert!(service.set_numbering_pattern("{year}-{ferme_code}").await.is_err());
+    service.set_numbering_pattern(" {year}-{ferme_code}-{seq:03} ").await.unwrap();
+    assert_eq!(service.get_numbering_pattern().await.unwrap(), "{year}-{ferme_code}-{seq:03}");
+
+    let date_entree = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+    let bande = BandeRepository::create(
+        &conn,
+        &CreateBande { date_entree, ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+        None,
+    )
+    .unwrap();
+    let ferme = FermeService::new(test_db.storage()).get_ferme_by_id(fixtures.ferme_id).await.unwrap();
+    let attendu = format!("2025-{}-002", code_ferme(&ferme.nom));
+    assert_eq!(bande.numero_affiche, attendu);
+
+    // Les bandes existantes gardent leur numéro, même modifiées après un changement de format
+    service.set_numbering_pattern("B{seq}").await.unwrap();
+    BandeRepository::update(
+        &conn,
+        bande.id.unwrap(),
+        &UpdateBande {
+            id: bande.id.unwrap(),
+            numero_bande: 2,
+            date_entree,
+            ferme_id: fixtures.ferme_id,
+            notes: Some("Lot renuméroté".to_string()),
+            version: None,
+            champs_personnalises: Default::default(),
+        },
+    )
+    .unwrap();
+    let bande = service.get_bande_by_id(bande.id.unwrap()).await.unwrap().unwrap();
+    assert_eq!(bande.numero_affiche, attendu);
+    let premiere = service.get_bande_by_id(fixtures.bande_id).await.unwrap().unwrap();
+    assert_eq!(premiere.numero_affiche, "1");
+}
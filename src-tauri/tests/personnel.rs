@@ -0,0 +1,111 @@
+//! Validation du personnel: téléphone normalisé et unique, réaffectation des bâtiments
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::models::{CreatePersonnel, ModificationBatiment, UpdatePersonnel};
+use tauri_app_lib::repositories::{BatimentRepository, PersonnelRepository, PersonnelRepositoryTrait};
+use tauri_app_lib::services::PersonnelService;
+
+#[tokio::test]
+async fn telephone_is_normalized_and_unique() {
+    let test_db = TestDb::new();
+    let service = PersonnelService::new(test_db.storage());
+
+    let karim = service
+        .create_personnel(CreatePersonnel { nom: "Karim".to_string(), telephone: "+212 6 12 34 56 78".to_string() })
+        .await
+        .unwrap();
+    assert_eq!(karim.telephone, "0612345678");
+
+    // Même numéro sous une autre forme
+    let doublon = service
+        .create_personnel(CreatePersonnel { nom: "Samir".to_string(), telephone: "06-12-34-56-78".to_string() })
+        .await;
+    assert!(doublon.is_err());
+
+    // Numéro saisi avant la normalisation, directement en base
+    PersonnelRepository::new(test_db.storage())
+        .create(CreatePersonnel { nom: "Youssef".to_string(), telephone: "07 00 11 22 33".to_string() })
+        .await
+        .unwrap();
+    assert!(service
+        .create_personnel(CreatePersonnel { nom: "Nadia".to_string(), telephone: "00212700112233".to_string() })
+        .await
+        .is_err());
+
+    assert!(service
+        .create_personnel(CreatePersonnel { nom: "Nadia".to_string(), telephone: "12345".to_string() })
+        .await
+        .is_err());
+
+    // Conserver son propre numéro lors d'une mise à jour reste possible
+    let id = karim.id.unwrap();
+    let modifie = service
+        .update_personnel(UpdatePersonnel { id, nom: "Karim B.".to_string(), telephone: "0612345678".to_string(), version: None })
+        .await
+        .unwrap();
+    assert_eq!(modifie.nom, "Karim B.");
+    assert!(service
+        .update_personnel(UpdatePersonnel { id, nom: "Karim B.".to_string(), telephone: "0700112233".to_string(), version: None })
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn batiments_are_reassigned_together_or_not_at_all() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let storage = test_db.storage();
+    let remplacant = PersonnelRepository::new(storage.clone())
+        .create(CreatePersonnel { nom: "Remplaçant".to_string(), telephone: "0698765432".to_string() })
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    let reaffecter = |batiment_id: i64, personnel_id: i64| ModificationBatiment {
+        batiment_id,
+        personnel_id: Some(personnel_id),
+        poussin_id: None,
+    };
+
+    // Le second changement est invalide: le premier n'est pas appliqué
+    let erreur = storage
+        .write(|tx| {
+            BatimentRepository::bulk_update(
+                tx,
+                fixtures.bande_id,
+                &[reaffecter(fixtures.batiment_ids[0], remplacant), reaffecter(fixtures.batiment_ids[1], remplacant + 100)],
+            )
+        })
+        .unwrap_err();
+    assert!(erreur.to_string().contains("personnel"), "{}", erreur);
+    assert_eq!(test_db.count("batiments", &format!("personnel_id = {}", remplacant)), 0);
+
+    let doublon = [reaffecter(fixtures.batiment_ids[0], remplacant), reaffecter(fixtures.batiment_ids[0], remplacant)];
+    assert!(storage.write(|tx| BatimentRepository::bulk_update(tx, fixtures.bande_id, &doublon)).is_err());
+    let sans_changement = [ModificationBatiment { batiment_id: fixtures.batiment_ids[0], ..Default::default() }];
+    assert!(storage.write(|tx| BatimentRepository::bulk_update(tx, fixtures.bande_id, &sans_changement)).is_err());
+    let autre_bande = [reaffecter(fixtures.batiment_ids[0], remplacant)];
+    assert!(storage.write(|tx| BatimentRepository::bulk_update(tx, fixtures.bande_id + 100, &autre_bande)).is_err());
+
+    let modifies = storage
+        .write(|tx| {
+            BatimentRepository::bulk_update(
+                tx,
+                fixtures.bande_id,
+                &[reaffecter(fixtures.batiment_ids[0], remplacant), reaffecter(fixtures.batiment_ids[1], remplacant)],
+            )
+        })
+        .unwrap();
+    assert_eq!(modifies, 2);
+
+    let conn = test_db.db.get_connection().unwrap();
+    let batiments = BatimentRepository::get_by_bande(&conn, fixtures.bande_id).unwrap();
+    for batiment in &batiments {
+        assert_eq!(batiment.personnel_id, remplacant);
+        assert_eq!(batiment.personnel_nom, "Remplaçant");
+        assert_eq!(batiment.poussin_id, fixtures.poussin_id);
+        assert_eq!(batiment.version, 2);
+    }
+}
@@ -0,0 +1,180 @@
+//! Plan du site des fermes: coordonnées, positions et état des bâtiments
+
+mod common;
+
+use chrono::{Duration, Local, NaiveDate};
+use common::{seed, TestDb};
+use tauri_app_lib::models::{CreateBande, CreateBatiment, PositionBatiment, NIVEAU_AVERTISSEMENT};
+use tauri_app_lib::repositories::{BandeRepository, BatimentRepository};
+use tauri_app_lib::services::{BandeService, PlanFermeService};
+
+fn position(ferme_id: i64, numero: &str) -> PositionBatiment {
+    PositionBatiment {
+        ferme_id,
+        numero_batiment: numero.to_string(),
+        plan_x: Some(10.0),
+        plan_y: Some(20.0),
+        largeur: Some(12.0),
+        hauteur: Some(80.0),
+        latitude: None,
+        longitude: None,
+    }
+}
+
+#[tokio::test]
+async fn plan_lists_positions_and_current_occupation() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = PlanFermeService::new(test_db.storage());
+    let conn = test_db.db.get_connection().unwrap();
+
+    // Bande en cours occupant le bâtiment 3
+    let bande = BandeRepository::create(
+        &conn,
+        &CreateBande {
+            date_entree: Local::now().date_naive() - Duration::days(9),
+            ferme_id: fixtures.ferme_id,
+            notes: None,
+            champs_personnalises: Default::default(),
+        },
+        None,
+    )
+    .unwrap();
+    let batiment = BatimentRepository::create(
+        &conn,
+        &CreateBatiment {
+            bande_id: bande.id.unwrap(),
+            numero_batiment: "3".to_string(),
+            poussin_id: fixtures.poussin_id,
+            personnel_id: fixtures.personnel_id,
+            quantite: 4000,
+            autoriser_cohabitation: false,
+            champs_personnalises: Default::default(),
+        },
+        None,
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO semaines (batiment_id, numero_semaine) VALUES (?1, 2), (?1, 1)",
+        [batiment.id.unwrap()],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, 1, 30), (?1, 2, 10)",
+        [conn.last_insert_rowid()],
+    )
+    .unwrap();
+
+    service.update_ferme_coordinates(fixtures.ferme_id, Some(33.57), Some(-7.59)).await.unwrap();
+    service.update_batiment_position(position(fixtures.ferme_id, " 3 ")).await.unwrap();
+    let mut deplace = position(fixtures.ferme_id, "3");
+    deplace.plan_x = Some(40.0);
+    service.update_batiment_position(deplace).await.unwrap();
+
+    let plan = service.get_ferme_plan(fixtures.ferme_id).await.unwrap();
+    assert_eq!(plan.latitude, Some(33.57));
+    assert_eq!(plan.batiments.len(), 4);
+    assert!(plan.batiments[0].position.is_none());
+    assert!(plan.batiments[0].occupation.is_none(), "la bande de 2024 est terminée");
+
+    let batiment_3 = &plan.batiments[2];
+    assert_eq!(batiment_3.numero_batiment, "3");
+    assert_eq!(batiment_3.position.as_ref().unwrap().plan_x, Some(40.0));
+    let occupation = batiment_3.occupation.as_ref().unwrap();
+    assert_eq!(occupation.batiment_id, batiment.id.unwrap());
+    assert_eq!(occupation.age_jours, 10);
+    assert_eq!(occupation.deces, 40);
+    assert_eq!(occupation.effectif_vivant, 3960);
+    assert!((occupation.mortalite_pourcentage - 1.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn invalid_geometry_is_rejected() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = PlanFermeService::new(test_db.storage());
+
+    assert!(service.update_ferme_coordinates(fixtures.ferme_id, Some(95.0), Some(0.0)).await.is_err());
+    assert!(service.update_ferme_coordinates(fixtures.ferme_id, Some(33.0), None).await.is_err());
+    assert!(service.update_ferme_coordinates(9999, None, None).await.is_err());
+
+    assert!(service.update_batiment_position(position(fixtures.ferme_id, "5")).await.is_err());
+    let mut sans_largeur = position(fixtures.ferme_id, "1");
+    sans_largeur.largeur = Some(0.0);
+    assert!(service.update_batiment_position(sans_largeur).await.is_err());
+}
+
+#[tokio::test]
+async fn occupancy_timeline_flags_double_booked_buildings() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = PlanFermeService::new(test_db.storage());
+    let date = |mois: u32, jour: u32| NaiveDate::from_ymd_opt(2024, mois, jour).unwrap();
+
+    // Bande entrée le 2024-04-10 dans le bâtiment 2, avant l'enlèvement de la bande du 2024-03-01
+    let conn = test_db.db.get_connection().unwrap();
+    let bande = BandeRepository::create(
+        &conn,
+        &CreateBande { date_entree: date(4, 10), ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+        None,
+    )
+    .unwrap();
+    let batiment = BatimentRepository::create(
+        &conn,
+        &CreateBatiment {
+            bande_id: bande.id.unwrap(),
+            numero_batiment: "2".to_string(),
+            poussin_id: fixtures.poussin_id,
+            personnel_id: fixtures.personnel_id,
+            quantite: 3000,
+            autoriser_cohabitation: false,
+            champs_personnalises: Default::default(),
+        },
+        None,
+    )
+    .unwrap();
+    drop(conn);
+
+    let chronologies = service.get_building_occupancy(fixtures.ferme_id, date(1, 1), date(12, 31)).await.unwrap();
+    assert_eq!(chronologies.len(), 4);
+    let batiment_1 = &chronologies[0].intervalles;
+    assert_eq!(batiment_1.len(), 1);
+    assert_eq!((batiment_1[0].date_debut, batiment_1[0].date_fin), (date(3, 1), date(4, 25)));
+    assert!(!batiment_1[0].chevauchement);
+
+    let batiment_2 = &chronologies[1].intervalles;
+    assert_eq!(batiment_2.len(), 2);
+    assert_eq!(batiment_2[1].batiment_id, batiment.id.unwrap());
+    // Sans semaines créées, l'occupation couvre les 8 semaines de suivi
+    assert_eq!(batiment_2[1].date_fin, date(6, 4));
+    assert!(batiment_2.iter().all(|intervalle| intervalle.chevauchement));
+    assert!(chronologies[2].intervalles.is_empty());
+
+    let mai = service.get_building_occupancy(fixtures.ferme_id, date(5, 1), date(5, 31)).await.unwrap();
+    assert!(mai[0].intervalles.is_empty());
+    assert_eq!(mai[1].intervalles.len(), 1);
+    assert!(!mai[1].intervalles[0].chevauchement);
+
+    assert!(service.get_building_occupancy(fixtures.ferme_id, date(5, 31), date(5, 1)).await.is_err());
+    assert!(service.get_building_occupancy(9999, date(5, 1), date(5, 31)).await.is_err());
+
+    // La création d'une bande dans le bâtiment 1 le 2024-04-01 chevauche la bande du 2024-03-01
+    let validation = BandeService::new(test_db.storage())
+        .validate_bande_creation(
+            CreateBande { date_entree: date(4, 1), ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+            Some(vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "1".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 3000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(validation.problemes.len(), 1);
+    assert_eq!(validation.problemes[0].niveau, NIVEAU_AVERTISSEMENT);
+    assert!(validation.problemes[0].message.contains("du 2024-03-01 au 2024-04-25"), "{}", validation.problemes[0].message);
+}
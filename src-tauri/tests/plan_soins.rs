@@ -0,0 +1,100 @@
+//! Plan de soins quotidien par type de poussin, reporté dans le suivi
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateBande, CreateBatiment, CreatePlanSoin, CreateSoin, UpdatePlanSoin};
+use tauri_app_lib::repositories::{
+    PlanSoinsRepository, SoinRepository, SoinRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{BandeService, SemaineService};
+
+fn plan_soin(poussin_id: i64, soin_id: i64, jour_debut: i32, jour_fin: i32, quantite: Option<&str>) -> CreatePlanSoin {
+    CreatePlanSoin {
+        poussin_id,
+        soin_id,
+        jour_debut,
+        jour_fin,
+        quantite: quantite.map(str::to_string),
+        unit: None,
+    }
+}
+
+#[tokio::test]
+async fn plan_is_applied_to_the_days_of_new_bandes() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let vitamine = soin_repo.create(CreateSoin { nom: "Vitamine".to_string(), unit: "ml".to_string(), ..Default::default() }).await.unwrap();
+    let anticoccidien = soin_repo.create(CreateSoin { nom: "Anticoccidien".to_string(), unit: "g".to_string(), ..Default::default() }).await.unwrap();
+    let (vitamine_id, anticoccidien_id) = (vitamine.id.unwrap(), anticoccidien.id.unwrap());
+
+    let conn = test_db.db.get_connection().unwrap();
+    PlanSoinsRepository::create(&conn, &plan_soin(fixtures.poussin_id, vitamine_id, 3, 5, Some("500"))).unwrap();
+    PlanSoinsRepository::create(&conn, &plan_soin(fixtures.poussin_id, anticoccidien_id, 10, 11, None)).unwrap();
+
+    // Le même soin ne peut pas être prévu deux fois le même jour, ni finir avant de commencer
+    assert!(PlanSoinsRepository::create(&conn, &plan_soin(fixtures.poussin_id, vitamine_id, 5, 6, None)).is_err());
+    assert!(PlanSoinsRepository::create(&conn, &plan_soin(fixtures.poussin_id, anticoccidien_id, 4, 2, None)).is_err());
+    assert_eq!(PlanSoinsRepository::get_by_poussin(&conn, fixtures.poussin_id).unwrap().len(), 2);
+    drop(conn);
+
+    let bande = BandeService::new(test_db.storage())
+        .create_bande_with_batiments_and_first_week(
+            CreateBande { date_entree: NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "9".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 1000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    let batiment_id: i64 = test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .query_row("SELECT id FROM batiments WHERE bande_id = ?1", [bande.id.unwrap()], |row| row.get(0))
+        .unwrap();
+
+    // Semaine 1: la vitamine est pré-remplie aux jours 3 à 5, avec l'unité du soin
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let jours = suivi_repo.get_by_semaine(semaine_id(&test_db, batiment_id, 1)).await.unwrap();
+    let avec_soin: Vec<_> = jours.iter().filter(|jour| !jour.soins.is_empty()).collect();
+    assert_eq!(avec_soin.iter().map(|jour| jour.age).collect::<Vec<_>>(), vec![3, 4, 5]);
+    assert_eq!(avec_soin[0].soins_id, Some(vitamine_id));
+    assert_eq!(avec_soin[0].soins_quantite.as_deref(), Some("500"));
+    assert_eq!(avec_soin[0].soins[0].unit.as_deref(), Some("ml"));
+
+    // Les semaines suivantes reçoivent leurs soins à leur création
+    SemaineService::new(test_db.storage()).initialize_batiment_semaines(batiment_id).await.unwrap();
+    let jours = suivi_repo.get_by_semaine(semaine_id(&test_db, batiment_id, 2)).await.unwrap();
+    let ages: Vec<_> = jours.iter().filter(|jour| jour.soins_id == Some(anticoccidien_id)).map(|jour| jour.age).collect();
+    assert_eq!(ages, vec![10, 11]);
+
+    // Modifier le plan ne change pas les soins déjà reportés
+    let conn = test_db.db.get_connection().unwrap();
+    let plan = PlanSoinsRepository::get_by_poussin(&conn, fixtures.poussin_id).unwrap();
+    PlanSoinsRepository::update(
+        &conn,
+        &UpdatePlanSoin {
+            id: plan[0].id.unwrap(),
+            soin_id: vitamine_id,
+            jour_debut: 1,
+            jour_fin: 2,
+            quantite: None,
+            unit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(test_db.count("suivi_soins", &format!("soin_id = {}", vitamine_id)), 3);
+
+    PlanSoinsRepository::delete(&conn, plan[1].id.unwrap()).unwrap();
+    assert_eq!(PlanSoinsRepository::get_by_poussin(&conn, fixtures.poussin_id).unwrap().len(), 1);
+}
@@ -0,0 +1,49 @@
+//! Préférences d'affichage enregistrées par compte
+
+mod common;
+
+use common::{invitation, TestDb};
+use serde_json::json;
+use tauri_app_lib::models::CreateUser;
+use tauri_app_lib::services::{AuthService, PreferenceService};
+
+async fn register(test_db: &TestDb, username: &str, registration_code: &str) -> String {
+    AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: username.to_string(),
+            email: format!("{}@example.com", username),
+            password: "motdepasse123".to_string(),
+            registration_code: registration_code.to_string(),
+        })
+        .await
+        .unwrap()
+        .token
+}
+
+#[tokio::test]
+async fn preferences_are_merged_per_account() {
+    let test_db = TestDb::new();
+    let admin = register(&test_db, "admin", "").await;
+    let code = invitation(&test_db, &admin).await;
+    let technicien = register(&test_db, "technicien", &code).await;
+    let service = PreferenceService::new(test_db.storage());
+
+    assert_eq!(service.get_user_preferences(&admin).await.unwrap(), json!({}));
+
+    service
+        .set_user_preferences(json!({ "default_ferme_id": 3, "columns": { "bandes": ["numero", "date"] } }), &admin)
+        .await
+        .unwrap();
+    let preferences = service
+        .set_user_preferences(json!({ "dashboard": ["alertes"], "default_ferme_id": null }), &admin)
+        .await
+        .unwrap();
+    assert_eq!(preferences, json!({ "columns": { "bandes": ["numero", "date"] }, "dashboard": ["alertes"] }));
+    assert_eq!(service.get_user_preferences(&admin).await.unwrap(), preferences);
+
+    // Chaque compte a ses propres préférences
+    assert_eq!(service.get_user_preferences(&technicien).await.unwrap(), json!({}));
+
+    assert!(service.set_user_preferences(json!(["pas", "un", "objet"]), &admin).await.is_err());
+    assert!(service.get_user_preferences("token-inconnu").await.is_err());
+}
@@ -0,0 +1,79 @@
+//! Assistant de premier démarrage
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{ConfigurationInitiale, CreateFerme};
+use tauri_app_lib::repositories::{
+    AlimentationRepository, ProgrammeAlimentationRepository, SemaineRepository, SuiviQuotidienRepository,
+    SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::ConfigurationService;
+
+fn ferme(nom: &str, nbr_meuble: i32) -> CreateFerme {
+    CreateFerme { nom: nom.to_string(), nbr_meuble }
+}
+
+#[tokio::test]
+async fn first_run_setup_provisions_fermes_and_settings_in_one_transaction() {
+    let test_db = TestDb::new();
+    let service = ConfigurationService::new(test_db.storage());
+    assert!(service.needs_first_run_setup().await.unwrap());
+
+    // Une ferme refusée annule toute la configuration
+    let refusee = service
+        .first_run_setup(ConfigurationInitiale {
+            fermes: vec![ferme("Ferme Nord", 4), ferme("ferme nord", 2)],
+            semaines_max: Some(7),
+            kg_par_sachet: Some(25.0),
+        })
+        .await;
+    assert!(refusee.is_err());
+    assert_eq!(test_db.count("fermes", "1 = 1"), 0);
+    let conn = test_db.db.get_connection().unwrap();
+    assert_eq!(SemaineRepository::get_semaines_max(&conn).unwrap(), 9);
+    drop(conn);
+
+    assert!(service.first_run_setup(ConfigurationInitiale::default()).await.is_err());
+    let sachet_invalide = ConfigurationInitiale {
+        fermes: vec![ferme("Ferme Nord", 4)],
+        kg_par_sachet: Some(0.0),
+        ..Default::default()
+    };
+    assert!(service.first_run_setup(sachet_invalide).await.is_err());
+
+    let configuration = service
+        .first_run_setup(ConfigurationInitiale {
+            fermes: vec![ferme(" Ferme Nord ", 4), ferme("Ferme Sud", 2)],
+            semaines_max: Some(7),
+            kg_par_sachet: Some(25.0),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        configuration.fermes.iter().map(|f| (f.nom.as_str(), f.nbr_meuble)).collect::<Vec<_>>(),
+        vec![("Ferme Nord", 4), ("Ferme Sud", 2)]
+    );
+    assert_eq!((configuration.semaines_max, configuration.kg_par_sachet), (7, 25.0));
+    assert!(!service.needs_first_run_setup().await.unwrap());
+
+    // L'assistant n'est plus disponible une fois la base configurée
+    let erreur = service
+        .first_run_setup(ConfigurationInitiale { fermes: vec![ferme("Ferme Est", 3)], ..Default::default() })
+        .await
+        .unwrap_err();
+    assert!(erreur.to_string().contains("déjà configurée"), "{}", erreur);
+
+    // Le poids du sachet sert à convertir l'alimentation saisie
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+    assert_eq!(ProgrammeAlimentationRepository::get_kg_par_sachet(&conn).unwrap(), 25.0);
+    let contour_initial = AlimentationRepository::get_contour(&conn, fixtures.bande_id).unwrap();
+    drop(conn);
+    SuiviQuotidienRepository::new(test_db.storage())
+        .upsert_field(semaine_id(&test_db, fixtures.batiment_ids[0], 1), 1, "alimentation_par_jour", "2")
+        .await
+        .unwrap();
+    let conn = test_db.db.get_connection().unwrap();
+    assert_eq!(AlimentationRepository::get_contour(&conn, fixtures.bande_id).unwrap(), contour_initial - 50.0);
+}
@@ -0,0 +1,78 @@
+//! Bases de profils ouvertes à la place de la base principale
+
+mod common;
+
+use common::{seed, TestDb};
+use tauri_app_lib::database::{Storage, PROFIL_PRINCIPAL};
+use tauri_app_lib::repositories::{FermeRepository, FermeRepositoryTrait};
+
+#[tokio::test]
+async fn switching_profile_opens_a_separate_database() {
+    let test_db = TestDb::new();
+    seed(&test_db).await;
+    let fermes = FermeRepository::new(test_db.storage());
+    let version = test_db.db.data_version();
+
+    let actif = test_db.db.get_active_profile();
+    assert_eq!(actif.nom, PROFIL_PRINCIPAL);
+    assert!(actif.principal);
+
+    let demo = test_db.db.switch_profile("demo").unwrap();
+    assert_eq!(demo.nom, "demo");
+    assert!(!demo.principal);
+    assert!(test_db.dir().join("demo.db").exists());
+    assert!(test_db.db.data_version() > version);
+
+    // Le bac à sable a son propre schéma et ses propres données
+    assert!(fermes.get_all().await.unwrap().is_empty());
+    assert_eq!(test_db.count("fermes", "1"), 0);
+
+    let principal = test_db.db.switch_profile(PROFIL_PRINCIPAL).unwrap();
+    assert!(principal.principal);
+    assert_eq!(fermes.get_all().await.unwrap().len(), 1);
+
+    for invalide in ["", "../x", "Demo", "geema", "geema-archive", "demo-archive", &"a".repeat(33)] {
+        assert!(test_db.db.switch_profile(invalide).is_err(), "{}", invalide);
+    }
+    assert_eq!(test_db.db.get_active_profile().nom, PROFIL_PRINCIPAL);
+}
+
+#[tokio::test]
+async fn profiles_can_be_listed_created_opened_and_deleted() {
+    let test_db = TestDb::new();
+    seed(&test_db).await;
+    let noms = |db: &TestDb| db.db.list_profiles().unwrap().into_iter().map(|p| (p.nom, p.actif)).collect::<Vec<_>>();
+    assert_eq!(noms(&test_db), [(PROFIL_PRINCIPAL.to_string(), true)]);
+
+    let cree = test_db.db.create_profile(" ferme-nord ").unwrap();
+    assert_eq!(cree.nom, "ferme-nord");
+    assert!(!cree.actif);
+    assert!(test_db.db.create_profile("ferme-nord").is_err());
+    test_db.db.create_profile("demo").unwrap();
+    // Un fichier étranger au format des profils n'est pas listé
+    std::fs::write(test_db.dir().join("Copie.db"), b"").unwrap();
+    assert_eq!(
+        noms(&test_db),
+        [(PROFIL_PRINCIPAL.to_string(), true), ("demo".to_string(), false), ("ferme-nord".to_string(), false)]
+    );
+    assert_eq!(test_db.db.get_active_profile().nom, PROFIL_PRINCIPAL);
+
+    // Seul un profil existant peut être ouvert
+    assert!(test_db.db.open_profile("inconnu").is_err());
+    assert!(!test_db.dir().join("inconnu.db").exists());
+    test_db.db.open_profile("ferme-nord").unwrap();
+    assert_eq!(test_db.count("fermes", "1"), 0);
+    assert_eq!(noms(&test_db)[2], ("ferme-nord".to_string(), true));
+
+    // Ni la base principale ni la base ouverte ne peuvent être supprimées
+    assert!(test_db.db.delete_profile(PROFIL_PRINCIPAL).is_err());
+    assert!(test_db.db.delete_profile("ferme-nord").is_err());
+    test_db.db.delete_profile("demo").unwrap();
+    assert!(!test_db.dir().join("demo.db").exists());
+    assert!(test_db.db.delete_profile("demo").is_err());
+
+    test_db.db.open_profile(PROFIL_PRINCIPAL).unwrap();
+    test_db.db.delete_profile("ferme-nord").unwrap();
+    assert_eq!(noms(&test_db), [(PROFIL_PRINCIPAL.to_string(), true)]);
+    assert_eq!(test_db.count("fermes", "1"), 1);
+}
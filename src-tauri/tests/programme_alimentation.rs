@@ -0,0 +1,125 @@
+//! Programme d'alimentation par type de poussin et conformité du suivi
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateAlimentationHistory, CreatePhaseAlimentation};
+use tauri_app_lib::repositories::{
+    AlimentationRepository, ProgrammeAlimentationRepository, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+
+fn phase(poussin_id: i64, nom: &str, jour_debut: Option<i32>, jour_fin: i32, grammes: f64) -> CreatePhaseAlimentation {
+    CreatePhaseAlimentation {
+        poussin_id,
+        nom_phase: nom.to_string(),
+        jour_debut,
+        jour_fin,
+        grammes_par_sujet: grammes,
+    }
+}
+
+#[tokio::test]
+async fn phases_follow_each_other_and_cannot_overlap() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    let demarrage = ProgrammeAlimentationRepository::create(&conn, &phase(fixtures.poussin_id, "Démarrage", None, 10, 25.0)).unwrap();
+    let croissance = ProgrammeAlimentationRepository::create(&conn, &phase(fixtures.poussin_id, "Croissance", None, 24, 90.0)).unwrap();
+    assert_eq!(demarrage.jour_debut, 1);
+    assert_eq!(croissance.jour_debut, 11);
+
+    let overlap = ProgrammeAlimentationRepository::create(&conn, &phase(fixtures.poussin_id, "Finition", Some(20), 42, 160.0));
+    assert!(overlap.is_err());
+
+    let programme = ProgrammeAlimentationRepository::get_by_poussin(&conn, fixtures.poussin_id).unwrap();
+    assert_eq!(programme.len(), 2);
+}
+
+#[tokio::test]
+async fn compliance_compares_grams_per_live_bird() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    let conn = test_db.db.get_connection().unwrap();
+    ProgrammeAlimentationRepository::create(&conn, &phase(fixtures.poussin_id, "Démarrage", None, 10, 20.0)).unwrap();
+    drop(conn);
+
+    // 5000 sujets: 2 sachets = 100 kg = 20 g/sujet
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, batiment_id, 1);
+    suivi_repo.upsert_field(semaine_1, 1, "alimentation_par_jour", "2").await.unwrap();
+    suivi_repo.upsert_field(semaine_1, 1, "deces_par_jour", "1000").await.unwrap();
+    // 4000 sujets restants: 2 sachets = 25 g/sujet, soit +25 %
+    suivi_repo.upsert_field(semaine_1, 2, "alimentation_par_jour", "2").await.unwrap();
+    // Jour sans saisie d'aliment: ignoré
+    suivi_repo.upsert_field(semaine_1, 3, "deces_par_jour", "3").await.unwrap();
+
+    let conn = test_db.db.get_connection().unwrap();
+    let conformite = ProgrammeAlimentationRepository::get_compliance(&conn, batiment_id).unwrap();
+
+    assert_eq!(conformite.jours.len(), 2);
+    assert_eq!(conformite.jours[0].effectif, 5000);
+    assert_eq!(conformite.jours[0].grammes_reels, Some(20.0));
+    assert_eq!(conformite.jours[0].ecart_pourcentage, Some(0.0));
+    assert_eq!(conformite.jours[1].effectif, 4000);
+    assert_eq!(conformite.jours[1].grammes_reels, Some(25.0));
+    assert_eq!(conformite.jours[1].ecart_pourcentage, Some(25.0));
+
+    assert_eq!(conformite.phases.len(), 1);
+    assert_eq!(conformite.phases[0].jours_saisis, 2);
+    assert_eq!(conformite.phases[0].grammes_reels_moyens, Some(22.5));
+}
+
+#[tokio::test]
+async fn forecast_projects_feed_needs_until_catch_against_stock() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+    ProgrammeAlimentationRepository::create(&conn, &phase(fixtures.poussin_id, "Unique", None, 56, 100.0)).unwrap();
+    AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 10000.0,
+            created_at: "2024-03-01 08:00:00".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    drop(conn);
+
+    // Bâtiment 1: 12 sachets par jour la première semaine, soit 120 g/sujet (+20 %)
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    for age in 1..=7 {
+        suivi_repo.upsert_field(semaine_1, age, "alimentation_par_jour", "12").await.unwrap();
+    }
+
+    let conn = test_db.db.get_connection().unwrap();
+    let prevision = ProgrammeAlimentationRepository::forecast_requirements(&conn, fixtures.bande_id).unwrap();
+
+    // 10 000 kg livrés, 84 sachets consommés
+    assert_eq!(prevision.stock_kg, 5800.0);
+    let saisi = &prevision.batiments[0];
+    assert_eq!((saisi.age_depart, saisi.age_enlevement, saisi.effectif), (8, 56, 5000));
+    assert!((saisi.facteur_consommation.unwrap() - 1.2).abs() < 1e-9);
+    assert!((saisi.besoin_kg - 49.0 * 600.0).abs() < 1e-6);
+    // Bâtiment 2 sans saisie: le programme seul, du jour 1 au jour 56
+    let vierge = &prevision.batiments[1];
+    assert_eq!(vierge.age_depart, 1);
+    assert!(vierge.facteur_consommation.is_none());
+    assert!((vierge.besoin_kg - 56.0 * 500.0).abs() < 1e-6);
+
+    assert!((prevision.besoin_kg - 57400.0).abs() < 1e-6);
+    assert!((prevision.besoin_sachets - 1148.0).abs() < 1e-6);
+    assert!((prevision.manque_kg - 51600.0).abs() < 1e-6);
+    assert_eq!(prevision.semaines.len(), 8);
+    assert_eq!(prevision.semaines[0].date_debut, "2024-03-01");
+    assert!((prevision.semaines[0].besoin_kg - 3500.0).abs() < 1e-6);
+    assert!((prevision.semaines[1].besoin_kg - 7700.0).abs() < 1e-6);
+    // Cumul: 3 500 kg à J7, 4 600 kg à J8, 5 700 kg à J9, 6 800 kg à J10
+    assert_eq!(prevision.date_rupture.as_deref(), Some("2024-03-10"));
+
+    assert!(ProgrammeAlimentationRepository::forecast_requirements(&conn, 9999).is_err());
+}
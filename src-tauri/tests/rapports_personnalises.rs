@@ -0,0 +1,113 @@
+//! Rapports personnalisés: définitions enregistrées et exécution
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use serde_json::{json, Map, Value};
+use tauri_app_lib::models::{ColonneRapport, CreateRapportPersonnalise, DefinitionRapport, FiltreRapport};
+use tauri_app_lib::services::RapportService;
+
+fn colonne(champ: &str, agregat: Option<&str>) -> ColonneRapport {
+    ColonneRapport { champ: champ.to_string(), agregat: agregat.map(str::to_string) }
+}
+
+fn rapport(nom: &str, definition: DefinitionRapport) -> CreateRapportPersonnalise {
+    CreateRapportPersonnalise { nom: nom.to_string(), definition }
+}
+
+#[tokio::test]
+async fn saved_report_runs_with_bound_parameters() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = RapportService::new(test_db.storage());
+
+    let conn = test_db.db.get_connection().unwrap();
+    for (batiment_id, age, deces) in [(fixtures.batiment_ids[0], 1, 10), (fixtures.batiment_ids[0], 2, 5), (fixtures.batiment_ids[1], 2, 7)] {
+        conn.execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour) VALUES (?1, ?2, ?3)",
+            [semaine_id(&test_db, batiment_id, 1), age, deces],
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    let definition = DefinitionRapport {
+        entite: "suivi_quotidien".to_string(),
+        colonnes: vec![colonne("numero_batiment", None), colonne("deces", Some("somme"))],
+        filtres: vec![
+            FiltreRapport { champ: "age".to_string(), operateur: ">=".to_string(), valeur: None, parametre: Some("age_min".to_string()) },
+            FiltreRapport { champ: "bande_id".to_string(), operateur: "=".to_string(), valeur: Some(json!(fixtures.bande_id)), parametre: None },
+        ],
+        regroupement: vec!["numero_batiment".to_string()],
+    };
+    let enregistre = service.create_report_definition(rapport("Mortalité par bâtiment", definition.clone()), None).await.unwrap();
+    assert!(service.create_report_definition(rapport("mortalité par bâtiment", definition.clone()), None).await.is_err());
+    assert_eq!(service.get_report_definitions().await.unwrap().len(), 1);
+
+    let mut params = Map::new();
+    params.insert("age_min".to_string(), json!(2));
+    let resultat = service.run_report(enregistre.id, params).await.unwrap();
+    assert_eq!(resultat.colonnes, vec!["numero_batiment", "somme_deces"]);
+    assert_eq!(resultat.lignes, vec![vec![json!("1"), json!(5)], vec![json!("2"), json!(7)]]);
+    assert!(!resultat.tronque);
+
+    // Paramètre absent
+    assert!(service.run_report(enregistre.id, Map::new()).await.is_err());
+
+    // Un texte de filtre n'est jamais interprété comme du SQL
+    let mut params = Map::new();
+    params.insert("age_min".to_string(), Value::from("0' OR '1'='1"));
+    assert!(service.run_report(enregistre.id, params).await.unwrap().lignes.is_empty());
+
+    // Seuls les entités, champs, agrégats et opérateurs du catalogue sont acceptés
+    let mut invalide = definition.clone();
+    invalide.colonnes.push(colonne("deces_par_jour); DROP TABLE bandes; --", None));
+    assert!(service.create_report_definition(rapport("Invalide", invalide), None).await.is_err());
+    let mut invalide = definition.clone();
+    invalide.entite = "users".to_string();
+    assert!(service.create_report_definition(rapport("Utilisateurs", invalide), None).await.is_err());
+    let mut invalide = definition.clone();
+    invalide.colonnes.push(colonne("age", None));
+    assert!(service.create_report_definition(rapport("Non agrégé", invalide), None).await.is_err());
+    assert_eq!(test_db.count("bandes", "1 = 1"), 1);
+
+    service.delete_report_definition(enregistre.id).await.unwrap();
+    assert!(service.run_report(enregistre.id, Map::new()).await.is_err());
+}
+
+#[tokio::test]
+async fn contains_filter_matches_wildcards_literally() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = RapportService::new(test_db.storage());
+
+    let conn = test_db.db.get_connection().unwrap();
+    for fournisseur in ["Aliments 100% bio", "Aliments 100 bio", "Moulin_Nord", "MoulinXNord", "Dépôt A\\B", "Dépôt AB"] {
+        conn.execute(
+            "INSERT INTO alimentation_history (bande_id, quantite, fournisseur) VALUES (?1, 100.0, ?2)",
+            rusqlite::params![fixtures.bande_id, fournisseur],
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    let definition = DefinitionRapport {
+        entite: "alimentation".to_string(),
+        colonnes: vec![colonne("fournisseur", None)],
+        filtres: vec![FiltreRapport {
+            champ: "fournisseur".to_string(),
+            operateur: "contient".to_string(),
+            valeur: None,
+            parametre: Some("texte".to_string()),
+        }],
+        regroupement: vec![],
+    };
+    let enregistre = service.create_report_definition(rapport("Fournisseurs", definition), None).await.unwrap();
+
+    for (texte, attendu) in [("100%", "Aliments 100% bio"), ("n_N", "Moulin_Nord"), ("A\\B", "Dépôt A\\B")] {
+        let mut params = Map::new();
+        params.insert("texte".to_string(), json!(texte));
+        let resultat = service.run_report(enregistre.id, params).await.unwrap();
+        assert_eq!(resultat.lignes, vec![vec![json!(attendu)]], "contient {}", texte);
+    }
+}
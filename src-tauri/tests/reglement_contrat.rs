@@ -0,0 +1,139 @@
+//! Contrat d'élevage d'une bande et règlement attendu
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{ContratBande, CreateUser, STATUT_BANDE_CLOTUREE};
+use tauri_app_lib::services::{ArchiveService, AuthService, BilanService, SemaineService};
+
+fn proche(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[tokio::test]
+async fn settlement_applies_guaranteed_price_and_bonuses() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_1 = fixtures.batiment_ids[0];
+    let service = BilanService::new(test_db.storage());
+
+    assert!(service.get_bande_contract(fixtures.bande_id).await.unwrap().is_none());
+    assert!(service.compute_settlement(fixtures.bande_id).await.is_err());
+    let invalide = ContratBande { prix_garanti_kg: -1.0, ..Default::default() };
+    assert!(service.set_bande_contract(fixtures.bande_id, invalide).await.is_err());
+    let sans_cible = ContratBande { prix_garanti_kg: 10.0, prime_ic_par_centieme: Some(0.01), ..Default::default() };
+    assert!(service.set_bande_contract(fixtures.bande_id, sans_cible).await.is_err());
+    assert!(service.set_bande_contract(9999, ContratBande::default()).await.is_err());
+
+    let contrat = ContratBande {
+        prix_garanti_kg: 10.0,
+        ic_cible: Some(1.6),
+        prime_ic_par_centieme: Some(0.01),
+        mortalite_cible_pourcentage: Some(3.0),
+        prime_mortalite_par_point: Some(100.0),
+        deduire_intrants: false,
+    };
+    service.set_bande_contract(fixtures.bande_id, contrat).await.unwrap();
+
+    // Bâtiment 1: 50 décès, 297 sachets (14 850 kg) et 2 kg à la pesée de la semaine 5
+    let conn = test_db.db.get_connection().unwrap();
+    conn.execute(
+        "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, alimentation_par_jour) VALUES (?1, 3, 50, 297)",
+        [semaine_id(&test_db, batiment_1, 1)],
+    )
+    .unwrap();
+    drop(conn);
+    SemaineService::new(test_db.storage())
+        .update_semaine_poids(semaine_id(&test_db, batiment_1, 5), Some(2.0), None)
+        .await
+        .unwrap();
+
+    // 4 950 sujets à 2 kg: IC de 1,5 et mortalité de 0,5 % sur les 10 000 poussins de la bande
+    let reglement = service.compute_settlement(fixtures.bande_id).await.unwrap();
+    assert!(proche(reglement.poids_vif_kg, 9900.0), "{}", reglement.poids_vif_kg);
+    assert_eq!(reglement.batiments_non_peses, 1);
+    assert!(proche(reglement.indice_consommation.unwrap(), 1.5));
+    assert!(proche(reglement.montant_base, 99_000.0));
+    assert!(proche(reglement.prime_ic, 990.0), "{}", reglement.prime_ic);
+    assert!(proche(reglement.prime_mortalite, 250.0), "{}", reglement.prime_mortalite);
+    assert_eq!(reglement.intrants_deduits, 0.0);
+    assert!(proche(reglement.montant_net, 100_240.0));
+    assert!(!reglement.definitif);
+
+    // Cible d'IC manquée: la prime devient une pénalité; les intrants sont déduits
+    let contrat = ContratBande { ic_cible: Some(1.4), deduire_intrants: true, ..reglement.contrat };
+    service.set_bande_contract(fixtures.bande_id, contrat).await.unwrap();
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute("UPDATE bandes SET statut = ?1 WHERE id = ?2", rusqlite::params![STATUT_BANDE_CLOTUREE, fixtures.bande_id])
+        .unwrap();
+    let reglement = service.compute_settlement(fixtures.bande_id).await.unwrap();
+    assert!(proche(reglement.prime_ic, -990.0), "{}", reglement.prime_ic);
+    let cout_total = service.get_bande_financial_summary(fixtures.bande_id).await.unwrap().cout_total;
+    assert_eq!(reglement.intrants_deduits, cout_total);
+    assert!(proche(reglement.montant_net, 99_000.0 - 990.0 + 250.0 - cout_total));
+    assert!(reglement.definitif);
+}
+
+#[tokio::test]
+async fn settlement_is_unchanged_after_archiving_and_restoring_the_bande() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = BilanService::new(test_db.storage());
+    let contrat = ContratBande {
+        prix_garanti_kg: 10.0,
+        ic_cible: Some(1.6),
+        prime_ic_par_centieme: Some(0.01),
+        ..Default::default()
+    };
+    service.set_bande_contract(fixtures.bande_id, contrat).await.unwrap();
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "INSERT INTO suivi_quotidien (semaine_id, age, deces_par_jour, alimentation_par_jour) VALUES (?1, 3, 50, 297)",
+            [semaine_id(&test_db, fixtures.batiment_ids[0], 1)],
+        )
+        .unwrap();
+    SemaineService::new(test_db.storage())
+        .update_semaine_poids(semaine_id(&test_db, fixtures.batiment_ids[0], 5), Some(2.0), None)
+        .await
+        .unwrap();
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "UPDATE bandes SET statut = ?1, date_cloture = '2020-05-01' WHERE id = ?2",
+            rusqlite::params![STATUT_BANDE_CLOTUREE, fixtures.bande_id],
+        )
+        .unwrap();
+    let avant = service.compute_settlement(fixtures.bande_id).await.unwrap();
+
+    let token = AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap()
+        .token;
+    let archives = ArchiveService::new(test_db.storage());
+    archives.archive_old_bandes(None, &token).await.unwrap();
+    assert_eq!(test_db.count("archive.contrats_bande", &format!("bande_id = {}", fixtures.bande_id)), 1);
+    assert!(service.compute_settlement(fixtures.bande_id).await.is_err());
+
+    archives.restore_archived_bande(fixtures.bande_id, &token).await.unwrap();
+    let apres = service.compute_settlement(fixtures.bande_id).await.unwrap();
+    assert_eq!(apres.contrat.prix_garanti_kg, 10.0);
+    assert_eq!(apres.contrat.ic_cible, Some(1.6));
+    assert!(proche(apres.poids_vif_kg, avant.poids_vif_kg));
+    assert!(proche(apres.prime_ic, avant.prime_ic));
+    assert!(proche(apres.montant_net, avant.montant_net));
+    assert!(apres.definitif);
+}
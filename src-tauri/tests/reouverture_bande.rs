@@ -0,0 +1,73 @@
+//! Clôture et réouverture des bandes, avec journal d'audit
+
+mod common;
+
+use common::{invitation, seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateUser, AUDIT_CLOTURE_BANDE, AUDIT_REOUVERTURE_BANDE, ROLE_ADMIN, ROLE_TECHNICIEN};
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::{AuthService, BandeService};
+
+async fn register(test_db: &TestDb, username: &str, registration_code: &str) -> (String, String) {
+    let response = AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: username.to_string(),
+            email: format!("{}@example.com", username),
+            password: "motdepasse123".to_string(),
+            registration_code: registration_code.to_string(),
+        })
+        .await
+        .unwrap();
+    (response.token, response.user.role)
+}
+
+#[tokio::test]
+async fn sessions_survive_a_new_service_instance() {
+    let test_db = TestDb::new();
+    let (token, role) = register(&test_db, "responsable", "").await;
+    assert_eq!(role, ROLE_ADMIN);
+
+    let user = AuthService::new(test_db.storage()).verify_token(&token).await.unwrap();
+    assert_eq!(user.unwrap().username, "responsable");
+
+    AuthService::new(test_db.storage()).logout(&token).await.unwrap();
+    assert!(AuthService::new(test_db.storage()).verify_token(&token).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn closed_bande_is_read_only_until_an_admin_reopens_it() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let (admin_token, _) = register(&test_db, "responsable", "").await;
+    let code = invitation(&test_db, &admin_token).await;
+    let (technicien_token, role) = register(&test_db, "technicien", &code).await;
+    assert_eq!(role, ROLE_TECHNICIEN);
+
+    let service = BandeService::new(test_db.storage());
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+
+    service.close_bande(fixtures.bande_id, &technicien_token).await.unwrap();
+    let bande = service.get_bande_by_id(fixtures.bande_id).await.unwrap().unwrap();
+    assert_eq!(bande.statut, "cloturee");
+    assert!(bande.date_cloture.is_some());
+    assert!(suivi_repo.upsert_field(semaine, 1, "deces_par_jour", "3").await.is_err());
+
+    // Réservée aux administrateurs, avec un motif
+    assert!(service.reopen_bande(fixtures.bande_id, "Erreur de saisie", &technicien_token).await.is_err());
+    assert!(service.reopen_bande(fixtures.bande_id, "  ", &admin_token).await.is_err());
+    service.reopen_bande(fixtures.bande_id, "Erreur de saisie", &admin_token).await.unwrap();
+
+    let bande = service.get_bande_by_id(fixtures.bande_id).await.unwrap().unwrap();
+    assert_eq!(bande.statut, "active");
+    assert!(bande.date_cloture.is_none());
+    suivi_repo.upsert_field(semaine, 1, "deces_par_jour", "3").await.unwrap();
+    assert!(service.reopen_bande(fixtures.bande_id, "Encore", &admin_token).await.is_err());
+
+    let journal = service.get_bande_audit_log(fixtures.bande_id).await.unwrap();
+    assert_eq!(journal.len(), 2);
+    assert_eq!(journal[0].action, AUDIT_REOUVERTURE_BANDE);
+    assert_eq!(journal[0].username.as_deref(), Some("responsable"));
+    assert_eq!(journal[0].details.as_deref(), Some("Erreur de saisie"));
+    assert_eq!(journal[1].action, AUDIT_CLOTURE_BANDE);
+    assert_eq!(journal[1].username.as_deref(), Some("technicien"));
+}
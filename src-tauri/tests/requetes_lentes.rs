@@ -0,0 +1,39 @@
+//! Journal des requêtes lentes (valeurs masquées)
+
+mod common;
+
+use common::TestDb;
+use std::time::Duration;
+use tauri_app_lib::database::{DatabaseConfig, DatabaseManager, Storage, NOM_JOURNAL_DIAGNOSTIC};
+
+#[test]
+fn queries_over_the_threshold_are_logged_without_their_values() {
+    let test_db = TestDb::new();
+    let chemin = test_db.dir().join("diagnostic.db");
+    let journal = test_db.dir().join(NOM_JOURNAL_DIAGNOSTIC);
+    let lire_journal = || std::fs::read_to_string(&journal).unwrap_or_default();
+
+    // Seuil nul: toutes les requêtes sont consignées
+    let config = DatabaseConfig { slow_query_threshold: Some(Duration::ZERO), ..DatabaseConfig::default() };
+    let db = DatabaseManager::with_config(&chemin, config).unwrap();
+    db.initialize_schema().unwrap();
+    let conn = db.get_connection().unwrap();
+    let fermes: i64 = conn
+        .query_row("SELECT COUNT(*) FROM fermes WHERE nom = 'Ferme Secrète' OR id = ?1", [42], |row| row.get(0))
+        .unwrap();
+    assert_eq!(fermes, 0);
+
+    let contenu = lire_journal();
+    let ligne = contenu.lines().find(|ligne| ligne.contains("FROM fermes WHERE")).unwrap();
+    assert!(ligne.contains("requête lente ("), "{}", ligne);
+    assert!(ligne.ends_with("SELECT COUNT(*) FROM fermes WHERE nom = ? OR id = ?1"), "{}", ligne);
+    assert!(!contenu.contains("Secrète"));
+    drop(conn);
+
+    // Journal désactivé: plus rien n'est consigné
+    let config = DatabaseConfig { slow_query_threshold: None, ..DatabaseConfig::default() };
+    let db = DatabaseManager::with_config(&chemin, config).unwrap();
+    let taille = lire_journal().len();
+    db.get_connection().unwrap().query_row("SELECT COUNT(*) FROM bandes", [], |row| row.get::<_, i64>(0)).unwrap();
+    assert_eq!(lire_journal().len(), taille);
+}
@@ -0,0 +1,69 @@
+//! Score de santé d'un bâtiment
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{
+    CreateMaladie, FACTEUR_MALADIES, FACTEUR_MORTALITE, FACTEUR_RATIO_EAU_ALIMENT, FACTEUR_TENDANCE_ALIMENTATION,
+    FACTEUR_TENDANCE_MORTALITE,
+};
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::{MaladieService, StatisticsService};
+
+#[tokio::test]
+async fn health_score_combines_mortality_feed_and_disease_signals() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+
+    // Semaine 1: 5 pertes et 10 sachets par jour; semaine 2: 15 pertes et 8 sachets
+    for (numero_semaine, deces, alimentation) in [(1, "5", "10"), (2, "15", "8")] {
+        let semaine = semaine_id(&test_db, batiment_id, numero_semaine);
+        for age in (numero_semaine - 1) * 7 + 1..=numero_semaine * 7 {
+            suivi_repo.upsert_field(semaine, age, "deces_par_jour", deces).await.unwrap();
+            suivi_repo.upsert_field(semaine, age, "alimentation_par_jour", alimentation).await.unwrap();
+        }
+    }
+
+    let coccidiose = MaladieService::new(test_db.storage())
+        .create_maladie(CreateMaladie { nom: "Coccidiose".to_string() })
+        .await
+        .unwrap();
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "INSERT INTO batiment_maladies (batiment_id, maladie_id) VALUES (?1, ?2)",
+            [batiment_id, coccidiose.id],
+        )
+        .unwrap();
+
+    let service = StatisticsService::new(test_db.storage());
+    let sante = service.get_batiment_health(batiment_id).await.unwrap();
+    assert_eq!(sante.bande_id, fixtures.bande_id);
+    assert_eq!(sante.age_reference, Some(14));
+
+    let facteur = |nom: &str| sante.facteurs.iter().find(|f| f.facteur == nom).unwrap();
+    // 105 pertes sur 4965 sujets présents à J8
+    let mortalite = facteur(FACTEUR_MORTALITE);
+    assert!((mortalite.valeur.unwrap() - 105.0 * 100.0 / 4965.0).abs() < 1e-9);
+    assert!((mortalite.points_perdus - 1050.0 / 4965.0 * 10.0).abs() < 1e-9);
+    // Mortalité passée de 0,70% à 2,11%: plafond atteint
+    assert_eq!(facteur(FACTEUR_TENDANCE_MORTALITE).points_perdus, 20.0);
+    let alimentation = facteur(FACTEUR_TENDANCE_ALIMENTATION);
+    assert!((alimentation.valeur.unwrap() + 20.0).abs() < 1e-9);
+    assert!((alimentation.points_perdus - 20.0).abs() < 1e-9);
+    assert!(facteur(FACTEUR_RATIO_EAU_ALIMENT).valeur.is_none());
+    assert_eq!(facteur(FACTEUR_MALADIES).points_perdus, 15.0);
+    assert!((sante.score - (100.0 - mortalite.points_perdus - 20.0 - 20.0 - 15.0)).abs() < 1e-9);
+
+    // Sans saisie ni maladie, le bâtiment garde le score maximal
+    let vierge = service.get_batiment_health(fixtures.batiment_ids[1]).await.unwrap();
+    assert_eq!(vierge.age_reference, None);
+    assert_eq!(vierge.score, 100.0);
+    assert!(vierge.facteurs.iter().all(|f| f.points_perdus == 0.0));
+
+    assert!(service.get_batiment_health(9999).await.is_err());
+}
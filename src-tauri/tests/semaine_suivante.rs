@@ -0,0 +1,62 @@
+//! Passage d'un bâtiment à la semaine suivante
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::repositories::{
+    SemaineRepository, SemaineRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+
+#[tokio::test]
+async fn next_week_requires_a_complete_last_week_and_respects_the_maximum() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    let repository = SemaineRepository::new(test_db.storage());
+    let suivi = SuiviQuotidienRepository::new(test_db.storage());
+
+    // La semaine 8 n'a pas de décès ni d'alimentation saisis
+    let erreur = repository.advance_to_next_week(batiment_id, true).await.unwrap_err();
+    assert!(erreur.to_string().contains("50, 51, 52, 53, 54, 55, 56"), "{}", erreur);
+
+    let semaine_8 = semaine_id(&test_db, batiment_id, 8);
+    for age in 50..=56 {
+        suivi.upsert_field(semaine_8, age, "deces_par_jour", "0").await.unwrap();
+        if age != 56 {
+            suivi.upsert_field(semaine_8, age, "alimentation_par_jour", "4").await.unwrap();
+        }
+    }
+    let erreur = repository.advance_to_next_week(batiment_id, true).await.unwrap_err();
+    assert!(erreur.to_string().contains("jours 56"), "{}", erreur);
+    suivi.upsert_field(semaine_8, 56, "alimentation_par_jour", "4").await.unwrap();
+
+    // Nombre maximal de semaines configurable entre 5 et 9
+    let conn = test_db.db.get_connection().unwrap();
+    assert_eq!(SemaineRepository::get_semaines_max(&conn).unwrap(), 9);
+    assert!(SemaineRepository::set_semaines_max(&conn, 4).is_err());
+    assert!(SemaineRepository::set_semaines_max(&conn, 10).is_err());
+    assert_eq!(SemaineRepository::set_semaines_max(&conn, 8).unwrap(), 8);
+    assert!(repository.advance_to_next_week(batiment_id, true).await.is_err());
+    SemaineRepository::set_semaines_max(&conn, 9).unwrap();
+    drop(conn);
+
+    let avancement = repository.advance_to_next_week(batiment_id, true).await.unwrap();
+    assert_eq!(avancement.semaine.numero_semaine, 9);
+    assert_eq!(avancement.semaine.batiment_id, batiment_id);
+    assert_eq!(avancement.jours_crees, (57..=63).collect::<Vec<_>>());
+    assert_eq!(test_db.count("suivi_quotidien", &format!("semaine_id = {}", avancement.semaine.id.unwrap())), 7);
+
+    // Sans création des jours, seuls ceux du plan de soins sont créés
+    let autre = fixtures.batiment_ids[1];
+    let semaine_8 = semaine_id(&test_db, autre, 8);
+    for age in 50..=56 {
+        suivi.upsert_field(semaine_8, age, "deces_par_jour", "1").await.unwrap();
+        suivi.upsert_field(semaine_8, age, "alimentation_par_jour", "4").await.unwrap();
+    }
+    let avancement = repository.advance_to_next_week(autre, false).await.unwrap();
+    assert_eq!(avancement.semaine.numero_semaine, 9);
+    assert!(avancement.jours_crees.is_empty());
+
+    assert!(repository.advance_to_next_week(batiment_id, false).await.is_err());
+    assert!(repository.advance_to_next_week(9999, false).await.is_err());
+}
@@ -0,0 +1,53 @@
+//! Unicité du numéro de semaine dans un bâtiment
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::error::AppError;
+use tauri_app_lib::models::{CreateSemaine, UpdateSemaine};
+use tauri_app_lib::repositories::{SemaineRepository, SemaineRepositoryTrait};
+use tauri_app_lib::services::SemaineService;
+
+#[tokio::test]
+async fn duplicate_semaines_are_refused_and_get_or_create_is_idempotent() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    let repository = SemaineRepository::new(test_db.storage());
+    let semaine_3 = semaine_id(&test_db, batiment_id, 3);
+
+    let erreur = repository
+        .create(CreateSemaine { batiment_id, numero_semaine: 3, poids: None })
+        .await
+        .unwrap_err();
+    match erreur {
+        AppError::Duplicate { id, .. } => assert_eq!(id, semaine_3),
+        autre => panic!("doublon attendu: {}", autre),
+    }
+
+    // Renuméroter une semaine sur un numéro déjà pris est aussi refusé
+    let erreur = repository
+        .update(UpdateSemaine {
+            id: semaine_id(&test_db, batiment_id, 4),
+            batiment_id,
+            numero_semaine: 3,
+            poids: None,
+            version: None,
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(erreur, AppError::Duplicate { .. }), "{}", erreur);
+
+    assert_eq!(repository.get_or_create_semaine(batiment_id, 3).await.unwrap().id, Some(semaine_3));
+    let semaine_9 = repository.get_or_create_semaine(batiment_id, 9).await.unwrap();
+    assert_eq!(semaine_9.numero_semaine, 9);
+    assert_eq!(repository.get_or_create_semaine(batiment_id, 9).await.unwrap().id, semaine_9.id);
+    assert_eq!(test_db.count("semaines", &format!("batiment_id = {}", batiment_id)), 9);
+
+    // Les semaines manquantes sont recréées une seule fois par le service
+    repository.delete(semaine_3).await.unwrap();
+    let service = SemaineService::new(test_db.storage());
+    assert_eq!(service.initialize_batiment_semaines(batiment_id).await.unwrap().len(), 9);
+    assert_eq!(service.get_full_semaines_by_batiment(batiment_id).await.unwrap().len(), 8);
+    assert_eq!(test_db.count("semaines", &format!("batiment_id = {}", batiment_id)), 9);
+}
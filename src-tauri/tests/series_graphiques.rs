@@ -0,0 +1,98 @@
+//! Séries prêtes à tracer par bâtiment ou par bande
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{
+    PointSerie, METRIQUE_ALIMENTATION, METRIQUE_MORTALITE, METRIQUE_POIDS, METRIQUE_TEMPERATURE,
+};
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::StatisticsService;
+
+fn point(x: &str, y: f64) -> PointSerie {
+    PointSerie { x: x.to_string(), y }
+}
+
+#[tokio::test]
+async fn chart_series_cover_batiment_and_bande_with_smoothing() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+
+    // J1 à J3 du bâtiment 1, J1 seulement pour le bâtiment 2
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    for (age, deces, alimentation) in [(1, "2", "10"), (2, "4", "11"), (3, "6", "12")] {
+        suivi_repo.upsert_field(semaine_1, age, "deces_par_jour", deces).await.unwrap();
+        suivi_repo.upsert_field(semaine_1, age, "alimentation_par_jour", alimentation).await.unwrap();
+    }
+    let autre_semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[1], 1);
+    suivi_repo.upsert_field(autre_semaine_1, 1, "deces_par_jour", "1").await.unwrap();
+
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute("UPDATE semaines SET poids = 0.2 WHERE id = ?1", [semaine_1]).unwrap();
+        conn.execute("UPDATE semaines SET poids = 0.4 WHERE id = ?1", [autre_semaine_1]).unwrap();
+        // Deux heures le premier jour, une le lendemain, une avant l'entrée de la bande
+        for (heure, somme, nombre) in [
+            ("2024-03-01 08:00", 60.0, 2),
+            ("2024-03-01 09:00", 34.0, 1),
+            ("2024-03-02 08:00", 30.0, 1),
+            ("2024-02-20 08:00", 18.0, 1),
+        ] {
+            conn.execute(
+                "INSERT INTO mesures_capteurs_horaires (ferme_id, numero_batiment, heure, nombre_mesures,
+                                                        temperature_somme, temperature_nombre)
+                 VALUES (?1, '1', ?2, ?3, ?4, ?3)",
+                rusqlite::params![fixtures.ferme_id, heure, nombre, somme],
+            )
+            .unwrap();
+        }
+    }
+
+    let service = StatisticsService::new(test_db.storage());
+    let batiment = Some(fixtures.batiment_ids[0]);
+
+    let mortalite = service.get_chart_series(METRIQUE_MORTALITE, None, batiment, None).await.unwrap();
+    assert_eq!(mortalite.unite, "sujets");
+    assert_eq!(
+        mortalite.points,
+        vec![point("2024-03-01", 2.0), point("2024-03-02", 4.0), point("2024-03-03", 6.0)]
+    );
+
+    let lissee = service.get_chart_series(METRIQUE_MORTALITE, None, batiment, Some(2)).await.unwrap();
+    assert_eq!(lissee.lissage, Some(2));
+    assert_eq!(
+        lissee.points,
+        vec![point("2024-03-01", 2.0), point("2024-03-02", 3.0), point("2024-03-03", 5.0)]
+    );
+
+    // La bande additionne ses bâtiments
+    let bande = service.get_chart_series(METRIQUE_MORTALITE, Some(fixtures.bande_id), None, None).await.unwrap();
+    assert_eq!(bande.points[0], point("2024-03-01", 3.0));
+    assert_eq!(bande.points.len(), 3);
+
+    let aliment = service.get_chart_series(METRIQUE_ALIMENTATION, None, batiment, None).await.unwrap();
+    assert_eq!(aliment.unite, "kg");
+    assert_eq!(aliment.points.iter().map(|p| p.y).collect::<Vec<_>>(), vec![500.0, 550.0, 600.0]);
+
+    let poids = service.get_chart_series(METRIQUE_POIDS, Some(fixtures.bande_id), None, None).await.unwrap();
+    assert_eq!(poids.points.len(), 1);
+    assert_eq!(poids.points[0].x, "2024-03-07");
+    assert!((poids.points[0].y - 0.3).abs() < 1e-9);
+
+    let temperature = service.get_chart_series(METRIQUE_TEMPERATURE, None, batiment, None).await.unwrap();
+    assert_eq!(temperature.unite, "°C");
+    assert_eq!(temperature.points, vec![point("2024-03-01", 94.0 / 3.0), point("2024-03-02", 30.0)]);
+    let sans_capteur = service
+        .get_chart_series(METRIQUE_TEMPERATURE, None, Some(fixtures.batiment_ids[1]), None)
+        .await
+        .unwrap();
+    assert!(sans_capteur.points.is_empty());
+
+    assert!(service.get_chart_series(METRIQUE_POIDS, None, None, None).await.is_err());
+    assert!(service.get_chart_series(METRIQUE_POIDS, Some(fixtures.bande_id), batiment, None).await.is_err());
+    assert!(service.get_chart_series(METRIQUE_POIDS, None, Some(9999), None).await.is_err());
+    assert!(service.get_chart_series(METRIQUE_POIDS, None, batiment, Some(0)).await.is_err());
+    assert!(service.get_chart_series(METRIQUE_POIDS, None, batiment, Some(31)).await.is_err());
+    assert!(service.get_chart_series("humidite", None, batiment, None).await.is_err());
+}
@@ -0,0 +1,594 @@
+//! Scénarios de bout en bout sur les services, avec une base fichier réelle
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{invitation, seed, semaine_id, TestDb};
+use tauri_app_lib::commands::auth_commands::UpdatePasswordData;
+use tauri_app_lib::models::{
+    BandeLoadOptions, CreateBande, CreateBatiment, CreateInvitation, CreateUser, LoginUser, ROLE_ADMIN,
+};
+use tauri_app_lib::repositories::{BandeRepository, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::totp::{self, TOTP_PERIODE};
+use tauri_app_lib::services::{AuthService, BandeService, ComparaisonService, FermeService, SemaineService};
+
+#[tokio::test]
+async fn ferme_statistics_reflect_daily_follow_up() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_2 = semaine_id(&test_db, fixtures.batiment_ids[1], 2);
+    suivi_repo.upsert_field(semaine_1, 1, "deces_par_jour", "10").await.unwrap();
+    suivi_repo.upsert_field(semaine_1, 2, "deces_par_jour", "4").await.unwrap();
+    suivi_repo.upsert_field(semaine_2, 9, "deces_par_jour", "6").await.unwrap();
+
+    let stats = FermeService::new(test_db.storage())
+        .get_ferme_detailed_statistics(fixtures.ferme_id)
+        .await
+        .unwrap();
+
+    assert_eq!(stats.total_bandes, 1);
+    assert_eq!(stats.bandes_with_deaths, 1);
+    assert_eq!(stats.total_deaths, 20);
+}
+
+#[tokio::test]
+async fn full_semaines_merge_saved_days_with_virtual_ones() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    let semaine_3 = semaine_id(&test_db, batiment_id, 3);
+
+    SuiviQuotidienRepository::new(test_db.storage())
+        .upsert_field(semaine_3, 16, "remarques", "Vaccination")
+        .await
+        .unwrap();
+
+    let semaines = SemaineService::new(test_db.storage())
+        .get_full_semaines_by_batiment(batiment_id)
+        .await
+        .unwrap();
+
+    assert_eq!(semaines.len(), 8);
+    let semaine = semaines.iter().find(|s| s.numero_semaine == 3).unwrap();
+    assert_eq!(semaine.suivi_quotidien.len(), 7);
+    let jour = semaine.suivi_quotidien.iter().find(|s| s.age == 16).unwrap();
+    assert!(jour.id.is_some());
+    assert_eq!(jour.remarques.as_deref(), Some("Vaccination"));
+}
+
+#[tokio::test]
+async fn full_semaines_include_weekly_totals() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, batiment_id, 1);
+    let semaine_2 = semaine_id(&test_db, batiment_id, 2);
+    for (semaine, age, field, value) in [
+        (semaine_1, 1, "deces_par_jour", "30"),
+        (semaine_1, 2, "deces_par_jour", "20"),
+        (semaine_2, 8, "deces_par_jour", "10"),
+        (semaine_2, 8, "alimentation_par_jour", "95"),
+        (semaine_2, 9, "alimentation_par_jour", "95"),
+    ] {
+        suivi_repo.upsert_field(semaine, age, field, value).await.unwrap();
+    }
+    let effectif_initial: i64 = test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .query_row("SELECT quantite FROM batiments WHERE id = ?1", [batiment_id], |row| row.get(0))
+        .unwrap();
+
+    let semaines = SemaineService::new(test_db.storage())
+        .get_full_semaines_by_batiment(batiment_id)
+        .await
+        .unwrap();
+
+    let premiere = &semaines[0].totaux;
+    assert_eq!(premiere.total_deces, 50);
+    assert_eq!(premiere.effectif_debut, effectif_initial);
+    assert!(premiere.alimentation_par_sujet_par_jour.is_none());
+
+    let deuxieme = &semaines[1].totaux;
+    assert_eq!(deuxieme.total_deces, 10);
+    assert_eq!(deuxieme.total_alimentation, 190.0);
+    assert_eq!(deuxieme.effectif_debut, effectif_initial - 50);
+    let par_sujet = 95_000.0 / (effectif_initial - 50) as f64;
+    assert!((deuxieme.alimentation_par_sujet_par_jour.unwrap() - par_sujet).abs() < 1e-9);
+    let cumulee = 60.0 * 100.0 / effectif_initial as f64;
+    assert!((deuxieme.mortalite_cumulee_pourcentage.unwrap() - cumulee).abs() < 1e-9);
+
+    // Les semaines sans saisie n'ajoutent rien
+    assert_eq!(semaines[7].totaux.total_deces, 0);
+    assert_eq!(semaines[7].totaux.mortalite_pourcentage, Some(0.0));
+}
+
+#[tokio::test]
+async fn culled_birds_are_counted_apart_from_mortality() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, batiment_id, 1);
+    suivi_repo.upsert_field(semaine_1, 1, "deces_par_jour", "50").await.unwrap();
+    let saisie = suivi_repo.upsert_field(semaine_1, 2, "elimines_par_jour", "100").await.unwrap();
+    assert_eq!(saisie.elimines_par_jour, Some(100));
+    assert_eq!(saisie.deces_par_jour, None);
+
+    let semaines = SemaineService::new(test_db.storage())
+        .get_full_semaines_by_batiment(batiment_id)
+        .await
+        .unwrap();
+    let premiere = &semaines[0].totaux;
+    assert_eq!(premiere.total_deces, 50);
+    assert_eq!(premiere.total_elimines, 100);
+    assert_eq!(premiere.mortalite_pourcentage, Some(1.0));
+    assert_eq!(premiere.elimines_pourcentage, Some(2.0));
+    assert_eq!(premiere.viabilite_pourcentage, Some(97.0));
+    assert_eq!(semaines[1].totaux.effectif_debut, 5000 - 150);
+
+    // La mortalité de la ferme reste celle des décès, la viabilité déduit aussi les éliminations
+    let kpi = ComparaisonService::new(test_db.storage())
+        .compare_fermes(None, None)
+        .await
+        .unwrap()
+        .remove(0)
+        .indicateurs;
+    assert_eq!(kpi.total_deces, 50);
+    assert_eq!(kpi.total_elimines, 100);
+    assert!((kpi.mortalite_pourcentage.unwrap() - 0.5).abs() < 1e-9);
+    assert!((kpi.viabilite_pourcentage.unwrap() - 98.5).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn bande_load_options_select_the_loaded_data() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    SuiviQuotidienRepository::new(test_db.storage())
+        .upsert_field(semaine, 2, "deces_par_jour", "7")
+        .await
+        .unwrap();
+    let conn = test_db.db.get_connection().unwrap();
+
+    // Par défaut: bâtiments et contour, sans indicateurs
+    let bande = BandeRepository::get_by_id(&conn, fixtures.bande_id, &BandeLoadOptions::default()).unwrap().unwrap();
+    assert_eq!(bande.batiments.len(), 2);
+    assert!(bande.stats.is_none());
+
+    let legere = BandeLoadOptions { include_batiments: false, include_contour: false, include_stats: false };
+    let bandes = BandeRepository::get_by_ferme(&conn, fixtures.ferme_id, &legere).unwrap();
+    assert!(bandes[0].batiments.is_empty());
+    assert!(serde_json::to_value(&bandes[0]).unwrap().get("stats").is_none());
+
+    let complete = BandeRepository::get_by_id(&conn, fixtures.bande_id, &BandeLoadOptions::complet()).unwrap().unwrap();
+    let stats = complete.stats.unwrap();
+    assert_eq!(stats.total_deces, 7);
+    assert_eq!(stats.dernier_age, Some(2));
+    assert_eq!(
+        stats.effectif_initial,
+        complete.batiments.iter().map(|b| b.quantite as i64).sum::<i64>()
+    );
+}
+
+#[tokio::test]
+async fn bande_creation_requires_an_existing_poussin() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = BandeService::new(test_db.storage());
+    let bandes_avant = test_db.count("bandes", "1 = 1");
+
+    for poussin_id in [0, fixtures.poussin_id + 100] {
+        let erreur = service
+            .create_bande_with_batiments_and_first_week(
+                CreateBande {
+                    date_entree: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+                    ferme_id: fixtures.ferme_id,
+                    notes: None,
+                    champs_personnalises: Default::default(),
+                },
+                vec![CreateBatiment {
+                    bande_id: 0,
+                    numero_batiment: "3".to_string(),
+                    poussin_id,
+                    personnel_id: fixtures.personnel_id,
+                    quantite: 4000,
+                    autoriser_cohabitation: false,
+                    champs_personnalises: Default::default(),
+                }],
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(erreur.to_string().contains("poussin"), "{}", erreur);
+    }
+    // La bande n'est pas créée sans ses bâtiments
+    assert_eq!(test_db.count("bandes", "1 = 1"), bandes_avant);
+}
+
+#[tokio::test]
+async fn bande_creation_dry_run_reports_every_problem_without_writing() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = BandeService::new(test_db.storage());
+    let bande = CreateBande { date_entree: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() };
+    let batiment = |numero: &str, personnel_id: i64, quantite: i32| CreateBatiment {
+        bande_id: 0,
+        numero_batiment: numero.to_string(),
+        poussin_id: fixtures.poussin_id,
+        personnel_id,
+        quantite,
+        autoriser_cohabitation: false,
+        champs_personnalises: Default::default(),
+    };
+
+    // Étape de la bande seule
+    let etape = service.validate_bande_creation(bande.clone(), None).await.unwrap();
+    assert!(etape.valide && etape.problemes.is_empty());
+    assert!(etape.numero_affiche.is_some());
+
+    let validation = service
+        .validate_bande_creation(
+            bande.clone(),
+            Some(vec![
+                batiment("1", fixtures.personnel_id, 4000),
+                batiment("3", fixtures.personnel_id + 100, 0),
+                batiment("3", fixtures.personnel_id, 4000),
+                batiment("9", fixtures.personnel_id, 4000),
+            ]),
+        )
+        .await
+        .unwrap();
+    assert!(!validation.valide);
+    let problemes: Vec<(&str, &str, Option<usize>)> = validation
+        .problemes
+        .iter()
+        .map(|probleme| (probleme.niveau.as_str(), probleme.champ.as_str(), probleme.batiment))
+        .collect();
+    assert_eq!(
+        problemes,
+        vec![
+            ("avertissement", "numero_batiment", Some(0)),
+            ("erreur", "quantite", Some(1)),
+            ("erreur", "personnel_id", Some(1)),
+            ("erreur", "numero_batiment", Some(2)),
+            ("erreur", "numero_batiment", Some(3)),
+        ]
+    );
+
+    let ferme_absente = CreateBande { ferme_id: fixtures.ferme_id + 100, ..bande };
+    let validation = service.validate_bande_creation(ferme_absente, Some(Vec::new())).await.unwrap();
+    assert_eq!(validation.problemes.len(), 2);
+    assert!(validation.numero_affiche.is_none());
+    assert_eq!(test_db.count("bandes", "1 = 1"), 1);
+}
+
+#[tokio::test]
+async fn building_of_a_bande_in_progress_requires_explicit_co_location() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = BandeService::new(test_db.storage());
+    // Seule la semaine 1 est créée: la bande reste en cours jusqu'au septième jour
+    let il_y_a_3_jours = chrono::Local::now().date_naive() - chrono::Days::new(3);
+    let creer = |autoriser_cohabitation: bool| {
+        service.create_bande_with_batiments_and_first_week(
+            CreateBande { date_entree: il_y_a_3_jours, ferme_id: fixtures.ferme_id, notes: None, champs_personnalises: Default::default() },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "3".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 4000,
+                autoriser_cohabitation,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+    };
+
+    // La bande de 2024 est terminée: tous les bâtiments sont libres
+    let conn = test_db.db.get_connection().unwrap();
+    assert_eq!(BandeRepository::get_available_batiments(&conn, fixtures.ferme_id, false).unwrap(), ["1", "2", "3", "4"]);
+
+    creer(false).await.unwrap();
+    assert_eq!(BandeRepository::get_available_batiments(&conn, fixtures.ferme_id, false).unwrap(), ["1", "2", "4"]);
+    assert_eq!(BandeRepository::get_available_batiments(&conn, fixtures.ferme_id, true).unwrap().len(), 4);
+
+    let bandes_avant = test_db.count("bandes", "1 = 1");
+    let erreur = creer(false).await.unwrap_err();
+    assert!(erreur.to_string().contains("cohabitation"), "{}", erreur);
+    assert_eq!(test_db.count("bandes", "1 = 1"), bandes_avant);
+
+    creer(true).await.unwrap();
+    assert_eq!(test_db.count("batiments", "numero_batiment = '3'"), 2);
+}
+
+#[tokio::test]
+async fn stored_weights_in_grams_are_normalized_and_outliers_flagged() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let service = SemaineService::new(test_db.storage());
+    service.initialize_batiment_semaines(fixtures.batiment_ids[0]).await.unwrap();
+    // Poids enregistrés avant la normalisation, dans des unités mélangées
+    for (numero, poids) in [(1, 0.18), (2, 470.0), (3, 0.3)] {
+        let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], numero);
+        test_db
+            .db
+            .get_connection()
+            .unwrap()
+            .execute("UPDATE semaines SET poids = ?1 WHERE id = ?2", rusqlite::params![poids, semaine])
+            .unwrap();
+    }
+    let semaine_2 = semaine_id(&test_db, fixtures.batiment_ids[0], 2);
+    let semaine_3 = semaine_id(&test_db, fixtures.batiment_ids[0], 3);
+
+    let apercu = service.normalize_semaine_poids(false).await.unwrap();
+    assert!(!apercu.appliquee);
+    assert_eq!(apercu.convertis.len(), 1);
+    assert_eq!(apercu.convertis[0].semaine_id, semaine_2);
+    assert_eq!(apercu.a_verifier.len(), 1);
+    assert_eq!(apercu.a_verifier[0].semaine_id, semaine_3);
+    assert_eq!(test_db.count("semaines", "poids = 470"), 1);
+
+    service.normalize_semaine_poids(true).await.unwrap();
+    assert_eq!(test_db.count("semaines", "poids = 0.47"), 1);
+    let semaines = service.get_full_semaines_by_batiment(fixtures.batiment_ids[0]).await.unwrap();
+    assert!(semaines[2].poids_a_verifier);
+    assert!(!semaines[1].poids_a_verifier);
+
+    // Une nouvelle saisie du poids lève le signalement
+    let corrigee = service.update_semaine_poids(semaine_3, Some(900.0), Some("g".to_string())).await.unwrap();
+    assert_eq!(corrigee.poids, Some(0.9));
+    assert!(!corrigee.poids_a_verifier);
+    assert_eq!(test_db.count("semaines", "poids_a_verifier = 1"), 0);
+    assert!(service.normalize_semaine_poids(false).await.unwrap().a_verifier.is_empty());
+}
+
+#[tokio::test]
+async fn registered_user_can_log_in() {
+    let test_db = TestDb::new();
+    let auth = AuthService::new(test_db.storage());
+
+    auth.register(CreateUser {
+        username: "technicien".to_string(),
+        email: "technicien@example.com".to_string(),
+        password: "motdepasse123".to_string(),
+        registration_code: "FERME2024".to_string(),
+    })
+    .await
+    .unwrap();
+
+    let response = auth
+        .login(LoginUser { username: "technicien".to_string(), password: "motdepasse123".to_string(), totp_code: None })
+        .await
+        .unwrap();
+    assert_eq!(response.user.username, "technicien");
+
+    let wrong_password = auth
+        .login(LoginUser { username: "technicien".to_string(), password: "incorrect".to_string(), totp_code: None })
+        .await;
+    assert!(wrong_password.is_err());
+}
+
+#[tokio::test]
+async fn reset_password_must_be_changed_before_working() {
+    let test_db = TestDb::new();
+    let auth = AuthService::new(test_db.storage());
+    let inscrire = |username: &str, code: &str| CreateUser {
+        username: username.to_string(),
+        email: format!("{}@example.com", username),
+        password: "motdepasse123".to_string(),
+        registration_code: code.to_string(),
+    };
+
+    let admin = auth.register(inscrire("admin", "")).await.unwrap();
+    let code = invitation(&test_db, &admin.token).await;
+    let technicien = auth.register(inscrire("technicien", &code)).await.unwrap();
+    let user_id = technicien.user.id;
+
+    // Réservé aux administrateurs
+    assert!(auth.reset_user_password(user_id, &technicien.token).await.is_err());
+
+    let temporaire = auth.reset_user_password(user_id, &admin.token).await.unwrap();
+    assert!(auth.verify_token(&technicien.token).await.unwrap().is_none());
+    assert_eq!(test_db.count("audit_log", &format!("action = 'reinitialisation_mot_de_passe' AND entite_id = {}", user_id)), 1);
+
+    let connexion = auth
+        .login(LoginUser { username: "technicien".to_string(), password: temporaire.clone(), totp_code: None })
+        .await
+        .unwrap();
+    assert!(connexion.user.must_change_password);
+    assert!(auth.current_user(&connexion.token).await.is_err());
+
+    auth.update_password(UpdatePasswordData {
+        user_id,
+        current_password: temporaire,
+        new_password: "nouveau-secret".to_string(),
+    })
+    .await
+    .unwrap();
+    let utilisateur = auth.current_user(&connexion.token).await.unwrap();
+    assert!(!utilisateur.must_change_password);
+}
+
+#[tokio::test]
+async fn user_activity_is_derived_from_logins_and_daily_entries() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let auth = AuthService::new(test_db.storage());
+    let inscrire = |username: &str, code: &str| CreateUser {
+        username: username.to_string(),
+        email: format!("{}@example.com", username),
+        password: "motdepasse123".to_string(),
+        registration_code: code.to_string(),
+    };
+
+    let admin = auth.register(inscrire("admin", "")).await.unwrap();
+    let code = invitation(&test_db, &admin.token).await;
+    let technicien = auth.register(inscrire("technicien", &code)).await.unwrap();
+    auth.login(LoginUser { username: "technicien".to_string(), password: "motdepasse123".to_string(), totp_code: None })
+        .await
+        .unwrap();
+
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let saisie_technicien = SuiviQuotidienRepository::new(test_db.storage()).with_author(Some(technicien.user.id));
+    for (age, field, value) in [(1, "deces_par_jour", "3"), (1, "alimentation_par_jour", "2"), (2, "deces_par_jour", "1")] {
+        saisie_technicien.upsert_field(semaine, age, field, value).await.unwrap();
+    }
+    SuiviQuotidienRepository::new(test_db.storage())
+        .with_author(Some(admin.user.id))
+        .upsert_field(semaine, 3, "remarques", "RAS")
+        .await
+        .unwrap();
+    // Sans auteur, la saisie n'est pas attribuée
+    SuiviQuotidienRepository::new(test_db.storage()).upsert_field(semaine, 4, "deces_par_jour", "0").await.unwrap();
+
+    assert!(auth.get_user_activity(None, None, &technicien.token).await.is_err());
+
+    let activite = auth.get_user_activity(None, None, &admin.token).await.unwrap();
+    assert_eq!(activite.len(), 2);
+    assert_eq!(activite[0].username, "technicien");
+    assert_eq!(activite[0].nombre_saisies, 3);
+    assert!(activite[0].derniere_connexion.is_some());
+    assert_eq!(activite[0].bandes_plus_modifiees.len(), 1);
+    assert_eq!(activite[0].bandes_plus_modifiees[0].bande_id, fixtures.bande_id);
+    assert_eq!(activite[0].bandes_plus_modifiees[0].nombre_saisies, 3);
+    assert_eq!(activite[1].username, "admin");
+    assert_eq!(activite[1].nombre_saisies, 1);
+    assert!(activite[1].derniere_connexion.is_none());
+
+    let an_2000 = Some(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap());
+    let ancienne = auth.get_user_activity(an_2000, an_2000, &admin.token).await.unwrap();
+    assert!(ancienne.iter().all(|utilisateur| utilisateur.nombre_saisies == 0 && utilisateur.bandes_plus_modifiees.is_empty()));
+    assert!(auth.get_user_activity(Some(NaiveDate::from_ymd_opt(2000, 1, 2).unwrap()), an_2000, &admin.token).await.is_err());
+}
+
+#[tokio::test]
+async fn accounts_after_the_first_need_a_valid_invitation() {
+    let test_db = TestDb::new();
+    let auth = AuthService::new(test_db.storage());
+    let inscrire = |username: &str, code: &str| CreateUser {
+        username: username.to_string(),
+        email: format!("{}@example.com", username),
+        password: "motdepasse123".to_string(),
+        registration_code: code.to_string(),
+    };
+
+    // Le premier compte n'a pas besoin d'invitation
+    let admin = auth.register(inscrire("admin", "")).await.unwrap();
+    assert!(auth.register(inscrire("intrus", "FERME2024")).await.is_err());
+
+    // Usage unique
+    let code = invitation(&test_db, &admin.token).await;
+    auth.register(inscrire("technicien", &code)).await.unwrap();
+    assert!(auth.register(inscrire("second", &code)).await.is_err());
+
+    // Le rôle vient de l'invitation; un code révoqué n'est plus valable
+    let partage = auth
+        .create_invitation(
+            CreateInvitation {
+                code: Some("EQUIPE-2025".to_string()),
+                role: ROLE_ADMIN.to_string(),
+                expires_on: None,
+                single_use: false,
+            },
+            &admin.token,
+        )
+        .await
+        .unwrap();
+    let adjoint = auth.register(inscrire("adjoint", "EQUIPE-2025")).await.unwrap();
+    assert_eq!(adjoint.user.role, ROLE_ADMIN);
+    auth.revoke_invitation(partage.id, &admin.token).await.unwrap();
+    assert!(auth.register(inscrire("retardataire", "EQUIPE-2025")).await.is_err());
+
+    let invitations = auth.get_invitations(&admin.token).await.unwrap();
+    assert_eq!(invitations.len(), 2);
+    assert_eq!(invitations[0].use_count, 1);
+    assert!(invitations[0].revoked);
+}
+
+#[tokio::test]
+async fn admin_second_factor_is_required_at_login_once_enrolled() {
+    let test_db = TestDb::new();
+    let auth = AuthService::new(test_db.storage());
+    let admin = auth
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap();
+    let connexion = |totp_code: Option<String>| LoginUser {
+        username: "admin".to_string(),
+        password: "motdepasse123".to_string(),
+        totp_code,
+    };
+
+    let enrolement = auth.start_mfa_enrollment(&admin.token).await.unwrap();
+    assert!(enrolement.provisioning_uri.starts_with("otpauth://totp/Geema:admin?secret="));
+    // Tant que l'enrôlement n'est pas confirmé, le code n'est pas exigé
+    auth.login(connexion(None)).await.unwrap();
+
+    let pas = chrono::Utc::now().timestamp() / TOTP_PERIODE;
+    assert!(auth.confirm_mfa_enrollment("000000x", &admin.token).await.is_err());
+    auth.confirm_mfa_enrollment(&totp::code(&enrolement.secret, pas).unwrap(), &admin.token).await.unwrap();
+
+    assert!(auth.login(connexion(None)).await.is_err());
+    let suivant = totp::code(&enrolement.secret, pas + 1).unwrap();
+    auth.login(connexion(Some(suivant.clone()))).await.unwrap();
+    // Un code ne sert qu'une fois
+    assert!(auth.login(connexion(Some(suivant))).await.is_err());
+
+    // Simule l'attente du code suivant, le code de la connexion étant consommé
+    test_db.db.get_connection().unwrap().execute("UPDATE user_mfa SET last_used_step = last_used_step - 2", []).unwrap();
+    auth.disable_mfa(&totp::code(&enrolement.secret, pas + 1).unwrap(), &admin.token).await.unwrap();
+    auth.login(connexion(None)).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn second_factor_code_is_accepted_once_by_concurrent_logins() {
+    let test_db = TestDb::new();
+    let auth = AuthService::new(test_db.storage());
+    let admin = auth
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap();
+    let enrolement = auth.start_mfa_enrollment(&admin.token).await.unwrap();
+    let pas = chrono::Utc::now().timestamp() / TOTP_PERIODE;
+    auth.confirm_mfa_enrollment(&totp::code(&enrolement.secret, pas - 1).unwrap(), &admin.token).await.unwrap();
+
+    // Le même code est rejoué par plusieurs connexions simultanées
+    let code = totp::code(&enrolement.secret, pas).unwrap();
+    let connexions = (0..6).map(|_| {
+        let auth = AuthService::new(test_db.storage());
+        let code = code.clone();
+        tokio::spawn(async move {
+            auth.login(LoginUser {
+                username: "admin".to_string(),
+                password: "motdepasse123".to_string(),
+                totp_code: Some(code),
+            })
+            .await
+        })
+    });
+
+    let mut acceptees = 0;
+    for connexion in connexions.collect::<Vec<_>>() {
+        if connexion.await.unwrap().is_ok() {
+            acceptees += 1;
+        }
+    }
+    assert_eq!(acceptees, 1);
+    assert_eq!(test_db.count("audit_log", "action = 'connexion'"), 1);
+}
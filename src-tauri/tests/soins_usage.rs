@@ -0,0 +1,169 @@
+//! Rapports de consommation des soins
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::database::Storage;
+use tauri_app_lib::models::{CreateSoin, CreateSuiviSoin};
+use tauri_app_lib::repositories::{
+    SoinRepository, SoinRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{BilanService, SoinService};
+
+#[tokio::test]
+async fn usage_sums_numeric_quantities_per_soin() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let vitamine = soin_repo.create(CreateSoin { nom: "Vitamine".to_string(), unit: "ml".to_string(), ..Default::default() }).await.unwrap();
+    let vaccin = soin_repo.create(CreateSoin { nom: "Vaccin".to_string(), unit: "dose".to_string(), ..Default::default() }).await.unwrap();
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_2 = semaine_id(&test_db, fixtures.batiment_ids[1], 2);
+    // La bande entre le 2024-03-01: l'âge 1 correspond au 1er mars
+    for (semaine, age, soin_id, quantite) in [
+        (semaine_1, 1, vitamine.id.unwrap(), "250 ml"),
+        (semaine_1, 2, vitamine.id.unwrap(), "0,5"),
+        (semaine_1, 3, vitamine.id.unwrap(), "selon notice"),
+        (semaine_2, 14, vaccin.id.unwrap(), "5000"),
+    ] {
+        suivi_repo.upsert_field(semaine, age, "soins_id", &soin_id.to_string()).await.unwrap();
+        suivi_repo.upsert_field(semaine, age, "soins_quantite", quantite).await.unwrap();
+    }
+
+    let service = SoinService::new(test_db.storage());
+    let usage = service.get_soins_usage(fixtures.bande_id).await.unwrap();
+    assert_eq!(usage.len(), 2);
+    assert_eq!(usage[0].soin_nom, "Vaccin");
+    assert_eq!(usage[0].total_quantite, 5000.0);
+    assert_eq!(usage[1].soin_nom, "Vitamine");
+    assert_eq!(usage[1].total_quantite, 250.5);
+    assert_eq!(usage[1].nombre_administrations, 3);
+    assert_eq!(usage[1].saisies_non_numeriques, 1);
+
+    let mars_debut = service
+        .get_soins_usage_by_ferme(fixtures.ferme_id, Some("2024-03-01".to_string()), Some("2024-03-10".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(mars_debut.len(), 1);
+    assert_eq!(mars_debut[0].soin_nom, "Vitamine");
+}
+
+#[tokio::test]
+async fn treatment_costs_roll_up_per_day_and_bande() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let vitamine = soin_repo
+        .create(CreateSoin { nom: "Vitamine".to_string(), unit: "ml".to_string(), prix_unitaire: Some(0.02), ..Default::default() })
+        .await
+        .unwrap();
+    let vaccin = soin_repo.create(CreateSoin { nom: "Vaccin".to_string(), unit: "dose".to_string(), ..Default::default() }).await.unwrap();
+    assert!(soin_repo
+        .create(CreateSoin { nom: "Antibiotique".to_string(), unit: "g".to_string(), prix_unitaire: Some(-1.0), ..Default::default() })
+        .await
+        .is_err());
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_1_bis = semaine_id(&test_db, fixtures.batiment_ids[1], 1);
+    for (semaine, age, soin_id, quantite, unit) in [
+        (semaine_1, 1, vitamine.id, "250", None),
+        (semaine_1_bis, 1, vitamine.id, "100", None),
+        (semaine_1, 2, vitamine.id, "0,5", Some("L")),
+        (semaine_1, 3, vaccin.id, "5000", None),
+    ] {
+        suivi_repo
+            .add_soin(CreateSuiviSoin {
+                semaine_id: semaine,
+                age,
+                soin_id,
+                quantite: Some(quantite.to_string()),
+                unit: unit.map(str::to_string),
+            })
+            .await
+            .unwrap();
+    }
+
+    // Les saisies sont converties dans l'unité du soin (0,5 L = 500 ml) avant d'être valorisées
+    let usage = SoinService::new(test_db.storage()).get_soins_usage(fixtures.bande_id).await.unwrap();
+    let ligne = |nom: &str, unit: &str| usage.iter().find(|u| u.soin_nom == nom && u.unit == unit).cloned();
+    let vitamine = ligne("Vitamine", "ml").unwrap();
+    assert!((vitamine.total_quantite - 850.0).abs() < 1e-9);
+    assert!((vitamine.cout.unwrap() - 17.0).abs() < 1e-9);
+    assert!(ligne("Vitamine", "L").is_none());
+    assert_eq!(ligne("Vaccin", "dose").unwrap().cout, None);
+
+    let bilan = BilanService::new(test_db.storage()).get_bande_financial_summary(fixtures.bande_id).await.unwrap();
+    assert!((bilan.cout_soins - 17.0).abs() < 1e-9);
+    assert_eq!(bilan.cout_soins_par_jour.len(), 2);
+    assert_eq!(bilan.cout_soins_par_jour[0].date, "2024-03-01");
+    assert_eq!(bilan.soins_non_valorises, 1);
+}
+
+#[tokio::test]
+async fn free_text_quantities_are_structured_and_summed_across_units() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let vitamine = soin_repo.create(CreateSoin { nom: "Vitamine".to_string(), unit: "L".to_string(), ..Default::default() }).await.unwrap();
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let ajout = suivi_repo
+        .add_soin(CreateSuiviSoin {
+            semaine_id: semaine,
+            age: 1,
+            soin_id: vitamine.id,
+            quantite: Some("5l".to_string()),
+            unit: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(ajout.quantite_valeur, Some(5.0));
+    assert_eq!(ajout.quantite_unite.as_deref(), Some("l"));
+    suivi_repo
+        .add_soin(CreateSuiviSoin {
+            semaine_id: semaine,
+            age: 2,
+            soin_id: vitamine.id,
+            quantite: Some("250".to_string()),
+            unit: Some("ml".to_string()),
+        })
+        .await
+        .unwrap();
+
+    // Saisie antérieure aux quantités structurées: reprise à l'ouverture de la base
+    let suivi_id: i64 = test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .query_row("SELECT suivi_id FROM suivi_soins WHERE id = ?1", [ajout.id.unwrap()], |row| row.get(0))
+        .unwrap();
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "INSERT INTO suivi_soins (suivi_id, soin_id, quantite) VALUES (?1, ?2, '1,5 L')",
+            rusqlite::params![suivi_id, vitamine.id],
+        )
+        .unwrap();
+    test_db.db.initialize_schema().unwrap();
+    let reprise: (Option<f64>, Option<String>) = test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .query_row("SELECT quantite_valeur, quantite_unite FROM suivi_soins WHERE quantite = '1,5 L'", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(reprise, (Some(1.5), Some("l".to_string())));
+
+    let usage = SoinService::new(test_db.storage()).get_soins_usage(fixtures.bande_id).await.unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].unit, "L");
+    assert!((usage[0].total_quantite - 6.75).abs() < 1e-9);
+    assert_eq!(usage[0].saisies_non_numeriques, 0);
+}
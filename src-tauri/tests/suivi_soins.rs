@@ -0,0 +1,132 @@
+//! Plusieurs soins par jour de suivi
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::error::AppError;
+use tauri_app_lib::models::{CreateSoin, CreateSuiviSoin, UpdateSuiviQuotidien, UpdateSuiviSoin};
+use tauri_app_lib::repositories::{
+    SoinRepository, SoinRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::SoinService;
+
+#[tokio::test]
+async fn day_keeps_several_soins_with_their_units() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let vitamine = soin_repo.create(CreateSoin { nom: "Vitamine".to_string(), unit: "ml".to_string(), ..Default::default() }).await.unwrap();
+    let vaccin = soin_repo.create(CreateSoin { nom: "Vaccin".to_string(), unit: "dose".to_string(), ..Default::default() }).await.unwrap();
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 2);
+
+    // Le premier soin passe par la saisie cellule, les suivants par add_soin
+    suivi_repo.upsert_field(semaine, 14, "soins_id", &vaccin.id.unwrap().to_string()).await.unwrap();
+    suivi_repo.upsert_field(semaine, 14, "soins_quantite", "5000").await.unwrap();
+    let ajout = suivi_repo.add_soin(CreateSuiviSoin {
+        semaine_id: semaine,
+        age: 14,
+        soin_id: vitamine.id,
+        quantite: Some("1".to_string()),
+        unit: Some("L".to_string()),
+    }).await.unwrap();
+    assert_eq!(ajout.unit.as_deref(), Some("L"));
+
+    let jours = suivi_repo.get_by_semaine(semaine).await.unwrap();
+    assert_eq!(jours.len(), 1);
+    let jour = &jours[0];
+    assert_eq!(jour.soins.len(), 2);
+    assert_eq!(jour.soins_nom.as_deref(), Some("Vaccin"));
+    assert_eq!(jour.soins_quantite.as_deref(), Some("5000"));
+    assert_eq!(jour.soins[1].soin_nom.as_deref(), Some("Vitamine"));
+
+    // Sans unité saisie, l'unité par défaut du soin s'applique
+    let modifie = suivi_repo.update_soin(UpdateSuiviSoin {
+        id: ajout.id.unwrap(),
+        soin_id: vitamine.id,
+        quantite: Some("250".to_string()),
+        unit: None,
+    }).await.unwrap();
+    assert_eq!(modifie.unit.as_deref(), Some("ml"));
+
+    let usage = SoinService::new(test_db.storage()).get_soins_usage(fixtures.bande_id).await.unwrap();
+    assert_eq!(usage.len(), 2);
+    assert_eq!(usage[1].total_quantite, 250.0);
+
+    // Un soin utilisé ne peut pas être supprimé
+    assert!(soin_repo.delete(vitamine.id.unwrap()).await.is_err());
+
+    suivi_repo.delete_soin(ajout.id.unwrap()).await.unwrap();
+    let soins = suivi_repo.get_soins(jour.id.unwrap()).await.unwrap();
+    assert_eq!(soins.len(), 1);
+
+    // Vider la cellule retire le premier soin
+    suivi_repo.upsert_field(semaine, 14, "soins_id", "").await.unwrap();
+    suivi_repo.upsert_field(semaine, 14, "soins_quantite", "").await.unwrap();
+    assert!(suivi_repo.get_soins(jour.id.unwrap()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn changing_the_soins_of_a_day_makes_a_loaded_form_stale() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let vaccin = SoinRepository::new(test_db.storage())
+        .create(CreateSoin { nom: "Vaccin".to_string(), unit: "dose".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 2);
+
+    let suivi_id = suivi_repo.upsert_field(semaine, 14, "deces_par_jour", "3").await.unwrap().id.unwrap();
+    // Horodatage ancien, posé avec la version pour ne pas passer par le déclencheur
+    test_db.db.get_connection().unwrap()
+        .execute("UPDATE suivi_quotidien SET updated_at = '2000-01-01 00:00:00', version = version + 1 WHERE id = ?1", [suivi_id])
+        .unwrap();
+    let charge_version = suivi_repo.get_by_id(suivi_id).await.unwrap().version;
+
+    // La saisie du soin dans la grille incrémente la version et updated_at du jour
+    let avec_soin = suivi_repo.upsert_field(semaine, 14, "soins_id", &vaccin.id.unwrap().to_string()).await.unwrap();
+    assert_eq!(avec_soin.version, charge_version + 1);
+    assert_eq!(test_db.count("suivi_quotidien", "updated_at > '2000-01-02'"), 1);
+    // Ressaisir le même soin ne modifie rien
+    let inchange = suivi_repo.upsert_field(semaine, 14, "soins_id", &vaccin.id.unwrap().to_string()).await.unwrap();
+    assert_eq!(inchange.version, avec_soin.version);
+
+    // Un formulaire chargé avant le soin est refusé
+    let formulaire = UpdateSuiviQuotidien {
+        id: suivi_id,
+        semaine_id: semaine,
+        age: 14,
+        deces_par_jour: Some(4),
+        elimines_par_jour: None,
+        alimentation_par_jour: None,
+        soins_id: None,
+        soins_quantite: None,
+        analyses: None,
+        remarques: None,
+        version: Some(charge_version),
+    };
+    let erreur = suivi_repo.update(formulaire.clone()).await.unwrap_err();
+    assert!(matches!(erreur, AppError::Conflict { .. }), "{}", erreur);
+    assert_eq!(suivi_repo.get_soins(suivi_id).await.unwrap().len(), 1);
+
+    // Les soins ajoutés, modifiés ou retirés un par un comptent aussi
+    let ajout = suivi_repo.add_soin(CreateSuiviSoin {
+        semaine_id: semaine,
+        age: 14,
+        soin_id: vaccin.id,
+        quantite: Some("1".to_string()),
+        unit: None,
+    }).await.unwrap();
+    suivi_repo.update_soin(UpdateSuiviSoin { id: ajout.id.unwrap(), soin_id: vaccin.id, quantite: Some("2".to_string()), unit: None })
+        .await
+        .unwrap();
+    suivi_repo.delete_soin(ajout.id.unwrap()).await.unwrap();
+    let version = suivi_repo.get_by_id(suivi_id).await.unwrap().version;
+    assert_eq!(version, avec_soin.version + 3);
+
+    let modifie = suivi_repo.update(UpdateSuiviQuotidien { version: Some(version), ..formulaire }).await.unwrap();
+    assert_eq!(modifie.version, version + 1);
+    assert!(suivi_repo.get_soins(suivi_id).await.unwrap().is_empty());
+}
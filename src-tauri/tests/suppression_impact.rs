@@ -0,0 +1,95 @@
+//! Aperçu de l'impact d'une suppression
+
+mod common;
+
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateSoin, CreateSuiviSoin, ImpactSuppression};
+use tauri_app_lib::repositories::{
+    SoinRepository, SoinRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::SuppressionService;
+
+fn nombre(impact: &ImpactSuppression, cle: &str) -> i64 {
+    impact.dependances.iter().find(|d| d.cle == cle).unwrap().nombre
+}
+
+#[tokio::test]
+async fn delete_impact_lists_dependent_rows() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+
+    let soin_repo = SoinRepository::new(test_db.storage());
+    let vitamine = soin_repo.create(CreateSoin {
+        nom: "Vitamine AD3E".to_string(),
+        unit: "ml".to_string(),
+        ..Default::default()
+    }).await.unwrap();
+    let inutilise = soin_repo.create(CreateSoin {
+        nom: "Vaccin Gumboro".to_string(),
+        unit: "dose".to_string(),
+        ..Default::default()
+    }).await.unwrap();
+
+    let suivi_repo = SuiviQuotidienRepository::new(test_db.storage());
+    for (batiment_id, age) in [(fixtures.batiment_ids[0], 1), (fixtures.batiment_ids[0], 2), (fixtures.batiment_ids[1], 1)] {
+        suivi_repo.add_soin(CreateSuiviSoin {
+            semaine_id: semaine_id(&test_db, batiment_id, 1),
+            age,
+            soin_id: vitamine.id,
+            quantite: Some("1".to_string()),
+            unit: None,
+        }).await.unwrap();
+    }
+
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "INSERT INTO plan_soins (poussin_id, soin_id, jour_debut, jour_fin) VALUES (?1, ?2, 1, 5)",
+            [fixtures.poussin_id, vitamine.id.unwrap()],
+        )
+        .unwrap();
+    test_db
+        .db
+        .get_connection()
+        .unwrap()
+        .execute(
+            "INSERT INTO historique_prix (article, poussin_id, prix_unitaire, date_debut) VALUES ('poussin', ?1, 4.0, '2024-01-01')",
+            [fixtures.poussin_id],
+        )
+        .unwrap();
+
+    let service = SuppressionService::new(test_db.storage());
+
+    let ferme = service.get_delete_impact("ferme", fixtures.ferme_id).await.unwrap();
+    assert!(!ferme.suppression_possible);
+    assert_eq!(nombre(&ferme, "bandes"), 1);
+    assert_eq!(nombre(&ferme, "batiments"), 2);
+    assert_eq!(nombre(&ferme, "suivis_quotidiens"), 3);
+
+    let personnel = service.get_delete_impact("Personnel", fixtures.personnel_id).await.unwrap();
+    assert!(!personnel.suppression_possible);
+    assert_eq!(nombre(&personnel, "batiments"), 2);
+    assert_eq!(nombre(&personnel, "bandes"), 1);
+
+    let soin = service.get_delete_impact("soin", vitamine.id.unwrap()).await.unwrap();
+    assert_eq!(soin.nom, "Vitamine AD3E");
+    assert!(!soin.suppression_possible);
+    assert_eq!(nombre(&soin, "administrations"), 3);
+    assert_eq!(nombre(&soin, "bandes"), 1);
+    assert_eq!(nombre(&soin, "plan_soins"), 1);
+
+    let poussin = service.get_delete_impact("poussin", fixtures.poussin_id).await.unwrap();
+    assert!(!poussin.suppression_possible);
+    assert_eq!(nombre(&poussin, "batiments"), 2);
+    assert_eq!(nombre(&poussin, "plan_soins"), 1);
+    assert_eq!(nombre(&poussin, "historique_prix"), 1);
+
+    let libre = service.get_delete_impact("soin", inutilise.id.unwrap()).await.unwrap();
+    assert!(libre.suppression_possible);
+    assert!(libre.dependances.iter().all(|d| d.nombre == 0));
+
+    assert!(service.get_delete_impact("soin", 9999).await.is_err());
+    assert!(service.get_delete_impact("bande", fixtures.bande_id).await.is_err());
+}
@@ -0,0 +1,74 @@
+//! Étiquettes des bandes et filtrage des listes
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, TestDb};
+use tauri_app_lib::models::{BandeLoadOptions, CreateBande, CreateTag, FiltreBandes};
+use tauri_app_lib::repositories::{BandeRepository, TagRepository};
+
+#[tokio::test]
+async fn tagged_bandes_can_be_filtered_in_paginated_listings() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    let tag = |nom: &str, couleur: Option<&str>| CreateTag { nom: nom.to_string(), couleur: couleur.map(str::to_string) };
+    let essai = TagRepository::create(&conn, &tag(" Essai ", Some("#1E90FF"))).unwrap();
+    assert_eq!(essai.nom, "Essai");
+    assert_eq!(essai.couleur.as_deref(), Some("#1e90ff"));
+    let probleme = TagRepository::create(&conn, &tag("Problème", None)).unwrap();
+
+    assert!(TagRepository::create(&conn, &tag("essai", None)).is_err());
+    assert!(TagRepository::create(&conn, &tag("  ", None)).is_err());
+    assert!(TagRepository::create(&conn, &tag("Intégration", Some("bleu"))).is_err());
+    assert!(TagRepository::update(&conn, probleme.id, &tag("ESSAI", None)).is_err());
+    let renomme = TagRepository::update(&conn, probleme.id, &tag("Suivi renforcé", None)).unwrap();
+    assert_eq!(renomme.nom, "Suivi renforcé");
+
+    let autre = BandeRepository::create(
+        &conn,
+        &CreateBande {
+            date_entree: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            ferme_id: fixtures.ferme_id,
+            notes: None,
+            champs_personnalises: Default::default(),
+        },
+        None,
+    )
+    .unwrap()
+    .id
+    .unwrap();
+
+    TagRepository::add_to_bande(&conn, fixtures.bande_id, essai.id).unwrap();
+    TagRepository::add_to_bande(&conn, fixtures.bande_id, essai.id).unwrap();
+    TagRepository::add_to_bande(&conn, fixtures.bande_id, probleme.id).unwrap();
+    TagRepository::add_to_bande(&conn, autre, probleme.id).unwrap();
+    assert!(TagRepository::add_to_bande(&conn, 9999, essai.id).is_err());
+    assert!(TagRepository::add_to_bande(&conn, autre, 9999).is_err());
+
+    let options = BandeLoadOptions::default();
+    let page = |tag_id: Option<i64>| {
+        let filtre = FiltreBandes { tag_id, ..Default::default() };
+        BandeRepository::get_by_ferme_paginated(&conn, fixtures.ferme_id, 1, 10, &filtre, &options).unwrap()
+    };
+    assert_eq!(page(None).total, 2);
+    let essais = page(Some(essai.id));
+    assert_eq!(essais.total, 1);
+    assert_eq!(essais.data[0].id, Some(fixtures.bande_id));
+    assert_eq!(
+        essais.data[0].tags.iter().map(|t| t.nom.as_str()).collect::<Vec<_>>(),
+        vec!["Essai", "Suivi renforcé"]
+    );
+    assert_eq!(page(Some(probleme.id)).total, 2);
+
+    TagRepository::remove_from_bande(&conn, autre, probleme.id).unwrap();
+    assert!(TagRepository::remove_from_bande(&conn, autre, probleme.id).is_err());
+    assert_eq!(page(Some(probleme.id)).total, 1);
+
+    // Supprimer une étiquette la retire des bandes
+    TagRepository::delete(&conn, essai.id).unwrap();
+    assert!(TagRepository::delete(&conn, essai.id).is_err());
+    assert_eq!(TagRepository::get_by_bande(&conn, fixtures.bande_id).unwrap(), vec![renomme]);
+    assert_eq!(TagRepository::get_all(&conn).unwrap().len(), 1);
+}
@@ -0,0 +1,92 @@
+//! Dates de création, de modification et auteur des bandes, bâtiments,
+//! semaines et suivis
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateBande, CreateBatiment, CreateUser};
+use tauri_app_lib::repositories::{SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait};
+use tauri_app_lib::services::{AuthService, BandeService};
+
+async fn connecter(test_db: &TestDb) -> (i64, String) {
+    let reponse = AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "technicien".to_string(),
+            email: "technicien@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: "FERME2024".to_string(),
+        })
+        .await
+        .unwrap();
+    (reponse.user.id, reponse.token)
+}
+
+#[tokio::test]
+async fn created_rows_record_their_author_and_dates() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let (user_id, token) = connecter(&test_db).await;
+    let service = BandeService::new(test_db.storage());
+
+    let bande = service
+        .create_bande_with_batiments_and_first_week(
+            CreateBande {
+                date_entree: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+                ferme_id: fixtures.ferme_id,
+                notes: None,
+                champs_personnalises: Default::default(),
+            },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "3".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 4000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            Some(&token),
+        )
+        .await
+        .unwrap();
+
+    let details = service.get_bande_by_id(bande.id.unwrap()).await.unwrap().unwrap();
+    assert_eq!(details.tracabilite.created_by, Some(user_id));
+    assert_eq!(details.tracabilite.created_by_nom.as_deref(), Some("technicien"));
+    assert!(details.tracabilite.created_at.is_some());
+    assert_eq!(details.batiments[0].tracabilite.created_by, Some(user_id));
+    let batiment_id = details.batiments[0].id.unwrap();
+    assert_eq!(test_db.count("semaines", &format!("batiment_id = {} AND created_by = {}", batiment_id, user_id)), 1);
+
+    // Les bandes créées sans session n'ont pas d'auteur
+    let anonyme = service.get_bande_by_id(fixtures.bande_id).await.unwrap().unwrap();
+    assert_eq!(anonyme.tracabilite.created_by, None);
+    assert!(anonyme.tracabilite.created_at.is_some());
+}
+
+#[tokio::test]
+async fn any_write_refreshes_updated_at() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let semaine = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let repository = SuiviQuotidienRepository::new(test_db.storage());
+
+    let suivi = repository.upsert_field(semaine, 1, "deces_par_jour", "3").await.unwrap();
+    let suivi_id = suivi.id.unwrap();
+    {
+        let conn = test_db.db.get_connection().unwrap();
+        conn.execute(
+            "UPDATE suivi_quotidien SET created_at = '2024-01-01 00:00:00', updated_at = '2024-01-01 00:00:00' WHERE id = ?1",
+            [suivi_id],
+        )
+        .unwrap();
+    }
+
+    // Saisie d'une cellule: la mise à jour passe par le déclencheur de version
+    repository.upsert_field(semaine, 1, "deces_par_jour", "5").await.unwrap();
+
+    let details = repository.get_by_id(suivi_id).await.unwrap();
+    assert_eq!(details.tracabilite.created_at.as_deref(), Some("2024-01-01 00:00:00"));
+    assert_ne!(details.tracabilite.updated_at.as_deref(), Some("2024-01-01 00:00:00"));
+}
@@ -0,0 +1,159 @@
+//! TVA des achats et récapitulatif trimestriel
+
+mod common;
+
+use common::{seed, TestDb};
+use std::fs;
+use tauri_app_lib::models::{CreateAlimentationHistory, CreateLitiere, SaisieTva, UpdateAlimentationHistory, MODE_TVA_TTC};
+use tauri_app_lib::repositories::{AlimentationRepository, LitiereRepository, LitiereRepositoryTrait};
+use tauri_app_lib::services::ExportService;
+
+fn litiere(batiment_id: i64, cout: f64, tva: SaisieTva, date_operation: &str) -> CreateLitiere {
+    CreateLitiere {
+        batiment_id,
+        type_operation: "livraison".to_string(),
+        materiau: "Paille".to_string(),
+        quantite: 10.0,
+        unite: "botte".to_string(),
+        cout: Some(cout),
+        tva,
+        date_operation: date_operation.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn purchases_are_recorded_before_tax_and_summarised_by_quarter() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let batiment_id = fixtures.batiment_ids[0];
+
+    // Aliment saisi TTC à 6 par kg avec 20 % de TVA: 5 hors taxes
+    let conn = test_db.db.get_connection().unwrap();
+    let livraison = |prix_unitaire: f64, tva: SaisieTva| {
+        AlimentationRepository::create(
+            &conn,
+            &CreateAlimentationHistory {
+                bande_id: fixtures.bande_id,
+                quantite: 1000.0,
+                created_at: "2024-03-05 08:00:00".to_string(),
+                prix_unitaire: Some(prix_unitaire),
+                tva,
+                confirmer_doublon: true,
+                ..Default::default()
+            },
+        )
+    };
+    let ttc = SaisieTva { taux_tva: Some(20.0), mode_tva: Some(MODE_TVA_TTC.to_string()), ..Default::default() };
+    let aliment = livraison(6.0, ttc).unwrap();
+    assert!((aliment.prix_unitaire.unwrap() - 5.0).abs() < 1e-9);
+    assert!((aliment.montant_tva.unwrap() - 1000.0).abs() < 1e-9);
+    assert!(livraison(6.0, SaisieTva { mode_tva: Some(MODE_TVA_TTC.to_string()), ..Default::default() }).is_err());
+    assert!(livraison(6.0, SaisieTva { taux_tva: Some(150.0), ..Default::default() }).is_err());
+    assert!(livraison(6.0, SaisieTva { mode_tva: Some("brut".to_string()), ..Default::default() }).is_err());
+    drop(conn);
+
+    // Litière hors taxes à 10 %, puis une facture avec un montant de TVA saisi sans taux
+    let repo = LitiereRepository::new(test_db.storage());
+    let paille = repo
+        .create(litiere(batiment_id, 1200.0, SaisieTva { taux_tva: Some(10.0), ..Default::default() }, "2024-02-10"))
+        .await
+        .unwrap();
+    assert_eq!((paille.cout, paille.taux_tva, paille.montant_tva), (Some(1200.0), Some(10.0), Some(120.0)));
+    repo.create(litiere(batiment_id, 500.0, SaisieTva { montant_tva: Some(50.0), ..Default::default() }, "2024-04-02"))
+        .await
+        .unwrap();
+    repo.create(litiere(batiment_id, 300.0, SaisieTva::default(), "2024-04-03")).await.unwrap();
+
+    let service = ExportService::new(test_db.storage());
+    let resume = service.get_vat_summary(2024).await.unwrap();
+    let lignes: Vec<_> = resume
+        .iter()
+        .map(|t| (t.trimestre.as_str(), t.taux_tva, t.achats, t.base_ht.round(), t.montant_tva.round()))
+        .collect();
+    assert_eq!(
+        lignes,
+        vec![
+            ("2024-T1", Some(10.0), 1, 1200.0, 120.0),
+            ("2024-T1", Some(20.0), 1, 5000.0, 1000.0),
+            ("2024-T2", None, 1, 500.0, 50.0),
+        ]
+    );
+    assert!(service.get_vat_summary(2023).await.unwrap().is_empty());
+
+    let chemin = test_db.dir().join("tva.csv");
+    service.export_vat_summary(2024, &chemin.to_string_lossy()).await.unwrap();
+    let contenu = fs::read_to_string(&chemin).unwrap();
+    assert!(contenu.starts_with("trimestre;taux_tva;achats;base_ht;montant_tva;montant_ttc;devise\r\n"));
+    assert!(contenu.contains("2024-T1;20;1;5000,00;1000,00;6000,00;MAD\r\n"), "{}", contenu);
+}
+
+#[tokio::test]
+async fn editing_a_delivery_recomputes_its_price_and_vat() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let conn = test_db.db.get_connection().unwrap();
+
+    // Facture de 1 000 kg à 5 hors taxes avec 1 000 de TVA saisis
+    let livraison = AlimentationRepository::create(
+        &conn,
+        &CreateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 1000.0,
+            created_at: "2024-03-05 08:00:00".to_string(),
+            prix_unitaire: Some(5.0),
+            tva: SaisieTva { taux_tva: Some(20.0), montant_tva: Some(1000.0), ..Default::default() },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let id = livraison.id.unwrap();
+
+    // Quantité corrigée: le montant de TVA suit la nouvelle quantité
+    AlimentationRepository::update(
+        &conn,
+        id,
+        &UpdateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 500.0,
+            prix_unitaire: Some(5.0),
+            tva: SaisieTva { taux_tva: Some(20.0), ..Default::default() },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let corrigee = AlimentationRepository::get_by_id(&conn, id).unwrap().unwrap();
+    assert!((corrigee.montant_tva.unwrap() - 500.0).abs() < 1e-9);
+
+    // Prix corrigé, saisi TTC
+    AlimentationRepository::update(
+        &conn,
+        id,
+        &UpdateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 500.0,
+            prix_unitaire: Some(7.2),
+            tva: SaisieTva { taux_tva: Some(20.0), mode_tva: Some(MODE_TVA_TTC.to_string()), ..Default::default() },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(AlimentationRepository::update(
+        &conn,
+        id,
+        &UpdateAlimentationHistory {
+            bande_id: fixtures.bande_id,
+            quantite: 500.0,
+            prix_unitaire: Some(-1.0),
+            ..Default::default()
+        },
+    )
+    .is_err());
+    drop(conn);
+
+    let resume = ExportService::new(test_db.storage()).get_vat_summary(2024).await.unwrap();
+    let lignes: Vec<_> = resume
+        .iter()
+        .map(|t| (t.trimestre.as_str(), t.taux_tva, t.achats, t.base_ht.round(), t.montant_tva.round()))
+        .collect();
+    assert_eq!(lignes, vec![("2024-T1", Some(20.0), 1, 3000.0, 600.0)]);
+}
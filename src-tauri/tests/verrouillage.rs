@@ -0,0 +1,89 @@
+//! Fenêtre de saisie du suivi et déverrouillage des périodes par un administrateur
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{invitation, seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreateUser, UpdateSemaine, AUDIT_DEVERROUILLAGE_PERIODE};
+use tauri_app_lib::repositories::{
+    SemaineRepository, SemaineRepositoryTrait, SuiviQuotidienRepository, SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{AuthService, BandeService, VerrouillageService};
+
+async fn register(test_db: &TestDb, username: &str, registration_code: &str) -> String {
+    AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: username.to_string(),
+            email: format!("{}@example.com", username),
+            password: "motdepasse123".to_string(),
+            registration_code: registration_code.to_string(),
+        })
+        .await
+        .unwrap()
+        .token
+}
+
+fn date(jour: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(jour, "%Y-%m-%d").unwrap()
+}
+
+#[tokio::test]
+async fn days_outside_the_edit_window_require_an_admin_unlock() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let admin = register(&test_db, "admin", "").await;
+    let service = VerrouillageService::new(test_db.storage());
+    let suivi = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_1 = semaine_id(&test_db, fixtures.batiment_ids[0], 1);
+    let semaine_2 = semaine_id(&test_db, fixtures.batiment_ids[0], 2);
+
+    // Sans fenêtre configurée, tous les jours restent modifiables
+    assert_eq!(service.get_edit_window().await.unwrap().jours, None);
+    suivi.upsert_field(semaine_1, 1, "deces_par_jour", "3").await.unwrap();
+
+    assert!(service.set_edit_window(Some(0), &admin).await.is_err());
+    assert_eq!(service.set_edit_window(Some(7), &admin).await.unwrap().jours, Some(7));
+
+    // La bande de test est entrée en mars 2024: tous ses jours sont verrouillés
+    let erreur = suivi.upsert_field(semaine_1, 1, "deces_par_jour", "4").await.unwrap_err();
+    assert!(erreur.to_string().contains("2024-03-01"), "{}", erreur);
+    let semaine = UpdateSemaine {
+        id: semaine_1,
+        batiment_id: fixtures.batiment_ids[0],
+        numero_semaine: 1,
+        poids: Some(0.18),
+        version: None,
+    };
+    assert!(SemaineRepository::new(test_db.storage()).update(semaine.clone()).await.is_err());
+
+    // Un technicien ne peut pas déverrouiller
+    let code = invitation(&test_db, &admin).await;
+    let technicien = register(&test_db, "technicien", &code).await;
+    assert!(service
+        .unlock_period(fixtures.bande_id, date("2024-03-01"), date("2024-03-07"), None, &technicien)
+        .await
+        .is_err());
+    assert!(service
+        .unlock_period(fixtures.bande_id, date("2024-03-07"), date("2024-03-01"), None, &admin)
+        .await
+        .is_err());
+
+    let deverrouillage = service
+        .unlock_period(fixtures.bande_id, date("2024-03-01"), date("2024-03-07"), Some("Pesée corrigée"), &admin)
+        .await
+        .unwrap();
+    assert_eq!(deverrouillage.date_fin, "2024-03-07");
+
+    // Seuls les jours de la période déverrouillée sont modifiables
+    assert_eq!(suivi.upsert_field(semaine_1, 1, "deces_par_jour", "4").await.unwrap().deces_par_jour, Some(4));
+    assert!(SemaineRepository::new(test_db.storage()).update(semaine).await.is_ok());
+    assert!(suivi.upsert_field(semaine_2, 10, "deces_par_jour", "1").await.is_err());
+
+    let journal = BandeService::new(test_db.storage()).get_bande_audit_log(fixtures.bande_id).await.unwrap();
+    let entree = journal.iter().find(|entree| entree.action == AUDIT_DEVERROUILLAGE_PERIODE).unwrap();
+    assert_eq!(entree.details.as_deref(), Some("2024-03-01 au 2024-03-07: Pesée corrigée"));
+
+    // Désactiver la fenêtre rend à nouveau tous les jours modifiables
+    assert_eq!(service.set_edit_window(None, &admin).await.unwrap().jours, None);
+    suivi.upsert_field(semaine_2, 10, "deces_par_jour", "1").await.unwrap();
+}
@@ -0,0 +1,113 @@
+//! Nettoyage et désinfection des bâtiments entre deux bandes
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, TestDb};
+use tauri_app_lib::models::{
+    CreateBande, CreateBatiment, CreateVideSanitaire, UpdateVideSanitaire, ALERTE_VIDE_SANITAIRE,
+};
+use tauri_app_lib::repositories::{VideSanitaireRepository, VideSanitaireRepositoryTrait};
+use tauri_app_lib::services::{AlerteService, BandeService};
+
+#[tokio::test]
+async fn cleaned_building_raises_restock_alert_until_next_bande() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let repo = VideSanitaireRepository::new(test_db.storage());
+
+    // Bâtiment 1 nettoyé après l'enlèvement de la bande entrée le 2024-03-01
+    let vide = repo.create(CreateVideSanitaire {
+        ferme_id: fixtures.ferme_id,
+        numero_batiment: " 1 ".to_string(),
+        date_debut: "2024-05-01".to_string(),
+        produits: Some("Virocid, chaux vive".to_string()),
+        ..Default::default()
+    }).await.unwrap();
+    assert_eq!(vide.numero_batiment, "1");
+    assert!(vide.date_remise_en_place.is_none());
+
+    // Nettoyage du bâtiment 2 toujours en cours: pas d'alerte
+    repo.create(CreateVideSanitaire {
+        ferme_id: fixtures.ferme_id,
+        numero_batiment: "2".to_string(),
+        date_debut: "2024-05-01".to_string(),
+        ..Default::default()
+    }).await.unwrap();
+
+    let invalide = |modifier: fn(&mut CreateVideSanitaire)| {
+        let mut vide = CreateVideSanitaire {
+            ferme_id: fixtures.ferme_id,
+            numero_batiment: "1".to_string(),
+            date_debut: "2024-05-01".to_string(),
+            ..Default::default()
+        };
+        modifier(&mut vide);
+        vide
+    };
+    assert!(repo.create(invalide(|v| v.numero_batiment = "5".to_string())).await.is_err());
+    assert!(repo.create(invalide(|v| v.numero_batiment = "A".to_string())).await.is_err());
+    assert!(repo.create(invalide(|v| v.date_debut = "01/05/2024".to_string())).await.is_err());
+    assert!(repo.create(invalide(|v| v.date_fin = Some("2024-04-30".to_string()))).await.is_err());
+    assert!(repo.create(invalide(|v| v.ferme_id = 9999)).await.is_err());
+
+    let service = AlerteService::new(test_db.storage());
+    assert!(service.get_pending_alerts(None).await.unwrap().is_empty());
+
+    let termine = repo.update(UpdateVideSanitaire {
+        id: vide.id,
+        date_debut: vide.date_debut.clone(),
+        date_fin: Some("2024-05-03".to_string()),
+        produits: vide.produits.clone(),
+    }).await.unwrap();
+    assert_eq!(termine.date_fin.as_deref(), Some("2024-05-03"));
+
+    let alertes = service.get_pending_alerts(None).await.unwrap();
+    assert_eq!(alertes.len(), 1);
+    assert_eq!(alertes[0].type_alerte, ALERTE_VIDE_SANITAIRE);
+    assert_eq!(alertes[0].bande_id, Some(fixtures.bande_id));
+    assert_eq!(alertes[0].batiment_id, Some(fixtures.batiment_ids[0]));
+    assert_eq!(alertes[0].date, "2024-05-15");
+
+    // Aucune moyenne tant qu'aucune bande n'est entrée
+    let statistiques = repo.get_statistics(fixtures.ferme_id).await.unwrap();
+    assert_eq!(statistiques.nombre_vides, 0);
+    assert!(statistiques.duree_moyenne_jours.is_none());
+
+    BandeService::new(test_db.storage())
+        .create_bande_with_batiments_and_first_week(
+            CreateBande {
+                date_entree: NaiveDate::from_ymd_opt(2024, 5, 20).unwrap(),
+                ferme_id: fixtures.ferme_id,
+                notes: None,
+                champs_personnalises: Default::default(),
+            },
+            vec![CreateBatiment {
+                bande_id: 0,
+                numero_batiment: "1".to_string(),
+                poussin_id: fixtures.poussin_id,
+                personnel_id: fixtures.personnel_id,
+                quantite: 5000,
+                autoriser_cohabitation: false,
+                champs_personnalises: Default::default(),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let vides = repo.get_by_ferme(fixtures.ferme_id).await.unwrap();
+    let remis = vides.iter().find(|v| v.id == vide.id).unwrap();
+    assert_eq!(remis.date_remise_en_place.as_deref(), Some("2024-05-20"));
+    assert_eq!(remis.jours_vide, 19);
+
+    let statistiques = repo.get_statistics(fixtures.ferme_id).await.unwrap();
+    assert_eq!(statistiques.nombre_vides, 1);
+    assert_eq!(statistiques.duree_moyenne_jours, Some(19.0));
+
+    assert!(service.get_pending_alerts(None).await.unwrap().is_empty());
+
+    repo.delete(vide.id).await.unwrap();
+    assert!(repo.delete(vide.id).await.is_err());
+    assert_eq!(test_db.count("vides_sanitaires", "1 = 1"), 1);
+}
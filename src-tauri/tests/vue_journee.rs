@@ -0,0 +1,87 @@
+//! Vue du jour des bâtiments des bandes actives
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{seed, semaine_id, TestDb};
+use tauri_app_lib::models::{CreatePhaseAlimentation, CreatePlanSoin, CreateSoin};
+use tauri_app_lib::repositories::{
+    PlanSoinsRepository, ProgrammeAlimentationRepository, SoinRepository, SoinRepositoryTrait, SuiviQuotidienRepository,
+    SuiviQuotidienRepositoryTrait,
+};
+use tauri_app_lib::services::{JourneeService, SemaineService};
+
+fn date(jour: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(jour, "%Y-%m-%d").unwrap()
+}
+
+#[tokio::test]
+async fn today_overview_lists_yesterday_entries_treatments_and_tasks() {
+    let test_db = TestDb::new();
+    let fixtures = seed(&test_db).await;
+    let [batiment_1, batiment_2] = fixtures.batiment_ids[..] else { panic!("deux bâtiments attendus") };
+
+    let vitamine = SoinRepository::new(test_db.storage())
+        .create(CreateSoin { nom: "Vitamine".to_string(), unit: "ml".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let conn = test_db.db.get_connection().unwrap();
+    PlanSoinsRepository::create(
+        &conn,
+        &CreatePlanSoin {
+            poussin_id: fixtures.poussin_id,
+            soin_id: vitamine.id.unwrap(),
+            jour_debut: 12,
+            jour_fin: 15,
+            quantite: Some("500".to_string()),
+            unit: None,
+        },
+    )
+    .unwrap();
+    for (nom, jour_fin) in [("Démarrage", 13), ("Croissance", 24)] {
+        ProgrammeAlimentationRepository::create(
+            &conn,
+            &CreatePhaseAlimentation {
+                poussin_id: fixtures.poussin_id,
+                nom_phase: nom.to_string(),
+                jour_debut: None,
+                jour_fin,
+                grammes_par_sujet: 50.0,
+            },
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    // Le 14 mars est le 14e jour de la bande: la veille est le jour 13
+    let suivi = SuiviQuotidienRepository::new(test_db.storage());
+    let semaine_2 = semaine_id(&test_db, batiment_1, 2);
+    suivi.upsert_field(semaine_2, 13, "deces_par_jour", "2").await.unwrap();
+    suivi.upsert_field(semaine_2, 13, "alimentation_par_jour", "3").await.unwrap();
+    SemaineService::new(test_db.storage()).update_semaine_poids(semaine_2, Some(0.45), None).await.unwrap();
+
+    let service = JourneeService::new(test_db.storage());
+    let vue = service.get_today_overview(date("2024-03-14"), None).await.unwrap();
+    let journees: Vec<_> = vue.batiments.iter().filter(|b| b.bande_id == fixtures.bande_id).collect();
+    assert_eq!(journees.iter().map(|b| b.batiment_id).collect::<Vec<_>>(), vec![batiment_1, batiment_2]);
+
+    let premier = journees[0];
+    assert_eq!(premier.age_jours, 14);
+    let hier = premier.hier.as_ref().unwrap();
+    assert_eq!((hier.age, hier.date), (13, date("2024-03-13")));
+    assert_eq!((hier.deces_par_jour, hier.alimentation_par_jour), (Some(2), Some(3.0)));
+    assert!(!premier.saisie_hier_manquante);
+    assert_eq!(premier.soins_prevus.len(), 1);
+    assert_eq!(premier.taches, vec!["Passage à l'aliment Croissance"]);
+
+    let second = journees[1];
+    assert!(second.saisie_hier_manquante);
+    assert_eq!(second.taches, vec!["Pesée de la semaine 2", "Passage à l'aliment Croissance"]);
+
+    // Le jour d'entrée, il n'y a pas de veille à saisir; avant, la bande n'apparaît pas
+    let vue = service.get_today_overview(date("2024-03-01"), None).await.unwrap();
+    let entree = vue.batiments.iter().find(|b| b.batiment_id == batiment_2).unwrap();
+    assert!(entree.hier.is_none() && !entree.saisie_hier_manquante);
+    let vue = service.get_today_overview(date("2024-02-20"), None).await.unwrap();
+    assert!(vue.batiments.iter().all(|b| b.bande_id != fixtures.bande_id));
+}
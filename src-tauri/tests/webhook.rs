@@ -0,0 +1,107 @@
+//! File d'envoi des événements vers le webhook de la coopérative
+
+mod common;
+
+use common::{seed, TestDb};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri_app_lib::error::{AppError, AppResult};
+use tauri_app_lib::models::{
+    Alerte, CreateUser, ALERTE_DELAI_ATTENTE, EVENEMENT_ALERTE_CRITIQUE, EVENEMENT_BANDE_CLOTUREE, GRAVITE_CRITICAL,
+};
+use tauri_app_lib::repositories::AlerteRepository;
+use tauri_app_lib::services::{AuthService, BandeService, ClientWebhook, WebhookService};
+
+/// Client qui enregistre les envois, hors ligne tant que `en_ligne` est faux
+#[derive(Default)]
+struct ClientTest {
+    en_ligne: AtomicBool,
+    recus: Mutex<Vec<(String, String)>>,
+}
+
+impl ClientWebhook for ClientTest {
+    fn poster(&self, url: &str, corps: &str) -> AppResult<()> {
+        if !self.en_ligne.load(Ordering::SeqCst) {
+            return Err(AppError::business_logic("Webhook injoignable: hors ligne"));
+        }
+        self.recus.lock().unwrap().push((url.to_string(), corps.to_string()));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn significant_events_are_queued_and_retried_until_delivered() {
+    let test_db = TestDb::new();
+    let sans_webhook = seed(&test_db).await;
+    let fixtures = seed(&test_db).await;
+    let token = AuthService::new(test_db.storage())
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: "motdepasse123".to_string(),
+            registration_code: String::new(),
+        })
+        .await
+        .unwrap()
+        .token;
+    let client = Arc::new(ClientTest::default());
+    let service = WebhookService::with_client(test_db.storage(), client.clone());
+    let bandes = BandeService::new(test_db.storage());
+
+    // Sans URL, rien n'est mis en file
+    bandes.close_bande(sans_webhook.bande_id, &token).await.unwrap();
+    assert_eq!(test_db.count("webhook_outbox", "1 = 1"), 0);
+    assert_eq!(service.deliver_pending().await.unwrap().envoyes, 0);
+
+    assert!(service.set_webhook_url(Some("ftp://cooperative".to_string()), &token).await.is_err());
+    assert!(service.set_webhook_url(Some("https://cooperative.example".to_string()), "inconnu").await.is_err());
+    let url = service.set_webhook_url(Some(" https://cooperative.example/hook ".to_string()), &token).await.unwrap();
+    assert_eq!(url.as_deref(), Some("https://cooperative.example/hook"));
+
+    bandes.close_bande(fixtures.bande_id, &token).await.unwrap();
+    let critique = Alerte {
+        type_alerte: ALERTE_DELAI_ATTENTE.to_string(),
+        gravite: GRAVITE_CRITICAL.to_string(),
+        ferme_id: fixtures.ferme_id,
+        bande_id: Some(fixtures.bande_id),
+        date: "2024-04-25".to_string(),
+        message: "Abattage avant la fin du délai d'attente".to_string(),
+        ..Default::default()
+    };
+    // Une alerte déjà détectée n'est notifiée qu'une fois
+    for _ in 0..2 {
+        test_db.storage().write(|tx| AlerteRepository::record(tx, std::slice::from_ref(&critique))).unwrap();
+    }
+    let file = service.get_webhook_outbox(&token).await.unwrap();
+    let types: Vec<&str> = file.iter().map(|e| e.type_evenement.as_str()).collect();
+    assert_eq!(types, [EVENEMENT_ALERTE_CRITIQUE, EVENEMENT_BANDE_CLOTUREE]);
+
+    // Hors ligne: les envois échouent et sont reprogrammés plus tard
+    let rapport = service.deliver_pending().await.unwrap();
+    assert_eq!((rapport.envoyes, rapport.echecs), (0, 2));
+    let file = service.get_webhook_outbox(&token).await.unwrap();
+    assert!(file.iter().all(|e| e.tentatives == 1 && e.derniere_erreur.is_some() && e.prochaine_tentative.is_some()));
+    client.en_ligne.store(true, Ordering::SeqCst);
+    assert_eq!(service.deliver_pending().await.unwrap().envoyes, 0);
+
+    // Relancé, l'événement de clôture part au passage suivant
+    let cloture = file.iter().find(|e| e.type_evenement == EVENEMENT_BANDE_CLOTUREE).unwrap();
+    service.retry_webhook_event(cloture.id, &token).await.unwrap();
+    assert_eq!(service.deliver_pending().await.unwrap().envoyes, 1);
+    let recus = client.recus.lock().unwrap().clone();
+    assert_eq!(recus.len(), 1);
+    assert_eq!(recus[0].0, "https://cooperative.example/hook");
+    let corps: serde_json::Value = serde_json::from_str(&recus[0].1).unwrap();
+    assert_eq!(corps["evenement"], EVENEMENT_BANDE_CLOTUREE);
+    assert_eq!(corps["donnees"]["bande_id"], fixtures.bande_id);
+    assert_eq!(corps["donnees"]["effectif_initial"], 10000);
+
+    assert!(service.retry_webhook_event(cloture.id, &token).await.is_err());
+    assert_eq!(test_db.count("webhook_outbox", "envoye_le IS NULL"), 1);
+
+    // Désactiver le webhook n'ajoute plus d'événement
+    assert_eq!(service.set_webhook_url(None, &token).await.unwrap(), None);
+    bandes.reopen_bande(fixtures.bande_id, "Erreur de saisie", &token).await.unwrap();
+    bandes.close_bande(fixtures.bande_id, &token).await.unwrap();
+    assert_eq!(test_db.count("webhook_outbox", "1 = 1"), 2);
+}
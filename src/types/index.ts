@@ -125,17 +125,6 @@ export interface PaginatedBandes {
   has_prev: boolean;
 }
 
-// Legacy interfaces for backward compatibility (will be removed)
-export interface OldCreateBande {
-  date_entree: string;
-  quantite: number;
-  ferme_id: number;
-  numero_batiment: string;
-  type_poussin: string;
-  personnel_id: number;
-  notes: string | null;
-}
-
 // Ferme interfaces
 export interface CreateFerme {
   nom: string;
@@ -285,6 +274,16 @@ export interface SemaineWithDetails {
   numero_semaine: number;
   poids: number | null;
   suivi_quotidien: SuiviQuotidienWithTotals[]; // Use extended type with totals for frontend
+  totaux?: TotauxSemaine; // Computed by get_full_semaines_by_batiment
+}
+
+export interface TotauxSemaine {
+  total_deces: number;
+  total_alimentation: number;
+  effectif_debut: number;
+  alimentation_par_sujet_par_jour: number | null; // grams
+  mortalite_pourcentage: number | null;
+  mortalite_cumulee_pourcentage: number | null;
 }
 
 export interface BatimentWithSemaines {
@@ -305,4 +304,5 @@ export interface Soin {
   nom: string;
   unit: string;
   created_at: string;
+  prix_unitaire?: number | null;
 }